@@ -0,0 +1,87 @@
+/*
+Program Details: Criterion benchmarks for the physics
+
+Run with `cargo bench`. Times, without a window:
+
+- building every shipped board into a PhysicsWorld (what loading a board costs),
+- copying a board for the trajectory preview (done every frame while aiming),
+- a second of play (60 fixed steps) with 0, 100 and 500 pieces on the classic
+  board, the pieces dropped by the same script as the benchmark scene's
+  scripted load (see benchmark_scene.rs) and already among the pegs.
+
+Criterion keeps the results of the last run in target/criterion and reports how
+much each benchmark has changed since, so a slower step after a refactor shows
+up as a regression.
+*/
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::load_board;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use plinko2::modules::aim::DROP_Y;
+use plinko2::modules::benchmark_scene::{drop_points, scripted_drop};
+use plinko2::modules::board::{BoardConfig, CLASSIC_BOARD, PRESET_BOARDS};
+use plinko2::modules::physics::PhysicsWorld;
+
+// Steps in a second of play, and steps run after the last drop so the pieces are spread over the board
+const STEPS_PER_SECOND: usize = 60;
+const SETTLE_STEPS: usize = 30;
+// Steps between two scripted drops while loading a world
+const STEPS_PER_DROP: usize = 2;
+
+// A world on `board` with `pieces` scripted pieces dropped in, a few steps apart
+fn loaded_world(board: &BoardConfig, pieces: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new();
+    world.build_board(board);
+    let points = drop_points(board);
+    for index in 0..pieces {
+        let (kind, x) = scripted_drop(index, &points);
+        world.spawn(kind, x, DROP_Y);
+        for _ in 0..STEPS_PER_DROP {
+            world.step();
+        }
+    }
+    for _ in 0..SETTLE_STEPS {
+        world.step();
+    }
+    world
+}
+
+fn build_boards(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_board");
+    for path in PRESET_BOARDS {
+        let board = load_board(path);
+        let mut world = PhysicsWorld::new();
+        group.bench_with_input(BenchmarkId::from_parameter(&board.name), &board, |b, board| b.iter(|| world.build_board(board)));
+    }
+    group.finish();
+}
+
+fn clone_board(c: &mut Criterion) {
+    let board = load_board(CLASSIC_BOARD);
+    let world = loaded_world(&board, 100);
+    c.bench_function("clone_board", |b| b.iter(|| world.clone_board()));
+}
+
+fn second_of_play(c: &mut Criterion) {
+    let board = load_board(CLASSIC_BOARD);
+    let mut group = c.benchmark_group("second_of_play");
+    group.sample_size(20);
+    for pieces in [0, 100, 500] {
+        group.bench_with_input(BenchmarkId::from_parameter(pieces), &pieces, |b, &pieces| {
+            b.iter_batched_ref(
+                || loaded_world(&board, pieces),
+                |world| {
+                    for _ in 0..STEPS_PER_SECOND {
+                        world.step();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build_boards, clone_board, second_of_play);
+criterion_main!(benches);
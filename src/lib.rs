@@ -0,0 +1,9 @@
+/*
+Program Details: The game's modules as a library
+
+Everything the game is made of lives in src/modules; main.rs only opens the
+window and runs the scenes. Building the modules as a library as well lets the
+benchmarks in benches/ drive the physics, the boards and the rest without a
+window.
+*/
+pub mod modules;
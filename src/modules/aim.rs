@@ -0,0 +1,176 @@
+/*
+Program Details: Mouse aiming for choosing where a piece is dropped
+
+The strip above the peg field (between the walls) is the drop zone. Moving the
+mouse there slides a marker along the top of the board; pressing the mouse grabs
+it so it can be dragged, and releasing drops the piece at the marker's x. While
+aiming, a dashed guide line runs down from the marker and a see-through ghost of
+the next piece is drawn where it will spawn.
+
+On touch screens there is no hovering, so the guide only shows while a finger is
+down: touch inside the drop zone, slide to aim and lift to drop.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod aim;
+
+Then with the other use commands add:
+use crate::modules::aim::DropAimer;
+
+Then above the loop section to use you would go:
+    let mut aimer = DropAimer::new();
+
+Then in the loop you would use:
+    if let Some(x) = aimer.update(&board) {
+        world.spawn(next_kind, x, DROP_Y);
+    }
+    // ... after the board is drawn:
+    aimer.draw(&board, next_kind);
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::board::BoardConfig;
+use crate::modules::physics::ShapeKind;
+use crate::modules::dialog::input_blocked;
+
+// Height pieces are dropped from
+pub const DROP_Y: f32 = 50.0;
+// Gap kept between the drop zone and the first row of pegs
+const ZONE_MARGIN: f32 = 20.0;
+// Gap kept between a dropped piece and the walls so it never spawns inside one
+const WALL_MARGIN: f32 = 12.0;
+// Length of the dashes (and the gaps between them) in the guide line
+const DASH: f32 = 10.0;
+
+pub struct DropAimer {
+    // Current marker position
+    x: f32,
+    // True while the mouse button is held after pressing inside the drop zone
+    dragging: bool,
+    // True while the mouse is over the drop zone (or dragging), which shows the guide
+    active: bool,
+    // Set once a touch has been seen; touch screens only show the guide while dragging
+    touch_input: bool,
+    pub enabled: bool,
+}
+
+impl Default for DropAimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DropAimer {
+    pub fn new() -> Self {
+        Self {
+            x: 400.0,
+            dragging: false,
+            active: false,
+            touch_input: false,
+            enabled: true,
+        }
+    }
+
+    // Getter for the marker's x position
+    pub fn get_x(&self) -> f32 {
+        self.x
+    }
+
+    // True while the guide is showing: the mouse is over the drop zone or the marker is held
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.active
+    }
+
+    /// The rectangle above the peg field the player can aim in.
+    pub fn drop_zone(board: &BoardConfig) -> Rect {
+        let (left, right) = Self::drop_range(board);
+        let segment_tops = board.segments.iter().flat_map(|segment| segment.points.iter().map(|[_, y]| *y));
+        let pegs_top = board.all_pegs().iter().map(|peg| peg.y).chain(segment_tops).fold(board.bins_top(), f32::min);
+        let bottom = (pegs_top - ZONE_MARGIN).max(DROP_Y + ZONE_MARGIN);
+        Rect::new(left, 0.0, right - left, bottom)
+    }
+
+    /// Follows the mouse inside the drop zone and returns the drop x when the button is released.
+    pub fn update(&mut self, board: &BoardConfig) -> Option<f32> {
+        if !self.enabled || input_blocked() {
+            self.dragging = false;
+            self.active = false;
+            return None;
+        }
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let zone = Self::drop_zone(board);
+        let over_zone = zone.contains(vec2(mouse_x, mouse_y));
+
+        if over_zone && is_mouse_button_pressed(MouseButton::Left) {
+            self.dragging = true;
+        }
+        // Touches leave the simulated mouse where the finger lifted, so hovering means nothing there
+        if !touches().is_empty() {
+            self.touch_input = true;
+        }
+        self.active = (over_zone && !self.touch_input) || self.dragging;
+        if self.active {
+            let (left, right) = Self::drop_range(board);
+            self.x = mouse_x.clamp(left, right);
+        }
+
+        if self.dragging && is_mouse_button_released(MouseButton::Left) {
+            self.dragging = false;
+            return Some(self.x);
+        }
+        None
+    }
+
+    /// Draws the marker, the dashed guide line down to the ground and a ghost of the next piece.
+    pub fn draw(&self, board: &BoardConfig, kind: ShapeKind) {
+        if !self.enabled || !self.active {
+            return;
+        }
+        let alpha = if self.dragging { 0.8 } else { 0.45 };
+
+        // Dashed guide line from the drop point to the ground
+        let ground = board.ground_top();
+        let mut y = DROP_Y;
+        while y < ground {
+            draw_line(self.x, y, self.x, (y + DASH).min(ground), 2.0, Color::new(1.0, 1.0, 1.0, alpha * 0.5));
+            y += DASH * 2.0;
+        }
+
+        // Marker on the top edge of the board
+        draw_triangle(vec2(self.x - 8.0, 4.0), vec2(self.x + 8.0, 4.0), vec2(self.x, 16.0), Color::new(1.0, 1.0, 1.0, alpha));
+
+        // Ghost of the piece at its spawn point (same sizes as PhysicsWorld::spawn)
+        let ghost = Color::new(1.0, 1.0, 0.0, alpha);
+        match kind {
+            ShapeKind::Ball => draw_circle(self.x, DROP_Y, 7.0, ghost),
+            ShapeKind::Square => draw_rectangle_lines(self.x - 7.75, DROP_Y - 7.75, 15.5, 15.5, 2.0, ghost),
+            ShapeKind::Triangle => {
+                let height = (3.0_f32).sqrt() / 2.0 * 15.0;
+                draw_triangle_lines(
+                    vec2(self.x, DROP_Y - height / 3.0),
+                    vec2(self.x - 7.5, DROP_Y + height * 2.0 / 3.0),
+                    vec2(self.x + 7.5, DROP_Y + height * 2.0 / 3.0),
+                    2.0,
+                    ghost,
+                );
+            }
+        }
+    }
+
+    /// Leftmost and rightmost x a piece can be dropped at: just inside the walls
+    pub fn drop_range(board: &BoardConfig) -> (f32, f32) {
+        let mut left = board.ground.x - board.ground.half_width;
+        let mut right = board.ground.x + board.ground.half_width;
+        for wall in &board.walls {
+            if wall.x < board.ground.x {
+                left = left.max(wall.x + wall.half_width);
+            } else {
+                right = right.min(wall.x - wall.half_width);
+            }
+        }
+        (left + WALL_MARGIN, right - WALL_MARGIN)
+    }
+}
@@ -0,0 +1,331 @@
+/*
+Program Details: Animated image module for sprite-sheet animations
+
+A sprite sheet is one image holding every frame of an animation in a grid of
+same-sized cells. Frames are numbered left to right, then top to bottom, from
+0. An AnimatedImage cuts the sheet into frames when it loads and plays named
+animations made of any of them, each at its own speed, looping or stopping on
+its last frame. Every frame gets its own transparency mask (and a coarse copy,
+see collision.rs), so collisions follow the frame being shown.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file
+    pub mod animated_image;
+
+Then add the following with the use commands:
+use crate::modules::animated_image::{AnimatedImage, PlayMode};
+
+Usage examples:
+1. Load a sprite sheet of 32 × 32 frames:
+    let mut coin = AnimatedImage::new(
+        "assets/coin_sheet.png",
+        32.0,   // frame width in the sheet
+        32.0,   // frame height in the sheet
+        48.0,   // width drawn
+        48.0,   // height drawn
+        200.0,  // x position
+        60.0,   // y position
+    ).await;
+
+2. Name the animations, with their frames, speed (frames per second) and mode:
+    coin.add_animation("spin", &[0, 1, 2, 3, 4, 5], 12.0, PlayMode::Loop);
+    coin.add_animation("collect", &[6, 7, 8, 9], 20.0, PlayMode::Once);
+    coin.play("spin");
+
+3. In the loop, move the animation on and draw it:
+    coin.update(get_frame_time());
+    coin.draw();
+
+4. Play a one-shot animation and wait for it to end:
+    coin.play("collect");
+    if coin.is_finished() { ... }
+
+Sheets loaded through Assets (see assets.rs) are cut up once, and every
+animation made from one shares its texture and masks:
+    let coin = assets.animated_image("coin", 48.0, 48.0, 200.0, 60.0).unwrap();
+
+Animated images collide like still ones:
+    if check_collision(&coin, &ball_image, 1) { ... }
+
+Additional functionality:
+- Playback: pause(), resume(), stop(), set_frame(), current_animation(), current_frame()
+- Position and size: set_position(), set_size(), set_angle()
+- Collision layer: set_layer() (see layers.rs)
+- Collision masks: regenerate_masks() with an alpha threshold or grown/shrunk (see collision.rs)
+*/
+use macroquad::prelude::*;
+use macroquad::texture::Texture2D;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::modules::assets::SheetAsset;
+use crate::modules::collision::{coarse_mask, frame_masks, MaskOptions};
+use crate::modules::layers::Layers;
+
+/// What an animation does once its last frame has been shown.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayMode {
+    /// Starts again from its first frame
+    Loop,
+    /// Stays on its last frame and counts as finished
+    Once,
+}
+
+// A named animation: the sheet frames it shows, in order, and how fast
+struct Animation {
+    frames: Vec<usize>,
+    fps: f32,
+    mode: PlayMode,
+}
+
+pub struct AnimatedImage {
+    texture: Texture2D,
+    frame_width: f32,
+    frame_height: f32,
+    columns: usize, // Frames across the sheet
+    frame_masks: Rc<Vec<Option<Vec<u8>>>>, // Transparency mask of every frame, None for a frame with no transparency
+    frame_coarse_masks: Rc<Vec<Option<Vec<u8>>>>, // The masks at 1/8 the size
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    angle: f32, // Angle of rotation
+    layer: Option<Layers>, // Collision layer, None to collide with everything
+    animations: HashMap<String, Animation>,
+    current: Option<String>, // Name of the animation playing
+    step: usize, // How far through the animation's frames it is
+    elapsed: f32, // Seconds the current frame has been shown
+    frame: usize, // The sheet frame shown
+    paused: bool,
+    finished: bool,
+}
+
+impl AnimatedImage {
+    // Constructor: loads the sprite sheet and cuts it into frame_width × frame_height frames
+    pub async fn new(
+        asset_path: &str,
+        frame_width: f32,
+        frame_height: f32,
+        width: f32,
+        height: f32,
+        x: f32,
+        y: f32,
+    ) -> Self {
+        let image = load_image(asset_path).await.unwrap();
+        Self::from_sheet(&SheetAsset::from_image(&image, frame_width, frame_height, &MaskOptions::default()), width, height, x, y)
+    }
+
+    // Constructor for a sheet already loaded, sharing its texture and frame masks
+    pub fn from_sheet(sheet: &SheetAsset, width: f32, height: f32, x: f32, y: f32) -> Self {
+        Self {
+            texture: sheet.texture.clone(),
+            frame_width: sheet.frame_width,
+            frame_height: sheet.frame_height,
+            columns: sheet.columns,
+            frame_masks: sheet.frame_masks.clone(),
+            frame_coarse_masks: sheet.frame_coarse_masks.clone(),
+            x,
+            y,
+            width,
+            height,
+            angle: 0.0,
+            layer: None,
+            animations: HashMap::new(),
+            current: None,
+            step: 0,
+            elapsed: 0.0,
+            frame: 0,
+            paused: false,
+            finished: false,
+        }
+    }
+
+    // Adds (or replaces) the animation `name`: the sheet frames it shows in order, at `fps` frames per second
+    #[allow(unused)]
+    pub fn add_animation(&mut self, name: &str, frames: &[usize], fps: f32, mode: PlayMode) {
+        // Frames past the end of the sheet are left out
+        let frames = frames.iter().copied().filter(|frame| *frame < self.frame_masks.len()).collect();
+        self.animations.insert(name.to_string(), Animation { frames, fps: fps.max(0.01), mode });
+    }
+
+    // Starts the animation `name` from its first frame (carries on if it is already playing)
+    #[allow(unused)]
+    pub fn play(&mut self, name: &str) {
+        if self.current.as_deref() == Some(name) && !self.finished {
+            self.paused = false;
+            return;
+        }
+        let Some(animation) = self.animations.get(name) else {
+            return;
+        };
+        if let Some(first) = animation.frames.first() {
+            self.frame = *first;
+        }
+        self.current = Some(name.to_string());
+        self.step = 0;
+        self.elapsed = 0.0;
+        self.paused = false;
+        self.finished = false;
+    }
+
+    // Stops the animation and stays on the frame shown
+    #[allow(unused)]
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.step = 0;
+        self.elapsed = 0.0;
+    }
+
+    #[allow(unused)]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    #[allow(unused)]
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    // Moves the animation on by `dt` seconds, skipping frames if more than one has gone by
+    pub fn update(&mut self, dt: f32) {
+        if self.paused || self.finished {
+            return;
+        }
+        let Some(animation) = self.current.as_ref().and_then(|name| self.animations.get(name)) else {
+            return;
+        };
+        if animation.frames.is_empty() {
+            return;
+        }
+        let frame_time = 1.0 / animation.fps;
+        self.elapsed += dt;
+        while self.elapsed >= frame_time {
+            self.elapsed -= frame_time;
+            if self.step + 1 < animation.frames.len() {
+                self.step += 1;
+            } else if animation.mode == PlayMode::Loop {
+                self.step = 0;
+            } else {
+                self.finished = true;
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+        self.frame = animation.frames[self.step];
+    }
+
+    // Method to draw the frame shown
+    pub fn draw(&self) {
+        let column = self.frame % self.columns;
+        let row = self.frame / self.columns;
+        draw_texture_ex(
+            &self.texture,
+            self.x,
+            self.y,
+            WHITE,
+            DrawTextureParams {
+                source: Some(Rect::new(column as f32 * self.frame_width, row as f32 * self.frame_height, self.frame_width, self.frame_height)),
+                rotation: self.angle,
+                dest_size: Some(vec2(self.width, self.height)),
+                ..Default::default()
+            },
+        );
+    }
+
+    // True once a PlayMode::Once animation has shown its last frame
+    #[allow(unused)]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // Name of the animation playing, if any
+    #[allow(unused)]
+    pub fn current_animation(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    // The sheet frame shown
+    #[allow(unused)]
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+
+    // Shows sheet frame `frame` (stopping any animation), e.g. for a bin lit up or not
+    #[allow(unused)]
+    pub fn set_frame(&mut self, frame: usize) {
+        self.stop();
+        self.frame = frame.min(self.frame_masks.len() - 1);
+    }
+
+    // How many frames the sheet holds
+    #[allow(unused)]
+    pub fn frame_count(&self) -> usize {
+        self.frame_masks.len()
+    }
+
+    // Accessors for image properties
+    #[allow(unused)]
+    pub fn pos(&self) -> Vec2 {
+        vec2(self.x, self.y)
+    }
+    #[allow(unused)]
+    pub fn size(&self) -> Vec2 {
+        vec2(self.width, self.height)
+    }
+    // The size of one frame, which is what the masks cover
+    #[allow(unused)]
+    pub fn texture_size(&self) -> Vec2 {
+        vec2(self.frame_width, self.frame_height)
+    }
+    #[allow(unused)]
+    pub fn set_position(&mut self, pos: Vec2) {
+        self.x = pos[0];
+        self.y = pos[1];
+    }
+    #[allow(unused)]
+    pub fn set_size(&mut self, size: Vec2) {
+        self.width = size[0];
+        self.height = size[1];
+    }
+    #[allow(unused)]
+    pub fn set_angle(&mut self, x: f32) {
+        self.angle = x;
+    }
+    #[allow(unused)]
+    pub fn get_angle(&self) -> f32 {
+        self.angle
+    }
+
+    // Makes every frame's transparency mask again from the sheet with other options (see MaskOptions in
+    // collision.rs). Animations sharing the old masks keep them
+    #[allow(unused)]
+    pub fn regenerate_masks(&mut self, options: &MaskOptions) {
+        let (frame_w, frame_h) = (self.frame_width as usize, self.frame_height as usize);
+        let masks = frame_masks(&self.texture.get_texture_data(), frame_w, frame_h, options);
+        let coarse_masks = masks.iter().map(|mask| mask.as_ref().map(|mask| coarse_mask(mask, frame_w, frame_h))).collect();
+        self.frame_masks = Rc::new(masks);
+        self.frame_coarse_masks = Rc::new(coarse_masks);
+    }
+
+    // Get the transparency mask (bitmask) of the frame shown
+    #[allow(unused)]
+    pub fn get_mask(&self) -> Option<Vec<u8>> {
+        self.frame_masks[self.frame].clone()
+    }
+
+    // Get the low resolution copy of the frame's transparency mask
+    #[allow(unused)]
+    pub fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.frame_coarse_masks[self.frame].clone()
+    }
+
+    // Get and set the collision layer
+    #[allow(unused)]
+    pub fn set_layer(&mut self, layer: Layers) {
+        self.layer = Some(layer);
+    }
+    #[allow(unused)]
+    pub fn get_layer(&self) -> Option<Layers> {
+        self.layer
+    }
+}
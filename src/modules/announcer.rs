@@ -0,0 +1,217 @@
+/*
+Program Details: Announcer banners for big wins, streaks and near misses
+
+Every landing is told to the announcer, which decides whether it is worth
+calling out across the board:
+
+- "BIG WIN!" when a landing pays BIG_WIN times its bet or more, "HUGE WIN!" from
+  HUGE_WIN times
+- "x5 STREAK!" once WIN_STREAK landings in a row have paid more than their bet,
+  and again for every winning landing after that
+- "So close..." when a losing landing was one bin away from the best-paying bin
+- "Cold streak..." once LOSS_STREAK landings in a row have paid less than their
+  bet, and again every LOSS_STREAK losses after that
+
+Banners are shown one at a time from a queue, so a burst of landings doesn't
+stack them on top of each other. Each one pops in with a little overshoot
+(ease-out-back), holds, then floats up and fades (ease-in). A streak banner
+still waiting its turn is updated with the latest count rather than queued
+again, and when banners pile up the one showing hurries off so the queue keeps
+up with the game.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod announcer;
+
+Then with the other use commands add:
+use crate::modules::announcer::Announcer;
+
+Then above the loop section to use you would go:
+    let mut announcer = Announcer::new();
+
+Then when a piece pays out:
+    announcer.record_landing(&board, bin, stake, won);
+
+Then in the loop you would use:
+    announcer.update(get_frame_time());
+    // ... after drawing the board, centred over it:
+    announcer.draw(board.ground.x);
+*/
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use crate::modules::board::BoardConfig;
+use crate::modules::theme::scaled_font_size;
+
+// Landings paying at least this many times their bet are big and huge wins
+const BIG_WIN: f32 = 3.0;
+const HUGE_WIN: f32 = 10.0;
+// Winning landings in a row that make a streak, and losing ones that make a cold streak
+const WIN_STREAK: u32 = 3;
+const LOSS_STREAK: u32 = 5;
+// Banners waiting beyond this many are dropped (the oldest first)
+const MAX_QUEUED: usize = 4;
+// Seconds a banner takes to pop in, stays and takes to fade out; with others waiting it stays
+// only HURRIED_HOLD
+const POP_SECONDS: f32 = 0.3;
+const HOLD_SECONDS: f32 = 1.0;
+const HURRIED_HOLD: f32 = 0.3;
+const FADE_SECONDS: f32 = 0.35;
+// Height the banners are shown at, and how far they float up as they fade
+const BANNER_Y: f32 = 250.0;
+const FLOAT_DISTANCE: f32 = 40.0;
+
+const HUGE_WIN_COLOR: Color = GOLD;
+const BIG_WIN_COLOR: Color = YELLOW;
+const STREAK_COLOR: Color = ORANGE;
+const NEAR_MISS_COLOR: Color = SKYBLUE;
+const COLD_STREAK_COLOR: Color = LIGHTGRAY;
+
+// What a banner announces; streak banners waiting in the queue are updated rather than queued twice
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BannerKind {
+    Win,
+    Streak,
+    NearMiss,
+    ColdStreak,
+}
+
+struct Banner {
+    kind: BannerKind,
+    text: String,
+    color: Color,
+    // Font size before the text scale
+    size: u16,
+}
+
+pub struct Announcer {
+    queue: VecDeque<Banner>,
+    // The banner on screen and how long it has been showing (real seconds)
+    showing: Option<(Banner, f32)>,
+    // Landings in a row that paid more than their bet, and that paid less
+    wins: u32,
+    losses: u32,
+}
+
+impl Default for Announcer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Announcer {
+    pub fn new() -> Self {
+        Self { queue: VecDeque::new(), showing: None, wins: 0, losses: 0 }
+    }
+
+    /// Winning landings in a row so far (0 after a losing one).
+    #[allow(unused)]
+    pub fn win_streak(&self) -> u32 {
+        self.wins
+    }
+
+    /// A piece bet `stake` landed in `bin` and paid `won`: keeps the streaks and queues whatever
+    /// banners the landing earns.
+    pub fn record_landing(&mut self, board: &BoardConfig, bin: usize, stake: u32, won: u32) {
+        // A free piece (nothing bet on it) can't win or lose anything
+        if stake == 0 {
+            return;
+        }
+        let ratio = won as f32 / stake as f32;
+        if ratio >= HUGE_WIN {
+            self.announce(BannerKind::Win, "HUGE WIN!".to_string(), HUGE_WIN_COLOR, 72);
+        } else if ratio >= BIG_WIN {
+            self.announce(BannerKind::Win, "BIG WIN!".to_string(), BIG_WIN_COLOR, 60);
+        }
+
+        if won > stake {
+            self.wins += 1;
+            self.losses = 0;
+            if self.wins >= WIN_STREAK {
+                self.announce(BannerKind::Streak, format!("x{} STREAK!", self.wins), STREAK_COLOR, 52);
+            }
+        } else if won < stake {
+            self.losses += 1;
+            self.wins = 0;
+            if near_best_bin(board, bin) {
+                self.announce(BannerKind::NearMiss, "So close...".to_string(), NEAR_MISS_COLOR, 44);
+            } else if self.losses % LOSS_STREAK == 0 {
+                self.announce(BannerKind::ColdStreak, "Cold streak...".to_string(), COLD_STREAK_COLOR, 40);
+            }
+        }
+        // Getting the bet back exactly neither builds nor breaks a streak
+    }
+
+    // Queues a banner; a streak banner still waiting takes the new count instead
+    fn announce(&mut self, kind: BannerKind, text: String, color: Color, size: u16) {
+        if kind == BannerKind::Streak
+            && let Some(waiting) = self.queue.iter_mut().find(|banner| banner.kind == kind)
+        {
+            waiting.text = text;
+            return;
+        }
+        if self.queue.len() >= MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(Banner { kind, text, color, size });
+    }
+
+    /// Moves the banner on screen along (`dt`, real seconds) and brings on the next one when it is done.
+    pub fn update(&mut self, dt: f32) {
+        if let Some((_, age)) = self.showing.as_mut() {
+            *age += dt;
+        }
+        let hold = if self.queue.is_empty() { HOLD_SECONDS } else { HURRIED_HOLD };
+        if self.showing.as_ref().is_some_and(|(_, age)| *age >= POP_SECONDS + hold + FADE_SECONDS) {
+            self.showing = None;
+        }
+        if self.showing.is_none() {
+            self.showing = self.queue.pop_front().map(|banner| (banner, 0.0));
+        }
+    }
+
+    /// Draws the banner on screen, centred on `center_x`.
+    pub fn draw(&self, center_x: f32) {
+        let Some((banner, age)) = &self.showing else {
+            return;
+        };
+        let hold = if self.queue.is_empty() { HOLD_SECONDS } else { HURRIED_HOLD };
+        let (scale, alpha, rise) = if *age < POP_SECONDS {
+            (ease_out_back(age / POP_SECONDS), 1.0, 0.0)
+        } else if *age < POP_SECONDS + hold {
+            (1.0, 1.0, 0.0)
+        } else {
+            let fade = ease_in(((age - POP_SECONDS - hold) / FADE_SECONDS).min(1.0));
+            (1.0, 1.0 - fade, fade * FLOAT_DISTANCE)
+        };
+        let font_size = (scaled_font_size(banner.size) as f32 * scale).max(1.0);
+        let width = measure_text(&banner.text, None, font_size as u16, 1.0).width;
+        let (x, y) = (center_x - width / 2.0, BANNER_Y - rise);
+        draw_text(&banner.text, x + 3.0, y + 3.0, font_size, Color { a: alpha * 0.6, ..BLACK });
+        draw_text(&banner.text, x, y, font_size, Color { a: alpha, ..banner.color });
+    }
+
+    /// Forgets the streaks and every banner, e.g. when a new game starts.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
+
+// True when `bin` isn't one of the best-paying bins but is right next to one
+fn near_best_bin(board: &BoardConfig, bin: usize) -> bool {
+    let best = board.payouts.iter().copied().fold(0.0, f32::max);
+    let is_best = |bin: usize| board.payouts.get(bin).is_some_and(|&payout| payout >= best);
+    !is_best(bin) && (is_best(bin + 1) || (bin > 0 && is_best(bin - 1)))
+}
+
+// Rises past 1.0 and settles back onto it, for a pop (0.0 - 1.0 in, 0.0 - ~1.1 out)
+fn ease_out_back(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.70158;
+    let t = t - 1.0;
+    1.0 + (OVERSHOOT + 1.0) * t * t * t + OVERSHOOT * t * t
+}
+
+// Starts slowly and speeds up (0.0 - 1.0 in and out)
+fn ease_in(t: f32) -> f32 {
+    t * t * t
+}
@@ -0,0 +1,245 @@
+/*
+Program Details: Asset cache loaded once at startup
+
+Images, sprite sheets and sounds are queued under a key, then loaded one at a
+time while the loading screen (see loading_scene.rs) shows how far along it is.
+Each image is decoded once: its texture and transparency masks (and their coarse
+copies, see collision.rs) are kept here and every StillImage or AnimatedImage
+made from the key shares them. A Texture2D is a handle to one GPU texture, so a
+hundred sprites of the same ball hold one texture rather than a hundred copies.
+
+A file that fails to load is left out and noted in errors(); asking for its key
+gives None, so the game can fall back to whatever it drew before.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod assets;
+
+Then with the other use commands add:
+use crate::modules::assets::Assets;
+
+Then above the loop section to use you would go:
+    let mut assets = Assets::new();
+    assets.queue_image("ball", "assets/ball.png");
+    assets.queue_sheet("coin", "assets/coin_sheet.png", 32.0, 32.0);
+    assets.queue_image("glow", "assets/glow.png").with_mask_options(MaskOptions { alpha_threshold: 128, ..Default::default() });
+    assets.queue_sound("chime", "assets/chime.wav");
+    LoadingScene::new("Plinko").run(&mut assets).await;   // or: while assets.load_next().await {}
+
+Then make sprites from the keys:
+    let ball = assets.still_image("ball", 40.0, 40.0, 100.0, 50.0, true, 1.0).unwrap();
+    let coin = assets.animated_image("coin", 48.0, 48.0, 200.0, 60.0).unwrap();
+    if let Some(icon) = assets.texture("ball") {
+        btn_random.with_icon(icon);
+    }
+*/
+use macroquad::audio::{load_sound, Sound};
+use macroquad::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use crate::modules::animated_image::AnimatedImage;
+use crate::modules::collision::{coarse_mask, frame_masks, mask_from_image, MaskOptions};
+use crate::modules::still_image::StillImage;
+
+/// A loaded image, shared by every sprite made from it.
+#[derive(Clone)]
+pub struct ImageAsset {
+    pub texture: Texture2D,
+    /// Transparency mask, None if the image has no transparent pixels
+    pub mask: Option<Rc<Vec<u8>>>,
+    /// The mask at 1/8 the size
+    pub coarse_mask: Option<Rc<Vec<u8>>>,
+    /// The file the image came from
+    pub path: String,
+}
+
+impl ImageAsset {
+    /// Uploads `image` as a texture and makes its masks.
+    pub fn from_image(image: &Image, path: &str, options: &MaskOptions) -> ImageAsset {
+        let texture = Texture2D::from_image(image);
+        texture.set_filter(FilterMode::Linear);
+        let (width, height) = (image.width(), image.height());
+        let mask = mask_from_image(image, options);
+        let coarse = mask.as_ref().map(|mask| Rc::new(coarse_mask(mask, width, height)));
+        ImageAsset { texture, mask: mask.map(Rc::new), coarse_mask: coarse, path: path.to_string() }
+    }
+}
+
+/// A loaded sprite sheet cut into same-sized frames, shared by every animation made from it.
+#[derive(Clone)]
+pub struct SheetAsset {
+    pub texture: Texture2D,
+    pub frame_width: f32,
+    pub frame_height: f32,
+    /// Frames across the sheet
+    pub columns: usize,
+    /// Transparency mask of every frame, None for a frame with no transparency
+    pub frame_masks: Rc<Vec<Option<Vec<u8>>>>,
+    /// The frame masks at 1/8 the size
+    pub frame_coarse_masks: Rc<Vec<Option<Vec<u8>>>>,
+}
+
+impl SheetAsset {
+    /// Uploads `image` as a texture and cuts it into frame_width × frame_height frames, numbered left to right
+    /// then top to bottom.
+    pub fn from_image(image: &Image, frame_width: f32, frame_height: f32, options: &MaskOptions) -> SheetAsset {
+        let texture = Texture2D::from_image(image);
+        texture.set_filter(FilterMode::Linear);
+        let (frame_w, frame_h) = (frame_width.max(1.0) as usize, frame_height.max(1.0) as usize);
+        let columns = (image.width() / frame_w).max(1);
+        let frame_masks = frame_masks(image, frame_w, frame_h, options);
+        let frame_coarse_masks = frame_masks.iter().map(|mask| mask.as_ref().map(|mask| coarse_mask(mask, frame_w, frame_h))).collect();
+        SheetAsset {
+            texture,
+            frame_width: frame_w as f32,
+            frame_height: frame_h as f32,
+            columns,
+            frame_masks: Rc::new(frame_masks),
+            frame_coarse_masks: Rc::new(frame_coarse_masks),
+        }
+    }
+}
+
+// A file waiting to be loaded, and the key it goes under
+enum Pending {
+    Image { key: String, path: String, mask: MaskOptions },
+    Sheet { key: String, path: String, frame_width: f32, frame_height: f32, mask: MaskOptions },
+    Sound { key: String, path: String },
+}
+
+impl Pending {
+    fn path(&self) -> &str {
+        match self {
+            Pending::Image { path, .. } | Pending::Sheet { path, .. } | Pending::Sound { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Assets {
+    pending: VecDeque<Pending>,
+    queued: usize, // Files queued since the last time loading finished, for the progress
+    images: HashMap<String, ImageAsset>,
+    sheets: HashMap<String, SheetAsset>,
+    sounds: HashMap<String, Sound>,
+    errors: Vec<String>,
+}
+
+impl Assets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the image at `path` to be loaded under `key`.
+    pub fn queue_image(&mut self, key: &str, path: &str) -> &mut Self {
+        self.queue(Pending::Image { key: key.to_string(), path: path.to_string(), mask: MaskOptions::default() })
+    }
+
+    /// Queues the sprite sheet at `path`, made of frame_width × frame_height frames, to be loaded under `key`.
+    pub fn queue_sheet(&mut self, key: &str, path: &str, frame_width: f32, frame_height: f32) -> &mut Self {
+        self.queue(Pending::Sheet { key: key.to_string(), path: path.to_string(), frame_width, frame_height, mask: MaskOptions::default() })
+    }
+
+    /// Queues the sound file at `path` to be loaded under `key`.
+    pub fn queue_sound(&mut self, key: &str, path: &str) -> &mut Self {
+        self.queue(Pending::Sound { key: key.to_string(), path: path.to_string() })
+    }
+
+    /// Makes the masks of the image or sheet queued last with `options` (see MaskOptions in collision.rs), e.g. a
+    /// higher alpha threshold for art with soft edges.
+    pub fn with_mask_options(&mut self, options: MaskOptions) -> &mut Self {
+        if let Some(Pending::Image { mask, .. } | Pending::Sheet { mask, .. }) = self.pending.back_mut() {
+            *mask = options;
+        }
+        self
+    }
+
+    fn queue(&mut self, pending: Pending) -> &mut Self {
+        if self.pending.is_empty() {
+            self.queued = 0;
+        }
+        self.pending.push_back(pending);
+        self.queued += 1;
+        self
+    }
+
+    /// Loads the next queued file. Returns false once there was nothing left to load.
+    pub async fn load_next(&mut self) -> bool {
+        let Some(pending) = self.pending.pop_front() else {
+            return false;
+        };
+        match pending {
+            Pending::Image { key, path, mask } => match load_image(&path).await {
+                Ok(image) => {
+                    self.images.insert(key, ImageAsset::from_image(&image, &path, &mask));
+                }
+                Err(e) => self.errors.push(format!("Couldn't load {}: {}", path, e)),
+            },
+            Pending::Sheet { key, path, frame_width, frame_height, mask } => match load_image(&path).await {
+                Ok(image) => {
+                    self.sheets.insert(key, SheetAsset::from_image(&image, frame_width, frame_height, &mask));
+                }
+                Err(e) => self.errors.push(format!("Couldn't load {}: {}", path, e)),
+            },
+            Pending::Sound { key, path } => match load_sound(&path).await {
+                Ok(sound) => {
+                    self.sounds.insert(key, sound);
+                }
+                Err(e) => self.errors.push(format!("Couldn't load {}: {}", path, e)),
+            },
+        }
+        true
+    }
+
+    /// How much of the queue has been loaded, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.queued == 0 {
+            return 1.0;
+        }
+        (self.queued - self.pending.len()) as f32 / self.queued as f32
+    }
+
+    /// True once everything queued has been loaded (or has failed to).
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The file that will be loaded next, if any.
+    pub fn next_path(&self) -> Option<&str> {
+        self.pending.front().map(Pending::path)
+    }
+
+    /// Why the files that failed to load didn't.
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    pub fn image(&self, key: &str) -> Option<&ImageAsset> {
+        self.images.get(key)
+    }
+
+    pub fn sheet(&self, key: &str) -> Option<&SheetAsset> {
+        self.sheets.get(key)
+    }
+
+    pub fn sound(&self, key: &str) -> Option<&Sound> {
+        self.sounds.get(key)
+    }
+
+    /// A handle to the texture of the image loaded under `key`.
+    pub fn texture(&self, key: &str) -> Option<Texture2D> {
+        self.images.get(key).map(|image| image.texture.clone())
+    }
+
+    /// A StillImage of the image loaded under `key`, sharing its texture and masks.
+    #[allow(clippy::too_many_arguments)]
+    pub fn still_image(&self, key: &str, width: f32, height: f32, x: f32, y: f32, stretch_enabled: bool, zoom_level: f32) -> Option<StillImage> {
+        self.images.get(key).map(|image| StillImage::from_asset(image, width, height, x, y, stretch_enabled, zoom_level))
+    }
+
+    /// An AnimatedImage of the sprite sheet loaded under `key`, sharing its texture and masks.
+    pub fn animated_image(&self, key: &str, width: f32, height: f32, x: f32, y: f32) -> Option<AnimatedImage> {
+        self.sheets.get(key).map(|sheet| AnimatedImage::from_sheet(sheet, width, height, x, y))
+    }
+}
@@ -0,0 +1,178 @@
+/*
+Program Details: Attract mode scene (the demo shown while nobody is playing)
+
+Like an arcade cabinet left alone, the game shows what it looks like when
+played. Once the title menu, or an arcade round (see RoundKind::Arcade in
+session.rs), has been left without input for the idle time picked in the
+settings (ATTRACT_SECONDS to begin with), it switches to this scene, which plays
+back a demo session (see demo.rs): the latest stretch of real play the Plinko
+scene recorded, or, until there is one, the short session written into the game
+on the classic board. The recorded pieces are dropped on a fresh copy of the
+board they were dropped on, with the physics settings of the time, when they
+were dropped, and the physics plays them out again; once they have had
+SETTLE_SECONDS to land the demo starts over. Every landing is shown again
+slowed down in the board's corner by the instant replay (see
+instant_replay.rs).
+
+"Press any key" blinks over the board, and any key, click or touch ends the
+demo and goes back to the title menu.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod attract_scene;
+
+Then with the other use commands add:
+use crate::modules::attract_scene::AttractScene;
+
+Then above the loop section to use you would go:
+    let demo = SharedDemo::default();
+    scenes.add(SceneId::Attract, Box::new(AttractScene::new(demo.clone()).await));
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{RigidBody, RigidBodyHandle};
+use crate::modules::board::{BoardConfig, CLASSIC_BOARD};
+use crate::modules::chain::draw_chains;
+use crate::modules::demo::{Demo, SharedDemo};
+use crate::modules::idle::any_input;
+use crate::modules::instant_replay::{InstantReplay, Outline};
+use crate::modules::label::Label;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+use crate::modules::presets_scene::{draw_board_colliders, BOARD_AREA};
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::theme::scaled_font_size;
+
+/// Real seconds without input before the attract mode starts, until the settings say otherwise.
+pub const ATTRACT_SECONDS: f32 = 30.0;
+// Game seconds a demo runs on after its last drop, for the pieces to land and be replayed, before it starts over
+const SETTLE_SECONDS: f32 = 10.0;
+// Real seconds "Press any key" takes to blink on and off
+const BLINK_SECONDS: f32 = 1.0;
+const PRESS_ANY_KEY: &str = "Press any key";
+const PIECE_COLORS: [Color; 3] = [Color::new(1.0, 0.85, 0.3, 1.0), Color::new(0.4, 0.8, 1.0, 1.0), Color::new(1.0, 0.5, 0.6, 1.0)];
+
+pub struct AttractScene {
+    // The latest recording from the Plinko scene, and the session written into the game for
+    // when there is none yet
+    recorded: SharedDemo,
+    scripted: Demo,
+    // The demo being played
+    demo: Demo,
+    world: PhysicsWorld,
+    replay: InstantReplay,
+    // Drops of the demo made so far, and game seconds since it started
+    dropped: usize,
+    run_time: f32,
+    // Real seconds the scene has been showing, for the blinking
+    clock: f32,
+    lbl_title: Label,
+    lbl_demo: Label,
+}
+
+impl AttractScene {
+    pub async fn new(recorded: SharedDemo) -> Self {
+        let scripted = Demo::scripted(BoardConfig::load(CLASSIC_BOARD).await.unwrap());
+        let mut lbl_title = Label::new("Plinko", 815.0, 120.0, 60);
+        lbl_title.with_colors(YELLOW, None);
+        let mut lbl_demo = Label::new("DEMO", 815.0, 170.0, 30);
+        lbl_demo.with_colors(LIGHTGRAY, None);
+
+        Self {
+            recorded,
+            demo: scripted.clone(),
+            scripted,
+            world: PhysicsWorld::new(),
+            replay: InstantReplay::new(),
+            dropped: 0,
+            run_time: 0.0,
+            clock: 0.0,
+            lbl_title,
+            lbl_demo,
+        }
+    }
+
+    // Starts the demo over on an empty copy of its board, with the physics it was recorded with
+    fn restart(&mut self) {
+        self.demo.settings.apply_physics(&mut self.world);
+        self.world.build_board(&self.demo.board);
+        self.replay.clear();
+        self.dropped = 0;
+        self.run_time = 0.0;
+    }
+}
+
+// Colour a demo piece is drawn in, by its shape
+fn piece_color(body: &RigidBody) -> Color {
+    let kind = PhysicsWorld::shape_of(body).unwrap_or(ShapeKind::Ball);
+    PIECE_COLORS[ShapeKind::ALL.iter().position(|shape| *shape == kind).unwrap_or(0)]
+}
+
+impl Scene for AttractScene {
+    fn on_enter(&mut self) {
+        // The latest recording, which may have changed since the last showing
+        self.demo = self.recorded.borrow().clone().unwrap_or_else(|| self.scripted.clone());
+        self.lbl_demo.set_text(if self.recorded.borrow().is_some() { "REPLAY" } else { "DEMO" });
+        self.restart();
+        self.clock = 0.0;
+    }
+
+    fn update(&mut self, dt: f32) -> SceneChange {
+        if any_input() {
+            return SceneChange::Goto(SceneId::Menu);
+        }
+        self.clock += dt;
+        if self.run_time >= self.demo.length + SETTLE_SECONDS {
+            self.restart();
+        }
+
+        // Every recorded piece whose time has come, as it was dropped
+        while let Some(&drop) = self.demo.drops.get(self.dropped)
+            && drop.time <= self.run_time
+        {
+            self.world.ball_variant = drop.variant;
+            self.world.spawn_with_velocity(drop.kind, drop.x, drop.y, drop.vx, drop.vy);
+            self.dropped += 1;
+        }
+        let steps = self.world.advance(dt);
+        let seconds = steps as f32 * self.world.integration_params.dt;
+        self.run_time += seconds;
+
+        // Each landing is played back slowed down as soon as the one before has finished
+        let world = &self.world;
+        self.replay.record(world, seconds);
+        self.replay.record_events(world.events(), |handle: RigidBodyHandle| world.bodies.get(handle).map_or(WHITE, piece_color));
+        self.replay.update(dt);
+        self.replay.play();
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        // The replay view is drawn into its viewport before anything goes on the screen
+        self.replay.render(&self.world);
+        draw_board_colliders(&self.world, BOARD_AREA);
+        draw_chains(&self.world);
+        for (handle, body) in self.world.bodies.iter() {
+            if !PhysicsWorld::is_piece(body) {
+                continue;
+            }
+            let Some(collider) = body.colliders().first().map(|collider| &self.world.colliders[*collider]) else {
+                continue;
+            };
+            let (pos, rot) = self.world.render_pose(handle, body);
+            Outline::of(collider.shape()).draw(pos.x, pos.y, rot, piece_color(body));
+        }
+        self.replay.draw();
+
+        self.lbl_title.draw();
+        self.lbl_demo.draw();
+        draw_text("INSERT COIN", 815.0, 400.0, 36.0, GOLD);
+        // "Press any key" over the board, on a dark band so it reads over the pieces
+        if self.clock % BLINK_SECONDS < BLINK_SECONDS / 2.0 {
+            let center_x = self.demo.board.ground.x;
+            let font_size = scaled_font_size(48);
+            let width = measure_text(PRESS_ANY_KEY, None, font_size, 1.0).width;
+            draw_rectangle(center_x - width / 2.0 - 20.0, 290.0, width + 40.0, 60.0, Color::new(0.0, 0.0, 0.0, 0.6));
+            draw_text(PRESS_ANY_KEY, center_x - width / 2.0, 332.0, font_size as f32, WHITE);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+/*
+Program Details: Audio module for peg hits, bin landings and UI clicks
+
+Wraps macroquad's sound API. The game's sounds are short synthesized tones, built
+as WAV data in memory at startup, so no sound files have to ship with the game.
+Impact sounds are driven by the world's Impact GameEvents, which only hits with a
+contact force above the world's threshold send, and get louder the faster the
+piece was moving when it hit. A piece makes at most one impact sound every
+IMPACT_COOLDOWN seconds, so one rattling on a peg doesn't buzz. Every sound is scaled by a master volume.
+The background music (see music.rs) is loaded and played from here too; its own
+volume is scaled by the master volume as well.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod audio;
+
+In the Cargo.toml file turn on macroquad's audio feature:
+    macroquad = { version = "0.4.14", features = ["audio"] }
+
+Then with the other use commands add:
+use crate::modules::audio::Audio;
+
+Then above the loop section to use you would go:
+    let mut audio = Audio::load().await;
+    audio.set_master_volume(0.8);
+
+Then in the loop you would use:
+    world.advance(get_frame_time());
+    audio.play_events(world.events());   // impact sounds for this frame's GameEvents
+
+    if btn_text.click() {
+        audio.play_click();
+    }
+    audio.play_jingle();             // when a piece lands in a bin
+
+And for the music (e.g. when the game screen is entered and left):
+    audio.play_music();
+    audio.update_music(falling_pieces, combo.length(), get_frame_time());
+    audio.stop_music();
+*/
+
+use crate::modules::events::{GameEvent, ImpactKind};
+use crate::modules::music::Music;
+use macroquad::audio::{load_sound_from_bytes, play_sound, PlaySoundParams, Sound};
+use macroquad::time::get_time;
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+/// Sample rate of the synthesized sounds
+pub const SAMPLE_RATE: u32 = 22050;
+// Pieces moving slower than this don't make a sound when they touch something
+const MIN_IMPACT_SPEED: f32 = 60.0;
+// Impacts at or above this speed play at full volume
+const MAX_IMPACT_SPEED: f32 = 600.0;
+// Shortest gap between two impact sounds from the same piece (seconds)
+const IMPACT_COOLDOWN: f64 = 0.08;
+
+pub struct Audio {
+    peg_hit: Option<Sound>,
+    wall_hit: Option<Sound>,
+    jingle: Option<Sound>,
+    click: Option<Sound>,
+    music: Music,
+    master_volume: f32,
+    music_volume: f32,
+    // When each piece last made an impact sound
+    last_impact: HashMap<RigidBodyHandle, f64>,
+}
+
+impl Audio {
+    /// Synthesizes and loads every game sound.
+    /// If a sound fails to load the game keeps running without it.
+    pub async fn load() -> Self {
+        Self {
+            peg_hit: load_tone(&[(1320.0, 0.06)], 0.5).await,
+            wall_hit: load_tone(&[(140.0, 0.12)], 0.8).await,
+            // Rising C-E-G-C arpeggio for a bin landing
+            jingle: load_tone(&[(523.25, 0.09), (659.25, 0.09), (783.99, 0.09), (1046.5, 0.22)], 0.5).await,
+            click: load_tone(&[(900.0, 0.03)], 0.4).await,
+            music: Music::load().await,
+            master_volume: 1.0,
+            music_volume: 1.0,
+            last_impact: HashMap::new(),
+        }
+    }
+
+    // Getter for the master volume (0.0 - 1.0)
+    #[allow(unused)]
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
+    // Setter for the master volume, clamped to 0.0 - 1.0
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+        self.music.set_volume(self.master_volume * self.music_volume);
+    }
+
+    // Setter for the music volume (0.0 - 1.0), which the master volume scales in turn
+    pub fn set_music_volume(&mut self, volume: f32) {
+        self.music_volume = volume.clamp(0.0, 1.0);
+        self.music.set_volume(self.master_volume * self.music_volume);
+    }
+
+    /// Starts the background music (if it isn't playing already).
+    pub fn play_music(&mut self) {
+        self.music.play();
+    }
+
+    /// Stops the background music.
+    pub fn stop_music(&mut self) {
+        self.music.stop();
+    }
+
+    /// Crossfades the music's layers for `falling_pieces` in play and a combo `combo` landings long.
+    pub fn update_music(&mut self, falling_pieces: usize, combo: u32, dt: f32) {
+        self.music.update(falling_pieces, combo, dt);
+    }
+
+    /// Plays an impact sound whose volume follows the impact speed.
+    pub fn play_impact(&self, kind: ImpactKind, speed: f32) {
+        if speed < MIN_IMPACT_SPEED {
+            return;
+        }
+        let strength = ((speed - MIN_IMPACT_SPEED) / (MAX_IMPACT_SPEED - MIN_IMPACT_SPEED)).clamp(0.1, 1.0);
+        let sound = match kind {
+            ImpactKind::Peg => &self.peg_hit,
+            ImpactKind::Wall => &self.wall_hit,
+        };
+        self.play(sound, strength);
+    }
+
+    /// Plays the jingle for a piece landing in a bin.
+    pub fn play_jingle(&self) {
+        self.play(&self.jingle, 1.0);
+    }
+
+    /// Plays the UI click.
+    pub fn play_click(&self) {
+        self.play(&self.click, 1.0);
+    }
+
+    /// Plays impact sounds for the impacts in a frame's GameEvents, each piece at most once every
+    /// IMPACT_COOLDOWN seconds.
+    pub fn play_events(&mut self, events: &[GameEvent]) {
+        let now = get_time();
+        self.last_impact.retain(|_, last| now - *last < IMPACT_COOLDOWN);
+        for event in events {
+            match *event {
+                GameEvent::Impact { piece, kind, speed } => {
+                    if speed < MIN_IMPACT_SPEED || self.last_impact.contains_key(&piece) {
+                        continue;
+                    }
+                    self.last_impact.insert(piece, now);
+                    self.play_impact(kind, speed);
+                }
+                // A breaking peg gets the loudest peg sound
+                GameEvent::PegBroken { .. } => self.play_impact(ImpactKind::Peg, MAX_IMPACT_SPEED),
+                _ => {}
+            }
+        }
+    }
+
+    // Plays a sound at the given volume scaled by the master volume
+    fn play(&self, sound: &Option<Sound>, volume: f32) {
+        if let Some(sound) = sound
+            && self.master_volume > 0.0
+        {
+            play_sound(sound, PlaySoundParams { looped: false, volume: volume * self.master_volume });
+        }
+    }
+}
+
+// Builds a sequence of decaying sine notes (frequency Hz, duration s) and loads it as a Sound
+async fn load_tone(notes: &[(f32, f32)], gain: f32) -> Option<Sound> {
+    let mut samples = Vec::new();
+    for &(frequency, duration) in notes {
+        let count = (duration * SAMPLE_RATE as f32) as usize;
+        for i in 0..count {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            // Quick exponential decay so each note sounds plucked rather than droning
+            let envelope = (-t * 6.0 / duration).exp();
+            samples.push((t * frequency * TAU).sin() * envelope * gain);
+        }
+    }
+    load_sound_from_bytes(&wav_bytes(&samples)).await.ok()
+}
+
+/// Encodes mono samples (-1.0 - 1.0) as a 16-bit PCM WAV file at SAMPLE_RATE.
+pub fn wav_bytes(samples: &[f32]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    bytes.extend_from_slice(&2u16.to_le_bytes()); // block align
+    bytes.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
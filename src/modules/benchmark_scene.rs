@@ -0,0 +1,359 @@
+/*
+Program Details: Physics benchmark scene
+
+Opened with the Benchmark button on the title menu. It runs one of two loads on
+the classic board, picked with the Load button, keeping every piece in play
+(nothing is paid out or removed unless the watchdog throws it out):
+
+- Stress drops BENCHMARK_BALLS balls, a row at a time wherever the drop line is
+  clear, so the physics ends up working on a thousand piled-up bodies.
+- Scripted drops SCRIPTED_PIECES pieces over SCRIPTED_SECONDS of game time, one
+  after another, balls, squares and triangles in turn, each from a spot along
+  the drop line worked out from its number. Every run drops exactly the same
+  pieces in the same places, so runs before and after a change can be compared.
+
+The readout shows how long a physics step and drawing the frame take, averaged
+over the last second, next to how many pieces are in play and awake. When a
+scripted run is over it writes a report (average and worst step and draw times
+over the whole run) to the terminal and, on desktops, to BENCHMARK_REPORT.
+
+Builds with the "native" feature (on by default, and left out of the web build)
+step Rapier with its "parallel" feature, spreading the broad phase, narrow phase
+and solver over Rayon's threads; the readout says which kind of build is
+running, so the step time of a native build can be held against one built
+without it (--no-default-features --features scale).
+
+Restart starts the load over on a fresh board; Back (or Escape) returns to the
+menu. The same loads without a window are in benches/physics.rs (cargo bench).
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod benchmark_scene;
+
+Then with the other use commands add:
+use crate::modules::benchmark_scene::BenchmarkScene;
+
+Then above the loop section to use you would go:
+    scenes.add(SceneId::Benchmark, Box::new(BenchmarkScene::new().await));
+*/
+use macroquad::prelude::*;
+use crate::modules::aim::DROP_Y;
+use crate::modules::board::{BoardConfig, CLASSIC_BOARD};
+use crate::modules::chain::draw_chains;
+use crate::modules::label::Label;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+use crate::modules::presets_scene::{draw_board_colliders, BOARD_AREA};
+use crate::modules::renderer::{Layer, Renderer};
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::text_button::TextButton;
+
+/// Balls dropped by the stress load.
+pub const BENCHMARK_BALLS: usize = 1000;
+/// Pieces dropped by the scripted load, and the seconds of game time they are dropped over.
+pub const SCRIPTED_PIECES: usize = 500;
+pub const SCRIPTED_SECONDS: f32 = 30.0;
+/// Where a scripted run's report is saved.
+pub const BENCHMARK_REPORT: &str = "benchmark_report.txt";
+// Gap between the drop points along the drop line (pixels), and how far in from the walls the first one is
+const DROP_SPACING: f32 = 24.0;
+const DROP_MARGIN: f32 = 30.0;
+// Seconds the readout is averaged over
+const AVERAGE_SECONDS: f32 = 1.0;
+const PIECE_COLORS: [Color; 3] = [Color::new(1.0, 0.85, 0.3, 1.0), Color::new(0.4, 0.8, 1.0, 1.0), Color::new(1.0, 0.5, 0.6, 1.0)];
+const FONT_SIZE: f32 = 18.0;
+const LINE_HEIGHT: f32 = 24.0;
+// Where the readout is drawn, right of the board
+const READOUT_X: f32 = 810.0;
+const READOUT_Y: f32 = 140.0;
+
+/// The load a benchmark run puts on the physics.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Load {
+    Stress,
+    Scripted,
+}
+
+// Step and draw times over a stretch of a run, in seconds
+#[derive(Default, Clone, Copy)]
+struct Timings {
+    step_time: f32,
+    steps: usize,
+    worst_step: f32,
+    draw_time: f32,
+    frames: usize,
+    worst_draw: f32,
+}
+
+impl Timings {
+    fn add_steps(&mut self, time: f32, steps: usize) {
+        self.step_time += time;
+        self.steps += steps;
+        if steps > 0 {
+            self.worst_step = self.worst_step.max(time / steps as f32);
+        }
+    }
+
+    fn add_frame(&mut self, draw_time: f32) {
+        self.draw_time += draw_time;
+        self.frames += 1;
+        self.worst_draw = self.worst_draw.max(draw_time);
+    }
+
+    // Average milliseconds per step and per drawn frame
+    fn step_ms(&self) -> f32 {
+        if self.steps > 0 { self.step_time * 1000.0 / self.steps as f32 } else { 0.0 }
+    }
+
+    fn draw_ms(&self) -> f32 {
+        if self.frames > 0 { self.draw_time * 1000.0 / self.frames as f32 } else { 0.0 }
+    }
+}
+
+pub struct BenchmarkScene {
+    board: BoardConfig,
+    world: PhysicsWorld,
+    load: Load,
+    dropped: usize,
+    // Game time since the run started
+    run_time: f32,
+    // Since the readout was last refreshed, and the last full second shown
+    recent: Timings,
+    recent_time: f32,
+    shown: Timings,
+    // The whole scripted run, for its report
+    total: Timings,
+    // Result of the last report, shown under the readout
+    status: String,
+    lbl_title: Label,
+    btn_back: TextButton,
+    btn_restart: TextButton,
+    btn_load: TextButton,
+}
+
+impl BenchmarkScene {
+    pub async fn new() -> Self {
+        let board = BoardConfig::load(CLASSIC_BOARD).await.unwrap();
+        let mut world = PhysicsWorld::new();
+        world.build_board(&board);
+        let mut lbl_title = Label::new("Benchmark", 810.0, 60.0, 40);
+        lbl_title.with_colors(YELLOW, None);
+
+        Self {
+            board,
+            world,
+            load: Load::Stress,
+            dropped: 0,
+            run_time: 0.0,
+            recent: Timings::default(),
+            recent_time: 0.0,
+            shown: Timings::default(),
+            total: Timings::default(),
+            status: String::new(),
+            lbl_title,
+            btn_back: TextButton::new(810.0, 680.0, 190.0, 50.0, "Back", DARKGRAY, GRAY, 28),
+            btn_restart: TextButton::new(810.0, 620.0, 190.0, 50.0, "Restart", DARKGREEN, GREEN, 28),
+            btn_load: TextButton::new(810.0, 560.0, 190.0, 50.0, load_text(Load::Stress), DARKBLUE, BLUE, 24),
+        }
+    }
+
+    // Starts over: a fresh board with nothing dropped yet
+    fn restart(&mut self) {
+        self.world.build_board(&self.board);
+        self.dropped = 0;
+        self.run_time = 0.0;
+        self.recent = Timings::default();
+        self.recent_time = 0.0;
+        self.shown = Timings::default();
+        self.total = Timings::default();
+        self.status.clear();
+    }
+
+    // Drops what the load calls for by now
+    fn drop_pieces(&mut self) {
+        let points = drop_points(&self.board);
+        match self.load {
+            // A row of balls, skipping points a ball is still in the way of
+            Load::Stress => {
+                for x in points {
+                    if self.dropped < BENCHMARK_BALLS && self.world.spawn_point_clear(ShapeKind::Ball, x, DROP_Y) {
+                        self.world.spawn(ShapeKind::Ball, x, DROP_Y);
+                        self.dropped += 1;
+                    }
+                }
+            }
+            // Every piece whose time has come, at the point and in the shape its number gives it
+            Load::Scripted => {
+                let due = ((self.run_time / SCRIPTED_SECONDS * SCRIPTED_PIECES as f32) as usize + 1).min(SCRIPTED_PIECES);
+                while self.dropped < due {
+                    let (kind, x) = scripted_drop(self.dropped, &points);
+                    self.world.spawn(kind, x, DROP_Y);
+                    self.dropped += 1;
+                }
+            }
+        }
+    }
+
+    // True once a scripted run has dropped everything and its time is up
+    fn finished(&self) -> bool {
+        self.load == Load::Scripted && self.run_time >= SCRIPTED_SECONDS && self.dropped >= SCRIPTED_PIECES
+    }
+
+    // The report of a finished scripted run
+    fn report(&self) -> String {
+        let total = &self.total;
+        [
+            format!("Plinko benchmark: {} pieces over {} s", SCRIPTED_PIECES, SCRIPTED_SECONDS),
+            build_text(),
+            format!("Steps: {}, frames drawn: {}", total.steps, total.frames),
+            format!("Step: {:.3} ms average, {:.3} ms worst", total.step_ms(), total.worst_step * 1000.0),
+            format!("Draw: {:.3} ms average, {:.3} ms worst", total.draw_ms(), total.worst_draw * 1000.0),
+            format!("Pieces still in play: {}", self.world.bodies.iter().filter(|(_, body)| PhysicsWorld::is_piece(body)).count()),
+        ]
+        .join("\n")
+    }
+
+    // Prints the report and saves it next to the game. Browsers can't write files, so on the web it is only printed.
+    fn save_report(&mut self) {
+        let report = self.report();
+        println!("{}", report);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.status = match std::fs::write(BENCHMARK_REPORT, &report) {
+                Ok(()) => format!("Saved {}", BENCHMARK_REPORT),
+                Err(error) => format!("Couldn't save {}: {}", BENCHMARK_REPORT, error),
+            };
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.status = "Report printed to the console".to_string();
+        }
+    }
+}
+
+/// Drop points (x) along the drop line of `board`, DROP_SPACING apart between its walls.
+pub fn drop_points(board: &BoardConfig) -> Vec<f32> {
+    let ground = &board.ground;
+    let (left, right) = (ground.x - ground.half_width + DROP_MARGIN, ground.x + ground.half_width - DROP_MARGIN);
+    (0..).map(|index| left + index as f32 * DROP_SPACING).take_while(|x| *x < right).collect()
+}
+
+/// The shape and drop point (one of `points`) of scripted piece number `index`: the shapes in turn, and points
+/// picked by a stride that doesn't share a factor with the usual point counts, so neighbouring pieces don't fall
+/// on top of each other.
+pub fn scripted_drop(index: usize, points: &[f32]) -> (ShapeKind, f32) {
+    let kind = ShapeKind::ALL[index % ShapeKind::ALL.len()];
+    let x = points.get(index * 7 % points.len().max(1)).copied().unwrap_or(DROP_MARGIN);
+    (kind, x)
+}
+
+// Text of the Load button
+fn load_text(load: Load) -> String {
+    match load {
+        Load::Stress => format!("Load: {} balls", BENCHMARK_BALLS),
+        Load::Scripted => format!("Load: scripted {}", SCRIPTED_PIECES),
+    }
+}
+
+// What kind of build is running: parallel physics on Rayon's threads, or a single thread
+#[cfg(feature = "native")]
+fn build_text() -> String {
+    format!("Parallel physics ({} threads)", rayon::current_num_threads())
+}
+
+#[cfg(not(feature = "native"))]
+fn build_text() -> String {
+    "Single-threaded physics".to_string()
+}
+
+impl Scene for BenchmarkScene {
+    fn on_enter(&mut self) {
+        self.restart();
+    }
+
+    fn update(&mut self, dt: f32) -> SceneChange {
+        if self.btn_back.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Menu);
+        }
+        if self.btn_restart.click() {
+            self.restart();
+        }
+        if self.btn_load.click() {
+            self.load = if self.load == Load::Stress { Load::Scripted } else { Load::Stress };
+            self.btn_load.set_text(load_text(self.load));
+            self.restart();
+        }
+        // A finished scripted run stays as it ended, report and all, until it is restarted
+        if self.finished() {
+            return SceneChange::None;
+        }
+
+        self.drop_pieces();
+        let start = get_time();
+        let steps = self.world.advance(dt);
+        let step_time = (get_time() - start) as f32;
+        self.recent.add_steps(step_time, steps);
+        self.total.add_steps(step_time, steps);
+        self.run_time += steps as f32 * self.world.integration_params.dt;
+        self.recent_time += dt;
+        if self.recent_time >= AVERAGE_SECONDS {
+            self.shown = self.recent;
+            self.recent = Timings::default();
+            self.recent_time = 0.0;
+        }
+        if self.finished() {
+            self.save_report();
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        let start = get_time();
+        draw_board_colliders(&self.world, BOARD_AREA);
+        draw_chains(&self.world);
+        let mut renderer = Renderer::new();
+        let (mut pieces, mut awake) = (0, 0);
+        for (handle, body) in self.world.bodies.iter() {
+            let Some(kind) = PhysicsWorld::shape_of(body).filter(|_| PhysicsWorld::is_piece(body)) else {
+                continue;
+            };
+            pieces += 1;
+            if !body.is_sleeping() {
+                awake += 1;
+            }
+            let Some(collider) = body.colliders().first().map(|collider| &self.world.colliders[*collider]) else {
+                continue;
+            };
+            // Every shape as a dot the size of its collider, in the colour of its shape
+            let radius = collider.shape().compute_local_aabb().half_extents().max();
+            let (pos, _) = self.world.render_pose(handle, body);
+            let color = PIECE_COLORS[ShapeKind::ALL.iter().position(|shape| *shape == kind).unwrap_or(0)];
+            renderer.circle(Layer::Pieces, pos.x, pos.y, radius, color);
+        }
+        renderer.flush();
+        if !self.finished() {
+            let draw_time = (get_time() - start) as f32;
+            self.recent.add_frame(draw_time);
+            self.total.add_frame(draw_time);
+        }
+
+        self.lbl_title.draw();
+        let target = if self.load == Load::Stress { BENCHMARK_BALLS } else { SCRIPTED_PIECES };
+        let mut lines = vec![
+            build_text(),
+            format!("Dropped: {} / {}", self.dropped, target),
+            format!("In play: {} ({} awake)", pieces, awake),
+            format!("Step: {:.2} ms", self.shown.step_ms()),
+            format!("Draw: {:.2} ms", self.shown.draw_ms()),
+        ];
+        if self.load == Load::Scripted {
+            lines.push(format!("Time: {:.0} / {:.0} s", self.run_time.min(SCRIPTED_SECONDS), SCRIPTED_SECONDS));
+        }
+        if !self.status.is_empty() {
+            lines.push(self.status.clone());
+        }
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, READOUT_X, READOUT_Y + LINE_HEIGHT * index as f32, FONT_SIZE, WHITE);
+        }
+    }
+}
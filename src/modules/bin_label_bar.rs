@@ -0,0 +1,117 @@
+/*
+Program Details: Prize labels along the bins
+
+A row of labels, one centered over each bin, showing the bin's payout
+multiplier. The labels are placed from the board's bin geometry (where the bins
+start and how wide they are), and the text shrinks when the bins get too narrow
+for it. Call set_board whenever the board changes: the labels are only rebuilt
+when the payouts or the bins have actually changed.
+
+Hovering a bin (or its label) shows a tooltip with what the bin pays.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod bin_label_bar;
+
+Then with the other use commands add:
+use crate::modules::bin_label_bar::BinLabelBar;
+
+Then above the loop section to use you would go:
+    let mut prize_bar = BinLabelBar::new(600.0);
+    prize_bar.set_board(&board);
+
+Then in the loop you would use:
+    prize_bar.draw();
+*/
+use macroquad::prelude::*;
+use crate::modules::board::BoardConfig;
+use crate::modules::label::Label;
+use crate::modules::tooltip::set_tooltip;
+use crate::modules::wallet::format_multiplier;
+
+// Font size on bins at least FULL_SIZE_BIN_WIDTH wide; narrower bins get smaller text, down to MIN_FONT_SIZE
+const FONT_SIZE: f32 = 30.0;
+const MIN_FONT_SIZE: f32 = 14.0;
+const FULL_SIZE_BIN_WIDTH: f32 = 110.0;
+
+pub struct BinLabelBar {
+    // Baseline of the labels
+    y: f32,
+    foreground: Color,
+    background: Option<Color>,
+    labels: Vec<Label>,
+    // Each bin's area, from its label down to the ground, and what its tooltip says
+    tooltips: Vec<(Rect, String)>,
+    // What the labels were built for: bin payouts, left edge and width of a bin
+    shown: Option<(Vec<f32>, f32, f32)>,
+}
+
+impl BinLabelBar {
+    /// An empty bar with its labels' baseline at `y`; set_board fills it in.
+    pub fn new(y: f32) -> Self {
+        Self { y, foreground: WHITE, background: Some(BLACK), labels: Vec::new(), tooltips: Vec::new(), shown: None }
+    }
+
+    // Text and background colours of the labels
+    #[allow(unused)]
+    pub fn with_colors(&mut self, foreground: Color, background: Option<Color>) -> &mut Self {
+        self.foreground = foreground;
+        self.background = background;
+        self.shown = None;
+        self
+    }
+
+    /// Shows the payouts of `board`, rebuilding the labels if its payouts or bins have changed.
+    pub fn set_board(&mut self, board: &BoardConfig) {
+        let shown = (board.payouts.clone(), board.bins_left(), board.bin_width());
+        if self.shown.as_ref() == Some(&shown) {
+            return;
+        }
+        let width = board.bin_width();
+        let font_size = (FONT_SIZE * (width / FULL_SIZE_BIN_WIDTH).min(1.0)).max(MIN_FONT_SIZE) as u16;
+        self.labels = board
+            .payouts
+            .iter()
+            .enumerate()
+            .map(|(bin, &multiplier)| {
+                let text = format_multiplier(multiplier);
+                let center = board.bins_left() + width * (bin as f32 + 0.5);
+                let text_width = measure_text(&text, None, font_size, 1.0).width;
+                let mut label = Label::new(text, center - text_width / 2.0, self.y, font_size);
+                label.with_colors(self.foreground, self.background);
+                label
+            })
+            .collect();
+        let top = board.bins_top().min(self.y - font_size as f32);
+        self.tooltips = (0..board.bins.count)
+            .map(|bin| {
+                let area = Rect::new(board.bins_left() + width * bin as f32, top, width, board.ground_top() - top);
+                (area, bin_tooltip(board, bin))
+            })
+            .collect();
+        self.shown = Some(shown);
+    }
+
+    pub fn draw(&self) {
+        for label in &self.labels {
+            label.draw();
+        }
+        for (area, text) in &self.tooltips {
+            set_tooltip(*area, text.as_str());
+        }
+    }
+}
+
+// What a bin pays, and that the middle bins also start the bonus wheel
+fn bin_tooltip(board: &BoardConfig, bin: usize) -> String {
+    let multiplier = board.payouts[bin];
+    let mut text = format!("Bin {}: pays {} the bet", bin + 1, format_multiplier(multiplier));
+    if multiplier < 1.0 {
+        text.push_str(" (less than it cost)");
+    }
+    if board.is_center_bin(bin) {
+        text.push_str(". Landing here also spins the bonus wheel");
+    }
+    text
+}
@@ -0,0 +1,952 @@
+/*
+Program Details: Board configuration module for data-driven Plinko boards
+
+A BoardConfig describes everything static on the board: ground and wall boxes,
+the bin dividers, the payout multiplier of each bin, the pegs, any angled
+segments (funnels and ramps), any force fields (wind, updrafts and magnets
+pushing the pieces inside them) and any sticky pads (ledges that hold on to the
+pieces landing on them and pay a bonus instead of a bin) and an optional water
+pool filling the bottom of the board up to a surface line, which slows the pieces
+sinking through it and carries them off towards the side bins. Chains hang from
+fixed points on the board and swing when a piece strikes them. Block stacks are
+piles of small loose blocks standing on a ledge of their own, waiting to be knocked
+over (each toppled block can be worth points to whoever knocked it); a board holds
+at most MAX_BLOCKS of them in all. Pegs can be
+listed one by one, or generated from staggered grids so a data file doesn't
+need hundreds of hand-written positions, or from a casino-style pyramid; a board
+holds at most MAX_PEGS pegs, however they are listed.
+
+BoardConfig::pyramid builds the well-known casino board in a preset's frame:
+a pyramid of 8, 12 or 16 rows (PYRAMID_ROWS) with one more bin than rows, lined
+up under its bottom row, the casino's payout table for that many rows, walls
+either side of the base, and a funnel leading every drop into the top of the
+pyramid.
+
+Boards are stored as JSON files in assets/boards and loaded with serde.
+
+The number of bins can be changed from the one a board was designed with
+(with_bin_count); the new bins get generated payouts, symmetric about the
+middle, that pay out about as much per drop as the board's own.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod board;
+
+In the Cargo.toml file add the following:
+    serde = { version = "1.0", features = ["derive"] }
+    serde_json = "1.0"
+
+Then with the other use commands add:
+use crate::modules::board::BoardConfig;
+
+Then above the loop section to use you would go:
+    let board = BoardConfig::load("assets/boards/circle.json").await.unwrap();
+    world.build_board(&board);
+
+Example board file (every peg, zone, segment, force field, sticky pad, pool, chain and block stack
+section is optional):
+    {
+        "name": "Example",
+        "ground": { "x": 432.0, "y": 700.0, "half_width": 355.0, "half_height": 20.0, "friction": 0.4 },
+        "walls": [
+            { "x": 70.0, "y": 400.0, "half_width": 10.0, "half_height": 400.0, "friction": 0.4 }
+        ],
+        "bins": { "count": 6, "divider_half_width": 4.0, "divider_half_height": 60.0, "friction": 0.4 },
+        "payouts": [5.0, 1.0, 0.5, 0.5, 1.0, 5.0],
+        "peg_pyramids": [
+            { "rows": 8, "center": 432.0, "top": 200.0, "spacing": 50.0, "row_spacing": 40.0,
+              "shape": { "type": "Circle", "radius": 6.0 } }
+        ],
+        "peg_grids": [
+            { "rows": 11, "cols": 18, "top": 120.0, "row_spacing": 40.0, "left": 98.0, "right": 752.0,
+              "shift": -3.0, "restitution": 0.5, "shape": { "type": "Circle", "radius": 8.0 } }
+        ],
+        "pegs": [
+            { "x": 400.0, "y": 90.0, "restitution": 0.5, "shape": { "type": "Triangle", "size": 12.0 } },
+            { "x": 250.0, "y": 500.0, "shape": { "type": "Circle", "radius": 10.0 },
+              "motion": { "type": "Oscillate", "amplitude": 40.0, "period": 3.0 } },
+            { "x": 600.0, "y": 500.0, "shape": { "type": "Bar", "length": 60.0, "thickness": 6.0 },
+              "motion": { "type": "Rotate", "speed": 90.0 } },
+            { "x": 430.0, "y": 300.0, "golden": true, "shape": { "type": "Circle", "radius": 9.0 } },
+            { "x": 320.0, "y": 380.0, "hit_points": 3.0, "shape": { "type": "Circle", "radius": 10.0 } },
+            { "x": 540.0, "y": 380.0, "bumper": 250.0, "shape": { "type": "Circle", "radius": 12.0 } }
+        ],
+        "zones": [
+            { "x": 432.0, "y": 420.0, "half_width": 60.0, "half_height": 20.0, "bonus": 1.0 }
+        ],
+        "segments": [
+            { "points": [[80.0, 150.0], [380.0, 300.0]] },
+            { "points": [[770.0, 150.0], [470.0, 300.0]], "restitution": 0.2 }
+        ],
+        "force_fields": [
+            { "x": 250.0, "y": 250.0, "half_width": 100.0, "half_height": 40.0,
+              "effect": { "type": "Wind", "strength": 400.0 } },
+            { "x": 620.0, "y": 560.0, "half_width": 60.0, "half_height": 60.0,
+              "effect": { "type": "Magnet", "strength": 900.0 } }
+        ],
+        "sticky_pads": [
+            { "x": 160.0, "y": 460.0, "half_width": 30.0, "half_height": 5.0, "bonus": 3.0, "delay": 2.0 }
+        ],
+        "pool": { "surface": 520.0, "density": 0.8, "drag": 2.0, "drift": 150.0 },
+        "chains": [
+            { "x": 300.0, "y": 130.0, "links": 6, "link_length": 14.0 }
+        ],
+        "block_stacks": [
+            { "x": 600.0, "y": 300.0, "cols": 3, "rows": 4, "block_size": 10.0, "points": 1 }
+        ]
+    }
+*/
+
+use serde::{Deserialize, Serialize};
+
+// Board files shipped with the game, in the order the map picker uses them
+pub const CLASSIC_BOARD: &str = "assets/boards/classic.json";
+pub const CIRCLE_BOARD: &str = "assets/boards/circle.json";
+pub const SQUARE_BOARD: &str = "assets/boards/square.json";
+pub const TRIANGLE_BOARD: &str = "assets/boards/triangle.json";
+pub const DIAMOND_BOARD: &str = "assets/boards/diamond.json";
+pub const FUNNEL_BOARD: &str = "assets/boards/funnel.json";
+pub const PYRAMID_BOARD: &str = "assets/boards/pyramid.json";
+pub const PACHINKO_BOARD: &str = "assets/boards/pachinko.json";
+pub const WINDY_BOARD: &str = "assets/boards/windy.json";
+pub const LAGOON_BOARD: &str = "assets/boards/lagoon.json";
+// Every shipped board, in the order the presets gallery shows them
+pub const PRESET_BOARDS: [&str; 10] = [
+    CLASSIC_BOARD,
+    CIRCLE_BOARD,
+    SQUARE_BOARD,
+    TRIANGLE_BOARD,
+    DIAMOND_BOARD,
+    FUNNEL_BOARD,
+    PYRAMID_BOARD,
+    PACHINKO_BOARD,
+    WINDY_BOARD,
+    LAGOON_BOARD,
+];
+// Board loaded by the "Load Board" button so players can drop in their own layout
+pub const CUSTOM_BOARD: &str = "assets/boards/custom.json";
+// Folder boards saved from the game under a name go in
+pub const SAVED_BOARDS_DIR: &str = "assets/boards/saved";
+// Most loose blocks a board may stack up in all, so knocking them over can't bog the solver down
+pub const MAX_BLOCKS: usize = 60;
+// Most pegs a board may have in all, counting the ones its grids and pyramids expand to, so a small
+// board file or share code can't ask for millions of them
+pub const MAX_PEGS: usize = 2000;
+// Row counts the casino-style pyramid boards come in
+pub const PYRAMID_ROWS: [usize; 3] = [8, 12, 16];
+// Widest gap between neighbouring pyramid pegs (few rows would otherwise spread them far apart)
+const PYRAMID_MAX_SPACING: f32 = 56.0;
+// Height the pyramid's funnel starts at by the walls, below where pieces are dropped
+const PYRAMID_FUNNEL_TOP: f32 = 90.0;
+// Least slope (rise over run) of the funnel, so pieces roll down it rather than rest on it
+const PYRAMID_FUNNEL_SLOPE: f32 = 0.3;
+// How far outside the pyramid's outer pegs its sides run, in peg spacings
+const PYRAMID_SIDE_GAP: f32 = 0.8;
+// Row spacing of an equilateral pyramid, as a share of the peg spacing
+const PYRAMID_ROW_RATIO: f32 = 0.87;
+// Half height of a pyramid board's bin dividers: shorter than usual to leave room for 16 rows
+const PYRAMID_DIVIDER_HALF_HEIGHT: f32 = 30.0;
+
+/// Everything static on a Plinko board.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BoardConfig {
+    pub name: String,
+    pub ground: BoxConfig,
+    pub walls: Vec<BoxConfig>,
+    pub bins: BinConfig,
+    /// Payout multiplier of each bin, left to right (one entry per bin)
+    pub payouts: Vec<f32>,
+    #[serde(default)]
+    pub peg_grids: Vec<PegGrid>,
+    /// Casino-style pyramids; the bins of a board with one line up with its bottom row
+    #[serde(default)]
+    pub peg_pyramids: Vec<PegPyramid>,
+    #[serde(default)]
+    pub pegs: Vec<PegConfig>,
+    #[serde(default)]
+    pub zones: Vec<ZoneConfig>,
+    #[serde(default)]
+    pub segments: Vec<SegmentConfig>,
+    #[serde(default)]
+    pub force_fields: Vec<ForceFieldConfig>,
+    #[serde(default)]
+    pub sticky_pads: Vec<StickyPadConfig>,
+    /// Water filling the board from the ground up to the pool's surface
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolConfig>,
+    #[serde(default)]
+    pub chains: Vec<ChainConfig>,
+    #[serde(default)]
+    pub block_stacks: Vec<BlockStackConfig>,
+}
+
+/// A fixed rectangle (ground platform or wall), positioned by its center.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BoxConfig {
+    pub x: f32,
+    pub y: f32,
+    pub half_width: f32,
+    pub half_height: f32,
+    #[serde(default = "default_friction")]
+    pub friction: f32,
+}
+
+/// The bin dividers standing on the ground. `count` bins span the ground's full width.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BinConfig {
+    pub count: usize,
+    pub divider_half_width: f32,
+    pub divider_half_height: f32,
+    #[serde(default = "default_friction")]
+    pub friction: f32,
+}
+
+/// Which bins pay the most when payouts are generated for a new bin count.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PayoutLayout {
+    /// The classic Plinko row: big prizes on the rarely reached edges, small ones in the middle
+    #[default]
+    EdgesHigh,
+    /// Small prizes on the edges and the biggest in the middle
+    CenterHigh,
+}
+
+impl PayoutLayout {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayoutLayout::EdgesHigh => "Edges high",
+            PayoutLayout::CenterHigh => "Center high",
+        }
+    }
+}
+
+/// How far apart the small and big payouts are, like the risk setting of casino Plinko.
+/// Every level pays the same on average; higher risk moves more of it to the rarely reached bins.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RiskLevel {
+    /// A flat table: every bin pays something close to the bet
+    Low,
+    /// The table as the board (or the bin count setting) laid it out
+    #[default]
+    Medium,
+    /// Huge prizes on the best bins and next to nothing on the common ones
+    High,
+}
+
+impl RiskLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "Low",
+            RiskLevel::Medium => "Medium",
+            RiskLevel::High => "High",
+        }
+    }
+
+    /// The level after this one, wrapping from High back to Low.
+    pub fn next(self) -> RiskLevel {
+        match self {
+            RiskLevel::Low => RiskLevel::Medium,
+            RiskLevel::Medium => RiskLevel::High,
+            RiskLevel::High => RiskLevel::Low,
+        }
+    }
+
+    // Power every payout is raised to before the table is scaled back to the same average
+    fn exponent(self) -> f32 {
+        match self {
+            RiskLevel::Low => 0.5,
+            RiskLevel::Medium => 1.0,
+            RiskLevel::High => 2.0,
+        }
+    }
+}
+
+/// The collision shape of a single peg.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum PegShape {
+    Circle { radius: f32 },
+    /// A square with the given side length, rotated by `angle` degrees (45 makes a diamond)
+    Square { size: f32, #[serde(default)] angle: f32 },
+    /// An upward-pointing equilateral triangle with the given side length
+    Triangle { size: f32 },
+    /// A flat bar `length` long and `thickness` thick, rotated by `angle` degrees (spinner bars)
+    Bar { length: f32, thickness: f32, #[serde(default)] angle: f32 },
+}
+
+/// How a moving peg is animated. Moving pegs are kinematic bodies: they push pieces around
+/// but nothing pushes them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum PegMotion {
+    /// Slides left and right around its position, `amplitude` pixels each way,
+    /// taking `period` seconds per full swing. `phase` (0.0 - 1.0) offsets where in the swing it starts.
+    Oscillate { amplitude: f32, period: f32, #[serde(default)] phase: f32 },
+    /// Spins in place at `speed` degrees per second (negative spins counter-clockwise).
+    Rotate { speed: f32 },
+}
+
+impl PegMotion {
+    /// Offset from the peg's resting position and extra rotation (radians) `time` seconds in.
+    pub fn pose_at(&self, time: f32) -> (f32, f32) {
+        match *self {
+            PegMotion::Oscillate { amplitude, period, phase } => {
+                if period <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                let angle = (time / period + phase) * std::f32::consts::TAU;
+                (amplitude * angle.sin(), 0.0)
+            }
+            PegMotion::Rotate { speed } => (0.0, (speed * time).to_radians()),
+        }
+    }
+}
+
+/// A single peg placed at an exact position.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PegConfig {
+    pub x: f32,
+    pub y: f32,
+    pub shape: PegShape,
+    #[serde(default = "default_restitution")]
+    pub restitution: f32,
+    /// Makes the peg move; None (the default) keeps it fixed in place
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<PegMotion>,
+    /// Golden pegs add +1 to the multiplier of every drop that strikes them
+    #[serde(default)]
+    pub golden: bool,
+    /// Makes the peg breakable: strong impacts wear these hit points down and the peg is
+    /// removed at zero. None (the default) keeps it unbreakable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_points: Option<f32>,
+    /// Makes the peg a pinball bumper: every piece that touches it is kicked straight away from it,
+    /// this many pixels per second faster. None (the default) leaves it an ordinary peg
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bumper: Option<f32>,
+}
+
+impl PegConfig {
+    /// A copy of the peg flipped across the vertical line x = `middle`: angles, spins and swings go the other way.
+    pub fn mirrored(&self, middle: f32) -> PegConfig {
+        let shape = match self.shape {
+            PegShape::Square { size, angle } => PegShape::Square { size, angle: -angle },
+            PegShape::Bar { length, thickness, angle } => PegShape::Bar { length, thickness, angle: -angle },
+            shape => shape,
+        };
+        let motion = self.motion.map(|motion| match motion {
+            PegMotion::Oscillate { amplitude, period, phase } => PegMotion::Oscillate { amplitude: -amplitude, period, phase },
+            PegMotion::Rotate { speed } => PegMotion::Rotate { speed: -speed },
+        });
+        PegConfig { x: 2.0 * middle - self.x, shape, motion, ..*self }
+    }
+}
+
+/// A multiplier zone: a see-through rectangle (positioned by its center) that adds `bonus`
+/// to the multiplier of every drop passing through it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ZoneConfig {
+    pub x: f32,
+    pub y: f32,
+    pub half_width: f32,
+    pub half_height: f32,
+    pub bonus: f32,
+}
+
+/// A chain of straight static segments through `points` (x, y), for funnels that channel pieces
+/// and ramps along the walls. Two points make a single segment; more make a bent line.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SegmentConfig {
+    pub points: Vec<[f32; 2]>,
+    #[serde(default = "default_friction")]
+    pub friction: f32,
+    #[serde(default = "default_restitution")]
+    pub restitution: f32,
+}
+
+/// A rectangle (positioned by its center) that pushes every piece overlapping it each physics step.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ForceFieldConfig {
+    pub x: f32,
+    pub y: f32,
+    pub half_width: f32,
+    pub half_height: f32,
+    pub effect: ForceEffect,
+}
+
+/// What a force field does to the pieces in it. Strengths are accelerations in pixels per second²
+/// (gravity is 800); wind and updrafts catch light balls more and heavy balls less.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum ForceEffect {
+    /// Blows sideways: positive strength pushes right, negative pushes left
+    Wind { strength: f32 },
+    /// Pushes upward against gravity
+    Updraft { strength: f32 },
+    /// Pulls pieces towards the field's center
+    Magnet { strength: f32 },
+}
+
+impl ForceFieldConfig {
+    /// True if the box from `min` to `max` (x, y) overlaps the field.
+    pub fn overlaps(&self, min: [f32; 2], max: [f32; 2]) -> bool {
+        min[0] <= self.x + self.half_width
+            && max[0] >= self.x - self.half_width
+            && min[1] <= self.y + self.half_height
+            && max[1] >= self.y - self.half_height
+    }
+
+    /// The acceleration (x, y) the field gives a piece of normal density whose center is at (x, y).
+    pub fn acceleration_at(&self, x: f32, y: f32) -> [f32; 2] {
+        match self.effect {
+            ForceEffect::Wind { strength } => [strength, 0.0],
+            ForceEffect::Updraft { strength } => [0.0, -strength],
+            ForceEffect::Magnet { strength } => {
+                let (dx, dy) = (self.x - x, self.y - y);
+                let distance = (dx * dx + dy * dy).sqrt();
+                // Right on the center there's no direction to pull in
+                if distance < 1.0 {
+                    return [0.0, 0.0];
+                }
+                [dx / distance * strength, dy / distance * strength]
+            }
+        }
+    }
+}
+
+/// A sticky pad: a solid ledge (positioned by its center) that holds on to every piece landing on it.
+/// `delay` seconds after sticking, the piece pays its stake × `bonus` (instead of landing in a bin)
+/// and is removed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct StickyPadConfig {
+    pub x: f32,
+    pub y: f32,
+    pub half_width: f32,
+    pub half_height: f32,
+    pub bonus: f32,
+    #[serde(default = "default_stick_delay")]
+    pub delay: f32,
+}
+
+/// A pool of water across the whole width of the board, from the top of the ground up to `surface`.
+/// Pieces in it are lifted by `density` (relative to a normal ball's: above 1.0 they float), slowed
+/// by `drag` (share of their speed lost per second) and pushed towards the nearer side of the board
+/// by `drift` (pixels per second²). All of it grows with how much of a piece is under the surface.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PoolConfig {
+    pub surface: f32,
+    #[serde(default = "default_pool_density")]
+    pub density: f32,
+    #[serde(default = "default_pool_drag")]
+    pub drag: f32,
+    #[serde(default)]
+    pub drift: f32,
+}
+
+impl PoolConfig {
+    /// How much of something reaching from `top` down to `bottom` is under the surface (0.0 - 1.0).
+    pub fn submerged(&self, top: f32, bottom: f32) -> f32 {
+        if bottom <= top {
+            return if bottom > self.surface { 1.0 } else { 0.0 };
+        }
+        ((bottom - self.surface) / (bottom - top)).clamp(0.0, 1.0)
+    }
+}
+
+/// A chain hanging from a fixed point at (`x`, `y`): `links` links, each `link_length` long and
+/// `thickness` across, joined end to end so they swing freely when a piece strikes them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ChainConfig {
+    pub x: f32,
+    pub y: f32,
+    pub links: usize,
+    pub link_length: f32,
+    #[serde(default = "default_chain_thickness")]
+    pub thickness: f32,
+}
+
+/// A stack of `cols` × `rows` loose square blocks, `block_size` across, standing on a ledge whose
+/// top is at `y` and which is centered on `x`. Every block a piece knocks over is worth `points`
+/// credits to whoever dropped the piece.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct BlockStackConfig {
+    pub x: f32,
+    pub y: f32,
+    pub cols: usize,
+    pub rows: usize,
+    pub block_size: f32,
+    #[serde(default)]
+    pub points: u32,
+}
+
+impl BlockStackConfig {
+    /// Number of blocks in the stack.
+    pub fn block_count(&self) -> usize {
+        self.cols * self.rows
+    }
+
+    /// Center (x, y) of every block in the stack, bottom row first, left to right.
+    pub fn block_positions(&self) -> Vec<(f32, f32)> {
+        let left = self.x - (self.cols as f32 - 1.0) * self.block_size / 2.0;
+        (0..self.rows)
+            .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+            .map(|(row, col)| (left + col as f32 * self.block_size, self.y - (row as f32 + 0.5) * self.block_size))
+            .collect()
+    }
+}
+
+/// A staggered grid of identical pegs.
+/// Columns are spread evenly from `left` to `right`; even rows are offset by half a column
+/// so pieces can't fall straight through. `shift` nudges the whole grid horizontally.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PegGrid {
+    pub rows: usize,
+    pub cols: usize,
+    pub top: f32,
+    pub row_spacing: f32,
+    pub left: f32,
+    pub right: f32,
+    #[serde(default)]
+    pub shift: f32,
+    /// Adds one more column to the left of `left`, one column spacing away
+    #[serde(default)]
+    pub extra_left_column: bool,
+    pub shape: PegShape,
+    #[serde(default = "default_restitution")]
+    pub restitution: f32,
+}
+
+/// A casino-style triangle of identical pegs: `rows` rows `row_spacing` apart from `top` down,
+/// the first with 3 pegs and every row after it with one more, `spacing` apart and centered on
+/// `center`. Unlike a grid it isn't scaled by the peg density setting, so it stays lined up
+/// with the bins under it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PegPyramid {
+    pub rows: usize,
+    pub center: f32,
+    pub top: f32,
+    pub spacing: f32,
+    pub row_spacing: f32,
+    pub shape: PegShape,
+    #[serde(default = "default_restitution")]
+    pub restitution: f32,
+}
+
+impl PegPyramid {
+    /// Number of pegs in the pyramid (3 in the top row and one more in every row after it).
+    pub fn peg_count(&self) -> usize {
+        self.rows.saturating_mul(self.rows.saturating_add(5)) / 2
+    }
+
+    /// Expands the pyramid into individual peg positions, top row first.
+    pub fn pegs(&self) -> Vec<PegConfig> {
+        let mut pegs = Vec::new();
+        for row in 0..self.rows {
+            let y = self.top + row as f32 * self.row_spacing;
+            let count = row + 3;
+            let first = self.center - (count - 1) as f32 * self.spacing / 2.0;
+            for index in 0..count {
+                let x = first + index as f32 * self.spacing;
+                pegs.push(PegConfig { x, y, shape: self.shape, restitution: self.restitution, motion: None, golden: false, hit_points: None, bumper: None });
+            }
+        }
+        pegs
+    }
+}
+
+// The casino's payout table for a pyramid of `rows` rows (medium risk), or None for a row count
+// it doesn't have one for
+fn pyramid_payouts(rows: usize) -> Option<Vec<f32>> {
+    let half: &[f32] = match rows {
+        8 => &[13.0, 3.0, 1.3, 0.7, 0.4],
+        12 => &[33.0, 11.0, 4.0, 2.0, 1.1, 0.6, 0.3],
+        16 => &[110.0, 41.0, 10.0, 5.0, 3.0, 1.5, 1.0, 0.5, 0.3],
+        _ => return None,
+    };
+    // Mirrored about the middle bin
+    Some(half.iter().chain(half.iter().rev().skip(1)).copied().collect())
+}
+
+// True when every size of a peg's shape is a finite number above zero and its angle is finite, so
+// its collider can be built (a shape squashed to nothing has no convex hull)
+fn peg_size_ok(shape: PegShape) -> bool {
+    let positive = |value: f32| value.is_finite() && value > 0.0;
+    match shape {
+        PegShape::Circle { radius } => positive(radius),
+        PegShape::Square { size, angle } => positive(size) && angle.is_finite(),
+        PegShape::Triangle { size } => positive(size),
+        PegShape::Bar { length, thickness, angle } => positive(length) && positive(thickness) && angle.is_finite(),
+    }
+}
+
+// Average multiplier of a drop if pieces spread over the bins like a fair Galton board
+// (a binomial spread: most in the middle, few on the edges)
+fn expected_payout(payouts: &[f32]) -> f32 {
+    let trials = payouts.len().saturating_sub(1);
+    let mut ways = 1.0;
+    let mut total = 0.0;
+    for (bin, payout) in payouts.iter().enumerate() {
+        total += ways * payout;
+        ways = ways * (trials - bin) as f32 / (bin + 1) as f32;
+    }
+    total / 2f32.powi(trials as i32)
+}
+
+fn default_friction() -> f32 {
+    0.4
+}
+
+fn default_restitution() -> f32 {
+    0.5
+}
+
+// Seconds a piece stays stuck to a sticky pad before it pays out
+fn default_stick_delay() -> f32 {
+    2.0
+}
+
+// A pool a normal ball slowly sinks through
+fn default_pool_density() -> f32 {
+    0.8
+}
+
+fn default_pool_drag() -> f32 {
+    2.0
+}
+
+fn default_chain_thickness() -> f32 {
+    4.0
+}
+
+impl PegGrid {
+    /// Number of pegs in the grid, the extra left column included.
+    pub fn peg_count(&self) -> usize {
+        self.rows.saturating_mul(self.cols.saturating_add(self.extra_left_column as usize))
+    }
+
+    /// Expands the grid into individual peg positions.
+    pub fn pegs(&self) -> Vec<PegConfig> {
+        let spacing = if self.cols > 1 { (self.right - self.left) / (self.cols as f32 - 1.0) } else { 0.0 };
+        let first_col = if self.extra_left_column { -1 } else { 0 };
+
+        let mut pegs = Vec::with_capacity(self.rows * (self.cols + 1));
+        for row in 0..self.rows {
+            let y = self.top + row as f32 * self.row_spacing;
+            let x_offset = if row % 2 == 0 { spacing / 2.0 } else { 0.0 };
+            for col in first_col..self.cols as i32 {
+                let x = self.left + col as f32 * spacing + x_offset + self.shift;
+                pegs.push(PegConfig { x, y, shape: self.shape, restitution: self.restitution, motion: None, golden: false, hit_points: None, bumper: None });
+            }
+        }
+        pegs
+    }
+
+    /// A copy of the grid with about `density` times as many rows and columns.
+    /// The grid still spans the same area; only the spacing between pegs changes.
+    pub fn scaled(&self, density: f32) -> PegGrid {
+        let rows = ((self.rows as f32 * density).round() as usize).max(1);
+        let cols = ((self.cols as f32 * density).round() as usize).max(2);
+        let row_spacing = if rows > 1 && self.rows > 1 {
+            self.row_spacing * (self.rows - 1) as f32 / (rows - 1) as f32
+        } else {
+            self.row_spacing
+        };
+        PegGrid { rows, cols, row_spacing, ..*self }
+    }
+}
+
+impl BoardConfig {
+    /// Loads and validates a board from a JSON file (works on native and on the web).
+    pub async fn load(path: &str) -> Result<BoardConfig, String> {
+        let text = macroquad::file::load_string(path).await.map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        Self::from_json(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Parses and validates a board from JSON text.
+    pub fn from_json(text: &str) -> Result<BoardConfig, String> {
+        let board: BoardConfig = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// Writes the board as JSON to SAVED_BOARDS_DIR, named after `name`, and returns the file's path.
+    /// The name is kept to letters, digits, '-' and '_' (spaces become '_') so it's a safe file name.
+    /// Browsers can't write files, so this fails on the web.
+    pub fn save_as(&self, name: &str) -> Result<String, String> {
+        let path = Self::saved_path(name)?;
+        let mut board = self.clone();
+        board.name = name.trim().to_string();
+        let text = serde_json::to_string_pretty(&board).map_err(|e| e.to_string())?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::create_dir_all(SAVED_BOARDS_DIR).map_err(|e| format!("Couldn't create {}: {}", SAVED_BOARDS_DIR, e))?;
+            std::fs::write(&path, text).map_err(|e| format!("Couldn't save {}: {}", path, e))?;
+            Ok(path)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (path, text);
+            Err("Saving boards isn't available on the web".to_string())
+        }
+    }
+
+    /// The file save_as writes a board named `name` to, or an error if the name has no usable characters.
+    pub fn saved_path(name: &str) -> Result<String, String> {
+        let file_name: String = name
+            .trim()
+            .chars()
+            .map(|c| if c == ' ' { '_' } else { c })
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if file_name.is_empty() {
+            return Err("Type a name for the board first".to_string());
+        }
+        Ok(format!("{}/{}.json", SAVED_BOARDS_DIR, file_name))
+    }
+
+    /// True if saving under `name` would replace a board saved before (never on the web).
+    pub fn is_saved(name: &str) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Self::saved_path(name).is_ok_and(|path| std::path::Path::new(&path).exists())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = name;
+            false
+        }
+    }
+
+    /// Checks the values serde can't: at least one bin, a payout for every bin, two points per segment,
+    /// no more than MAX_PEGS pegs, a finite size above zero for every peg (grids and pyramids included), positive hit points on breakable pegs, a positive kick on bumpers, no negative bonus or delay
+    /// on sticky pads, a pool surface above the ground with no negative density, drag or drift,
+    /// at least one link of some length and thickness in every chain, and no more than MAX_BLOCKS
+    /// blocks (each with a size) in the block stacks.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bins.count == 0 {
+            return Err("board needs at least one bin".to_string());
+        }
+        if self.payouts.len() != self.bins.count {
+            return Err(format!("board has {} bins but {} payouts", self.bins.count, self.payouts.len()));
+        }
+        if self.segments.iter().any(|segment| segment.points.len() < 2) {
+            return Err("every segment needs at least two points".to_string());
+        }
+        // Counted before the grids and pyramids are expanded, so a huge one is turned down without building it
+        let peg_count = self.pegs.len()
+            .saturating_add(self.peg_grids.iter().map(PegGrid::peg_count).fold(0, usize::saturating_add))
+            .saturating_add(self.peg_pyramids.iter().map(PegPyramid::peg_count).fold(0, usize::saturating_add));
+        if peg_count > MAX_PEGS {
+            return Err(format!("board has {} pegs, more than the {} allowed", peg_count, MAX_PEGS));
+        }
+        // Pegs from the grids and pyramids too, not just the ones listed one by one
+        let pegs = self.all_pegs();
+        if pegs.iter().any(|peg| !peg_size_ok(peg.shape)) {
+            return Err("every peg needs a size above zero".to_string());
+        }
+        if pegs.iter().any(|peg| peg.hit_points.is_some_and(|hit_points| hit_points <= 0.0)) {
+            return Err("breakable pegs need more than zero hit points".to_string());
+        }
+        if pegs.iter().any(|peg| peg.bumper.is_some_and(|kick| kick <= 0.0)) {
+            return Err("bumpers need a kick of more than zero".to_string());
+        }
+        if self.sticky_pads.iter().any(|pad| pad.bonus < 0.0 || pad.delay < 0.0) {
+            return Err("sticky pads can't have a negative bonus or delay".to_string());
+        }
+        if let Some(pool) = self.pool {
+            if pool.surface >= self.ground.y - self.ground.half_height {
+                return Err("the pool's surface has to be above the ground".to_string());
+            }
+            if pool.density < 0.0 || pool.drag < 0.0 || pool.drift < 0.0 {
+                return Err("the pool can't have a negative density, drag or drift".to_string());
+            }
+        }
+        if self.chains.iter().any(|chain| chain.links == 0 || chain.link_length <= 0.0 || chain.thickness <= 0.0) {
+            return Err("every chain needs at least one link, with a length and thickness above zero".to_string());
+        }
+        if self.block_stacks.iter().any(|stack| stack.block_size <= 0.0) {
+            return Err("blocks need a size above zero".to_string());
+        }
+        let blocks: usize = self.block_stacks.iter().map(BlockStackConfig::block_count).sum();
+        if blocks > MAX_BLOCKS {
+            return Err(format!("board has {} blocks, more than the {} allowed", blocks, MAX_BLOCKS));
+        }
+        Ok(())
+    }
+
+    /// A casino-style pyramid board of `rows` rows in the frame (ground and walls) of `frame`:
+    /// the pyramid is as wide as the ground allows, stands just above the bins and leaves room
+    /// above it for a funnel from the walls into its top, so a drop anywhere starts at the top.
+    /// Walls stand either side of the base, and the bins get the casino's payout table for
+    /// `rows` (or generated edge-high payouts for a row count it has none for).
+    pub fn pyramid(frame: &BoardConfig, rows: usize) -> BoardConfig {
+        let rows = rows.max(1);
+        let center = frame.ground.x;
+        let spacing = (frame.ground.half_width * 2.0 / (rows + 1) as f32).min(PYRAMID_MAX_SPACING);
+        let half_base = (rows + 1) as f32 * spacing / 2.0;
+        let wall_half_width = frame.walls.first().map_or(10.0, |wall| wall.half_width);
+
+        let mut bins = frame.bins.clone();
+        bins.count = rows + 1;
+        bins.divider_half_height = bins.divider_half_height.min(PYRAMID_DIVIDER_HALF_HEIGHT);
+        let ground = BoxConfig { x: center, half_width: half_base, ..frame.ground.clone() };
+        let bins_top = ground.y - ground.half_height - bins.divider_half_height * 2.0;
+
+        // The funnel ends a row and a half above the pyramid's top, half a gap either side of its top peg
+        let funnel_bottom = PYRAMID_FUNNEL_TOP + PYRAMID_FUNNEL_SLOPE * (half_base - spacing / 2.0);
+        let bottom = bins_top - spacing / 2.0;
+        let row_spacing = ((bottom - funnel_bottom) / (rows as f32 + 0.5)).min(spacing * PYRAMID_ROW_RATIO);
+        let top = bottom - row_spacing * (rows - 1) as f32;
+        let radius = (spacing * 0.07).clamp(2.5, 4.0);
+        let pyramid = PegPyramid { rows, center, top, spacing, row_spacing, shape: PegShape::Circle { radius }, restitution: default_restitution() };
+
+        // Each side: down the funnel from the wall to half a gap from the middle, right above the
+        // top peg so drops start on it, then back out along the pyramid's side to the wall. The
+        // side keeps PYRAMID_SIDE_GAP spacings outside the outer pegs, so a piece knocked outwards
+        // has room to fall past them into the edge bin. Slippery, so nothing rests on the funnel.
+        let side_top = spacing / 2.0 + spacing * PYRAMID_SIDE_GAP;
+        let side_bottom = top - row_spacing + (half_base - side_top) * 2.0 * row_spacing / spacing;
+        let side = |direction: f32| SegmentConfig {
+            points: vec![
+                [center + direction * half_base, PYRAMID_FUNNEL_TOP],
+                [center + direction * spacing / 2.0, top - row_spacing * 1.5],
+                [center + direction * side_top, top - row_spacing],
+                [center + direction * half_base, side_bottom],
+            ],
+            friction: 0.0,
+            restitution: 0.2,
+        };
+        let wall = |direction: f32| BoxConfig {
+            x: center + direction * (half_base + wall_half_width),
+            y: frame.walls.first().map_or(400.0, |wall| wall.y),
+            half_width: wall_half_width,
+            half_height: frame.walls.first().map_or(400.0, |wall| wall.half_height),
+            friction: default_friction(),
+        };
+
+        let mut board = BoardConfig {
+            name: format!("Casino {} Rows", rows),
+            ground,
+            walls: vec![wall(-1.0), wall(1.0)],
+            bins,
+            payouts: Vec::new(),
+            peg_grids: Vec::new(),
+            peg_pyramids: vec![pyramid],
+            pegs: Vec::new(),
+            zones: Vec::new(),
+            segments: vec![side(-1.0), side(1.0)],
+            force_fields: Vec::new(),
+            sticky_pads: Vec::new(),
+            pool: None,
+            chains: Vec::new(),
+            block_stacks: Vec::new(),
+        };
+        board.payouts = match pyramid_payouts(rows) {
+            Some(payouts) => payouts,
+            None => frame.with_bin_count(rows + 1, PayoutLayout::EdgesHigh).payouts,
+        };
+        board
+    }
+
+    /// A copy of the board with every peg grid scaled by `density` (1.0 leaves it unchanged).
+    /// Individually placed pegs (and pyramids) are kept as they are.
+    pub fn with_peg_density(&self, density: f32) -> BoardConfig {
+        let mut board = self.clone();
+        board.peg_grids = self.peg_grids.iter().map(|grid| grid.scaled(density)).collect();
+        board
+    }
+
+    /// A copy of the board with `count` bins across the same ground, paying symmetric payouts laid
+    /// out by `layout`. The payouts run between the board's own smallest and biggest ones and are
+    /// then scaled so a drop pays out about as much on average as it does on the board as designed.
+    /// A count of 0 leaves the board unchanged, and so does a board with a pyramid, whose bins
+    /// have to line up with it.
+    pub fn with_bin_count(&self, count: usize, layout: PayoutLayout) -> BoardConfig {
+        let mut board = self.clone();
+        if count == 0 || !self.peg_pyramids.is_empty() {
+            return board;
+        }
+        let low = self.payouts.iter().copied().fold(f32::MAX, f32::min);
+        let high = self.payouts.iter().copied().fold(0.0, f32::max);
+        let middle = (count as f32 - 1.0) / 2.0;
+        let shape: Vec<f32> = (0..count)
+            .map(|bin| {
+                // 0.0 in the middle of the row, 1.0 on the edges
+                let edge = if middle > 0.0 { (bin as f32 - middle).abs() / middle } else { 0.0 };
+                let rise = match layout {
+                    PayoutLayout::EdgesHigh => edge * edge,
+                    PayoutLayout::CenterHigh => (1.0 - edge) * (1.0 - edge),
+                };
+                low + (high - low) * rise
+            })
+            .collect();
+        let scale = expected_payout(&self.payouts) / expected_payout(&shape).max(f32::EPSILON);
+        // Rounded to tenths so they read well on the labels, and never nothing at all
+        board.payouts = shape.iter().map(|payout| ((payout * scale * 10.0).round() / 10.0).max(0.1)).collect();
+        board.bins.count = count;
+        board
+    }
+
+    /// A copy of the board with its payout table reshaped for `risk`: each payout is raised to a
+    /// power (flattening the table for low risk, stretching it for high risk), then the table is
+    /// scaled so a drop pays out as much on average as before. Medium leaves it unchanged.
+    pub fn with_risk(&self, risk: RiskLevel) -> BoardConfig {
+        let mut board = self.clone();
+        if risk == RiskLevel::Medium {
+            return board;
+        }
+        let shape: Vec<f32> = self.payouts.iter().map(|payout| payout.max(0.1).powf(risk.exponent())).collect();
+        let scale = expected_payout(&self.payouts) / expected_payout(&shape).max(f32::EPSILON);
+        board.payouts = shape.iter().map(|payout| ((payout * scale * 10.0).round() / 10.0).max(0.1)).collect();
+        board
+    }
+
+    /// What a drop pays back on average (as a multiple of the bet), with pieces spreading over
+    /// the bins like on a Galton board.
+    pub fn expected_payout(&self) -> f32 {
+        expected_payout(&self.payouts)
+    }
+
+    /// Every peg on the board: the expanded grids and pyramids followed by the individually placed pegs.
+    pub fn all_pegs(&self) -> Vec<PegConfig> {
+        let mut pegs: Vec<PegConfig> = self.peg_grids.iter().flat_map(PegGrid::pegs).collect();
+        pegs.extend(self.peg_pyramids.iter().flat_map(PegPyramid::pegs));
+        pegs.extend(self.pegs.iter().copied());
+        pegs
+    }
+
+    // Left edge of the ground, where the first bin starts
+    pub fn bins_left(&self) -> f32 {
+        self.ground.x - self.ground.half_width
+    }
+
+    // Width of a single bin
+    pub fn bin_width(&self) -> f32 {
+        self.ground.half_width * 2.0 / self.bins.count as f32
+    }
+
+    // Top edge of the ground platform
+    pub fn ground_top(&self) -> f32 {
+        self.ground.y - self.ground.half_height
+    }
+
+    // Top edge of the bin dividers; anything resting below this line is inside a bin
+    pub fn bins_top(&self) -> f32 {
+        self.ground_top() - self.bins.divider_half_height * 2.0
+    }
+
+    /// Returns the index of the bin (0 = leftmost) that contains the given x coordinate.
+    pub fn bin_index(&self, x: f32) -> usize {
+        let index = ((x - self.bins_left()) / self.bin_width()).floor().max(0.0) as usize;
+        index.min(self.bins.count - 1)
+    }
+
+    /// Whether `bin` is in the middle of the row: the single middle bin when the count is odd,
+    /// either of the two middle bins when it is even.
+    pub fn is_center_bin(&self, bin: usize) -> bool {
+        let count = self.bins.count;
+        bin == count / 2 || (count.is_multiple_of(2) && bin + 1 == count / 2)
+    }
+}
@@ -0,0 +1,361 @@
+/*
+Program Details: Procedural board generator
+
+A BoardGenerator fills the frame of a board (its ground, walls, bins and
+payouts) with a random but playable layout of pegs, shaped by a few
+parameters:
+- density: how many rows and columns of pegs there are (0.0 sparse - 1.0 dense)
+- symmetric: whether the right half is a mirror image of the left
+- shape_mix: how likely each peg shape is (circle, square, triangle, bar)
+- obstacle_budget: how many special features are added (golden, breakable and
+  moving pegs, and multiplier zones)
+
+Pegs sit on staggered rows like a Galton board, each nudged a little off its
+spot, with the odd one left out on sparse boards. Every layout is then tried out
+headless: a row of balls is dropped across the whole width on a PhysicsWorld of
+its own, and the layout is rejected if a ball gets stuck or lost on the way down
+(a jam) or a bin receives none of them (an unreachable bin). A PlayableSearch
+keeps trying new layouts until one passes, running the trials a few physics
+steps a frame so the game doesn't freeze while it looks; generate_playable does
+the same search all at once.
+
+Layouts are made with a random number generator of their own seeded from the
+seed asked for, so the same seed always gives the same board and generating
+one doesn't disturb the global generator the drops use.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod board_generator;
+
+Then with the other use commands add:
+use crate::modules::board_generator::{BoardGenerator, GeneratorParams};
+
+Then above the loop section to use you would go:
+    let generator = BoardGenerator::new(GeneratorParams::default());
+
+To make a new board in the frame of another one, start a search:
+    let mut search = generator.search(&classic_board, seed);
+
+Then in the loop, until it has an answer:
+    match search.update() {
+        Some(Ok(board)) => world.build_board(&board),
+        Some(Err(error)) => println!("{}", error),
+        None => {} // still trying layouts
+    }
+*/
+use macroquad::rand::RandGenerator;
+use crate::modules::aim::DropAimer;
+use crate::modules::board::{BoardConfig, PegConfig, PegMotion, PegShape, ZoneConfig};
+use crate::modules::events::GameEvent;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+
+// Layouts tried by generate_playable before it gives up
+const MAX_ATTEMPTS: usize = 8;
+// Space left clear above the first row (for dropping) and above the bins
+const TOP_ROW: f32 = 120.0;
+const BOTTOM_MARGIN: f32 = 45.0;
+// Space left clear inside the drop range, so a ball can't wedge between a wall and a peg
+const SIDE_MARGIN: f32 = 25.0;
+// Fewest and most rows and columns, at density 0.0 and 1.0
+const MIN_ROWS: f32 = 6.0;
+const MAX_ROWS: f32 = 12.0;
+const MIN_COLUMNS: f32 = 8.0;
+const MAX_COLUMNS: f32 = 15.0;
+// How far a peg may be nudged off its spot, as a share of the spacing
+const JITTER: f32 = 0.15;
+// Chance of a spot being left empty on the sparsest boards
+const MAX_HOLE_CHANCE: f32 = 0.3;
+// Gap kept between neighbouring pegs, so a ball (14 across) always fits through
+const MIN_GAP: f32 = 22.0;
+// Test drops per bin in the headless trial, and how long they get to land
+const TEST_DROPS_PER_BIN: usize = 4;
+const TEST_SECONDS: f32 = 12.0;
+// Physics steps of the trial run per frame by a PlayableSearch
+const STEPS_PER_FRAME: usize = 60;
+// Height of the first test drop, and how much higher each next one starts so the balls don't
+// fall in step (two mirrored balls landing on a peg together can hold each other up)
+const TEST_DROP_Y: f32 = 50.0;
+const TEST_DROP_STAGGER: f32 = 12.0;
+// Pegs closer than this to the middle line count as on it (they aren't mirrored)
+const MIDDLE_TOLERANCE: f32 = 1.0;
+
+/// What kind of board to generate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneratorParams {
+    /// How many rows and columns of pegs: 0.0 is sparse, 1.0 dense
+    pub density: f32,
+    /// Makes the right half a mirror image of the left
+    pub symmetric: bool,
+    /// Relative chances of a circle, square, triangle and bar peg
+    pub shape_mix: [f32; 4],
+    /// How many golden, breakable and moving pegs and multiplier zones are added
+    pub obstacle_budget: usize,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        Self { density: 0.5, symmetric: true, shape_mix: [6.0, 2.0, 2.0, 1.0], obstacle_budget: 4 }
+    }
+}
+
+pub struct BoardGenerator {
+    pub params: GeneratorParams,
+}
+
+impl BoardGenerator {
+    pub fn new(params: GeneratorParams) -> Self {
+        Self { params }
+    }
+
+    /// Starts looking for a layout from `seed` (and the seeds after it) that passes the headless trial,
+    /// with the generator's current parameters. See PlayableSearch::update.
+    pub fn search(&self, frame: &BoardConfig, seed: u64) -> PlayableSearch {
+        let generator = BoardGenerator::new(self.params);
+        let trial = Trial::new(generator.generate(frame, seed));
+        PlayableSearch { generator, frame: frame.clone(), seed, attempt: 0, trial }
+    }
+
+    /// Generates layouts from `seed` (and the seeds after it) until one passes the headless trial, all
+    /// at once. Returns the reason the last one failed if none of them did.
+    pub fn generate_playable(&self, frame: &BoardConfig, seed: u64) -> Result<BoardConfig, String> {
+        let mut search = self.search(frame, seed);
+        loop {
+            if let Some(result) = search.update() {
+                return result;
+            }
+        }
+    }
+
+    /// One random layout from `seed` inside the frame (ground, walls, bins and payouts) of
+    /// `frame`. Not checked for playability; see trial().
+    pub fn generate(&self, frame: &BoardConfig, seed: u64) -> BoardConfig {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let density = self.params.density.clamp(0.0, 1.0);
+        // Pegs go as far out as the nearer wall allows on both sides, so a mirrored board fits too
+        let middle = frame.ground.x;
+        let (drop_left, drop_right) = DropAimer::drop_range(frame);
+        let half_width = (middle - drop_left).min(drop_right - middle) - SIDE_MARGIN;
+        let (left, right) = (middle - half_width, middle + half_width);
+        let bottom = frame.bins_top() - BOTTOM_MARGIN;
+
+        let rows = (MIN_ROWS + (MAX_ROWS - MIN_ROWS) * density).round() as usize;
+        let cols = (MIN_COLUMNS + (MAX_COLUMNS - MIN_COLUMNS) * density).round() as usize;
+        let row_spacing = (bottom - TOP_ROW) / (rows - 1) as f32;
+        let spacing = (right - left) / (cols - 1) as f32;
+        let hole_chance = MAX_HOLE_CHANCE * (1.0 - density);
+
+        // Staggered rows centered on the middle of the board: odd rows have one peg fewer
+        let mut pegs = Vec::new();
+        for row in 0..rows {
+            let in_row = if row % 2 == 0 { cols } else { cols - 1 };
+            let y = TOP_ROW + row as f32 * row_spacing;
+            for col in 0..in_row {
+                let x = middle + (col as f32 - (in_row - 1) as f32 / 2.0) * spacing;
+                let on_middle = (x - middle).abs() < MIDDLE_TOLERANCE;
+                // The right half is filled in by mirroring the left
+                if self.params.symmetric && x > middle + MIDDLE_TOLERANCE {
+                    continue;
+                }
+                if rng.gen_range(0.0, 1.0) < hole_chance {
+                    continue;
+                }
+                let jitter_x = if self.params.symmetric && on_middle { 0.0 } else { rng.gen_range(-JITTER, JITTER) * spacing };
+                let jitter_y = rng.gen_range(-JITTER, JITTER) * row_spacing;
+                let shape = self.random_shape(&rng, spacing.min(row_spacing), self.params.symmetric && on_middle);
+                pegs.push(PegConfig { x: (x + jitter_x).clamp(left, right), y: y + jitter_y, shape, restitution: 0.5, motion: None, golden: false, hit_points: None, bumper: None });
+            }
+        }
+
+        let mut zones = Vec::new();
+        self.add_obstacles(&rng, &mut pegs, &mut zones, spacing, middle, (left, right, bottom));
+
+        if self.params.symmetric {
+            let mirrored: Vec<PegConfig> = pegs.iter().filter(|peg| (peg.x - middle).abs() >= MIDDLE_TOLERANCE).map(|peg| peg.mirrored(middle)).collect();
+            pegs.extend(mirrored);
+        }
+
+        BoardConfig {
+            name: format!("Random {}", seed % 10_000),
+            pegs,
+            peg_grids: Vec::new(),
+            peg_pyramids: Vec::new(),
+            zones,
+            segments: Vec::new(),
+            force_fields: Vec::new(),
+            sticky_pads: Vec::new(),
+            pool: None,
+            chains: Vec::new(),
+            block_stacks: Vec::new(),
+            ..frame.clone()
+        }
+    }
+
+    // A shape picked by the shape mix with `rng`, small enough to leave MIN_GAP to its neighbours `spacing`
+    // away. Pegs on the middle line of a symmetric board get shapes that are symmetric themselves.
+    fn random_shape(&self, rng: &RandGenerator, spacing: f32, symmetric: bool) -> PegShape {
+        let max_extent = ((spacing - MIN_GAP) / 2.0).max(4.0);
+        let mix = self.params.shape_mix.map(|weight| weight.max(0.0));
+        let total: f32 = mix.iter().sum();
+        let mut pick = rng.gen_range(0.0, total.max(f32::EPSILON));
+        let kind = mix.iter().position(|weight| {
+            pick -= weight;
+            pick < 0.0
+        });
+        match kind.unwrap_or(0) {
+            1 => {
+                let size = rng.gen_range(10.0, 16.0f32).min(max_extent * std::f32::consts::SQRT_2);
+                let angle = if symmetric || rng.gen_range(0, 2) == 0 { 45.0 } else { rng.gen_range(0.0, 90.0) };
+                PegShape::Square { size, angle }
+            }
+            2 => PegShape::Triangle { size: rng.gen_range(12.0, 18.0f32).min(max_extent * 3f32.sqrt()) },
+            3 => {
+                let length = rng.gen_range(24.0, 40.0f32).min(max_extent * 2.0);
+                let angle = if symmetric { 0.0 } else { rng.gen_range(-30.0, 30.0) };
+                PegShape::Bar { length, thickness: 6.0, angle }
+            }
+            _ => PegShape::Circle { radius: rng.gen_range(6.0, 9.0f32).min(max_extent) },
+        }
+    }
+
+    // Spends the obstacle budget on golden, breakable and moving pegs and multiplier zones, picked with `rng`.
+    // `area` is the left, right and bottom edge of where pegs go.
+    fn add_obstacles(&self, rng: &RandGenerator, pegs: &mut [PegConfig], zones: &mut Vec<ZoneConfig>, spacing: f32, middle: f32, area: (f32, f32, f32)) {
+        if pegs.is_empty() {
+            return;
+        }
+        let (left, right, bottom) = area;
+        for _ in 0..self.params.obstacle_budget {
+            let peg = &mut pegs[rng.gen_range(0, pegs.len())];
+            match rng.gen_range(0, 4) {
+                0 => peg.golden = true,
+                1 => peg.hit_points = Some(rng.gen_range(2.0, 4.0)),
+                2 => {
+                    peg.motion = Some(match peg.shape {
+                        PegShape::Bar { .. } => PegMotion::Rotate { speed: rng.gen_range(60.0, 150.0) },
+                        // Swings stay within a third of the spacing so it never reaches a neighbour
+                        _ => PegMotion::Oscillate { amplitude: spacing / 3.0 - 4.0, period: rng.gen_range(2.0, 4.0), phase: rng.gen_range(0.0, 1.0) },
+                    });
+                }
+                _ => {
+                    let half_width = rng.gen_range(40.0, 70.0);
+                    // A zone on a symmetric board sits on the middle line so it mirrors onto itself
+                    let x = if self.params.symmetric { middle } else { rng.gen_range(left + half_width, right - half_width) };
+                    let y = rng.gen_range(TOP_ROW + 60.0, bottom - 60.0);
+                    zones.push(ZoneConfig { x, y, half_width, half_height: 18.0, bonus: 1.0 });
+                }
+            }
+        }
+    }
+
+    /// Drops a row of balls across the whole width of `board` on a headless world, and fails if a
+    /// ball never lands in a bin (stuck on a peg, or lost off the board) or a bin gets no ball at all.
+    pub fn trial(board: &BoardConfig) -> Result<(), String> {
+        let mut trial = Trial::new(board.clone());
+        loop {
+            if let Some(result) = trial.advance(STEPS_PER_FRAME) {
+                return result;
+            }
+        }
+    }
+}
+
+/// A search for a playable layout, started by BoardGenerator::search, that runs the headless trials a
+/// few physics steps at a time.
+pub struct PlayableSearch {
+    // A copy of the generator, so changing its parameters doesn't change a search under way
+    generator: BoardGenerator,
+    frame: BoardConfig,
+    seed: u64,
+    // Layouts given up on so far
+    attempt: usize,
+    // The layout being tried out
+    trial: Trial,
+}
+
+impl PlayableSearch {
+    /// Runs this frame's physics steps of the trial. Returns the first layout to pass, or the reason
+    /// the last one failed once MAX_ATTEMPTS have; None while it is still trying.
+    pub fn update(&mut self) -> Option<Result<BoardConfig, String>> {
+        match self.trial.advance(STEPS_PER_FRAME)? {
+            Ok(()) => Some(Ok(self.trial.board.clone())),
+            Err(reason) => {
+                self.attempt += 1;
+                if self.attempt >= MAX_ATTEMPTS {
+                    return Some(Err(format!("No playable board in {} tries ({})", MAX_ATTEMPTS, reason)));
+                }
+                let seed = self.seed.wrapping_add(self.attempt as u64);
+                self.trial = Trial::new(self.generator.generate(&self.frame, seed));
+                None
+            }
+        }
+    }
+}
+
+// One layout's headless trial: a row of balls falling through it
+struct Trial {
+    board: BoardConfig,
+    world: PhysicsWorld,
+    drops: usize,
+    // Balls landed so far, and which bins got one
+    landed: usize,
+    bins_reached: Vec<bool>,
+    time: f32,
+}
+
+impl Trial {
+    fn new(board: BoardConfig) -> Self {
+        let mut world = PhysicsWorld::new();
+        world.build_board(&board);
+
+        let drops = board.bins.count * TEST_DROPS_PER_BIN;
+        let (left, right) = DropAimer::drop_range(&board);
+        let spacing = (right - left) / (drops - 1).max(1) as f32;
+        for drop in 0..drops {
+            world.spawn(ShapeKind::Ball, left + drop as f32 * spacing, TEST_DROP_Y - drop as f32 * TEST_DROP_STAGGER);
+        }
+        let bins_reached = vec![false; board.bins.count];
+        Self { board, world, drops, landed: 0, bins_reached, time: 0.0 }
+    }
+
+    // Runs up to `steps` physics steps; returns how the trial went once every ball has landed, one
+    // is lost or TEST_SECONDS have passed
+    fn advance(&mut self, steps: usize) -> Option<Result<(), String>> {
+        for _ in 0..steps {
+            if self.landed >= self.drops || self.time >= TEST_SECONDS {
+                break;
+            }
+            let dt = self.world.integration_params.dt;
+            self.world.advance(dt);
+            self.time += dt;
+            let mut settled = Vec::new();
+            for event in self.world.events() {
+                match *event {
+                    GameEvent::Settled { piece, bin } => {
+                        settled.push(piece);
+                        self.bins_reached[bin] = true;
+                    }
+                    GameEvent::PieceLost { .. } => return Some(Err("a ball was lost off the board".to_string())),
+                    _ => {}
+                }
+            }
+            // Landed balls are taken out so the bins don't fill up and the rest fall faster
+            self.landed += settled.len();
+            for piece in settled {
+                self.world.remove_body(piece);
+            }
+        }
+
+        if self.landed < self.drops && self.time < TEST_SECONDS {
+            return None;
+        }
+        if self.landed < self.drops {
+            return Some(Err(format!("{} of {} balls got stuck", self.drops - self.landed, self.drops)));
+        }
+        if let Some(bin) = self.bins_reached.iter().position(|reached| !reached) {
+            return Some(Err(format!("no ball reached bin {}", bin + 1)));
+        }
+        Some(Ok(()))
+    }
+}
@@ -0,0 +1,243 @@
+/*
+Program Details: Board themes (background, palette and parallax decoration)
+
+A board theme decides how the board looks: the background colour, an optional
+background image stretched over the screen, the colours of the pegs, the walls
+(and bin dividers, funnels and ground), the squares and triangles and each ball
+variant, and any number of parallax layers: transparent images tiled across the
+screen behind the board that shift with the mouse (more the bigger their depth)
+and drift sideways on their own.
+
+Themes live in JSON files in assets/themes, listed by assets/themes/themes.json,
+so a new theme is a new file (and its images) added to the list, with no code
+changes. Colours are written "#rrggbb" or "#rrggbbaa"; anything a theme leaves
+out keeps the Classic look. A theme that can't be read is skipped, and the
+built-in Classic theme is used if none can be.
+
+    {
+      "name": "Casino Neon",
+      "background": "#0a0018",
+      "background_image": "assets/themes/neon_bg.png",
+      "parallax": [{ "image": "assets/themes/neon_stars.png", "depth": 0.03, "drift": 12.0, "tint": "#ffffffcc" }],
+      "peg": "#00f0ff",
+      "wall": "#ff2bd6",
+      "piece": "#ff3860",
+      "balls": ["#fff200", "#c0c0ff", "#39ff14", "#ff6ec7"]
+    }
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod board_theme;
+
+Then with the other use commands add:
+use crate::modules::board_theme::{load_themes, BoardTheme};
+
+Then above the loop section to use you would go:
+    let themes = load_themes().await;
+    let theme = &themes[0];
+
+Then in the loop you would use (before anything else on the board is drawn):
+    theme.draw_background(get_time() as f32);
+    draw_circle(x, y, radius, theme.peg_color());
+*/
+use macroquad::prelude::*;
+use serde::Deserialize;
+use crate::modules::layout::current_resolution;
+use crate::modules::physics::BallVariant;
+use crate::modules::scale::mouse_position_world as mouse_position;
+
+// The list of theme files
+pub const THEMES_INDEX: &str = "assets/themes/themes.json";
+// Folder the theme files named in the list are in
+const THEMES_DIR: &str = "assets/themes";
+// Theme used when the one in the settings can't be found
+pub const DEFAULT_THEME: &str = "Classic";
+
+// A colour written "#rrggbb" or "#rrggbbaa" in a theme file
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(try_from = "String")]
+struct HexColor(Color);
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let digits = text.strip_prefix('#').unwrap_or(&text);
+        let value = u32::from_str_radix(digits, 16).map_err(|_| format!("{} isn't a colour", text))?;
+        match digits.len() {
+            6 => Ok(HexColor(Color::from_hex(value))),
+            8 => Ok(HexColor(Color::from_rgba((value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8))),
+            _ => Err(format!("{} isn't a colour", text)),
+        }
+    }
+}
+
+// One parallax layer as written in a theme file
+#[derive(Deserialize, Clone, Debug)]
+struct ParallaxConfig {
+    image: String,
+    // How far the layer shifts with the mouse: 0 stays put, 0.05 moves 5% of the mouse's distance
+    // from the middle of the screen (the other way, as if it were behind the board)
+    #[serde(default)]
+    depth: f32,
+    // Sideways drift in pixels per second
+    #[serde(default)]
+    drift: f32,
+    #[serde(default = "opaque")]
+    tint: HexColor,
+}
+
+fn opaque() -> HexColor {
+    HexColor(WHITE)
+}
+
+// A theme file; anything left out keeps the Classic look
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+struct ThemeConfig {
+    name: String,
+    background: HexColor,
+    background_image: Option<String>,
+    parallax: Vec<ParallaxConfig>,
+    peg: HexColor,
+    wall: HexColor,
+    piece: HexColor,
+    // One per ball variant, in BallVariant::ALL order
+    balls: [HexColor; 4],
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_THEME.to_string(),
+            background: HexColor(BLACK),
+            background_image: None,
+            parallax: Vec::new(),
+            peg: HexColor(GREEN),
+            wall: HexColor(GREEN),
+            piece: HexColor(RED),
+            balls: [HexColor(YELLOW), HexColor(LIGHTGRAY), HexColor(SKYBLUE), HexColor(PINK)],
+        }
+    }
+}
+
+// A parallax layer with its image loaded
+struct ParallaxLayer {
+    texture: Texture2D,
+    depth: f32,
+    drift: f32,
+    tint: Color,
+}
+
+/// A theme with its images loaded, ready to draw the board with.
+pub struct BoardTheme {
+    config: ThemeConfig,
+    background: Option<Texture2D>,
+    layers: Vec<ParallaxLayer>,
+}
+
+impl Default for BoardTheme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl BoardTheme {
+    /// The built-in theme: the look the game has always had, on a black background.
+    pub fn classic() -> Self {
+        Self { config: ThemeConfig::default(), background: None, layers: Vec::new() }
+    }
+
+    /// Loads a theme file and its images (works on native and on the web).
+    pub async fn load(path: &str) -> Result<BoardTheme, String> {
+        let text = macroquad::file::load_string(path).await.map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        let config: ThemeConfig = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+        let background = match &config.background_image {
+            Some(image) => Some(load_theme_texture(image).await?),
+            None => None,
+        };
+        let mut layers = Vec::with_capacity(config.parallax.len());
+        for layer in &config.parallax {
+            let texture = load_theme_texture(&layer.image).await?;
+            layers.push(ParallaxLayer { texture, depth: layer.depth, drift: layer.drift, tint: layer.tint.0 });
+        }
+        Ok(BoardTheme { config, background, layers })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn peg_color(&self) -> Color {
+        self.config.peg.0
+    }
+
+    /// Colour of the walls, bin dividers, funnels and ground.
+    pub fn wall_color(&self) -> Color {
+        self.config.wall.0
+    }
+
+    /// Colour of the squares and triangles.
+    pub fn piece_color(&self) -> Color {
+        self.config.piece.0
+    }
+
+    pub fn ball_color(&self, variant: BallVariant) -> Color {
+        let index = BallVariant::ALL.iter().position(|ball| *ball == variant).unwrap_or(0);
+        self.config.balls[index].0
+    }
+
+    /// Fills the screen with the background colour and image, then draws the parallax layers
+    /// over it in the order they are listed (furthest first). `time` drives the drift.
+    pub fn draw_background(&self, time: f32) {
+        let (width, height) = current_resolution();
+        draw_rectangle(0.0, 0.0, width, height, self.config.background.0);
+        if let Some(texture) = &self.background {
+            draw_texture_ex(texture, 0.0, 0.0, WHITE, DrawTextureParams { dest_size: Some(vec2(width, height)), ..Default::default() });
+        }
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let (from_center_x, from_center_y) = (mouse_x - width / 2.0, mouse_y - height / 2.0);
+        for layer in &self.layers {
+            // Tiles as tall as the screen, as wide as the image's shape makes them, repeated sideways
+            let tile_width = height * layer.texture.width() / layer.texture.height().max(1.0);
+            let shift = layer.drift * time - from_center_x * layer.depth;
+            let start = shift.rem_euclid(tile_width) - tile_width;
+            let y = -from_center_y * layer.depth;
+            let mut x = start;
+            while x < width {
+                let params = DrawTextureParams { dest_size: Some(vec2(tile_width, height)), ..Default::default() };
+                draw_texture_ex(&layer.texture, x, y, layer.tint, params);
+                x += tile_width;
+            }
+        }
+    }
+}
+
+// Loads an image named in a theme file, filtered smoothly since it is stretched over the screen
+async fn load_theme_texture(path: &str) -> Result<Texture2D, String> {
+    let texture = load_texture(path).await.map_err(|e| format!("Couldn't load {}: {}", path, e))?;
+    texture.set_filter(FilterMode::Linear);
+    Ok(texture)
+}
+
+/// Loads every theme listed in THEMES_INDEX, in its order, skipping any that can't be loaded.
+/// Never empty: without any theme files the built-in Classic theme is the only one.
+pub async fn load_themes() -> Vec<BoardTheme> {
+    let files: Vec<String> = match macroquad::file::load_string(THEMES_INDEX).await {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    let mut themes = Vec::with_capacity(files.len());
+    for file in files {
+        match BoardTheme::load(&format!("{}/{}", THEMES_DIR, file)).await {
+            Ok(theme) => themes.push(theme),
+            Err(error) => eprintln!("Skipping theme: {}", error),
+        }
+    }
+    if themes.is_empty() {
+        themes.push(BoardTheme::classic());
+    }
+    themes
+}
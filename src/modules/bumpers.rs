@@ -0,0 +1,90 @@
+/*
+Program Details: Pinball bumper flashes and hit scoring
+
+Bumper pegs (see PegConfig::bumper in board.rs) kick every piece that touches
+them away on top of their bounce; PhysicsWorld gives the push and reports a
+GameEvent::BumperHit. BumperFlashes turns those hits into the rest of the
+bumper's behaviour: the bumper lights up for a few frames, fading from white
+back to its own colour, and every hit is worth BUMPER_POINTS credits to whoever
+dropped the piece.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod bumpers;
+
+Then with the other use commands add:
+use crate::modules::bumpers::{BumperFlashes, BUMPER_POINTS};
+
+Then above the loop section to use you would go:
+    let mut bumpers = BumperFlashes::new();
+
+Then in the loop you would use:
+    for piece in bumpers.record_events(world.events()) {
+        wallet.deposit(BUMPER_POINTS);
+    }
+    bumpers.update(dt);
+    // ... when drawing a peg whose collider is a bumper:
+    let color = bumpers.color(peg_body_handle);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::HashMap;
+use crate::modules::events::GameEvent;
+
+// Colour of a bumper at rest
+pub const BUMPER_COLOR: Color = Color::new(1.0, 0.35, 0.55, 1.0);
+// Credits every bumper hit scores for the piece's owner
+pub const BUMPER_POINTS: u32 = 2;
+// How long a bumper stays lit after a hit, in seconds (a few frames)
+const FLASH_SECONDS: f32 = 0.12;
+
+/// How lit up each bumper is after its latest hit.
+#[derive(Default)]
+pub struct BumperFlashes {
+    // Seconds of flash left on each bumper that was hit lately
+    flashes: HashMap<RigidBodyHandle, f32>,
+}
+
+impl BumperFlashes {
+    pub fn new() -> Self {
+        Self { flashes: HashMap::new() }
+    }
+
+    /// Lights up every bumper hit in a frame's GameEvents and returns the pieces that hit one,
+    /// once per hit, for scoring.
+    pub fn record_events(&mut self, events: &[GameEvent]) -> Vec<RigidBodyHandle> {
+        let mut scored = Vec::new();
+        for event in events {
+            if let GameEvent::BumperHit { piece, bumper, .. } = *event {
+                self.flashes.insert(bumper, FLASH_SECONDS);
+                scored.push(piece);
+            }
+        }
+        scored
+    }
+
+    /// Fades the flashes by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        for left in self.flashes.values_mut() {
+            *left -= dt;
+        }
+        self.flashes.retain(|_, left| *left > 0.0);
+    }
+
+    /// The colour to draw a bumper in: white right after a hit, back to BUMPER_COLOR as the flash fades.
+    pub fn color(&self, bumper: RigidBodyHandle) -> Color {
+        let flash = self.flashes.get(&bumper).map_or(0.0, |left| left / FLASH_SECONDS);
+        Color::new(
+            BUMPER_COLOR.r + (1.0 - BUMPER_COLOR.r) * flash,
+            BUMPER_COLOR.g + (1.0 - BUMPER_COLOR.g) * flash,
+            BUMPER_COLOR.b + (1.0 - BUMPER_COLOR.b) * flash,
+            1.0,
+        )
+    }
+
+    /// Forgets every flash (e.g. when the board is rebuilt).
+    pub fn clear(&mut self) {
+        self.flashes.clear();
+    }
+}
@@ -0,0 +1,76 @@
+/*
+Program Details: Drawing the board's hanging chains
+
+Chains hang from fixed anchors on the board (see ChainConfig in board.rs). Each
+link is a dynamic body, and PhysicsWorld joins every link to the one above it (or
+to the anchor) with a revolute joint, so a struck chain swings and settles back.
+The links aren't drawn like the rest of the board's bodies: every link is drawn
+as a bar along its collider, and every joint as a ring where the two bodies it
+holds together meet, placed from the joint's own anchor so the chain shows how
+it is put together even while it swings.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod chain;
+
+Then with the other use commands add:
+use crate::modules::chain::draw_chains;
+
+Then in the loop you would use:
+    draw_chains(&world);
+*/
+use macroquad::prelude::*;
+use crate::modules::entity_registry::EntityKind;
+use crate::modules::physics::PhysicsWorld;
+
+// Colour of the links and of the rings at the joints
+const LINK_COLOR: Color = Color::new(0.75, 0.75, 0.8, 1.0);
+const JOINT_COLOR: Color = Color::new(0.45, 0.45, 0.5, 1.0);
+// Radius of an anchor, and of the ring at each joint, as a share of the link's thickness
+const ANCHOR_SCALE: f32 = 1.5;
+const JOINT_SCALE: f32 = 0.6;
+
+/// Draws every chain on the board: anchors, links and the joints between them, each body where it
+/// is drawn this frame (see PhysicsWorld::render_pose).
+pub fn draw_chains(world: &PhysicsWorld) {
+    for (handle, body) in world.bodies.iter() {
+        if EntityKind::of(body) != Some(EntityKind::Chain) {
+            continue;
+        }
+        let (pos, rot) = world.render_pose(handle, body);
+        let Some(collider) = body.colliders().first().and_then(|collider| world.colliders.get(*collider)) else {
+            continue;
+        };
+        if let Some(capsule) = collider.shape().as_capsule() {
+            let (a, b) = (capsule.segment.a, capsule.segment.b);
+            let end = |x: f32, y: f32| vec2(pos.x + x * rot.cos() - y * rot.sin(), pos.y + x * rot.sin() + y * rot.cos());
+            let (start, finish) = (end(a.x, a.y), end(b.x, b.y));
+            draw_line(start.x, start.y, finish.x, finish.y, capsule.radius * 2.0, LINK_COLOR);
+            draw_circle(start.x, start.y, capsule.radius, LINK_COLOR);
+            draw_circle(finish.x, finish.y, capsule.radius, LINK_COLOR);
+        } else if let Some(ball) = collider.shape().as_ball() {
+            // The anchor the chain hangs from
+            draw_circle(pos.x, pos.y, ball.radius * ANCHOR_SCALE, JOINT_COLOR);
+        }
+    }
+
+    for (_, joint) in world.joints.iter() {
+        let Some(body) = world.bodies.get(joint.body2) else {
+            continue;
+        };
+        if EntityKind::of(body) != Some(EntityKind::Chain) {
+            continue;
+        }
+        let (pos, rot) = world.render_pose(joint.body2, body);
+        let anchor = joint.data.local_anchor2();
+        let (x, y) = (pos.x + anchor.x * rot.cos() - anchor.y * rot.sin(), pos.y + anchor.x * rot.sin() + anchor.y * rot.cos());
+        let radius = body
+            .colliders()
+            .first()
+            .and_then(|collider| world.colliders.get(*collider))
+            .and_then(|collider| collider.shape().as_capsule())
+            .map_or(2.0, |capsule| capsule.radius);
+        draw_circle_lines(x, y, radius * JOINT_SCALE + 1.0, 1.5, JOINT_COLOR);
+    }
+}
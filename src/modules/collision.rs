@@ -26,20 +26,247 @@ let collision = check_collision(&img1, &img2, 1); //Where 1 is the number of pix
     } else {
         println!("No collision.");
     }
+
+To bounce two images apart, check_collision_info also says where they touch:
+use crate::modules::collision::check_collision_info;
+
+if let Some(hit) = check_collision_info(&img1, &img2, 1) {
+    // Move img1 out of img2, then bounce it off along hit.normal
+    img1.set_position(img1.pos() + hit.mtv);
+}
+
+Sprites fast enough to move further than they are wide in one frame can pass
+straight through each other between two checks. Keep where they were last frame
+and use check_swept_collision instead:
+use crate::modules::collision::check_swept_collision;
+
+if let Some(time) = check_swept_collision(&img1, img1_last_pos, &img2, img2_last_pos, 1) {
+    // time is how far through the frame's move they hit (0.0 to 1.0)
+    img1.set_position(img1_last_pos.lerp(img1.pos(), time));
+}
+
+To ask about one point or one line without a second sprite (a click, or a
+line of sight), point_hits and raycast follow the sprite's rotation and mask:
+use crate::modules::collision::{point_hits, raycast};
+
+if is_mouse_button_pressed(MouseButton::Left) && point_hits(&img1, mouse_position().into()) {
+    println!("Clicked on the opaque part of img1");
+}
+if let Some(distance) = raycast(&img1, eye, target - eye) {
+    // Something opaque of img1 is `distance` pixels from eye along the line; it blocks the view if that is
+    // closer than the target
+}
+
+Images that aren't rectangles can collide as a circle or a convex polygon by
+giving their Collidable implementation a hitbox (StillImage collides as its
+rectangle and mask):
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::Circle { radius: 16.0 }
+    }
+
+Pixel-perfect checks go faster when a Collidable also gives a coarse copy of
+its mask (StillImage makes one when it loads): whole blocks of pixels the coarse
+mask shows as transparent are skipped. Make it once with coarse_mask:
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.coarse.clone() // coarse_mask(&mask, width, height), made when the mask was
+    }
+
+Masks are made from the image's alpha channel: by default any pixel that isn't
+fully transparent is solid. For art with soft edges, make the mask again with
+a higher alpha threshold so the faint halo doesn't collide, and grow (dilate)
+then shrink (erode) it to close pinholes in speckled art:
+use crate::modules::collision::MaskOptions;
+
+img1.regenerate_mask(&MaskOptions { alpha_threshold: 128, dilate: 2, erode: 2 });
+    // or when loading: assets.queue_image("glow", "assets/glow.png").with_mask_options(options)
+    // or by hand: mask_from_image(&image, &options), dilate_mask(), erode_mask()
+
+Sprites can be put on a collision layer (see layers.rs) so, say, decorations
+never collide and powerups only collide with the player's pieces:
+    img1.set_layer(Layers::Powerups);
+
+With many sprites, checking every pair gets slow. A CollisionWorld sorts them
+into a quadtree by their bounding boxes so only sprites that are near each other
+get checked. Add with the other use commands:
+
+use crate::modules::collision::CollisionWorld;
+
+Then above the loop section:
+let mut collision_world = CollisionWorld::new(Rect::new(0.0, 0.0, screen_width(), screen_height()));
+
+Then in the loop, once the sprites have moved:
+collision_world.clear();
+for sprite in &sprites {
+    collision_world.insert(sprite);
+}
+for (a, b) in collision_world.collisions(&sprites, 1) {
+    println!("Sprite {} hit sprite {}", a, b);
+}
 */
 
-use macroquad::prelude::Vec2;
+use macroquad::prelude::{Image, Rect, Texture2D, Vec2};
+use crate::modules::layers::Layers;
 
 #[cfg(not(target_arch = "wasm32"))]
 use rayon::prelude::*;
 
 // Define the Collidable trait
+#[allow(unused)]
 pub trait Collidable {
     fn pos(&self) -> Vec2;
     fn size(&self) -> Vec2;
     fn texture_size(&self) -> Vec2;
     fn get_mask(&self) -> Option<Vec<u8>>;
     fn get_angle(&self) -> f32; // New method to get rotation angle
+    // The shape that collides: the image's rectangle unless said otherwise
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::Rectangle
+    }
+    // The mask at 1/COARSE_MASK_SCALE the size (see coarse_mask), for skipping transparent parts quickly. Without
+    // one every pixel is checked
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        None
+    }
+    // The collision layer the object is on (see layers.rs). Without one it collides with everything
+    fn layer(&self) -> Option<Layers> {
+        None
+    }
+}
+
+/// How many texture pixels across (and down) one bit of a coarse mask covers.
+pub const COARSE_MASK_SCALE: usize = 8;
+
+/// A low resolution copy of a `width` × `height` transparency mask: each bit stands for a COARSE_MASK_SCALE ×
+/// COARSE_MASK_SCALE block of pixels and is set if any pixel in the block is opaque. Make it once, when the mask
+/// is made.
+pub fn coarse_mask(mask: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let (coarse_width, coarse_height) = (width.div_ceil(COARSE_MASK_SCALE), height.div_ceil(COARSE_MASK_SCALE));
+    let mut coarse = vec![0; (coarse_width * coarse_height).div_ceil(8)];
+    for y in 0..height {
+        for x in 0..width {
+            if is_mask_bit_set(mask, y * width + x).unwrap_or(false) {
+                let idx = (y / COARSE_MASK_SCALE) * coarse_width + x / COARSE_MASK_SCALE;
+                coarse[idx / 8] |= 1 << (7 - idx % 8);
+            }
+        }
+    }
+    coarse
+}
+
+/// How a transparency mask is made from an image's alpha channel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MaskOptions {
+    /// Pixels at least this opaque are solid. The default, 1, makes every pixel that isn't fully transparent solid;
+    /// raise it so the soft edge of antialiased art doesn't collide.
+    pub alpha_threshold: u8,
+    /// Pixels the solid area is grown by, closing pinholes and thin gaps.
+    pub dilate: usize,
+    /// Pixels the solid area is then shrunk by, removing stray specks (and, after dilate, giving back the outline).
+    pub erode: usize,
+}
+
+impl Default for MaskOptions {
+    fn default() -> Self {
+        Self { alpha_threshold: 1, dilate: 0, erode: 0 }
+    }
+}
+
+/// The transparency mask of an RGBA8 image, or None if every pixel of it is solid.
+pub fn mask_from_image(image: &Image, options: &MaskOptions) -> Option<Vec<u8>> {
+    region_mask(image, 0, 0, image.width(), image.height(), options)
+}
+
+/// The transparency mask of a texture, read back from the GPU (see mask_from_image).
+pub fn mask_from_texture(texture: &Texture2D, options: &MaskOptions) -> Option<Vec<u8>> {
+    mask_from_image(&texture.get_texture_data(), options)
+}
+
+/// The transparency masks of every frame_width × frame_height frame of a sprite sheet, numbered left to right then
+/// top to bottom, each None if every pixel of the frame is solid.
+pub fn frame_masks(image: &Image, frame_width: usize, frame_height: usize, options: &MaskOptions) -> Vec<Option<Vec<u8>>> {
+    let (frame_width, frame_height) = (frame_width.max(1), frame_height.max(1));
+    let columns = (image.width() / frame_width).max(1);
+    let rows = (image.height() / frame_height).max(1);
+    (0..columns * rows)
+        .map(|frame| region_mask(image, (frame % columns) * frame_width, (frame / columns) * frame_height, frame_width, frame_height, options))
+        .collect()
+}
+
+/// A `width` × `height` mask with its solid area grown by `radius` pixels in every direction.
+pub fn dilate_mask(mask: &[u8], width: usize, height: usize, radius: usize) -> Vec<u8> {
+    morph_mask(mask, width, height, radius, true)
+}
+
+/// A `width` × `height` mask with its solid area shrunk by `radius` pixels. The edges of the image don't count as
+/// transparent, so a sprite filling its texture keeps its edges.
+pub fn erode_mask(mask: &[u8], width: usize, height: usize, radius: usize) -> Vec<u8> {
+    morph_mask(mask, width, height, radius, false)
+}
+
+// The mask of the width × height part of `image` at (left, top), or None if all of it is solid. Pixels past the
+// edge of the image are transparent
+fn region_mask(image: &Image, left: usize, top: usize, width: usize, height: usize, options: &MaskOptions) -> Option<Vec<u8>> {
+    let image_width = image.width();
+    let pixels = &image.bytes; // Image pixels in RGBA8 format
+    let mut mask = vec![0; (width * height).div_ceil(8)];
+    for y in 0..height {
+        for x in 0..width {
+            let alpha = if left + x < image_width { pixels.get(((top + y) * image_width + left + x) * 4 + 3).copied().unwrap_or(0) } else { 0 };
+            if alpha >= options.alpha_threshold {
+                set_mask_bit(&mut mask, y * width + x);
+            }
+        }
+    }
+    let mask = erode_mask(&dilate_mask(&mask, width, height, options.dilate), width, height, options.erode);
+
+    // If there's no transparency, there's no mask
+    if (0..width * height).all(|idx| is_mask_bit_set(&mask, idx).unwrap_or(false)) {
+        return None;
+    }
+    Some(mask)
+}
+
+// Dilation (a pixel is solid if any pixel within `radius` is) or erosion (if every one is), done across the rows then
+// down the columns so a square of side 2 × radius + 1 costs two passes of 2 × radius + 1 reads a pixel
+fn morph_mask(mask: &[u8], width: usize, height: usize, radius: usize, grow: bool) -> Vec<u8> {
+    if radius == 0 || width == 0 || height == 0 {
+        return mask.to_vec();
+    }
+    let pass = |source: &[u8], across: bool| {
+        let mut result = vec![0; source.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let (along, length) = if across { (x, width) } else { (y, height) };
+                let mut neighbours = (along.saturating_sub(radius)..=(along + radius).min(length - 1))
+                    .map(|i| if across { y * width + i } else { i * width + x });
+                let solid = if grow {
+                    neighbours.any(|idx| is_mask_bit_set(source, idx).unwrap_or(false))
+                } else {
+                    neighbours.all(|idx| is_mask_bit_set(source, idx).unwrap_or(false))
+                };
+                if solid {
+                    set_mask_bit(&mut result, y * width + x);
+                }
+            }
+        }
+        result
+    };
+    pass(&pass(mask, true), false)
+}
+
+fn set_mask_bit(mask: &mut [u8], idx: usize) {
+    mask[idx / 8] |= 1 << (7 - idx % 8);
+}
+
+/// The shape of an object that collides.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Hitbox {
+    /// The object's rectangle, turned by its angle, with its transparency mask if it has one
+    Rectangle,
+    /// A circle of `radius` around the object's centre
+    Circle { radius: f32 },
+    /// A convex polygon: its corners, in order, from the object's centre before the object is turned by its angle
+    Polygon(Vec<Vec2>),
 }
 use crate::modules::still_image::StillImage;
 // Implement for StillImage
@@ -63,10 +290,17 @@ impl Collidable for StillImage {
     fn get_angle(&self) -> f32 {
         self.get_angle()
     }
+
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.get_coarse_mask()
+    }
+
+    fn layer(&self) -> Option<Layers> {
+        self.get_layer()
+    }
 }
-/* 
 use crate::modules::animated_image::AnimatedImage;
-// Implement for AnimatedImage
+// Implement for AnimatedImage (the mask is the one of the frame shown)
 impl Collidable for AnimatedImage {
     fn pos(&self) -> Vec2 {
         self.pos()
@@ -87,8 +321,15 @@ impl Collidable for AnimatedImage {
     fn get_angle(&self) -> f32 {
         self.get_angle()
     }
+
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.get_coarse_mask()
+    }
+
+    fn layer(&self) -> Option<Layers> {
+        self.get_layer()
+    }
 }
-*/
 
 // Utility function to calculate texture coordinates safely
 #[inline]
@@ -99,8 +340,8 @@ fn calc_tex_coord(point: Vec2, pos: Vec2, size: Vec2, tex_size: Vec2) -> (usize,
     let norm_x = (point.x - pos.x) / safe_size_x;
     let norm_y = (point.y - pos.y) / safe_size_y;
     
-    let clamped_x = norm_x.max(0.0).min(0.999);
-    let clamped_y = norm_y.max(0.0).min(0.999);
+    let clamped_x = norm_x.clamp(0.0, 0.999);
+    let clamped_y = norm_y.clamp(0.0, 0.999);
     
     let tx = (clamped_x * tex_size.x) as usize;
     let ty = (clamped_y * tex_size.y) as usize;
@@ -120,6 +361,15 @@ fn is_mask_bit_set(mask: &[u8], idx: usize) -> Option<bool> {
     }
 }
 
+// Utility function to check if two objects' layers collide (an object without a layer collides with everything)
+#[inline]
+fn layers_interact(layer1: Option<Layers>, layer2: Option<Layers>) -> bool {
+    match (layer1, layer2) {
+        (Some(layer1), Some(layer2)) => layer1.interacts_with(layer2),
+        _ => true,
+    }
+}
+
 // Utility function to check if a point is within rectangle bounds
 #[inline]
 fn is_point_in_bounds(point: Vec2, pos: Vec2, size: Vec2) -> bool {
@@ -128,11 +378,24 @@ fn is_point_in_bounds(point: Vec2, pos: Vec2, size: Vec2) -> bool {
 }
 
 // Generic collision detection function that works with anything implementing Collidable
+#[allow(unused)]
 pub fn check_collision<T, U>(obj1: &T, obj2: &U, skip_pixels: usize) -> bool
 where
     T: Collidable,
     U: Collidable,
 {
+    // Objects on layers that don't collide are never checked
+    if !layers_interact(obj1.layer(), obj2.layer()) {
+        return false;
+    }
+
+    // Circles and polygons have checks of their own
+    let hitbox1 = obj1.hitbox();
+    let hitbox2 = obj2.hitbox();
+    if hitbox1 != Hitbox::Rectangle || hitbox2 != Hitbox::Rectangle {
+        return check_shape_collision(obj1, &hitbox1, obj2, &hitbox2, skip_pixels);
+    }
+
     let pos1 = obj1.pos();
     let size1 = obj1.size();
     let mask1_opt = obj1.get_mask();
@@ -172,6 +435,15 @@ where
         return true; // Bounding boxes overlap
     }
     
+    // With coarse masks whole blocks of the overlap can be ruled out without checking their pixels
+    if obj1.get_coarse_mask().is_some() || obj2.get_coarse_mask().is_some() {
+        return check_coarse_to_fine_collision(
+            &PixelView::of(obj1), &PixelView::of(obj2),
+            overlap_x, overlap_y, overlap_w, overlap_h,
+            skip_pixels
+        );
+    }
+    
     // If at least one object has rotation, we need to use the rotation-aware collision code
     if angle1 != 0.0 || angle2 != 0.0 {
         return check_rotated_pixel_collision(
@@ -182,20 +454,20 @@ where
     }
     
     // Handle case where only one mask is available (mixed case: one has transparency, one doesn't)
-    if mask1_opt.is_some() && mask2_opt.is_none() {
+    if let (Some(mask1), None) = (&mask1_opt, &mask2_opt) {
         // Only obj1 has a mask
         return check_one_masked_collision(
-            &pos1, &size1, &texture1_size, &mask1_opt.unwrap(),
+            &pos1, &size1, &texture1_size, mask1,
             &pos2, &size2,
             &overlap_x, &overlap_y, &overlap_w, &overlap_h,
             skip_pixels
         );
     }
     
-    if mask1_opt.is_none() && mask2_opt.is_some() {
+    if let (None, Some(mask2)) = (&mask1_opt, &mask2_opt) {
         // Only obj2 has a mask
         return check_one_masked_collision(
-            &pos2, &size2, &texture2_size, &mask2_opt.unwrap(),
+            &pos2, &size2, &texture2_size, mask2,
             &pos1, &size1,
             &overlap_x, &overlap_y, &overlap_w, &overlap_h,
             skip_pixels
@@ -209,7 +481,7 @@ where
     #[cfg(not(target_arch = "wasm32"))]
     {
         // Parallel processing (Rayon) on Linux/Windows
-        return (0..overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
+        (0..overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
             (0..overlap_w as usize).into_par_iter().step_by(skip_pixels).any(|x| {
                 let world_point = Vec2::new(overlap_x + x as f32, overlap_y + y as f32);
                 
@@ -228,7 +500,7 @@ where
                 // If either mask check failed or one of the bits is not set, no collision
                 mask1_bit.unwrap_or(false) && mask2_bit.unwrap_or(false)
             })
-        });
+        })
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -260,13 +532,642 @@ where
     }
 }
 
+/// Where and how two objects collide, for bouncing them apart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollisionInfo {
+    /// Roughly where the objects touch
+    pub contact: Vec2,
+    /// Unit vector pointing from the first object towards the second
+    pub normal: Vec2,
+    /// How far the objects overlap along the normal
+    pub depth: f32,
+    /// The shortest move that separates the first object from the second (-normal * depth)
+    pub mtv: Vec2,
+}
+
+// Generic collision detection that also says where the objects touch. Whether they collide is decided exactly
+// like check_collision (masks and hitboxes included); the contact, normal and depth come from the objects' rotated
+// rectangles, whatever their hitboxes
+#[allow(unused)]
+pub fn check_collision_info<T, U>(obj1: &T, obj2: &U, skip_pixels: usize) -> Option<CollisionInfo>
+where
+    T: Collidable,
+    U: Collidable,
+{
+    if !check_collision(obj1, obj2, skip_pixels) {
+        return None;
+    }
+    let (pos1, size1, angle1) = (obj1.pos(), obj1.size(), obj1.get_angle());
+    let (pos2, size2, angle2) = (obj2.pos(), obj2.size(), obj2.get_angle());
+    let center1 = Vec2::new(pos1.x + size1.x / 2.0, pos1.y + size1.y / 2.0);
+    let center2 = Vec2::new(pos2.x + size2.x / 2.0, pos2.y + size2.y / 2.0);
+    let corners1 = rectangle_corners(pos1, size1, angle1);
+    let corners2 = rectangle_corners(pos2, size2, angle2);
+
+    // The rectangles can only miss here when check_collision treated tiny angles as none: they are touching
+    let (normal, depth) = rectangle_penetration(&corners1, &corners2, center2 - center1).unwrap_or_else(|| {
+        let between = center2 - center1;
+        let normal = if between.length() > 0.0001 { between.normalize() } else { Vec2::new(0.0, 1.0) };
+        (normal, 0.0)
+    });
+
+    // The contact is the middle of the corners that are inside the other rectangle. Rectangles crossing like a
+    // plus sign have none, so the middle of the two centres is used instead
+    let mut inside = Vec::with_capacity(8);
+    inside.extend(corners1.iter().copied().filter(|&corner| {
+        is_point_in_bounds(rotate_point(corner, center2, -angle2), pos2, size2)
+    }));
+    inside.extend(corners2.iter().copied().filter(|&corner| {
+        is_point_in_bounds(rotate_point(corner, center1, -angle1), pos1, size1)
+    }));
+    let contact = if inside.is_empty() {
+        (center1 + center2) / 2.0
+    } else {
+        inside.iter().copied().sum::<Vec2>() / inside.len() as f32
+    };
+
+    Some(CollisionInfo { contact, normal, depth, mtv: -normal * depth })
+}
+
+// The four corners of a rectangle rotated by `angle` about its centre
+fn rectangle_corners(pos: Vec2, size: Vec2, angle: f32) -> [Vec2; 4] {
+    let center = Vec2::new(pos.x + size.x / 2.0, pos.y + size.y / 2.0);
+    [
+        rotate_point(pos, center, angle),
+        rotate_point(Vec2::new(pos.x + size.x, pos.y), center, angle),
+        rotate_point(Vec2::new(pos.x + size.x, pos.y + size.y), center, angle),
+        rotate_point(Vec2::new(pos.x, pos.y + size.y), center, angle),
+    ]
+}
+
+// The Separating Axis Theorem again, but keeping the axis the rectangles overlap least along. Returns that axis
+// (turned to point along `towards`, from the first rectangle to the second) and the overlap, or None if there is a gap
+fn rectangle_penetration(corners1: &[Vec2; 4], corners2: &[Vec2; 4], towards: Vec2) -> Option<(Vec2, f32)> {
+    let mut best: Option<(Vec2, f32)> = None;
+    // Two edges of each rectangle are enough, the other two are parallel to them
+    for corners in [corners1, corners2] {
+        for edge in [corners[1] - corners[0], corners[2] - corners[1]] {
+            let length = edge.length();
+            if length <= 0.0001 {
+                continue;
+            }
+            let axis = Vec2::new(-edge.y, edge.x) / length;
+
+            let (min1, max1) = project_corners(corners1, axis);
+            let (min2, max2) = project_corners(corners2, axis);
+            let overlap = max1.min(max2) - min1.max(min2);
+            if overlap < 0.0 {
+                return None; // Gap found, no collision
+            }
+            if best.is_none_or(|(_, depth)| overlap < depth) {
+                best = Some((if axis.dot(towards) < 0.0 { -axis } else { axis }, overlap));
+            }
+        }
+    }
+    best
+}
+
+// The shadow a rectangle's corners cast on `axis`, as its lowest and highest points along it
+fn project_corners(corners: &[Vec2; 4], axis: Vec2) -> (f32, f32) {
+    corners.iter().fold((f32::MAX, f32::MIN), |(min, max), corner| {
+        let projection = corner.dot(axis);
+        (min.min(projection), max.max(projection))
+    })
+}
+
+// A Collidable seen somewhere else, for checking objects part of the way through a move
+struct MovedTo<'a, T: Collidable> {
+    obj: &'a T,
+    pos: Vec2,
+}
+
+impl<T: Collidable> Collidable for MovedTo<'_, T> {
+    fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    fn size(&self) -> Vec2 {
+        self.obj.size()
+    }
+
+    fn texture_size(&self) -> Vec2 {
+        self.obj.texture_size()
+    }
+
+    fn get_mask(&self) -> Option<Vec<u8>> {
+        self.obj.get_mask()
+    }
+
+    fn get_angle(&self) -> f32 {
+        self.obj.get_angle()
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        self.obj.hitbox()
+    }
+
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.obj.get_coarse_mask()
+    }
+
+    fn layer(&self) -> Option<Layers> {
+        self.obj.layer()
+    }
+}
+
+// Swept collision detection for objects too fast for check_collision: an object that moves further in a frame
+// than it is wide can jump right over another one. `prev_pos1` and `prev_pos2` are where the objects were last
+// frame and their pos() is where they are now; both are taken to have moved in a straight line. The move is swept
+// with the objects' rectangles, and then checked with their hitboxes. Returns how far
+// through the move (0.0 at the previous positions, 1.0 at the current ones) they first touch, or None if they
+// never do
+#[allow(unused)]
+pub fn check_swept_collision<T, U>(obj1: &T, prev_pos1: Vec2, obj2: &U, prev_pos2: Vec2, skip_pixels: usize) -> Option<f32>
+where
+    T: Collidable,
+    U: Collidable,
+{
+    if !layers_interact(obj1.layer(), obj2.layer()) {
+        return None;
+    }
+    let start1 = rectangle_corners(prev_pos1, obj1.size(), obj1.get_angle());
+    let start2 = rectangle_corners(prev_pos2, obj2.size(), obj2.get_angle());
+    // Only how the objects move compared to each other matters, so the second is treated as standing still
+    let motion = (obj1.pos() - prev_pos1) - (obj2.pos() - prev_pos2);
+    let (enter, exit) = rectangle_sweep(&start1, &start2, motion)?;
+    let solid = |hitbox: Hitbox, mask: Option<Vec<u8>>| hitbox == Hitbox::Rectangle && mask.is_none();
+    if solid(obj1.hitbox(), obj1.get_mask()) && solid(obj2.hitbox(), obj2.get_mask()) {
+        return Some(enter);
+    }
+
+    // With transparency or another hitbox the rectangles touching isn't enough: step through the time the rectangles overlap,
+    // `skip_pixels` of movement at a time, until the pixels hit
+    let travel = motion.length() * (exit - enter);
+    let steps = (travel / skip_pixels.max(1) as f32).ceil().max(1.0) as usize;
+    (0..=steps).map(|step| enter + (exit - enter) * step as f32 / steps as f32).find(|&t| {
+        let moved1 = MovedTo { obj: obj1, pos: prev_pos1.lerp(obj1.pos(), t) };
+        let moved2 = MovedTo { obj: obj2, pos: prev_pos2.lerp(obj2.pos(), t) };
+        check_collision(&moved1, &moved2, skip_pixels)
+    })
+}
+
+// The Separating Axis Theorem for a rectangle moving by `motion` past one standing still: on every axis the
+// times the shadows overlap are worked out, and the rectangles touch while they overlap on all of them. Returns
+// the first and last time (0.0 to 1.0) they touch, or None if they don't
+fn rectangle_sweep(corners1: &[Vec2; 4], corners2: &[Vec2; 4], motion: Vec2) -> Option<(f32, f32)> {
+    let (mut enter, mut exit) = (0.0f32, 1.0f32);
+    for corners in [corners1, corners2] {
+        for edge in [corners[1] - corners[0], corners[2] - corners[1]] {
+            let length = edge.length();
+            if length <= 0.0001 {
+                continue;
+            }
+            let axis = Vec2::new(-edge.y, edge.x) / length;
+            let (min1, max1) = project_corners(corners1, axis);
+            let (min2, max2) = project_corners(corners2, axis);
+            let speed = motion.dot(axis);
+
+            if speed.abs() < 0.000001 {
+                // Not moving along this axis: the shadows overlap the whole time or never
+                if min1 > max2 || min2 > max1 {
+                    return None;
+                }
+                continue;
+            }
+            let (first, second) = ((min2 - max1) / speed, (max2 - min1) / speed);
+            enter = enter.max(first.min(second));
+            exit = exit.min(first.max(second));
+            if enter > exit {
+                return None; // Gap found the whole time, no collision
+            }
+        }
+    }
+    Some((enter, exit))
+}
+
+/// True if `point` is on `obj`: inside its hitbox and, for a rectangle with a transparency mask, on an opaque pixel.
+pub fn point_hits<T: Collidable>(obj: &T, point: Vec2) -> bool {
+    match obj.hitbox() {
+        Hitbox::Rectangle => PixelView::of(obj).is_opaque(point),
+        hitbox => is_point_in_shape(point, &shape_of(obj, &hitbox)),
+    }
+}
+
+/// How far along the ray from `origin` in direction `dir` (of any length) it first meets `obj`, in pixels: where it
+/// enters the hitbox or, for a rectangle with a transparency mask, the first opaque pixel it crosses. 0.0 if it
+/// starts on `obj`, None if it never meets it.
+pub fn raycast<T: Collidable>(obj: &T, origin: Vec2, dir: Vec2) -> Option<f32> {
+    let dir = dir.try_normalize()?;
+    let hitbox = obj.hitbox();
+    let (enter, exit) = ray_interval(origin, dir, &shape_of(obj, &hitbox))?;
+    let tex_size = obj.texture_size();
+    match obj.get_mask() {
+        Some(mask) if hitbox == Hitbox::Rectangle && tex_size.x >= 1.0 && tex_size.y >= 1.0 => {
+            raycast_mask(obj, &mask, origin, dir, enter, exit)
+        }
+        _ => Some(enter),
+    }
+}
+
+// Where the ray from `origin` along the unit vector `dir` is inside a placed hitbox, as the distances it enters and
+// leaves it (entering at 0.0 if it starts inside), or None if it misses
+fn ray_interval(origin: Vec2, dir: Vec2, shape: &Shape) -> Option<(f32, f32)> {
+    match shape {
+        Shape::Circle { center, radius } => {
+            // Solving |origin + t dir - center| = radius for t
+            let to_origin = origin - *center;
+            let half_b = to_origin.dot(dir);
+            let discriminant = half_b * half_b - (to_origin.length_squared() - radius * radius);
+            if discriminant < 0.0 {
+                return None;
+            }
+            let (enter, exit) = (-half_b - discriminant.sqrt(), -half_b + discriminant.sqrt());
+            (exit >= 0.0).then_some((enter.max(0.0), exit))
+        }
+        Shape::Polygon(corners) => {
+            // Clipping the ray by every edge in turn, each one keeping the part on the inside of it
+            let inside = corners.iter().copied().sum::<Vec2>() / corners.len() as f32;
+            let (mut enter, mut exit) = (0.0f32, f32::MAX);
+            for (index, &corner) in corners.iter().enumerate() {
+                let mut normal = (corners[(index + 1) % corners.len()] - corner).perp();
+                if normal.dot(inside - corner) > 0.0 {
+                    normal = -normal; // Pointing out of the polygon
+                }
+                let (distance, speed) = (normal.dot(origin - corner), normal.dot(dir));
+                if speed.abs() < 0.000001 {
+                    // Running along the edge: inside it the whole way or never
+                    if distance > 0.0 {
+                        return None;
+                    }
+                    continue;
+                }
+                let t = -distance / speed;
+                if speed < 0.0 {
+                    enter = enter.max(t);
+                } else {
+                    exit = exit.min(t);
+                }
+                if enter > exit {
+                    return None;
+                }
+            }
+            Some((enter, exit))
+        }
+    }
+}
+
+// Walks the ray through the texture pixels of a masked rectangle from `enter` to `exit` (distances along the ray),
+// one pixel boundary at a time, and returns the distance at which it first reaches an opaque pixel
+fn raycast_mask<T: Collidable>(obj: &T, mask: &[u8], origin: Vec2, dir: Vec2, enter: f32, exit: f32) -> Option<f32> {
+    let (pos, size, tex_size, angle) = (obj.pos(), obj.size(), obj.texture_size(), obj.get_angle());
+    let center = Vec2::new(pos.x + size.x / 2.0, pos.y + size.y / 2.0);
+    // In the rectangle's own (unturned) space the pixels are a plain grid
+    let origin = rotate_point(origin, center, -angle);
+    let dir = rotate_point(dir, Vec2::ZERO, -angle);
+    let pixel = size / tex_size;
+    let (columns, rows) = (tex_size.x as usize, tex_size.y as usize);
+
+    let (mut tx, mut ty) = calc_tex_coord(origin + dir * enter, pos, size, tex_size);
+    // For each axis: which way the ray steps through the pixels, the distance to the next pixel boundary it crosses
+    // and the distance between two boundaries
+    let axis = |along: usize, origin: f32, dir: f32, start: f32, pixel: f32| -> (isize, f32, f32) {
+        if dir.abs() < 0.000001 {
+            return (0, f32::MAX, f32::MAX);
+        }
+        let boundary = start + (along + usize::from(dir > 0.0)) as f32 * pixel;
+        (if dir > 0.0 { 1 } else { -1 }, (boundary - origin) / dir, pixel / dir.abs())
+    };
+    let (step_x, mut next_x, delta_x) = axis(tx, origin.x, dir.x, pos.x, pixel.x);
+    let (step_y, mut next_y, delta_y) = axis(ty, origin.y, dir.y, pos.y, pixel.y);
+
+    let mut t = enter;
+    while t <= exit {
+        if is_mask_bit_set(mask, ty * columns + tx).unwrap_or(false) {
+            return Some(t);
+        }
+        if next_x < next_y {
+            t = next_x;
+            next_x += delta_x;
+            tx = tx.checked_add_signed(step_x).filter(|&tx| tx < columns)?;
+        } else {
+            t = next_y;
+            next_y += delta_y;
+            ty = ty.checked_add_signed(step_y).filter(|&ty| ty < rows)?;
+        }
+    }
+    None
+}
+
+// A hitbox placed in the world: circles by their centre, everything else as the corners of a convex polygon
+enum Shape {
+    Circle { center: Vec2, radius: f32 },
+    Polygon(Vec<Vec2>),
+}
+
+// Where `obj`'s hitbox is in the world
+fn shape_of<T: Collidable>(obj: &T, hitbox: &Hitbox) -> Shape {
+    let (pos, size, angle) = (obj.pos(), obj.size(), obj.get_angle());
+    let center = Vec2::new(pos.x + size.x / 2.0, pos.y + size.y / 2.0);
+    match hitbox {
+        Hitbox::Rectangle => Shape::Polygon(rectangle_corners(pos, size, angle).to_vec()),
+        Hitbox::Circle { radius } => Shape::Circle { center, radius: *radius },
+        Hitbox::Polygon(corners) => {
+            Shape::Polygon(corners.iter().map(|&corner| rotate_point(center + corner, center, angle)).collect())
+        }
+    }
+}
+
+// The box around a placed hitbox
+fn shape_bounds(shape: &Shape) -> Rect {
+    match shape {
+        Shape::Circle { center, radius } => Rect::new(center.x - radius, center.y - radius, radius * 2.0, radius * 2.0),
+        Shape::Polygon(corners) => {
+            let min = corners.iter().fold(Vec2::splat(f32::MAX), |min, &corner| min.min(corner));
+            let max = corners.iter().fold(Vec2::splat(f32::MIN), |max, &corner| max.max(corner));
+            Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+        }
+    }
+}
+
+// The box around `obj`'s hitbox where it is now
+fn hitbox_bounds<T: Collidable>(obj: &T) -> Rect {
+    match obj.hitbox() {
+        Hitbox::Rectangle => {
+            let (pos, size) = calculate_rotated_bounding_box(obj.pos(), obj.size(), obj.get_angle());
+            Rect::new(pos.x, pos.y, size.x, size.y)
+        }
+        hitbox => shape_bounds(&shape_of(obj, &hitbox)),
+    }
+}
+
+// True if `point` is inside (or on the edge of) a placed hitbox
+fn is_point_in_shape(point: Vec2, shape: &Shape) -> bool {
+    match shape {
+        Shape::Circle { center, radius } => point.distance_squared(*center) <= radius * radius,
+        Shape::Polygon(corners) => {
+            // Inside a convex polygon the point is on the same side of every edge, whichever way round it goes
+            let (mut left, mut right) = (false, false);
+            for (index, &corner) in corners.iter().enumerate() {
+                let next = corners[(index + 1) % corners.len()];
+                let cross = (next - corner).perp_dot(point - corner);
+                left |= cross > 0.0;
+                right |= cross < 0.0;
+            }
+            !(left && right)
+        }
+    }
+}
+
+// The shadow a placed hitbox casts on `axis`, as its lowest and highest points along it
+fn project_shape(shape: &Shape, axis: Vec2) -> (f32, f32) {
+    match shape {
+        Shape::Circle { center, radius } => (center.dot(axis) - radius, center.dot(axis) + radius),
+        Shape::Polygon(corners) => corners.iter().fold((f32::MAX, f32::MIN), |(min, max), corner| {
+            let projection = corner.dot(axis);
+            (min.min(projection), max.max(projection))
+        }),
+    }
+}
+
+// The Separating Axis Theorem for circles and convex polygons. The axes to try are the normals of every polygon
+// edge, plus, for a circle, the line from its centre to the nearest corner of the polygon (or to the other
+// circle's centre)
+fn shapes_overlap(shape1: &Shape, shape2: &Shape) -> bool {
+    if let (Shape::Circle { center: center1, radius: radius1 }, Shape::Circle { center: center2, radius: radius2 }) = (shape1, shape2) {
+        return center1.distance_squared(*center2) <= (radius1 + radius2) * (radius1 + radius2);
+    }
+
+    let mut axes = Vec::new();
+    for (shape, other) in [(shape1, shape2), (shape2, shape1)] {
+        match (shape, other) {
+            (Shape::Polygon(corners), _) => {
+                for (index, &corner) in corners.iter().enumerate() {
+                    let edge = corners[(index + 1) % corners.len()] - corner;
+                    if edge.length() > 0.0001 {
+                        axes.push(edge.perp().normalize());
+                    }
+                }
+            }
+            (Shape::Circle { center, .. }, Shape::Polygon(corners)) => {
+                let nearest = corners
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| a.distance_squared(*center).total_cmp(&b.distance_squared(*center)));
+                if let Some(nearest) = nearest.filter(|nearest| nearest.distance(*center) > 0.0001) {
+                    axes.push((nearest - *center).normalize());
+                }
+            }
+            (Shape::Circle { .. }, Shape::Circle { .. }) => {}
+        }
+    }
+
+    axes.iter().all(|&axis| {
+        let (min1, max1) = project_shape(shape1, axis);
+        let (min2, max2) = project_shape(shape2, axis);
+        min1 <= max2 && min2 <= max1
+    })
+}
+
+// Helper function for collision detection when at least one object is a circle or a polygon. A rectangle with a
+// transparency mask only collides where it is opaque
+fn check_shape_collision<T, U>(obj1: &T, hitbox1: &Hitbox, obj2: &U, hitbox2: &Hitbox, skip_pixels: usize) -> bool
+where
+    T: Collidable,
+    U: Collidable,
+{
+    let shape1 = shape_of(obj1, hitbox1);
+    let shape2 = shape_of(obj2, hitbox2);
+    if !shapes_overlap(&shape1, &shape2) {
+        return false;
+    }
+    match (hitbox1, obj1.get_mask(), hitbox2, obj2.get_mask()) {
+        (Hitbox::Rectangle, Some(mask), _, _) => check_masked_shape_collision(obj1, &mask, &shape2, skip_pixels),
+        (_, _, Hitbox::Rectangle, Some(mask)) => check_masked_shape_collision(obj2, &mask, &shape1, skip_pixels),
+        _ => true,
+    }
+}
+
+// Helper function for collision detection between a (possibly rotated) masked rectangle and a circle or polygon
+fn check_masked_shape_collision<T: Collidable>(masked: &T, mask: &[u8], other: &Shape, skip_pixels: usize) -> bool {
+    let masked_pos = masked.pos();
+    let masked_size = masked.size();
+    let masked_tex_size = masked.texture_size();
+    let masked_angle = masked.get_angle();
+    let masked_center = Vec2::new(masked_pos.x + masked_size.x / 2.0, masked_pos.y + masked_size.y / 2.0);
+
+    // Only the pixels in both boxes need checking
+    let (masked_box, other_box) = (hitbox_bounds(masked), shape_bounds(other));
+    let Some(overlap) = masked_box.intersect(other_box) else {
+        return false;
+    };
+
+    // True if the pixel at `world_point` is opaque and inside the other shape
+    let hits = |world_point: Vec2| {
+        let local_point = rotate_point(world_point, masked_center, -masked_angle);
+        if !is_point_in_bounds(local_point, masked_pos, masked_size) {
+            return false;
+        }
+        let (tx, ty) = calc_tex_coord(local_point, masked_pos, masked_size, masked_tex_size);
+        let idx = ty * masked_tex_size.x as usize + tx;
+        is_mask_bit_set(mask, idx).unwrap_or(false) && is_point_in_shape(world_point, other)
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // Parallel processing for Linux/Windows
+        (0..overlap.h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
+            (0..overlap.w as usize).into_par_iter().step_by(skip_pixels).any(|x| {
+                hits(Vec2::new(overlap.x + x as f32, overlap.y + y as f32))
+            })
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        // Sequential for Web (WASM)
+        for y in (0..overlap.h as usize).step_by(skip_pixels) {
+            for x in (0..overlap.w as usize).step_by(skip_pixels) {
+                if hits(Vec2::new(overlap.x + x as f32, overlap.y + y as f32)) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+// One object as the pixel checks see it
+struct PixelView {
+    pos: Vec2,
+    size: Vec2,
+    tex_size: Vec2,
+    angle: f32,
+    center: Vec2,
+    mask: Option<Vec<u8>>,
+    coarse: Option<Vec<u8>>,
+}
+
+impl PixelView {
+    fn of<T: Collidable>(obj: &T) -> Self {
+        let (pos, size) = (obj.pos(), obj.size());
+        Self {
+            pos,
+            size,
+            tex_size: obj.texture_size(),
+            angle: obj.get_angle(),
+            center: Vec2::new(pos.x + size.x / 2.0, pos.y + size.y / 2.0),
+            mask: obj.get_mask(),
+            coarse: obj.get_coarse_mask(),
+        }
+    }
+
+    // True if the object is opaque at `point` (anywhere inside it, without a mask)
+    fn is_opaque(&self, point: Vec2) -> bool {
+        let local_point = rotate_point(point, self.center, -self.angle);
+        if !is_point_in_bounds(local_point, self.pos, self.size) {
+            return false;
+        }
+        match &self.mask {
+            Some(mask) => {
+                let (tx, ty) = calc_tex_coord(local_point, self.pos, self.size, self.tex_size);
+                is_mask_bit_set(mask, ty * self.tex_size.x as usize + tx).unwrap_or(false)
+            }
+            None => true,
+        }
+    }
+
+    // False only if the coarse mask shows the object is transparent everywhere in `area`
+    fn may_be_opaque(&self, area: Rect) -> bool {
+        let (Some(_), Some(coarse)) = (&self.mask, &self.coarse) else {
+            return true;
+        };
+        // The texture pixels under the area's corners (turned into the object's space) bound every pixel under it
+        let corners = [
+            Vec2::new(area.x, area.y),
+            Vec2::new(area.x + area.w, area.y),
+            Vec2::new(area.x, area.y + area.h),
+            Vec2::new(area.x + area.w, area.y + area.h),
+        ];
+        let (mut min, mut max) = ((usize::MAX, usize::MAX), (0, 0));
+        for corner in corners {
+            let local_corner = rotate_point(corner, self.center, -self.angle);
+            let (tx, ty) = calc_tex_coord(local_corner, self.pos, self.size, self.tex_size);
+            min = (min.0.min(tx), min.1.min(ty));
+            max = (max.0.max(tx), max.1.max(ty));
+        }
+        let coarse_width = (self.tex_size.x as usize).div_ceil(COARSE_MASK_SCALE);
+        (min.1 / COARSE_MASK_SCALE..=max.1 / COARSE_MASK_SCALE).any(|cy| {
+            (min.0 / COARSE_MASK_SCALE..=max.0 / COARSE_MASK_SCALE)
+                .any(|cx| is_mask_bit_set(coarse, cy * coarse_width + cx).unwrap_or(false))
+        })
+    }
+
+    // Screen pixels across one coarse mask bit (as good as endless without a coarse mask)
+    fn coarse_cell(&self) -> f32 {
+        match &self.coarse {
+            Some(_) => (self.size / self.tex_size * COARSE_MASK_SCALE as f32).min_element(),
+            None => f32::MAX,
+        }
+    }
+}
+
+// Helper function for pixel-perfect collision with coarse masks. The overlap is split into blocks about the size
+// of one coarse mask bit; blocks either object's coarse mask shows as transparent are skipped, and the rest are
+// checked at the same pixels the full check would look at
+#[allow(clippy::too_many_arguments)]
+fn check_coarse_to_fine_collision(
+    view1: &PixelView,
+    view2: &PixelView,
+    overlap_x: f32,
+    overlap_y: f32,
+    overlap_w: f32,
+    overlap_h: f32,
+    skip_pixels: usize
+) -> bool {
+    let (width, height) = (overlap_w as usize, overlap_h as usize);
+    let block = view1.coarse_cell().min(view2.coarse_cell()).floor().clamp(1.0, width.max(height).max(1) as f32) as usize;
+    let (columns, rows) = (width.div_ceil(block), height.div_ceil(block));
+
+    // True if any pixel the full check would look at in block (bx, by) is opaque in both objects
+    let block_hits = |bx: usize, by: usize| {
+        let area = Rect::new(
+            overlap_x + (bx * block) as f32, overlap_y + (by * block) as f32,
+            block as f32, block as f32
+        );
+        if !view1.may_be_opaque(area) || !view2.may_be_opaque(area) {
+            return false;
+        }
+        // The first pixels in the block on the full check's every-skip_pixels grid
+        let (first_x, first_y) = ((bx * block).div_ceil(skip_pixels) * skip_pixels, (by * block).div_ceil(skip_pixels) * skip_pixels);
+        (first_y..((by + 1) * block).min(height)).step_by(skip_pixels).any(|y| {
+            (first_x..((bx + 1) * block).min(width)).step_by(skip_pixels).any(|x| {
+                let world_point = Vec2::new(overlap_x + x as f32, overlap_y + y as f32);
+                view1.is_opaque(world_point) && view2.is_opaque(world_point)
+            })
+        })
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // Parallel processing (Rayon) on Linux/Windows
+        (0..rows).into_par_iter().any(|by| (0..columns).into_par_iter().any(|bx| block_hits(bx, by)))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        // Sequential for Web (WASM)
+        (0..rows).any(|by| (0..columns).any(|bx| block_hits(bx, by)))
+    }
+}
+
 // Helper function for collision detection when only one object has a mask
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn check_one_masked_collision(
     masked_pos: &Vec2,
     masked_size: &Vec2,
     masked_tex_size: &Vec2,
-    mask: &Vec<u8>,
+    mask: &[u8],
     other_pos: &Vec2,
     other_size: &Vec2,
     overlap_x: &f32,
@@ -278,7 +1179,7 @@ fn check_one_masked_collision(
     #[cfg(not(target_arch = "wasm32"))]
     {
         // Parallel processing for Linux/Windows
-        return (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
+        (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
             (0..*overlap_w as usize).into_par_iter().step_by(skip_pixels).any(|x| {
                 let world_point = Vec2::new(*overlap_x + x as f32, *overlap_y + y as f32);
                 
@@ -297,7 +1198,7 @@ fn check_one_masked_collision(
                 // Collision occurs if point is opaque in the masked object and also within the other object
                 is_opaque && in_other_bounds
             })
-        });
+        })
     }
     
     #[cfg(target_arch = "wasm32")]
@@ -330,6 +1231,7 @@ fn check_one_masked_collision(
 
 // Helper function for collision detection when only one rotated object has a transparency mask
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn check_one_rotated_masked_collision(
     masked_pos: Vec2,
     masked_size: Vec2,
@@ -350,7 +1252,7 @@ fn check_one_rotated_masked_collision(
     #[cfg(not(target_arch = "wasm32"))]
     {
         // Parallel processing for Linux/Windows
-        return (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
+        (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
             (0..*overlap_w as usize).into_par_iter().step_by(skip_pixels).any(|x| {
                 // For each pixel in the overlap region
                 let world_point = Vec2::new(*overlap_x + x as f32, *overlap_y + y as f32);
@@ -380,7 +1282,7 @@ fn check_one_rotated_masked_collision(
                 
                 is_opaque && in_other_bounds
             })
-        });
+        })
     }
     
     #[cfg(target_arch = "wasm32")]
@@ -452,20 +1354,20 @@ where
     let center2 = Vec2::new(pos2.x + size2.x / 2.0, pos2.y + size2.y / 2.0);
     
     // Mixed case: Only one image has transparency
-    if mask1_opt.is_some() && mask2_opt.is_none() {
+    if let (Some(mask1), None) = (&mask1_opt, &mask2_opt) {
         // Object 1 has transparency, object 2 doesn't
         return check_one_rotated_masked_collision(
-            pos1, size1, texture1_size, mask1_opt.unwrap(), angle1, center1,
+            pos1, size1, texture1_size, mask1.clone(), angle1, center1,
             pos2, size2, angle2, center2,
             overlap_x, overlap_y, overlap_w, overlap_h,
             skip_pixels
         );
     }
     
-    if mask1_opt.is_none() && mask2_opt.is_some() {
+    if let (None, Some(mask2)) = (&mask1_opt, &mask2_opt) {
         // Object 2 has transparency, object 1 doesn't
         return check_one_rotated_masked_collision(
-            pos2, size2, texture2_size, mask2_opt.unwrap(), angle2, center2,
+            pos2, size2, texture2_size, mask2.clone(), angle2, center2,
             pos1, size1, angle1, center1,
             overlap_x, overlap_y, overlap_w, overlap_h,
             skip_pixels
@@ -487,7 +1389,7 @@ where
     #[cfg(not(target_arch = "wasm32"))]
     {
         // Parallel processing (Rayon) for Linux/Windows
-        return (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
+        (0..*overlap_h as usize).into_par_iter().step_by(skip_pixels).any(|y| {
             (0..*overlap_w as usize).into_par_iter().step_by(skip_pixels).any(|x| {
                 // For each pixel in the overlap region
                 let world_point = Vec2::new(*overlap_x + x as f32, *overlap_y + y as f32);
@@ -519,7 +1421,7 @@ where
                 // If both bits are set, we have a collision at this pixel
                 mask1_bit && mask2_bit
             })
-        });
+        })
     }
     
     #[cfg(target_arch = "wasm32")]
@@ -744,3 +1646,144 @@ fn calculate_rotated_bounding_box(pos: Vec2, size: Vec2, angle: f32) -> (Vec2, V
         Vec2::new(max_x - min_x + 2.0 * margin_x, max_y - min_y + 2.0 * margin_y)
     )
 }
+
+
+// Most objects a quadtree node holds before it splits into four, and how many times the tree can split
+const QUADTREE_NODE_ITEMS: usize = 8;
+const QUADTREE_MAX_DEPTH: usize = 8;
+
+// True if `inner` lies completely inside `outer`
+#[inline]
+fn encloses(outer: Rect, inner: Rect) -> bool {
+    inner.x >= outer.x && inner.y >= outer.y &&
+    inner.x + inner.w <= outer.x + outer.w &&
+    inner.y + inner.h <= outer.y + outer.h
+}
+
+// One square of the quadtree: the objects that fit in it but in none of its quarters, and the quarters once it
+// has split
+#[derive(Clone, Debug)]
+struct QuadNode {
+    bounds: Rect,
+    depth: usize,
+    items: Vec<usize>,
+    children: Option<Box<[QuadNode; 4]>>,
+}
+
+impl QuadNode {
+    fn new(bounds: Rect, depth: usize) -> Self {
+        Self { bounds, depth, items: Vec::new(), children: None }
+    }
+
+    // Adds object `index`, whose bounding box is boxes[index], to the deepest node it fits in
+    fn insert(&mut self, index: usize, boxes: &[Rect]) {
+        if let Some(children) = self.children.as_mut() {
+            if let Some(child) = children.iter_mut().find(|child| encloses(child.bounds, boxes[index])) {
+                child.insert(index, boxes);
+                return;
+            }
+        }
+        self.items.push(index);
+        if self.children.is_none() && self.items.len() > QUADTREE_NODE_ITEMS && self.depth < QUADTREE_MAX_DEPTH {
+            self.split(boxes);
+        }
+    }
+
+    // Splits the node into quarters and moves every object that fits in one down into it
+    fn split(&mut self, boxes: &[Rect]) {
+        let (half_w, half_h) = (self.bounds.w / 2.0, self.bounds.h / 2.0);
+        let (x, y, depth) = (self.bounds.x, self.bounds.y, self.depth + 1);
+        self.children = Some(Box::new([
+            QuadNode::new(Rect::new(x, y, half_w, half_h), depth),
+            QuadNode::new(Rect::new(x + half_w, y, half_w, half_h), depth),
+            QuadNode::new(Rect::new(x, y + half_h, half_w, half_h), depth),
+            QuadNode::new(Rect::new(x + half_w, y + half_h, half_w, half_h), depth),
+        ]));
+        for index in std::mem::take(&mut self.items) {
+            self.insert(index, boxes);
+        }
+    }
+
+    // Adds every object in the node or below it whose bounding box overlaps `area` to `found`
+    fn query(&self, area: Rect, boxes: &[Rect], found: &mut Vec<usize>) {
+        found.extend(self.items.iter().copied().filter(|&index| boxes[index].overlaps(&area)));
+        if let Some(children) = self.children.as_ref() {
+            for child in children.iter().filter(|child| child.bounds.overlaps(&area)) {
+                child.query(area, boxes, found);
+            }
+        }
+    }
+}
+
+/// A quadtree of Collidable objects' bounding boxes, so a scene with many sprites can find which of them
+/// collide without checking every pair. Objects are numbered in the order they are inserted; anything outside
+/// the world's area still works, it is just checked against more objects.
+#[derive(Clone, Debug)]
+pub struct CollisionWorld {
+    root: QuadNode,
+    // Every object's bounding box (rotation included), by number
+    boxes: Vec<Rect>,
+}
+
+impl CollisionWorld {
+    /// An empty world covering `area` (usually the screen).
+    pub fn new(area: Rect) -> Self {
+        Self { root: QuadNode::new(area, 0), boxes: Vec::new() }
+    }
+
+    /// Removes every object, ready for this frame's positions to be inserted.
+    pub fn clear(&mut self) {
+        self.root = QuadNode::new(self.root.bounds, 0);
+        self.boxes.clear();
+    }
+
+    /// Adds `obj` where it is now and returns its number.
+    pub fn insert<T: Collidable>(&mut self, obj: &T) -> usize {
+        let index = self.boxes.len();
+        self.boxes.push(hitbox_bounds(obj));
+        self.root.insert(index, &self.boxes);
+        index
+    }
+
+    /// How many objects have been inserted.
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// True if nothing has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// The numbers of the objects whose bounding boxes overlap `area`, smallest first.
+    pub fn query(&self, area: Rect) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.root.query(area, &self.boxes, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    /// Every pair of objects (smaller number first, in order) whose bounding boxes overlap. These might collide;
+    /// check them with check_collision, or use collisions.
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        let mut found = Vec::new();
+        for (index, bounds) in self.boxes.iter().enumerate() {
+            found.clear();
+            self.root.query(*bounds, &self.boxes, &mut found);
+            pairs.extend(found.iter().filter(|&&other| other > index).map(|&other| (index, other)));
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Every pair of objects that collide, checked with check_collision. `objects` has to be the objects that
+    /// were inserted, in the same order.
+    pub fn collisions<T: Collidable>(&self, objects: &[T], skip_pixels: usize) -> Vec<(usize, usize)> {
+        debug_assert_eq!(objects.len(), self.boxes.len(), "CollisionWorld::collisions needs the inserted objects");
+        self.candidate_pairs()
+            .into_iter()
+            .filter(|&(a, b)| check_collision(&objects[a], &objects[b], skip_pixels))
+            .collect()
+    }
+}
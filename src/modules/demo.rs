@@ -0,0 +1,156 @@
+/*
+Program Details: Demo recordings of real play for the attract mode
+
+While the game is played, a DemoRecorder writes down every piece dropped: the
+game time it was dropped at, its shape and ball variant, and where and how fast
+it started. Together with the board it was dropped on and the physics settings
+of the time, that is enough for the attract mode (see attract_scene.rs) to drop
+the same pieces on a fresh copy of the board and let the physics play the
+session out again.
+
+A recording covers at most DEMO_SECONDS of game time. When that is up, or the
+board changes under it, the recording is finished and handed over through the
+shared demo (if it has at least MIN_DROPS drops, so a stretch of idling isn't
+shown off), and the next one starts. Until anything has been recorded the
+attract mode plays Demo::scripted, a short session written into the game.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod demo;
+
+Then with the other use commands add:
+use crate::modules::demo::{DemoRecorder, SharedDemo};
+
+Then above the loop section to use you would go:
+    let demo = SharedDemo::default();
+    let mut recorder = DemoRecorder::new(demo.clone());
+    recorder.start(&board, &settings);
+
+When a piece is dropped:
+    recorder.record_drop(&world, handle);
+
+Then in the loop you would use:
+    let steps = world.advance(get_frame_time());
+    recorder.update(steps as f32 * world.integration_params.dt);
+*/
+use rapier2d::prelude::RigidBodyHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::modules::aim::DROP_Y;
+use crate::modules::board::BoardConfig;
+use crate::modules::physics::{BallVariant, PhysicsWorld, ShapeKind};
+use crate::modules::settings::Settings;
+
+/// Longest stretch of play (game seconds) one recording covers.
+pub const DEMO_SECONDS: f32 = 30.0;
+// Fewest drops a recording needs to be worth showing
+const MIN_DROPS: usize = 5;
+// The session written into the game: when each piece is dropped (game seconds in), its shape and the x
+// it is dropped at (the columns the Random button drops in)
+const SCRIPTED_DROPS: [(f32, ShapeKind, f32); 12] = [
+    (0.5, ShapeKind::Ball, 400.0),
+    (1.5, ShapeKind::Ball, 300.0),
+    (2.5, ShapeKind::Square, 501.0),
+    (4.0, ShapeKind::Ball, 201.0),
+    (5.0, ShapeKind::Triangle, 590.0),
+    (6.5, ShapeKind::Ball, 690.0),
+    (8.0, ShapeKind::Ball, 400.0),
+    (8.4, ShapeKind::Ball, 400.0),
+    (10.0, ShapeKind::Square, 300.0),
+    (12.0, ShapeKind::Triangle, 201.0),
+    (13.5, ShapeKind::Ball, 501.0),
+    (15.0, ShapeKind::Ball, 590.0),
+];
+
+/// One piece dropped during a recording.
+#[derive(Clone, Copy, Debug)]
+pub struct DemoDrop {
+    /// Game seconds into the recording it was dropped at
+    pub time: f32,
+    pub kind: ShapeKind,
+    /// Ball variant the world was dropping at the time (only matters for balls)
+    pub variant: BallVariant,
+    /// Where it appeared and how fast it was already moving (multiball drops start with a push)
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+}
+
+/// A recorded session: the board, the physics settings and every drop.
+#[derive(Clone, Debug)]
+pub struct Demo {
+    pub board: BoardConfig,
+    pub settings: Settings,
+    pub drops: Vec<DemoDrop>,
+    /// Game seconds recorded
+    pub length: f32,
+}
+
+impl Demo {
+    /// The session written into the game, played on `board` with the default settings.
+    pub fn scripted(board: BoardConfig) -> Self {
+        let drops: Vec<DemoDrop> = SCRIPTED_DROPS
+            .iter()
+            .map(|&(time, kind, x)| DemoDrop { time, kind, variant: BallVariant::Normal, x, y: DROP_Y, vx: 0.0, vy: 0.0 })
+            .collect();
+        let length = drops.last().map_or(0.0, |drop| drop.time);
+        Self { board, settings: Settings::default(), drops, length }
+    }
+}
+
+/// The latest finished recording, handed from the Plinko scene to the attract mode.
+pub type SharedDemo = Rc<RefCell<Option<Demo>>>;
+
+pub struct DemoRecorder {
+    recording: Option<Demo>,
+    shared: SharedDemo,
+}
+
+impl DemoRecorder {
+    pub fn new(shared: SharedDemo) -> Self {
+        Self { recording: None, shared }
+    }
+
+    /// Finishes the recording so far and starts a new one on `board` (as it is built in the world) with
+    /// `settings`.
+    pub fn start(&mut self, board: &BoardConfig, settings: &Settings) {
+        self.finish();
+        self.recording = Some(Demo { board: board.clone(), settings: settings.clone(), drops: Vec::new(), length: 0.0 });
+    }
+
+    /// Writes down the piece `handle` just spawned in `world`: its shape, variant, position and velocity.
+    pub fn record_drop(&mut self, world: &PhysicsWorld, handle: RigidBodyHandle) {
+        let (Some(recording), Some(body)) = (self.recording.as_mut(), world.bodies.get(handle)) else {
+            return;
+        };
+        let Some(kind) = PhysicsWorld::shape_of(body) else {
+            return;
+        };
+        let (pos, vel) = (body.translation(), body.linvel());
+        recording.drops.push(DemoDrop { time: recording.length, kind, variant: world.ball_variant, x: pos.x, y: pos.y, vx: vel.x, vy: vel.y });
+    }
+
+    /// Moves the recording on by `seconds` of game time, starting the next one on the same board once it
+    /// has covered DEMO_SECONDS.
+    pub fn update(&mut self, seconds: f32) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        recording.length += seconds;
+        if recording.length >= DEMO_SECONDS {
+            let (board, settings) = (recording.board.clone(), recording.settings.clone());
+            self.start(&board, &settings);
+        }
+    }
+
+    // Hands the recording over to the attract mode, if there's enough in it to show
+    fn finish(&mut self) {
+        if let Some(recording) = self.recording.take()
+            && recording.drops.len() >= MIN_DROPS
+        {
+            *self.shared.borrow_mut() = Some(recording);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+/*
+Program Details: Modal confirmation dialogs
+
+A Dialog asks a yes/no question ("Clear all shapes?", "Overwrite saved board?")
+in a box over a dimmed screen. While it is open it captures the input: buttons,
+sliders, dropdowns, text boxes and the drop aimer everywhere else ignore the
+mouse (see input_blocked), and Enter / Escape answer the dialog. The answer is
+returned from update() on the frame it is given, so the caller can run whatever
+was being confirmed.
+
+Like the other popups it is drawn last in the frame so it sits on top of
+everything. The rest of the UI still updates and draws behind it (the game
+keeps running); it just can't be clicked.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod dialog;
+
+Then with the other use commands add:
+use crate::modules::dialog::Dialog;
+
+Then above the loop section to use you would go:
+    let mut dialog = Dialog::new();
+
+To ask (e.g. when a Clear button is clicked):
+    dialog.open("Clear all shapes?", "Clear", "Cancel");
+
+Then in the loop, after everything else has been drawn:
+    if dialog.update() == Some(true) {
+        world.clear_shapes();
+    }
+
+Widgets that read the mouse check, so they stop while a dialog is open:
+    if input_blocked() { ... ignore the mouse ... }
+*/
+use macroquad::prelude::*;
+use std::cell::Cell;
+use crate::modules::label::Label;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 180.0;
+// Longest line of the question before it wraps
+const MESSAGE_WIDTH: f32 = PANEL_WIDTH - 60.0;
+
+thread_local! {
+    // Dialogs open right now, and whether one of them is handling its own buttons
+    static OPEN_DIALOGS: Cell<usize> = const { Cell::new(0) };
+    static IN_DIALOG: Cell<bool> = const { Cell::new(false) };
+}
+
+/// True while a dialog is open and the widget asking isn't one of the dialog's own:
+/// the widget should ignore the mouse this frame.
+pub fn input_blocked() -> bool {
+    OPEN_DIALOGS.with(Cell::get) > 0 && !IN_DIALOG.with(Cell::get)
+}
+
+pub struct Dialog {
+    open: bool,
+    // Set by open(): the click or key that opened the dialog mustn't answer it in the same frame
+    just_opened: bool,
+    lbl_message: Label,
+    btn_confirm: TextButton,
+    btn_cancel: TextButton,
+}
+
+impl Default for Dialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dialog {
+    /// A closed dialog, centered on the 1024x768 screen.
+    pub fn new() -> Self {
+        let x = (1024.0 - PANEL_WIDTH) / 2.0;
+        let y = (768.0 - PANEL_HEIGHT) / 2.0;
+        let buttons_y = y + PANEL_HEIGHT - 70.0;
+        Self {
+            open: false,
+            just_opened: false,
+            lbl_message: Label::new("", x + 30.0, y + 50.0, 28),
+            btn_confirm: TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "OK", DARKGREEN, GREEN, 25),
+            btn_cancel: TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Cancel", DARKGRAY, GRAY, 25),
+        }
+    }
+
+    /// Shows the dialog asking `message`, with `confirm` and `cancel` on its two buttons.
+    pub fn open(&mut self, message: &str, confirm: &str, cancel: &str) {
+        self.lbl_message.set_text(message);
+        self.lbl_message.with_colors(WHITE, None).with_wrap(MESSAGE_WIDTH);
+        self.btn_confirm.set_text(confirm);
+        self.btn_cancel.set_text(cancel);
+        if !self.open {
+            OPEN_DIALOGS.with(|open| open.set(open.get() + 1));
+        }
+        self.open = true;
+        self.just_opened = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Closes the dialog without an answer (e.g. when the screen it belongs to is left).
+    pub fn close(&mut self) {
+        if self.open {
+            OPEN_DIALOGS.with(|open| open.set(open.get().saturating_sub(1)));
+        }
+        self.open = false;
+    }
+
+    /// Draws the dialog over a dimmed screen and handles its buttons and keys.
+    /// Returns Some(true) on the frame it is confirmed (button or Enter) and Some(false) on the
+    /// frame it is cancelled (button or Escape); the dialog closes either way.
+    pub fn update(&mut self) -> Option<bool> {
+        if !self.open {
+            return None;
+        }
+
+        draw_rectangle(0.0, 0.0, 1024.0, 768.0, Color::new(0.0, 0.0, 0.0, 0.5));
+        draw_panel((1024.0 - PANEL_WIDTH) / 2.0, (768.0 - PANEL_HEIGHT) / 2.0, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_message.draw();
+
+        IN_DIALOG.with(|inside| inside.set(true));
+        let answerable = !std::mem::take(&mut self.just_opened);
+        let confirmed = (self.btn_confirm.click() || is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter)) && answerable;
+        let cancelled = (self.btn_cancel.click() || is_key_pressed(KeyCode::Escape)) && answerable;
+        IN_DIALOG.with(|inside| inside.set(false));
+
+        let answer = if confirmed {
+            Some(true)
+        } else if cancelled {
+            Some(false)
+        } else {
+            None
+        };
+        if answer.is_some() {
+            self.close();
+        }
+        answer
+    }
+}
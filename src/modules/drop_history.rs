@@ -0,0 +1,184 @@
+/*
+Program Details: Drop history log
+
+Keeps a record of every piece dropped: its shape, the x it was dropped at, the
+random seed its random choices came from, the bin it settled in, what it paid
+out and how long it took to get there (in simulated seconds, so slow motion and
+fast-forward don't skew it). Pieces that never land (removed by the lifetime
+manager or a board rebuild) stay in the log with no bin. The log can be saved
+as CSV for balancing work in a spreadsheet.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod drop_history;
+
+Then with the other use commands add:
+use crate::modules::drop_history::DropHistory;
+
+Then above the loop section to use you would go:
+    let mut history = DropHistory::new();
+
+When a piece is dropped:
+    history.record_drop(handle, kind, x, seed, stake);
+
+Then in the loop you would use:
+    history.update(sim_dt);
+    // ... when a piece settles and has been paid:
+    history.land(piece, bin, won);
+    // ... when a piece is removed without landing:
+    history.lose(piece);
+
+To save it:
+    history.export_csv(HISTORY_FILE)?;
+*/
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::{HashMap, VecDeque};
+use crate::modules::physics::ShapeKind;
+use serde::{Deserialize, Serialize};
+
+// Where export_csv() is pointed by the history panel
+pub const HISTORY_FILE: &str = "drop_history.csv";
+// Oldest records are forgotten past this many, so a long session doesn't grow forever
+pub const MAX_RECORDS: usize = 10_000;
+
+/// Everything known about one drop.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct DropRecord {
+    // Drop number, counting from 1 for the session
+    pub number: u32,
+    pub kind: ShapeKind,
+    // x position the piece was dropped at
+    pub spawn_x: f32,
+    // Seed the random number generator was given just before the drop's random choices
+    pub seed: u64,
+    pub stake: u32,
+    // Bin the piece settled in, or None while falling / if it was lost
+    pub bin: Option<usize>,
+    // Credits paid out (0 until it lands)
+    pub payout: u32,
+    // Simulated seconds from the drop until it settled
+    pub flight_time: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DropHistory {
+    records: VecDeque<DropRecord>,
+    // Record number and drop time of each piece still falling
+    in_flight: HashMap<RigidBodyHandle, (u32, f32)>,
+    // Simulated seconds since the history started
+    time: f32,
+    next_number: u32,
+}
+
+impl Default for DropHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DropHistory {
+    pub fn new() -> Self {
+        Self {
+            records: VecDeque::new(),
+            in_flight: HashMap::new(),
+            time: 0.0,
+            next_number: 1,
+        }
+    }
+
+    /// Starts a record for a piece that was just dropped.
+    pub fn record_drop(&mut self, piece: RigidBodyHandle, kind: ShapeKind, spawn_x: f32, seed: u64, stake: u32) {
+        let number = self.next_number;
+        self.next_number += 1;
+        self.records.push_back(DropRecord {
+            number,
+            kind,
+            spawn_x,
+            seed,
+            stake,
+            bin: None,
+            payout: 0,
+            flight_time: None,
+        });
+        if self.records.len() > MAX_RECORDS {
+            self.records.pop_front();
+        }
+        self.in_flight.insert(piece, (number, self.time));
+    }
+
+    /// Advances the clock flight times are measured with (pass the simulated frame time).
+    pub fn update(&mut self, sim_dt: f32) {
+        self.time += sim_dt;
+    }
+
+    /// Completes the record of a piece that settled in `bin` and paid `payout`.
+    pub fn land(&mut self, piece: RigidBodyHandle, bin: usize, payout: u32) {
+        let Some((number, dropped_at)) = self.in_flight.remove(&piece) else {
+            return;
+        };
+        let flight_time = self.time - dropped_at;
+        if let Some(record) = self.record_mut(number) {
+            record.bin = Some(bin);
+            record.payout = payout;
+            record.flight_time = Some(flight_time);
+        }
+    }
+
+    /// Stops tracking a piece that was removed without landing (its record keeps no bin).
+    pub fn lose(&mut self, piece: RigidBodyHandle) {
+        self.in_flight.remove(&piece);
+    }
+
+    /// Stops tracking every falling piece (e.g. when the board is rebuilt).
+    pub fn lose_all(&mut self) {
+        self.in_flight.clear();
+    }
+
+    // Every record, oldest first
+    pub fn records(&self) -> &VecDeque<DropRecord> {
+        &self.records
+    }
+
+    // Forget every record
+    #[allow(unused)]
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.in_flight.clear();
+    }
+
+    /// The history as CSV text with a header row. Falling or lost pieces have empty bin and time.
+    #[cfg_attr(target_arch = "wasm32", allow(unused))]
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("drop,shape,spawn_x,seed,stake,bin,payout,flight_time\n");
+        for record in &self.records {
+            let bin = record.bin.map(|bin| bin.to_string()).unwrap_or_default();
+            let time = record.flight_time.map(|time| format!("{:.3}", time)).unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{:?},{:.1},{},{},{},{},{}\n",
+                record.number, record.kind, record.spawn_x, record.seed, record.stake, bin, record.payout, time
+            ));
+        }
+        csv
+    }
+
+    /// Writes the history to a CSV file. Browsers can't write files, so this fails on the web.
+    pub fn export_csv(&self, path: &str) -> Result<(), String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::write(path, self.to_csv()).map_err(|e| format!("Couldn't save {}: {}", path, e))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = path;
+            Err("CSV export isn't available on the web".to_string())
+        }
+    }
+
+    // The record with the given drop number (records are in number order, oldest may be gone)
+    fn record_mut(&mut self, number: u32) -> Option<&mut DropRecord> {
+        let first = self.records.front()?.number;
+        let index = number.checked_sub(first)? as usize;
+        self.records.get_mut(index)
+    }
+}
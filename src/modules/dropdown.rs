@@ -0,0 +1,265 @@
+/*
+To import you need:
+Adds a dropdown (combobox) object: a button showing the current choice that
+opens a list of options when clicked
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod dropdown;
+
+Then with the other use commands add:
+use crate::modules::dropdown::Dropdown;
+
+Then above the loop section to use you would go:
+
+    let mut dd_shape = Dropdown::new(
+        40.0,     // x
+        726.0,    // y
+        100.0,    // width
+        34.0,     // height of the closed box and of each option in the list
+        &["Ball", "Square", "Triangle"],
+        0,        // index of the option selected to start with
+    );
+
+The list opens below the box; for a dropdown near the bottom of the screen
+open it upwards instead with:
+    dd_shape.with_open_up();
+
+You can customize the colors with:
+    dd_shape.with_colors(DARKGRAY, GRAY, Color::new(0.1, 0.1, 0.15, 0.95));   // box, hover, list background
+
+And the text size with:
+    dd_shape.with_font_size(18);
+
+You can run code every time a new option is picked with:
+    dd_shape.on_change(|index| println!("picked option {}", index));
+
+To read or set the selection:
+    let index = dd_shape.selected();
+    let text = dd_shape.selected_text();
+    dd_shape.set_selected(2);
+
+Then in the loop, like TextButton::click(), update() handles the mouse and
+returns the newly picked index on the frame a choice is made:
+if let Some(index) = dd_shape.update() {
+
+}
+
+Drawing is separate from update() so the open list can be drawn after (on top
+of) everything else:
+    dd_shape.draw();
+
+While the list is open it takes the clicks inside it; check is_open() to keep
+other mouse handling from reacting to the same click.
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::dialog::input_blocked;
+
+pub struct Dropdown {
+    x: f32,
+    y: f32,
+    pub width: f32,
+    pub height: f32,
+    options: Vec<String>,
+    selected: usize,
+    open: bool,
+    open_up: bool,
+    pub enabled: bool,
+    pub visible: bool,
+    pub normal_color: Color,
+    pub hover_color: Color,
+    pub list_color: Color,
+    pub text_color: Color,
+    pub font_size: u16,
+    on_change: Option<Box<dyn FnMut(usize)>>,
+}
+
+impl Dropdown {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, options: &[&str], selected: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            options: options.iter().map(|option| option.to_string()).collect(),
+            selected: selected.min(options.len().saturating_sub(1)),
+            open: false,
+            open_up: false,
+            enabled: true,
+            visible: true,
+            normal_color: DARKGRAY,
+            hover_color: GRAY,
+            list_color: Color::new(0.1, 0.1, 0.15, 0.95),
+            text_color: WHITE,
+            font_size: 20,
+            on_change: None,
+        }
+    }
+
+    // Method to make the list open above the box instead of below it
+    #[allow(unused)]
+    pub fn with_open_up(&mut self) -> &mut Self {
+        self.open_up = true;
+        self
+    }
+
+    // Method to set the box, hovered option and list background colors
+    #[allow(unused)]
+    pub fn with_colors(&mut self, normal: Color, hover: Color, list: Color) -> &mut Self {
+        self.normal_color = normal;
+        self.hover_color = hover;
+        self.list_color = list;
+        self
+    }
+
+    // Method to set the text size of the box and the options
+    #[allow(unused)]
+    pub fn with_font_size(&mut self, font_size: u16) -> &mut Self {
+        self.font_size = font_size;
+        self
+    }
+
+    // Method to set a callback that runs with the picked index whenever the selection changes
+    #[allow(unused)]
+    pub fn on_change(&mut self, callback: impl FnMut(usize) + 'static) -> &mut Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    // Getter for the index of the selected option
+    #[allow(unused)]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    // Getter for the text of the selected option
+    #[allow(unused)]
+    pub fn selected_text(&self) -> &str {
+        self.options.get(self.selected).map(String::as_str).unwrap_or("")
+    }
+
+    // Setter for the selection - clamped to the options (doesn't run the callback)
+    #[allow(unused)]
+    pub fn set_selected(&mut self, index: usize) -> &mut Self {
+        self.selected = index.min(self.options.len().saturating_sub(1));
+        self
+    }
+
+    // Whether the list of options is showing
+    #[allow(unused)]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    // Closes the list without changing the selection
+    #[allow(unused)]
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    // Getter for position as Vec2
+    #[allow(unused)]
+    pub fn get_position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    // Update method to move the dropdown
+    #[allow(unused)]
+    pub fn update_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Opens and closes the list and picks the option clicked in it.
+    /// Returns the new index on the frame a different option is picked; the on_change
+    /// callback runs at the same time. Clicking outside the open list closes it.
+    pub fn update(&mut self) -> Option<usize> {
+        if !self.visible || !self.enabled {
+            self.open = false;
+            return None;
+        }
+        if !is_mouse_button_pressed(MouseButton::Left) || input_blocked() {
+            return None;
+        }
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse_pos = Vec2::new(mouse_x, mouse_y);
+        if self.box_rect().contains(mouse_pos) {
+            self.open = !self.open;
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+
+        self.open = false;
+        let picked = (0..self.options.len()).find(|index| self.option_rect(*index).contains(mouse_pos))?;
+        if picked == self.selected {
+            return None;
+        }
+        self.selected = picked;
+        if let Some(callback) = self.on_change.as_mut() {
+            callback(picked);
+        }
+        Some(picked)
+    }
+
+    /// Draws the box with the current choice and, while open, the list with the hovered option highlighted.
+    pub fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse_pos = Vec2::new(mouse_x, mouse_y);
+        let alpha = if self.enabled { 1.0 } else { 0.5 };
+
+        let rect = self.box_rect();
+        let box_color = if self.enabled && (self.open || rect.contains(mouse_pos)) { self.hover_color } else { self.normal_color };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color { a: alpha, ..box_color });
+        self.draw_option_text(self.selected_text(), rect, alpha);
+
+        // Small arrow on the right pointing the way the list opens
+        let arrow_x = rect.x + rect.w - 12.0;
+        let arrow_y = rect.y + rect.h / 2.0;
+        let flip = if self.open_up { -1.0 } else { 1.0 };
+        draw_triangle(
+            vec2(arrow_x - 5.0, arrow_y - 3.0 * flip),
+            vec2(arrow_x + 5.0, arrow_y - 3.0 * flip),
+            vec2(arrow_x, arrow_y + 3.0 * flip),
+            Color { a: alpha, ..self.text_color },
+        );
+
+        if !self.open {
+            return;
+        }
+        for index in 0..self.options.len() {
+            let option = self.option_rect(index);
+            let color = if option.contains(mouse_pos) { self.hover_color } else { self.list_color };
+            draw_rectangle(option.x, option.y, option.w, option.h, color);
+            self.draw_option_text(&self.options[index], option, 1.0);
+            if index == self.selected {
+                draw_rectangle_lines(option.x, option.y, option.w, option.h, 2.0, self.text_color);
+            }
+        }
+    }
+
+    // Text left-aligned and vertically centered in a box (leaving room for the arrow)
+    fn draw_option_text(&self, text: &str, rect: Rect, alpha: f32) {
+        let size = measure_text(text, None, self.font_size, 1.0);
+        let text_y = rect.y + rect.h / 2.0 + size.offset_y / 2.0;
+        draw_text(text, rect.x + 8.0, text_y, self.font_size as f32, Color { a: alpha, ..self.text_color });
+    }
+
+    fn box_rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    // Where option `index` sits in the open list
+    fn option_rect(&self, index: usize) -> Rect {
+        let offset = (index + 1) as f32 * self.height;
+        let y = if self.open_up { self.y - offset } else { self.y + offset };
+        Rect::new(self.x, y, self.width, self.height)
+    }
+}
@@ -0,0 +1,176 @@
+/*
+Program Details: Undo and redo for the board editor
+
+Every change the board editor makes to a board is an EditCommand: adding,
+moving or deleting a peg, or changing its properties (shape, size, golden,
+breakable). Changes to a whole selection of pegs at once (moving a group,
+pasting, mirroring) are a Batch of those, undone and redone as one. A command
+knows how to apply itself to a BoardConfig and how to revert it, so the EditHistory can step back through the changes (undo) and
+forward again (redo). Making a new change after an undo throws away the
+changes that were undone, like any editor.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod edit_history;
+
+Then with the other use commands add:
+use crate::modules::edit_history::{EditCommand, EditHistory};
+
+Then above the loop section to use you would go:
+    let mut history = EditHistory::new();
+
+To make a change:
+    history.execute(EditCommand::Add { index: board.pegs.len(), peg }, &mut board);
+
+A change already made to the board (the end of a drag, say) is only recorded:
+    history.record(EditCommand::Move { index, from: (x, y), to: (new_x, new_y) });
+
+Several changes that should undo together go in a batch (applied in order, reverted in reverse):
+    history.execute(EditCommand::Batch { name: "paste pegs", commands }, &mut board);
+
+Then in the loop you would use:
+    if ctrl_pressed && is_key_pressed(KeyCode::Z) {
+        history.undo(&mut board);
+    }
+    if ctrl_pressed && is_key_pressed(KeyCode::Y) {
+        history.redo(&mut board);
+    }
+*/
+use std::collections::VecDeque;
+use crate::modules::board::{BoardConfig, PegConfig};
+
+// Most changes kept to undo; the oldest is forgotten beyond that
+const MAX_HISTORY: usize = 200;
+
+/// One change to a board's pegs. Pegs are referred to by their index in `board.pegs`.
+#[derive(Clone, Debug)]
+pub enum EditCommand {
+    /// Inserts `peg` at `index`
+    Add { index: usize, peg: PegConfig },
+    /// Removes the peg at `index` (`peg` is what it was, to put back)
+    Delete { index: usize, peg: PegConfig },
+    /// Moves the peg at `index` from one position to another
+    Move { index: usize, from: (f32, f32), to: (f32, f32) },
+    /// Replaces the peg at `index` (shape, size, golden, breakable...)
+    Change { index: usize, before: PegConfig, after: PegConfig },
+    /// Several changes made as one, applied in order; `name` describes them on the undo button
+    Batch { name: &'static str, commands: Vec<EditCommand> },
+}
+
+impl EditCommand {
+    /// Makes the change on `board`.
+    pub fn apply(&self, board: &mut BoardConfig) {
+        match self {
+            EditCommand::Add { index, peg } => board.pegs.insert(*index, *peg),
+            EditCommand::Delete { index, .. } => {
+                board.pegs.remove(*index);
+            }
+            EditCommand::Move { index, to, .. } => {
+                (board.pegs[*index].x, board.pegs[*index].y) = *to;
+            }
+            EditCommand::Change { index, after, .. } => board.pegs[*index] = *after,
+            EditCommand::Batch { commands, .. } => {
+                for command in commands {
+                    command.apply(board);
+                }
+            }
+        }
+    }
+
+    /// Takes the change back off `board`.
+    pub fn revert(&self, board: &mut BoardConfig) {
+        match self {
+            EditCommand::Add { index, .. } => {
+                board.pegs.remove(*index);
+            }
+            EditCommand::Delete { index, peg } => board.pegs.insert(*index, *peg),
+            EditCommand::Move { index, from, .. } => {
+                (board.pegs[*index].x, board.pegs[*index].y) = *from;
+            }
+            EditCommand::Change { index, before, .. } => board.pegs[*index] = *before,
+            // Later changes may depend on earlier ones (indices shifted by a delete), so back to front
+            EditCommand::Batch { commands, .. } => {
+                for command in commands.iter().rev() {
+                    command.revert(board);
+                }
+            }
+        }
+    }
+
+    /// A few words describing the change, for the undo and redo buttons.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditCommand::Add { .. } => "add peg",
+            EditCommand::Delete { .. } => "delete peg",
+            EditCommand::Move { .. } => "move peg",
+            EditCommand::Change { .. } => "change peg",
+            EditCommand::Batch { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EditHistory {
+    // Changes made, oldest first; the last one is undone next
+    done: VecDeque<EditCommand>,
+    // Changes undone, the last one is redone next
+    undone: Vec<EditCommand>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies `command` to `board` and records it.
+    pub fn execute(&mut self, command: EditCommand, board: &mut BoardConfig) {
+        command.apply(board);
+        self.record(command);
+    }
+
+    /// Records a change that has already been made to the board. Anything undone is forgotten.
+    pub fn record(&mut self, command: EditCommand) {
+        self.done.push_back(command);
+        if self.done.len() > MAX_HISTORY {
+            self.done.pop_front();
+        }
+        self.undone.clear();
+    }
+
+    /// Reverts the latest change. Returns false if there was nothing to undo.
+    pub fn undo(&mut self, board: &mut BoardConfig) -> bool {
+        let Some(command) = self.done.pop_back() else {
+            return false;
+        };
+        command.revert(board);
+        self.undone.push(command);
+        true
+    }
+
+    /// Makes the latest undone change again. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, board: &mut BoardConfig) -> bool {
+        let Some(command) = self.undone.pop() else {
+            return false;
+        };
+        command.apply(board);
+        self.done.push_back(command);
+        true
+    }
+
+    /// The change undo() would revert.
+    pub fn next_undo(&self) -> Option<&EditCommand> {
+        self.done.back()
+    }
+
+    /// The change redo() would make again.
+    pub fn next_redo(&self) -> Option<&EditCommand> {
+        self.undone.last()
+    }
+
+    /// Forgets every change (e.g. when another board is opened).
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
@@ -0,0 +1,692 @@
+/*
+Program Details: Board editor scene
+
+Opened with the Edit button next to Save Board, on a copy of the board being
+played (its peg grids are expanded into single pegs so each one can be edited).
+Clicking an empty spot above the bins adds a peg of the shape picked in the
+right column, clicking a peg selects it and dragging moves it, right-clicking
+removes it. Dragging across empty space selects every peg inside the rectangle,
+Shift+click adds a peg to the selection (or takes it out) and Ctrl+A selects
+them all; dragging any selected peg moves the whole group. The buttons change
+every selected peg: its shape, its size, and whether it is golden or breakable;
+Delete removes them.
+
+Copy (Ctrl+C) keeps the selected pegs and Paste (Ctrl+V) puts a copy of them
+centered on the mouse (or just beside the originals if the mouse is off the
+board). Mirror adds a copy of the selection flipped across the middle of the
+board, so one half of a symmetric layout only has to be built once.
+
+With Snap on (or G), new, dragged and pasted pegs land on a grid centered on
+the middle of the board; the Grid button cycles its cell size. With it off, a
+peg dragged or placed close to the row or column of another peg lines up with
+it exactly, and a guide line shows the alignment. Spread spaces three or more
+selected pegs evenly between the two outermost ones, along whichever way the
+selection is wider.
+
+Every change goes through an EditHistory (see edit_history.rs), so Ctrl+Z (or
+the Undo button) takes it back and Ctrl+Y / Ctrl+Shift+Z (or Redo) makes it
+again: layouts can be experimented with without losing anything.
+
+Play hands the edited board back to the Plinko scene, which rebuilds the world
+from it (Save Board there writes it to disk); Back (or Escape) leaves the board
+in play as it was.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod editor_scene;
+
+Then with the other use commands add:
+use crate::modules::editor_scene::{EditorScene, SharedEditorBoard};
+
+Then above the loop section to use you would go:
+    let editor_board = SharedEditorBoard::default();
+    scenes.add(SceneId::Editor, Box::new(EditorScene::new(editor_board.clone(), board_choice.clone())));
+
+To open the editor on a board (from another scene):
+    *editor_board.borrow_mut() = Some(board.clone());
+    return SceneChange::Goto(SceneId::Editor);
+*/
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::modules::board::{BoardConfig, PegConfig, PegShape};
+use crate::modules::edit_history::{EditCommand, EditHistory};
+use crate::modules::force_field::draw_force_fields;
+use crate::modules::pool::draw_pool;
+use crate::modules::chain::draw_chains;
+use crate::modules::sticky_pad::draw_sticky_pads;
+use crate::modules::label::Label;
+use crate::modules::multiplier::DropMultipliers;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::presets_scene::{draw_board_colliders, BoardChoice, SharedBoardChoice};
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::text_button::TextButton;
+use crate::modules::viewport::screen_view;
+
+// Left edge of the controls; the board is edited to the left of it
+const COLUMN_X: f32 = 830.0;
+// Shapes new pegs can be given, in the order the shape button cycles through them
+const PEG_SHAPES: [PegShape; 4] = [
+    PegShape::Circle { radius: 8.0 },
+    PegShape::Square { size: 14.0, angle: 45.0 },
+    PegShape::Triangle { size: 16.0 },
+    PegShape::Bar { length: 40.0, thickness: 6.0, angle: 0.0 },
+];
+// How much one press of the size buttons scales the selected peg, and the limits of its size
+const SIZE_STEP: f32 = 1.15;
+const MIN_PEG_EXTENT: f32 = 3.0;
+const MAX_PEG_EXTENT: f32 = 60.0;
+// Hit points given to a peg made breakable
+const BREAKABLE_HIT_POINTS: f32 = 3.0;
+// Pegs smaller than this are still this easy to click
+const MIN_PICK_RADIUS: f32 = 10.0;
+const BOARD_BACKGROUND: Color = Color::new(0.1, 0.1, 0.15, 1.0);
+const SELECTED_COLOR: Color = YELLOW;
+// The mouse moving less than this between press and release is a click, not a drag
+const CLICK_DISTANCE: f32 = 4.0;
+// How far from the copied pegs a paste lands when the mouse is off the board
+const PASTE_OFFSET: Vec2 = vec2(20.0, 20.0);
+// A mirrored peg closer than this to an existing one isn't added
+const MIRROR_OVERLAP: f32 = 1.0;
+// Cell sizes the grid button cycles through
+const GRID_SIZES: [f32; 4] = [10.0, 20.0, 25.0, 40.0];
+// A peg this close to another peg's row or column lines up with it (when not snapping to the grid)
+const ALIGN_DISTANCE: f32 = 6.0;
+const GRID_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.08);
+const GUIDE_COLOR: Color = Color::new(0.3, 0.8, 1.0, 0.8);
+
+/// The board handed to the editor when it is opened, waiting to be taken by on_enter().
+pub type SharedEditorBoard = Rc<RefCell<Option<BoardConfig>>>;
+
+// A drag in progress on the board
+enum Drag {
+    // Moving the selected pegs: where the mouse started, where the grabbed peg and each peg was then
+    Move { start: Vec2, anchor: Vec2, origins: Vec<(usize, (f32, f32))> },
+    // Pulling a selection rectangle from `start` (or clicking an empty spot, if the mouse barely moves)
+    Select { start: Vec2 },
+}
+
+pub struct EditorScene {
+    incoming: SharedEditorBoard,
+    choice: SharedBoardChoice,
+    board: BoardConfig,
+    history: EditHistory,
+    // Built from the board for drawing; rebuilt whenever the board changes
+    world: PhysicsWorld,
+    dirty: bool,
+    // Indices of the selected pegs, in increasing order
+    selected: Vec<usize>,
+    drag: Option<Drag>,
+    // Pegs copied with Copy, pasted with Paste
+    clipboard: Vec<PegConfig>,
+    snap: bool,
+    // Index into GRID_SIZES of the grid's cell size
+    grid: usize,
+    // Column (x) and row (y) the peg being placed is lined up on, drawn as guide lines
+    guides: (Option<f32>, Option<f32>),
+    // Index into PEG_SHAPES of the shape new pegs get
+    shape: usize,
+    lbl_title: Label,
+    lbl_hint: Label,
+    lbl_status: Label,
+    btn_shape: TextButton,
+    btn_smaller: TextButton,
+    btn_bigger: TextButton,
+    btn_golden: TextButton,
+    btn_breakable: TextButton,
+    btn_delete: TextButton,
+    btn_copy: TextButton,
+    btn_paste: TextButton,
+    btn_mirror: TextButton,
+    btn_spread: TextButton,
+    btn_snap: TextButton,
+    btn_grid: TextButton,
+    btn_undo: TextButton,
+    btn_redo: TextButton,
+    btn_play: TextButton,
+    btn_back: TextButton,
+}
+
+impl EditorScene {
+    pub fn new(incoming: SharedEditorBoard, choice: SharedBoardChoice) -> Self {
+        let mut lbl_title = Label::new("Board Editor", COLUMN_X, 40.0, 32);
+        lbl_title.with_colors(YELLOW, None);
+        let mut lbl_hint = Label::new(
+            "Click: add or select. Drag: move, or select an area. Shift+click: add to selection. Right-click: delete. Ctrl+C/V: copy/paste. Ctrl+Z/Y: undo/redo. G: snap.",
+            COLUMN_X,
+            632.0,
+            15,
+        );
+        lbl_hint.with_colors(LIGHTGRAY, None).with_wrap(180.0);
+        let mut lbl_status = Label::new("", COLUMN_X, 608.0, 18);
+        let mut btn_spread = TextButton::new(COLUMN_X + 92.0, 340.0, 88.0, 40.0, "Spread", DARKGRAY, GRAY, 22);
+        btn_spread.with_tooltip("Space the selected pegs evenly");
+        let mut btn_grid = TextButton::new(COLUMN_X + 92.0, 385.0, 88.0, 40.0, grid_text(GRID_SIZES[1]), DARKGRAY, GRAY, 20);
+        btn_grid.with_tooltip("Size of the snapping grid");
+        lbl_status.with_colors(WHITE, None);
+
+        Self {
+            incoming,
+            choice,
+            board: empty_board(),
+            history: EditHistory::new(),
+            world: PhysicsWorld::new(),
+            dirty: true,
+            selected: Vec::new(),
+            drag: None,
+            clipboard: Vec::new(),
+            snap: false,
+            grid: 1,
+            guides: (None, None),
+            shape: 0,
+            lbl_title,
+            lbl_hint,
+            lbl_status,
+            btn_shape: TextButton::new(COLUMN_X, 70.0, 180.0, 40.0, shape_text(0), DARKPURPLE, PURPLE, 22),
+            btn_smaller: TextButton::new(COLUMN_X, 115.0, 88.0, 40.0, "Size -", DARKGRAY, GRAY, 22),
+            btn_bigger: TextButton::new(COLUMN_X + 92.0, 115.0, 88.0, 40.0, "Size +", DARKGRAY, GRAY, 22),
+            btn_golden: TextButton::new(COLUMN_X, 160.0, 180.0, 40.0, "Golden", DARKGRAY, GRAY, 22),
+            btn_breakable: TextButton::new(COLUMN_X, 205.0, 180.0, 40.0, "Breakable", DARKGRAY, GRAY, 22),
+            btn_delete: TextButton::new(COLUMN_X, 250.0, 180.0, 40.0, "Delete", MAROON, RED, 22),
+            btn_copy: TextButton::new(COLUMN_X, 295.0, 88.0, 40.0, "Copy", DARKGRAY, GRAY, 22),
+            btn_paste: TextButton::new(COLUMN_X + 92.0, 295.0, 88.0, 40.0, "Paste", DARKGRAY, GRAY, 22),
+            btn_mirror: TextButton::new(COLUMN_X, 340.0, 88.0, 40.0, "Mirror", DARKGRAY, GRAY, 22),
+            btn_spread,
+            btn_snap: TextButton::new(COLUMN_X, 385.0, 88.0, 40.0, "Snap: Off", DARKGRAY, GRAY, 20),
+            btn_grid,
+            btn_undo: TextButton::new(COLUMN_X, 440.0, 88.0, 40.0, "Undo", DARKGRAY, GRAY, 22),
+            btn_redo: TextButton::new(COLUMN_X + 92.0, 440.0, 88.0, 40.0, "Redo", DARKGRAY, GRAY, 22),
+            btn_play: TextButton::new(COLUMN_X, 490.0, 180.0, 50.0, "Play", DARKGREEN, GREEN, 26),
+            btn_back: TextButton::new(COLUMN_X, 550.0, 180.0, 40.0, "Back", DARKGRAY, GRAY, 22),
+        }
+    }
+
+    // Makes a change through the history so it can be undone
+    fn execute(&mut self, command: EditCommand) {
+        self.history.execute(command, &mut self.board);
+        self.dirty = true;
+    }
+
+    // Makes several changes as one undoable change
+    fn execute_batch(&mut self, name: &'static str, commands: Vec<EditCommand>) {
+        if let Some(command) = batch(name, commands) {
+            self.execute(command);
+        }
+    }
+
+    // Replaces every selected peg with `change(peg)`, as one undoable change
+    fn change_selected(&mut self, change: impl Fn(&mut PegConfig)) {
+        let commands = self
+            .selected
+            .iter()
+            .map(|&index| {
+                let before = self.board.pegs[index];
+                let mut after = before;
+                change(&mut after);
+                EditCommand::Change { index, before, after }
+            })
+            .collect();
+        self.execute_batch("change pegs", commands);
+    }
+
+    // The peg under (x, y), the topmost (last drawn) one if pegs overlap
+    fn peg_at(&self, x: f32, y: f32) -> Option<usize> {
+        self.board.pegs.iter().rposition(|peg| vec2(peg.x - x, peg.y - y).length() <= peg_extent(&peg.shape).max(MIN_PICK_RADIUS))
+    }
+
+    // True if (x, y) is above the bins and between the ends of the ground, where pegs can go
+    fn in_board(&self, x: f32, y: f32) -> bool {
+        let ground = &self.board.ground;
+        x > ground.x - ground.half_width && x < ground.x + ground.half_width && y > 0.0 && y < self.board.bins_top()
+    }
+
+    // Where a peg put down at `pos` goes: onto the grid when snapping, otherwise lined up with the
+    // row or column of a nearby peg (other than the ones in `moving`). Remembers the guides to draw.
+    fn place(&mut self, pos: Vec2, moving: &[usize]) -> Vec2 {
+        if self.snap {
+            self.guides = (None, None);
+            let size = GRID_SIZES[self.grid];
+            let origin = vec2(self.board.ground.x, 0.0);
+            return origin + ((pos - origin) / size).round() * size;
+        }
+        let others = || self.board.pegs.iter().enumerate().filter(|(index, _)| !moving.contains(index)).map(|(_, peg)| vec2(peg.x, peg.y));
+        let nearest = |along: fn(Vec2) -> f32| {
+            others().map(along).filter(|line| (line - along(pos)).abs() <= ALIGN_DISTANCE).min_by(|a, b| (a - along(pos)).abs().total_cmp(&(b - along(pos)).abs()))
+        };
+        let column = nearest(|point| point.x);
+        let row = nearest(|point| point.y);
+        self.guides = (column, row);
+        vec2(column.unwrap_or(pos.x), row.unwrap_or(pos.y))
+    }
+
+    // Takes every click on the board: select, drag, add and delete pegs
+    fn handle_mouse(&mut self) {
+        self.guides = (None, None);
+        let (x, y) = mouse_position();
+        let mouse = vec2(x, y);
+        if is_mouse_button_pressed(MouseButton::Left) && x < COLUMN_X - 10.0 {
+            match self.peg_at(x, y) {
+                Some(index) if shift_down() => match self.selected.binary_search(&index) {
+                    Ok(position) => {
+                        self.selected.remove(position);
+                    }
+                    Err(position) => self.selected.insert(position, index),
+                },
+                Some(index) => {
+                    // Dragging a selected peg takes the rest of the selection along
+                    if self.selected.binary_search(&index).is_err() {
+                        self.selected = vec![index];
+                    }
+                    let origins = self.selected.iter().map(|&index| (index, (self.board.pegs[index].x, self.board.pegs[index].y))).collect();
+                    let anchor = vec2(self.board.pegs[index].x, self.board.pegs[index].y);
+                    self.drag = Some(Drag::Move { start: mouse, anchor, origins });
+                }
+                None => self.drag = Some(Drag::Select { start: mouse }),
+            }
+        }
+
+        match self.drag.take() {
+            Some(Drag::Move { start, anchor, origins }) if is_mouse_button_down(MouseButton::Left) => {
+                // The grabbed peg is snapped or lined up and the rest of the group follows it; the
+                // group only moves as far as keeps every peg on the board
+                let moving: Vec<usize> = origins.iter().map(|(index, _)| *index).collect();
+                let offset = self.place(anchor + mouse - start, &moving) - anchor;
+                if origins.iter().all(|(_, (peg_x, peg_y))| self.in_board(peg_x + offset.x, peg_y + offset.y)) {
+                    for &(index, (peg_x, peg_y)) in &origins {
+                        (self.board.pegs[index].x, self.board.pegs[index].y) = (peg_x + offset.x, peg_y + offset.y);
+                    }
+                    self.dirty = true;
+                }
+                self.drag = Some(Drag::Move { start, anchor, origins });
+            }
+            Some(Drag::Move { origins, .. }) => {
+                // The drag already moved the pegs, so it is only recorded
+                let commands: Vec<EditCommand> = origins
+                    .into_iter()
+                    .filter_map(|(index, from)| {
+                        let peg = self.board.pegs[index];
+                        ((peg.x, peg.y) != from).then_some(EditCommand::Move { index, from, to: (peg.x, peg.y) })
+                    })
+                    .collect();
+                if let Some(command) = batch("move pegs", commands) {
+                    self.history.record(command);
+                }
+            }
+            Some(Drag::Select { start }) if is_mouse_button_down(MouseButton::Left) => self.drag = Some(Drag::Select { start }),
+            Some(Drag::Select { start }) if start.distance(mouse) < CLICK_DISTANCE && !shift_down() => {
+                // A click on an empty spot: a new peg there, or nothing selected off the board
+                let at = self.place(start, &[]);
+                if self.in_board(at.x, at.y) {
+                    let peg = PegConfig { x: at.x, y: at.y, shape: PEG_SHAPES[self.shape], restitution: 0.5, motion: None, golden: false, hit_points: None, bumper: None };
+                    let index = self.board.pegs.len();
+                    self.execute(EditCommand::Add { index, peg });
+                    self.selected = vec![index];
+                } else {
+                    self.selected.clear();
+                }
+            }
+            Some(Drag::Select { start }) => {
+                let area = Rect::new(start.x.min(mouse.x), start.y.min(mouse.y), (start.x - mouse.x).abs(), (start.y - mouse.y).abs());
+                let inside = (0..self.board.pegs.len()).filter(|&index| area.contains(vec2(self.board.pegs[index].x, self.board.pegs[index].y)));
+                if shift_down() {
+                    self.selected.extend(inside);
+                    self.selected.sort_unstable();
+                    self.selected.dedup();
+                } else {
+                    self.selected = inside.collect();
+                }
+            }
+            None => {}
+        }
+
+        if is_mouse_button_pressed(MouseButton::Right)
+            && self.drag.is_none()
+            && let Some(index) = self.peg_at(x, y)
+        {
+            self.selected = vec![index];
+            self.delete_selected();
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        // Highest index first so the indices of the pegs still to delete don't shift
+        let commands = self.selected.iter().rev().map(|&index| EditCommand::Delete { index, peg: self.board.pegs[index] }).collect();
+        self.execute_batch("delete pegs", commands);
+        self.selected.clear();
+    }
+
+    fn copy_selected(&mut self) {
+        if !self.selected.is_empty() {
+            self.clipboard = self.selected.iter().map(|&index| self.board.pegs[index]).collect();
+        }
+    }
+
+    // Adds the copied pegs centered on the mouse, or beside the originals if the mouse is off the
+    // board, and selects them
+    fn paste(&mut self) {
+        if self.clipboard.is_empty() {
+            return;
+        }
+        let center = self.clipboard.iter().fold(Vec2::ZERO, |sum, peg| sum + vec2(peg.x, peg.y)) / self.clipboard.len() as f32;
+        let (x, y) = mouse_position();
+        let target = if self.in_board(x, y) { vec2(x, y) } else { center + PASTE_OFFSET };
+        let mut offset = target - center;
+        // Snapping puts the first copied peg on the grid, and the rest keep their places around it
+        if self.snap {
+            let first_peg = vec2(self.clipboard[0].x, self.clipboard[0].y) + offset;
+            offset += self.place(first_peg, &[]) - first_peg;
+        }
+
+        let first = self.board.pegs.len();
+        let pasted: Vec<PegConfig> = self
+            .clipboard
+            .iter()
+            .map(|peg| PegConfig { x: peg.x + offset.x, y: peg.y + offset.y, ..*peg })
+            .filter(|peg| self.in_board(peg.x, peg.y))
+            .collect();
+        self.selected = (first..first + pasted.len()).collect();
+        let commands = pasted.into_iter().enumerate().map(|(number, peg)| EditCommand::Add { index: first + number, peg }).collect();
+        self.execute_batch("paste pegs", commands);
+    }
+
+    // Adds a copy of the selection flipped across the middle of the board, leaving out copies
+    // that would land on a peg already there (pegs on the middle line mirror onto themselves)
+    fn mirror_selected(&mut self) {
+        let middle = self.board.ground.x;
+        let mut pegs = self.board.pegs.clone();
+        let mut commands = Vec::new();
+        for &index in &self.selected {
+            let mirrored = self.board.pegs[index].mirrored(middle);
+            let taken = pegs.iter().any(|peg| vec2(peg.x - mirrored.x, peg.y - mirrored.y).length() < MIRROR_OVERLAP);
+            if !taken && self.in_board(mirrored.x, mirrored.y) {
+                commands.push(EditCommand::Add { index: pegs.len(), peg: mirrored });
+                pegs.push(mirrored);
+            }
+        }
+        let first = self.board.pegs.len();
+        let added = commands.len();
+        self.execute_batch("mirror pegs", commands);
+        self.selected.extend(first..first + added);
+    }
+
+    // Spaces the selected pegs evenly between the two outermost ones, along whichever way the
+    // selection is wider; the other coordinate of every peg stays as it is
+    fn spread_selected(&mut self) {
+        if self.selected.len() < 3 {
+            return;
+        }
+        let positions: Vec<Vec2> = self.selected.iter().map(|&index| vec2(self.board.pegs[index].x, self.board.pegs[index].y)).collect();
+        let (min, max) = positions.iter().fold((positions[0], positions[0]), |(min, max), pos| (min.min(*pos), max.max(*pos)));
+        let horizontal = max.x - min.x >= max.y - min.y;
+        let along = |pos: Vec2| if horizontal { pos.x } else { pos.y };
+
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        order.sort_by(|a, b| along(positions[*a]).total_cmp(&along(positions[*b])));
+        let (first, last) = (along(min), along(max));
+        let step = (last - first) / (order.len() - 1) as f32;
+        let commands = order
+            .iter()
+            .enumerate()
+            .filter_map(|(rank, &number)| {
+                let from = positions[number];
+                let spaced = first + step * rank as f32;
+                let to = if horizontal { vec2(spaced, from.y) } else { vec2(from.x, spaced) };
+                (to != from).then_some(EditCommand::Move { index: self.selected[number], from: (from.x, from.y), to: (to.x, to.y) })
+            })
+            .collect();
+        self.execute_batch("spread pegs", commands);
+    }
+
+    // Faint grid lines over the part of the board pegs can go on, through the snapping points
+    fn draw_grid(&self) {
+        let size = GRID_SIZES[self.grid];
+        let ground = &self.board.ground;
+        let (left, right, bottom) = (ground.x - ground.half_width, ground.x + ground.half_width, self.board.bins_top());
+        let mut x = ground.x - ((ground.x - left) / size).floor() * size;
+        while x <= right {
+            draw_line(x, 0.0, x, bottom, 1.0, GRID_COLOR);
+            x += size;
+        }
+        let mut y = 0.0;
+        while y <= bottom {
+            draw_line(left, y, right, y, 1.0, GRID_COLOR);
+            y += size;
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.history.undo(&mut self.board) {
+            self.after_history_step();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.history.redo(&mut self.board) {
+            self.after_history_step();
+        }
+    }
+
+    // Undo and redo can take pegs away and shift the rest, so the selection starts over
+    fn after_history_step(&mut self) {
+        self.dirty = true;
+        self.drag = None;
+        self.selected.clear();
+    }
+
+    // Shows what the selected pegs are, and greys out what can't be done right now
+    fn update_buttons(&mut self) {
+        let any = !self.selected.is_empty();
+        for button in [&mut self.btn_smaller, &mut self.btn_bigger, &mut self.btn_golden, &mut self.btn_breakable, &mut self.btn_delete, &mut self.btn_copy, &mut self.btn_mirror] {
+            button.enabled = any;
+        }
+        self.btn_spread.enabled = self.selected.len() >= 3;
+        self.btn_paste.enabled = !self.clipboard.is_empty();
+        let golden = any && self.selected.iter().all(|&index| self.board.pegs[index].golden);
+        let breakable = any && self.selected.iter().all(|&index| self.board.pegs[index].hit_points.is_some());
+        self.btn_golden.set_text(if golden { "Golden: On" } else { "Golden: Off" });
+        self.btn_breakable.set_text(if breakable { "Breakable: On" } else { "Breakable: Off" });
+
+        self.btn_undo.enabled = self.history.next_undo().is_some();
+        self.btn_redo.enabled = self.history.next_redo().is_some();
+        let status = match (self.history.next_undo(), self.selected.len()) {
+            (_, 2..) => format!("{} pegs selected", self.selected.len()),
+            (Some(command), _) => format!("Undo {}", command.name()),
+            (None, 1) => "Peg selected".to_string(),
+            (None, _) => format!("{} pegs", self.board.pegs.len()),
+        };
+        self.lbl_status.set_text(status);
+    }
+}
+
+// A stand-in until the first board is handed over: just the ground and one bin
+fn empty_board() -> BoardConfig {
+    BoardConfig::from_json(
+        r#"{ "name": "Empty", "ground": { "x": 432.0, "y": 700.0, "half_width": 355.0, "half_height": 20.0 }, "walls": [],
+            "bins": { "count": 1, "divider_half_width": 4.0, "divider_half_height": 60.0 }, "payouts": [1.0] }"#,
+    )
+    .expect("the empty board is valid")
+}
+
+// Roughly how far a peg reaches from its center, for picking it and drawing the selection
+fn peg_extent(shape: &PegShape) -> f32 {
+    match *shape {
+        PegShape::Circle { radius } => radius,
+        PegShape::Square { size, .. } => size * std::f32::consts::FRAC_1_SQRT_2,
+        PegShape::Triangle { size } => size / 3f32.sqrt(),
+        PegShape::Bar { length, .. } => length / 2.0,
+    }
+}
+
+// The shape scaled by `factor`, kept between the size limits
+fn scaled_shape(shape: PegShape, factor: f32) -> PegShape {
+    let extent = peg_extent(&shape);
+    let factor = (extent * factor).clamp(MIN_PEG_EXTENT, MAX_PEG_EXTENT) / extent;
+    match shape {
+        PegShape::Circle { radius } => PegShape::Circle { radius: radius * factor },
+        PegShape::Square { size, angle } => PegShape::Square { size: size * factor, angle },
+        PegShape::Triangle { size } => PegShape::Triangle { size: size * factor },
+        PegShape::Bar { length, thickness, angle } => PegShape::Bar { length: length * factor, thickness, angle },
+    }
+}
+
+// Text of the shape button for PEG_SHAPES[index]
+fn shape_text(index: usize) -> &'static str {
+    match PEG_SHAPES[index] {
+        PegShape::Circle { .. } => "Shape: Circle",
+        PegShape::Square { .. } => "Shape: Diamond",
+        PegShape::Triangle { .. } => "Shape: Triangle",
+        PegShape::Bar { .. } => "Shape: Bar",
+    }
+}
+
+// Text of the grid button for a cell size
+fn grid_text(size: f32) -> String {
+    format!("Grid: {}", size)
+}
+
+// One undoable change out of `commands`: nothing for none, the command itself for one, a batch otherwise
+fn batch(name: &'static str, mut commands: Vec<EditCommand>) -> Option<EditCommand> {
+    match commands.len() {
+        0 => None,
+        1 => commands.pop(),
+        _ => Some(EditCommand::Batch { name, commands }),
+    }
+}
+
+fn shift_down() -> bool {
+    is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+}
+
+fn ctrl_down() -> bool {
+    is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) || is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper)
+}
+
+impl Scene for EditorScene {
+    fn on_enter(&mut self) {
+        let incoming = self.incoming.borrow_mut().take();
+        if let Some(mut board) = incoming {
+            // Every peg becomes a single peg so it can be moved on its own
+            board.pegs = board.all_pegs();
+            board.peg_grids.clear();
+            board.peg_pyramids.clear();
+            self.board = board;
+            self.history.clear();
+            self.selected.clear();
+            self.drag = None;
+            self.dirty = true;
+        }
+    }
+
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        if self.btn_back.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_play.click() {
+            *self.choice.borrow_mut() = Some(BoardChoice::Preset(Box::new(self.board.clone())));
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+
+        if self.btn_shape.click() {
+            self.shape = (self.shape + 1) % PEG_SHAPES.len();
+            self.btn_shape.set_text(shape_text(self.shape));
+            // The selected pegs take the new shape too, each at its own size
+            let shape = PEG_SHAPES[self.shape];
+            self.change_selected(|peg| {
+                let extent = peg_extent(&peg.shape);
+                peg.shape = scaled_shape(shape, extent / peg_extent(&shape));
+            });
+        }
+        if self.btn_smaller.click() {
+            self.change_selected(|peg| peg.shape = scaled_shape(peg.shape, 1.0 / SIZE_STEP));
+        }
+        if self.btn_bigger.click() {
+            self.change_selected(|peg| peg.shape = scaled_shape(peg.shape, SIZE_STEP));
+        }
+        if self.btn_golden.click() {
+            self.change_selected(|peg| peg.golden = !peg.golden);
+        }
+        if self.btn_breakable.click() {
+            self.change_selected(|peg| peg.hit_points = if peg.hit_points.is_some() { None } else { Some(BREAKABLE_HIT_POINTS) });
+        }
+        if self.btn_delete.click() || is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+            self.delete_selected();
+        }
+        if self.btn_copy.click() || (ctrl_down() && is_key_pressed(KeyCode::C)) {
+            self.copy_selected();
+        }
+        if self.btn_paste.click() || (ctrl_down() && is_key_pressed(KeyCode::V)) {
+            self.paste();
+        }
+        if self.btn_mirror.click() {
+            self.mirror_selected();
+        }
+        if self.btn_spread.click() {
+            self.spread_selected();
+        }
+        if self.btn_snap.click() || (!ctrl_down() && is_key_pressed(KeyCode::G)) {
+            self.snap = !self.snap;
+            self.btn_snap.set_text(if self.snap { "Snap: On" } else { "Snap: Off" });
+        }
+        if self.btn_grid.click() {
+            self.grid = (self.grid + 1) % GRID_SIZES.len();
+            self.btn_grid.set_text(grid_text(GRID_SIZES[self.grid]));
+        }
+        if ctrl_down() && is_key_pressed(KeyCode::A) {
+            self.selected = (0..self.board.pegs.len()).collect();
+        }
+
+        let undo_clicked = self.btn_undo.click();
+        let redo_clicked = self.btn_redo.click();
+        if redo_clicked || (ctrl_down() && (is_key_pressed(KeyCode::Y) || (shift_down() && is_key_pressed(KeyCode::Z)))) {
+            self.redo();
+        } else if undo_clicked || (ctrl_down() && is_key_pressed(KeyCode::Z)) {
+            self.undo();
+        }
+
+        self.handle_mouse();
+        self.update_buttons();
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        if self.dirty {
+            self.world.build_board(&self.board);
+            self.dirty = false;
+        }
+        draw_rectangle(0.0, 0.0, COLUMN_X - 10.0, 768.0, BOARD_BACKGROUND);
+        DropMultipliers::draw_zones(&self.board.zones);
+        draw_force_fields(&self.board.force_fields, 0.0);
+        if self.snap {
+            self.draw_grid();
+        }
+        draw_board_colliders(&self.world, screen_view());
+        draw_sticky_pads(&self.board.sticky_pads);
+        draw_pool(&self.board, 0.0);
+        draw_chains(&self.world);
+        let (guide_x, guide_y) = self.guides;
+        if let Some(x) = guide_x {
+            draw_line(x, 0.0, x, self.board.bins_top(), 1.0, GUIDE_COLOR);
+        }
+        if let Some(y) = guide_y {
+            draw_line(self.board.ground.x - self.board.ground.half_width, y, self.board.ground.x + self.board.ground.half_width, y, 1.0, GUIDE_COLOR);
+        }
+
+        for peg in self.selected.iter().filter_map(|&index| self.board.pegs.get(index)) {
+            draw_circle_lines(peg.x, peg.y, peg_extent(&peg.shape).max(MIN_PICK_RADIUS) + 4.0, 2.0, SELECTED_COLOR);
+        }
+        if let Some(Drag::Select { start }) = self.drag {
+            let (x, y) = mouse_position();
+            if start.distance(vec2(x, y)) >= CLICK_DISTANCE {
+                draw_rectangle(start.x.min(x), start.y.min(y), (start.x - x).abs(), (start.y - y).abs(), Color::new(1.0, 1.0, 0.0, 0.1));
+                draw_rectangle_lines(start.x.min(x), start.y.min(y), (start.x - x).abs(), (start.y - y).abs(), 1.5, SELECTED_COLOR);
+            }
+        }
+
+        self.lbl_title.draw();
+        self.lbl_status.draw();
+        self.lbl_hint.draw();
+    }
+}
@@ -0,0 +1,216 @@
+/*
+Program Details: What every body in the physics world is, and what the game knows about it
+
+Every body the physics world creates is tagged with its EntityKind in its
+user_data (above the sprite and owner tags, see texture_atlas.rs and
+players.rs): a piece with its shape, or the peg, wall, bin divider, funnel,
+ground, multiplier zone, sticky pad, hanging chain (anchor and links) or loose
+block of the board it was built for. Anything holding just
+the body can read the tag back, so the renderer, tooltips and hit events no
+longer have to guess what a body is from the shape of its collider.
+
+The EntityRegistry maps every body handle to an Entity: its kind and owner (from
+the tags), the game time it was spawned at, the bet placed on it and the colour
+it is drawn in. Pieces are registered with their bet and colour when they are
+dropped; update() drops the bodies that have left the world and picks up any it
+hasn't seen yet (the board's bodies, pieces put back by a quick load) with no
+bet and the default colour of their kind.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod entity_registry;
+
+Then with the other use commands add:
+use crate::modules::entity_registry::{EntityKind, EntityRegistry};
+
+Then above the loop section to use you would go:
+    let mut entities = EntityRegistry::default();
+
+When dropping a piece, register it with its bet and colour:
+    let handle = world.spawn(ShapeKind::Ball, 400.0, 50.0);
+    entities.register(handle, &world.bodies[handle], bet, RED);
+
+Then in the loop you would use (with the seconds of game time the frame's steps took):
+    let steps = world.advance(get_frame_time());
+    entities.update(&world, steps as f32 * world.integration_params.dt);
+
+And when drawing:
+    if let Some(entity) = entities.lookup(handle, body) {
+        match entity.kind { EntityKind::Piece(_) => ..., EntityKind::Peg => ..., _ => ... }
+    }
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{RigidBody, RigidBodyHandle};
+use std::collections::HashMap;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+use crate::modules::players::PlayerManager;
+
+// A body's user_data holds its kind tag from this bit up, clear of the sprite (the low 64 bits)
+// and the owner tag (the 32 bits above them)
+const KIND_SHIFT: u32 = 96;
+const KIND_BITS: u128 = (u32::MAX as u128) << KIND_SHIFT;
+// Colour of the loose blocks in the board's block stacks
+const BLOCK_COLOR: Color = Color::new(0.8, 0.6, 0.35, 1.0);
+
+/// What a body in the physics world is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntityKind {
+    /// A dropped piece of the given shape
+    Piece(ShapeKind),
+    Peg,
+    Wall,
+    Bin,
+    Segment,
+    Ground,
+    Zone,
+    StickyPad,
+    /// A hanging chain's anchor or one of its links
+    Chain,
+    /// A loose block from one of the board's block stacks
+    Block,
+}
+
+impl EntityKind {
+    // The kinds after the pieces, in tag order
+    const BOARD: [EntityKind; 7] = [
+        EntityKind::Peg,
+        EntityKind::Wall,
+        EntityKind::Bin,
+        EntityKind::Segment,
+        EntityKind::Ground,
+        EntityKind::Zone,
+        EntityKind::StickyPad,
+        EntityKind::Chain,
+        EntityKind::Block,
+    ];
+
+    // The tag stored for the kind: pieces are ShapeKind::ALL index + 1, the board's bodies follow
+    fn tag(self) -> u128 {
+        let index = match self {
+            EntityKind::Piece(shape) => ShapeKind::ALL.iter().position(|kind| *kind == shape).unwrap_or(0),
+            kind => ShapeKind::ALL.len() + Self::BOARD.iter().position(|board| *board == kind).unwrap_or(0),
+        };
+        index as u128 + 1
+    }
+
+    /// Tags a body as this kind, keeping the sprite and owner stored below the tag.
+    pub fn assign(self, body: &mut RigidBody) {
+        body.user_data = (body.user_data & !KIND_BITS) | (self.tag() << KIND_SHIFT);
+    }
+
+    /// The kind a body was tagged as, or None for a body created outside the physics world.
+    pub fn of(body: &RigidBody) -> Option<EntityKind> {
+        Self::from_user_data(body.user_data)
+    }
+
+    /// The kind tagged in a body's user_data, for bodies already removed from the world.
+    pub fn from_user_data(user_data: u128) -> Option<EntityKind> {
+        let index = ((user_data & KIND_BITS) >> KIND_SHIFT).checked_sub(1)? as usize;
+        match ShapeKind::ALL.get(index) {
+            Some(shape) => Some(EntityKind::Piece(*shape)),
+            None => Self::BOARD.get(index - ShapeKind::ALL.len()).copied(),
+        }
+    }
+
+    /// True for dropped pieces.
+    pub fn is_piece(self) -> bool {
+        matches!(self, EntityKind::Piece(_))
+    }
+
+    /// The colour a body of this kind is drawn in unless the game gives it its own.
+    pub fn default_color(self) -> Color {
+        match self {
+            EntityKind::Piece(_) => RED,
+            EntityKind::Zone => BLANK,
+            EntityKind::Block => BLOCK_COLOR,
+            _ => GREEN,
+        }
+    }
+}
+
+/// What the game knows about one body.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Entity {
+    pub kind: EntityKind,
+    /// The player who dropped it, in a hot-seat game
+    pub owner: Option<usize>,
+    /// Seconds of game time since the registry was cleared, when it was registered
+    pub spawn_time: f32,
+    /// Credits bet on it when it was dropped (0 for the board)
+    pub bet: u32,
+    pub color: Color,
+}
+
+#[derive(Default)]
+pub struct EntityRegistry {
+    entities: HashMap<RigidBodyHandle, Entity>,
+    // Game time passed since the registry was cleared
+    time: f32,
+}
+
+impl EntityRegistry {
+    /// Forgets every body, e.g. when the world is rebuilt or restored.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.time = 0.0;
+    }
+
+    /// Registers a body just spawned with the bet placed on it and its colour; the kind and owner
+    /// come from its tags. Bodies without a kind tag aren't registered.
+    pub fn register(&mut self, handle: RigidBodyHandle, body: &RigidBody, bet: u32, color: Color) {
+        if let Some(entity) = self.untracked(body) {
+            self.entities.insert(handle, Entity { bet, color, ..entity });
+        }
+    }
+
+    /// Moves the game time on by `seconds`, forgets the bodies gone from the world and registers
+    /// the tagged ones not seen before.
+    pub fn update(&mut self, world: &PhysicsWorld, seconds: f32) {
+        self.time += seconds;
+        self.entities.retain(|handle, _| world.bodies.contains(*handle));
+        for (handle, body) in world.bodies.iter() {
+            if !self.entities.contains_key(&handle)
+                && let Some(entity) = self.untracked(body)
+            {
+                self.entities.insert(handle, entity);
+            }
+        }
+    }
+
+    /// The registered entity of a body.
+    pub fn get(&self, handle: RigidBodyHandle) -> Option<&Entity> {
+        self.entities.get(&handle)
+    }
+
+    /// The entity of a body, made up from its tags if it hasn't been registered yet (e.g. the
+    /// board built since the last update).
+    pub fn lookup(&self, handle: RigidBodyHandle, body: &RigidBody) -> Option<Entity> {
+        self.entities.get(&handle).copied().or_else(|| self.untracked(body))
+    }
+
+    /// Changes the colour a registered body is drawn in, e.g. when the board theme changes.
+    pub fn set_color(&mut self, handle: RigidBodyHandle, color: Color) {
+        if let Some(entity) = self.entities.get_mut(&handle) {
+            entity.color = color;
+        }
+    }
+
+    /// Seconds of game time since a body was registered.
+    #[allow(unused)]
+    pub fn age(&self, handle: RigidBodyHandle) -> Option<f32> {
+        self.entities.get(&handle).map(|entity| self.time - entity.spawn_time)
+    }
+
+    /// Every registered body with its entity, in no particular order.
+    #[allow(unused)]
+    pub fn iter(&self) -> impl Iterator<Item = (RigidBodyHandle, &Entity)> {
+        self.entities.iter().map(|(handle, entity)| (*handle, entity))
+    }
+
+    // An entity for a tagged body, spawned now, with no bet and its kind's colour
+    fn untracked(&self, body: &RigidBody) -> Option<Entity> {
+        let kind = EntityKind::of(body)?;
+        Some(Entity { kind, owner: PlayerManager::owner(body), spawn_time: self.time, bet: 0, color: kind.default_color() })
+    }
+}
@@ -0,0 +1,232 @@
+/*
+Program Details: Event log console overlay
+
+A see-through console over the board listing the latest game events, newest at
+the bottom, each with the time it happened (seconds since the program started) and
+its category: pieces spawned, pieces reaching a bin, payouts, pieces despawned
+and warnings (pieces the watchdog removed, failed saves). It is meant for
+checking the gameplay logic where println! output can't be seen, such as the
+web version.
+
+The physics events are logged straight from the event stream with
+record_events(); the game logs the rest (spawns, payouts, despawns) itself with
+log(). Only the last MAX_ENTRIES entries are kept. The chips along the top of
+the console (or the keys 1-5 while it is open) show and hide each category; a
+click on a chip is the console's only (see contains()), and the chips ignore the
+mouse while a dialog is open.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod event_console;
+
+Then with the other use commands add:
+use crate::modules::event_console::{EventConsole, LogCategory};
+
+Then above the loop section to use you would go:
+    let mut console = EventConsole::new();
+
+Then in the loop you would use:
+    if is_key_pressed(KeyCode::GraveAccent) {
+        console.visible = !console.visible;
+    }
+    console.update();
+    console.record_events(world.events());
+    console.log(LogCategory::Payout, format!("Won {}", won));
+
+And after everything else on the board has been drawn:
+    console.draw();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::VecDeque;
+use crate::modules::dialog::input_blocked;
+use crate::modules::events::GameEvent;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+
+// Entries kept; older ones are forgotten
+pub const MAX_ENTRIES: usize = 200;
+// Where the console sits: over the lower half of the board, clear of the drop zone
+const CONSOLE_RECT: Rect = Rect { x: 90.0, y: 300.0, w: 560.0, h: 260.0 };
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.75);
+const FONT_SIZE: f32 = 16.0;
+const LINE_HEIGHT: f32 = 18.0;
+const PADDING: f32 = 8.0;
+// Size of the category chips along the top
+const CHIP_WIDTH: f32 = 84.0;
+const CHIP_HEIGHT: f32 = 22.0;
+const CHIP_GAP: f32 = 6.0;
+
+/// What kind of thing a log entry is about; each can be shown or hidden.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LogCategory {
+    Spawn,
+    Bin,
+    Payout,
+    Despawn,
+    Warning,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 5] = [LogCategory::Spawn, LogCategory::Bin, LogCategory::Payout, LogCategory::Despawn, LogCategory::Warning];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogCategory::Spawn => "Spawn",
+            LogCategory::Bin => "Bin",
+            LogCategory::Payout => "Payout",
+            LogCategory::Despawn => "Despawn",
+            LogCategory::Warning => "Warning",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            LogCategory::Spawn => SKYBLUE,
+            LogCategory::Bin => LIGHTGRAY,
+            LogCategory::Payout => GOLD,
+            LogCategory::Despawn => GRAY,
+            LogCategory::Warning => ORANGE,
+        }
+    }
+
+    // Position in ALL, which is also its key (1-5) and its chip
+    fn index(self) -> usize {
+        LogCategory::ALL.iter().position(|category| *category == self).unwrap_or(0)
+    }
+}
+
+/// One line of the console.
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    /// Seconds since the program started
+    pub time: f64,
+    pub category: LogCategory,
+    pub text: String,
+}
+
+pub struct EventConsole {
+    pub visible: bool,
+    entries: VecDeque<LogEntry>,
+    // Whether each category (in LogCategory::ALL order) is shown
+    shown: [bool; 5],
+}
+
+impl Default for EventConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventConsole {
+    pub fn new() -> Self {
+        Self { visible: false, entries: VecDeque::new(), shown: [true; 5] }
+    }
+
+    /// Adds an entry stamped with the current time.
+    pub fn log(&mut self, category: LogCategory, text: impl Into<String>) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry { time: get_time(), category, text: text.into() });
+    }
+
+    /// Logs the events of a frame worth showing: pieces reaching and settling in a bin or sticking
+    /// to a sticky pad, pieces the watchdog removed and jammed pieces nudged loose (with where they
+    /// jammed, for finding the spots on a board that catch pieces). Hits are left out; there are far
+    /// too many to read.
+    pub fn record_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match *event {
+                GameEvent::BinEntered { piece, bin } => self.log(LogCategory::Bin, format!("{} entered bin {}", piece_name(piece), bin + 1)),
+                GameEvent::Settled { piece, bin } => self.log(LogCategory::Bin, format!("{} settled in bin {}", piece_name(piece), bin + 1)),
+                GameEvent::PieceStuck { piece, pad } => self.log(LogCategory::Bin, format!("{} stuck to sticky pad {}", piece_name(piece), pad + 1)),
+                GameEvent::PieceLost { piece, reason, .. } => {
+                    self.log(LogCategory::Warning, format!("{} removed by the watchdog ({})", piece_name(piece), reason.name()));
+                }
+                GameEvent::PieceJammed { piece, x, y, nudges } => {
+                    self.log(LogCategory::Warning, format!("{} jammed at ({:.0}, {:.0}), nudge {}", piece_name(piece), x, y, nudges));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether entries of `category` are shown.
+    pub fn is_shown(&self, category: LogCategory) -> bool {
+        self.shown[category.index()]
+    }
+
+    /// Shows or hides the entries of `category`.
+    pub fn toggle(&mut self, category: LogCategory) {
+        self.shown[category.index()] = !self.shown[category.index()];
+    }
+
+    /// Forgets every entry.
+    #[allow(unused)]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Screen rectangle of the chip for category number `index`
+    fn chip_rect(index: usize) -> Rect {
+        Rect::new(
+            CONSOLE_RECT.x + PADDING + index as f32 * (CHIP_WIDTH + CHIP_GAP),
+            CONSOLE_RECT.y + PADDING,
+            CHIP_WIDTH,
+            CHIP_HEIGHT,
+        )
+    }
+
+    /// True when `point` is over one of the category chips (only while the console is open), where a
+    /// click toggles the chip and shouldn't reach the board underneath.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.visible && (0..LogCategory::ALL.len()).any(|index| Self::chip_rect(index).contains(point))
+    }
+
+    /// Handles the category chips and keys while the console is open.
+    pub fn update(&mut self) {
+        if !self.visible {
+            return;
+        }
+        let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5];
+        let (mouse_x, mouse_y) = mouse_position();
+        let clicked = is_mouse_button_pressed(MouseButton::Left) && !input_blocked();
+        for (index, category) in LogCategory::ALL.into_iter().enumerate() {
+            if is_key_pressed(keys[index]) || (clicked && Self::chip_rect(index).contains(vec2(mouse_x, mouse_y))) {
+                self.toggle(category);
+            }
+        }
+    }
+
+    /// Draws the console: the chips, then as many of the latest shown entries as fit.
+    pub fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        draw_rectangle(CONSOLE_RECT.x, CONSOLE_RECT.y, CONSOLE_RECT.w, CONSOLE_RECT.h, BACKGROUND);
+        draw_rectangle_lines(CONSOLE_RECT.x, CONSOLE_RECT.y, CONSOLE_RECT.w, CONSOLE_RECT.h, 1.0, GRAY);
+
+        for (index, category) in LogCategory::ALL.into_iter().enumerate() {
+            let rect = Self::chip_rect(index);
+            let color = if self.is_shown(category) { category.color() } else { DARKGRAY };
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, color);
+            let label = format!("{} {}", index + 1, category.name());
+            draw_text(&label, rect.x + 5.0, rect.y + rect.h - 6.0, FONT_SIZE, color);
+        }
+
+        let top = CONSOLE_RECT.y + PADDING * 2.0 + CHIP_HEIGHT;
+        let lines = ((CONSOLE_RECT.y + CONSOLE_RECT.h - PADDING - top) / LINE_HEIGHT) as usize;
+        let latest: Vec<&LogEntry> = self.entries.iter().rev().filter(|entry| self.is_shown(entry.category)).take(lines).collect();
+        for (row, entry) in latest.iter().rev().enumerate() {
+            let text = format!("{:>8.2}  {:<8} {}", entry.time, entry.category.name(), entry.text);
+            draw_text(&text, CONSOLE_RECT.x + PADDING, top + (row + 1) as f32 * LINE_HEIGHT - 4.0, FONT_SIZE, entry.category.color());
+        }
+    }
+}
+
+/// How a piece is named in the console: the index of its body handle.
+pub fn piece_name(piece: RigidBodyHandle) -> String {
+    format!("Piece #{}", piece.into_raw_parts().0)
+}
@@ -0,0 +1,126 @@
+/*
+Program Details: Game events produced by the physics world
+
+Rapier reports raw collider contacts; PhysicsWorld turns them (plus what it sees
+of the pieces each step) into typed GameEvents that gameplay systems such as
+scoring, audio and particles can react to. A fresh list is produced by every
+world.advance() call, covering all the physics steps taken that frame.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod events;
+
+Then with the other use commands add:
+use crate::modules::events::GameEvent;
+
+Then in the loop you would use:
+    world.advance(get_frame_time());
+    for event in world.events() {
+        match event {
+            GameEvent::PegHit { piece, speed, .. } => { /* tick sound, sparks */ }
+            GameEvent::WallHit { piece, speed } => { /* dust */ }
+            GameEvent::Impact { piece, kind, speed } => { /* tick or thud */ }
+            GameEvent::PegBroken { piece, x, y } => { /* shatter effect where the peg stood */ }
+            GameEvent::BumperHit { piece, bumper, .. } => { /* flash the bumper, score a hit */ }
+            GameEvent::ZoneEntered { piece, zone } => { /* multiplier bonus */ }
+            GameEvent::BinEntered { piece, bin } => { /* piece dropped into a bin */ }
+            GameEvent::Settled { piece, bin } => { /* piece came to rest: pay out */ }
+            GameEvent::PieceStuck { piece, pad } => { /* piece held by a sticky pad */ }
+            GameEvent::StickyPayout { piece, bonus, .. } => { /* its wait is over: pay the bonus */ }
+            GameEvent::BlockToppled { piece, points, .. } => { /* a block it knocked over fell */ }
+            GameEvent::PieceLost { piece, reason, .. } => { /* piece was removed by the watchdog */ }
+            GameEvent::PieceJammed { piece, x, y, .. } => { /* piece was stuck above the bins and nudged */ }
+        }
+    }
+*/
+
+use rapier2d::prelude::RigidBodyHandle;
+
+/// Something that happened to a dropped piece during the last physics steps.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    /// A piece started touching a peg (or a link of a hanging chain, `peg` being the link).
+    /// `speed` is how fast the piece was moving; `golden` is set for golden pegs.
+    PegHit { piece: RigidBodyHandle, peg: RigidBodyHandle, speed: f32, golden: bool },
+    /// A piece broke a breakable peg standing at (`x`, `y`); the peg is already gone.
+    PegBroken { piece: RigidBodyHandle, x: f32, y: f32 },
+    /// A bumper peg standing at (`x`, `y`) kicked a piece away; the piece has its push already.
+    BumperHit { piece: RigidBodyHandle, bumper: RigidBodyHandle, x: f32, y: f32 },
+    /// A piece started touching the ground, a wall or a bin divider.
+    WallHit { piece: RigidBodyHandle, speed: f32 },
+    /// A piece hit something on the board hard enough for its contact force to pass IMPACT_FORCE
+    /// (see physics.rs), the source of the impact sounds. `speed` is how fast the piece was moving.
+    Impact { piece: RigidBodyHandle, kind: ImpactKind, speed: f32 },
+    /// A piece passed into multiplier zone `zone` (index into the board's zones).
+    ZoneEntered { piece: RigidBodyHandle, zone: usize },
+    /// A piece dropped below the top of the bin dividers into bin `bin` (0 = leftmost).
+    BinEntered { piece: RigidBodyHandle, bin: usize },
+    /// A piece inside bin `bin` has come to rest (sent once per landing).
+    Settled { piece: RigidBodyHandle, bin: usize },
+    /// A piece landed on sticky pad `pad` (index into the board's sticky pads) and is held there.
+    PieceStuck { piece: RigidBodyHandle, pad: usize },
+    /// A piece's wait on sticky pad `pad` is over: it pays its stake × `bonus` and is already gone.
+    /// `user_data` is what the piece's body carried (sprite and owner tags).
+    StickyPayout { piece: RigidBodyHandle, pad: usize, bonus: f32, user_data: u128 },
+    /// A block from one of the board's block stacks was knocked over, by `piece` or by blocks it
+    /// knocked into, and is worth `points` credits. Sent once per block.
+    BlockToppled { piece: RigidBodyHandle, block: RigidBodyHandle, points: u32 },
+    /// The watchdog removed a piece that blew up or left the board; the piece is already gone.
+    /// `user_data` is what the piece's body carried (sprite and owner tags).
+    PieceLost { piece: RigidBodyHandle, reason: LostReason, user_data: u128 },
+    /// A piece had been stuck above the bins (wedged between pegs or balanced on one) at (`x`, `y`)
+    /// and has been nudged loose. `nudges` counts the nudges it has needed so far.
+    PieceJammed { piece: RigidBodyHandle, x: f32, y: f32, nudges: u32 },
+}
+
+/// What a piece hit, which decides the impact sound.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImpactKind {
+    /// A peg: a short bright tick
+    Peg,
+    /// The ground, a wall or a bin divider: a low thud
+    Wall,
+}
+
+/// Why the watchdog removed a piece.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LostReason {
+    /// Its position or velocity stopped being a number (NaN or infinite)
+    Invalid,
+    /// It was moving faster than any drop could make it
+    TooFast,
+    /// It was far outside the board, e.g. pushed through a wall
+    OutOfBounds,
+}
+
+impl LostReason {
+    pub fn name(self) -> &'static str {
+        match self {
+            LostReason::Invalid => "invalid state",
+            LostReason::TooFast => "too fast",
+            LostReason::OutOfBounds => "out of bounds",
+        }
+    }
+}
+
+impl GameEvent {
+    /// The dropped piece the event is about.
+    pub fn piece(&self) -> RigidBodyHandle {
+        match *self {
+            GameEvent::PegHit { piece, .. }
+            | GameEvent::PegBroken { piece, .. }
+            | GameEvent::BumperHit { piece, .. }
+            | GameEvent::WallHit { piece, .. }
+            | GameEvent::Impact { piece, .. }
+            | GameEvent::ZoneEntered { piece, .. }
+            | GameEvent::BinEntered { piece, .. }
+            | GameEvent::Settled { piece, .. }
+            | GameEvent::PieceStuck { piece, .. }
+            | GameEvent::StickyPayout { piece, .. }
+            | GameEvent::BlockToppled { piece, .. }
+            | GameEvent::PieceLost { piece, .. }
+            | GameEvent::PieceJammed { piece, .. } => piece,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+/*
+Program Details: Drawing the board's force fields
+
+Force fields are the board's wind, updraft and magnet rectangles (see
+ForceFieldConfig in board.rs; PhysicsWorld applies their push every step). Each
+one is drawn as a translucent rectangle tinted by what it does, with arrows
+showing which way it pushes. The arrows drift along with the push so the field
+reads as moving air (or a pull) even when no piece is in it.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod force_field;
+
+Then with the other use commands add:
+use crate::modules::force_field::draw_force_fields;
+
+Then in the loop you would use (before drawing the pieces so they show on top):
+    draw_force_fields(&board.force_fields, get_time() as f32);
+*/
+use macroquad::prelude::*;
+use crate::modules::board::{ForceEffect, ForceFieldConfig};
+
+// Tint of each kind of field; the fill uses it see-through, the outline and arrows less so
+const WIND_COLOR: Color = Color::new(0.55, 0.8, 1.0, 1.0);
+const UPDRAFT_COLOR: Color = Color::new(0.6, 1.0, 0.65, 1.0);
+const MAGNET_COLOR: Color = Color::new(1.0, 0.4, 0.4, 1.0);
+const FILL_ALPHA: f32 = 0.12;
+const OUTLINE_ALPHA: f32 = 0.45;
+const ARROW_ALPHA: f32 = 0.6;
+// Distance between neighbouring arrows, length of an arrow and how fast the arrows drift (px/s)
+const ARROW_SPACING: f32 = 40.0;
+const ARROW_LENGTH: f32 = 18.0;
+const DRIFT_SPEED: f32 = 20.0;
+
+/// Draws every force field as a tinted rectangle with drifting arrows. `time` (seconds) moves the arrows.
+pub fn draw_force_fields(fields: &[ForceFieldConfig], time: f32) {
+    for field in fields {
+        let color = field_color(field.effect);
+        let rect = Rect::new(field.x - field.half_width, field.y - field.half_height, field.half_width * 2.0, field.half_height * 2.0);
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color { a: FILL_ALPHA, ..color });
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, Color { a: OUTLINE_ALPHA, ..color });
+
+        let arrow_color = Color { a: ARROW_ALPHA, ..color };
+        let drift = (time * DRIFT_SPEED).rem_euclid(ARROW_SPACING);
+        match field.effect {
+            ForceEffect::Wind { strength } => {
+                let direction = if strength < 0.0 { -1.0 } else { 1.0 };
+                for y in grid_lines(rect.y, rect.h, 0.0) {
+                    for x in grid_lines(rect.x, rect.w, drift * direction) {
+                        draw_arrow(vec2(x, y), vec2(direction, 0.0), rect, arrow_color);
+                    }
+                }
+            }
+            ForceEffect::Updraft { strength } => {
+                let direction = if strength < 0.0 { 1.0 } else { -1.0 };
+                for x in grid_lines(rect.x, rect.w, 0.0) {
+                    for y in grid_lines(rect.y, rect.h, drift * direction) {
+                        draw_arrow(vec2(x, y), vec2(0.0, direction), rect, arrow_color);
+                    }
+                }
+            }
+            ForceEffect::Magnet { strength } => {
+                // Rings closing in on the center (or spreading out for a negative, pushing magnet)
+                let center = vec2(field.x, field.y);
+                let max_radius = field.half_width.min(field.half_height);
+                let shrink = if strength < 0.0 { -drift } else { drift };
+                let mut radius = (max_radius - shrink).rem_euclid(ARROW_SPACING);
+                while radius < max_radius {
+                    if radius > 4.0 {
+                        draw_circle_lines(center.x, center.y, radius, 1.5, arrow_color);
+                    }
+                    radius += ARROW_SPACING;
+                }
+                for angle in [0.0, 90.0, 180.0, 270.0_f32] {
+                    let outward = Vec2::from_angle(angle.to_radians());
+                    let direction = if strength < 0.0 { outward } else { -outward };
+                    draw_arrow(center + outward * (max_radius * 0.6), direction, rect, arrow_color);
+                }
+            }
+        }
+    }
+}
+
+// Tint for a kind of field
+fn field_color(effect: ForceEffect) -> Color {
+    match effect {
+        ForceEffect::Wind { .. } => WIND_COLOR,
+        ForceEffect::Updraft { .. } => UPDRAFT_COLOR,
+        ForceEffect::Magnet { .. } => MAGNET_COLOR,
+    }
+}
+
+// Positions ARROW_SPACING apart across the span from `start` (`length` long), shifted by `offset`
+// and kept clear of the span's ends by half an arrow
+fn grid_lines(start: f32, length: f32, offset: f32) -> Vec<f32> {
+    let margin = ARROW_LENGTH / 2.0;
+    let mut lines = Vec::new();
+    let mut position = start + ARROW_SPACING / 2.0 + offset.rem_euclid(ARROW_SPACING) - ARROW_SPACING;
+    while position <= start + length - margin {
+        if position >= start + margin {
+            lines.push(position);
+        }
+        position += ARROW_SPACING;
+    }
+    // A field narrower than the spacing still gets one arrow through its middle
+    if lines.is_empty() {
+        lines.push(start + length / 2.0);
+    }
+    lines
+}
+
+// An arrow centred on `center` pointing along the unit vector `direction`, skipped if it would stick out of `bounds`
+fn draw_arrow(center: Vec2, direction: Vec2, bounds: Rect, color: Color) {
+    let tip = center + direction * (ARROW_LENGTH / 2.0);
+    let tail = center - direction * (ARROW_LENGTH / 2.0);
+    if !bounds.contains(tip) || !bounds.contains(tail) {
+        return;
+    }
+    let side = vec2(-direction.y, direction.x) * 5.0;
+    let base = tip - direction * 7.0;
+    draw_line(tail.x, tail.y, base.x, base.y, 2.0, color);
+    draw_triangle(tip, base + side, base - side, color);
+}
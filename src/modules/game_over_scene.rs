@@ -0,0 +1,190 @@
+/*
+Program Details: Game over / session summary scene
+
+Shown when a session ends: the player has run out of credits, or the clock of a
+timed round has run out, and nothing is left falling. It sums up the session
+handed over through the shared Session: drops made, credits bet and won, the
+biggest single payout, the balance left and a bar chart of how many pieces
+settled in each bin (with what each bin paid).
+
+If the credits won make it onto the high score table, a panel asks for the
+player's name first and the entry is saved with the board the session ended on.
+
+Play Again starts a fresh round of the same kind (the Plinko scene resets itself
+when it is entered after a game over); Main Menu goes back to the title screen.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod game_over_scene;
+
+Then with the other use commands add:
+use crate::modules::game_over_scene::GameOverScene;
+
+Then above the loop section to use you would go:
+    let session = SharedSession::default();
+    scenes.add(SceneId::GameOver, Box::new(GameOverScene::new(session.clone())));
+*/
+use macroquad::prelude::*;
+use crate::modules::high_scores::{HighScore, HighScores};
+use crate::modules::label::Label;
+use crate::modules::name_entry_panel::NameEntryPanel;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::session::{SessionEnd, SessionSummary, SharedSession};
+use crate::modules::text_button::TextButton;
+use crate::modules::wallet::format_multiplier;
+
+// Area the bin distribution chart is drawn in
+const CHART: Rect = Rect { x: 540.0, y: 250.0, w: 380.0, h: 220.0 };
+// Space left between neighbouring bars
+const BAR_GAP: f32 = 8.0;
+
+pub struct GameOverScene {
+    session: SharedSession,
+    // The summary being shown, taken from the session when the scene is entered
+    summary: SessionSummary,
+    lbl_title: Label,
+    lbl_message: Label,
+    lbl_stats: Label,
+    lbl_chart: Label,
+    // Where the session placed on the high score table, once its name is saved
+    lbl_rank: Label,
+    name_entry: NameEntryPanel,
+    btn_again: TextButton,
+    btn_menu: TextButton,
+}
+
+impl GameOverScene {
+    pub fn new(session: SharedSession) -> Self {
+        let mut lbl_title = Label::new("Game Over", 362.0, 80.0, 60);
+        lbl_title.with_colors(RED, None);
+        let mut lbl_message = Label::new("You're out of credits.", 392.0, 150.0, 30);
+        lbl_message.with_colors(WHITE, None);
+        let mut lbl_stats = Label::new("", 110.0, 250.0, 28);
+        lbl_stats.with_colors(WHITE, None);
+        let mut lbl_chart = Label::new("Where the pieces landed", CHART.x, CHART.y - 40.0, 24);
+        lbl_chart.with_colors(LIGHTGRAY, None);
+        let mut lbl_rank = Label::new("", 110.0, 470.0, 28);
+        lbl_rank.with_colors(GOLD, None);
+
+        Self {
+            session,
+            summary: SessionSummary::default(),
+            lbl_title,
+            lbl_message,
+            lbl_stats,
+            lbl_chart,
+            lbl_rank,
+            name_entry: NameEntryPanel::new(312.0, 290.0),
+            btn_again: TextButton::new(412.0, 580.0, 200.0, 60.0, "Play Again", DARKGREEN, GREEN, 30),
+            btn_menu: TextButton::new(412.0, 660.0, 200.0, 60.0, "Main Menu", DARKGRAY, GRAY, 30),
+        }
+    }
+
+    // Bar chart of the pieces settled in each bin, labelled with the count and the bin's payout
+    fn draw_bins(&self) {
+        let bins = self.summary.payouts.len().max(self.summary.bins.counts().len());
+        if bins == 0 {
+            return;
+        }
+        let counts = self.summary.bins.counts();
+        let max = counts.iter().copied().max().unwrap_or(0).max(1);
+        let width = CHART.w / bins as f32;
+        let bottom = CHART.y + CHART.h;
+        draw_line(CHART.x, bottom, CHART.x + CHART.w, bottom, 2.0, GRAY);
+
+        for bin in 0..bins {
+            let count = counts.get(bin).copied().unwrap_or(0);
+            let height = count as f32 / max as f32 * (CHART.h - 30.0);
+            let x = CHART.x + width * bin as f32 + BAR_GAP / 2.0;
+            let bar_width = width - BAR_GAP;
+            draw_rectangle(x, bottom - height, bar_width, height, Color::new(0.2, 0.6, 1.0, 0.35));
+            draw_rectangle_lines(x, bottom - height, bar_width, height, 2.0, Color::new(0.2, 0.6, 1.0, 0.8));
+
+            let text = count.to_string();
+            let size = measure_text(&text, None, 20, 1.0);
+            draw_text(&text, x + (bar_width - size.width) / 2.0, bottom - height - 6.0, 20.0, WHITE);
+            if let Some(payout) = self.summary.payouts.get(bin) {
+                let text = format_multiplier(*payout);
+                let size = measure_text(&text, None, 20, 1.0);
+                draw_text(&text, x + (bar_width - size.width) / 2.0, bottom + 22.0, 20.0, YELLOW);
+            }
+        }
+    }
+
+    // Adds the session to the high score table under `name` and shows where it placed
+    fn save_score(&mut self, name: &str) {
+        let mut scores = HighScores::load();
+        let rank = scores.insert(HighScore::new(name, self.summary.score(), &self.summary.board));
+        let saved = scores.save();
+        match (rank, saved) {
+            (Some(rank), Ok(())) => self.lbl_rank.set_text(format!("#{} on the high score table!", rank + 1)),
+            (_, Err(error)) => self.lbl_rank.set_text(error),
+            (None, Ok(())) => self.lbl_rank.set_text(""),
+        };
+    }
+}
+
+impl Scene for GameOverScene {
+    fn on_enter(&mut self) {
+        if let Some(summary) = self.session.borrow_mut().summary.take() {
+            self.summary = summary;
+        }
+        let (title, color, message) = match self.summary.end {
+            Some(SessionEnd::TimeUp) => ("Time's Up!", YELLOW, "The timed round is over."),
+            Some(SessionEnd::OutOfPieces) => ("Out of Pieces!", SKYBLUE, "Every piece has been dropped."),
+            _ => ("Game Over", RED, "You're out of credits."),
+        };
+        self.lbl_title.set_text(title);
+        self.lbl_title.with_colors(color, None);
+        self.lbl_message.set_text(message);
+
+        let summary = &self.summary;
+        self.lbl_stats.set_text(format!(
+            "Drops: {}\nCredits bet: {}\nCredits won: {}\nBiggest payout: {}\nCredits left: {}",
+            summary.drops, summary.total_bet, summary.total_won, summary.biggest_payout, summary.final_balance
+        ));
+
+        self.lbl_rank.set_text("");
+        if HighScores::load().qualifies(summary.score()) {
+            self.name_entry.open(summary.score());
+        }
+    }
+
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        // The name entry panel takes the input while it's open (including on the frame it closes, so
+        // the Enter that saves a name doesn't start another round too)
+        let entering = self.name_entry.visible;
+        if let Some(name) = self.name_entry.update() {
+            self.save_score(&name);
+        }
+        self.btn_again.enabled = !entering;
+        self.btn_menu.enabled = !entering;
+        if entering {
+            self.btn_again.click();
+            self.btn_menu.click();
+            return SceneChange::None;
+        }
+
+        if self.btn_again.click() || is_key_pressed(KeyCode::Enter) {
+            self.session.borrow_mut().request = Some(self.summary.round);
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_menu.click() {
+            return SceneChange::Goto(SceneId::Menu);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.lbl_title.draw();
+        self.lbl_message.draw();
+        self.lbl_stats.draw();
+        self.lbl_chart.draw();
+        self.draw_bins();
+        self.lbl_rank.draw();
+
+        // The name entry panel goes over everything else
+        self.name_entry.draw();
+    }
+}
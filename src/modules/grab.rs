@@ -0,0 +1,153 @@
+/*
+Program Details: Grabbing and throwing pieces with the mouse (sandbox mode)
+
+With the "Sandbox" setting on, pressing the mouse on a dropped piece picks it
+up: a small kinematic anchor body is put under the cursor and joined to the
+piece, at the point that was clicked, by a joint whose X and Y motors pull the
+two together like a stiff, damped spring (a "mouse joint"). The anchor follows
+the cursor every frame, so the piece is dragged through the physics rather than
+teleported: it still pushes other pieces aside, swings around the point it is
+held by and can't be pulled through a peg. Letting go removes the anchor and the
+joint, and the piece flies off with whatever velocity the spring gave it, so a
+quick flick of the mouse throws it. Handy for freeing a piece wedged between
+pegs, and for playing.
+
+The anchor has no collider and no kind tag, so nothing draws or counts it.
+Only pieces can be picked up; the board's bodies stay where they are.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod grab;
+
+Then with the other use commands add:
+use crate::modules::grab::PieceGrab;
+
+Then above the loop section to use you would go:
+    let mut grab = PieceGrab::new();
+    grab.enabled = settings.sandbox;
+
+Then in the loop, before the physics steps (returns true while it has the mouse,
+so a click on a piece isn't also taken as a drop):
+    let (mouse_x, mouse_y) = mouse_position();
+    if grab.update(&mut world, vec2(mouse_x, mouse_y)) { ... }
+
+And when drawing (a line from the point held to the cursor):
+    grab.draw(&world);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{point, vector, GenericJointBuilder, JointAxesMask, JointAxis, Point, Real, RigidBodyBuilder, RigidBodyHandle};
+use crate::modules::dialog::input_blocked;
+use crate::modules::physics::PhysicsWorld;
+
+// How hard the joint pulls the piece towards the cursor (per second squared, per pixel apart),
+// and how much of the piece's speed relative to the cursor it takes away (per second)
+const GRAB_STIFFNESS: f32 = 400.0;
+const GRAB_DAMPING: f32 = 25.0;
+// How far outside a piece a press still picks it up, so small fast pieces can be caught
+const GRAB_DISTANCE: f32 = 8.0;
+// Colour of the line from the point held to the cursor
+const GRAB_LINE_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.6);
+
+// A piece being held: the anchor under the cursor and the point of the piece it holds, in the piece's own space
+struct Held {
+    piece: RigidBodyHandle,
+    anchor: RigidBodyHandle,
+    local_point: Point<Real>,
+}
+
+pub struct PieceGrab {
+    /// Whether pieces can be picked up (the Sandbox setting)
+    pub enabled: bool,
+    held: Option<Held>,
+}
+
+impl Default for PieceGrab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PieceGrab {
+    pub fn new() -> Self {
+        Self { enabled: false, held: None }
+    }
+
+    /// The piece being held, if any.
+    pub fn held(&self) -> Option<RigidBodyHandle> {
+        self.held.as_ref().map(|held| held.piece)
+    }
+
+    /// Picks up the piece under `mouse` when the button is pressed, drags it while the button is
+    /// down and throws it when the button comes up. Returns true while the mouse is busy with a
+    /// piece (including the frame it was picked up).
+    pub fn update(&mut self, world: &mut PhysicsWorld, mouse: Vec2) -> bool {
+        if !self.enabled {
+            self.release(world);
+            return false;
+        }
+
+        if let Some(held) = &self.held {
+            // The piece may have despawned (or the board been rebuilt) while it was held
+            if !world.bodies.contains(held.piece) || !world.bodies.contains(held.anchor) {
+                self.release(world);
+                return false;
+            }
+            if !is_mouse_button_down(MouseButton::Left) {
+                self.release(world);
+                return false;
+            }
+            world.bodies[held.anchor].set_next_kinematic_translation(vector![mouse.x, mouse.y]);
+            return true;
+        }
+
+        if !is_mouse_button_pressed(MouseButton::Left) || input_blocked() {
+            return false;
+        }
+        let Some(piece) = world
+            .point_project(point![mouse.x, mouse.y])
+            .filter(|hit| hit.distance <= GRAB_DISTANCE)
+            .and_then(|hit| hit.body)
+            .filter(|body| PhysicsWorld::is_piece(&world.bodies[*body]))
+        else {
+            return false;
+        };
+        self.grab(world, piece, mouse);
+        true
+    }
+
+    // Puts an anchor under the cursor and joins it to the piece at the point pressed
+    fn grab(&mut self, world: &mut PhysicsWorld, piece: RigidBodyHandle, mouse: Vec2) {
+        let local_point = world.bodies[piece].position().inverse_transform_point(&point![mouse.x, mouse.y]);
+        let anchor = world.bodies.insert(RigidBodyBuilder::kinematic_position_based().translation(vector![mouse.x, mouse.y]));
+        // No axis is locked: the motors pull the held point onto the anchor, leaving the piece free to turn
+        let joint = GenericJointBuilder::new(JointAxesMask::empty())
+            .local_anchor2(local_point)
+            .motor_position(JointAxis::X, 0.0, GRAB_STIFFNESS, GRAB_DAMPING)
+            .motor_position(JointAxis::Y, 0.0, GRAB_STIFFNESS, GRAB_DAMPING);
+        world.joints.insert(anchor, piece, joint, true);
+        self.held = Some(Held { piece, anchor, local_point });
+    }
+
+    /// Lets go of the piece held (it keeps its velocity). Also call this before saving the world, so
+    /// the anchor isn't saved with it.
+    pub fn release(&mut self, world: &mut PhysicsWorld) {
+        if let Some(held) = self.held.take() {
+            world.remove_body(held.anchor);
+        }
+    }
+
+    /// Draws a line from the point of the piece held to the cursor.
+    pub fn draw(&self, world: &PhysicsWorld) {
+        let Some(held) = &self.held else {
+            return;
+        };
+        let (Some(piece), Some(anchor)) = (world.bodies.get(held.piece), world.bodies.get(held.anchor)) else {
+            return;
+        };
+        let from = piece.position() * held.local_point;
+        let to = anchor.next_position().translation.vector;
+        draw_line(from.x, from.y, to.x, to.y, 2.0, GRAB_LINE_COLOR);
+        draw_circle(from.x, from.y, 3.0, GRAB_LINE_COLOR);
+    }
+}
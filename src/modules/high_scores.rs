@@ -0,0 +1,180 @@
+/*
+Program Details: High score table with local persistence
+
+Keeps the ten best session scores. A session's score is the credits it won
+(bins plus bonus wheel), so a game that ends out of credits still scores what
+it won on the way. Every entry has the player's name, the score, the date it
+was set and the board it was played on.
+
+The table is stored as TOML like the settings: in a high_scores.toml file next
+to the game on native, and in the browser's localStorage on the web.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod high_scores;
+
+In the Cargo.toml file add the following:
+    toml = "0.8"
+    [target.'cfg(target_arch = "wasm32")'.dependencies]
+    quad-storage = "0.1"
+
+Then with the other use commands add:
+use crate::modules::high_scores::{HighScore, HighScores};
+
+Then to use you would go (a missing or broken table loads as empty):
+    let mut scores = HighScores::load();
+    if scores.qualifies(score) {
+        let rank = scores.insert(HighScore::new("Ada", score, "Classic"));
+        scores.save();
+    }
+    for (rank, entry) in scores.entries().iter().enumerate() { ... }
+*/
+
+use serde::{Deserialize, Serialize};
+
+// How many scores the table keeps
+pub const MAX_HIGH_SCORES: usize = 10;
+// Longest name that can be entered
+pub const MAX_NAME_LENGTH: usize = 12;
+// File the table is saved to on native
+#[cfg(not(target_arch = "wasm32"))]
+pub const HIGH_SCORES_FILE: &str = "high_scores.toml";
+// localStorage key the table is saved under on the web
+#[cfg(target_arch = "wasm32")]
+pub const HIGH_SCORES_KEY: &str = "plinko2_high_scores";
+
+/// One line of the table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    /// Day the score was set, as YYYY-MM-DD
+    pub date: String,
+    /// Name of the board the session ended on
+    pub board: String,
+}
+
+impl HighScore {
+    /// An entry dated today. A blank name is entered as "Anonymous".
+    pub fn new(name: &str, score: u32, board: &str) -> Self {
+        let name: String = name.trim().chars().take(MAX_NAME_LENGTH).collect();
+        Self {
+            name: if name.is_empty() { "Anonymous".to_string() } else { name },
+            score,
+            date: today(),
+            board: board.to_string(),
+        }
+    }
+}
+
+/// The best MAX_HIGH_SCORES scores, highest first.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Loads the saved table, or an empty one if nothing was saved or it can't be read.
+    pub fn load() -> HighScores {
+        storage::read().and_then(|text| Self::from_toml(&text).ok()).unwrap_or_default()
+    }
+
+    /// Saves the table so it's there next session.
+    pub fn save(&self) -> Result<(), String> {
+        storage::write(&self.to_toml()?)
+    }
+
+    /// Parses a table from TOML text, putting it in order and cutting it to MAX_HIGH_SCORES.
+    pub fn from_toml(text: &str) -> Result<HighScores, String> {
+        let mut table: HighScores = toml::from_str(text).map_err(|e| e.to_string())?;
+        table.scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        table.scores.truncate(MAX_HIGH_SCORES);
+        Ok(table)
+    }
+
+    /// Writes the table as TOML text.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| e.to_string())
+    }
+
+    // The entries, highest score first
+    pub fn entries(&self) -> &[HighScore] {
+        &self.scores
+    }
+
+    /// True if `score` would make it onto the table. A session that won nothing never does.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.scores.len() < MAX_HIGH_SCORES || self.scores.iter().any(|entry| score > entry.score))
+    }
+
+    /// Adds an entry in order (below any equal score already there) and drops whatever falls off
+    /// the bottom. Returns its place on the table (0 = top), or None if it didn't make it.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self.scores.iter().position(|existing| entry.score > existing.score).unwrap_or(self.scores.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.scores.insert(rank, entry);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+/// Today's date (UTC) as YYYY-MM-DD.
+pub fn today() -> String {
+    let days = (unix_seconds() / 86_400.0).floor() as i64;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's days_from_civil, reversed)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Seconds since 1970-01-01: the system clock on native, JavaScript's Date.now() on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_seconds() -> f64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|time| time.as_secs_f64()).unwrap_or(0.0)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_seconds() -> f64 {
+    macroquad::miniquad::date::now()
+}
+
+// Where the table's text lives: a file on native
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use super::HIGH_SCORES_FILE;
+
+    pub fn read() -> Option<String> {
+        std::fs::read_to_string(HIGH_SCORES_FILE).ok()
+    }
+
+    pub fn write(text: &str) -> Result<(), String> {
+        std::fs::write(HIGH_SCORES_FILE, text).map_err(|e| format!("Couldn't save {}: {}", HIGH_SCORES_FILE, e))
+    }
+}
+
+// ... and localStorage on the web
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use super::HIGH_SCORES_KEY;
+
+    pub fn read() -> Option<String> {
+        quad_storage::STORAGE.lock().ok()?.get(HIGH_SCORES_KEY)
+    }
+
+    pub fn write(text: &str) -> Result<(), String> {
+        let mut storage = quad_storage::STORAGE.lock().map_err(|e| e.to_string())?;
+        storage.set(HIGH_SCORES_KEY, text);
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+/*
+Program Details: High scores screen
+
+Opened from the title menu. Shows the saved high score table (rank, name,
+score, board and date), read fresh from storage every time the screen is
+entered so a score saved on the game over screen shows up straight away.
+Back (or Escape) returns to the menu.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod high_scores_scene;
+
+Then with the other use commands add:
+use crate::modules::high_scores_scene::HighScoresScene;
+
+Then above the loop section to use you would go:
+    scenes.add(SceneId::HighScores, Box::new(HighScoresScene::new()));
+*/
+use macroquad::prelude::*;
+use crate::modules::high_scores::{HighScores, MAX_HIGH_SCORES};
+use crate::modules::label::Label;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::text_button::TextButton;
+
+// Left edge of each column of the table: rank, name, score, board, date
+const COLUMNS: [f32; 5] = [170.0, 230.0, 470.0, 600.0, 760.0];
+const TABLE_TOP: f32 = 190.0;
+const ROW_HEIGHT: f32 = 40.0;
+const FONT_SIZE: f32 = 26.0;
+
+pub struct HighScoresScene {
+    scores: HighScores,
+    lbl_title: Label,
+    btn_back: TextButton,
+}
+
+impl Default for HighScoresScene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HighScoresScene {
+    pub fn new() -> Self {
+        let mut lbl_title = Label::new("High Scores", 372.0, 70.0, 60);
+        lbl_title.with_colors(YELLOW, None);
+
+        Self {
+            scores: HighScores::default(),
+            lbl_title,
+            btn_back: TextButton::new(412.0, 670.0, 200.0, 60.0, "Back", DARKGRAY, GRAY, 30),
+        }
+    }
+}
+
+impl Scene for HighScoresScene {
+    fn on_enter(&mut self) {
+        self.scores = HighScores::load();
+    }
+
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        if self.btn_back.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Menu);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.lbl_title.draw();
+
+        for (heading, x) in ["#", "Name", "Score", "Board", "Date"].iter().zip(COLUMNS) {
+            draw_text(heading, x, TABLE_TOP - 20.0, FONT_SIZE, LIGHTGRAY);
+        }
+        draw_line(COLUMNS[0], TABLE_TOP - 10.0, 900.0, TABLE_TOP - 10.0, 2.0, GRAY);
+
+        if self.scores.entries().is_empty() {
+            draw_text("No scores yet - finish a game to set one!", COLUMNS[1], TABLE_TOP + ROW_HEIGHT, FONT_SIZE, WHITE);
+            return;
+        }
+        for rank in 0..MAX_HIGH_SCORES {
+            let y = TABLE_TOP + ROW_HEIGHT * (rank as f32 + 0.75);
+            let Some(entry) = self.scores.entries().get(rank) else {
+                draw_text(&format!("{}", rank + 1), COLUMNS[0], y, FONT_SIZE, DARKGRAY);
+                continue;
+            };
+            // Gold, silver and bronze for the top three
+            let color = match rank {
+                0 => GOLD,
+                1 => LIGHTGRAY,
+                2 => ORANGE,
+                _ => WHITE,
+            };
+            let cells = [format!("{}", rank + 1), entry.name.clone(), entry.score.to_string(), entry.board.clone(), entry.date.clone()];
+            for (cell, x) in cells.iter().zip(COLUMNS) {
+                draw_text(cell, x, y, FONT_SIZE, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+/*
+Program Details: Drop history panel drawn over the board
+
+A popup listing the drop history, newest drop first: drop number, shape, drop
+x, bin, payout and flight time. The mouse wheel (over the panel) or the Up/Down
+arrow keys scroll through older drops. "Export CSV" saves the whole history to
+drop_history.csv next to the game.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod history_panel;
+
+Then with the other use commands add:
+use crate::modules::history_panel::HistoryPanel;
+
+Then above the loop section to use you would go:
+    let mut history_panel = HistoryPanel::new(200.0, 150.0);
+
+To show it (e.g. from a "History" button):
+    history_panel.open();
+
+Then in the loop, after the board has been drawn so the panel sits on top:
+    history_panel.update(&history);
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::drop_history::{DropHistory, HISTORY_FILE};
+use crate::modules::label::Label;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 560.0;
+const PANEL_HEIGHT: f32 = 430.0;
+const ROW_HEIGHT: f32 = 22.0;
+const VISIBLE_ROWS: usize = 12;
+const FONT_SIZE: f32 = 18.0;
+// x offset of each column from the panel's left edge
+const COLUMNS: [(&str, f32); 6] = [("#", 30.0), ("Shape", 90.0), ("Drop x", 190.0), ("Bin", 280.0), ("Payout", 340.0), ("Time", 440.0)];
+
+pub struct HistoryPanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    // How many of the newest records are scrolled past
+    scroll: usize,
+    btn_export: TextButton,
+    btn_close: TextButton,
+    lbl_title: Label,
+    // Result of the last export, shown at the bottom of the panel
+    lbl_status: Label,
+}
+
+impl HistoryPanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let buttons_y = y + PANEL_HEIGHT - 80.0;
+        let btn_export = TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "Export CSV", DARKGREEN, GREEN, 25);
+        let btn_close = TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Close", DARKGRAY, GRAY, 25);
+
+        let mut lbl_title = Label::new("Drop History", x + 30.0, y + 45.0, 35);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_status = Label::new("", x + 30.0, y + PANEL_HEIGHT - 12.0, 18);
+        lbl_status.with_colors(LIGHTGRAY, None);
+
+        Self {
+            x,
+            y,
+            visible: false,
+            scroll: 0,
+            btn_export,
+            btn_close,
+            lbl_title,
+            lbl_status,
+        }
+    }
+
+    /// Shows the panel scrolled to the newest drops.
+    pub fn open(&mut self) {
+        self.scroll = 0;
+        self.lbl_status.set_text("");
+        self.visible = true;
+    }
+
+    /// Draws the panel and handles scrolling, exporting and closing.
+    pub fn update(&mut self, history: &DropHistory) {
+        if !self.visible {
+            return;
+        }
+
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+
+        let records = history.records();
+        let max_scroll = records.len().saturating_sub(VISIBLE_ROWS);
+        let (mouse_x, mouse_y) = mouse_position();
+        let over_panel = Rect::new(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT).contains(vec2(mouse_x, mouse_y));
+        let wheel = if over_panel { mouse_wheel().1 } else { 0.0 };
+        if wheel < 0.0 || is_key_pressed(KeyCode::Down) {
+            self.scroll += 1;
+        }
+        if wheel > 0.0 || is_key_pressed(KeyCode::Up) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        self.scroll = self.scroll.min(max_scroll);
+
+        // Column headings
+        let header_y = self.y + 80.0;
+        for (heading, offset) in COLUMNS {
+            draw_text(heading, self.x + offset, header_y, FONT_SIZE, YELLOW);
+        }
+
+        // Newest drop first
+        for (row, record) in records.iter().rev().skip(self.scroll).take(VISIBLE_ROWS).enumerate() {
+            let y = header_y + ROW_HEIGHT * (row + 1) as f32;
+            let bin = record.bin.map(|bin| (bin + 1).to_string()).unwrap_or_else(|| "-".to_string());
+            let payout = if record.bin.is_some() { record.payout.to_string() } else { "-".to_string() };
+            let time = record.flight_time.map(|time| format!("{:.1}s", time)).unwrap_or_else(|| "-".to_string());
+            let cells = [record.number.to_string(), format!("{:?}", record.kind), format!("{:.0}", record.spawn_x), bin, payout, time];
+            // Wins are green, losses (paid back less than the stake) gray
+            let color = if record.bin.is_some() && record.payout >= record.stake { GREEN } else { LIGHTGRAY };
+            for ((_, offset), cell) in COLUMNS.iter().zip(cells.iter()) {
+                draw_text(cell, self.x + offset, y, FONT_SIZE, color);
+            }
+        }
+
+        if records.is_empty() {
+            draw_text("No drops yet", self.x + 30.0, header_y + ROW_HEIGHT, FONT_SIZE, LIGHTGRAY);
+        } else if max_scroll > 0 {
+            let shown = format!("{}-{} of {}", self.scroll + 1, (self.scroll + VISIBLE_ROWS).min(records.len()), records.len());
+            let width = measure_text(&shown, None, FONT_SIZE as u16, 1.0).width;
+            draw_text(&shown, self.x + PANEL_WIDTH - 30.0 - width, self.y + 45.0, FONT_SIZE, LIGHTGRAY);
+        }
+
+        if self.btn_export.click() {
+            match history.export_csv(HISTORY_FILE) {
+                Ok(()) => {
+                    self.lbl_status.set_text(format!("Saved {} drops to {}", records.len(), HISTORY_FILE));
+                }
+                Err(error) => {
+                    self.lbl_status.set_text(error);
+                }
+            }
+        }
+        if self.btn_close.click() {
+            self.visible = false;
+        }
+
+        self.lbl_status.draw();
+    }
+}
@@ -0,0 +1,73 @@
+/*
+Program Details: Idle timer for starting the attract mode
+
+Counts the real seconds since the player last did anything: pressed a key or
+a mouse button, touched the screen, scrolled or moved the mouse. Once that
+passes the timer's limit it reports the game as idle, which is when the
+attract mode (see attract_scene.rs) takes over the screen. Any input starts
+the count again. A limit of 0 means the game is never idle.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod idle;
+
+Then with the other use commands add:
+use crate::modules::idle::IdleTimer;
+
+Then above the loop section to use you would go:
+    let mut idle = IdleTimer::new(30.0);
+
+Then in the loop you would use:
+    if idle.update(get_frame_time()) {
+        // nobody has touched anything for 30 seconds
+    }
+*/
+use macroquad::prelude::*;
+
+pub struct IdleTimer {
+    // Seconds without input that count as idle, and the seconds without input so far
+    limit: f32,
+    idle: f32,
+    // Where the mouse was last frame, to tell when it moves
+    mouse: Vec2,
+}
+
+impl IdleTimer {
+    pub fn new(limit: f32) -> Self {
+        Self { limit, idle: 0.0, mouse: Vec2::from(mouse_position()) }
+    }
+
+    /// Changes how many seconds without input count as idle (0: never).
+    pub fn set_limit(&mut self, limit: f32) {
+        self.limit = limit;
+    }
+
+    /// Counts `dt` more real seconds, or starts over if there was input this frame. True once there
+    /// has been no input for the timer's limit.
+    pub fn update(&mut self, dt: f32) -> bool {
+        let mouse = Vec2::from(mouse_position());
+        if any_input() || mouse != self.mouse {
+            self.idle = 0.0;
+        } else {
+            self.idle += dt;
+        }
+        self.mouse = mouse;
+        self.limit > 0.0 && self.idle >= self.limit
+    }
+
+    /// Starts the count over, e.g. when coming back to a screen.
+    pub fn reset(&mut self) {
+        self.idle = 0.0;
+        self.mouse = Vec2::from(mouse_position());
+    }
+}
+
+/// True when a key or mouse button was pressed this frame, the screen was touched or the mouse wheel turned
+/// (moving the mouse doesn't count).
+pub fn any_input() -> bool {
+    !get_keys_pressed().is_empty()
+        || [MouseButton::Left, MouseButton::Right, MouseButton::Middle].into_iter().any(is_mouse_button_pressed)
+        || touches().iter().any(|touch| touch.phase == TouchPhase::Started)
+        || mouse_wheel() != (0.0, 0.0)
+}
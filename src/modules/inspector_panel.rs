@@ -0,0 +1,278 @@
+/*
+Program Details: Inspector panel for poking at the bodies on the board
+
+While inspect mode is on (the I key on the board), clicking a body selects it
+instead of dropping a piece: the click is looked up in the physics world's query
+pipeline (see PhysicsWorld::point_project), so pegs, walls, bin dividers and
+falling pieces can all be picked, with a few pixels of slack around the small
+ones. The selected body is outlined on the board and a side panel shows what it
+is (its EntityKind), whether it is fixed, moving or asleep, and one slider each
+for its position, velocity, restitution and friction. The sliders follow the
+body as it moves; dragging one writes the value straight back into the world,
+so a peg can be slid sideways or a piece flung upwards mid-fall to see what
+happens. Restitution and friction are set on every collider of the body. The
+Sleep button puts a piece to sleep (or wakes it), Close deselects the body.
+
+Edits are for experimenting: they last until the board is rebuilt and are not
+saved with the board.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod inspector_panel;
+
+Then with the other use commands add:
+use crate::modules::inspector_panel::InspectorPanel;
+
+Then above the loop section to use you would go:
+    let mut inspector = InspectorPanel::new(20.0, 110.0);
+
+Switch inspect mode on and off (e.g. with a key):
+    if is_key_pressed(KeyCode::I) {
+        inspector.toggle();
+    }
+
+Then in the loop, select on a click (it returns false for clicks it didn't take,
+which can go on to the rest of the game):
+if is_mouse_button_pressed(MouseButton::Left) && inspector.select_at(&world, mouse_position().into()) { ... }
+
+And after the board has been drawn so the panel sits on top:
+    inspector.update(&mut world);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{point, vector, RigidBody, RigidBodyHandle};
+use crate::modules::entity_registry::EntityKind;
+use crate::modules::label::Label;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::slider::Slider;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 300.0;
+const PANEL_HEIGHT: f32 = 540.0;
+const SLIDER_WIDTH: f32 = 240.0;
+// Vertical distance between two sliders
+const SLIDER_SPACING: f32 = 55.0;
+// How far outside a body a click still selects it, so thin walls and small pegs are easy to pick
+const PICK_DISTANCE: f32 = 6.0;
+// Ranges of the position and velocity sliders (the board's virtual resolution, and a fast fall)
+const MAX_X: f32 = 1024.0;
+const MAX_Y: f32 = 768.0;
+const MAX_SPEED: f32 = 1500.0;
+// Outline drawn around the selected body
+const HIGHLIGHT_COLOR: Color = YELLOW;
+const HIGHLIGHT_PADDING: f32 = 4.0;
+
+pub struct InspectorPanel {
+    x: f32,
+    y: f32,
+    /// Inspect mode: clicks on the board select bodies instead of dropping pieces
+    pub enabled: bool,
+    selected: Option<RigidBodyHandle>,
+    lbl_title: Label,
+    lbl_kind: Label,
+    lbl_state: Label,
+    lbl_hint: Label,
+    sld_x: Slider,
+    sld_y: Slider,
+    sld_vel_x: Slider,
+    sld_vel_y: Slider,
+    sld_restitution: Slider,
+    sld_friction: Slider,
+    btn_sleep: TextButton,
+    btn_close: TextButton,
+}
+
+impl InspectorPanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let slider_x = x + (PANEL_WIDTH - SLIDER_WIDTH) / 2.0;
+        let first_y = y + 150.0;
+        // Parameters: x_pos, y_pos, width, min, max, starting value
+        let mut sld_x = Slider::new(slider_x, first_y, SLIDER_WIDTH, 0.0, MAX_X, 0.0);
+        sld_x.with_label("Position x", 18);
+        let mut sld_y = Slider::new(slider_x, first_y + SLIDER_SPACING, SLIDER_WIDTH, 0.0, MAX_Y, 0.0);
+        sld_y.with_label("Position y", 18);
+        let mut sld_vel_x = Slider::new(slider_x, first_y + SLIDER_SPACING * 2.0, SLIDER_WIDTH, -MAX_SPEED, MAX_SPEED, 0.0);
+        sld_vel_x.with_step(10.0).with_label("Velocity x", 18);
+        let mut sld_vel_y = Slider::new(slider_x, first_y + SLIDER_SPACING * 3.0, SLIDER_WIDTH, -MAX_SPEED, MAX_SPEED, 0.0);
+        sld_vel_y.with_step(10.0).with_label("Velocity y", 18);
+        let mut sld_restitution = Slider::new(slider_x, first_y + SLIDER_SPACING * 4.0, SLIDER_WIDTH, 0.0, 1.5, 0.0);
+        sld_restitution.with_step(0.05).with_label("Restitution", 18).with_decimals(2);
+        let mut sld_friction = Slider::new(slider_x, first_y + SLIDER_SPACING * 5.0, SLIDER_WIDTH, 0.0, 2.0, 0.0);
+        sld_friction.with_step(0.05).with_label("Friction", 18).with_decimals(2);
+
+        let buttons_y = y + PANEL_HEIGHT - 65.0;
+        let btn_sleep = TextButton::new(x + 20.0, buttons_y, 120.0, 45.0, "Sleep", DARKGRAY, GRAY, 22);
+        let btn_close = TextButton::new(x + PANEL_WIDTH - 140.0, buttons_y, 120.0, 45.0, "Close", DARKGREEN, GREEN, 22);
+
+        let mut lbl_title = Label::new("Inspector", x + 20.0, y + 40.0, 32);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_kind = Label::new("", x + 20.0, y + 75.0, 22);
+        lbl_kind.with_colors(WHITE, None);
+        let mut lbl_state = Label::new("", x + 20.0, y + 100.0, 20);
+        lbl_state.with_colors(LIGHTGRAY, None);
+        let mut lbl_hint = Label::new("Inspect mode: click a body on the board (I to leave)", x, y - 10.0, 18);
+        lbl_hint.with_colors(HIGHLIGHT_COLOR, None);
+
+        Self {
+            x,
+            y,
+            enabled: false,
+            selected: None,
+            lbl_title,
+            lbl_kind,
+            lbl_state,
+            lbl_hint,
+            sld_x,
+            sld_y,
+            sld_vel_x,
+            sld_vel_y,
+            sld_restitution,
+            sld_friction,
+            btn_sleep,
+            btn_close,
+        }
+    }
+
+    /// Switches inspect mode on or off; leaving it deselects the body.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        if !self.enabled {
+            self.selected = None;
+        }
+    }
+
+    /// The body being inspected, if any.
+    pub fn selected(&self) -> Option<RigidBodyHandle> {
+        self.selected
+    }
+
+    /// Forgets the selected body, e.g. when the board is rebuilt under it.
+    pub fn deselect(&mut self) {
+        self.selected = None;
+    }
+
+    /// True when `point` is over the panel (only shown while a body is selected).
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.enabled && self.selected.is_some() && Rect::new(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT).contains(point)
+    }
+
+    /// In inspect mode, selects the body at (or just beside) `point`, or deselects when there is none.
+    /// Returns true when the click was taken, false outside inspect mode and over the panel itself.
+    pub fn select_at(&mut self, world: &PhysicsWorld, point: Vec2) -> bool {
+        if !self.enabled || self.contains(point) {
+            return false;
+        }
+        self.selected = world
+            .point_project(point![point.x, point.y])
+            .filter(|hit| hit.distance <= PICK_DISTANCE)
+            .and_then(|hit| hit.body);
+        true
+    }
+
+    /// Outlines the selected body, draws the panel and writes slider changes back into the world.
+    pub fn update(&mut self, world: &mut PhysicsWorld) {
+        if !self.enabled {
+            return;
+        }
+        self.lbl_hint.draw();
+        // The body may have despawned (or the board been rebuilt) since it was picked
+        let Some(handle) = self.selected.filter(|handle| world.bodies.contains(*handle)) else {
+            self.selected = None;
+            return;
+        };
+
+        self.draw_highlight(world, handle);
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+
+        let body = &world.bodies[handle];
+        self.lbl_kind.set_text(kind_name(body));
+        self.lbl_state.set_text(state_name(body));
+        self.lbl_kind.draw();
+        self.lbl_state.draw();
+        let dynamic = body.is_dynamic();
+
+        // The sliders follow the body; a slider being dragged overrides it straight after
+        let (pos, vel) = (*body.translation(), *body.linvel());
+        self.sld_x.set_value(pos.x);
+        self.sld_y.set_value(pos.y);
+        self.sld_vel_x.set_value(vel.x);
+        self.sld_vel_y.set_value(vel.y);
+        self.sld_vel_x.enabled = dynamic;
+        self.sld_vel_y.enabled = dynamic;
+        if let Some(collider) = body.colliders().first().and_then(|collider| world.colliders.get(*collider)) {
+            self.sld_restitution.set_value(collider.restitution());
+            self.sld_friction.set_value(collider.friction());
+        }
+
+        if self.sld_x.update() | self.sld_y.update() {
+            world.bodies[handle].set_translation(vector![self.sld_x.value(), self.sld_y.value()], true);
+            world.update_queries();
+        }
+        if self.sld_vel_x.update() | self.sld_vel_y.update() {
+            world.bodies[handle].set_linvel(vector![self.sld_vel_x.value(), self.sld_vel_y.value()], true);
+        }
+        if self.sld_restitution.update() | self.sld_friction.update() {
+            for collider in world.bodies[handle].colliders() {
+                if let Some(collider) = world.colliders.get_mut(*collider) {
+                    collider.set_restitution(self.sld_restitution.value());
+                    collider.set_friction(self.sld_friction.value());
+                }
+            }
+        }
+
+        // Only pieces fall asleep; the board's bodies never move on their own
+        self.btn_sleep.enabled = dynamic;
+        self.btn_sleep.set_text(if world.bodies[handle].is_sleeping() { "Wake" } else { "Sleep" });
+        if self.btn_sleep.click() {
+            let body = &mut world.bodies[handle];
+            if body.is_sleeping() {
+                body.wake_up(true);
+            } else {
+                body.sleep();
+            }
+        }
+        if self.btn_close.click() {
+            self.selected = None;
+        }
+    }
+
+    // Draws a box around the selected body's colliders, where the physics has them
+    fn draw_highlight(&self, world: &PhysicsWorld, handle: RigidBodyHandle) {
+        let bounds = world.bodies[handle]
+            .colliders()
+            .iter()
+            .filter_map(|collider| world.colliders.get(*collider))
+            .map(|collider| collider.compute_aabb())
+            .reduce(|a, b| a.merged(&b));
+        if let Some(aabb) = bounds {
+            let (x, y) = (aabb.mins.x - HIGHLIGHT_PADDING, aabb.mins.y - HIGHLIGHT_PADDING);
+            let (w, h) = (aabb.maxs.x - aabb.mins.x + HIGHLIGHT_PADDING * 2.0, aabb.maxs.y - aabb.mins.y + HIGHLIGHT_PADDING * 2.0);
+            draw_rectangle_lines(x, y, w, h, 2.0, HIGHLIGHT_COLOR);
+        }
+    }
+}
+
+// What a body is, e.g. "Ball piece" or "Peg"
+fn kind_name(body: &RigidBody) -> String {
+    match EntityKind::of(body) {
+        Some(EntityKind::Piece(shape)) => format!("{:?} piece", shape),
+        Some(EntityKind::StickyPad) => "Sticky pad".to_string(),
+        Some(kind) => format!("{:?}", kind),
+        None => "Untagged body".to_string(),
+    }
+}
+
+// How the body moves: fixed, moved by the board (kinematic), or simulated and awake or asleep
+fn state_name(body: &RigidBody) -> &'static str {
+    if body.is_fixed() {
+        "Fixed"
+    } else if body.is_kinematic() {
+        "Kinematic (moved by the board)"
+    } else if body.is_sleeping() {
+        "Dynamic, asleep"
+    } else {
+        "Dynamic, awake"
+    }
+}
@@ -0,0 +1,339 @@
+/*
+Program Details: Slow-motion instant replay of a piece's landing
+
+InstantReplay records where every piece has been over the last REPLAY_SECONDS of
+game time (position and rotation after every physics step). When a piece settles
+in a bin it offers a replay of that landing for a few seconds: pressing R plays
+the recorded poses of that piece, and of every other piece that was nearby at
+the time, back at REPLAY_SPEED in a small picture-in-picture view that follows
+the landed piece. The view is drawn into a Viewport of its own (see
+viewport.rs), with the board's bodies as they are now, and then shown over the
+board.
+
+Nothing is simulated again: the replay only moves the recorded shapes between
+the recorded poses, so it shows exactly what happened even if the board has
+changed since.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod instant_replay;
+
+Then with the other use commands add:
+use crate::modules::instant_replay::InstantReplay;
+
+Then above the loop section to use you would go:
+    let mut replay = InstantReplay::new();
+
+Then in the loop you would use (after the physics has stepped):
+    replay.record(&world, steps as f32 * world.integration_params.dt);
+    replay.record_events(world.events(), |handle| YELLOW);
+    if is_key_pressed(KeyCode::R) {
+        replay.play();
+    }
+    replay.update(dt);
+    // ... at the start of drawing, before anything else is drawn to the screen:
+    replay.render(&world);
+    // ... and once the board has been drawn:
+    replay.draw();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{RigidBodyHandle, Shape};
+use std::collections::{HashMap, VecDeque};
+use crate::modules::chain::draw_chains;
+use crate::modules::events::GameEvent;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::presets_scene::draw_board_colliders;
+use crate::modules::viewport::Viewport;
+
+/// Seconds of each piece's flight kept for a replay.
+pub const REPLAY_SECONDS: f32 = 2.0;
+/// How fast a replay plays back (a quarter of real speed).
+pub const REPLAY_SPEED: f32 = 0.25;
+// How long a replay stays on offer after its landing, in seconds
+const OFFER_SECONDS: f32 = 5.0;
+// How long the last frame stays up once a replay has finished, in seconds
+const HOLD_SECONDS: f32 = 0.75;
+// Where the picture-in-picture view sits on the screen (the board's top-left corner)
+const VIEWPORT: Rect = Rect { x: 60.0, y: 45.0, w: 240.0, h: 180.0 };
+// How much the view magnifies the board
+const ZOOM: f32 = 1.5;
+// Viewport pixels per screen pixel, so the view stays sharp when the window is scaled up
+const TARGET_SCALE: f32 = 2.0;
+// Colours of the view's background, frame and caption, and of the ring around the landed piece
+const VIEW_BACKGROUND: Color = Color::new(0.05, 0.05, 0.1, 1.0);
+const FRAME_COLOR: Color = Color::new(0.9, 0.9, 0.9, 1.0);
+const HIGHLIGHT_COLOR: Color = Color::new(1.0, 0.9, 0.3, 1.0);
+// Pieces other than the landed one are dimmed to this opacity
+const OTHER_ALPHA: f32 = 0.6;
+
+// Where a piece was after one physics step
+#[derive(Clone, Copy)]
+struct Pose {
+    time: f32,
+    x: f32,
+    y: f32,
+    rot: f32,
+}
+
+/// The outline of a piece's collider, relative to its centre: a circle of some radius or a convex polygon.
+#[derive(Clone)]
+pub enum Outline {
+    Round(f32),
+    Polygon(Vec<Vec2>),
+}
+
+impl Outline {
+    /// The outline of `shape`. Boxes and convex polygons keep their corners; anything else is drawn as the
+    /// circle around it.
+    pub fn of(shape: &dyn Shape) -> Self {
+        if let Some(cuboid) = shape.as_cuboid() {
+            let (hx, hy) = (cuboid.half_extents.x, cuboid.half_extents.y);
+            Outline::Polygon(vec![vec2(-hx, -hy), vec2(hx, -hy), vec2(hx, hy), vec2(-hx, hy)])
+        } else if let Some(convex) = shape.as_convex_polygon() {
+            Outline::Polygon(convex.points().iter().map(|point| vec2(point.x, point.y)).collect())
+        } else {
+            Outline::Round(shape.compute_local_aabb().half_extents().max())
+        }
+    }
+
+    /// Draws the outline filled in `color`, centred on (`x`, `y`) and turned by `rot` radians.
+    pub fn draw(&self, x: f32, y: f32, rot: f32, color: Color) {
+        match self {
+            Outline::Round(radius) => draw_circle(x, y, *radius, color),
+            Outline::Polygon(points) => {
+                let (sin, cos) = rot.sin_cos();
+                let corner = |point: Vec2| vec2(x + point.x * cos - point.y * sin, y + point.x * sin + point.y * cos);
+                // A fan of triangles from the first corner (the outlines are convex)
+                for pair in points.windows(2).skip(1) {
+                    draw_triangle(corner(points[0]), corner(pair[0]), corner(pair[1]), color);
+                }
+            }
+        }
+    }
+}
+
+// A piece's poses over the last REPLAY_SECONDS, oldest first
+struct Track {
+    outline: Outline,
+    poses: VecDeque<Pose>,
+}
+
+// A recorded piece in a replay, in the colour it was drawn in when it landed
+struct ReplayTrack {
+    piece: RigidBodyHandle,
+    outline: Outline,
+    color: Color,
+    poses: Vec<Pose>,
+}
+
+// One landing's replay and how far it has been played
+struct Replay {
+    piece: RigidBodyHandle,
+    tracks: Vec<ReplayTrack>,
+    end: f32,
+    cursor: f32,
+}
+
+/// Records the pieces' recent flights and plays a landing back slowed down in a picture-in-picture view.
+#[derive(Default)]
+pub struct InstantReplay {
+    // Game time recorded so far, in seconds
+    time: f32,
+    tracks: HashMap<RigidBodyHandle, Track>,
+    // The latest landing's replay, and the seconds it stays on offer
+    offer: Option<(Replay, f32)>,
+    playing: Option<Replay>,
+    // The viewport the view is drawn into, made for the first replay and reused
+    viewport: Option<Viewport>,
+}
+
+impl InstantReplay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records where every piece is after `seconds` more of game time (nothing when the physics hasn't moved),
+    /// and forgets poses older than REPLAY_SECONDS.
+    pub fn record(&mut self, world: &PhysicsWorld, seconds: f32) {
+        if seconds <= 0.0 {
+            return;
+        }
+        self.time += seconds;
+        for (handle, body) in world.bodies.iter() {
+            if !PhysicsWorld::is_piece(body) {
+                continue;
+            }
+            let pos = body.translation();
+            let pose = Pose { time: self.time, x: pos.x, y: pos.y, rot: body.rotation().angle() };
+            if let Some(track) = self.tracks.get_mut(&handle) {
+                track.poses.push_back(pose);
+                continue;
+            }
+            let Some(collider) = body.colliders().first().and_then(|collider| world.colliders.get(*collider)) else {
+                continue;
+            };
+            self.tracks.insert(handle, Track { outline: Outline::of(collider.shape()), poses: VecDeque::from([pose]) });
+        }
+
+        let oldest = self.time - REPLAY_SECONDS;
+        for track in self.tracks.values_mut() {
+            while track.poses.front().is_some_and(|pose| pose.time < oldest) {
+                track.poses.pop_front();
+            }
+        }
+        self.tracks.retain(|_, track| !track.poses.is_empty());
+    }
+
+    /// Offers a replay of the latest piece in a frame's GameEvents to settle in a bin, with every recorded
+    /// piece in the colour `color_of` gives it now.
+    pub fn record_events(&mut self, events: &[GameEvent], color_of: impl Fn(RigidBodyHandle) -> Color) {
+        let Some(piece) = events.iter().rev().find_map(|event| match *event {
+            GameEvent::Settled { piece, .. } => Some(piece),
+            _ => None,
+        }) else {
+            return;
+        };
+        let Some(start) = self.tracks.get(&piece).and_then(|track| track.poses.front()).map(|pose| pose.time) else {
+            return;
+        };
+        let tracks = self
+            .tracks
+            .iter()
+            .map(|(handle, track)| ReplayTrack {
+                piece: *handle,
+                outline: track.outline.clone(),
+                color: color_of(*handle),
+                poses: track.poses.iter().copied().collect(),
+            })
+            .collect();
+        let replay = Replay { piece, tracks, end: self.time, cursor: start };
+        self.offer = Some((replay, OFFER_SECONDS));
+    }
+
+    /// True while a landing's replay is on offer (and not already playing).
+    pub fn is_offered(&self) -> bool {
+        self.offer.is_some() && self.playing.is_none()
+    }
+
+    /// True while a replay is showing.
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    /// Plays the replay on offer, if there is one.
+    pub fn play(&mut self) {
+        if self.playing.is_some() {
+            return;
+        }
+        if let Some((replay, _)) = self.offer.take() {
+            self.playing = Some(replay);
+        }
+    }
+
+    /// Moves the replay on by `dt` seconds of real time (at REPLAY_SPEED) and runs down the offer.
+    pub fn update(&mut self, dt: f32) {
+        if let Some((_, left)) = self.offer.as_mut() {
+            *left -= dt;
+            if *left <= 0.0 {
+                self.offer = None;
+            }
+        }
+        if let Some(replay) = self.playing.as_mut() {
+            replay.cursor += dt * REPLAY_SPEED;
+            // The last frame is held for a moment before the view closes
+            if replay.cursor > replay.end + HOLD_SECONDS * REPLAY_SPEED {
+                self.playing = None;
+            }
+        }
+    }
+
+    /// Draws the replay's current frame into the view's viewport. Call it before anything else is drawn
+    /// this frame.
+    pub fn render(&mut self, world: &PhysicsWorld) {
+        let Some(replay) = self.playing.as_ref() else {
+            return;
+        };
+        let viewport = self
+            .viewport
+            .get_or_insert_with(|| Viewport::new((VIEWPORT.w * TARGET_SCALE) as u32, (VIEWPORT.h * TARGET_SCALE) as u32));
+
+        // The camera follows the landed piece
+        let time = replay.cursor.min(replay.end);
+        let center = replay
+            .tracks
+            .iter()
+            .find(|track| track.piece == replay.piece)
+            .and_then(|track| pose_at(&track.poses, time))
+            .map_or(vec2(VIEWPORT.x + VIEWPORT.w / 2.0, VIEWPORT.y + VIEWPORT.h / 2.0), |pose| vec2(pose.x, pose.y));
+        let (width, height) = (VIEWPORT.w / ZOOM, VIEWPORT.h / ZOOM);
+        let view = Rect::new(center.x - width / 2.0, center.y - height / 2.0, width, height);
+        viewport.render(view, || {
+            draw_rectangle(view.x, view.y, view.w, view.h, VIEW_BACKGROUND);
+            draw_board_colliders(world, view);
+            draw_chains(world);
+            for track in &replay.tracks {
+                let Some(pose) = pose_at(&track.poses, time) else {
+                    continue;
+                };
+                let landed = track.piece == replay.piece;
+                let color = if landed { track.color } else { Color { a: OTHER_ALPHA, ..track.color } };
+                track.outline.draw(pose.x, pose.y, pose.rot, color);
+                if landed {
+                    let radius = match &track.outline {
+                        Outline::Round(radius) => *radius,
+                        Outline::Polygon(points) => points.iter().map(|point| point.length()).fold(0.0, f32::max),
+                    };
+                    draw_circle_lines(pose.x, pose.y, radius + 3.0, 1.5, HIGHLIGHT_COLOR);
+                }
+            }
+        });
+    }
+
+    /// Draws the picture-in-picture view while a replay plays, and a hint while one is on offer.
+    pub fn draw(&self) {
+        if self.is_offered() {
+            draw_text("R: instant replay", VIEWPORT.x, VIEWPORT.y + 14.0, 20.0, FRAME_COLOR);
+            return;
+        }
+        let (Some(_), Some(viewport)) = (self.playing.as_ref(), self.viewport.as_ref()) else {
+            return;
+        };
+        viewport.draw(VIEWPORT);
+        draw_rectangle_lines(VIEWPORT.x, VIEWPORT.y, VIEWPORT.w, VIEWPORT.h, 2.0, FRAME_COLOR);
+        draw_text("Replay x0.25", VIEWPORT.x + 6.0, VIEWPORT.y + VIEWPORT.h - 8.0, 18.0, FRAME_COLOR);
+    }
+
+    /// Forgets every recorded flight and any replay on offer or playing (e.g. when the board is rebuilt).
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+        self.offer = None;
+        self.playing = None;
+    }
+}
+
+// A track's pose at `time`, between the two recorded poses either side of it (None before the track starts)
+fn pose_at(poses: &[Pose], time: f32) -> Option<Pose> {
+    let next = poses.partition_point(|pose| pose.time < time);
+    if next == 0 {
+        return poses.first().filter(|pose| pose.time <= time).copied();
+    }
+    let before = poses[next - 1];
+    let Some(after) = poses.get(next) else {
+        return Some(before);
+    };
+    let along = ((time - before.time) / (after.time - before.time).max(f32::EPSILON)).clamp(0.0, 1.0);
+    let mut turn = after.rot - before.rot;
+    // The short way round
+    if turn > std::f32::consts::PI {
+        turn -= std::f32::consts::TAU;
+    } else if turn < -std::f32::consts::PI {
+        turn += std::f32::consts::TAU;
+    }
+    Some(Pose {
+        time,
+        x: before.x + (after.x - before.x) * along,
+        y: before.y + (after.y - before.y) * along,
+        rot: before.rot + turn * along,
+    })
+}
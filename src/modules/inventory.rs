@@ -0,0 +1,131 @@
+/*
+Program Details: Piece inventory for limited-drop rounds
+
+In an inventory round (see RoundKind::Inventory in session.rs) the player
+doesn't get as many drops as their credits allow: they get a fixed stock of
+pieces, STARTING_PIECES of each shape (5 balls, 3 squares and 2 triangles to
+begin with). Every drop takes one piece of its shape out of the inventory, a
+shape can't be dropped once none of it is left, and the round is over once
+every piece has been dropped and has landed.
+
+The inventory is shown on the spawn buttons as a small icon of each shape with
+the count left next to it; shape_icon draws those icons, so no image files are
+needed for them.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod inventory;
+
+Then with the other use commands add:
+use crate::modules::inventory::{shape_icon, Inventory};
+
+Then above the loop section to use you would go:
+    let mut inventory = Inventory::new();
+    let mut btn_ball = TextButton::new(820.0, 500.0, 58.0, 40.0, "x5", DARKGRAY, GRAY, 20);
+    btn_ball.with_icon(shape_icon(ShapeKind::Ball));
+
+Then when a piece is dropped:
+    if inventory.take(ShapeKind::Ball) {
+        world.spawn(ShapeKind::Ball, 400.0, 50.0);
+    }
+*/
+use macroquad::prelude::*;
+use crate::modules::physics::ShapeKind;
+use serde::{Deserialize, Serialize};
+
+/// Pieces of each shape an inventory round starts with, in ShapeKind::ALL order (balls, squares, triangles).
+pub const STARTING_PIECES: [u32; 3] = [5, 3, 2];
+// Width and height of the shape icons (pixels; the buttons scale them to their height)
+const ICON_SIZE: u16 = 32;
+
+/// The pieces of each shape left to drop.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Inventory {
+    // Pieces left, in ShapeKind::ALL order
+    counts: [u32; 3],
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    /// A full inventory of STARTING_PIECES.
+    pub fn new() -> Self {
+        Self { counts: STARTING_PIECES }
+    }
+
+    /// Pieces of shape `kind` left.
+    pub fn count(&self, kind: ShapeKind) -> u32 {
+        self.counts[shape_index(kind)]
+    }
+
+    /// Takes one piece of shape `kind` out of the inventory. False (and nothing taken) if there is none left.
+    pub fn take(&mut self, kind: ShapeKind) -> bool {
+        let count = &mut self.counts[shape_index(kind)];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Puts a piece of shape `kind` back, e.g. for a drop that never made it onto the board.
+    pub fn put_back(&mut self, kind: ShapeKind) {
+        self.counts[shape_index(kind)] += 1;
+    }
+
+    /// The first shape (in ShapeKind::ALL order) with pieces left, if any.
+    pub fn first_left(&self) -> Option<ShapeKind> {
+        ShapeKind::ALL.into_iter().find(|kind| self.count(*kind) > 0)
+    }
+
+    /// Pieces left of every shape together.
+    #[allow(unused)]
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    /// True once every piece has been taken.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+}
+
+// Where `kind` is in ShapeKind::ALL (and in the counts)
+fn shape_index(kind: ShapeKind) -> usize {
+    ShapeKind::ALL.iter().position(|shape| *shape == kind).unwrap_or(0)
+}
+
+/// A small white icon of shape `kind` (a circle, square or triangle) on a clear background, to show
+/// next to the count left.
+pub fn shape_icon(kind: ShapeKind) -> Texture2D {
+    let mut image = Image::gen_image_color(ICON_SIZE, ICON_SIZE, BLANK);
+    let size = ICON_SIZE as f32;
+    let center = size / 2.0;
+    // Room left around the shape so it doesn't touch the button's edges
+    let margin = 3.0;
+    for y in 0..ICON_SIZE as u32 {
+        for x in 0..ICON_SIZE as u32 {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let inside = match kind {
+                ShapeKind::Ball => (px - center).powi(2) + (py - center).powi(2) <= (center - margin).powi(2),
+                ShapeKind::Square => (px - center).abs() <= center - margin - 2.0 && (py - center).abs() <= center - margin - 2.0,
+                // Point up, base along the bottom: the sides narrow evenly towards the top
+                ShapeKind::Triangle => {
+                    let height = (py - margin) / (size - margin * 2.0);
+                    (0.0..=1.0).contains(&height) && (px - center).abs() <= height * (center - margin)
+                }
+            };
+            if inside {
+                image.set_pixel(x, y, WHITE);
+            }
+        }
+    }
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
@@ -0,0 +1,204 @@
+/*
+Program Details: Juice: screen shake, hit-pause and squash-and-stretch
+
+Small touches that make the game's big moments feel heavier, all set off by the
+game events of the latest physics steps:
+
+- Screen shake: hard hits (pegs and walls struck faster than IMPACT_SPEED),
+  bumper kicks and breaking pegs add "trauma" that wears off over a moment; the
+  screen moves by the square of it, so small knocks barely show and pile-ups
+  rattle the board. Winning the jackpot (settling in the best-paying bin)
+  shakes it hardest.
+- Hit-pause: winning the jackpot also freezes the game for a split second, so
+  the landing lands. Only the game stops: the UI keeps running.
+- Squash-and-stretch: a piece landing in a bin squashes flat and wobbles back,
+  more the harder it came down. Only how it is drawn changes; its collider
+  doesn't.
+
+How strong each of the three is comes from JuiceSettings, kept with the other
+settings and tuned on the Juice panel (see juice_panel.rs); 0 switches one off.
+The shake wobbles on a clock rather than on random numbers, so it can't change
+the seeded drops.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod juice;
+
+Then with the other use commands add:
+use crate::modules::juice::Juice;
+
+Then above the loop section to use you would go:
+    let mut juice = Juice::new();
+    juice.settings = settings.juice;
+
+Then in the loop, with the game held still during a hit-pause:
+    let sim_dt = if juice.hit_paused() { 0.0 } else { get_frame_time() };
+    world.advance(sim_dt);
+    juice.record_events(&world, world.events());
+    juice.update(get_frame_time(), sim_dt);
+
+And when drawing the board:
+    offset_camera(juice.shake_offset());
+    let squash = juice.squash(handle);   // scale each piece is drawn at, (1, 1) when it isn't squashed
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::modules::events::GameEvent;
+use crate::modules::physics::PhysicsWorld;
+
+// Hits at least this fast shake the screen, adding TRAUMA_PER_SPEED for every pixel/second over it,
+// up to MAX_HIT_TRAUMA for one hit
+const IMPACT_SPEED: f32 = 600.0;
+const TRAUMA_PER_SPEED: f32 = 0.0005;
+const MAX_HIT_TRAUMA: f32 = 0.4;
+// Trauma added by a bumper kick, a peg breaking and the jackpot
+const BUMPER_TRAUMA: f32 = 0.2;
+const BREAK_TRAUMA: f32 = 0.3;
+const JACKPOT_TRAUMA: f32 = 0.7;
+// Trauma worn off per second, how far full trauma moves the screen (pixels) and how fast it
+// wobbles (radians per second)
+const TRAUMA_DECAY: f32 = 1.5;
+const MAX_SHAKE: f32 = 14.0;
+const SHAKE_FREQUENCY: f32 = 45.0;
+// Real seconds the game freezes for when the jackpot is won
+const JACKPOT_PAUSE: f32 = 0.12;
+// Landings at least this fast squash the piece, by SQUASH_PER_SPEED for every pixel/second, up to
+// MAX_SQUASH (a share of its height)
+const SQUASH_SPEED: f32 = 80.0;
+const SQUASH_PER_SPEED: f32 = 0.0004;
+const MAX_SQUASH: f32 = 0.35;
+// Game seconds a squash wobbles for, how fast it springs back and forth (radians per second) and
+// how quickly it dies down (per second)
+const SQUASH_SECONDS: f32 = 0.4;
+const SQUASH_FREQUENCY: f32 = 28.0;
+const SQUASH_DAMPING: f32 = 9.0;
+
+/// How strong each effect is (1.0 as designed, 0.0 off, up to 2.0).
+/// Fields missing from a saved file keep their default value.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct JuiceSettings {
+    /// Screen shake on hard hits, bumpers, breaking pegs and the jackpot
+    pub shake: f32,
+    /// How long the game freezes for when the jackpot is won
+    pub hit_pause: f32,
+    /// How far landing pieces squash and stretch
+    pub squash: f32,
+}
+
+impl Default for JuiceSettings {
+    fn default() -> Self {
+        Self { shake: 1.0, hit_pause: 1.0, squash: 1.0 }
+    }
+}
+
+// A landed piece's squash: how far it went flat and how long ago (game seconds)
+struct Squash {
+    amount: f32,
+    age: f32,
+}
+
+pub struct Juice {
+    /// How strong the effects are (from the settings)
+    pub settings: JuiceSettings,
+    // 0.0 - 1.0; the screen shakes by its square
+    trauma: f32,
+    // Real seconds the shake has been running, which the wobble is worked out from
+    shake_clock: f32,
+    // Real seconds of hit-pause left
+    pause: f32,
+    squashes: HashMap<RigidBodyHandle, Squash>,
+}
+
+impl Default for Juice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Juice {
+    pub fn new() -> Self {
+        Self { settings: JuiceSettings::default(), trauma: 0.0, shake_clock: 0.0, pause: 0.0, squashes: HashMap::new() }
+    }
+
+    /// Reacts to a frame's GameEvents: shakes the screen for hard hits, bumpers, breaking pegs and
+    /// the jackpot, pauses for the jackpot and squashes pieces landing in the bins.
+    pub fn record_events(&mut self, world: &PhysicsWorld, events: &[GameEvent]) {
+        let Some(board) = world.board.as_ref() else {
+            return;
+        };
+        // The jackpot is the best-paying bin; with every bin paying the same there is none to win
+        let (low, high) = board.payouts.iter().fold((f32::MAX, 0.0_f32), |(low, high), &payout| (low.min(payout), high.max(payout)));
+        for event in events {
+            match *event {
+                GameEvent::PegHit { speed, .. } | GameEvent::WallHit { speed, .. } if speed > IMPACT_SPEED => {
+                    self.add_trauma(((speed - IMPACT_SPEED) * TRAUMA_PER_SPEED).min(MAX_HIT_TRAUMA));
+                }
+                GameEvent::BumperHit { .. } => self.add_trauma(BUMPER_TRAUMA),
+                GameEvent::PegBroken { .. } => self.add_trauma(BREAK_TRAUMA),
+                GameEvent::Settled { bin, .. } if high > low && board.payouts[bin] >= high => {
+                    self.add_trauma(JACKPOT_TRAUMA);
+                    self.pause = JACKPOT_PAUSE * self.settings.hit_pause;
+                }
+                _ => {}
+            }
+            // Only hits down in the bins count as landing, not scrapes along the walls on the way down
+            if let GameEvent::WallHit { piece, speed } = *event
+                && speed >= SQUASH_SPEED
+                && world.bodies.get(piece).is_some_and(|body| body.translation().y > board.bins_top())
+            {
+                let amount = (speed * SQUASH_PER_SPEED).min(MAX_SQUASH) * self.settings.squash;
+                self.squashes.insert(piece, Squash { amount, age: 0.0 });
+            }
+        }
+    }
+
+    // Adds to the trauma behind the screen shake, which never goes past 1.0
+    fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    /// True while the game is frozen for a hit-pause; run no game time then.
+    pub fn hit_paused(&self) -> bool {
+        self.pause > 0.0
+    }
+
+    /// Wears the shake and the hit-pause off in real time (`dt`) and winds the squashes down in
+    /// game time (`sim_dt`).
+    pub fn update(&mut self, dt: f32, sim_dt: f32) {
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+        self.shake_clock = if self.trauma > 0.0 { self.shake_clock + dt } else { 0.0 };
+        self.pause = (self.pause - dt).max(0.0);
+        self.squashes.retain(|_, squash| {
+            squash.age += sim_dt;
+            squash.age < SQUASH_SECONDS
+        });
+    }
+
+    /// How far to move the view this frame for the screen shake (zero when nothing is shaking it).
+    pub fn shake_offset(&self) -> Vec2 {
+        let strength = MAX_SHAKE * self.settings.shake * self.trauma * self.trauma;
+        let phase = self.shake_clock * SHAKE_FREQUENCY;
+        // Two wobbles at unrelated rates, so the screen doesn't just swing back and forth along a line
+        vec2(phase.sin(), (phase * 1.37 + 1.0).sin()) * strength
+    }
+
+    /// Scale to draw `piece` at: wider and flatter just after it lands, springing back to (1, 1).
+    pub fn squash(&self, piece: RigidBodyHandle) -> Vec2 {
+        let Some(squash) = self.squashes.get(&piece) else {
+            return Vec2::ONE;
+        };
+        let flat = squash.amount * (-squash.age * SQUASH_DAMPING).exp() * (squash.age * SQUASH_FREQUENCY).cos();
+        vec2(1.0 + flat, 1.0 - flat)
+    }
+
+    /// Stops every effect, e.g. when the board is rebuilt.
+    pub fn clear(&mut self) {
+        self.trauma = 0.0;
+        self.pause = 0.0;
+        self.squashes.clear();
+    }
+}
@@ -0,0 +1,147 @@
+/*
+Program Details: Juice panel for tuning the screen shake, hit-pause and squash
+
+A popup (opened from the settings panel) with one slider per effect in
+juice.rs: how hard the screen shakes, how long the game freezes when the
+jackpot is won, and how far landing pieces squash. 1.0 is the effect as
+designed and 0.0 switches it off. Changes are copied into the JuiceSettings
+right away so they can be tried on the next drop. "Back" closes the panel; the
+juice settings are saved with the rest of the settings.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod juice_panel;
+
+Then with the other use commands add:
+use crate::modules::juice_panel::JuicePanel;
+
+Then above the loop section to use you would go:
+    let mut juice_panel = JuicePanel::new(200.0, 150.0);
+
+To show it (e.g. from a "Juice" button):
+    juice_panel.open(&settings.juice);
+
+Then in the loop, after the board has been drawn so the panel sits on top:
+if juice_panel.update(&mut settings.juice) {
+    juice.settings = settings.juice;
+}
+*/
+use macroquad::prelude::*;
+use crate::modules::juice::JuiceSettings;
+use crate::modules::label::Label;
+use crate::modules::slider::Slider;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 480.0;
+const SLIDER_WIDTH: f32 = 320.0;
+// Vertical distance between two sliders
+const SLIDER_SPACING: f32 = 62.0;
+// Strongest each effect can be turned up to
+const MAX_INTENSITY: f32 = 2.0;
+
+pub struct JuicePanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    sld_shake: Slider,
+    sld_hit_pause: Slider,
+    sld_squash: Slider,
+    btn_back: TextButton,
+    btn_defaults: TextButton,
+    lbl_title: Label,
+    lbl_hint: Label,
+}
+
+impl JuicePanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let slider_x = x + (PANEL_WIDTH - SLIDER_WIDTH) / 2.0;
+        let first_y = y + 100.0;
+        let defaults = JuiceSettings::default();
+        // Parameters: x_pos, y_pos, width, min, max, starting value
+        let mut sld_shake = Slider::new(slider_x, first_y, SLIDER_WIDTH, 0.0, MAX_INTENSITY, defaults.shake);
+        sld_shake.with_step(0.1).with_label("Screen shake", 20).with_decimals(1);
+        let mut sld_hit_pause = Slider::new(slider_x, first_y + SLIDER_SPACING, SLIDER_WIDTH, 0.0, MAX_INTENSITY, defaults.hit_pause);
+        sld_hit_pause.with_step(0.1).with_label("Jackpot hit-pause", 20).with_decimals(1);
+        let mut sld_squash = Slider::new(slider_x, first_y + SLIDER_SPACING * 2.0, SLIDER_WIDTH, 0.0, MAX_INTENSITY, defaults.squash);
+        sld_squash.with_step(0.1).with_label("Squash and stretch", 20).with_decimals(1);
+
+        let buttons_y = y + PANEL_HEIGHT - 80.0;
+        let btn_back = TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "Back", DARKGREEN, GREEN, 25);
+        let btn_defaults = TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Defaults", DARKGRAY, GRAY, 25);
+
+        let mut lbl_title = Label::new("Juice", x + 30.0, y + 45.0, 35);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_hint = Label::new(
+            "1.0 is each effect as designed, 0.0 switches it off.",
+            slider_x,
+            first_y + SLIDER_SPACING * 2.0 + 50.0,
+            18,
+        );
+        lbl_hint.with_colors(LIGHTGRAY, None).with_wrap(SLIDER_WIDTH);
+
+        Self {
+            x,
+            y,
+            visible: false,
+            sld_shake,
+            sld_hit_pause,
+            sld_squash,
+            btn_back,
+            btn_defaults,
+            lbl_title,
+            lbl_hint,
+        }
+    }
+
+    /// Shows the panel with the sliders set to the current juice settings.
+    pub fn open(&mut self, juice: &JuiceSettings) {
+        self.sync(juice);
+        self.visible = true;
+    }
+
+    /// Draws the panel and copies any slider changes into `juice`. "Back" hides the panel.
+    /// Returns true when a setting changed this frame.
+    pub fn update(&mut self, juice: &mut JuiceSettings) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+        self.lbl_hint.draw();
+
+        let mut changed = false;
+        if self.sld_shake.update() {
+            juice.shake = self.sld_shake.value();
+            changed = true;
+        }
+        if self.sld_hit_pause.update() {
+            juice.hit_pause = self.sld_hit_pause.value();
+            changed = true;
+        }
+        if self.sld_squash.update() {
+            juice.squash = self.sld_squash.value();
+            changed = true;
+        }
+
+        if self.btn_defaults.click() {
+            *juice = JuiceSettings::default();
+            self.sync(juice);
+            changed = true;
+        }
+        if self.btn_back.click() {
+            self.visible = false;
+        }
+        changed
+    }
+
+    // Moves every slider to the matching setting
+    fn sync(&mut self, juice: &JuiceSettings) {
+        self.sld_shake.set_value(juice.shake);
+        self.sld_hit_pause.set_value(juice.hit_pause);
+        self.sld_squash.set_value(juice.squash);
+    }
+}
@@ -36,6 +36,29 @@ Where the values are width and height in pixels.
 You can also set the text alignment within a fixed-size label with:
      lbl_out.with_alignment(modules::label::TextAlign::Center);
 Options are modules::label::TextAlign::Left, objects::label::TextAlign::Center, and objects::label::TextAlign::Right.
+Without a fixed width the lines are aligned against the widest one.
+The lines can be placed vertically within a fixed-size label with:
+     lbl_out.with_vertical_alignment(modules::label::VerticalAlign::Middle);
+Options are VerticalAlign::Top (the default), VerticalAlign::Middle and VerticalAlign::Bottom.
+
+To wrap long text onto more lines so no line is wider than a width in pixels:
+     lbl_out.with_wrap(200.0);
+Lines break between words; a single word wider than the width gets a line of its own.
+
+To draw a drop shadow behind the text, offset by x and y pixels:
+     lbl_out.with_shadow(BLACK, 2.0, 2.0);
+To draw an outline around every letter:
+     lbl_out.with_outline(BLACK, 1.5);
+
+To keep the text readable when the window is much smaller than the virtual resolution:
+     lbl_out.with_min_screen_size(12.0);
+The font grows so the text is never drawn smaller than 12 real screen pixels, and the
+letters are rendered at the screen's resolution so they stay sharp when scaled.
+
+Labels are drawn at the theme's text scale (see theme.rs). To keep a label that has to fit
+in a narrow space (e.g. the right-hand column) from spilling out when the text is scaled up:
+     lbl_out.with_max_width(180.0);
+The font shrinks until the widest line is no wider than 180 pixels.
 
 To access the label's position:
      let x = lbl_out.get_x();
@@ -80,6 +103,8 @@ Then in the loop you would use:
     lbl_out.draw();
 */
 use macroquad::prelude::*;
+use crate::modules::scale::screen_scale;
+use crate::modules::theme::{scaled_font_size, theme};
 
 pub struct Label {
     text: String,
@@ -90,6 +115,7 @@ pub struct Label {
     background: Option<Color>,
     line_spacing: f32,
     font: Option<Font>, // Store the font directly since Font is Clone
+    padding: f32,       // Space between the text and the edge of the background (left and right)
     corner_radius: f32, // For rounded corners
     border: bool,       // Whether to draw a border
     border_color: Color, // Color of the border
@@ -100,7 +126,15 @@ pub struct Label {
     fixed_width: Option<f32>,
     fixed_height: Option<f32>,
     text_align: TextAlign,
-    
+    vertical_align: VerticalAlign,
+
+    // Wrapping, shadow, outline and screen-size scaling (all off by default)
+    wrap_width: Option<f32>,
+    shadow: Option<(Color, Vec2)>,
+    outline: Option<(Color, f32)>,
+    min_screen_size: Option<f32>,
+    max_width: Option<f32>,
+
     // Cached values for performance
     cached_lines: Vec<String>,
     cached_line_dimensions: Vec<TextDimensions>,
@@ -116,9 +150,18 @@ pub enum TextAlign {
     Right,
 }
 
+// Enum for placing the lines vertically within a fixed-height label
+#[allow(unused)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
 impl Label {
     // Constructor using x and y separately
     pub fn new<T: Into<String>>(text: T, x: f32, y: f32, font_size: u16) -> Self {
+        let theme = theme();
         let mut label = Self {
             text: text.into(),
             x,
@@ -127,7 +170,8 @@ impl Label {
             foreground: BLACK, // Default to black
             background: None,  // No background by default
             line_spacing: 1.2,
-            font: None,        // Default to None (use system font)
+            font: theme.font,  // The theme's font (None uses the system font)
+            padding: theme.padding, // Space around the text inside the background
             corner_radius: 0.0, // Default to no rounded corners
             border: false,      // Default to no border
             border_color: BLACK, // Default border color
@@ -136,6 +180,12 @@ impl Label {
             fixed_width: None, // No fixed width by default
             fixed_height: None, // No fixed height by default
             text_align: TextAlign::Left, // Default to left alignment
+            vertical_align: VerticalAlign::Top, // Default to the top of the label
+            wrap_width: None,   // No wrapping by default
+            shadow: None,
+            outline: None,
+            min_screen_size: None, // Drawn at its font size whatever the window size
+            max_width: None,    // As wide as the text at the text scale
             cached_lines: Vec::new(),
             cached_line_dimensions: Vec::new(),
             cached_max_width: 0.0,
@@ -150,34 +200,75 @@ impl Label {
     
     // Calculate and cache text dimensions
     fn calculate_text_dimensions(&mut self) {
-        // Split text into lines and store for later use
-        self.cached_lines = self.text.split('\n').map(String::from).collect();
+        // Split (and wrap) text into lines and store for later use
+        let (lines, dimensions) = self.layout(self.font_size);
+        self.cached_lines = lines;
+        self.cached_line_dimensions = dimensions;
         let line_height = self.font_size as f32 * self.line_spacing;
-        
-        // Clear previous cached values
-        self.cached_line_dimensions.clear();
+
+        // Only update max_width if we don't have a fixed width
         self.cached_max_width = 0.0;
-        
-        // Calculate dimensions for each line
-        for line in &self.cached_lines {
-            let dimensions = match &self.font {
-                Some(font) => measure_text(line, Some(font), self.font_size, 1.0),
-                None => measure_text(line, None, self.font_size, 1.0),
-            };
-            self.cached_line_dimensions.push(dimensions);
-            
-            // Only update max_width if we don't have a fixed width
-            if self.fixed_width.is_none() {
-                self.cached_max_width = self.cached_max_width.max(dimensions.width);
-            }
+        if self.fixed_width.is_none() {
+            self.cached_max_width = self.cached_line_dimensions.iter().map(|d| d.width).fold(0.0, f32::max);
         }
-        
+
         // Calculate total height (only if we don't have fixed height)
         if self.fixed_height.is_none() {
             self.cached_total_height = self.cached_lines.len() as f32 * line_height;
         }
     }
 
+    // Splits the text into the lines drawn at the given font size (wrapping them if a wrap
+    // width is set) and measures each line
+    fn layout(&self, font_size: u16) -> (Vec<String>, Vec<TextDimensions>) {
+        let mut lines = Vec::new();
+        for paragraph in self.text.split('\n') {
+            match self.wrap_width {
+                Some(max_width) => lines.extend(self.wrap(paragraph, max_width, font_size)),
+                None => lines.push(paragraph.to_string()),
+            }
+        }
+        let dimensions = lines.iter().map(|line| self.measure(line, font_size)).collect();
+        (lines, dimensions)
+    }
+
+    // Breaks one paragraph between words so no line is wider than max_width
+    fn wrap(&self, paragraph: &str, max_width: f32, font_size: u16) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if !line.is_empty() && self.measure(&candidate, font_size).width > max_width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+        lines
+    }
+
+    fn measure(&self, text: &str, font_size: u16) -> TextDimensions {
+        measure_text(text, self.font.as_ref(), font_size, 1.0)
+    }
+
+    // Font size the label is drawn at: its own at the theme's text scale, shrunk to the maximum
+    // width, or bigger if that would be smaller than the minimum screen size at the current window size
+    fn screen_font_size(&self) -> u16 {
+        let mut font_size = scaled_font_size(self.font_size);
+        if let Some(max_width) = self.max_width {
+            // Text width grows in step with the font size, so the cached widths say how big it may get
+            let widest = self.cached_line_dimensions.iter().map(|d| d.width).fold(0.0, f32::max);
+            if widest > 0.0 {
+                font_size = font_size.min(((self.font_size as f32 * max_width / widest).floor() as u16).max(1));
+            }
+        }
+        match self.min_screen_size {
+            Some(min_size) => font_size.max((min_size / screen_scale()).ceil() as u16),
+            None => font_size,
+        }
+    }
+
     // Method to set foreground and background colors
     #[allow(unused)]
     pub fn with_colors(&mut self, foreground: Color, background: Option<Color>) -> &mut Self {
@@ -233,6 +324,49 @@ impl Label {
         self
     }
 
+    // Method to place the lines vertically (only applies when using fixed height)
+    #[allow(unused)]
+    pub fn with_vertical_alignment(&mut self, alignment: VerticalAlign) -> &mut Self {
+        self.vertical_align = alignment;
+        self
+    }
+
+    // Method to wrap the text so no line is wider than max_width pixels
+    #[allow(unused)]
+    pub fn with_wrap(&mut self, max_width: f32) -> &mut Self {
+        self.wrap_width = Some(max_width);
+        self.calculate_text_dimensions();
+        self
+    }
+
+    // Method to draw a drop shadow offset by (x, y) behind the text
+    #[allow(unused)]
+    pub fn with_shadow(&mut self, color: Color, x: f32, y: f32) -> &mut Self {
+        self.shadow = Some((color, vec2(x, y)));
+        self
+    }
+
+    // Method to draw an outline of the given thickness around the letters
+    #[allow(unused)]
+    pub fn with_outline(&mut self, color: Color, thickness: f32) -> &mut Self {
+        self.outline = Some((color, thickness));
+        self
+    }
+
+    // Method to keep the text at least min_size real screen pixels tall however small the window is
+    #[allow(unused)]
+    pub fn with_min_screen_size(&mut self, min_size: f32) -> &mut Self {
+        self.min_screen_size = Some(min_size);
+        self
+    }
+
+    // Shrinks the font whenever the widest line would be wider than max_width
+    #[allow(unused)]
+    pub fn with_max_width(&mut self, max_width: f32) -> &mut Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
     // Method to set text - now accepts both String and &str
     #[allow(unused)]
     pub fn set_text<T: Into<String>>(&mut self, new_text: T) -> &mut Self {
@@ -250,7 +384,7 @@ impl Label {
     pub fn get_width(&self) -> Option<f32> {
         match self.fixed_width {
             Some(width) => Some(width),
-            None => Some(self.cached_max_width + 10.0) // Same padding as in draw method
+            None => Some(self.cached_max_width + self.padding * 2.0) // Same padding as in draw method
         }
     }
     
@@ -338,16 +472,27 @@ impl Label {
         if !self.visible {
             return;
         }
-        
-        let line_height = self.font_size as f32 * self.line_spacing;
-        
+
+        // A different text scale or a window too small for the font size re-lays the text out
+        let font_size = self.screen_font_size();
+        let relaid;
+        let (lines, line_dimensions) = if font_size == self.font_size {
+            (&self.cached_lines, &self.cached_line_dimensions)
+        } else {
+            relaid = self.layout(font_size);
+            (&relaid.0, &relaid.1)
+        };
+        let line_height = font_size as f32 * self.line_spacing;
+        let content_width = line_dimensions.iter().map(|d| d.width).fold(0.0, f32::max);
+        let content_height = lines.len() as f32 * line_height;
+
         // Determine width and height (using fixed values if set, otherwise use content size)
-        let width = self.fixed_width.unwrap_or(self.cached_max_width + 10.0);
-        let height = self.fixed_height.unwrap_or(self.cached_total_height);
-        
+        let width = self.fixed_width.unwrap_or(content_width + self.padding * 2.0);
+        let height = self.fixed_height.unwrap_or(content_height);
+
         // Calculate positions for all elements
-        let bg_x = self.x - 5.0;
-        let bg_y = self.y - self.font_size as f32;
+        let bg_x = self.x - self.padding;
+        let bg_y = self.y - font_size as f32;
         
         // Draw background first
         if let Some(bg) = self.background {
@@ -390,43 +535,59 @@ impl Label {
             }
         }
 
+        // Move the block of lines down inside a fixed-height label
+        let y_offset = match (self.fixed_height, &self.vertical_align) {
+            (Some(fixed_height), VerticalAlign::Middle) => (fixed_height - content_height) / 2.0,
+            (Some(fixed_height), VerticalAlign::Bottom) => fixed_height - content_height,
+            _ => 0.0,
+        };
+
         // Draw each line of text
-        for (i, (line, dimensions)) in self.cached_lines.iter().zip(self.cached_line_dimensions.iter()).enumerate() {
-            let y = self.y + i as f32 * line_height;
+        for (i, (line, dimensions)) in lines.iter().zip(line_dimensions.iter()).enumerate() {
+            let y = self.y + y_offset + i as f32 * line_height;
             
-            // Calculate x position based on alignment (if fixed width is set)
-            let x = if let Some(fixed_width) = self.fixed_width {
-                match self.text_align {
-                    TextAlign::Left => self.x,
-                    TextAlign::Center => self.x + (fixed_width / 2.0) - (dimensions.width / 2.0),
-                    TextAlign::Right => self.x + fixed_width - dimensions.width - 10.0, // 10.0 for padding
-                }
-            } else {
-                self.x
+            // Calculate x position based on alignment (within the fixed width, or the widest line)
+            let x = match self.text_align {
+                TextAlign::Left => self.x,
+                TextAlign::Center => self.x + (width / 2.0) - (dimensions.width / 2.0),
+                TextAlign::Right => self.x + width - dimensions.width - self.padding * 2.0,
             };
-            
-            // Draw the text - use draw_text_ex if we have a custom font
-            match &self.font {
-                Some(font) => {
-                    draw_text_ex(
-                        line,
-                        x,
-                        y,
-                        TextParams {
-                            font: Some(font),
-                            font_size: self.font_size,
-                            color: self.foreground,
-                            ..Default::default()
-                        },
-                    );
-                },
-                None => {
-                    // Use the default draw_text function
-                    draw_text(line, x, y, self.font_size as f32, self.foreground);
+
+            if let Some((color, offset)) = self.shadow {
+                self.draw_text_line(line, x + offset.x, y + offset.y, font_size, color);
+            }
+            if let Some((color, thickness)) = self.outline {
+                for (dx, dy) in [(-1.0, -1.0), (0.0, -1.0), (1.0, -1.0), (-1.0, 0.0), (1.0, 0.0), (-1.0, 1.0), (0.0, 1.0), (1.0, 1.0)] {
+                    self.draw_text_line(line, x + dx * thickness, y + dy * thickness, font_size, color);
                 }
             }
+            self.draw_text_line(line, x, y, font_size, self.foreground);
         }
     }
+
+    // Draws one line of text. With a minimum screen size set the letters are rasterized at the
+    // size they take up on screen, so they aren't blurred by the scaling
+    fn draw_text_line(&self, line: &str, x: f32, y: f32, font_size: u16, color: Color) {
+        let (raster_size, font_scale) = match self.min_screen_size {
+            Some(_) => {
+                let raster_size = ((font_size as f32 * screen_scale()).round() as u16).max(1);
+                (raster_size, font_size as f32 / raster_size as f32)
+            }
+            None => (font_size, 1.0),
+        };
+        draw_text_ex(
+            line,
+            x,
+            y,
+            TextParams {
+                font: self.font.as_ref(),
+                font_size: raster_size,
+                font_scale,
+                color,
+                ..Default::default()
+            },
+        );
+    }
 }
 
 // Function to draw a rectangle with rounded corners - optimized version
@@ -468,6 +629,7 @@ fn draw_rectangle_border(x: f32, y: f32, w: f32, h: f32, thickness: f32, color:
 
 // New function to draw rounded rectangle borders with consistent thickness
 #[allow(unused)]
+#[allow(clippy::too_many_arguments)]
 fn draw_round_rect_border(x: f32, y: f32, w: f32, h: f32, radius: f32, thickness: f32, color: Color, bg_color: Color) {
     if radius <= 0.0 {
         // Use our new rectangle border function for non-rounded corners
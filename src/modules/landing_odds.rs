@@ -0,0 +1,234 @@
+/*
+Program Details: Landing odds of each bin for the drop column being aimed at
+
+The drop range is split into ODDS_COLUMNS columns. For each column a batch of
+DROPS_PER_COLUMN balls, spread evenly across it, is dropped on a headless copy
+of the board (see PhysicsWorld::clone_board), and the share of them landing in
+each bin is the odds shown for that column. The balls of a batch pass through
+each other, so they fall exactly as a lone ball would; balls that never land
+(stuck, or lost off the board) count towards no bin, so a column's odds can add
+up to less than 100%.
+
+While aiming, the column under the marker is shaded and the odds of each bin are
+written at the bottom of the bins, the likeliest one in yellow. The batches are
+run a few physics steps a frame, the aimed-at column first and then the rest in
+the background, so moving the aim usually shows odds that are already worked
+out. Call reset() whenever the board (or the physics settings) change; the odds
+are also worked out again when the ball variant changes. On boards with moving
+pegs they are a snapshot of where the pegs were when the batch started.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod landing_odds;
+
+Then with the other use commands add:
+use crate::modules::landing_odds::LandingOdds;
+
+Then above the loop section to use you would go:
+    let mut odds = LandingOdds::new();
+
+Then in the loop you would use (with the marker's x while aiming, None otherwise):
+    odds.update(&world, aiming.then_some(x));
+    // ... after the board is drawn:
+    odds.draw(&board);
+
+And when the board changes:
+    odds.reset();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::{Group, InteractionGroups};
+use crate::modules::aim::{DropAimer, DROP_Y};
+use crate::modules::board::BoardConfig;
+use crate::modules::events::GameEvent;
+use crate::modules::physics::{BallVariant, PhysicsWorld, ShapeKind};
+
+// Columns the drop range is split into
+pub const ODDS_COLUMNS: usize = 12;
+// Balls dropped per column, spread evenly across it
+const DROPS_PER_COLUMN: usize = 60;
+// Physics steps run per frame, so working the odds out doesn't drag the frame rate down
+const STEPS_PER_FRAME: usize = 40;
+// Longest a batch's balls get to land, in seconds of game time
+const MAX_SECONDS: f32 = 20.0;
+const FONT_SIZE: f32 = 16.0;
+const COLUMN_SHADE: Color = Color::new(1.0, 1.0, 1.0, 0.07);
+
+// One column's balls falling through a copy of the board
+struct Batch {
+    column: usize,
+    world: PhysicsWorld,
+    // Balls landed in each bin so far, and balls landed or lost
+    landed: Vec<u32>,
+    finished: usize,
+    steps: usize,
+    max_steps: usize,
+}
+
+impl Batch {
+    fn new(world: &PhysicsWorld, board: &BoardConfig, column: usize) -> Self {
+        let mut copy = world.clone_board();
+        let (left, right) = column_range(board, column);
+        // In a group of their own that doesn't collide with itself: pegs, walls and bins are
+        // in every group, so they still collide with those
+        let groups = InteractionGroups::new(Group::GROUP_2, !Group::GROUP_2);
+        for drop in 0..DROPS_PER_COLUMN {
+            let x = left + (drop as f32 + 0.5) * (right - left) / DROPS_PER_COLUMN as f32;
+            let piece = copy.spawn(ShapeKind::Ball, x, DROP_Y);
+            // CCD is most of the cost of a step, and the odd miss hardly moves the odds
+            copy.bodies[piece].enable_ccd(false);
+            for collider in copy.bodies[piece].colliders().to_vec() {
+                copy.colliders[collider].set_collision_groups(groups);
+            }
+        }
+        let max_steps = (MAX_SECONDS / copy.integration_params.dt) as usize;
+        Self { column, world: copy, landed: vec![0; board.bins.count], finished: 0, steps: 0, max_steps }
+    }
+
+    // Runs up to `steps` physics steps; returns the share of the balls in each bin once every
+    // ball has landed (or been lost) or MAX_SECONDS have passed
+    fn advance(&mut self, steps: usize) -> Option<Vec<f32>> {
+        for _ in 0..steps {
+            let dt = self.world.integration_params.dt;
+            self.world.advance(dt);
+            self.steps += 1;
+            let mut settled = Vec::new();
+            for event in self.world.events() {
+                match *event {
+                    GameEvent::Settled { piece, bin } => {
+                        settled.push(piece);
+                        if let Some(count) = self.landed.get_mut(bin) {
+                            *count += 1;
+                        }
+                    }
+                    // Pieces held by a sticky pad never reach a bin
+                    GameEvent::PieceLost { .. } | GameEvent::StickyPayout { .. } => self.finished += 1,
+                    _ => {}
+                }
+            }
+            // Landed balls are taken out so they don't pile up in the bins
+            self.finished += settled.len();
+            for piece in settled {
+                self.world.remove_body(piece);
+            }
+            if self.finished >= DROPS_PER_COLUMN || self.steps >= self.max_steps {
+                return Some(self.landed.iter().map(|count| *count as f32 / DROPS_PER_COLUMN as f32).collect());
+            }
+        }
+        None
+    }
+}
+
+/// The x range of drop column `column` (0 = leftmost) on `board`.
+pub fn column_range(board: &BoardConfig, column: usize) -> (f32, f32) {
+    let (left, right) = DropAimer::drop_range(board);
+    let width = (right - left) / ODDS_COLUMNS as f32;
+    (left + column as f32 * width, left + (column + 1) as f32 * width)
+}
+
+/// The drop column `x` falls in on `board`.
+pub fn column_at(board: &BoardConfig, x: f32) -> usize {
+    let (left, right) = DropAimer::drop_range(board);
+    let share = (x - left) / (right - left).max(1.0);
+    ((share * ODDS_COLUMNS as f32).max(0.0) as usize).min(ODDS_COLUMNS - 1)
+}
+
+pub struct LandingOdds {
+    // Share of the balls landing in each bin, for each column worked out so far
+    columns: Vec<Option<Vec<f32>>>,
+    batch: Option<Batch>,
+    // Ball variant the odds are for
+    variant: Option<BallVariant>,
+    // Column being aimed at, if any
+    selected: Option<usize>,
+}
+
+impl Default for LandingOdds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LandingOdds {
+    pub fn new() -> Self {
+        Self { columns: vec![None; ODDS_COLUMNS], batch: None, variant: None, selected: None }
+    }
+
+    /// Forgets every column's odds (and the batch being run), so they are worked out again.
+    pub fn reset(&mut self) {
+        self.columns = vec![None; ODDS_COLUMNS];
+        self.batch = None;
+    }
+
+    /// The share of balls dropped in `column` that land in each bin, once worked out.
+    pub fn odds(&self, column: usize) -> Option<&[f32]> {
+        self.columns.get(column)?.as_deref()
+    }
+
+    /// Selects the column `aim` is in (None when not aiming) and runs this frame's physics steps:
+    /// on the selected column if its odds aren't known yet, otherwise on the next unknown column.
+    pub fn update(&mut self, world: &PhysicsWorld, aim: Option<f32>) {
+        let Some(board) = world.board.as_ref() else {
+            return;
+        };
+        if self.variant != Some(world.ball_variant) {
+            self.reset();
+            self.variant = Some(world.ball_variant);
+        }
+        self.selected = aim.map(|x| column_at(board, x));
+
+        // The aimed-at column jumps the queue
+        let wanted = self.selected.filter(|column| self.columns[*column].is_none());
+        if let Some(column) = wanted
+            && self.batch.as_ref().is_some_and(|batch| batch.column != column)
+        {
+            self.batch = None;
+        }
+        if self.batch.is_none() {
+            let next = wanted.or_else(|| self.columns.iter().position(Option::is_none));
+            self.batch = next.map(|column| Batch::new(world, board, column));
+        }
+        if let Some(batch) = self.batch.as_mut()
+            && let Some(odds) = batch.advance(STEPS_PER_FRAME)
+        {
+            self.columns[batch.column] = Some(odds);
+            self.batch = None;
+        }
+    }
+
+    /// Shades the aimed-at column and writes each bin's odds at the bottom of the bins.
+    pub fn draw(&self, board: &BoardConfig) {
+        let Some(column) = self.selected else {
+            return;
+        };
+        let (left, right) = column_range(board, column);
+        let zone = DropAimer::drop_zone(board);
+        draw_rectangle(left, zone.y, right - left, zone.h, COLUMN_SHADE);
+
+        let y = board.ground_top() - 6.0;
+        let Some(odds) = self.odds(column) else {
+            let text = "Working out the odds...";
+            let width = measure_text(text, None, FONT_SIZE as u16, 1.0).width;
+            draw_text(text, board.ground.x - width / 2.0, y, FONT_SIZE, LIGHTGRAY);
+            return;
+        };
+        let likeliest = odds.iter().copied().fold(0.0, f32::max);
+        for (bin, share) in odds.iter().enumerate() {
+            let text = percentage(*share);
+            let width = measure_text(&text, None, FONT_SIZE as u16, 1.0).width;
+            let center = board.bins_left() + board.bin_width() * (bin as f32 + 0.5);
+            let color = if *share > 0.0 && *share >= likeliest { YELLOW } else { SKYBLUE };
+            draw_text(&text, center - width / 2.0, y, FONT_SIZE, color);
+        }
+    }
+}
+
+// A share as a short percentage: whole percents, with a decimal under 10%
+fn percentage(share: f32) -> String {
+    let percent = share * 100.0;
+    if percent == 0.0 || percent >= 10.0 {
+        format!("{:.0}%", percent)
+    } else {
+        format!("{:.1}%", percent)
+    }
+}
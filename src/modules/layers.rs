@@ -0,0 +1,96 @@
+/*
+Program Details: Collision layers for the physics world and for sprites
+
+Every collider in the physics world, and any sprite that wants one, sits on one
+Layers entry. Each layer lists the layers it collides with, and two things only
+collide (or report a collision) if each is on a layer the other one collides
+with:
+
+    Board        the board's bodies: collides with the board, pieces and ghosts
+    Pieces       dropped pieces: collide with the board, pieces, sensors and powerups
+    Ghosts       pieces dropped to try out a drop (e.g. the trajectory preview):
+                 collide with the board and sensors, never with live pieces
+    Sensors      multiplier zones: see pieces and ghosts pass through
+    Decorations  things that are only drawn: collide with nothing
+    Powerups     collectables: only pieces pick them up
+
+For Rapier the layer becomes the collider's CollisionGroups; the physics world
+puts everything it builds on its layer (see PhysicsWorld::set_layer to move a
+body to another one). For sprites the Collidable trait has a layer() method
+(see collision.rs); a sprite without a layer collides with everything, as
+before.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod layers;
+
+Then with the other use commands add:
+use crate::modules::layers::Layers;
+
+To give a Rapier collider a layer:
+    let collider = ColliderBuilder::ball(5.0).collision_groups(Layers::Powerups.groups()).build();
+
+To check two layers by hand:
+    if Layers::Pieces.interacts_with(Layers::Powerups) { ... }
+*/
+use rapier2d::prelude::{Group, InteractionGroups};
+use crate::modules::entity_registry::EntityKind;
+
+/// What a collider or sprite is, as far as deciding what it collides with goes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Layers {
+    /// Pegs, walls, bins, funnels, the ground, sticky pads, chains and blocks
+    Board,
+    /// Pieces dropped into play
+    Pieces,
+    /// Pieces dropped only to try something out, which must not disturb live pieces
+    Ghosts,
+    /// Areas that report what passes through them
+    Sensors,
+    /// Things that are only drawn
+    Decorations,
+    /// Collectables that only pieces can pick up
+    Powerups,
+}
+
+impl Layers {
+    pub const ALL: [Layers; 6] = [Layers::Board, Layers::Pieces, Layers::Ghosts, Layers::Sensors, Layers::Decorations, Layers::Powerups];
+
+    /// The layer's own bit.
+    pub fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    /// The layers this one collides with, as bits.
+    pub fn filter(self) -> u32 {
+        let layers: &[Layers] = match self {
+            Layers::Board => &[Layers::Board, Layers::Pieces, Layers::Ghosts],
+            Layers::Pieces => &[Layers::Board, Layers::Pieces, Layers::Sensors, Layers::Powerups],
+            Layers::Ghosts => &[Layers::Board, Layers::Sensors],
+            Layers::Sensors => &[Layers::Pieces, Layers::Ghosts],
+            Layers::Decorations => &[],
+            Layers::Powerups => &[Layers::Pieces],
+        };
+        layers.iter().fold(0, |bits, layer| bits | layer.bit())
+    }
+
+    /// True if things on this layer and on `other` collide.
+    pub fn interacts_with(self, other: Layers) -> bool {
+        self.filter() & other.bit() != 0 && other.filter() & self.bit() != 0
+    }
+
+    /// The layer as Rapier collision groups.
+    pub fn groups(self) -> InteractionGroups {
+        InteractionGroups::new(Group::from_bits_truncate(self.bit()), Group::from_bits_truncate(self.filter()))
+    }
+
+    /// The layer the physics world puts a body of `kind` on.
+    pub fn of(kind: EntityKind) -> Layers {
+        match kind {
+            EntityKind::Piece(_) => Layers::Pieces,
+            EntityKind::Zone => Layers::Sensors,
+            _ => Layers::Board,
+        }
+    }
+}
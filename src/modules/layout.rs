@@ -0,0 +1,132 @@
+/*
+Program Details: Anchored layout for the HUD
+
+Instead of giving every widget a fixed pixel position, a Layout starts from an
+anchor on the screen (the top-left corner, the top center, the right-hand
+column or the bottom-left corner) and hands out positions for a row or a column
+of widgets, one after another with the same spacing between them. Anchors are
+worked out from the current virtual resolution, so laying the HUD out again
+after the resolution changes moves everything to match.
+
+A column can also reserve a line for a row of its own (e.g. two buttons side by
+side in the right-hand column) with row_in.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod layout;
+
+Then with the other use commands add:
+use crate::modules::layout::{Anchor, Layout};
+
+Then where the widgets are placed (at start-up, and again whenever the resolution changes):
+    let mut column = Layout::column(Anchor::RightColumn, vec2(0.0, 10.0), 10.0);
+    let pos = column.next(180.0, 50.0);
+    btn_settings.update_position(pos.x, pos.y, None, None);
+    let mut buttons = column.row_in(50.0, 20.0);
+    let pos = buttons.next(80.0, 50.0);
+    btn_bet_down.update_position(pos.x, pos.y, None, None);
+
+To check whether the resolution has changed since the last layout:
+    if layout_resolution != current_resolution() { ... lay everything out again ... }
+*/
+use macroquad::prelude::*;
+use crate::modules::scale::VIRTUAL_RESOLUTION;
+
+/// Width of the right-hand column, including the margin to the right edge of the screen.
+pub const RIGHT_COLUMN_WIDTH: f32 = 204.0;
+
+/// The point of the screen a layout is placed from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    #[allow(unused)]
+    TopLeft,
+    #[allow(unused)]
+    TopCenter,
+    /// Top-left corner of the column RIGHT_COLUMN_WIDTH wide along the right edge
+    RightColumn,
+    BottomLeft,
+}
+
+impl Anchor {
+    /// Where the anchor is at the current virtual resolution.
+    pub fn point(self) -> Vec2 {
+        let (width, height) = current_resolution();
+        match self {
+            Anchor::TopLeft => vec2(0.0, 0.0),
+            Anchor::TopCenter => vec2(width / 2.0, 0.0),
+            Anchor::RightColumn => vec2(width - RIGHT_COLUMN_WIDTH, 0.0),
+            Anchor::BottomLeft => vec2(0.0, height),
+        }
+    }
+}
+
+/// The virtual resolution the screen is laid out in right now.
+pub fn current_resolution() -> (f32, f32) {
+    VIRTUAL_RESOLUTION.with(|resolution| *resolution.borrow())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Row,
+    Column,
+}
+
+/// Hands out positions for a row or column of widgets, `spacing` apart.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    // Top-left corner of the first widget
+    origin: Vec2,
+    direction: Direction,
+    spacing: f32,
+    // Distance along the row or column to the next widget
+    cursor: f32,
+}
+
+impl Layout {
+    /// A column going down from `offset` past the anchor.
+    pub fn column(anchor: Anchor, offset: Vec2, spacing: f32) -> Self {
+        Self { origin: anchor.point() + offset, direction: Direction::Column, spacing, cursor: 0.0 }
+    }
+
+    /// A row going right from `offset` past the anchor.
+    pub fn row(anchor: Anchor, offset: Vec2, spacing: f32) -> Self {
+        Self { origin: anchor.point() + offset, direction: Direction::Row, spacing, cursor: 0.0 }
+    }
+
+    /// Top-left corner for the next widget, `width` x `height`; the layout moves on past it.
+    pub fn next(&mut self, width: f32, height: f32) -> Vec2 {
+        let pos = self.peek();
+        self.cursor += match self.direction {
+            Direction::Row => width,
+            Direction::Column => height,
+        } + self.spacing;
+        pos
+    }
+
+    /// Baseline position for the next label of `font_size` (labels are placed by their baseline).
+    pub fn next_text(&mut self, font_size: u16) -> Vec2 {
+        self.next(0.0, font_size as f32) + vec2(0.0, font_size as f32)
+    }
+
+    /// Reserves a line `height` tall in a column and returns a row laid out along it.
+    pub fn row_in(&mut self, height: f32, spacing: f32) -> Layout {
+        let origin = self.next(0.0, height);
+        Layout { origin, direction: Direction::Row, spacing, cursor: 0.0 }
+    }
+
+    /// Where the next widget would go, without moving on.
+    pub fn peek(&self) -> Vec2 {
+        match self.direction {
+            Direction::Row => self.origin + vec2(self.cursor, 0.0),
+            Direction::Column => self.origin + vec2(0.0, self.cursor),
+        }
+    }
+
+    /// Leaves an extra gap of `distance` before the next widget.
+    #[allow(unused)]
+    pub fn skip(&mut self, distance: f32) -> &mut Self {
+        self.cursor += distance;
+        self
+    }
+}
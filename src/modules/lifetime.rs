@@ -0,0 +1,161 @@
+/*
+Program Details: Lifetime module that despawns settled and lost pieces
+
+Dynamic pieces are never removed by the physics engine on their own, so long
+sessions pile up bodies until the solver slows down. The LifetimeManager:
+- fades out and removes pieces that have been asleep in a bin for `despawn_after` seconds
+- forgets pieces that left the world some other way (the physics watchdog
+  removes the ones that blow up or fall outside the board)
+- keeps at most `max_live_objects` pieces alive by removing the oldest one first
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod lifetime;
+
+Then with the other use commands add:
+use crate::modules::lifetime::LifetimeManager;
+
+Then above the loop section to use you would go:
+    let mut lifetime = LifetimeManager::new();
+
+Every time a piece is spawned, register it (this may remove the oldest piece if the cap is hit):
+    let handle = world.spawn(ShapeKind::Ball, 400.0, 50.0);
+    let removed = lifetime.track(&mut world, handle);
+
+Then in the loop you would use:
+    let removed = lifetime.update(&mut world, get_frame_time());
+    // draw dynamic pieces with lifetime.opacity(handle) as their alpha
+
+When the board is rebuilt (all pieces are gone), forget every tracked piece:
+    lifetime.clear();
+*/
+
+use crate::modules::physics::PhysicsWorld;
+use rapier2d::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+
+// Seconds a piece may sleep in a bin before it starts fading out
+pub const DESPAWN_AFTER: f32 = 5.0;
+// Seconds the fade-out takes
+pub const FADE_DURATION: f32 = 1.0;
+// Most dynamic pieces allowed on the board at once
+pub const MAX_LIVE_OBJECTS: usize = 150;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LifetimeManager {
+    pub despawn_after: f32,
+    pub fade_duration: f32,
+    pub max_live_objects: usize,
+    // Seconds each piece has been asleep inside a bin
+    settled_time: HashMap<RigidBodyHandle, f32>,
+    // Fade time remaining for pieces on their way out
+    fading: HashMap<RigidBodyHandle, f32>,
+    // Live pieces, oldest first
+    spawn_order: VecDeque<RigidBodyHandle>,
+}
+
+impl Default for LifetimeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LifetimeManager {
+    pub fn new() -> Self {
+        Self {
+            despawn_after: DESPAWN_AFTER,
+            fade_duration: FADE_DURATION,
+            max_live_objects: MAX_LIVE_OBJECTS,
+            settled_time: HashMap::new(),
+            fading: HashMap::new(),
+            spawn_order: VecDeque::new(),
+        }
+    }
+
+    // Number of pieces currently tracked (including ones fading out)
+    #[allow(unused)]
+    pub fn live_count(&self) -> usize {
+        self.spawn_order.len()
+    }
+
+    /// Starts tracking a newly spawned piece.
+    /// If that puts the board over the cap, the oldest pieces are removed right away.
+    /// Returns the handles of any pieces that were removed.
+    pub fn track(&mut self, world: &mut PhysicsWorld, handle: RigidBodyHandle) -> Vec<RigidBodyHandle> {
+        self.spawn_order.push_back(handle);
+
+        let mut removed = Vec::new();
+        while self.spawn_order.len() > self.max_live_objects {
+            if let Some(oldest) = self.spawn_order.front().copied() {
+                self.remove(world, oldest);
+                removed.push(oldest);
+            }
+        }
+        removed
+    }
+
+    /// Ages settled pieces, advances fades and removes finished pieces.
+    /// Returns the handles of the pieces removed this frame.
+    pub fn update(&mut self, world: &mut PhysicsWorld, dt: f32) -> Vec<RigidBodyHandle> {
+        let mut expired = Vec::new();
+
+        for &handle in &self.spawn_order {
+            let Some(body) = world.bodies.get(handle) else {
+                // Already gone from the world (board rebuilt, or lost) - just stop tracking it
+                expired.push(handle);
+                continue;
+            };
+
+            if let Some(remaining) = self.fading.get_mut(&handle) {
+                *remaining -= dt;
+                if *remaining <= 0.0 {
+                    expired.push(handle);
+                }
+                continue;
+            }
+
+            // Count up while the piece sleeps inside a bin; any movement restarts the clock
+            let in_bin = world.board.as_ref().is_some_and(|board| body.translation().y > board.bins_top());
+            if in_bin && body.is_sleeping() {
+                let settled = self.settled_time.entry(handle).or_insert(0.0);
+                *settled += dt;
+                if *settled >= self.despawn_after {
+                    self.fading.insert(handle, self.fade_duration);
+                }
+            } else {
+                self.settled_time.remove(&handle);
+            }
+        }
+
+        for &handle in &expired {
+            self.remove(world, handle);
+        }
+        expired
+    }
+
+    /// How visible a piece should be drawn: 1.0 normally, dropping to 0.0 as it fades out.
+    pub fn opacity(&self, handle: RigidBodyHandle) -> f32 {
+        match self.fading.get(&handle) {
+            Some(remaining) if self.fade_duration > 0.0 => (remaining / self.fade_duration).clamp(0.0, 1.0),
+            Some(_) => 0.0,
+            None => 1.0,
+        }
+    }
+
+    /// Forgets every tracked piece (use after the board has been rebuilt).
+    pub fn clear(&mut self) {
+        self.settled_time.clear();
+        self.fading.clear();
+        self.spawn_order.clear();
+    }
+
+    // Removes a piece from the world and from all tracking
+    fn remove(&mut self, world: &mut PhysicsWorld, handle: RigidBodyHandle) {
+        world.remove_body(handle);
+        self.settled_time.remove(&handle);
+        self.fading.remove(&handle);
+        self.spawn_order.retain(|h| *h != handle);
+    }
+}
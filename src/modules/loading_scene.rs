@@ -0,0 +1,74 @@
+/*
+Program Details: Loading screen shown while the game's assets load
+
+Shown once at startup, before the scenes are built (they take their images from
+the loaded Assets, see assets.rs), so it isn't one of the SceneManager's scenes:
+run() has its own loop that loads one queued file a frame and draws the game's
+title, a progress bar and the file being loaded, then returns when everything
+is in. Files that failed to load are printed to the console.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod loading_scene;
+
+Then with the other use commands add:
+use crate::modules::loading_scene::LoadingScene;
+
+Then above the loop section to use you would go:
+    let mut assets = Assets::new();
+    assets.queue_image("ball", "assets/ball.png");
+    LoadingScene::new("Plinko Slot Game").run(&mut assets).await;
+*/
+use macroquad::prelude::*;
+use crate::modules::assets::Assets;
+use crate::modules::scale::use_virtual_resolution;
+use crate::modules::theme::theme;
+
+// Size of the progress bar, centred on the screen
+const BAR_WIDTH: f32 = 500.0;
+const BAR_HEIGHT: f32 = 28.0;
+
+pub struct LoadingScene {
+    title: String,
+}
+
+impl LoadingScene {
+    pub fn new(title: &str) -> Self {
+        Self { title: title.to_string() }
+    }
+
+    /// Loads everything queued in `assets`, drawing the progress every frame.
+    pub async fn run(&self, assets: &mut Assets) {
+        while !assets.is_done() {
+            use_virtual_resolution(1024.0, 768.0);
+            clear_background(BLACK);
+            self.draw(assets);
+            next_frame().await;
+            assets.load_next().await;
+        }
+        for error in assets.errors() {
+            eprintln!("{}", error);
+        }
+    }
+
+    fn draw(&self, assets: &Assets) {
+        let style = theme();
+        let (center_x, center_y) = (512.0, 384.0);
+
+        let size = measure_text(&self.title, style.font.as_ref(), 48, 1.0);
+        draw_text_ex(&self.title, center_x - size.width / 2.0, center_y - 60.0, TextParams { font: style.font.as_ref(), font_size: 48, color: style.text_color, ..Default::default() });
+
+        let (x, y) = (center_x - BAR_WIDTH / 2.0, center_y - BAR_HEIGHT / 2.0);
+        draw_rectangle(x, y, BAR_WIDTH, BAR_HEIGHT, style.panel_color);
+        draw_rectangle(x, y, BAR_WIDTH * assets.progress(), BAR_HEIGHT, GREEN);
+        draw_rectangle_lines(x, y, BAR_WIDTH, BAR_HEIGHT, 2.0, style.panel_border);
+
+        let text = match assets.next_path() {
+            Some(path) => format!("Loading {}", path),
+            None => "Ready".to_string(),
+        };
+        let size = measure_text(&text, style.font.as_ref(), 20, 1.0);
+        draw_text_ex(&text, center_x - size.width / 2.0, y + BAR_HEIGHT + 30.0, TextParams { font: style.font.as_ref(), font_size: 20, color: LIGHTGRAY, ..Default::default() });
+    }
+}
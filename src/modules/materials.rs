@@ -0,0 +1,193 @@
+/*
+Program Details: Physics material presets for the board and the pieces
+
+A Material is how bouncy (restitution), how grippy (friction) and how heavy for
+its size (density) a collider is. Four named presets (rubber, steel, wood and
+ice) can each be tuned, and any of them can be given to the pegs, the walls
+(including bin dividers, funnels and ramps), the ground or the dropped pieces.
+Parts left on "Board" keep the values their board file was designed with.
+
+The presets and which part uses which are saved with the settings, and
+PhysicsWorld::set_materials changes every existing collider in place, so tuning
+a preset in the materials panel is felt straight away by the pieces already
+falling.
+
+A preset given to the pieces replaces their bounce (including the Bounciness
+setting) and their friction, but a piece with a bounce of its own (the bouncy
+ball) keeps it; their density is the preset's times their own, so heavy balls
+stay heavier than light ones.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod materials;
+
+Then with the other use commands add:
+use crate::modules::materials::{BoardPart, MaterialPreset, Materials};
+
+Then above the loop section to use you would go:
+    let mut materials = Materials::default();
+    materials.set_preset_for(BoardPart::Pegs, Some(MaterialPreset::Rubber));
+    materials.material_mut(MaterialPreset::Rubber).restitution = 0.9;
+    world.set_materials(&materials);
+*/
+use serde::{Deserialize, Serialize};
+
+/// How a collider bounces, grips and weighs.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Share of energy kept after a bounce (0.0 - 1.0)
+    pub restitution: f32,
+    /// Grip against whatever it touches (0.0 is frictionless)
+    pub friction: f32,
+    /// Mass per unit of area
+    pub density: f32,
+}
+
+impl Material {
+    pub const fn new(restitution: f32, friction: f32, density: f32) -> Self {
+        Self { restitution, friction, density }
+    }
+}
+
+/// The named presets a part of the board can be made of.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaterialPreset {
+    Rubber,
+    Steel,
+    Wood,
+    Ice,
+}
+
+impl MaterialPreset {
+    pub const ALL: [MaterialPreset; 4] = [MaterialPreset::Rubber, MaterialPreset::Steel, MaterialPreset::Wood, MaterialPreset::Ice];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MaterialPreset::Rubber => "Rubber",
+            MaterialPreset::Steel => "Steel",
+            MaterialPreset::Wood => "Wood",
+            MaterialPreset::Ice => "Ice",
+        }
+    }
+
+    /// The preset's values before any tuning.
+    pub fn defaults(self) -> Material {
+        match self {
+            MaterialPreset::Rubber => Material::new(0.8, 0.9, 1.2),
+            MaterialPreset::Steel => Material::new(0.5, 0.3, 5.0),
+            MaterialPreset::Wood => Material::new(0.35, 0.6, 0.6),
+            MaterialPreset::Ice => Material::new(0.1, 0.02, 0.9),
+        }
+    }
+}
+
+/// The parts of the board a preset can be given to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoardPart {
+    Pegs,
+    /// Walls, bin dividers, funnels and ramps
+    Walls,
+    Ground,
+    /// The dropped pieces
+    Pieces,
+}
+
+impl BoardPart {
+    pub const ALL: [BoardPart; 4] = [BoardPart::Pegs, BoardPart::Walls, BoardPart::Ground, BoardPart::Pieces];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BoardPart::Pegs => "Pegs",
+            BoardPart::Walls => "Walls",
+            BoardPart::Ground => "Ground",
+            BoardPart::Pieces => "Pieces",
+        }
+    }
+}
+
+/// The tuned presets and which part of the board uses which.
+/// Fields missing from a saved file keep their default value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Materials {
+    pub rubber: Material,
+    pub steel: Material,
+    pub wood: Material,
+    pub ice: Material,
+    /// Preset of each part; None keeps the values from the board file
+    pub pegs: Option<MaterialPreset>,
+    pub walls: Option<MaterialPreset>,
+    pub ground: Option<MaterialPreset>,
+    pub pieces: Option<MaterialPreset>,
+}
+
+impl Default for Materials {
+    fn default() -> Self {
+        Self {
+            rubber: MaterialPreset::Rubber.defaults(),
+            steel: MaterialPreset::Steel.defaults(),
+            wood: MaterialPreset::Wood.defaults(),
+            ice: MaterialPreset::Ice.defaults(),
+            pegs: None,
+            walls: None,
+            ground: None,
+            pieces: None,
+        }
+    }
+}
+
+impl Materials {
+    /// The current (possibly tuned) values of a preset.
+    pub fn material(&self, preset: MaterialPreset) -> Material {
+        match preset {
+            MaterialPreset::Rubber => self.rubber,
+            MaterialPreset::Steel => self.steel,
+            MaterialPreset::Wood => self.wood,
+            MaterialPreset::Ice => self.ice,
+        }
+    }
+
+    pub fn material_mut(&mut self, preset: MaterialPreset) -> &mut Material {
+        match preset {
+            MaterialPreset::Rubber => &mut self.rubber,
+            MaterialPreset::Steel => &mut self.steel,
+            MaterialPreset::Wood => &mut self.wood,
+            MaterialPreset::Ice => &mut self.ice,
+        }
+    }
+
+    /// The preset a part is made of (None when it keeps the board's values).
+    pub fn preset_for(&self, part: BoardPart) -> Option<MaterialPreset> {
+        match part {
+            BoardPart::Pegs => self.pegs,
+            BoardPart::Walls => self.walls,
+            BoardPart::Ground => self.ground,
+            BoardPart::Pieces => self.pieces,
+        }
+    }
+
+    pub fn set_preset_for(&mut self, part: BoardPart, preset: Option<MaterialPreset>) {
+        match part {
+            BoardPart::Pegs => self.pegs = preset,
+            BoardPart::Walls => self.walls = preset,
+            BoardPart::Ground => self.ground = preset,
+            BoardPart::Pieces => self.pieces = preset,
+        }
+    }
+
+    /// What a collider of `part` designed as `designed` is made of with these materials.
+    /// `own_bounce` is set for pieces whose restitution is part of their design (the bouncy ball).
+    pub fn resolve(&self, part: BoardPart, designed: Material, own_bounce: bool) -> Material {
+        match self.preset_for(part).map(|preset| self.material(preset)) {
+            None => designed,
+            // Pieces keep their own weight relative to each other (heavy and light balls), and a bounce of their own
+            Some(material) if part == BoardPart::Pieces => Material {
+                density: material.density * designed.density,
+                restitution: if own_bounce { designed.restitution } else { material.restitution },
+                ..material
+            },
+            Some(material) => material,
+        }
+    }
+}
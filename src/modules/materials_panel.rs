@@ -0,0 +1,199 @@
+/*
+Program Details: Materials panel for tuning the physics material presets
+
+A popup (opened from the settings panel) for the material presets: a button
+picks the preset being tuned (rubber, steel, wood or ice) and three sliders set
+its restitution, friction and density. Four buttons below choose the preset of
+the pegs, the walls, the ground and the pieces, cycling through the presets and
+"Board" (the values from the board file). Every change is copied into the
+Materials right away and applied to the board in place, so the pieces already
+falling feel it. "Back" closes the panel; the materials are saved with the rest
+of the settings.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod materials_panel;
+
+Then with the other use commands add:
+use crate::modules::materials_panel::MaterialsPanel;
+
+Then above the loop section to use you would go:
+    let mut materials_panel = MaterialsPanel::new(200.0, 150.0);
+
+To show it (e.g. from a "Materials" button):
+    materials_panel.open(&settings.materials);
+
+Then in the loop, after the board has been drawn so the panel sits on top:
+if materials_panel.update(&mut settings.materials) {
+    world.set_materials(&settings.materials);
+}
+*/
+use macroquad::prelude::*;
+use crate::modules::label::Label;
+use crate::modules::materials::{BoardPart, MaterialPreset, Materials};
+use crate::modules::slider::Slider;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 560.0;
+const SLIDER_WIDTH: f32 = 320.0;
+// Vertical distance between two sliders
+const SLIDER_SPACING: f32 = 62.0;
+
+pub struct MaterialsPanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    // The preset the sliders are tuning
+    editing: MaterialPreset,
+    btn_editing: TextButton,
+    sld_restitution: Slider,
+    sld_friction: Slider,
+    sld_density: Slider,
+    // One button per part of the board, in BoardPart::ALL order
+    btn_parts: Vec<TextButton>,
+    btn_back: TextButton,
+    btn_defaults: TextButton,
+    lbl_title: Label,
+    lbl_parts: Label,
+}
+
+impl MaterialsPanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let slider_x = x + (PANEL_WIDTH - SLIDER_WIDTH) / 2.0;
+        let editing = MaterialPreset::Rubber;
+        let btn_editing = TextButton::new(slider_x, y + 65.0, SLIDER_WIDTH, 35.0, editing_text(editing), DARKPURPLE, PURPLE, 20);
+
+        let first_y = y + 150.0;
+        let defaults = editing.defaults();
+        // Parameters: x_pos, y_pos, width, min, max, starting value
+        let mut sld_restitution = Slider::new(slider_x, first_y, SLIDER_WIDTH, 0.0, 1.0, defaults.restitution);
+        sld_restitution.with_step(0.05).with_label("Restitution", 20).with_decimals(2);
+        let mut sld_friction = Slider::new(slider_x, first_y + SLIDER_SPACING, SLIDER_WIDTH, 0.0, 1.0, defaults.friction);
+        sld_friction.with_step(0.02).with_label("Friction", 20).with_decimals(2);
+        let mut sld_density = Slider::new(slider_x, first_y + SLIDER_SPACING * 2.0, SLIDER_WIDTH, 0.1, 10.0, defaults.density);
+        sld_density.with_step(0.1).with_label("Density", 20).with_decimals(1);
+
+        // Part buttons in a 2x2 grid under the sliders
+        let parts_y = first_y + SLIDER_SPACING * 2.0 + 70.0;
+        let btn_parts = BoardPart::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let button_x = slider_x + 165.0 * (index % 2) as f32;
+                let button_y = parts_y + 45.0 * (index / 2) as f32;
+                TextButton::new(button_x, button_y, 155.0, 35.0, part_text(*part, None), DARKGRAY, GRAY, 20)
+            })
+            .collect();
+
+        let buttons_y = y + PANEL_HEIGHT - 80.0;
+        let btn_back = TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "Back", DARKGREEN, GREEN, 25);
+        let btn_defaults = TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Defaults", DARKGRAY, GRAY, 25);
+
+        let mut lbl_title = Label::new("Materials", x + 30.0, y + 45.0, 35);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_parts = Label::new("Made of:", slider_x, parts_y - 12.0, 20);
+        lbl_parts.with_colors(LIGHTGRAY, None);
+
+        Self {
+            x,
+            y,
+            visible: false,
+            editing,
+            btn_editing,
+            sld_restitution,
+            sld_friction,
+            sld_density,
+            btn_parts,
+            btn_back,
+            btn_defaults,
+            lbl_title,
+            lbl_parts,
+        }
+    }
+
+    /// Shows the panel with the sliders and buttons set to the current materials.
+    pub fn open(&mut self, materials: &Materials) {
+        self.sync(materials);
+        self.visible = true;
+    }
+
+    /// Draws the panel and copies any changes into `materials`. "Back" hides the panel.
+    /// Returns true when a material changed this frame.
+    pub fn update(&mut self, materials: &mut Materials) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+        self.lbl_parts.draw();
+
+        let mut changed = false;
+        if self.btn_editing.click() {
+            let next = MaterialPreset::ALL.iter().position(|preset| *preset == self.editing).map_or(0, |index| index + 1);
+            self.editing = MaterialPreset::ALL[next % MaterialPreset::ALL.len()];
+            self.sync(materials);
+        }
+        let material = materials.material_mut(self.editing);
+        if self.sld_restitution.update() {
+            material.restitution = self.sld_restitution.value();
+            changed = true;
+        }
+        if self.sld_friction.update() {
+            material.friction = self.sld_friction.value();
+            changed = true;
+        }
+        if self.sld_density.update() {
+            material.density = self.sld_density.value();
+            changed = true;
+        }
+
+        for (button, part) in self.btn_parts.iter_mut().zip(BoardPart::ALL) {
+            if button.click() {
+                // Board -> Rubber -> Steel -> Wood -> Ice -> Board
+                let next = match materials.preset_for(part) {
+                    None => Some(MaterialPreset::ALL[0]),
+                    Some(preset) => MaterialPreset::ALL.iter().position(|other| *other == preset).and_then(|index| MaterialPreset::ALL.get(index + 1).copied()),
+                };
+                materials.set_preset_for(part, next);
+                button.set_text(part_text(part, next));
+                changed = true;
+            }
+        }
+
+        if self.btn_defaults.click() {
+            *materials = Materials::default();
+            self.sync(materials);
+            changed = true;
+        }
+        if self.btn_back.click() {
+            self.visible = false;
+        }
+        changed
+    }
+
+    // Moves every slider and button to the matching material
+    fn sync(&mut self, materials: &Materials) {
+        let material = materials.material(self.editing);
+        self.btn_editing.set_text(editing_text(self.editing));
+        self.sld_restitution.set_value(material.restitution);
+        self.sld_friction.set_value(material.friction);
+        self.sld_density.set_value(material.density);
+        for (button, part) in self.btn_parts.iter_mut().zip(BoardPart::ALL) {
+            button.set_text(part_text(part, materials.preset_for(part)));
+        }
+    }
+}
+
+// Text of the button picking the preset being tuned
+fn editing_text(preset: MaterialPreset) -> String {
+    format!("Tuning: {}", preset.name())
+}
+
+// Text of a part's button: its preset, or "Board" for the values from the board file
+fn part_text(part: BoardPart, preset: Option<MaterialPreset>) -> String {
+    format!("{}: {}", part.name(), preset.map_or("Board", MaterialPreset::name))
+}
@@ -0,0 +1,226 @@
+/*
+Program Details: Title menu scene
+
+The first screen of the game: the title, a short how-to-play and buttons to
+start playing or quit (the web version has no Quit button: closing the tab does
+that). Coming back here from the board (Escape or the Menu button) keeps the
+game as it was, so Play carries on where the player left off.
+
+High Scores opens the table of the best sessions so far, and Benchmark beside it
+the physics benchmark (see benchmark_scene.rs).
+
+Hot Seat starts a local multiplayer game for the number of players shown on the
+small button next to it (click it to cycle through 2, 3 and 4 players).
+
+Timed Round starts a new game that ends after two minutes of play (or when the
+credits run out, like any game), followed by the session summary.
+
+Arcade, beside it, starts a new coin-operated game: it starts with no credits,
+Insert Coin (or the C key) adds COIN_CREDITS at a time, and running out just
+waits for the next coin. Left alone for ATTRACT_SECONDS, the board gives the
+screen to the attract mode demo (see attract_scene.rs).
+
+Inventory, beside Play, starts a new game with a fixed stock of pieces (5 balls,
+3 squares and 2 triangles, see inventory.rs) instead of as many drops as the
+credits allow; it ends once they have all been dropped and have landed.
+
+The menu itself, left without input for the idle time picked in the settings,
+also plays the attract mode demo, which comes back here on any key.
+
+Typing a seed in the box under the buttons and pressing Play (or Enter) starts
+a new game whose drops all come from that seed, so anyone typing the same seed
+(a "daily seed") plays the same drops. The box is cleared once the game starts;
+a seed typed before Timed Round seeds the timed round.
+
+A share code (copied with the Share button on the board, see share_code.rs)
+pasted into the code box under it works the same way, and also switches to the
+board it was made on. A code that can't be read is reported above the box and
+the game doesn't start.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod menu_scene;
+
+Then with the other use commands add:
+use crate::modules::menu_scene::MenuScene;
+
+Then above the loop section to use you would go:
+    let seed = SharedSeed::default();
+    let session = SharedSession::default();
+    let board_choice = SharedBoardChoice::default();
+    scenes.add(SceneId::Menu, Box::new(MenuScene::new(seed.clone(), session.clone(), board_choice.clone())));
+*/
+use macroquad::prelude::*;
+use crate::modules::attract_scene::ATTRACT_SECONDS;
+use crate::modules::idle::IdleTimer;
+use crate::modules::label::Label;
+use crate::modules::players::{MAX_PLAYERS, MIN_PLAYERS};
+use crate::modules::presets_scene::{BoardChoice, SharedBoardChoice};
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::seed::{parse_seed, SharedSeed};
+use crate::modules::session::{RoundKind, SharedSession};
+use crate::modules::settings::Settings;
+use crate::modules::share_code::ShareCode;
+use crate::modules::text_button::TextButton;
+use crate::modules::text_input::TextInput;
+
+// Shown above the code box (until a code can't be read)
+const CODE_LABEL: &str = "Board code (optional):";
+const MAX_CODE_LENGTH: usize = 8000;
+
+pub struct MenuScene {
+    lbl_title: Label,
+    lbl_help: Label,
+    btn_play: TextButton,
+    btn_timed: TextButton,
+    btn_arcade: TextButton,
+    btn_inventory: TextButton,
+    btn_scores: TextButton,
+    btn_benchmark: TextButton,
+    btn_hot_seat: TextButton,
+    btn_player_count: TextButton,
+    // Players the next hot-seat game is for
+    player_count: usize,
+    #[cfg_attr(target_arch = "wasm32", allow(unused))]
+    btn_quit: TextButton,
+    lbl_seed: Label,
+    txt_seed: TextInput,
+    lbl_code: Label,
+    txt_code: TextInput,
+    // Real seconds without input, after which the attract mode demo plays
+    idle: IdleTimer,
+    // Where a typed seed, a timed round request and a shared board are handed to the Plinko scene
+    seed: SharedSeed,
+    session: SharedSession,
+    board_choice: SharedBoardChoice,
+}
+
+impl MenuScene {
+    pub fn new(seed: SharedSeed, session: SharedSession, board_choice: SharedBoardChoice) -> Self {
+        let mut lbl_title = Label::new("Plinko Slot Game", 312.0, 220.0, 60);
+        lbl_title.with_colors(YELLOW, None);
+        let mut lbl_help = Label::new("Bet credits, drop pieces through the pegs\nand win what the bin they land in pays.", 312.0, 290.0, 25);
+        lbl_help.with_colors(WHITE, None);
+
+        let mut lbl_seed = Label::new("Seed (optional):", 412.0, 630.0, 20);
+        lbl_seed.with_colors(LIGHTGRAY, None);
+        let mut txt_seed = TextInput::new(412.0, 640.0, 200.0, 40.0, 24);
+        txt_seed.with_placeholder("Random").with_max_length(20);
+        let mut lbl_code = Label::new(CODE_LABEL, 412.0, 705.0, 20);
+        lbl_code.with_colors(LIGHTGRAY, None);
+        // Codes for busy boards run to a few thousand characters
+        let mut txt_code = TextInput::new(412.0, 715.0, 200.0, 40.0, 24);
+        txt_code.with_placeholder("Paste a code").with_max_length(MAX_CODE_LENGTH);
+
+        Self {
+            lbl_title,
+            lbl_help,
+            btn_play: TextButton::new(412.0, 340.0, 200.0, 45.0, "Play", DARKGREEN, GREEN, 30),
+            btn_timed: TextButton::new(412.0, 395.0, 200.0, 45.0, "Timed Round", DARKBLUE, BLUE, 30),
+            btn_arcade: TextButton::new(622.0, 395.0, 140.0, 45.0, "Arcade", DARKBROWN, BROWN, 26),
+            btn_inventory: TextButton::new(622.0, 340.0, 140.0, 45.0, "Inventory", DARKGREEN, GREEN, 26),
+            btn_hot_seat: TextButton::new(412.0, 450.0, 200.0, 45.0, "Hot Seat", MAROON, RED, 30),
+            btn_player_count: TextButton::new(622.0, 450.0, 60.0, 45.0, format!("{}P", MIN_PLAYERS), MAROON, RED, 26),
+            player_count: MIN_PLAYERS,
+            btn_scores: TextButton::new(412.0, 505.0, 200.0, 45.0, "High Scores", DARKPURPLE, PURPLE, 30),
+            btn_benchmark: TextButton::new(622.0, 505.0, 140.0, 45.0, "Benchmark", DARKGRAY, GRAY, 24),
+            btn_quit: TextButton::new(412.0, 560.0, 200.0, 45.0, "Quit", DARKGRAY, GRAY, 30),
+            lbl_seed,
+            txt_seed,
+            lbl_code,
+            txt_code,
+            idle: IdleTimer::new(ATTRACT_SECONDS),
+            seed,
+            session,
+            board_choice,
+        }
+    }
+
+    // Hands the board and seed of the pasted share code to the Plinko scene. False (with the
+    // reason shown above the box) if the code can't be read; true if it was read or the box is empty.
+    fn apply_code(&mut self) -> bool {
+        let text = self.txt_code.text();
+        if text.trim().is_empty() {
+            return true;
+        }
+        match ShareCode::decode(&text) {
+            Ok(shared) => {
+                self.seed.set(Some(shared.seed));
+                *self.board_choice.borrow_mut() = Some(BoardChoice::Preset(Box::new(shared.board)));
+                self.txt_code.set_text("");
+                self.lbl_code.set_text(CODE_LABEL).with_colors(LIGHTGRAY, None);
+                true
+            }
+            Err(error) => {
+                self.lbl_code.set_text(error).with_colors(RED, None);
+                false
+            }
+        }
+    }
+}
+
+impl Scene for MenuScene {
+    fn on_enter(&mut self) {
+        // Read fresh every time, so a new idle time picked in the settings counts from the next visit
+        self.idle.set_limit(Settings::load().attract_seconds);
+        self.idle.reset();
+    }
+
+    fn update(&mut self, dt: f32) -> SceneChange {
+        // Enter works whether or not the seed box has focus
+        let submitted = self.txt_seed.update() | self.txt_code.update();
+        let play = self.btn_play.click() || submitted || is_key_pressed(KeyCode::Enter);
+        let timed = self.btn_timed.click();
+        let arcade = self.btn_arcade.click();
+        let inventory = self.btn_inventory.click();
+        let hot_seat = self.btn_hot_seat.click();
+        if self.btn_player_count.click() {
+            self.player_count = if self.player_count >= MAX_PLAYERS { MIN_PLAYERS } else { self.player_count + 1 };
+            self.btn_player_count.set_text(format!("{}P", self.player_count));
+        }
+        if (play || timed || arcade || inventory || hot_seat) && self.apply_code() {
+            if timed {
+                self.session.borrow_mut().request = Some(RoundKind::Timed);
+            }
+            if arcade {
+                self.session.borrow_mut().request = Some(RoundKind::Arcade);
+            }
+            if inventory {
+                self.session.borrow_mut().request = Some(RoundKind::Inventory);
+            }
+            if hot_seat {
+                self.session.borrow_mut().request = Some(RoundKind::HotSeat { players: self.player_count });
+            }
+            // A seed typed next to a code wins over the code's own
+            if let Some(seed) = parse_seed(&self.txt_seed.text()) {
+                self.seed.set(Some(seed));
+                self.txt_seed.set_text("");
+            }
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_scores.click() {
+            return SceneChange::Goto(SceneId::HighScores);
+        }
+        if self.btn_benchmark.click() {
+            return SceneChange::Goto(SceneId::Benchmark);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.btn_quit.click() {
+            return SceneChange::Quit;
+        }
+        if self.idle.update(dt) {
+            return SceneChange::Goto(SceneId::Attract);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.lbl_title.draw();
+        self.lbl_help.draw();
+        self.lbl_seed.draw();
+        self.txt_seed.draw();
+        self.lbl_code.draw();
+        self.txt_code.draw();
+    }
+}
@@ -0,0 +1,100 @@
+/*
+Program Details: Minimap of the whole board
+
+A small map of the board in its top-right corner: the board's bodies, chains and
+pool, with every piece on it as a dot in its own colour (drawn bigger than the
+piece so it stays visible at the map's size). The map is drawn through a
+Viewport (see viewport.rs) whose camera always shows the whole board, whatever
+the main view is doing.
+
+M shows and hides it.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod minimap;
+
+Then with the other use commands add:
+use crate::modules::minimap::Minimap;
+
+Then above the loop section to use you would go:
+    let mut minimap = Minimap::new();
+
+Then in the loop you would use:
+    if is_key_pressed(KeyCode::M) {
+        minimap.visible = !minimap.visible;
+    }
+    // ... at the start of drawing, before anything else is drawn to the screen:
+    minimap.render(&world, &board, |handle| YELLOW);
+    // ... and once the board has been drawn:
+    minimap.draw();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use crate::modules::board::BoardConfig;
+use crate::modules::chain::draw_chains;
+use crate::modules::entity_registry::EntityKind;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::pool::draw_pool;
+use crate::modules::presets_scene::{draw_board_colliders, BOARD_AREA};
+use crate::modules::viewport::Viewport;
+
+// Where the map sits on the screen (the board's top-right corner), the same shape as the board
+const MAP_AREA: Rect = Rect { x: 640.0, y: 45.0, w: 150.0, h: 141.0 };
+// Viewport pixels per screen pixel, so the map stays sharp when the window is scaled up
+const MAP_SCALE: f32 = 2.0;
+// Radius (board pixels) every piece's dot is drawn with
+const DOT_RADIUS: f32 = 14.0;
+// Colours of the map's background and frame
+const MAP_BACKGROUND: Color = Color::new(0.05, 0.05, 0.1, 0.85);
+const FRAME_COLOR: Color = Color::new(0.7, 0.7, 0.7, 1.0);
+
+/// A map of the whole board and the pieces on it, shown in the board's corner.
+#[derive(Default)]
+pub struct Minimap {
+    /// Whether the map is shown
+    pub visible: bool,
+    // The viewport the map is drawn into, made the first time it is shown and reused
+    viewport: Option<Viewport>,
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws the board and its pieces, in the colour `color_of` gives them, into the map's viewport. Call it
+    /// before anything else is drawn this frame.
+    pub fn render(&mut self, world: &PhysicsWorld, board: &BoardConfig, color_of: impl Fn(RigidBodyHandle) -> Color) {
+        if !self.visible {
+            return;
+        }
+        let viewport = self
+            .viewport
+            .get_or_insert_with(|| Viewport::new((MAP_AREA.w * MAP_SCALE) as u32, (MAP_AREA.h * MAP_SCALE) as u32));
+        viewport.render(BOARD_AREA, || {
+            clear_background(BLANK);
+            draw_rectangle(BOARD_AREA.x, BOARD_AREA.y, BOARD_AREA.w, BOARD_AREA.h, MAP_BACKGROUND);
+            draw_board_colliders(world, BOARD_AREA);
+            draw_chains(world);
+            draw_pool(board, 0.0);
+            for (handle, body) in world.bodies.iter() {
+                if EntityKind::of(body).is_some_and(EntityKind::is_piece) {
+                    let pos = body.translation();
+                    draw_circle(pos.x, pos.y, DOT_RADIUS, color_of(handle));
+                }
+            }
+        });
+    }
+
+    /// Draws the map in the board's corner, if it is shown.
+    pub fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        if let Some(viewport) = self.viewport.as_ref() {
+            viewport.draw(MAP_AREA);
+            draw_rectangle_lines(MAP_AREA.x, MAP_AREA.y, MAP_AREA.w, MAP_AREA.h, 2.0, FRAME_COLOR);
+        }
+    }
+}
@@ -17,4 +17,88 @@ pub mod scale;
 pub mod collision;
 pub mod still_image;
 pub mod text_button;
- pub mod label;
\ No newline at end of file
+pub mod label;
+pub mod physics;
+pub mod wallet;
+pub mod board;
+pub mod lifetime;
+pub mod audio;
+pub mod slider;
+pub mod settings;
+pub mod settings_panel;
+pub mod events;
+pub mod particles;
+pub mod texture_atlas;
+pub mod aim;
+pub mod time_control;
+pub mod stats;
+pub mod multiplier;
+pub mod scene;
+pub mod plinko_scene;
+pub mod menu_scene;
+pub mod game_over_scene;
+pub mod drop_history;
+pub mod history_panel;
+pub mod prize_wheel;
+pub mod prize_wheel_scene;
+pub mod dropdown;
+pub mod text_input;
+pub mod seed;
+pub mod presets_scene;
+pub mod force_field;
+pub mod session;
+pub mod high_scores;
+pub mod high_scores_scene;
+pub mod name_entry_panel;
+pub mod players;
+pub mod winner_scene;
+pub mod trajectory;
+pub mod trails;
+pub mod slot_reel;
+pub mod bin_label_bar;
+pub mod nine_patch;
+pub mod theme;
+pub mod dialog;
+pub mod tooltip;
+pub mod layout;
+pub mod materials;
+pub mod materials_panel;
+pub mod solver_panel;
+pub mod perf_hud;
+pub mod spawn_queue;
+pub mod edit_history;
+pub mod editor_scene;
+pub mod board_generator;
+pub mod share_code;
+pub mod snapshot;
+pub mod event_console;
+pub mod objectives;
+pub mod landing_odds;
+pub mod shape_telemetry;
+pub mod shape_stats_scene;
+pub mod entity_registry;
+pub mod renderer;
+pub mod board_theme;
+pub mod bumpers;
+pub mod sticky_pad;
+pub mod pool;
+pub mod chain;
+pub mod instant_replay;
+pub mod viewport;
+pub mod minimap;
+pub mod benchmark_scene;
+pub mod layers;
+pub mod animated_image;
+pub mod assets;
+pub mod loading_scene;
+pub mod inspector_panel;
+pub mod grab;
+pub mod tilt;
+pub mod juice;
+pub mod juice_panel;
+pub mod music;
+pub mod announcer;
+pub mod idle;
+pub mod attract_scene;
+pub mod demo;
+pub mod inventory;
\ No newline at end of file
@@ -0,0 +1,245 @@
+/*
+Program Details: Per-drop score multipliers from golden pegs and multiplier zones
+
+Every dropped piece starts with a 1x multiplier. Each time it strikes a golden
+peg the multiplier goes up by 1, and passing through a multiplier zone adds that
+zone's bonus. When the piece settles its payout is bin value × its multiplier.
+Multipliers are looked up by body handle, so they have to be forgotten when a
+piece is removed (take() does that when paying out).
+
+Landings in the same bin one after another build a combo (Combo): the second in
+a row pays COMBO_STEP more, the third twice that, and so on up to COMBO_MAX, and
+a landing in any other bin starts over from that bin. The chain is shown over
+its bin, bigger, brighter and shakier the longer it gets. It relies on the
+physics world telling landings in the order the pieces reached their bins.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod multiplier;
+
+Then with the other use commands add:
+use crate::modules::multiplier::DropMultipliers;
+
+Then above the loop section to use you would go:
+    let mut multipliers = DropMultipliers::new();
+
+Then in the loop you would use:
+    multipliers.record_events(world.events(), &board);
+    // ... when a piece settles:
+    let won = wallet.pay_out(stake, board.payouts[bin] * multipliers.take(piece));
+    // ... after the pieces are drawn:
+    multipliers.draw(&world);
+
+For combos, above the loop:
+    let mut combo = Combo::new();
+And for every piece that settles, in the order of world.events():
+    let won = wallet.pay_out(stake, board.payouts[bin] * multipliers.take(piece) * combo.land(bin));
+    // ... every frame:
+    combo.update(dt);
+    combo.draw(&board);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::HashMap;
+use crate::modules::board::{BoardConfig, ZoneConfig};
+use crate::modules::events::GameEvent;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::theme::scaled_font_size;
+use crate::modules::wallet::format_multiplier;
+use serde::{Deserialize, Serialize};
+
+// Colour of golden pegs
+pub const GOLDEN_PEG_COLOR: Color = GOLD;
+// Fill and outline colours of the multiplier zones
+const ZONE_FILL: Color = Color::new(1.0, 0.84, 0.0, 0.15);
+const ZONE_OUTLINE: Color = Color::new(1.0, 0.84, 0.0, 0.5);
+// What each landing after the first in a combo adds to the multiplier, and the most it can reach
+pub const COMBO_STEP: f32 = 0.25;
+pub const COMBO_MAX: f32 = 3.0;
+// How long the combo pops after growing, in seconds
+const COMBO_POP_SECONDS: f32 = 0.4;
+// Colours of a growing combo: 2, 3, 4, 5 and longer chains
+const COMBO_COLORS: [Color; 5] = [YELLOW, GOLD, ORANGE, RED, MAGENTA];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DropMultipliers {
+    // Accumulated multiplier of each falling piece that has gained a bonus
+    multipliers: HashMap<RigidBodyHandle, f32>,
+}
+
+impl Default for DropMultipliers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DropMultipliers {
+    pub fn new() -> Self {
+        Self { multipliers: HashMap::new() }
+    }
+
+    /// The piece's current multiplier (1.0 if it hasn't gained any bonus).
+    #[allow(unused)]
+    pub fn get(&self, piece: RigidBodyHandle) -> f32 {
+        self.multipliers.get(&piece).copied().unwrap_or(1.0)
+    }
+
+    /// Adds `bonus` to the piece's multiplier (a negative one is a penalty, e.g. for tilting).
+    pub fn add(&mut self, piece: RigidBodyHandle, bonus: f32) {
+        *self.multipliers.entry(piece).or_insert(1.0) += bonus;
+    }
+
+    /// Applies a frame's golden peg hits (+1 each) and zone entries (+ the zone's bonus).
+    pub fn record_events(&mut self, events: &[GameEvent], board: &BoardConfig) {
+        for event in events {
+            match *event {
+                GameEvent::PegHit { piece, golden: true, .. } => self.add(piece, 1.0),
+                GameEvent::ZoneEntered { piece, zone } => {
+                    if let Some(zone) = board.zones.get(zone) {
+                        self.add(piece, zone.bonus);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the piece's multiplier and forgets it.
+    pub fn take(&mut self, piece: RigidBodyHandle) -> f32 {
+        self.multipliers.remove(&piece).unwrap_or(1.0)
+    }
+
+    // Forget the multiplier of a piece that was removed without paying out
+    pub fn remove(&mut self, piece: RigidBodyHandle) {
+        self.multipliers.remove(&piece);
+    }
+
+    // Forget every multiplier (e.g. when the board is rebuilt)
+    pub fn clear(&mut self) {
+        self.multipliers.clear();
+    }
+
+    /// Draws the board's multiplier zones as see-through rectangles labelled with their bonus.
+    pub fn draw_zones(zones: &[ZoneConfig]) {
+        for zone in zones {
+            let (x, y) = (zone.x - zone.half_width, zone.y - zone.half_height);
+            let (w, h) = (zone.half_width * 2.0, zone.half_height * 2.0);
+            draw_rectangle(x, y, w, h, ZONE_FILL);
+            draw_rectangle_lines(x, y, w, h, 2.0, ZONE_OUTLINE);
+
+            let text = format!("+{}", format_multiplier(zone.bonus));
+            let size = measure_text(&text, None, 20, 1.0);
+            draw_text(&text, zone.x - size.width / 2.0, zone.y + size.height / 2.0, 20.0, ZONE_OUTLINE);
+        }
+    }
+
+    /// Draws the current multiplier above every falling piece that has gained a bonus.
+    pub fn draw(&self, world: &PhysicsWorld) {
+        for (&piece, &multiplier) in &self.multipliers {
+            let Some(body) = world.bodies.get(piece) else {
+                continue;
+            };
+            let (pos, _) = world.render_pose(piece, body);
+            let text = format_multiplier(multiplier);
+            let font_size = scaled_font_size(18);
+            let width = measure_text(&text, None, font_size, 1.0).width;
+            draw_text(&text, pos.x - width / 2.0, pos.y - 12.0, font_size as f32, GOLD);
+        }
+    }
+}
+
+/// Where a combo chain stands.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ComboState {
+    /// Nothing has landed yet (or the board was cleared)
+    #[default]
+    Idle,
+    /// The last `length` landings were all in bin `bin`
+    Chain { bin: usize, length: u32 },
+}
+
+/// Counts consecutive landings in the same bin and the multiplier they earn.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Combo {
+    state: ComboState,
+    // Time left of the pop shown when the chain grows
+    #[serde(skip)]
+    pop: f32,
+}
+
+impl Combo {
+    pub fn new() -> Self {
+        Self { state: ComboState::Idle, pop: 0.0 }
+    }
+
+    #[allow(unused)]
+    pub fn state(&self) -> ComboState {
+        self.state
+    }
+
+    /// Length of the chain (0 when idle).
+    pub fn length(&self) -> u32 {
+        match self.state {
+            ComboState::Idle => 0,
+            ComboState::Chain { length, .. } => length,
+        }
+    }
+
+    /// Multiplier the chain gives the landing that made it this long.
+    pub fn multiplier(&self) -> f32 {
+        (1.0 + COMBO_STEP * self.length().saturating_sub(1) as f32).min(COMBO_MAX)
+    }
+
+    /// A piece landed in `bin`: the chain grows if it is the chain's bin, otherwise a new one
+    /// starts there. Returns the multiplier for this landing.
+    pub fn land(&mut self, bin: usize) -> f32 {
+        self.state = match self.state {
+            ComboState::Chain { bin: chain_bin, length } if chain_bin == bin => {
+                self.pop = COMBO_POP_SECONDS;
+                ComboState::Chain { bin, length: length + 1 }
+            }
+            _ => ComboState::Chain { bin, length: 1 },
+        };
+        self.multiplier()
+    }
+
+    // Start over (e.g. on a new game or board)
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.pop = (self.pop - dt).max(0.0);
+    }
+
+    /// Shows a chain of two or more over its bin: the bin lights up and the combo's count and
+    /// multiplier grow, change colour and shake the longer it gets.
+    pub fn draw(&self, board: &BoardConfig) {
+        let ComboState::Chain { bin, length } = self.state else {
+            return;
+        };
+        if length < 2 || bin >= board.bins.count {
+            return;
+        }
+        let tier = (length as usize - 2).min(COMBO_COLORS.len() - 1);
+        let color = COMBO_COLORS[tier];
+        let pop = self.pop / COMBO_POP_SECONDS;
+
+        let left = board.bins_left() + board.bin_width() * bin as f32;
+        let top = board.bins_top();
+        let glow = 0.12 + 0.05 * tier as f32 + 0.25 * pop;
+        draw_rectangle(left, top, board.bin_width(), board.ground_top() - top, Color { a: glow, ..color });
+
+        let text = format!("Combo {} {}", length, format_multiplier(self.multiplier()));
+        let size = scaled_font_size(20) as f32 + 4.0 * tier as f32 + 12.0 * pop;
+        // Long chains tremble
+        let time = get_time() as f32;
+        let shake = tier as f32 * 0.6;
+        let offset = vec2((time * 47.0).sin() * shake, (time * 61.0).cos() * shake);
+        let width = measure_text(&text, None, size as u16, 1.0).width;
+        let x = (left + board.bin_width() / 2.0 - width / 2.0 + offset.x).max(4.0);
+        draw_text(&text, x + 2.0, top - 14.0 + offset.y + 2.0, size, BLACK);
+        draw_text(&text, x, top - 14.0 + offset.y, size, color);
+    }
+}
@@ -0,0 +1,313 @@
+/*
+Program Details: Background music in layers that follow the action
+
+The music is four stems of the same eight-second loop (Am - F - C - G at 120
+beats per minute), synthesized into WAV data in memory at startup like the
+game's other sounds (see audio.rs):
+
+- the base: a bass line on every beat under soft chords, always playing
+- the pulse: an arpeggio of the chords in eighth notes
+- the drums: kick, snare and hi-hats
+- the lead: a melody over the top
+
+All four are started together and loop for as long as the game plays, so they
+stay in time; what changes is how loud each one is. The game's intensity, from
+how many pieces are falling and how long the current combo is, fades each layer
+in once it passes the layer's threshold and back out when things calm down,
+over a couple of seconds rather than with a jump.
+
+Nothing is read from disk and no thread is started, so this runs the same on
+the web: the stems are short enough to keep in memory whole and are looped by
+the audio backend (macroquad has no streaming of its own), and they are only
+started once the scene is entered, after the player has clicked through the
+menu, since browsers keep sound off until then.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod music;
+
+Then with the other use commands add:
+use crate::modules::music::Music;
+
+Then above the loop section to use you would go:
+    let mut music = Music::load().await;
+    music.set_volume(0.5);
+    music.play();
+
+Then in the loop you would use (falling pieces and the combo's length drive the layers):
+    music.update(falling_pieces, combo.length(), get_frame_time());
+
+And when leaving the screen:
+    music.stop();
+*/
+use crate::modules::audio::{wav_bytes, SAMPLE_RATE};
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use std::f32::consts::{PI, TAU};
+
+// Length of a beat (120 beats per minute) and of the loop: four bars of four beats
+const BEAT: f32 = 0.5;
+const BEATS_PER_BAR: usize = 4;
+const BARS: usize = 4;
+const BAR: f32 = BEAT * BEATS_PER_BAR as f32;
+const LOOP_SECONDS: f32 = BAR * BARS as f32;
+// The chords bar by bar (Am, F, C, G) as MIDI note numbers: the bass note and the chord's three notes
+const CHORDS: [(u8, [u8; 3]); BARS] = [(45, [57, 60, 64]), (41, [53, 57, 60]), (48, [60, 64, 67]), (43, [55, 59, 62])];
+// The lead's four notes in each bar
+const MELODY: [[u8; 4]; BARS] = [[76, 74, 72, 69], [72, 69, 72, 77], [76, 79, 76, 72], [74, 71, 74, 79]];
+// Order the arpeggio plays the chord's notes in, one per eighth note
+const ARPEGGIO: [usize; 8] = [0, 1, 2, 1, 0, 1, 2, 1];
+
+// Intensity at which each layer starts fading in (the base always plays), and how much more it takes
+// to reach full volume
+const PULSE_THRESHOLD: f32 = 0.1;
+const DRUMS_THRESHOLD: f32 = 0.35;
+const LEAD_THRESHOLD: f32 = 0.65;
+const FADE_IN_RANGE: f32 = 0.15;
+// Falling pieces that make the board busy, and the combo length that makes it hot; the pieces
+// alone take the music up to the drums, a long combo on top of them brings in the lead
+const BUSY_PIECES: f32 = 12.0;
+const HOT_COMBO: f32 = 5.0;
+const PIECES_WEIGHT: f32 = 0.6;
+const COMBO_WEIGHT: f32 = 0.4;
+// How much of its volume a layer gains or loses per second while crossfading
+const FADE_SPEED: f32 = 0.5;
+
+// One layer of the music, the intensity that brings it in and how loud it is right now (0.0 - 1.0)
+struct Stem {
+    sound: Option<Sound>,
+    threshold: f32,
+    level: f32,
+}
+
+impl Stem {
+    // How loud the layer should be at `intensity`
+    fn target(&self, intensity: f32) -> f32 {
+        if self.threshold <= 0.0 { 1.0 } else { ((intensity - self.threshold) / FADE_IN_RANGE).clamp(0.0, 1.0) }
+    }
+}
+
+pub struct Music {
+    stems: Vec<Stem>,
+    volume: f32,
+    playing: bool,
+}
+
+impl Music {
+    /// Synthesizes and loads the four stems. A stem that fails to load is left out of the mix.
+    pub async fn load() -> Self {
+        let layers = [(base_stem(), 0.0), (pulse_stem(), PULSE_THRESHOLD), (drum_stem(), DRUMS_THRESHOLD), (lead_stem(), LEAD_THRESHOLD)];
+        let mut stems = Vec::with_capacity(layers.len());
+        for (samples, threshold) in layers {
+            let sound = load_sound_from_bytes(&wav_bytes(&samples)).await.ok();
+            stems.push(Stem { sound, threshold, level: if threshold <= 0.0 { 1.0 } else { 0.0 } });
+        }
+        Self { stems, volume: 1.0, playing: false }
+    }
+
+    /// Sets how loud the music is overall (0.0 - 1.0).
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        self.apply_levels();
+    }
+
+    /// Starts every layer looping, together so they stay in time. Does nothing if already playing.
+    pub fn play(&mut self) {
+        if self.playing {
+            return;
+        }
+        self.playing = true;
+        for stem in &self.stems {
+            if let Some(sound) = &stem.sound {
+                play_sound(sound, PlaySoundParams { looped: true, volume: stem.level * self.volume });
+            }
+        }
+    }
+
+    /// Stops the music; play() starts it again from the top.
+    pub fn stop(&mut self) {
+        if !self.playing {
+            return;
+        }
+        self.playing = false;
+        for sound in self.stems.iter().filter_map(|stem| stem.sound.as_ref()) {
+            stop_sound(sound);
+        }
+    }
+
+    /// How intense the game is (0.0 - 1.0) with `falling_pieces` in play and a combo `combo` landings long.
+    pub fn intensity(falling_pieces: usize, combo: u32) -> f32 {
+        let pieces = (falling_pieces as f32 / BUSY_PIECES).min(1.0);
+        // A lone landing isn't a combo yet
+        let combo = (combo.saturating_sub(1) as f32 / (HOT_COMBO - 1.0)).min(1.0);
+        (pieces * PIECES_WEIGHT + combo * COMBO_WEIGHT).min(1.0)
+    }
+
+    /// Fades the layers towards the mix for the game's intensity, over `dt` real seconds.
+    pub fn update(&mut self, falling_pieces: usize, combo: u32, dt: f32) {
+        let intensity = Self::intensity(falling_pieces, combo);
+        let step = FADE_SPEED * dt;
+        let mut changed = false;
+        for stem in &mut self.stems {
+            let target = stem.target(intensity);
+            if stem.level != target {
+                stem.level = if stem.level < target { (stem.level + step).min(target) } else { (stem.level - step).max(target) };
+                changed = true;
+            }
+        }
+        if changed {
+            self.apply_levels();
+        }
+    }
+
+    // Sets every playing layer's volume from its level and the music volume
+    fn apply_levels(&self) {
+        if !self.playing {
+            return;
+        }
+        for stem in &self.stems {
+            if let Some(sound) = &stem.sound {
+                set_sound_volume(sound, stem.level * self.volume);
+            }
+        }
+    }
+}
+
+// ----- THE STEMS -----
+
+// Bass on every beat, under the chord held softly through each bar
+fn base_stem() -> Vec<f32> {
+    let mut samples = silence();
+    for (bar, (bass, chord)) in CHORDS.iter().enumerate() {
+        let start = bar as f32 * BAR;
+        for beat in 0..BEATS_PER_BAR {
+            add_note(&mut samples, start + beat as f32 * BEAT, BEAT * 0.9, frequency(*bass), 0.45, 3.0);
+        }
+        for &note in chord {
+            add_swell(&mut samples, start, BAR, frequency(note), 0.06);
+        }
+    }
+    samples
+}
+
+// The chord's notes an octave up, one per eighth note
+fn pulse_stem() -> Vec<f32> {
+    let mut samples = silence();
+    let eighth = BEAT / 2.0;
+    for (bar, (_, chord)) in CHORDS.iter().enumerate() {
+        for (step, &index) in ARPEGGIO.iter().enumerate() {
+            let start = bar as f32 * BAR + step as f32 * eighth;
+            add_note(&mut samples, start, eighth, frequency(chord[index] + 12), 0.16, 5.0);
+        }
+    }
+    samples
+}
+
+// Kick on beats one and three, snare on two and four, hi-hats on every eighth note
+fn drum_stem() -> Vec<f32> {
+    let mut samples = silence();
+    let mut noise = Noise(0x1234_5678);
+    for beat in 0..BEATS_PER_BAR * BARS {
+        let start = beat as f32 * BEAT;
+        if beat % 2 == 0 {
+            add_kick(&mut samples, start);
+        } else {
+            add_snare(&mut samples, start, &mut noise);
+        }
+        add_hat(&mut samples, start, &mut noise);
+        add_hat(&mut samples, start + BEAT / 2.0, &mut noise);
+    }
+    samples
+}
+
+// The melody, a note per beat
+fn lead_stem() -> Vec<f32> {
+    let mut samples = silence();
+    for (bar, notes) in MELODY.iter().enumerate() {
+        for (beat, &note) in notes.iter().enumerate() {
+            add_note(&mut samples, bar as f32 * BAR + beat as f32 * BEAT, BEAT * 0.95, frequency(note), 0.2, 2.0);
+        }
+    }
+    samples
+}
+
+// ----- SYNTHESIS -----
+
+// One loop's worth of silence
+fn silence() -> Vec<f32> {
+    vec![0.0; (LOOP_SECONDS * SAMPLE_RATE as f32) as usize]
+}
+
+// Frequency (Hz) of a MIDI note number (69 = the A at 440 Hz)
+fn frequency(note: u8) -> f32 {
+    440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+// Mixes `length` seconds of sound into `samples` from `start` seconds on, `sample(t)` giving the sound at
+// `t` seconds into it. Whatever runs past the end of the loop comes back in at its start, so the loop is seamless
+fn mix(samples: &mut [f32], start: f32, length: f32, mut sample: impl FnMut(f32) -> f32) {
+    let first = (start * SAMPLE_RATE as f32) as usize;
+    let count = (length * SAMPLE_RATE as f32) as usize;
+    for i in 0..count {
+        let index = (first + i) % samples.len();
+        samples[index] += sample(i as f32 / SAMPLE_RATE as f32);
+    }
+}
+
+// A plucked note: a sine with a touch of its octave for body, dying away `decay` times over its length
+fn add_note(samples: &mut [f32], start: f32, length: f32, frequency: f32, gain: f32, decay: f32) {
+    mix(samples, start, length, |t| {
+        let phase = t * frequency * TAU;
+        let envelope = (-t * decay / length).exp() * release(t, length);
+        (phase.sin() + 0.3 * (phase * 2.0).sin()) * envelope * gain
+    });
+}
+
+// A soft held note swelling in and out over its length
+fn add_swell(samples: &mut [f32], start: f32, length: f32, frequency: f32, gain: f32) {
+    mix(samples, start, length, |t| (t * frequency * TAU).sin() * (t / length * PI).sin() * gain);
+}
+
+// A kick drum: a sine dropping quickly from a thump to a low boom
+fn add_kick(samples: &mut [f32], start: f32) {
+    let mut phase = 0.0_f32;
+    mix(samples, start, 0.3, |t| {
+        phase += (45.0 + 75.0 * (-t * 30.0).exp()) * TAU / SAMPLE_RATE as f32;
+        phase.sin() * (-t * 12.0).exp() * 0.6
+    });
+}
+
+// A snare: a burst of noise over a short low tone
+fn add_snare(samples: &mut [f32], start: f32, noise: &mut Noise) {
+    mix(samples, start, 0.2, |t| noise.sample() * (-t * 25.0).exp() * 0.22 + (t * 190.0 * TAU).sin() * (-t * 30.0).exp() * 0.2);
+}
+
+// A closed hi-hat: a very short tick of noise, with the low end taken out
+fn add_hat(samples: &mut [f32], start: f32, noise: &mut Noise) {
+    let mut previous = 0.0;
+    mix(samples, start, 0.05, |t| {
+        let current = noise.sample();
+        let hiss = current - previous;
+        previous = current;
+        hiss * (-t * 60.0).exp() * 0.05
+    });
+}
+
+// Fades the last few milliseconds of a note out, so cutting it off doesn't click
+fn release(t: f32, length: f32) -> f32 {
+    ((length - t) / 0.005).clamp(0.0, 1.0)
+}
+
+// White noise for the drums from a fixed seed (xorshift), so the stems come out the same every time and
+// leave the game's random numbers alone
+struct Noise(u32);
+
+impl Noise {
+    // The next sample, -1.0 - 1.0
+    fn sample(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
@@ -0,0 +1,123 @@
+/*
+Program Details: High score name entry panel
+
+A popup asking for the player's name when a finished session made it onto the
+high score table. It shows the score, a TextInput for the name and Save / Skip
+buttons; Enter in the name box saves too. Saving hands the name back to the
+caller, which adds the entry to the table; Skip just closes the panel.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod name_entry_panel;
+
+Then with the other use commands add:
+use crate::modules::name_entry_panel::NameEntryPanel;
+
+Then above the loop section to use you would go:
+    let mut name_entry = NameEntryPanel::new(312.0, 290.0);
+
+To show it (e.g. when a session ends with a high score):
+    name_entry.open(score);
+
+Then in the loop, with the rest of the input handling:
+    if let Some(name) = name_entry.update() {
+        scores.insert(HighScore::new(&name, score, &board));
+    }
+
+And after everything else has been drawn so the panel sits on top:
+    name_entry.draw();
+
+The Save and Skip buttons are drawn (and so clicked) in draw(); update() acts on
+those clicks the frame after.
+*/
+use macroquad::prelude::*;
+use crate::modules::high_scores::MAX_NAME_LENGTH;
+use crate::modules::label::Label;
+use crate::modules::text_button::TextButton;
+use crate::modules::text_input::TextInput;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 400.0;
+const PANEL_HEIGHT: f32 = 200.0;
+
+pub struct NameEntryPanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    lbl_title: Label,
+    txt_name: TextInput,
+    btn_save: TextButton,
+    btn_skip: TextButton,
+    // Clicks on Save and Skip seen while drawing, for update() to act on
+    save_clicked: bool,
+    skip_clicked: bool,
+}
+
+impl NameEntryPanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let mut lbl_title = Label::new("", x + 30.0, y + 45.0, 30);
+        lbl_title.with_colors(YELLOW, None);
+        let mut txt_name = TextInput::new(x + 30.0, y + 70.0, PANEL_WIDTH - 60.0, 40.0, 26);
+        txt_name.with_placeholder("Your name").with_max_length(MAX_NAME_LENGTH);
+        let buttons_y = y + PANEL_HEIGHT - 70.0;
+
+        Self {
+            x,
+            y,
+            visible: false,
+            lbl_title,
+            txt_name,
+            btn_save: TextButton::new(x + 30.0, buttons_y, 150.0, 45.0, "Save", DARKGREEN, GREEN, 25),
+            btn_skip: TextButton::new(x + PANEL_WIDTH - 180.0, buttons_y, 150.0, 45.0, "Skip", DARKGRAY, GRAY, 25),
+            save_clicked: false,
+            skip_clicked: false,
+        }
+    }
+
+    /// Shows the panel for a new high score of `score`, with the name box focused and empty.
+    pub fn open(&mut self, score: u32) {
+        self.lbl_title.set_text(format!("New high score: {}!", score));
+        self.txt_name.set_text("");
+        self.txt_name.focus();
+        self.save_clicked = false;
+        self.skip_clicked = false;
+        self.visible = true;
+    }
+
+    /// Handles typing and the button clicks. Returns the name entered on the frame it's saved.
+    pub fn update(&mut self) -> Option<String> {
+        if !self.visible {
+            return None;
+        }
+
+        let entered = self.txt_name.update();
+        let (save, skip) = (self.save_clicked, self.skip_clicked);
+        self.save_clicked = false;
+        self.skip_clicked = false;
+        if save || entered {
+            self.visible = false;
+            self.txt_name.unfocus();
+            return Some(self.txt_name.text());
+        }
+        if skip {
+            self.visible = false;
+            self.txt_name.unfocus();
+        }
+        None
+    }
+
+    /// Draws the panel over a dimmed screen, if it's open.
+    pub fn draw(&mut self) {
+        if !self.visible {
+            return;
+        }
+
+        draw_rectangle(0.0, 0.0, 1024.0, 768.0, Color::new(0.0, 0.0, 0.0, 0.5));
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+        self.txt_name.draw();
+        self.save_clicked |= self.btn_save.click();
+        self.skip_clicked |= self.btn_skip.click();
+    }
+}
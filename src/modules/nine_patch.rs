@@ -0,0 +1,110 @@
+/*
+Program Details: Nine-patch textures for skinning panels and buttons
+
+A nine-patch is a texture cut into a 3x3 grid by four margins. When it is drawn
+into a rectangle of any size the four corners are drawn as they are, the edges
+are stretched along their length only and the middle is stretched both ways, so
+rounded corners and borders keep their shape however big the panel or button is.
+
+A skin can be loaded from an image with the margins given in texture pixels, or
+generated as a rounded, bordered rectangle so the game has skins without any
+image files.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod nine_patch;
+
+Then with the other use commands add:
+use crate::modules::nine_patch::NinePatch;
+
+Then above the loop section to use you would go:
+    let skin = NinePatch::new(load_texture("assets/panel.png").await.unwrap(), 12.0);
+or, without an image:
+    let skin = NinePatch::rounded(Color::new(0.05, 0.05, 0.1, 0.92), GRAY, 10, 2);
+
+Then in the loop you would use (x, y, width, height and a tint, WHITE for none):
+    skin.draw(200.0, 150.0, 420.0, 500.0, WHITE);
+*/
+use macroquad::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct NinePatch {
+    texture: Texture2D,
+    // Margins in texture pixels: left, top, right, bottom
+    margins: [f32; 4],
+}
+
+impl NinePatch {
+    /// A skin from a texture, with the same margin on all four sides.
+    pub fn new(texture: Texture2D, margin: f32) -> Self {
+        Self { texture, margins: [margin; 4] }
+    }
+
+    // Separate margins for each side: left, top, right, bottom
+    #[allow(unused)]
+    pub fn with_margins(&mut self, left: f32, top: f32, right: f32, bottom: f32) -> &mut Self {
+        self.margins = [left, top, right, bottom];
+        self
+    }
+
+    /// A rectangle with corners rounded by `radius` pixels, filled with `fill` and outlined in
+    /// `border` `border_width` pixels thick. Tinting it WHITE draws it in exactly these colours.
+    pub fn rounded(fill: Color, border: Color, radius: u16, border_width: u16) -> Self {
+        // Corners of `radius` pixels plus a single stretchable pixel between them
+        let size = radius * 2 + 1;
+        let mut image = Image::gen_image_color(size, size, Color::new(0.0, 0.0, 0.0, 0.0));
+        let radius_f = radius as f32;
+        for y in 0..size {
+            for x in 0..size {
+                // Distance of the pixel's center from the stretchable middle pixel: the rectangle's edge
+                // is `radius` away from it, and the corners are rounded by measuring diagonally
+                let dx = (x.abs_diff(radius) as f32 - 0.5).max(0.0);
+                let dy = (y.abs_diff(radius) as f32 - 0.5).max(0.0);
+                let distance = (dx * dx + dy * dy).sqrt();
+                // Share of the pixel inside the edge, which smooths the curve of the corners
+                let coverage = (radius_f + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let color = if distance > radius_f - border_width as f32 { border } else { fill };
+                image.set_pixel(x as u32, y as u32, Color { a: color.a * coverage, ..color });
+            }
+        }
+        let texture = Texture2D::from_image(&image);
+        texture.set_filter(FilterMode::Nearest);
+        Self::new(texture, radius_f)
+    }
+
+    /// Draws the skin stretched over the rectangle at (x, y), tinted with `tint`.
+    pub fn draw(&self, x: f32, y: f32, width: f32, height: f32, tint: Color) {
+        let (tex_w, tex_h) = (self.texture.width(), self.texture.height());
+        let [left, top, right, bottom] = self.margins;
+        // Corners shrink with a rectangle too small to fit them
+        let scale = (width / (left + right)).min(height / (top + bottom)).min(1.0);
+        let (dest_left, dest_top, dest_right, dest_bottom) = (left * scale, top * scale, right * scale, bottom * scale);
+
+        // Column and row edges in the texture and on screen
+        let source_x = [0.0, left, tex_w - right, tex_w];
+        let source_y = [0.0, top, tex_h - bottom, tex_h];
+        let dest_x = [x, x + dest_left, x + width - dest_right, x + width];
+        let dest_y = [y, y + dest_top, y + height - dest_bottom, y + height];
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let dest_size = vec2(dest_x[col + 1] - dest_x[col], dest_y[row + 1] - dest_y[row]);
+                if dest_size.x <= 0.0 || dest_size.y <= 0.0 {
+                    continue;
+                }
+                let source = Rect::new(source_x[col], source_y[row], source_x[col + 1] - source_x[col], source_y[row + 1] - source_y[row]);
+                draw_texture_ex(
+                    &self.texture,
+                    dest_x[col],
+                    dest_y[row],
+                    tint,
+                    DrawTextureParams { dest_size: Some(dest_size), source: Some(source), ..Default::default() },
+                );
+            }
+        }
+    }
+}
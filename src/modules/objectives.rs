@@ -0,0 +1,268 @@
+/*
+Program Details: Challenge objectives with rewards
+
+Three objectives are active at a time, each a small goal with a reward in
+credits, listed in the top-left corner of the board with how far along it is:
+
+  - land a number of pieces in one bin within a number of drops,
+  - win an amount of credits dropping only one shape (dropping another shape
+    starts the count over),
+  - win at least an amount in a single landing.
+
+A finished objective pays its reward and is replaced by a new one; a bin
+objective whose drops ran out (and whose pieces have all landed) fails and is
+replaced too, so the set keeps rotating. The goals are picked at random for the
+board being played: rarer bins (towards the edges) give more drops and bigger
+rewards, and the amounts and rewards grow with the bet.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod objectives;
+
+Then with the other use commands add:
+use crate::modules::objectives::Objectives;
+
+Then above the loop section to use you would go:
+    let mut objectives = Objectives::new();
+
+Then in the loop you would use:
+    let failed = objectives.rotate(&board, wallet.bet());
+    // ... when a piece is dropped:
+    objectives.record_drop(handle, kind);
+    // ... when a piece settles and has been paid:
+    for done in objectives.record_landing(piece, bin, won) {
+        wallet.deposit(done.reward);
+    }
+    // ... when a piece is removed without landing:
+    objectives.forget(piece);
+
+And to draw the list:
+    objectives.draw();
+*/
+use macroquad::prelude::*;
+use macroquad::rand;
+use rapier2d::prelude::RigidBodyHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::modules::board::BoardConfig;
+use crate::modules::physics::ShapeKind;
+
+// How many objectives are active at a time
+pub const ACTIVE_OBJECTIVES: usize = 3;
+// Where the list is drawn: the top-left corner of the board, left of the slot reels
+const PANEL_X: f32 = 76.0;
+const PANEL_Y: f32 = 4.0;
+const PANEL_WIDTH: f32 = 268.0;
+const LINE_HEIGHT: f32 = 18.0;
+const FONT_SIZE: f32 = 16.0;
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.55);
+
+/// What an objective asks for.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Goal {
+    /// Land `count` pieces in bin `bin` (0 = leftmost) within `drops` drops
+    HitBin { bin: usize, count: u32, drops: u32 },
+    /// Win `amount` credits dropping nothing but `kind`
+    ScoreWithShape { kind: ShapeKind, amount: u32 },
+    /// Win at least `amount` in one landing
+    BigWin { amount: u32 },
+}
+
+/// One active objective and how far along it is.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Objective {
+    pub goal: Goal,
+    /// Credits paid when it is done
+    pub reward: u32,
+    // Pieces landed (HitBin), credits won (ScoreWithShape) or best single win (BigWin)
+    progress: u32,
+    // Drops made towards a HitBin objective
+    drops: u32,
+    // Pieces dropped for it that haven't landed yet
+    in_flight: HashSet<RigidBodyHandle>,
+}
+
+impl Objective {
+    fn new(goal: Goal, reward: u32) -> Self {
+        Self { goal, reward, progress: 0, drops: 0, in_flight: HashSet::new() }
+    }
+
+    // Picks a random objective for `board`, sized to `bet`
+    fn random(board: &BoardConfig, bet: u32) -> Self {
+        let bins = board.bins.count.max(1);
+        match rand::gen_range(0, 3) {
+            0 => {
+                let bin = rand::gen_range(0, bins);
+                // 0 in the middle bin, 1 in the edge bins
+                let half = (bins as f32 - 1.0) / 2.0;
+                let rarity = if half > 0.0 { (bin as f32 - half).abs() / half } else { 0.0 };
+                let count = rand::gen_range(1, 4u32);
+                let drops = count * (3 + (rarity * 6.0).round() as u32);
+                let reward = bet * count * (2 + (rarity * 8.0).round() as u32);
+                Objective::new(Goal::HitBin { bin, count, drops }, reward)
+            }
+            1 => {
+                let kind = [ShapeKind::Ball, ShapeKind::Square, ShapeKind::Triangle][rand::gen_range(0, 3)];
+                let amount = bet * rand::gen_range(3, 8u32) * 10;
+                Objective::new(Goal::ScoreWithShape { kind, amount }, amount / 5)
+            }
+            _ => {
+                // Half to all of the board's best payout, so it can always be reached
+                let best = board.payouts.iter().copied().fold(1.0, f32::max);
+                let amount = ((bet as f32 * best * rand::gen_range(0.5, 1.0)).round() as u32).max(bet * 2);
+                Objective::new(Goal::BigWin { amount }, amount / 2)
+            }
+        }
+    }
+
+    /// What the objective asks for, e.g. "Land 3 in bin 5 within 10 drops".
+    pub fn describe(&self) -> String {
+        match self.goal {
+            Goal::HitBin { bin, count, drops } => format!("Land {} in bin {} within {} drops", count, bin + 1, drops),
+            Goal::ScoreWithShape { kind, amount } => format!("Win {} using only {:?}s", amount, kind),
+            Goal::BigWin { amount } => format!("Win {} in one landing", amount),
+        }
+    }
+
+    /// How far along it is, e.g. "1/3".
+    pub fn progress_text(&self) -> String {
+        match self.goal {
+            Goal::HitBin { count, .. } => format!("{}/{}", self.progress, count),
+            Goal::ScoreWithShape { amount, .. } | Goal::BigWin { amount } => format!("{}/{}", self.progress.min(amount), amount),
+        }
+    }
+
+    // How far along it is, from 0 to 1
+    fn fraction(&self) -> f32 {
+        let target = match self.goal {
+            Goal::HitBin { count, .. } => count,
+            Goal::ScoreWithShape { amount, .. } | Goal::BigWin { amount } => amount,
+        };
+        (self.progress as f32 / target.max(1) as f32).min(1.0)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.fraction() >= 1.0
+    }
+
+    // A bin objective fails once its drops are used up and all of them have landed
+    fn has_failed(&self) -> bool {
+        match self.goal {
+            Goal::HitBin { drops, .. } => !self.is_done() && self.drops >= drops && self.in_flight.is_empty(),
+            _ => false,
+        }
+    }
+
+    // Whether the two would mostly be done together: two bin objectives on the same bin, or
+    // two of another kind
+    fn overlaps(&self, other: &Objective) -> bool {
+        match (self.goal, other.goal) {
+            (Goal::HitBin { bin, .. }, Goal::HitBin { bin: other_bin, .. }) => bin == other_bin,
+            (goal, other_goal) => std::mem::discriminant(&goal) == std::mem::discriminant(&other_goal),
+        }
+    }
+
+    // Whether it can still be played on `board` (a bin objective's bin may have gone)
+    fn fits(&self, board: &BoardConfig) -> bool {
+        match self.goal {
+            Goal::HitBin { bin, .. } => bin < board.bins.count,
+            _ => true,
+        }
+    }
+}
+
+/// The active objectives.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Objectives {
+    active: Vec<Objective>,
+}
+
+impl Objectives {
+    pub fn new() -> Self {
+        Self { active: Vec::new() }
+    }
+
+    /// Drops every objective; the next rotate() picks a fresh set.
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    /// Removes the failed objectives (returned) and those that don't fit `board`, then tops the
+    /// set back up to ACTIVE_OBJECTIVES with new ones for `board` at `bet`.
+    pub fn rotate(&mut self, board: &BoardConfig, bet: u32) -> Vec<Objective> {
+        let (failed, kept): (Vec<Objective>, Vec<Objective>) = self.active.drain(..).partition(|objective| objective.has_failed());
+        self.active = kept.into_iter().filter(|objective| objective.fits(board)).collect();
+        while self.active.len() < ACTIVE_OBJECTIVES {
+            let objective = Objective::random(board, bet);
+            if !self.active.iter().any(|other| other.overlaps(&objective)) {
+                self.active.push(objective);
+            }
+        }
+        failed
+    }
+
+    /// A piece of `kind` was dropped.
+    pub fn record_drop(&mut self, piece: RigidBodyHandle, kind: ShapeKind) {
+        for objective in &mut self.active {
+            match objective.goal {
+                Goal::HitBin { drops, .. } if objective.drops < drops => {
+                    objective.drops += 1;
+                    objective.in_flight.insert(piece);
+                }
+                Goal::ScoreWithShape { kind: wanted, .. } if kind == wanted => {
+                    objective.in_flight.insert(piece);
+                }
+                // Another shape starts the count over
+                Goal::ScoreWithShape { .. } => {
+                    objective.progress = 0;
+                    objective.in_flight.clear();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// A piece settled in `bin` and won `won`. Returns the objectives it finished, which are
+    /// taken out of the set (rotate() replaces them); the caller pays their rewards.
+    pub fn record_landing(&mut self, piece: RigidBodyHandle, bin: usize, won: u32) -> Vec<Objective> {
+        for objective in &mut self.active {
+            let counted = objective.in_flight.remove(&piece);
+            match objective.goal {
+                Goal::HitBin { bin: wanted, .. } if counted && bin == wanted => objective.progress += 1,
+                Goal::ScoreWithShape { .. } if counted => objective.progress += won,
+                Goal::BigWin { .. } => objective.progress = objective.progress.max(won),
+                _ => {}
+            }
+        }
+        let (done, active) = self.active.drain(..).partition(|objective| objective.is_done());
+        self.active = active;
+        done
+    }
+
+    /// A piece was removed without landing.
+    pub fn forget(&mut self, piece: RigidBodyHandle) {
+        for objective in &mut self.active {
+            objective.in_flight.remove(&piece);
+        }
+    }
+
+    /// Draws the list with a progress bar under each objective.
+    pub fn draw(&self) {
+        if self.active.is_empty() {
+            return;
+        }
+        let height = self.active.len() as f32 * LINE_HEIGHT * 1.5 + 6.0;
+        draw_rectangle(PANEL_X, PANEL_Y, PANEL_WIDTH, height, BACKGROUND);
+        for (row, objective) in self.active.iter().enumerate() {
+            let y = PANEL_Y + row as f32 * LINE_HEIGHT * 1.5 + LINE_HEIGHT;
+            draw_text(&objective.describe(), PANEL_X + 6.0, y - 2.0, FONT_SIZE, WHITE);
+            let progress = objective.progress_text();
+            let width = measure_text(&progress, None, FONT_SIZE as u16, 1.0).width;
+            draw_text(&progress, PANEL_X + PANEL_WIDTH - 6.0 - width, y - 2.0, FONT_SIZE, GOLD);
+            let bar_width = PANEL_WIDTH - 12.0;
+            draw_rectangle(PANEL_X + 6.0, y + 2.0, bar_width, 4.0, DARKGRAY);
+            draw_rectangle(PANEL_X + 6.0, y + 2.0, bar_width * objective.fraction(), 4.0, GOLD);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+/*
+Program Details: Particle effects driven by the game events
+
+Sparks fly when a piece strikes a peg hard, a breakable peg shatters into shards
+when it breaks, dust puffs up when a piece lands on the ground, and confetti
+bursts out when a piece settles in a high-value bin.
+
+Particles live in a fixed-size pool: spawning reuses dead slots instead of
+allocating, and once the pool is full new particles are skipped. Every live
+particle is a small colored quad; they are all written into one mesh per chunk
+so the whole effect layer takes only a couple of draw calls.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod particles;
+
+Then with the other use commands add:
+use crate::modules::particles::ParticleSystem;
+
+Then above the loop section to use you would go:
+    let mut particles = ParticleSystem::new(MAX_PARTICLES);
+
+Then in the loop you would use:
+    world.advance(get_frame_time());
+    particles.emit_for_events(&world, world.events());
+    particles.update(get_frame_time());
+    // ... draw the board ...
+    particles.draw();
+*/
+
+use crate::modules::events::GameEvent;
+use crate::modules::physics::PhysicsWorld;
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+use macroquad::prelude::*;
+
+// Particles the pool holds by default
+pub const MAX_PARTICLES: usize = 2000;
+// Peg hits at least this fast throw sparks
+const SPARK_SPEED: f32 = 250.0;
+// Ground landings at least this fast kick up dust
+const DUST_SPEED: f32 = 80.0;
+// Bins paying at least this multiplier fire confetti
+const CONFETTI_MULTIPLIER: f32 = 2.0;
+// Quads per mesh: 800 quads = 4800 indices, under macroquad's default 5000 index draw call limit
+const QUADS_PER_MESH: usize = 800;
+
+const CONFETTI_COLORS: [Color; 6] = [RED, YELLOW, GREEN, SKYBLUE, PINK, ORANGE];
+// Colour of breakable pegs and of the shards they break into
+pub const BREAKABLE_PEG_COLOR: Color = Color::new(0.75, 0.6, 0.95, 1.0);
+
+#[derive(Clone, Copy)]
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    // Downward acceleration; confetti floats, sparks drop
+    gravity: f32,
+    // Fraction of velocity kept per second (air drag)
+    drag: f32,
+    size: f32,
+    color: Color,
+    life: f32,
+    max_life: f32,
+}
+
+pub struct ParticleSystem {
+    // Fixed pool; a slot with life <= 0 is free
+    pool: Vec<Particle>,
+    // Slots known to be free, so spawning doesn't have to scan the pool
+    free: Vec<usize>,
+    // Reused every frame to build the batched meshes
+    mesh: Mesh,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new(MAX_PARTICLES)
+    }
+}
+
+impl ParticleSystem {
+    /// Creates a pool that can hold `capacity` particles at once.
+    pub fn new(capacity: usize) -> Self {
+        let dead = Particle {
+            pos: Vec2::ZERO,
+            vel: Vec2::ZERO,
+            gravity: 0.0,
+            drag: 1.0,
+            size: 0.0,
+            color: WHITE,
+            life: 0.0,
+            max_life: 1.0,
+        };
+        Self {
+            pool: vec![dead; capacity],
+            free: (0..capacity).rev().collect(),
+            mesh: Mesh {
+                vertices: Vec::with_capacity(QUADS_PER_MESH * 4),
+                indices: Vec::with_capacity(QUADS_PER_MESH * 6),
+                texture: None,
+            },
+        }
+    }
+
+    // Number of particles currently alive
+    #[allow(unused)]
+    pub fn live_count(&self) -> usize {
+        self.pool.len() - self.free.len()
+    }
+
+    /// Spawns the effects for a frame's GameEvents: sparks on hard peg hits, shards where a peg
+    /// broke, dust on ground landings and confetti when a piece settles in a bin paying
+    /// CONFETTI_MULTIPLIER or more.
+    pub fn emit_for_events(&mut self, world: &PhysicsWorld, events: &[GameEvent]) {
+        let Some(board) = world.board.as_ref() else {
+            return;
+        };
+        for event in events {
+            let Some(body) = world.bodies.get(event.piece()) else {
+                continue;
+            };
+            let pos = vec2(body.translation().x, body.translation().y);
+            match *event {
+                GameEvent::PegHit { speed, .. } if speed >= SPARK_SPEED => {
+                    self.sparks(pos, speed);
+                }
+                GameEvent::PegBroken { x, y, .. } => self.shatter(vec2(x, y)),
+                // Only hits down in the bins count as landing on the ground, not wall scrapes
+                GameEvent::WallHit { speed, .. } if speed >= DUST_SPEED && pos.y > board.bins_top() => {
+                    self.dust(vec2(pos.x, board.ground_top()));
+                }
+                GameEvent::Settled { bin, .. } if board.payouts[bin] >= CONFETTI_MULTIPLIER => {
+                    self.confetti(pos);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// A burst of short-lived bright sparks; harder hits throw more of them, faster.
+    pub fn sparks(&mut self, pos: Vec2, speed: f32) {
+        let count = (speed / 40.0).clamp(4.0, 16.0) as usize;
+        for _ in 0..count {
+            let angle = rand::gen_range(0.0, std::f32::consts::TAU);
+            let launch = rand::gen_range(0.3, 0.8) * speed;
+            let color = if rand::gen_range(0, 2) == 0 { YELLOW } else { WHITE };
+            self.spawn(Particle {
+                pos,
+                vel: vec2(angle.cos(), angle.sin()) * launch,
+                gravity: 600.0,
+                drag: 0.05,
+                size: 2.0,
+                color,
+                life: 0.0,
+                max_life: rand::gen_range(0.15, 0.35),
+            });
+        }
+    }
+
+    /// Shards flung out in every direction from a breaking peg, falling away under gravity.
+    pub fn shatter(&mut self, pos: Vec2) {
+        for _ in 0..24 {
+            let angle = rand::gen_range(0.0, std::f32::consts::TAU);
+            let launch = rand::gen_range(80.0, 260.0);
+            self.spawn(Particle {
+                pos: pos + vec2(angle.cos(), angle.sin()) * 4.0,
+                vel: vec2(angle.cos(), angle.sin()) * launch,
+                gravity: 700.0,
+                drag: 0.2,
+                size: rand::gen_range(2.5, 4.5),
+                color: if rand::gen_range(0, 3) == 0 { WHITE } else { BREAKABLE_PEG_COLOR },
+                life: 0.0,
+                max_life: rand::gen_range(0.4, 0.8),
+            });
+        }
+    }
+
+    /// A soft grey puff that spreads sideways along the ground.
+    pub fn dust(&mut self, pos: Vec2) {
+        for _ in 0..10 {
+            self.spawn(Particle {
+                pos: pos + vec2(rand::gen_range(-6.0, 6.0), 0.0),
+                vel: vec2(rand::gen_range(-60.0, 60.0), rand::gen_range(-40.0, -10.0)),
+                gravity: 0.0,
+                drag: 0.1,
+                size: rand::gen_range(3.0, 5.0),
+                color: Color::new(0.7, 0.7, 0.65, 0.6),
+                life: 0.0,
+                max_life: rand::gen_range(0.4, 0.8),
+            });
+        }
+    }
+
+    /// A colorful shower that pops upward and flutters back down.
+    pub fn confetti(&mut self, pos: Vec2) {
+        for _ in 0..40 {
+            let color = CONFETTI_COLORS[rand::gen_range(0, CONFETTI_COLORS.len())];
+            self.spawn(Particle {
+                pos,
+                vel: vec2(rand::gen_range(-150.0, 150.0), rand::gen_range(-350.0, -150.0)),
+                gravity: 250.0,
+                drag: 0.3,
+                size: rand::gen_range(3.0, 5.0),
+                color,
+                life: 0.0,
+                max_life: rand::gen_range(1.0, 1.8),
+            });
+        }
+    }
+
+    /// Moves every live particle and frees the ones that have run out of life.
+    pub fn update(&mut self, dt: f32) {
+        for (index, particle) in self.pool.iter_mut().enumerate() {
+            if particle.life <= 0.0 {
+                continue;
+            }
+            particle.life -= dt;
+            if particle.life <= 0.0 {
+                self.free.push(index);
+                continue;
+            }
+            particle.vel.y += particle.gravity * dt;
+            particle.vel *= particle.drag.powf(dt);
+            particle.pos += particle.vel * dt;
+        }
+    }
+
+    /// Draws every live particle, fading each out over its lifetime.
+    /// Particles are batched into one mesh per QUADS_PER_MESH particles.
+    pub fn draw(&mut self) {
+        self.mesh.vertices.clear();
+        self.mesh.indices.clear();
+
+        for particle in self.pool.iter().filter(|p| p.life > 0.0) {
+            let alpha = particle.color.a * (particle.life / particle.max_life);
+            let color = Color { a: alpha, ..particle.color };
+            let half = particle.size / 2.0;
+            let (x, y) = (particle.pos.x, particle.pos.y);
+
+            let first = self.mesh.vertices.len() as u16;
+            self.mesh.vertices.push(Vertex::new(x - half, y - half, 0.0, 0.0, 0.0, color));
+            self.mesh.vertices.push(Vertex::new(x + half, y - half, 0.0, 1.0, 0.0, color));
+            self.mesh.vertices.push(Vertex::new(x + half, y + half, 0.0, 1.0, 1.0, color));
+            self.mesh.vertices.push(Vertex::new(x - half, y + half, 0.0, 0.0, 1.0, color));
+            self.mesh.indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+
+            if self.mesh.indices.len() >= QUADS_PER_MESH * 6 {
+                draw_mesh(&self.mesh);
+                self.mesh.vertices.clear();
+                self.mesh.indices.clear();
+            }
+        }
+
+        if !self.mesh.indices.is_empty() {
+            draw_mesh(&self.mesh);
+        }
+    }
+
+    // Puts a particle in a free slot (skipped when the pool is full)
+    fn spawn(&mut self, mut particle: Particle) {
+        if let Some(index) = self.free.pop() {
+            particle.life = particle.max_life;
+            self.pool[index] = particle;
+        }
+    }
+}
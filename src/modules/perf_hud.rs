@@ -0,0 +1,163 @@
+/*
+Program Details: Performance overlay for the physics and the frame rate
+
+A small box in the top-left corner showing the frames per second, how long a
+frame takes, how long the physics steps of a frame take (and how many steps
+that was), how many pieces are awake and how many Rapier has put to sleep, and
+how many colliders are in the world, and how long drawing the board took in how
+many draw calls. The numbers are averaged over half a second so they can be read.
+Batching (see renderer.rs) can be switched off and on again (batched) to compare
+what it saves. Sleeping pieces can also be dimmed on the board
+(dim_sleeping) to show what the solver is actually working on.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod perf_hud;
+
+Then with the other use commands add:
+use crate::modules::perf_hud::PerfHud;
+
+Then above the loop section to use you would go:
+    let mut perf = PerfHud::new();
+
+Then in the loop you would use (timing the physics with get_time()):
+    if is_key_pressed(KeyCode::F1) {
+        perf.visible = !perf.visible;
+    }
+    let start = get_time();
+    let steps = world.advance(sim_dt);
+    perf.update(get_frame_time(), (get_time() - start) as f32, steps);
+
+When drawing the frame:
+    let start = get_time();
+    let draw_calls = Renderer::new().batched(perf.batched).flush();
+    perf.record_draw((get_time() - start) as f32, draw_calls);
+
+To fade a sleeping piece while drawing it:
+    let alpha = perf.body_alpha(body);
+
+And after the board has been drawn:
+    perf.draw(&world);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBody;
+use crate::modules::physics::PhysicsWorld;
+
+// Seconds the readout is averaged over
+const REFRESH_SECONDS: f32 = 0.5;
+// Opacity of sleeping pieces while dim_sleeping is on
+const SLEEPING_ALPHA: f32 = 0.3;
+const FONT_SIZE: f32 = 18.0;
+const LINE_HEIGHT: f32 = 20.0;
+
+pub struct PerfHud {
+    pub visible: bool,
+    pub dim_sleeping: bool,
+    /// Whether the board is drawn with batched meshes
+    pub batched: bool,
+    // Sums since the readout was last refreshed
+    frames: u32,
+    frame_time: f32,
+    physics_time: f32,
+    steps: usize,
+    draw_time: f32,
+    draw_calls: usize,
+    // Averages shown: frames per second, milliseconds per frame, physics milliseconds and steps per frame,
+    // drawing milliseconds and draw calls per frame
+    fps: f32,
+    frame_ms: f32,
+    physics_ms: f32,
+    steps_per_frame: f32,
+    draw_ms: f32,
+    draw_calls_per_frame: f32,
+}
+
+impl Default for PerfHud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerfHud {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            dim_sleeping: false,
+            batched: true,
+            frames: 0,
+            frame_time: 0.0,
+            physics_time: 0.0,
+            steps: 0,
+            draw_time: 0.0,
+            draw_calls: 0,
+            fps: 0.0,
+            frame_ms: 0.0,
+            physics_ms: 0.0,
+            steps_per_frame: 0.0,
+            draw_ms: 0.0,
+            draw_calls_per_frame: 0.0,
+        }
+    }
+
+    /// Adds a frame that took `frame_time` seconds, `physics_time` of them in `steps` physics steps.
+    pub fn update(&mut self, frame_time: f32, physics_time: f32, steps: usize) {
+        self.frames += 1;
+        self.frame_time += frame_time;
+        self.physics_time += physics_time;
+        self.steps += steps;
+        if self.frame_time < REFRESH_SECONDS {
+            return;
+        }
+        let frames = self.frames as f32;
+        self.fps = frames / self.frame_time;
+        self.frame_ms = self.frame_time * 1000.0 / frames;
+        self.physics_ms = self.physics_time * 1000.0 / frames;
+        self.steps_per_frame = self.steps as f32 / frames;
+        self.draw_ms = self.draw_time * 1000.0 / frames;
+        self.draw_calls_per_frame = self.draw_calls as f32 / frames;
+        self.frames = 0;
+        self.frame_time = 0.0;
+        self.physics_time = 0.0;
+        self.steps = 0;
+        self.draw_time = 0.0;
+        self.draw_calls = 0;
+    }
+
+    /// Adds the drawing of a frame, which took `draw_time` seconds in `draw_calls` draw calls.
+    pub fn record_draw(&mut self, draw_time: f32, draw_calls: usize) {
+        self.draw_time += draw_time;
+        self.draw_calls += draw_calls;
+    }
+
+    /// How opaque to draw a body: sleeping pieces are faded while dim_sleeping is on.
+    pub fn body_alpha(&self, body: &RigidBody) -> f32 {
+        if self.dim_sleeping && body.is_dynamic() && body.is_sleeping() { SLEEPING_ALPHA } else { 1.0 }
+    }
+
+    /// Draws the readout for `world` in the top-left corner, if it is visible.
+    pub fn draw(&self, world: &PhysicsWorld) {
+        if !self.visible {
+            return;
+        }
+        let (awake, sleeping) = world.bodies.iter().filter(|(_, body)| body.is_dynamic()).fold((0, 0), |(awake, sleeping), (_, body)| {
+            if body.is_sleeping() { (awake, sleeping + 1) } else { (awake + 1, sleeping) }
+        });
+        let lines = [
+            format!("FPS: {:.0}  ({:.1} ms)", self.fps, self.frame_ms),
+            format!("Physics: {:.2} ms, {:.1} steps", self.physics_ms, self.steps_per_frame),
+            format!("Pieces: {} awake, {} asleep", awake, sleeping),
+            format!("Colliders: {}", world.colliders.len()),
+            format!("Drawing: {:.2} ms, {:.0} draw calls", self.draw_ms, self.draw_calls_per_frame),
+            format!("F2 dim sleeping: {}", if self.dim_sleeping { "On" } else { "Off" }),
+            format!("F3 batching: {}", if self.batched { "On" } else { "Off" }),
+        ];
+
+        let width = lines.iter().map(|line| measure_text(line, None, FONT_SIZE as u16, 1.0).width).fold(0.0, f32::max) + 16.0;
+        let height = LINE_HEIGHT * lines.len() as f32 + 10.0;
+        draw_rectangle(6.0, 6.0, width, height, Color::new(0.0, 0.0, 0.0, 0.75));
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, 14.0, 6.0 + LINE_HEIGHT * (index + 1) as f32, FONT_SIZE, GREEN);
+        }
+    }
+}
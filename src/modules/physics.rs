@@ -0,0 +1,1712 @@
+/*
+Program Details: Physics world wrapper for the Plinko board
+
+Holds every piece of Rapier state (pipeline, body/collider sets, solvers) in one
+PhysicsWorld so the game loop can reset the board, build a peg layout, spawn
+shapes and step the simulation with one call each.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod physics;
+
+Then with the other use commands add:
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+
+Then above the loop section to use you would go:
+    let board = BoardConfig::load(CLASSIC_BOARD).await.unwrap();
+    let mut world = PhysicsWorld::new();
+    world.build_board(&board);
+
+To switch to a different board (clears all shapes, rebuilds ground, pegs, walls and bins):
+    world.build_board(&other_board);
+To switch the layout under the pieces instead, leaving the ones still falling in play:
+    world.swap_board(&other_board);
+
+To drop a shape:
+    world.spawn(ShapeKind::Ball, 400.0, 50.0);
+
+To make balls dropped from now on heavy (or light, or bouncy):
+    world.ball_variant = BallVariant::Heavy;
+
+To try a drop out on a copy of the board (no pieces on it) without touching the real world:
+    let mut preview = world.clone_board();
+    let handle = preview.spawn(ShapeKind::Ball, 400.0, 50.0);
+    preview.step();
+
+To drop a burst of shapes at once (e.g. multiball), spread around a column with a sideways push each:
+    let spawns: Vec<(f32, f32, f32)> = PhysicsWorld::spread(400.0, 50.0, 10).into_iter().map(|(x, y)| (x, y, 30.0)).collect();
+    let handles = world.spawn_batch(ShapeKind::Ball, &spawns);
+
+Then in the loop you would use:
+    world.advance(get_frame_time());
+
+advance() runs as many fixed steps (integration_params.dt long, FIXED_DT unless the solver
+settings change it) as the elapsed frame time covers, so the game plays at the same speed on
+60Hz and 144Hz monitors. To draw a body smoothly between two
+physics steps, use its interpolated pose instead of its raw translation:
+    let (pos, rot) = world.render_pose(handle, body);
+
+Every body carries its EntityKind in its user_data (see entity_registry.rs): pieces their
+ShapeKind, the board's bodies the part they are (peg, wall, bin, funnel, ground, zone, sticky pad
+or chain), so anything holding just the body can tell what it is:
+    let kind = PhysicsWorld::shape_of(&world.bodies[handle]);
+Chains are the one part of the board that moves freely: their links are dynamic bodies held
+together (and to their fixed anchor) by revolute joints in the ImpulseJointSet. So "dynamic"
+doesn't mean "a piece" (the loose blocks of the board's block stacks are dynamic too); to tell
+the pieces still in play apart use:
+    let in_play = PhysicsWorld::is_piece(body);
+
+Every block of a block stack remembers the last piece that hit it (or hit the block that fell on
+it). Once it has been knocked out of place a GameEvent::BlockToppled credits that piece with it;
+blocks that fall into the bins or off the board are removed so the bins stay clear.
+
+Force fields on the board (wind, updrafts and magnets) push every piece whose bounding box
+overlaps them, at the start of every step. So does the board's pool, if it has one: every piece
+partly under its surface is lifted, slowed down and carried towards the nearer side of the board,
+the more the deeper it is.
+
+Every advance() also turns Rapier's collision events into GameEvents (peg hits, wall hits,
+pieces entering a bin and pieces settling) for the rest of the game to react to. Pieces also
+report contact forces: a hit on the board harder than IMPACT_FORCE is sent as a GameEvent::Impact,
+which is what the impact sounds play from:
+    for event in world.events() { ... }
+Landings come in a fixed order, the order the pieces reached their bins, so anything counting
+them in sequence (like combos) sees the same sequence on every run.
+
+A watchdog runs after every step: pieces whose position or velocity is no longer a number,
+that move faster than MAX_PIECE_SPEED or that end up far outside the board (e.g. pushed through
+a wall) are removed and reported as GameEvent::PieceLost, so one exploding piece can't stall the
+solver or sit in the world forever.
+
+Pieces can also wedge between two pegs (or balance on top of one) and stop above the bins. A piece
+that has moved slower than JAM_SPEED for JAM_SECONDS without reaching the bins is nudged: an
+impulse up and to one side, harder for every nudge it has needed so far, and a
+GameEvent::PieceJammed is sent with where it was, so boards that jam often can be found and fixed.
+The side and angle of each nudge are mixed from the piece's handle and the number of the nudge,
+so they look random but are the same on every run. Pieces held by a joint (picked up with the
+mouse, see grab.rs) are left alone.
+
+Pegs given hit points on the board are breakable: their colliders report contact forces, every
+impact harder than BREAK_FORCE wears their hit points down and at zero the peg is removed and a
+GameEvent::PegBroken is sent. What is left of a peg (1.0 = untouched) can be read for drawing:
+    let health = world.peg_health(collider_handle);
+
+Pegs given a kick on the board are pinball bumpers: on top of their bounce, every piece that
+starts touching one is pushed straight away from its center with an impulse worth the kick in
+speed, and a GameEvent::BumperHit is sent. To tell a bumper's collider apart for drawing:
+    let bumper = world.is_bumper(collider_handle);
+
+Sticky pads on the board hold on to the pieces that land on them: a piece touching one is
+stopped and turned into a fixed body right away (GameEvent::PieceStuck), and once the pad's
+delay has run out it is removed and a GameEvent::StickyPayout is sent with the pad's bonus.
+How far along a stuck piece's wait is (0.0 just stuck, 1.0 about to pay) can be read for drawing:
+    let progress = world.stick_progress(piece_handle);
+
+A snapshot saves everything the simulation needs to carry on exactly where it was (bodies with
+their positions and velocities, colliders, contacts, moving and breakable pegs, stuck pieces), and restoring
+it puts it back. The world's settings (gravity, solver, materials) aren't part of it: the ones
+the world has are kept, and the restored board is given its materials:
+    let snapshot = world.snapshot();
+    world.restore(snapshot);
+
+Every collider is put on the collision layer of its body's kind (see layers.rs): the board on
+Layers::Board, zones on Layers::Sensors and pieces on Layers::Pieces. A piece dropped only to try
+something out can be moved to Layers::Ghosts so it never touches the live pieces:
+    world.set_layer(handle, Layers::Ghosts);
+
+The world keeps a Rapier QueryPipeline up to date (every step, and whenever the board is built
+or a piece spawned) for asking what is where without stepping: the first thing a ray hits, the
+bodies in a box and the nearest collider to a point. The sensors (zones) are left out of ray
+casts and point projections, which only see solid things:
+    if let Some(hit) = world.raycast(point![x, y], vector![0.0, 1.0]) { ... hit.body, hit.distance }
+    let bodies = world.bodies_in_aabb(Aabb::new(point![x0, y0], point![x1, y1]));
+    if let Some(nearest) = world.point_project(point![mouse_x, mouse_y]) { ... nearest.distance }
+After moving bodies or colliders by hand, bring the queries up to date with:
+    world.update_queries();
+
+The pegs, walls, ground and pieces can be given material presets (see materials.rs); every
+collider remembers the values it was built with, so set_materials can switch a part to a preset
+and back in place, on the colliders already in the world:
+    world.set_materials(&settings.materials);
+*/
+
+use crate::modules::board::{BlockStackConfig, BoardConfig, BoxConfig, ChainConfig, ForceEffect, PegConfig, PegMotion, PegShape, SegmentConfig, MAX_BLOCKS};
+use crate::modules::entity_registry::EntityKind;
+use crate::modules::events::{GameEvent, ImpactKind, LostReason};
+use crate::modules::layers::Layers;
+use crate::modules::materials::{BoardPart, Material, Materials};
+use rapier2d::crossbeam::channel::{unbounded, Receiver};
+use rapier2d::prelude::*;
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+// Default length of one physics step in seconds: physics runs at 120 steps per second
+pub const FIXED_DT: f32 = 1.0 / 120.0;
+// Velocity iterations (solver substeps) Rapier runs by default, and impacts CCD resolves per piece in a step
+pub const VELOCITY_ITERATIONS: usize = 4;
+pub const CCD_SUBSTEPS: usize = 2;
+// Longest frame the accumulator will catch up on, so a stall doesn't trigger hundreds of steps
+const MAX_FRAME_TIME: f32 = 0.25;
+// Speed below which a piece inside a bin counts as settled
+const LANDED_SPEED: f32 = 20.0;
+// Default bounciness of dropped pieces: they keep 40% of their energy after each bounce
+pub const PIECE_RESTITUTION: f32 = 0.4;
+// Default air resistance on the movement and spin of dropped pieces
+pub const PIECE_DAMPING: f32 = 1.0;
+// Deepest overlap (in pixels) the solver pushes apart in one step; a burst of pieces spawned
+// close together then separates smoothly instead of flinging pieces across the board
+const MAX_PENETRATION_CORRECTION: f32 = 2.0;
+// Gap between neighbouring pieces in a spread() burst and how many go in each row
+const SPREAD_SPACING: f32 = 18.0;
+const SPREAD_ROW_LENGTH: usize = 5;
+// Collider user_data tags: golden pegs, multiplier zone i is tagged ZONE_TAG + i and a ball
+// of variant i is tagged BALL_TAG + i (far above any zone tag)
+const GOLDEN_PEG_TAG: u128 = 1;
+const ZONE_TAG: u128 = 2;
+const BALL_TAG: u128 = 1 << 64;
+// Contact force (summed over the contact points) a breakable peg has to take in one step to lose
+// hit points. Even a heavy ball resting on a peg pushes below it; a ball falling onto one is well above
+const BREAK_FORCE: f32 = 1.0e6;
+// Impulse (force x step length) that takes one hit point off a breakable peg: about what a normal
+// ball hitting a peg at 250 px/s gives it. Heavy balls do five times the damage
+const DAMAGE_IMPULSE: f32 = 5.0e4;
+// Contact force (summed over the contact points) a piece's hit has to pass in one step to be sent as
+// a GameEvent::Impact: about twice a normal ball's weight, so a piece resting on something is quiet
+const IMPACT_FORCE: f32 = 5.0e5;
+// Fastest a piece can move before the watchdog treats it as blown up (pixels per second);
+// a drop from the top of the board lands at about 1000
+pub const MAX_PIECE_SPEED: f32 = 4000.0;
+// How far past the ground and walls a piece can be before the watchdog counts it as lost
+const OUT_OF_BOUNDS_MARGIN: f32 = 200.0;
+// Highest a piece can fly above the board before it counts as lost
+const OUT_OF_BOUNDS_TOP: f32 = -2000.0;
+// A piece above the bins moving slower than JAM_SPEED (pixels per second) for JAM_SECONDS is jammed
+const JAM_SPEED: f32 = 5.0;
+const JAM_SECONDS: f32 = 1.5;
+// Speed a nudge gives a jammed piece, times the number of nudges it has needed (up to MAX_NUDGE_SCALE)
+const NUDGE_SPEED: f32 = 120.0;
+const MAX_NUDGE_SCALE: u32 = 4;
+// Most of gravity a pool's lift can cancel: even a piece lighter than the water keeps sinking,
+// slowly, so nothing floats on the surface forever instead of landing in a bin
+const MAX_POOL_LIFT: f32 = 0.9;
+// Air resistance on the movement and spin of chain links, so a struck chain swings for a while
+// and then hangs still again
+const CHAIN_DAMPING: f32 = 0.5;
+// How far (as a share of its size) a block must be pushed from where it was stacked, or how far
+// it must tip over (in radians), to count as toppled
+const TOPPLE_DISTANCE: f32 = 0.5;
+const TOPPLE_ANGLE: f32 = 0.5;
+// How far the ledge under a block stack reaches past the stack either side, and its half thickness
+const LEDGE_OVERHANG: f32 = 4.0;
+const LEDGE_HALF_HEIGHT: f32 = 3.0;
+
+/// The kinds of dynamic pieces the player can drop.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShapeKind {
+    Ball,
+    Square,
+    Triangle,
+}
+
+impl ShapeKind {
+    pub const ALL: [ShapeKind; 3] = [ShapeKind::Ball, ShapeKind::Square, ShapeKind::Triangle];
+}
+
+/// Physical make-up of a ball: density feeds its mass, restitution its bounce.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BallSpec {
+    pub density: f32,
+    // None keeps the world's piece_restitution (the Bounciness setting)
+    pub restitution: Option<f32>,
+}
+
+/// The kinds of ball the player can choose between.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BallVariant {
+    #[default]
+    Normal,
+    // Dense: plows through the pegs and gets knocked aside less
+    Heavy,
+    // Barely any mass, so damping makes it drift down slowly
+    Light,
+    // Keeps most of its energy on every bounce
+    Bouncy,
+}
+
+impl BallVariant {
+    pub const ALL: [BallVariant; 4] = [BallVariant::Normal, BallVariant::Heavy, BallVariant::Light, BallVariant::Bouncy];
+
+    pub fn spec(self) -> BallSpec {
+        match self {
+            BallVariant::Normal => BallSpec { density: 1.0, restitution: None },
+            BallVariant::Heavy => BallSpec { density: 5.0, restitution: None },
+            BallVariant::Light => BallSpec { density: 0.2, restitution: None },
+            BallVariant::Bouncy => BallSpec { density: 1.0, restitution: Some(0.9) },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BallVariant::Normal => "Normal",
+            BallVariant::Heavy => "Heavy",
+            BallVariant::Light => "Light",
+            BallVariant::Bouncy => "Bouncy",
+        }
+    }
+}
+
+/// The first solid collider a ray cast into the world hits.
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub collider: ColliderHandle,
+    pub body: Option<RigidBodyHandle>,
+    /// Where the ray hits it
+    pub point: Point<Real>,
+    /// The collider's surface normal there
+    pub normal: Vector<Real>,
+    /// Distance from the ray's origin to `point`
+    pub distance: Real,
+}
+
+/// The solid collider nearest to a point, and the point on it nearest to the point.
+#[derive(Clone, Copy, Debug)]
+pub struct PointHit {
+    pub collider: ColliderHandle,
+    pub body: Option<RigidBodyHandle>,
+    /// The nearest point of the collider (the point itself when it is inside)
+    pub point: Point<Real>,
+    /// Distance from the point to the collider, 0.0 when it is inside
+    pub distance: Real,
+    pub inside: bool,
+}
+
+// A peg animated by its PegMotion, remembered with the position it moves around
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct MovingPeg {
+    handle: RigidBodyHandle,
+    x: f32,
+    y: f32,
+    motion: PegMotion,
+}
+
+// Hit points of a breakable peg: what it has left and what it started with
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct PegHitPoints {
+    left: f32,
+    max: f32,
+}
+
+// A loose block of a block stack: where it was stacked, its size, what it is worth, whether it has
+// been knocked over yet and the last piece that hit it (directly or through other blocks)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct Block {
+    home_x: f32,
+    home_y: f32,
+    size: f32,
+    points: u32,
+    toppled: bool,
+    hit_by: Option<RigidBodyHandle>,
+}
+
+// How long a piece above the bins has been barely moving, and how many nudges it has needed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+struct Jam {
+    seconds: f32,
+    nudges: u32,
+}
+
+// A piece held by a sticky pad: the pad (index into the board's sticky pads), the bonus it pays
+// and the seconds it waits in all and has left. The bonus is kept here so a piece stuck before
+// a board swap still pays what its pad promised
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct StuckPiece {
+    pad: usize,
+    bonus: f32,
+    delay: f32,
+    left: f32,
+}
+
+/// Every piece of Rapier state needed to simulate the board.
+pub struct PhysicsWorld {
+    // Downward pull applied to all dynamic bodies
+    pub gravity: Vector<Real>,
+    // Timestep duration, damping and other solver properties
+    pub integration_params: IntegrationParameters,
+    pub pipeline: PhysicsPipeline,
+    pub island_manager: IslandManager,
+    pub broad_phase: BroadPhase,
+    pub narrow_phase: NarrowPhase,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub ccd: CCDSolver,
+    // Answers ray casts and point and box queries (see raycast, bodies_in_aabb and point_project)
+    pub query_pipeline: QueryPipeline,
+    // The board the world was last built from (None until build_board is called)
+    pub board: Option<BoardConfig>,
+    // Bounciness and air resistance given to pieces spawned from now on
+    pub piece_restitution: f32,
+    pub piece_damping: f32,
+    // Variant given to balls spawned from now on
+    pub ball_variant: BallVariant,
+    // Multiplies the rows and columns of the board's peg grids the next time it is built
+    pub peg_density: f32,
+    // Presets given to the parts of the board (change them with set_materials)
+    materials: Materials,
+    // Which part of the board each collider is, and the material it was built with
+    parts: HashMap<ColliderHandle, (BoardPart, Material)>,
+    // Frame time not yet consumed by a fixed step
+    accumulator: f32,
+    // Simulated seconds since the board was built; drives the moving pegs
+    time: f32,
+    moving_pegs: Vec<MovingPeg>,
+    // Every body the board was built from (ground, pegs, walls, bins, segments and zones), so
+    // swap_board can take the board away without touching the pieces
+    board_bodies: Vec<RigidBodyHandle>,
+    // Pose of each dynamic body before the latest step, used to interpolate rendering
+    previous_poses: HashMap<RigidBodyHandle, Isometry<Real>>,
+    // Collects the collision events Rapier emits during a step
+    event_collector: ChannelEventCollector,
+    collision_recv: Receiver<CollisionEvent>,
+    contact_force_recv: Receiver<ContactForceEvent>,
+    // Hit points of the breakable pegs, by collider
+    peg_hit_points: HashMap<ColliderHandle, PegHitPoints>,
+    // Kick (extra speed in pixels per second) of the bumper pegs, by collider
+    bumpers: HashMap<ColliderHandle, f32>,
+    // Index into the board's sticky pads of each sticky pad collider
+    sticky_pads: HashMap<ColliderHandle, usize>,
+    // Pieces held by a sticky pad, waiting to pay out
+    stuck: HashMap<RigidBodyHandle, StuckPiece>,
+    // The loose blocks of the board's block stacks still on the board
+    blocks: HashMap<RigidBodyHandle, Block>,
+    // Pieces above the bins that are barely moving, or have been nudged loose before
+    jams: HashMap<RigidBodyHandle, Jam>,
+    // Game events from the steps taken by the latest advance()
+    events: Vec<GameEvent>,
+    // Pieces currently below the top of the bin dividers, each with the number it got on the way
+    // in (counting up, so it orders them by arrival), and the ones of those that have settled
+    in_bin: HashMap<RigidBodyHandle, u64>,
+    bin_arrivals: u64,
+    settled: HashSet<RigidBodyHandle>,
+}
+
+/// The saved state of a PhysicsWorld, taken by snapshot() and put back by restore().
+/// Serializable with serde (rapier's sets have map keys JSON can't hold, so use a binary format).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WorldSnapshot {
+    island_manager: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd: CCDSolver,
+    board: Option<BoardConfig>,
+    ball_variant: BallVariant,
+    parts: HashMap<ColliderHandle, (BoardPart, Material)>,
+    time: f32,
+    moving_pegs: Vec<MovingPeg>,
+    board_bodies: Vec<RigidBodyHandle>,
+    peg_hit_points: HashMap<ColliderHandle, PegHitPoints>,
+    bumpers: HashMap<ColliderHandle, f32>,
+    sticky_pads: HashMap<ColliderHandle, usize>,
+    stuck: HashMap<RigidBodyHandle, StuckPiece>,
+    blocks: HashMap<RigidBodyHandle, Block>,
+    jams: HashMap<RigidBodyHandle, Jam>,
+    in_bin: HashMap<RigidBodyHandle, u64>,
+    bin_arrivals: u64,
+    settled: HashSet<RigidBodyHandle>,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicsWorld {
+    /// Creates an empty world with the game's gravity (x=0, y=800 strong downward pull).
+    pub fn new() -> Self {
+        // Contact forces are reported by pieces above IMPACT_FORCE, and by breakable pegs above BREAK_FORCE
+        let (collision_send, collision_recv) = unbounded();
+        let (contact_force_send, contact_force_recv) = unbounded();
+        Self {
+            gravity: vector![0.0, 800.0],
+            integration_params: IntegrationParameters {
+                dt: FIXED_DT,
+                max_penetration_correction: MAX_PENETRATION_CORRECTION,
+                // Lets CCD resolve more than one impact per step when a burst of fast pieces crowds together
+                max_ccd_substeps: CCD_SUBSTEPS,
+                ..Default::default()
+            },
+            pipeline: PhysicsPipeline::new(),
+            island_manager: IslandManager::new(),
+            broad_phase: BroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
+            board: None,
+            piece_restitution: PIECE_RESTITUTION,
+            piece_damping: PIECE_DAMPING,
+            ball_variant: BallVariant::Normal,
+            peg_density: 1.0,
+            materials: Materials::default(),
+            parts: HashMap::new(),
+            accumulator: 0.0,
+            time: 0.0,
+            moving_pegs: Vec::new(),
+            board_bodies: Vec::new(),
+            previous_poses: HashMap::new(),
+            event_collector: ChannelEventCollector::new(collision_send, contact_force_send),
+            collision_recv,
+            contact_force_recv,
+            peg_hit_points: HashMap::new(),
+            bumpers: HashMap::new(),
+            sticky_pads: HashMap::new(),
+            stuck: HashMap::new(),
+            blocks: HashMap::new(),
+            jams: HashMap::new(),
+            events: Vec::new(),
+            in_bin: HashMap::new(),
+            bin_arrivals: 0,
+            settled: HashSet::new(),
+        }
+    }
+
+    /// Removes every body, collider and joint and resets the solver state.
+    pub fn reset(&mut self) {
+        self.pipeline = PhysicsPipeline::new();
+        self.island_manager = IslandManager::new();
+        self.broad_phase = BroadPhase::new();
+        self.narrow_phase = NarrowPhase::new();
+        self.bodies = RigidBodySet::new();
+        self.colliders = ColliderSet::new();
+        self.joints = ImpulseJointSet::new();
+        self.multibody_joints = MultibodyJointSet::new();
+        self.ccd = CCDSolver::new();
+        self.query_pipeline = QueryPipeline::new();
+        self.previous_poses.clear();
+        self.time = 0.0;
+        self.moving_pegs.clear();
+        self.board_bodies.clear();
+        self.events.clear();
+        self.in_bin.clear();
+        self.settled.clear();
+        self.peg_hit_points.clear();
+        self.bumpers.clear();
+        self.sticky_pads.clear();
+        self.stuck.clear();
+        self.blocks.clear();
+        self.jams.clear();
+        self.parts.clear();
+        // Events still queued belong to the old colliders
+        while self.collision_recv.try_recv().is_ok() {}
+        while self.contact_force_recv.try_recv().is_ok() {}
+    }
+
+    /// Clears the world and builds a fresh board: ground, pegs, walls and bins.
+    pub fn build_board(&mut self, board: &BoardConfig) {
+        self.reset();
+        self.create_board(board);
+    }
+
+    /// Replaces the board with `board` but keeps every piece on it where it is, still moving:
+    /// only the bodies of the old board are removed before the new one is built.
+    /// Pieces the new pegs land on top of are pushed out by the solver; pieces stuck to a sticky
+    /// pad stay where they are and still pay out.
+    pub fn swap_board(&mut self, board: &BoardConfig) {
+        for handle in std::mem::take(&mut self.board_bodies) {
+            self.remove_body(handle);
+        }
+        self.moving_pegs.clear();
+        self.peg_hit_points.clear();
+        self.bumpers.clear();
+        self.sticky_pads.clear();
+        self.create_board(board);
+        // Pieces asleep on the old ground or in the old bins have nothing under them any more
+        for (_, body) in self.bodies.iter_mut() {
+            if body.is_dynamic() {
+                body.wake_up(true);
+            }
+        }
+    }
+
+    // Builds the board's bodies: ground, pegs, walls, bins, funnels, zones, sticky pads, chains and
+    // block stacks, each tagged with its
+    // kind (which also decides the render layer it is drawn on, see renderer.rs)
+    fn create_board(&mut self, board: &BoardConfig) {
+        self.create_box(&board.ground, BoardPart::Ground, EntityKind::Ground);
+        for peg in board.with_peg_density(self.peg_density).all_pegs() {
+            self.create_peg(&peg);
+        }
+        for wall in &board.walls {
+            self.create_box(wall, BoardPart::Walls, EntityKind::Wall);
+        }
+        self.create_bins(board);
+        for segment in &board.segments {
+            self.create_segment(segment);
+        }
+        for (index, zone) in board.zones.iter().enumerate() {
+            // Sensors report pieces passing through without pushing them around
+            let collider = ColliderBuilder::cuboid(zone.half_width, zone.half_height)
+                .sensor(true)
+                .user_data(ZONE_TAG + index as u128)
+                .build();
+            self.insert_fixed(zone.x, zone.y, collider, EntityKind::Zone);
+        }
+        for (index, pad) in board.sticky_pads.iter().enumerate() {
+            // No bounce, so a piece touching a pad stays on it
+            let collider = ColliderBuilder::cuboid(pad.half_width, pad.half_height).restitution(0.0).build();
+            let handle = self.insert_fixed(pad.x, pad.y, collider, EntityKind::StickyPad);
+            self.sticky_pads.insert(self.bodies[handle].colliders()[0], index);
+        }
+        for chain in &board.chains {
+            self.create_chain(chain);
+        }
+        // Blocks past MAX_BLOCKS are left out (validate() turns such boards down when they're loaded)
+        let mut budget = MAX_BLOCKS;
+        for stack in &board.block_stacks {
+            budget -= self.create_block_stack(stack, budget);
+        }
+        self.board = Some(board.clone());
+        self.update_queries();
+    }
+
+    /// Changes the payout table of the board the world was built from. Payouts only matter to
+    /// the game, so no bodies change.
+    pub fn set_payouts(&mut self, payouts: &[f32]) {
+        if let Some(board) = self.board.as_mut() {
+            board.payouts = payouts.to_vec();
+        }
+    }
+
+    /// A copy of the board as it is right now (pegs, walls, bins, zones, moving pegs mid-swing and
+    /// chains mid-swing) with none of the pieces on it, for trying out a drop without touching the
+    /// real world. Only the bodies, colliders, joints, bumpers and sticky pads are copied; the
+    /// solver state starts fresh.
+    pub fn clone_board(&self) -> PhysicsWorld {
+        let mut world = PhysicsWorld::new();
+        world.gravity = self.gravity;
+        world.integration_params = self.integration_params;
+        world.piece_restitution = self.piece_restitution;
+        world.piece_damping = self.piece_damping;
+        world.ball_variant = self.ball_variant;
+        world.peg_density = self.peg_density;
+        world.materials = self.materials.clone();
+        world.board = self.board.clone();
+        world.time = self.time;
+
+        let mut handles = HashMap::new();
+        // Everything but the pieces (stuck pieces are fixed bodies, and chain links dynamic ones)
+        for (handle, body) in self.bodies.iter().filter(|(_, body)| !EntityKind::of(body).is_some_and(EntityKind::is_piece)) {
+            let copy = world.bodies.insert(body.clone());
+            for collider in body.colliders() {
+                let collider_copy = world.colliders.insert_with_parent(self.colliders[*collider].clone(), copy, &mut world.bodies);
+                if let Some(part) = self.parts.get(collider) {
+                    world.parts.insert(collider_copy, *part);
+                }
+                if let Some(kick) = self.bumpers.get(collider) {
+                    world.bumpers.insert(collider_copy, *kick);
+                }
+                if let Some(pad) = self.sticky_pads.get(collider) {
+                    world.sticky_pads.insert(collider_copy, *pad);
+                }
+            }
+            handles.insert(handle, copy);
+            world.board_bodies.push(copy);
+        }
+        for (_, joint) in self.joints.iter() {
+            if let (Some(body1), Some(body2)) = (handles.get(&joint.body1), handles.get(&joint.body2)) {
+                world.joints.insert(*body1, *body2, joint.data, true);
+            }
+        }
+        world.moving_pegs = self
+            .moving_pegs
+            .iter()
+            .filter_map(|peg| Some(MovingPeg { handle: *handles.get(&peg.handle)?, ..*peg }))
+            .collect();
+        world.update_queries();
+        world
+    }
+
+    /// Spawns a dynamic piece of the given kind at (x, y) and returns its body handle.
+    /// The body's user_data is tagged with the kind (see shape_of).
+    pub fn spawn(&mut self, kind: ShapeKind, x: f32, y: f32) -> RigidBodyHandle {
+        let handle = match kind {
+            ShapeKind::Ball => self.spawn_ball(x, y),
+            ShapeKind::Square => self.spawn_square_as_convex(x, y),
+            ShapeKind::Triangle => self.spawn_triangle(x, y),
+        };
+        EntityKind::Piece(kind).assign(&mut self.bodies[handle]);
+        // So the next spawn_point_clear sees it, even before the next step. Only the new piece's colliders
+        // are added, so spawning many pieces in a row doesn't rebuild the pipeline for every one of them
+        let added = self.bodies[handle].colliders().to_vec();
+        self.query_pipeline.update_incremental(&self.colliders, &added, &[], true);
+        handle
+    }
+
+    /// True if a piece of `kind` spawned at (x, y) wouldn't overlap any piece already in play.
+    /// Spawning into another piece makes the solver force them apart, which launches both.
+    pub fn spawn_point_clear(&self, kind: ShapeKind, x: f32, y: f32) -> bool {
+        let shape = piece_shape(kind);
+        let position = Isometry::translation(x, y);
+        let is_piece = |_: ColliderHandle, collider: &Collider| collider.parent().is_some_and(|body| Self::is_piece(&self.bodies[body]));
+        let filter = QueryFilter::default().exclude_sensors().predicate(&is_piece);
+        let mut clear = true;
+        self.query_pipeline.intersections_with_shape(&self.bodies, &self.colliders, &position, shape.as_ref(), filter, |_| {
+            clear = false;
+            false // One is enough
+        });
+        clear
+    }
+
+    /// Rebuilds the query pipeline from where every collider is now. Stepping and building the
+    /// board do this already; call it after moving bodies or colliders by hand.
+    pub fn update_queries(&mut self) {
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+    }
+
+    /// The first solid collider on the ray from `origin` in direction `dir` (of any length), or
+    /// None if it hits nothing.
+    pub fn raycast(&self, origin: Point<Real>, dir: Vector<Real>) -> Option<RayHit> {
+        let dir = dir.try_normalize(0.000001)?;
+        let ray = Ray::new(origin, dir);
+        let filter = QueryFilter::default().exclude_sensors();
+        let (collider, hit) = self.query_pipeline.cast_ray_and_get_normal(&self.bodies, &self.colliders, &ray, Real::MAX, true, filter)?;
+        Some(RayHit {
+            collider,
+            body: self.colliders.get(collider).and_then(|collider| collider.parent()),
+            point: ray.point_at(hit.toi),
+            normal: hit.normal,
+            distance: hit.toi,
+        })
+    }
+
+    /// Every body with a collider (sensors included) whose bounding box overlaps `aabb`, each once.
+    pub fn bodies_in_aabb(&self, aabb: Aabb) -> Vec<RigidBodyHandle> {
+        let mut bodies = Vec::new();
+        self.query_pipeline.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+            let body = self.colliders.get(*handle).and_then(|collider| collider.parent());
+            if let Some(body) = body.filter(|body| !bodies.contains(body)) {
+                bodies.push(body);
+            }
+            true // Keep looking
+        });
+        bodies
+    }
+
+    /// The solid collider nearest to `point` and the point on it nearest to `point`, or None if
+    /// the world has no solid colliders.
+    pub fn point_project(&self, point: Point<Real>) -> Option<PointHit> {
+        let filter = QueryFilter::default().exclude_sensors();
+        let (collider, projection) = self.query_pipeline.project_point(&self.bodies, &self.colliders, &point, true, filter)?;
+        Some(PointHit {
+            collider,
+            body: self.colliders.get(collider).and_then(|collider| collider.parent()),
+            point: projection.point,
+            distance: if projection.is_inside { 0.0 } else { (projection.point - point).norm() },
+            inside: projection.is_inside,
+        })
+    }
+
+    /// Moves every collider of a body to `layer`, e.g. a preview piece to Layers::Ghosts.
+    pub fn set_layer(&mut self, handle: RigidBodyHandle, layer: Layers) {
+        if let Some(body) = self.bodies.get(handle) {
+            for collider in body.colliders() {
+                self.colliders[*collider].set_collision_groups(layer.groups());
+            }
+        }
+    }
+
+    /// Spawns a piece at (x, y) already moving with velocity (vx, vy).
+    pub fn spawn_with_velocity(&mut self, kind: ShapeKind, x: f32, y: f32, vx: f32, vy: f32) -> RigidBodyHandle {
+        let handle = self.spawn(kind, x, y);
+        self.bodies[handle].set_linvel(vector![vx, vy], true);
+        handle
+    }
+
+    /// Spawns one piece of `kind` for every (x, y, sideways speed) and returns their handles in order.
+    pub fn spawn_batch(&mut self, kind: ShapeKind, spawns: &[(f32, f32, f32)]) -> Vec<RigidBodyHandle> {
+        spawns.iter().map(|&(x, y, vx)| self.spawn_with_velocity(kind, x, y, vx, 0.0)).collect()
+    }
+
+    /// Positions for `count` pieces spread around (x, y): rows of SPREAD_ROW_LENGTH pieces
+    /// SPREAD_SPACING apart, each row shifted half a gap from the one above so they don't stack.
+    pub fn spread(x: f32, y: f32, count: usize) -> Vec<(f32, f32)> {
+        (0..count)
+            .map(|index| {
+                let (row, col) = (index / SPREAD_ROW_LENGTH, index % SPREAD_ROW_LENGTH);
+                let row_len = (count - row * SPREAD_ROW_LENGTH).min(SPREAD_ROW_LENGTH);
+                let shift = if row % 2 == 1 { SPREAD_SPACING / 2.0 } else { 0.0 };
+                let offset = (col as f32 - (row_len - 1) as f32 / 2.0) * SPREAD_SPACING + shift;
+                (x + offset, y - row as f32 * SPREAD_SPACING)
+            })
+            .collect()
+    }
+
+    /// Half the width of a spread() burst, for keeping it clear of the walls.
+    pub fn spread_half_width() -> f32 {
+        (SPREAD_ROW_LENGTH as f32 - 1.0) / 2.0 * SPREAD_SPACING + SPREAD_SPACING / 2.0
+    }
+
+    /// Removes a body along with its colliders and joints.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) {
+        if let Some(body) = self.bodies.get(handle) {
+            for collider in body.colliders() {
+                self.parts.remove(collider);
+            }
+        }
+        self.bodies.remove(handle, &mut self.island_manager, &mut self.colliders, &mut self.joints, &mut self.multibody_joints, true);
+        self.previous_poses.remove(&handle);
+        self.in_bin.remove(&handle);
+        self.settled.remove(&handle);
+        self.stuck.remove(&handle);
+        self.blocks.remove(&handle);
+        self.jams.remove(&handle);
+    }
+
+    /// Adds the elapsed frame time to the accumulator and runs as many fixed steps as it covers.
+    /// Returns the number of steps taken this frame.
+    pub fn advance(&mut self, frame_time: f32) -> usize {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+
+        self.events.clear();
+        let mut steps = 0;
+        let dt = self.integration_params.dt;
+        while self.accumulator >= dt {
+            self.store_previous_poses();
+            self.step();
+            self.collect_events();
+            self.release_stuck();
+            self.check_blocks();
+            self.watchdog();
+            self.free_jams();
+            self.accumulator -= dt;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Game events from the physics steps taken by the latest advance(), in the order they happened.
+    /// Within a step, pieces entering bins come in the order they crossed in, then the pieces that
+    /// settled in the order they entered their bins, so the same landings are always told in the
+    /// same order.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    // Turns the step's collision events and piece positions into GameEvents
+    fn collect_events(&mut self) {
+        while let Ok(event) = self.collision_recv.try_recv() {
+            if let CollisionEvent::Started(collider1, collider2, _) = event {
+                if let Some(hit) = self.hit_event(collider1, collider2) {
+                    self.events.push(hit);
+                }
+                if let Some(kick) = self.kick_from_bumper(collider1, collider2) {
+                    self.events.push(kick);
+                }
+                if let Some(stuck) = self.stick_to_pad(collider1, collider2) {
+                    self.events.push(stuck);
+                }
+                self.pass_block_hit(collider1, collider2);
+            }
+        }
+        while let Ok(event) = self.contact_force_recv.try_recv() {
+            if let Some(impact) = self.impact_event(&event) {
+                self.events.push(impact);
+            }
+            self.damage_peg(event);
+        }
+
+        let Some(board) = self.board.as_ref() else {
+            return;
+        };
+        let bins_top = board.bins_top();
+        // (piece, bin, depth below the dividers' top) of the pieces that crossed into a bin this
+        // step, and (arrival, piece, bin) of those that came to rest
+        let mut entered = Vec::new();
+        let mut landed = Vec::new();
+        for (handle, body) in self.bodies.iter() {
+            if !Self::is_piece(body) {
+                continue;
+            }
+            let pos = body.translation();
+            if pos.y <= bins_top {
+                // Bounced back out (or not there yet) - it can enter and settle again
+                self.in_bin.remove(&handle);
+                self.settled.remove(&handle);
+                continue;
+            }
+
+            let bin = board.bin_index(pos.x);
+            let at_rest = body.is_sleeping() || body.linvel().norm() < LANDED_SPEED;
+            match self.in_bin.get(&handle) {
+                None => entered.push((handle, bin, pos.y - bins_top, at_rest)),
+                Some(&arrival) => {
+                    if at_rest && self.settled.insert(handle) {
+                        landed.push((arrival, handle, bin));
+                    }
+                }
+            }
+        }
+
+        // Pieces crossing in the same step are numbered deepest first: it got there first
+        entered.sort_by(|a, b| b.2.total_cmp(&a.2));
+        for (handle, bin, _, at_rest) in entered {
+            self.bin_arrivals += 1;
+            self.in_bin.insert(handle, self.bin_arrivals);
+            self.events.push(GameEvent::BinEntered { piece: handle, bin });
+            if at_rest && self.settled.insert(handle) {
+                landed.push((self.bin_arrivals, handle, bin));
+            }
+        }
+        // ... and settle in the order they arrived, so landings are told in a fixed order
+        landed.sort_by_key(|(arrival, _, _)| *arrival);
+        for (_, handle, bin) in landed {
+            self.events.push(GameEvent::Settled { piece: handle, bin });
+        }
+    }
+
+    // Removes the pieces that have blown up or left the board, sending a PieceLost for each
+    fn watchdog(&mut self) {
+        let lost: Vec<(RigidBodyHandle, LostReason, u128)> = self
+            .bodies
+            .iter()
+            .filter(|(_, body)| Self::is_piece(body))
+            .filter_map(|(handle, body)| self.lost_reason(body).map(|reason| (handle, reason, body.user_data)))
+            .collect();
+        for (piece, reason, user_data) in lost {
+            self.remove_body(piece);
+            self.events.push(GameEvent::PieceLost { piece, reason, user_data });
+        }
+    }
+
+    // Nudges the pieces that have been barely moving above the bins for JAM_SECONDS, sending a
+    // PieceJammed for each
+    fn free_jams(&mut self) {
+        let Some(bins_top) = self.board.as_ref().map(BoardConfig::bins_top) else {
+            return;
+        };
+        let dt = self.integration_params.dt;
+        let mut jammed = Vec::new();
+        for (handle, body) in self.bodies.iter() {
+            if !Self::is_piece(body) || body.translation().y > bins_top {
+                self.jams.remove(&handle);
+                continue;
+            }
+            // A piece held by the mouse is where it is on purpose
+            if self.joints.attached_joints(handle).next().is_some() {
+                continue;
+            }
+            let jam = self.jams.entry(handle).or_default();
+            if !body.is_sleeping() && body.linvel().norm() >= JAM_SPEED {
+                jam.seconds = 0.0;
+                continue;
+            }
+            jam.seconds += dt;
+            if jam.seconds >= JAM_SECONDS {
+                jam.seconds = 0.0;
+                jam.nudges += 1;
+                jammed.push((handle, jam.nudges));
+            }
+        }
+        for (piece, nudges) in jammed {
+            let body = &mut self.bodies[piece];
+            let speed = NUDGE_SPEED * nudges.min(MAX_NUDGE_SCALE) as f32;
+            body.apply_impulse(nudge_direction(piece, nudges) * speed * body.mass(), true);
+            let pos = *body.translation();
+            self.events.push(GameEvent::PieceJammed { piece, x: pos.x, y: pos.y, nudges });
+        }
+    }
+
+    // Why the watchdog should remove a piece, or None if it is fine
+    fn lost_reason(&self, body: &RigidBody) -> Option<LostReason> {
+        let pos = body.translation();
+        let vel = body.linvel();
+        let valid = pos.x.is_finite() && pos.y.is_finite() && body.rotation().angle().is_finite() && vel.x.is_finite() && vel.y.is_finite() && body.angvel().is_finite();
+        if !valid {
+            return Some(LostReason::Invalid);
+        }
+        if vel.norm() > MAX_PIECE_SPEED {
+            return Some(LostReason::TooFast);
+        }
+        let ground = &self.board.as_ref()?.ground;
+        let outside = pos.y > ground.y + ground.half_height + OUT_OF_BOUNDS_MARGIN
+            || pos.y < OUT_OF_BOUNDS_TOP
+            || pos.x < ground.x - ground.half_width - OUT_OF_BOUNDS_MARGIN
+            || pos.x > ground.x + ground.half_width + OUT_OF_BOUNDS_MARGIN;
+        outside.then_some(LostReason::OutOfBounds)
+    }
+
+    // A peg or wall hit if one collider belongs to a dropped piece and the other to the board:
+    // a peg hit for the bodies tagged as pegs or chains, a wall hit for the ground, walls, bin
+    // dividers and funnels
+    fn hit_event(&self, collider1: ColliderHandle, collider2: ColliderHandle) -> Option<GameEvent> {
+        let parent = |collider| self.colliders.get(collider).and_then(|c| c.parent());
+        let (handle1, handle2) = (parent(collider1)?, parent(collider2)?);
+        let (body1, body2) = (self.bodies.get(handle1)?, self.bodies.get(handle2)?);
+
+        let (piece, piece_body, other, other_body, other_collider) = if Self::is_piece(body1) && !Self::is_piece(body2) {
+            (handle1, body1, handle2, body2, collider2)
+        } else if Self::is_piece(body2) && !Self::is_piece(body1) {
+            (handle2, body2, handle1, body1, collider1)
+        } else {
+            return None;
+        };
+
+        let speed = piece_body.linvel().norm();
+        let collider = self.colliders.get(other_collider)?;
+        if collider.is_sensor() {
+            Some(GameEvent::ZoneEntered { piece, zone: collider.user_data.checked_sub(ZONE_TAG)? as usize })
+        } else if matches!(EntityKind::of(other_body), Some(EntityKind::Peg | EntityKind::Chain)) {
+            Some(GameEvent::PegHit { piece, peg: other, speed, golden: collider.user_data == GOLDEN_PEG_TAG })
+        } else {
+            // Funnels and ramps sound and count like walls
+            Some(GameEvent::WallHit { piece, speed })
+        }
+    }
+
+    // An impact if a hard contact force was between a dropped piece and the board: on a peg for the
+    // bodies tagged as pegs or chains, on a wall for everything else
+    fn impact_event(&self, event: &ContactForceEvent) -> Option<GameEvent> {
+        let parent = |collider| self.colliders.get(collider).and_then(|c| c.parent());
+        let (handle1, handle2) = (parent(event.collider1)?, parent(event.collider2)?);
+        let (body1, body2) = (self.bodies.get(handle1)?, self.bodies.get(handle2)?);
+        let (piece, piece_body, other_body) = if Self::is_piece(body1) && !Self::is_piece(body2) {
+            (handle1, body1, body2)
+        } else if Self::is_piece(body2) && !Self::is_piece(body1) {
+            (handle2, body2, body1)
+        } else {
+            return None;
+        };
+        let kind = if matches!(EntityKind::of(other_body), Some(EntityKind::Peg | EntityKind::Chain)) { ImpactKind::Peg } else { ImpactKind::Wall };
+        Some(GameEvent::Impact { piece, kind, speed: piece_body.linvel().norm() })
+    }
+
+    // Pushes a piece that has just started touching a bumper straight away from the bumper's
+    // center (an impulse worth the bumper's kick in speed, whatever the piece weighs)
+    fn kick_from_bumper(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) -> Option<GameEvent> {
+        let (bumper, other) = if self.bumpers.contains_key(&collider1) { (collider1, collider2) } else { (collider2, collider1) };
+        let kick = *self.bumpers.get(&bumper)?;
+        let parent = |collider| self.colliders.get(collider).and_then(|c| c.parent());
+        let (bumper_body, piece) = (parent(bumper)?, parent(other)?);
+        let center = *self.bodies.get(bumper_body)?.translation();
+        let body = self.bodies.get_mut(piece).filter(|body| Self::is_piece(body))?;
+        let away = body.translation() - center;
+        // A piece right on the center is sent straight up
+        let direction = if away.norm() > f32::EPSILON { away.normalize() } else { vector![0.0, -1.0] };
+        let impulse = direction * kick * body.mass();
+        body.apply_impulse(impulse, true);
+        Some(GameEvent::BumperHit { piece, bumper: bumper_body, x: center.x, y: center.y })
+    }
+
+    // Holds a piece that has just started touching a sticky pad: it stops dead and becomes a fixed
+    // body, so nothing can knock it off, until its pad's delay runs out
+    fn stick_to_pad(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) -> Option<GameEvent> {
+        let (pad_collider, other) = if self.sticky_pads.contains_key(&collider1) { (collider1, collider2) } else { (collider2, collider1) };
+        let pad = *self.sticky_pads.get(&pad_collider)?;
+        let config = *self.board.as_ref()?.sticky_pads.get(pad)?;
+        let piece = self.colliders.get(other)?.parent()?;
+        let body = self.bodies.get_mut(piece).filter(|body| Self::is_piece(body))?;
+        body.set_linvel(vector![0.0, 0.0], false);
+        body.set_angvel(0.0, false);
+        body.set_body_type(RigidBodyType::Fixed, true);
+        self.previous_poses.remove(&piece);
+        self.stuck.insert(piece, StuckPiece { pad, bonus: config.bonus, delay: config.delay, left: config.delay });
+        Some(GameEvent::PieceStuck { piece, pad })
+    }
+
+    // Counts down the stuck pieces' wait by a step, and removes the ones whose wait is over with a
+    // StickyPayout each (in handle order, so the same payouts always come in the same order)
+    fn release_stuck(&mut self) {
+        let dt = self.integration_params.dt;
+        let mut released: Vec<(RigidBodyHandle, StuckPiece)> = Vec::new();
+        for (handle, stuck) in self.stuck.iter_mut() {
+            stuck.left -= dt;
+            if stuck.left <= 0.0 {
+                released.push((*handle, *stuck));
+            }
+        }
+        released.sort_by_key(|(handle, _)| handle.into_raw_parts());
+        for (piece, stuck) in released {
+            let user_data = self.bodies.get(piece).map_or(0, |body| body.user_data);
+            self.remove_body(piece);
+            self.events.push(GameEvent::StickyPayout { piece, pad: stuck.pad, bonus: stuck.bonus, user_data });
+        }
+    }
+
+    // Remembers the piece behind a hit on a block: the piece itself when it hits one, or the piece
+    // that hit a block when that block hits another that has nothing to go by yet
+    fn pass_block_hit(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) {
+        let parent = |collider| self.colliders.get(collider).and_then(|c| c.parent());
+        let (Some(body1), Some(body2)) = (parent(collider1), parent(collider2)) else {
+            return;
+        };
+        for (block, other) in [(body1, body2), (body2, body1)] {
+            let from_piece = self.bodies.get(other).is_some_and(Self::is_piece);
+            let hit_by = if from_piece { Some(other) } else { self.blocks.get(&other).and_then(|other| other.hit_by) };
+            // A piece's own hit always counts; one passed on only if the block has none yet
+            if let Some(block) = self.blocks.get_mut(&block)
+                && hit_by.is_some()
+                && (from_piece || block.hit_by.is_none())
+            {
+                block.hit_by = hit_by;
+            }
+        }
+    }
+
+    // Sends a BlockToppled for every block knocked out of place since the last step (in handle
+    // order, so the same blocks are always told in the same order), and removes the blocks that
+    // have fallen into the bins or off the board
+    fn check_blocks(&mut self) {
+        let Some(bins_top) = self.board.as_ref().map(BoardConfig::bins_top) else {
+            return;
+        };
+        let mut toppled = Vec::new();
+        let mut fallen = Vec::new();
+        for (handle, block) in &self.blocks {
+            let Some(body) = self.bodies.get(*handle) else {
+                continue;
+            };
+            let pos = body.translation();
+            let gone = pos.y > bins_top || self.lost_reason(body).is_some();
+            if gone {
+                fallen.push(*handle);
+            }
+            let moved = (pos.x - block.home_x).hypot(pos.y - block.home_y) > block.size * TOPPLE_DISTANCE;
+            let tipped = body.rotation().angle().abs() > TOPPLE_ANGLE;
+            if !block.toppled && (moved || tipped || gone) {
+                toppled.push((*handle, block.hit_by, block.points));
+            }
+        }
+        toppled.sort_by_key(|(block, _, _)| block.into_raw_parts());
+        for (handle, hit_by, points) in toppled {
+            if let Some(block) = self.blocks.get_mut(&handle) {
+                block.toppled = true;
+            }
+            if let Some(piece) = hit_by {
+                self.events.push(GameEvent::BlockToppled { piece, block: handle, points });
+            }
+        }
+        for block in fallen {
+            self.remove_body(block);
+        }
+    }
+
+    // Takes a hard impact's share of hit points off the breakable peg in it, and removes the peg
+    // once it has none left
+    fn damage_peg(&mut self, event: ContactForceEvent) {
+        let (peg, other) = if self.peg_hit_points.contains_key(&event.collider1) {
+            (event.collider1, event.collider2)
+        } else {
+            (event.collider2, event.collider1)
+        };
+        // Pieces report softer contacts than this too, for the impact sounds
+        if event.total_force_magnitude < BREAK_FORCE {
+            return;
+        }
+        let Some(hit_points) = self.peg_hit_points.get_mut(&peg) else {
+            return;
+        };
+        hit_points.left -= event.total_force_magnitude * self.integration_params.dt / DAMAGE_IMPULSE;
+        if hit_points.left > 0.0 {
+            return;
+        }
+
+        // Out of hit points the peg goes, whatever hit it; the break is only reported when a piece did it
+        self.peg_hit_points.remove(&peg);
+        let parent = |collider| self.colliders.get(collider).and_then(|c| c.parent());
+        let (peg_body, piece) = (parent(peg), parent(other));
+        let Some(peg_body) = peg_body else {
+            self.parts.remove(&peg);
+            self.colliders.remove(peg, &mut self.island_manager, &mut self.bodies, false);
+            return;
+        };
+        let pos = *self.bodies[peg_body].translation();
+        self.moving_pegs.retain(|moving| moving.handle != peg_body);
+        self.remove_body(peg_body);
+        if let Some(piece) = piece {
+            self.events.push(GameEvent::PegBroken { piece, x: pos.x, y: pos.y });
+        }
+    }
+
+    /// Gives the parts of the board the presets in `materials`, changing the colliders already in
+    /// the world in place; parts without a preset go back to the values they were built with.
+    pub fn set_materials(&mut self, materials: &Materials) {
+        self.materials = materials.clone();
+        for (handle, (part, designed)) in &self.parts {
+            if let Some(collider) = self.colliders.get_mut(*handle) {
+                let material = self.materials.resolve(*part, *designed, has_own_bounce(collider));
+                apply_material(collider, material);
+            }
+        }
+    }
+
+    /// A copy of everything the simulation needs to carry on from this step.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            island_manager: self.island_manager.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            bodies: self.bodies.clone(),
+            colliders: self.colliders.clone(),
+            joints: self.joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+            ccd: self.ccd.clone(),
+            board: self.board.clone(),
+            ball_variant: self.ball_variant,
+            parts: self.parts.clone(),
+            time: self.time,
+            moving_pegs: self.moving_pegs.clone(),
+            board_bodies: self.board_bodies.clone(),
+            peg_hit_points: self.peg_hit_points.clone(),
+            bumpers: self.bumpers.clone(),
+            sticky_pads: self.sticky_pads.clone(),
+            stuck: self.stuck.clone(),
+            blocks: self.blocks.clone(),
+            jams: self.jams.clone(),
+            in_bin: self.in_bin.clone(),
+            bin_arrivals: self.bin_arrivals,
+            settled: self.settled.clone(),
+        }
+    }
+
+    /// Replaces the simulation with a snapshot, keeping the world's settings and giving the
+    /// restored board the world's materials. Events not yet read are dropped.
+    pub fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.pipeline = PhysicsPipeline::new();
+        self.island_manager = snapshot.island_manager;
+        self.broad_phase = snapshot.broad_phase;
+        self.narrow_phase = snapshot.narrow_phase;
+        self.bodies = snapshot.bodies;
+        self.colliders = snapshot.colliders;
+        self.joints = snapshot.joints;
+        self.multibody_joints = snapshot.multibody_joints;
+        self.ccd = snapshot.ccd;
+        self.board = snapshot.board;
+        self.ball_variant = snapshot.ball_variant;
+        self.parts = snapshot.parts;
+        self.time = snapshot.time;
+        self.moving_pegs = snapshot.moving_pegs;
+        self.board_bodies = snapshot.board_bodies;
+        self.peg_hit_points = snapshot.peg_hit_points;
+        self.bumpers = snapshot.bumpers;
+        self.sticky_pads = snapshot.sticky_pads;
+        self.stuck = snapshot.stuck;
+        self.blocks = snapshot.blocks;
+        self.jams = snapshot.jams;
+        self.in_bin = snapshot.in_bin;
+        self.bin_arrivals = snapshot.bin_arrivals;
+        self.settled = snapshot.settled;
+        // Nothing to interpolate from until the next step
+        self.accumulator = 0.0;
+        self.previous_poses.clear();
+        self.events.clear();
+        while self.collision_recv.try_recv().is_ok() {}
+        while self.contact_force_recv.try_recv().is_ok() {}
+        let materials = self.materials.clone();
+        self.set_materials(&materials);
+        self.update_queries();
+    }
+
+    /// True if the board has pegs that move by themselves.
+    pub fn has_moving_pegs(&self) -> bool {
+        !self.moving_pegs.is_empty()
+    }
+
+    /// What is left of a breakable peg's hit points, from 1.0 (untouched) down towards 0.0.
+    /// None for colliders that aren't breakable pegs.
+    pub fn peg_health(&self, collider: ColliderHandle) -> Option<f32> {
+        self.peg_hit_points.get(&collider).map(|hit_points| hit_points.left / hit_points.max)
+    }
+
+    /// True for the colliders of bumper pegs.
+    pub fn is_bumper(&self, collider: ColliderHandle) -> bool {
+        self.bumpers.contains_key(&collider)
+    }
+
+    /// How far along a piece stuck to a sticky pad is in its wait, from 0.0 (just stuck) to 1.0
+    /// (about to pay out). None for pieces that aren't stuck.
+    pub fn stick_progress(&self, piece: RigidBodyHandle) -> Option<f32> {
+        let stuck = self.stuck.get(&piece)?;
+        Some(if stuck.delay > 0.0 { 1.0 - stuck.left / stuck.delay } else { 1.0 })
+    }
+
+    /// Handles of the pieces stuck to a sticky pad right now.
+    pub fn stuck_pieces(&self) -> impl Iterator<Item = RigidBodyHandle> + '_ {
+        self.stuck.keys().copied()
+    }
+
+    /// How far between the last step and the next one the current frame is (0.0 - 1.0).
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.accumulator / self.integration_params.dt
+    }
+
+    /// The position and rotation to draw a body at this frame.
+    /// Dynamic bodies are interpolated between their previous and current step so motion stays
+    /// smooth when the render rate doesn't match the physics rate; fixed bodies are drawn as-is.
+    pub fn render_pose(&self, handle: RigidBodyHandle, body: &RigidBody) -> (Vector<Real>, Real) {
+        let current = body.position();
+        match self.previous_poses.get(&handle) {
+            Some(previous) => {
+                let alpha = self.interpolation_alpha();
+                let translation = previous.translation.vector.lerp(&current.translation.vector, alpha);
+                let rotation = previous.rotation.slerp(&current.rotation, alpha);
+                (translation, rotation.angle())
+            }
+            None => (current.translation.vector, current.rotation.angle()),
+        }
+    }
+
+    // Remembers where every moving (dynamic or kinematic) body is before a step
+    fn store_previous_poses(&mut self) {
+        self.previous_poses.clear();
+        for (handle, body) in self.bodies.iter() {
+            if !body.is_fixed() {
+                self.previous_poses.insert(handle, *body.position());
+            }
+        }
+    }
+
+    /// Advances the simulation by one fixed timestep (integration_params.dt).
+    pub fn step(&mut self) {
+        let dt = self.integration_params.dt;
+        self.animate_pegs(self.time + dt);
+        self.apply_force_fields();
+        self.apply_pool();
+        self.pipeline.step(
+            &self.gravity,              // Apply gravity force to all dynamic bodies
+            &self.integration_params,   // Use configured physics parameters for this simulation step
+            &mut self.island_manager,   // Update body islands for optimization (groups related bodies)
+            &mut self.broad_phase,      // Quick collision detection pass (AABB overlap tests)
+            &mut self.narrow_phase,     // Precise collision detection and response calculation
+            &mut self.bodies,           // Update all body positions, velocities, and rotations
+            &mut self.colliders,        // Update collision shape positions (attached to bodies)
+            &mut self.joints,           // Process any joint constraints between bodies
+            &mut self.multibody_joints, // Process multi-body joint constraints
+            &mut self.ccd,              // Continuous collision detection for fast-moving objects
+            Some(&mut self.query_pipeline), // Keep ray casts and point queries in step with the bodies
+            &(),                        // No additional physics hooks
+            &self.event_collector,      // Send collision events to the channel
+        );
+        self.time += dt;
+    }
+
+    // Tells Rapier where every moving peg should be at the end of the coming step;
+    // it then gives the kinematic body the velocity needed to get there, so pieces get pushed
+    fn animate_pegs(&mut self, time: f32) {
+        for peg in &self.moving_pegs {
+            let (offset_x, rotation) = peg.motion.pose_at(time);
+            if let Some(body) = self.bodies.get_mut(peg.handle) {
+                body.set_next_kinematic_position(Isometry::new(vector![peg.x + offset_x, peg.y], rotation));
+            }
+        }
+    }
+
+    // Gives every piece overlapping one of the board's force fields that field's push for the
+    // coming step. Wind and updrafts push by collider size rather than mass, so denser balls feel them less
+    fn apply_force_fields(&mut self) {
+        let Some(board) = self.board.as_ref() else {
+            return;
+        };
+        let dt = self.integration_params.dt;
+        if board.force_fields.is_empty() {
+            return;
+        }
+        for (_, body) in self.bodies.iter_mut() {
+            if !Self::is_piece(body) {
+                continue;
+            }
+            let Some(collider) = body.colliders().first().and_then(|handle| self.colliders.get(*handle)) else {
+                continue;
+            };
+            let aabb = collider.compute_aabb();
+            let pos = *body.translation();
+            let mut acceleration = vector![0.0, 0.0];
+            for field in board.force_fields.iter().filter(|field| field.overlaps([aabb.mins.x, aabb.mins.y], [aabb.maxs.x, aabb.maxs.y])) {
+                let [x, y] = field.acceleration_at(pos.x, pos.y);
+                let scale = match field.effect {
+                    ForceEffect::Wind { .. } | ForceEffect::Updraft { .. } => 1.0 / collider.density(),
+                    ForceEffect::Magnet { .. } => 1.0,
+                };
+                acceleration += vector![x, y] * scale;
+            }
+            if acceleration != vector![0.0, 0.0] {
+                body.apply_impulse(acceleration * body.mass() * dt, true);
+            }
+        }
+    }
+
+    // Gives every piece in the board's pool its lift, drag and drift for the coming step, each by
+    // how much of the piece is under the surface. The lift goes by the piece's density, so heavy
+    // balls sink through the water and light ones hang just under the surface
+    fn apply_pool(&mut self) {
+        let Some(board) = self.board.as_ref() else {
+            return;
+        };
+        let Some(pool) = board.pool else {
+            return;
+        };
+        let dt = self.integration_params.dt;
+        let middle = board.ground.x;
+        for (_, body) in self.bodies.iter_mut() {
+            if !Self::is_piece(body) {
+                continue;
+            }
+            let Some(collider) = body.colliders().first().and_then(|handle| self.colliders.get(*handle)) else {
+                continue;
+            };
+            let aabb = collider.compute_aabb();
+            let submerged = pool.submerged(aabb.mins.y, aabb.maxs.y);
+            if submerged <= 0.0 {
+                continue;
+            }
+            let lift = (pool.density / collider.density()).min(MAX_POOL_LIFT) * self.gravity.y * submerged;
+            let drift = (body.translation().x - middle).signum() * pool.drift * submerged;
+            let drag = -*body.linvel() * pool.drag * submerged;
+            body.apply_impulse((vector![drift, -lift] + drag) * body.mass() * dt, true);
+            // Water slows spinning down too
+            let spin = body.angvel() * (1.0 - (pool.drag * submerged * dt).min(1.0));
+            body.set_angvel(spin, true);
+        }
+    }
+
+    /// True for the colliders of golden pegs.
+    pub fn is_golden(collider: &Collider) -> bool {
+        !collider.is_sensor() && collider.user_data == GOLDEN_PEG_TAG
+    }
+
+    /// True for the pieces still in play: dropped pieces that are moving freely (not the board's
+    /// chain links, and not pieces held by a sticky pad).
+    pub fn is_piece(body: &RigidBody) -> bool {
+        body.is_dynamic() && EntityKind::of(body).is_some_and(EntityKind::is_piece)
+    }
+
+    /// The shape a piece was spawned as (None for the board's bodies).
+    pub fn shape_of(body: &RigidBody) -> Option<ShapeKind> {
+        Self::shape_from(body.user_data)
+    }
+
+    /// The shape tagged in a body's user_data, for bodies already removed from the world.
+    pub fn shape_from(user_data: u128) -> Option<ShapeKind> {
+        match EntityKind::from_user_data(user_data)? {
+            EntityKind::Piece(kind) => Some(kind),
+            _ => None,
+        }
+    }
+
+    /// The variant a ball collider was spawned as (None for anything that isn't a ball piece).
+    pub fn ball_variant_of(collider: &Collider) -> Option<BallVariant> {
+        let index = collider.user_data.checked_sub(BALL_TAG)?;
+        BallVariant::ALL.get(index as usize).copied()
+    }
+
+    // Records the collider of `body` as a part of the board built as `designed`, and gives it the
+    // part's preset if it has one
+    fn add_part(&mut self, body: RigidBodyHandle, part: BoardPart, designed: Material) {
+        let handle = self.bodies[body].colliders()[0];
+        self.parts.insert(handle, (part, designed));
+        let collider = &mut self.colliders[handle];
+        let material = self.materials.resolve(part, designed, has_own_bounce(collider));
+        apply_material(collider, material);
+    }
+
+    // Inserts a fixed body of the board at (x, y), tagged as `kind`, with the given collider attached
+    // on the kind's layer
+    fn insert_fixed(&mut self, x: f32, y: f32, mut collider: Collider, kind: EntityKind) -> RigidBodyHandle {
+        collider.set_collision_groups(Layers::of(kind).groups());
+        let mut body = RigidBodyBuilder::fixed().translation(vector![x, y]).build();
+        kind.assign(&mut body);
+        let handle = self.bodies.insert(body);
+        self.colliders.insert_with_parent(collider, handle, &mut self.bodies);
+        self.board_bodies.push(handle);
+        handle
+    }
+
+    // Inserts a dynamic piece at (x, y): starts at rest, CCD on, with air resistance on both
+    // movement and spin so pieces don't accelerate forever or spin endlessly.
+    // Pieces report collision events so hits can become GameEvents, and the contact forces of hits
+    // harder than IMPACT_FORCE so they can be heard.
+    fn insert_dynamic(&mut self, x: f32, y: f32, mut collider: Collider) -> RigidBodyHandle {
+        collider.set_active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS);
+        collider.set_contact_force_event_threshold(IMPACT_FORCE);
+        collider.set_collision_groups(Layers::Pieces.groups());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(vector![x, y])
+            .linvel(vector![0.0, 0.0])
+            .angvel(0.0)
+            .ccd_enabled(true)
+            .linear_damping(self.piece_damping)
+            .angular_damping(self.piece_damping)
+            .build();
+        let designed = material_of(&collider);
+        let handle = self.bodies.insert(body);
+        self.colliders.insert_with_parent(collider, handle, &mut self.bodies);
+        self.add_part(handle, BoardPart::Pieces, designed);
+        handle
+    }
+
+    // Fixed rectangle for the ground platform or a wall
+    fn create_box(&mut self, config: &BoxConfig, part: BoardPart, kind: EntityKind) {
+        let collider = ColliderBuilder::cuboid(config.half_width, config.half_height).friction(config.friction).build();
+        let designed = material_of(&collider);
+        let handle = self.insert_fixed(config.x, config.y, collider, kind);
+        self.add_part(handle, part, designed);
+    }
+
+    // A hanging chain: a fixed anchor with the links hanging straight down from it, each joined to
+    // the one above by a revolute joint at their shared end. Jointed bodies don't collide with each
+    // other, so neighbouring links can bend freely
+    fn create_chain(&mut self, config: &ChainConfig) {
+        let radius = config.thickness / 2.0;
+        let half_length = config.link_length / 2.0;
+        let anchor = self.insert_fixed(config.x, config.y, ColliderBuilder::ball(radius).build(), EntityKind::Chain);
+        let mut above = (anchor, point![0.0, 0.0]);
+        for index in 0..config.links {
+            let y = config.y + (index as f32 + 0.5) * config.link_length;
+            let mut body = RigidBodyBuilder::dynamic()
+                .translation(vector![config.x, y])
+                .linear_damping(CHAIN_DAMPING)
+                .angular_damping(CHAIN_DAMPING)
+                .build();
+            EntityKind::Chain.assign(&mut body);
+            let link = self.bodies.insert(body);
+            let collider = ColliderBuilder::capsule_y((half_length - radius).max(0.0), radius).collision_groups(Layers::Board.groups()).build();
+            self.colliders.insert_with_parent(collider, link, &mut self.bodies);
+            self.board_bodies.push(link);
+            let joint = RevoluteJointBuilder::new().local_anchor1(above.1).local_anchor2(point![0.0, -half_length]);
+            self.joints.insert(above.0, link, joint, true);
+            above = (link, point![0.0, half_length]);
+        }
+    }
+
+    // A block stack: a fixed ledge with up to `budget` loose blocks stacked on it, asleep until
+    // something knocks into them. Returns the number of blocks built
+    fn create_block_stack(&mut self, config: &BlockStackConfig, budget: usize) -> usize {
+        let ledge = BoxConfig {
+            x: config.x,
+            y: config.y + LEDGE_HALF_HEIGHT,
+            half_width: config.cols as f32 * config.block_size / 2.0 + LEDGE_OVERHANG,
+            half_height: LEDGE_HALF_HEIGHT,
+            friction: 0.6,
+        };
+        self.create_box(&ledge, BoardPart::Walls, EntityKind::Wall);
+
+        let positions: Vec<(f32, f32)> = config.block_positions().into_iter().take(budget).collect();
+        let half = config.block_size / 2.0;
+        for &(x, y) in &positions {
+            let mut body = RigidBodyBuilder::dynamic().translation(vector![x, y]).sleeping(true).build();
+            EntityKind::Block.assign(&mut body);
+            let handle = self.bodies.insert(body);
+            // Blocks report their hits too, so a hit can be passed on from block to block
+            let collider = ColliderBuilder::cuboid(half, half)
+                .friction(0.6)
+                .restitution(0.1)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .collision_groups(Layers::Board.groups())
+                .build();
+            self.colliders.insert_with_parent(collider, handle, &mut self.bodies);
+            self.board_bodies.push(handle);
+            self.blocks.insert(handle, Block { home_x: x, home_y: y, size: config.block_size, points: config.points, toppled: false, hit_by: None });
+        }
+        positions.len()
+    }
+
+    // A funnel or ramp: a polyline collider through the segment's points, on a fixed body at the origin
+    fn create_segment(&mut self, config: &SegmentConfig) {
+        let points: Vec<Point<f32>> = config.points.iter().map(|[x, y]| point![*x, *y]).collect();
+        let collider = ColliderBuilder::polyline(points, None).friction(config.friction).restitution(config.restitution).build();
+        let designed = material_of(&collider);
+        let handle = self.insert_fixed(0.0, 0.0, collider, EntityKind::Segment);
+        self.add_part(handle, BoardPart::Walls, designed);
+    }
+
+    /// Create the bottom bins (vertical dividers) and attach colliders.
+    /// Dividers sit between the bins so `count` sections span the ground width.
+    fn create_bins(&mut self, board: &BoardConfig) {
+        let bins = &board.bins;
+        for i in 1..bins.count {
+            let x = board.bins_left() + board.bin_width() * i as f32;
+            // Center Y so dividers sit directly above ground (bottom aligns with ground top)
+            let y = board.ground_top() - bins.divider_half_height;
+
+            let collider = ColliderBuilder::cuboid(bins.divider_half_width, bins.divider_half_height).friction(bins.friction).build();
+            let designed = material_of(&collider);
+            let handle = self.insert_fixed(x, y, collider, EntityKind::Bin);
+            self.add_part(handle, BoardPart::Walls, designed);
+        }
+    }
+
+    // A single peg with the configured shape and bounciness.
+    // Pegs with a motion become kinematic bodies that are animated every step.
+    fn create_peg(&mut self, peg: &PegConfig) {
+        let builder = match peg.shape {
+            PegShape::Circle { radius } => ColliderBuilder::ball(radius),
+            PegShape::Square { size, angle } => {
+                let half = size / 2.0;
+                let (sin_a, cos_a) = angle.to_radians().sin_cos();
+
+                // Square vertices BEFORE rotation
+                let base_vertices = [Point::new(-half, -half), Point::new(half, -half), Point::new(half, half), Point::new(-half, half)];
+
+                // Rotate each vertex by the configured angle (45° creates a diamond shape)
+                let rotated_vertices: Vec<Point<f32>> = base_vertices.iter().map(|v| Point::new(v.x * cos_a - v.y * sin_a, v.x * sin_a + v.y * cos_a)).collect();
+                hull_or_ball(&rotated_vertices, half)
+            }
+            PegShape::Triangle { size } => {
+                let height = (3.0_f32).sqrt() / 2.0 * size;
+                let vertices = [
+                    Point::new(0.0, -height / 3.0),
+                    Point::new(-size / 2.0, height * 2.0 / 3.0),
+                    Point::new(size / 2.0, height * 2.0 / 3.0),
+                ];
+                hull_or_ball(&vertices, size / 2.0)
+            }
+            PegShape::Bar { length, thickness, angle } => {
+                let (half_l, half_t) = (length / 2.0, thickness / 2.0);
+                let (sin_a, cos_a) = angle.to_radians().sin_cos();
+                // A convex polygon rather than a cuboid so it renders rotated and counts as a peg
+                let corners = [Point::new(-half_l, -half_t), Point::new(half_l, -half_t), Point::new(half_l, half_t), Point::new(-half_l, half_t)];
+                let rotated: Vec<Point<f32>> = corners.iter().map(|v| Point::new(v.x * cos_a - v.y * sin_a, v.x * sin_a + v.y * cos_a)).collect();
+                hull_or_ball(&rotated, half_t)
+            }
+        };
+        let tag = if peg.golden { GOLDEN_PEG_TAG } else { 0 };
+        let mut builder = builder.restitution(peg.restitution).user_data(tag).collision_groups(Layers::Board.groups());
+        if peg.hit_points.is_some() {
+            // Breakable pegs report the contact forces of impacts hard enough to damage them
+            builder = builder.active_events(ActiveEvents::CONTACT_FORCE_EVENTS).contact_force_event_threshold(BREAK_FORCE);
+        }
+        let collider = builder.build();
+        let designed = material_of(&collider);
+
+        let handle = match peg.motion {
+            None => self.insert_fixed(peg.x, peg.y, collider, EntityKind::Peg),
+            Some(motion) => {
+                let mut body = RigidBodyBuilder::kinematic_position_based().translation(vector![peg.x, peg.y]).build();
+                EntityKind::Peg.assign(&mut body);
+                let handle = self.bodies.insert(body);
+                self.colliders.insert_with_parent(collider, handle, &mut self.bodies);
+                self.moving_pegs.push(MovingPeg { handle, x: peg.x, y: peg.y, motion });
+                self.board_bodies.push(handle);
+                handle
+            }
+        };
+        self.add_part(handle, BoardPart::Pegs, designed);
+        let collider = self.bodies[handle].colliders()[0];
+        if let Some(max) = peg.hit_points {
+            self.peg_hit_points.insert(collider, PegHitPoints { left: max, max });
+        }
+        if let Some(kick) = peg.bumper {
+            self.bumpers.insert(collider, kick);
+        }
+    }
+
+    /// Spawns a spherical ball of the current ball_variant at the specified coordinates.
+    /// Balls are small, round objects that fall through the peg grid unpredictably.
+    fn spawn_ball(&mut self, x: f32, y: f32) -> RigidBodyHandle {
+        let spec = self.ball_variant.spec();
+        let variant_index = BallVariant::ALL.iter().position(|variant| *variant == self.ball_variant).unwrap_or(0);
+        let collider = ColliderBuilder::new(piece_shape(ShapeKind::Ball))
+            .density(spec.density) // Heavier variants get more mass for the same size
+            .restitution(spec.restitution.unwrap_or(self.piece_restitution)) // Bounciness coefficient: share of energy the ball keeps after each bounce
+            .friction(0.2) // Low friction allows ball to roll smoothly without excessive grip
+            .user_data(BALL_TAG + variant_index as u128) // Lets the renderer colour it by variant
+            .build();
+        self.insert_dynamic(x, y, collider)
+    }
+
+    /// Spawns a square-shaped object at the specified coordinates.
+    /// Uses a convex polygon to define the square's collision shape.
+    fn spawn_square_as_convex(&mut self, x: f32, y: f32) -> RigidBodyHandle {
+        let collider = ColliderBuilder::new(piece_shape(ShapeKind::Square))
+            .restitution(self.piece_restitution) // Same bounciness as the ball
+            .friction(0.3) // Higher friction than balls reduces sliding behavior
+            .build();
+        self.insert_dynamic(x, y, collider)
+    }
+
+    /// Spawns an equilateral triangle-shaped object at the specified coordinates.
+    /// Triangles are angular objects that can produce unpredictable and varied bounces.
+    fn spawn_triangle(&mut self, x: f32, y: f32) -> RigidBodyHandle {
+        let collider = ColliderBuilder::new(piece_shape(ShapeKind::Triangle))
+            .restitution(self.piece_restitution) // Bounciness (same as balls)
+            .friction(0.2) // Low friction like balls, allowing more sliding than squares
+            .build();
+        self.insert_dynamic(x, y, collider)
+    }
+}
+
+// The collision shape of a dropped piece of `kind`, centered on the piece's position
+fn piece_shape(kind: ShapeKind) -> SharedShape {
+    match kind {
+        ShapeKind::Ball => SharedShape::ball(7.0),
+        ShapeKind::Square => {
+            let size = 15.5;
+            let half = size / 2.0;
+
+            // Four corner vertices of a square centered at the origin (0,0)
+            let vertices = [
+                Point::new(-half, -half), // Top-left corner
+                Point::new(half, -half),  // Top-right corner
+                Point::new(half, half),   // Bottom-right corner
+                Point::new(-half, half),  // Bottom-left corner
+            ];
+            SharedShape::convex_hull(&vertices).unwrap()
+        }
+        ShapeKind::Triangle => {
+            let side = 15.0;
+            // Height of equilateral triangle = (√3/2) * side_length
+            let height = (3.0_f32).sqrt() / 2.0 * side;
+
+            // Vertices positioned so the centroid is near the origin so the triangle balances properly
+            let vertices = [
+                Point::new(0.0, -height / 3.0),              // Top vertex (pointing upward)
+                Point::new(-side / 2.0, height * 2.0 / 3.0), // Bottom-left vertex
+                Point::new(side / 2.0, height * 2.0 / 3.0),  // Bottom-right vertex
+            ];
+            SharedShape::convex_hull(&vertices).unwrap()
+        }
+    }
+}
+
+// A convex collider around `points`, or a ball of `radius` if they don't make a polygon (a peg
+// squashed flat or to a point, which validate() keeps out of boards but is no reason to crash)
+fn hull_or_ball(points: &[Point<f32>], radius: f32) -> ColliderBuilder {
+    ColliderBuilder::convex_hull(points).unwrap_or_else(|| ColliderBuilder::ball(radius.abs().max(1.0)))
+}
+
+// The material a collider was built with
+fn material_of(collider: &Collider) -> Material {
+    Material::new(collider.restitution(), collider.friction(), collider.density())
+}
+
+// True for a ball whose variant has a bounce of its own (see BallSpec), which material presets don't replace
+fn has_own_bounce(collider: &Collider) -> bool {
+    PhysicsWorld::ball_variant_of(collider).is_some_and(|variant| variant.spec().restitution.is_some())
+}
+
+// Changes a collider's bounce, grip and density in place (Rapier recomputes the body's mass)
+fn apply_material(collider: &mut Collider, material: Material) {
+    collider.set_restitution(material.restitution);
+    collider.set_friction(material.friction);
+    collider.set_density(material.density);
+}
+
+// Direction of a jammed piece's `nudges`th nudge: up and 30-60 degrees to one side, mixed from the
+// piece's handle and the nudge's number so it looks random but is the same on every run
+fn nudge_direction(piece: RigidBodyHandle, nudges: u32) -> Vector<Real> {
+    let (index, generation) = piece.into_raw_parts();
+    let mut hash = index.wrapping_mul(0x9E37_79B9) ^ generation.wrapping_mul(0x85EB_CA6B) ^ nudges.wrapping_mul(0xC2B2_AE35);
+    hash ^= hash >> 16;
+    let side = if hash & 1 == 0 { -1.0 } else { 1.0 };
+    let angle = (30.0 + ((hash >> 1) % 31) as f32).to_radians();
+    vector![side * angle.sin(), -angle.cos()]
+}
@@ -0,0 +1,247 @@
+/*
+Program Details: Players for the hot-seat (local multiplayer) mode
+
+In a hot-seat game 2 to 4 players share the board and take turns: on each turn
+the player whose turn it is drops DROPS_PER_TURN pieces, waits for them to land
+and hands over to the next player. Every player has their own wallet, bet and
+colour. Once every player has had TURNS_PER_PLAYER turns (or nobody has the
+credits for another bet) the game is over and the richest player wins.
+
+Each dropped piece is tagged with the player who dropped it in its body's
+user_data, so its payout goes to the right wallet even if it lands after the
+turn has moved on, and so it can be drawn in its owner's colour.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod players;
+
+Then with the other use commands add:
+use crate::modules::players::{owner_wallet, turn_wallet, turn_wallet_mut, PlayerManager};
+
+Then above the loop section to use you would go:
+    let mut players = PlayerManager::new(3);
+
+When the player on turn drops a piece:
+    if let Some(stake) = players.current_mut().wallet.place_bet() {
+        let handle = world.spawn(ShapeKind::Ball, x, DROP_Y);
+        PlayerManager::tag(&mut world.bodies[handle], players.current());
+        players.record_drop();
+    }
+
+When a piece lands, pay whoever dropped it (from the one-player wallet when
+there are no players, or the piece has no owner):
+    let owner = PlayerManager::owner(&world.bodies[handle]);
+    owner_wallet(players.as_mut(), &mut wallet, owner).pay_out(stake, payout);
+
+The wallet the next drop is paid from (the player on turn's):
+    let bet = turn_wallet(players.as_ref(), &wallet).bet();
+
+Then once nothing of the turn is left falling:
+    if players.turn_over() {
+        players.next_turn();
+    }
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBody;
+use crate::modules::wallet::{Wallet, DEFAULT_BET, MIN_BET, STARTING_CREDITS};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 4;
+// Pieces dropped per turn, and turns each player gets in a game
+pub const DROPS_PER_TURN: u32 = 5;
+pub const TURNS_PER_PLAYER: u32 = 3;
+// Colour of each player's pieces and scoreboard line
+pub const PLAYER_COLORS: [Color; MAX_PLAYERS] = [GOLD, SKYBLUE, PINK, LIME];
+// The owner tag sits in the 32 bits above the low 64 bits of a body's user_data, which hold its
+// sprite; the bits above it hold what the body is (see EntityKind in entity_registry.rs)
+const OWNER_SHIFT: u32 = 64;
+const OWNER_BITS: u128 = (u32::MAX as u128) << OWNER_SHIFT;
+
+/// One seat at the board.
+pub struct Player {
+    pub name: String,
+    pub color: Color,
+    pub wallet: Wallet,
+    // Turns this player has started
+    pub turns_taken: u32,
+}
+
+impl Player {
+    // A player without the credits for even the smallest bet sits the rest of the game out
+    pub fn is_broke(&self) -> bool {
+        self.wallet.balance() < MIN_BET
+    }
+}
+
+/// A player's result, handed to the winner screen when the game ends.
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub name: String,
+    pub color: Color,
+    pub balance: u32,
+}
+
+/// The players of a hot-seat game and whose turn it is.
+pub struct PlayerManager {
+    players: Vec<Player>,
+    current: usize,
+    // Drops the player on turn has left this turn
+    drops_left: u32,
+}
+
+impl PlayerManager {
+    /// A game for `count` players (clamped to MIN_PLAYERS - MAX_PLAYERS), Player 1 to start.
+    pub fn new(count: usize) -> Self {
+        let players = (0..count.clamp(MIN_PLAYERS, MAX_PLAYERS))
+            .map(|index| Player {
+                name: format!("Player {}", index + 1),
+                color: PLAYER_COLORS[index],
+                wallet: Wallet::new(STARTING_CREDITS, DEFAULT_BET),
+                turns_taken: 0,
+            })
+            .collect();
+        let mut manager = Self { players, current: 0, drops_left: DROPS_PER_TURN };
+        manager.players[0].turns_taken = 1;
+        manager
+    }
+
+    // Index of the player whose turn it is
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // The player whose turn it is
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Player {
+        &mut self.players[self.current]
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> &mut Player {
+        &mut self.players[index]
+    }
+
+    // Drops the player on turn has left this turn
+    pub fn drops_left(&self) -> u32 {
+        self.drops_left
+    }
+
+    /// True while the player on turn may drop another piece (drops left and a bet they can pay).
+    pub fn can_drop(&self) -> bool {
+        self.drops_left > 0 && self.current_player().wallet.can_afford()
+    }
+
+    /// Counts a drop by the player on turn.
+    pub fn record_drop(&mut self) {
+        self.drops_left = self.drops_left.saturating_sub(1);
+    }
+
+    /// Gives back a drop counted by record_drop() that never made it onto the board, if `owner` is
+    /// still the player on turn (a later turn starts with a full count anyway).
+    pub fn return_drop(&mut self, owner: usize) {
+        if owner == self.current {
+            self.drops_left = (self.drops_left + 1).min(DROPS_PER_TURN);
+        }
+    }
+
+    /// True once the player on turn has nothing left to drop: out of drops, or without the
+    /// credits for even the smallest bet. The caller waits for their pieces to land before
+    /// calling next_turn().
+    pub fn turn_over(&self) -> bool {
+        self.drops_left == 0 || self.current_player().is_broke()
+    }
+
+    /// Hands the board to the next player who still has turns and credits.
+    /// Returns false (and leaves the turn where it is) when nobody has, which ends the game.
+    pub fn next_turn(&mut self) -> bool {
+        for step in 1..=self.players.len() {
+            let index = (self.current + step) % self.players.len();
+            let player = &self.players[index];
+            if player.turns_taken < TURNS_PER_PLAYER && !player.is_broke() {
+                self.current = index;
+                self.players[index].turns_taken += 1;
+                self.drops_left = DROPS_PER_TURN;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Every player's final balance, richest first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .players
+            .iter()
+            .map(|player| Standing { name: player.name.clone(), color: player.color, balance: player.wallet.balance() })
+            .collect();
+        standings.sort_by_key(|standing| std::cmp::Reverse(standing.balance));
+        standings
+    }
+
+    /// Marks a body as dropped by player `index`, keeping the sprite and kind stored around the tag.
+    pub fn tag(body: &mut RigidBody, index: usize) {
+        body.user_data = (body.user_data & !OWNER_BITS) | ((index as u128 + 1) << OWNER_SHIFT);
+    }
+
+    /// The player who dropped a body, or None for untagged bodies (pegs, solo play).
+    pub fn owner(body: &RigidBody) -> Option<usize> {
+        Self::owner_from(body.user_data)
+    }
+
+    /// The player tagged in a body's user_data, for bodies already removed from the world.
+    pub fn owner_from(user_data: u128) -> Option<usize> {
+        match (user_data & OWNER_BITS) >> OWNER_SHIFT {
+            0 => None,
+            tag => Some(tag as usize - 1),
+        }
+    }
+
+    /// The colour of the player who dropped a body, if it was dropped in this game.
+    pub fn owner_color(&self, body: &RigidBody) -> Option<Color> {
+        Self::owner(body).and_then(|index| self.players.get(index)).map(|player| player.color)
+    }
+
+    /// Draws the scoreboard at (x, y): a line per player with their credits and turns left,
+    /// the player on turn marked with an arrow.
+    pub fn draw_scoreboard(&self, x: f32, y: f32) {
+        for (index, player) in self.players.iter().enumerate() {
+            let line_y = y + 22.0 * index as f32;
+            if index == self.current {
+                draw_text(">", x, line_y, 22.0, player.color);
+            }
+            draw_circle(x + 20.0, line_y - 6.0, 6.0, player.color);
+            let turns_left = TURNS_PER_PLAYER - player.turns_taken.min(TURNS_PER_PLAYER);
+            let color = if player.is_broke() { GRAY } else { player.color };
+            let text = format!("P{} {:>4}  ({} left)", index + 1, player.wallet.balance(), turns_left);
+            draw_text(&text, x + 32.0, line_y, 20.0, color);
+        }
+    }
+}
+
+/// The wallet of hot-seat player `owner`, or `solo` (the one-player wallet) when there are no players or
+/// nobody owns the piece.
+pub fn owner_wallet<'a>(players: Option<&'a mut PlayerManager>, solo: &'a mut Wallet, owner: Option<usize>) -> &'a mut Wallet {
+    match (players, owner) {
+        (Some(players), Some(owner)) => &mut players.get_mut(owner).wallet,
+        _ => solo,
+    }
+}
+
+/// The wallet drops are paid from: the player on turn's in a hot-seat game, otherwise `solo`.
+pub fn turn_wallet<'a>(players: Option<&'a PlayerManager>, solo: &'a Wallet) -> &'a Wallet {
+    match players {
+        Some(players) => &players.current_player().wallet,
+        None => solo,
+    }
+}
+
+/// The wallet drops are paid from, to place a bet or change it with.
+pub fn turn_wallet_mut<'a>(players: Option<&'a mut PlayerManager>, solo: &'a mut Wallet) -> &'a mut Wallet {
+    match players {
+        Some(players) => &mut players.current_mut().wallet,
+        None => solo,
+    }
+}
@@ -0,0 +1,2145 @@
+/*
+Program Details: The Plinko board scene (the game itself)
+
+Holds everything the game screen needs: the physics world and board, the wallet
+and the stakes riding on falling pieces, the UI column on the right, and the
+effects (sound, particles, statistics). update() handles the buttons, drops
+pieces and advances the simulation; draw() renders the board, the pieces and
+the labels, with the settings and history panels last so they sit on top. The
+board and HUD are queued on render layers (background, pegs, pieces, walls,
+effects, UI; see renderer.rs) and drawn in that order.
+The board is drawn in the board theme picked in the settings (see board_theme.rs):
+its background and parallax layers behind everything, and its peg, wall and
+piece colours.
+Fast pieces leave fading motion trails unless they are switched off in the settings.
+While aiming, a faint dotted line previews where the next piece is likely to go,
+and the bottom of each bin shows the chance of a ball dropped in the column being
+aimed at landing there, worked out by dropping batches of balls on a headless copy
+of the board in the background (see landing_odds.rs).
+
+The number of bins can be changed in the settings; each board is fitted to it
+(with generated payouts) when it is built, so the change shows on the next board
+or the next game.
+
+Every landing also spins the slot reels at the top of the board: three matching
+symbols multiply what the landing won, paid once the reels stop.
+A piece landing in the center bin starts a bonus round on the prize wheel scene;
+whatever the wheel awards is paid into the wallet when play comes back here.
+Running out of credits (with nothing left falling) ends the game and switches to
+the game over scene, which shows a summary of the session (drops, biggest payout,
+bin distribution); the next time this scene is entered it starts a fresh game.
+A timed round (started from the menu) also ends once its clock, shown above the
+balance, runs out: no more drops can be made and the pieces still falling land
+(and pay) before the summary is shown. The clock counts the fixed physics steps
+actually run rather than frame time, so pausing, slow motion and slow frames
+all stop or slow it along with the game; it turns red for the last
+ROUND_WARNING_SECONDS.
+Escape or the Menu button goes back to the title menu without losing the game.
+Clear Board (and Save Board, when it would replace a saved board) asks for
+confirmation in a dialog first.
+The right-hand column and the bottom row are placed by layout_hud from their
+anchors (see layout.rs) rather than at fixed pixels, and laid out again if the
+virtual resolution changes.
+F5 quick-saves the whole game (see snapshot.rs), pieces in mid-air included, and
+F9 loads it back, even after the game has been closed. Hot-seat games can't be
+quick-saved.
+The ` key opens an event console over the board (see event_console.rs) listing the
+latest spawns, landings, payouts, despawns and warnings, for following the game
+logic where there is no terminal to print to.
+F1 shows a performance overlay (frame rate, physics time, awake and sleeping
+pieces, collider count, drawing time and draw calls); with it open, F2 dims the
+pieces Rapier has put to sleep and F3 switches batched drawing off and on.
+Bodies entirely outside what the camera shows aren't drawn at all.
+The Shapes button opens a screen comparing how balls, squares and triangles
+fall (see shape_stats_scene.rs): every landing feeds the shared per-shape
+telemetry, which tells the shapes apart by the tag on each piece's body.
+Bodies are drawn by what the EntityRegistry says they are (see entity_registry.rs):
+pegs, walls and the rest of the board by the kind tagged on their body, and pieces
+in the colour they were registered with when dropped, next to their bet.
+Bumper pegs kick the pieces that touch them away, flash and score BUMPER_POINTS
+credits for whoever dropped the piece on every hit (see bumpers.rs).
+Sticky pads hold on to the pieces landing on them; once a pad's delay is up the
+piece pays its stake × the pad's bonus (and any multiplier it picked up) instead
+of a bin, and is removed (see sticky_pad.rs).
+A board's pool is drawn as rippling water over the pieces in it (see pool.rs),
+and its chains link by link with a ring at every joint (see chain.rs). Every
+block a piece knocks off a block stack scores the block's points for whoever
+dropped the piece.
+Every bin landing is offered as an instant replay for a few seconds: R plays the
+last two seconds of that piece's fall again at quarter speed in a small view in
+the board's corner (see instant_replay.rs).
+M shows a minimap of the whole board and its pieces in the board's other top
+corner (see minimap.rs).
+The background music builds up in layers (see music.rs) while the board is
+busy: more of them fade in as more pieces are falling and the combo gets
+longer, and out again when it calms down.
+Resting the mouse on a bin, a peg or one of the less obvious buttons shows a
+tooltip explaining it: what the bin pays, and what kind of peg it is.
+I switches inspect mode on and off (see inspector_panel.rs): clicking any body on
+the board then selects it instead of dropping, and a panel on the left shows its
+kind, position, velocity, bounce, friction and whether it is asleep, with sliders
+to change them live.
+With the Sandbox setting on, pieces can be picked up with the mouse, dragged
+around the board and thrown (see grab.rs), e.g. to free one wedged between pegs.
+The Tilt button under Clear Board tips the board for a moment and shakes the
+screen (see tilt.rs), knocking stuck pieces loose. A round has TILTS_PER_ROUND
+tilts, and every piece still falling loses TILT_PENALTY off its payout multiplier
+each time; like Clear Board it isn't offered in hot-seat games.
+
+An arcade round (started from the menu) is coin-operated: the wallet starts
+empty, Insert Coin (in Clear Board's place) or the C key adds COIN_CREDITS, and
+running out of credits blinks "INSERT COIN" over the board rather than ending
+the game. Left without any input for ATTRACT_SECONDS with nothing in play, the
+board hands the screen over to the attract mode demo (see attract_scene.rs).
+The idle time is picked in the settings, the same as the title menu's.
+
+An inventory round (started from the menu) has a fixed stock of pieces (see
+inventory.rs) rather than as many drops as the credits allow. Three spawn
+buttons in Clear Board's place show an icon of each shape with the count left:
+clicking one picks that shape for the next drop (and, in random mode, drops it
+in a random column straight away), and it greys out once none are left. The
+shape dropdown and Multiball are put away, and the round ends on the summary
+once every piece has been dropped and has landed.
+Every drop is also written into a demo recording (see demo.rs), which the
+attract mode plays back: the latest DEMO_SECONDS of play on one board.
+Hard hits, bumpers and breaking pegs shake the screen, the jackpot (settling in
+the best-paying bin) freezes the game for a split second, and pieces squash as
+they land in the bins (see juice.rs); how strong each is is set on the Juice
+panel in the settings.
+
+A hot-seat game (2-4 players, started from the menu) gives every player their
+own wallet and colour. Players take turns of DROPS_PER_TURN drops, shown above
+the balance and on the scoreboard in the right column; the turn passes once the
+last of them has landed. Every piece pays whoever dropped it, and when nobody
+has turns or credits left the winner screen shows the standings. Multiball is
+off in hot-seat games so every turn is the same number of pieces.
+
+Three challenge objectives at a time are listed in the board's top-left corner
+(see objectives.rs), e.g. landing pieces in one bin within a number of drops;
+finishing one pays its reward into the wallet and a new one takes its place.
+The O key hides and shows the list.
+
+The Risk button between the bet buttons picks Low, Medium or High risk: the
+board's payout table flattened, as designed, or stretched into big edge prizes
+and punishing middle bins (see RiskLevel in board.rs), all paying the same on
+average. The bin labels, payouts and statistics follow it; it can't be changed
+while pieces with a bet on them are still in play.
+
+Landing in the same bin as the landings just before builds a combo (see Combo in
+multiplier.rs) that multiplies the payout more with every landing in the chain,
+shown over that bin; a landing anywhere else starts a new chain.
+Big wins, winning and losing streaks and near misses of the best bin are called
+out in banners across the board, one at a time (see announcer.rs).
+
+Drops made faster than the pieces clear the drop point (fast clicks, holding the
+Random button) wait in a spawn queue and are released one by one, so no piece
+spawns inside another.
+
+Pieces the physics watchdog removes (blown up, or pushed off the board) are
+reported on the last-win line, and their bet is refunded unless the "Refund
+lost" setting is switched off.
+
+The Multiball button arms the next drop: instead of one piece it releases a
+burst of balls spread around the chosen column, each nudged sideways a little,
+and takes one bet per ball.
+
+Dropdowns along the bottom choose the shape dropped (or a random one each
+time) and the variant balls are dropped as (normal, heavy, light or bouncy);
+each ball variant is drawn in its own colour. The Boards button opens the
+presets gallery: the board picked there is played on until another is picked,
+or with "Random Maps" the Random button picks a random map on every drop. Switching
+maps that way swaps the pegs and bins under the pieces still falling rather than
+clearing them off the board.
+
+Every game's drops take their seeds from one game seed's sequence (shown under
+the board name): the seed typed on the title menu, or a random one when none was
+typed. Share copies a share code for the board and that seed to the clipboard
+(see share_code.rs); pasting it into the menu's code box plays the same board and
+drops on any machine. Save Board writes the
+current board to assets/boards/saved under the name typed above it, and Edit
+opens the board in the editor (see editor_scene.rs); the edited board comes back
+the same way a board picked in the presets gallery does.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod plinko_scene;
+
+Then with the other use commands add:
+use crate::modules::plinko_scene::PlinkoScene;
+
+Then above the loop section to use you would go:
+    scenes.add(SceneId::Plinko, Box::new(PlinkoScene::new(&assets, bonus.clone(), seed.clone(), board_choice.clone(), editor_board.clone(), session.clone(), telemetry.clone(), demo.clone()).await));
+*/
+use macroquad::experimental::coroutines::{start_coroutine, Coroutine};
+use macroquad::prelude::*;
+use rapier2d::prelude::{RigidBody, RigidBodyHandle};
+use std::collections::{HashMap, VecDeque};
+use crate::modules::aim::{DropAimer, DROP_Y};
+use crate::modules::announcer::Announcer;
+use crate::modules::attract_scene::ATTRACT_SECONDS;
+use crate::modules::assets::Assets;
+use crate::modules::audio::Audio;
+use crate::modules::demo::{DemoRecorder, SharedDemo};
+use crate::modules::dialog::{input_blocked, Dialog};
+use crate::modules::drop_history::DropHistory;
+use crate::modules::dropdown::Dropdown;
+use crate::modules::entity_registry::{EntityKind, EntityRegistry};
+use crate::modules::grab::PieceGrab;
+use crate::modules::history_panel::HistoryPanel;
+use crate::modules::idle::IdleTimer;
+use crate::modules::inspector_panel::InspectorPanel;
+use crate::modules::juice::Juice;
+use crate::modules::bin_label_bar::BinLabelBar;
+use crate::modules::bumpers::{BumperFlashes, BUMPER_POINTS};
+use crate::modules::instant_replay::InstantReplay;
+use crate::modules::inventory::{shape_icon, Inventory};
+use crate::modules::minimap::Minimap;
+use crate::modules::board_theme::{load_themes, BoardTheme};
+use crate::modules::board::{BoardConfig, RiskLevel, CIRCLE_BOARD, CLASSIC_BOARD, CUSTOM_BOARD, SQUARE_BOARD, TRIANGLE_BOARD};
+use crate::modules::events::GameEvent;
+use crate::modules::force_field::draw_force_fields;
+use crate::modules::pool::draw_pool;
+use crate::modules::chain::draw_chains;
+use crate::modules::sticky_pad::{draw_sticky_pads, draw_stuck_timers, STICKY_PAD_COLOR};
+use crate::modules::landing_odds::LandingOdds;
+use crate::modules::label::Label;
+use crate::modules::layout::{current_resolution, Anchor, Layout, RIGHT_COLUMN_WIDTH};
+use crate::modules::lifetime::LifetimeManager;
+use crate::modules::multiplier::{Combo, DropMultipliers, GOLDEN_PEG_COLOR};
+use crate::modules::perf_hud::PerfHud;
+use crate::modules::event_console::{EventConsole, LogCategory, piece_name};
+use crate::modules::objectives::{Objective, Objectives};
+use crate::modules::particles::{ParticleSystem, BREAKABLE_PEG_COLOR, MAX_PARTICLES};
+use crate::modules::physics::{BallVariant, PhysicsWorld, ShapeKind};
+use crate::modules::players::{owner_wallet, turn_wallet, turn_wallet_mut, PlayerManager};
+use crate::modules::presets_scene::{BoardChoice, SharedBoardChoice};
+use crate::modules::editor_scene::SharedEditorBoard;
+use crate::modules::prize_wheel::{BonusRound, SharedBonus};
+use crate::modules::renderer::{Layer, Renderer};
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::scale::offset_camera;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::seed::{random_game_seed, SeedSequence, SharedSeed};
+use crate::modules::session::{RoundKind, SessionEnd, SessionSummary, SharedSession, ROUND_SECONDS};
+use crate::modules::settings::Settings;
+use crate::modules::share_code::ShareCode;
+use crate::modules::settings_panel::SettingsPanel;
+use crate::modules::shape_telemetry::SharedShapeTelemetry;
+use crate::modules::slot_reel::SlotMachine;
+use crate::modules::snapshot::{GameSnapshot, SNAPSHOT_VERSION};
+use crate::modules::spawn_queue::{QueuedDrop, SpawnQueue};
+use crate::modules::stats::BinStats;
+use crate::modules::still_image::StillImage;
+use crate::modules::theme::{scaled_font_size, text_scale};
+use crate::modules::text_button::TextButton;
+use crate::modules::text_input::TextInput;
+use crate::modules::texture_atlas::{SpriteId, TextureAtlas};
+use crate::modules::tilt::{BoardTilt, TILT_PENALTY};
+use crate::modules::time_control::{GameSpeed, TimeControl};
+use crate::modules::tooltip::set_tooltip;
+use crate::modules::trails::TrailRenderer;
+use crate::modules::trajectory::TrajectoryPreview;
+use crate::modules::viewport::screen_view;
+use crate::modules::wallet::{format_multiplier, Wallet, COIN_CREDITS, DEFAULT_BET, MAX_BET, MIN_BET, STARTING_CREDITS};
+
+// Balls released by one multiball drop, and the fastest sideways push each one gets (pixels/second)
+const MULTIBALL_BALLS: u32 = 10;
+const MULTIBALL_SIDE_SPEED: f32 = 60.0;
+// Room (pixels) left around a body's collider bounds before it counts as out of view, for what is drawn
+// around it (outlines, thick lines)
+const CULL_MARGIN: f32 = 8.0;
+// How long "Player N's turn" shows over the board when the turn passes
+const TURN_BANNER_SECONDS: f32 = 2.0;
+// Seconds left on a timed round's clock from which it is shown in red
+const ROUND_WARNING_SECONDS: f32 = 10.0;
+// Real seconds "INSERT COIN" takes to blink on and off in an arcade round out of credits
+const INSERT_COIN_BLINK: f32 = 1.0;
+// What the confirmation dialog is asking about
+#[derive(Clone, Copy)]
+enum Confirm {
+    ClearBoard,
+    OverwriteBoard,
+}
+
+// Holding the Random button keeps dropping after DROP_REPEAT_DELAY seconds, and holding a bet button
+// keeps stepping the bet (seconds before the first repeat, then between repeats)
+const DROP_REPEAT_DELAY: f32 = 0.5;
+const DROP_REPEAT_INTERVAL: f32 = 0.25;
+const BET_REPEAT_DELAY: f32 = 0.4;
+const BET_REPEAT_INTERVAL: f32 = 0.1;
+// Smallest the small HUD text in the right column may get on screen (real pixels) in a small window
+const HUD_MIN_TEXT_SIZE: f32 = 11.0;
+// Gap between the widgets in the right column, and the room the hot-seat scoreboard takes up there
+const HUD_SPACING: f32 = 10.0;
+const SCOREBOARD_HEIGHT: f32 = 88.0;
+// Font sizes of the lines of text in the right column, the number of gaps between its widgets, and
+// the smallest the gaps may get when the text is scaled up
+const HUD_TEXT_SIZES: [u16; 6] = [26, 30, 30, 25, 20, 18];
+const HUD_GAPS: f32 = 14.0;
+const HUD_MIN_SPACING: f32 = 2.0;
+// Widest the text in the right column may get before it shrinks to fit
+const HUD_TEXT_WIDTH: f32 = RIGHT_COLUMN_WIDTH - 20.0;
+
+// Index of the theme called `name`, or of the first theme if there is none by that name
+fn theme_index(themes: &[BoardTheme], name: &str) -> usize {
+    themes.iter().position(|theme| theme.name() == name).unwrap_or(0)
+}
+
+// Colour of a breakable peg with `health` of its hit points left (1.0 = untouched): it fades as it cracks
+fn breakable_peg_color(health: f32) -> Color {
+    Color { a: 0.35 + 0.65 * health.clamp(0.0, 1.0), ..BREAKABLE_PEG_COLOR }
+}
+
+// Text of the risk button at `risk`
+fn risk_text(risk: RiskLevel) -> String {
+    format!("Risk: {}", risk.name())
+}
+
+// Shapes the shape dropdown offers after its first "Any Shape" option
+const SHAPE_CHOICES: [ShapeKind; 3] = [ShapeKind::Ball, ShapeKind::Square, ShapeKind::Triangle];
+
+// Picks one of the three droppable shapes at random
+fn random_kind() -> ShapeKind {
+    match rand::gen_range(0, 3) {
+        0 => ShapeKind::Ball,
+        1 => ShapeKind::Square,
+        _ => ShapeKind::Triangle,
+    }
+}
+
+pub struct PlinkoScene {
+    // The Random button picks between the circle, square and triangle maps while random_maps is on
+    peg_maps: [BoardConfig; 3],
+    random_maps: bool,
+    // Board (or Random Maps) picked in the presets gallery, waiting to be applied
+    board_choice: SharedBoardChoice,
+    // Where the board in play is handed to the editor
+    editor_board: SharedEditorBoard,
+    // The board as loaded, and the board in play: the same one fitted to the bin count in the settings
+    design: BoardConfig,
+    board: BoardConfig,
+    world: PhysicsWorld,
+    // What each body in the world is, and the bet and colour of each piece
+    entities: EntityRegistry,
+    audio: Audio,
+    settings: Settings,
+    // The board themes to pick from, and the one the board is drawn with
+    themes: Vec<BoardTheme>,
+    theme: usize,
+    atlas: Option<TextureAtlas>,
+    ball_sprite: Option<SpriteId>,
+    particles: ParticleSystem,
+    trails: TrailRenderer,
+    // Screen shake, hit-pause and squash-and-stretch set off by the game events
+    juice: Juice,
+
+    wallet: Wallet,
+    // Stake riding on each piece that hasn't landed yet, keyed by its body handle
+    stakes: HashMap<RigidBodyHandle, u32>,
+    // Multiplier each piece has built up from golden pegs and multiplier zones
+    multipliers: DropMultipliers,
+    // Bumpers lit up by a recent hit
+    bumpers: BumperFlashes,
+    // Recent flights of the pieces, replayed slowed down after a landing
+    replay: InstantReplay,
+    minimap: Minimap,
+    // Reels spun by every landing, paying a multiple of its win on three of a kind
+    slots: SlotMachine,
+    // Picks the reels' symbols; seeded with every new game
+    slot_rng: rand::RandGenerator,
+    lifetime: LifetimeManager,
+    // Record of every drop for the history panel and CSV export
+    history: DropHistory,
+
+    btn_random: TextButton,
+    btn_load_board: TextButton,
+    btn_bet_down: TextButton,
+    btn_bet_up: TextButton,
+    btn_risk: TextButton,
+    btn_mode: TextButton,
+    btn_settings: TextButton,
+    btn_menu: TextButton,
+    btn_share: TextButton,
+    btn_pause: TextButton,
+    btn_slow: TextButton,
+    btn_fast: TextButton,
+    btn_stats: TextButton,
+    btn_shapes: TextButton,
+    btn_reset_stats: TextButton,
+    btn_history: TextButton,
+    btn_multiball: TextButton,
+    btn_save_board: TextButton,
+    btn_edit: TextButton,
+    btn_clear: TextButton,
+    btn_tilt: TextButton,
+    btn_coin: TextButton,
+    // One per shape, in ShapeKind::ALL order, in an inventory round
+    btn_pieces: [TextButton; 3],
+    txt_board_name: TextInput,
+    dd_shape: Dropdown,
+    btn_boards: TextButton,
+    dd_ball: Dropdown,
+
+    lbl_balance: Label,
+    lbl_bet: Label,
+    lbl_last_win: Label,
+    lbl_board: Label,
+    lbl_seed: Label,
+    lbl_timer: Label,
+    prize_bar: BinLabelBar,
+    slot_machine: StillImage,
+    settings_panel: SettingsPanel,
+    history_panel: HistoryPanel,
+    // Side panel for the body clicked in inspect mode, toggled with the I key
+    inspector: InspectorPanel,
+    // The piece picked up with the mouse in sandbox mode
+    grab: PieceGrab,
+    // Tilts left this round, and the one tipping the board right now
+    tilt: BoardTilt,
+    // Asks before clearing the board or overwriting a saved board, and what it is asking about
+    dialog: Dialog,
+    confirming: Option<Confirm>,
+
+    random_mode: bool,
+    // How spread out the payout table is (the Risk button between the bet buttons)
+    risk: RiskLevel,
+    aimer: DropAimer,
+    trajectory: TrajectoryPreview,
+    odds: LandingOdds,
+    time: TimeControl,
+    // Frame rate and physics overlay, toggled with F1
+    perf: PerfHud,
+    // Log of the latest game events, toggled with the ` key
+    console: EventConsole,
+    stats: BinStats,
+    show_stats: bool,
+    // The three challenge objectives in the board's top-left corner, hidden with the O key
+    objectives: Objectives,
+    show_objectives: bool,
+    // Chain of landings in the same bin, paying more the longer it gets
+    combo: Combo,
+    // Banners calling out big wins, streaks and near misses
+    announcer: Announcer,
+    // The shape the next drop will be (shown as a ghost while aiming)
+    next_kind: ShapeKind,
+    // Whether the next drop is a multiball burst
+    multiball: bool,
+    // Drops waiting for the drop point to clear before they spawn
+    spawn_queue: SpawnQueue,
+    // The custom board being read from disk after Load Board was clicked
+    custom_board: Option<Coroutine<Result<BoardConfig, String>>>,
+    // Set when the player ran out of credits; the next on_enter() starts a new game
+    game_over: bool,
+    // Stake going to the prize wheel and the award coming back from it
+    bonus: SharedBonus,
+    // Center bin landings still owed a spin of the wheel (stake and owner), one spin each
+    pending_bonuses: VecDeque<(u32, Option<usize>)>,
+    // Seed for every drop, and a seed typed on the menu waiting to start a new game
+    seeds: SeedSequence,
+    requested_seed: SharedSeed,
+    // Whether the game seed was typed (kept for the next games) rather than random (rolled again
+    // for every new game)
+    seed_typed: bool,
+    // Drops and payouts of the current session, handed to the summary scene when it ends
+    session: SharedSession,
+    // How each shape falls, for the shape stats screen
+    telemetry: SharedShapeTelemetry,
+    summary: SessionSummary,
+    // Seconds of play left in a timed round (None when the round isn't timed)
+    round_left: Option<f32>,
+    // Pieces left to drop in an inventory round (None in any other round)
+    inventory: Option<Inventory>,
+    // Real seconds without input, after which an arcade round shows the attract mode
+    idle: IdleTimer,
+    // Writes the drops down for the attract mode to play back
+    demo: DemoRecorder,
+    // The players of a hot-seat game (None in a one-player game), the one whose piece won the
+    // bonus round being played, and how long the turn banner has left on screen
+    players: Option<PlayerManager>,
+    bonus_owner: Option<usize>,
+    turn_banner: f32,
+    lbl_turn: Label,
+    // Where the hot-seat scoreboard goes, and the virtual resolution and text scale the HUD was last laid out for
+    scoreboard_pos: Vec2,
+    hud_resolution: (f32, f32),
+    hud_text_scale: f32,
+}
+
+impl PlinkoScene {
+    /// Loads the boards, sounds, settings and images and builds the starting board.
+    pub async fn new(assets: &Assets, bonus: SharedBonus, requested_seed: SharedSeed, board_choice: SharedBoardChoice, editor_board: SharedEditorBoard, session: SharedSession, telemetry: SharedShapeTelemetry, demo: SharedDemo) -> Self {
+        // The boards shipped with the game are JSON files in assets/boards.
+        let classic_board = BoardConfig::load(CLASSIC_BOARD).await.unwrap();
+        let peg_maps = [
+            BoardConfig::load(CIRCLE_BOARD).await.unwrap(),
+            BoardConfig::load(SQUARE_BOARD).await.unwrap(),
+            BoardConfig::load(TRIANGLE_BOARD).await.unwrap(),
+        ];
+
+        // Dropdowns in the bottom row: shape and ball variant. Their lists open upwards over the board.
+        // Everything in the bottom row and the right-hand column is put in place by layout_hud.
+        let mut dd_shape = Dropdown::new(0.0, 0.0, 100.0, 34.0, &["Any Shape", "Ball", "Square", "Triangle"], 0);
+        dd_shape.with_open_up().with_font_size(18);
+        let ball_names: Vec<&str> = BallVariant::ALL.iter().map(|variant| variant.name()).collect();
+        let mut dd_ball = Dropdown::new(0.0, 0.0, 100.0, 34.0, &ball_names, 0);
+        dd_ball.with_open_up().with_font_size(18);
+
+        // The PhysicsWorld holds gravity, the pipeline, body/collider sets and all solvers.
+        let mut world = PhysicsWorld::new();
+
+        // Synthesizes the game's sounds; master volume scales every sound (0.0 - 1.0)
+        let mut audio = Audio::load().await;
+
+        // Gravity, bounciness, damping, volume and peg density saved from the last session
+        // Applied before the first board is built so the saved peg density is used
+        let settings = Settings::load();
+        settings.apply(&mut world, &mut audio);
+
+        // Sprites for the pieces, packed into one texture. If the images are missing every body
+        // just keeps its primitive rendering.
+        let atlas = TextureAtlas::load(&["assets/ball.png"]).await.ok();
+        let ball_sprite = atlas.as_ref().and_then(|atlas| atlas.sprite("assets/ball.png"));
+
+        // Build the starting board: ground, the classic peg grid, walls and bin dividers.
+        let design = classic_board;
+        let board = design.with_bin_count(settings.bins, settings.payout_layout);
+        world.build_board(&board);
+
+        // The player starts with STARTING_CREDITS; every drop costs the current bet
+        let wallet = Wallet::new(STARTING_CREDITS, DEFAULT_BET);
+
+        // Balance and bet readouts above the bet buttons
+        let mut lbl_balance = Label::new(format!("Credits: {}", wallet.balance()), 0.0, 0.0, 30);
+        lbl_balance.with_colors(WHITE, Some(BLACK)).with_max_width(HUD_TEXT_WIDTH);
+        let mut lbl_bet = Label::new(format!("Bet: {}", wallet.bet()), 0.0, 0.0, 30);
+        lbl_bet.with_colors(WHITE, Some(BLACK)).with_max_width(HUD_TEXT_WIDTH);
+        let mut lbl_last_win = Label::new("", 0.0, 0.0, 25);
+        lbl_last_win.with_colors(YELLOW, None).with_min_screen_size(HUD_MIN_TEXT_SIZE).with_max_width(HUD_TEXT_WIDTH);
+        // Name of the current board, or the error if the custom board failed to load
+        let mut lbl_board = Label::new(format!("Board: {}", board.name), 0.0, 0.0, 20);
+        lbl_board.with_colors(WHITE, None).with_min_screen_size(HUD_MIN_TEXT_SIZE).with_max_width(HUD_TEXT_WIDTH);
+        let mut lbl_seed = Label::new("", 0.0, 0.0, 18);
+        lbl_seed.with_colors(LIGHTGRAY, None).with_min_screen_size(HUD_MIN_TEXT_SIZE).with_max_width(HUD_TEXT_WIDTH);
+        // Clock of a timed round, above the balance
+        let mut lbl_timer = Label::new("", 0.0, 0.0, 26);
+        lbl_timer.with_colors(YELLOW, None).with_min_screen_size(HUD_MIN_TEXT_SIZE).with_max_width(HUD_TEXT_WIDTH);
+        // Whose turn it is in a hot-seat game, in the same spot
+        let mut lbl_turn = Label::new("", 0.0, 0.0, 24);
+        lbl_turn.with_colors(WHITE, None).with_min_screen_size(HUD_MIN_TEXT_SIZE).with_max_width(HUD_TEXT_WIDTH);
+        let mut txt_board_name = TextInput::new(0.0, 0.0, 180.0, 34.0, 20);
+        txt_board_name.with_placeholder("Board name").with_max_length(30);
+
+        // One label under each bin showing its payout multiplier
+        let mut prize_bar = BinLabelBar::new(600.0);
+        prize_bar.set_board(&board);
+
+        // Board themes from assets/themes; the settings name the one in use
+        let themes = load_themes().await;
+        let theme = theme_index(&themes, &settings.theme);
+        let mut settings_panel = SettingsPanel::new(200.0, 70.0, &settings);
+        settings_panel.with_themes(themes.iter().map(|theme| theme.name().to_string()).collect(), &settings);
+
+        // The Random button picks a random peg map, shape and drop column each time it is clicked,
+        // and keeps dropping while it is held down
+        // Parameters: x_pos, y_pos, width, height, label, background_color, hover_color, font_size
+        let mut btn_random = TextButton::new(-100.0, 500.0, 150.0, 60.0, "Random", ORANGE, GREEN, 25);
+        btn_random.with_repeat(DROP_REPEAT_DELAY, DROP_REPEAT_INTERVAL);
+        if let Some(icon) = assets.texture("ball") {
+            btn_random.with_icon(icon);
+        }
+        // Holding a bet button keeps stepping the bet
+        let mut btn_bet_down = TextButton::new(0.0, 0.0, 44.0, 50.0, "-", DARKGRAY, GRAY, 30);
+        btn_bet_down.with_repeat(BET_REPEAT_DELAY, BET_REPEAT_INTERVAL);
+        let mut btn_bet_up = TextButton::new(0.0, 0.0, 44.0, 50.0, "+", DARKGRAY, GRAY, 30);
+        btn_bet_up.with_repeat(BET_REPEAT_DELAY, BET_REPEAT_INTERVAL);
+        // Sits between the bet buttons, coloured by the risk level (see show_risk)
+        let mut btn_risk = TextButton::new(0.0, 0.0, 100.0, 50.0, risk_text(RiskLevel::Medium), DARKGRAY, GRAY, 20);
+        btn_risk.with_tooltip("Low risk pays close to the bet everywhere; high risk pays big on the edges and next to nothing in the middle");
+        let mut btn_mode = TextButton::new(0.0, 0.0, 180.0, 35.0, "Mode: Random", DARKPURPLE, PURPLE, 22);
+        btn_mode.with_tooltip("Random drops each piece in a random column; Aim drops it where you click");
+        let mut btn_multiball = TextButton::new(0.0, 0.0, 100.0, 34.0, "Multiball", DARKGRAY, GRAY, 20);
+        btn_multiball.with_tooltip("The next drop releases a burst of balls, one bet each");
+        let mut btn_clear = TextButton::new(0.0, 0.0, 180.0, 40.0, "Clear Board", MAROON, RED, 22);
+        btn_clear.with_tooltip("Removes every piece and rebuilds the board as it was loaded");
+        let mut btn_tilt = TextButton::new(0.0, 0.0, 180.0, 40.0, "", DARKBROWN, BROWN, 22);
+        btn_tilt.with_tooltip(format!("Tips the board to shake stuck pieces loose; every piece still falling pays {} less", format_multiplier(TILT_PENALTY)));
+        let mut btn_coin = TextButton::new(0.0, 0.0, 180.0, 40.0, "Insert Coin", DARKGREEN, GREEN, 22);
+        btn_coin.with_tooltip(format!("Adds {} credits (C)", COIN_CREDITS));
+        // The spawn buttons of an inventory round show their shape's icon and how many are left
+        let btn_pieces = ShapeKind::ALL.map(|kind| {
+            let mut button = TextButton::new(0.0, 0.0, 58.0, 40.0, "", DARKGRAY, GRAY, 20);
+            button.with_icon(shape_icon(kind)).with_tooltip(format!("{:?}s left to drop; picks the shape of the next drop", kind));
+            button
+        });
+        let mut btn_share = TextButton::new(0.0, 0.0, 72.0, 40.0, "Share", DARKBLUE, BLUE, 22);
+        btn_share.with_tooltip("Copies a code for this board and seed; paste it on the menu to play the same drops");
+
+        // The slot machine artwork shares the texture loaded at startup; if it failed to load the
+        // slot machine is drawn without it
+        let slot_machine = match assets.still_image("slot", 500.0, 500.0, 0.0, 0.0, true, 1.0) {
+            Some(image) => image,
+            None => StillImage::new("", 500.0, 500.0, 0.0, 0.0, true, 1.0).await,
+        };
+
+        let mut scene = Self {
+            peg_maps,
+            random_maps: true,
+            board_choice,
+            editor_board,
+            design,
+            board,
+            world,
+            entities: EntityRegistry::default(),
+            audio,
+            settings,
+            themes,
+            theme,
+            atlas,
+            ball_sprite,
+            // Pool of spark, dust and confetti particles spawned from the physics events
+            particles: ParticleSystem::new(MAX_PARTICLES),
+            // Fading lines behind fast pieces (switched on and off in the settings)
+            trails: TrailRenderer::new(),
+            juice: Juice::new(),
+
+            wallet,
+            stakes: HashMap::new(),
+            multipliers: DropMultipliers::new(),
+            bumpers: BumperFlashes::new(),
+            replay: InstantReplay::new(),
+            minimap: Minimap::new(),
+            slots: SlotMachine::new(),
+            slot_rng: rand::RandGenerator::new(),
+            // Removes pieces that have settled in a bin for a while or fallen off the board
+            lifetime: LifetimeManager::new(),
+            history: DropHistory::new(),
+
+            btn_random,
+            // Loads assets/boards/custom.json from disk so players can try their own layouts
+            btn_load_board: TextButton::new(0.0, 0.0, 180.0, 50.0, "Load Board", DARKBLUE, BLUE, 25),
+            // Bet controls in the right-hand column
+            btn_bet_down,
+            btn_bet_up,
+            btn_risk,
+            risk: RiskLevel::Medium,
+            // Toggles between random drops and aiming with the mouse
+            btn_mode,
+            // Opens the settings panel over the board
+            btn_settings: TextButton::new(0.0, 0.0, 180.0, 50.0, "Settings", DARKGRAY, GRAY, 25),
+            // Back to the title menu (the game carries on where it was when coming back)
+            btn_menu: TextButton::new(0.0, 0.0, 104.0, 40.0, "Menu", DARKGRAY, GRAY, 22),
+            btn_share,
+            // Time controls under the slot machine: pause, 0.25x slow motion and 4x fast-forward
+            btn_pause: TextButton::new(0.0, 0.0, 60.0, 40.0, "Pause", DARKGRAY, GRAY, 20),
+            btn_slow: TextButton::new(0.0, 0.0, 56.0, 40.0, "Slow", DARKGRAY, GRAY, 20),
+            btn_fast: TextButton::new(0.0, 0.0, 56.0, 40.0, "Fast", DARKGRAY, GRAY, 20),
+            btn_stats: TextButton::new(0.0, 0.0, 100.0, 34.0, "Stats", DARKGRAY, GRAY, 20),
+            btn_shapes: TextButton::new(0.0, 0.0, 72.0, 34.0, "Shapes", DARKBLUE, BLUE, 20),
+            btn_reset_stats: TextButton::new(0.0, 0.0, 100.0, 34.0, "Reset Stats", DARKGRAY, GRAY, 20),
+            // Opens the drop history list
+            btn_history: TextButton::new(0.0, 0.0, 84.0, 34.0, "History", DARKGRAY, GRAY, 20),
+            // Arms a multiball burst for the next drop
+            btn_multiball,
+            // Saves the current board under the name typed in the box above it
+            btn_save_board: TextButton::new(0.0, 0.0, 124.0, 40.0, "Save Board", DARKBLUE, BLUE, 22),
+            btn_edit: TextButton::new(0.0, 0.0, 52.0, 40.0, "Edit", DARKBLUE, BLUE, 22),
+            // Throws every piece off the board (after asking); not offered in hot-seat games
+            btn_clear,
+            // Tips the board for a moment, a few times a round; not offered in hot-seat games either
+            btn_tilt,
+            // Buys credits in an arcade round, in Clear Board's place
+            btn_coin,
+            // Drop the pieces of an inventory round, in Clear Board's place too
+            btn_pieces,
+            txt_board_name,
+            dd_shape,
+            // Opens the board presets gallery
+            btn_boards: TextButton::new(0.0, 0.0, 84.0, 34.0, "Boards", DARKBLUE, BLUE, 20),
+            dd_ball,
+
+            lbl_balance,
+            lbl_bet,
+            lbl_last_win,
+            lbl_board,
+            lbl_seed,
+            lbl_timer,
+            prize_bar,
+            slot_machine,
+            settings_panel,
+            dialog: Dialog::new(),
+            confirming: None,
+            history_panel: HistoryPanel::new(232.0, 150.0),
+            inspector: InspectorPanel::new(20.0, 110.0),
+            grab: PieceGrab::new(),
+            tilt: BoardTilt::new(),
+
+            random_mode: true,
+            // Marker the player drags along the top of the board in aim mode
+            aimer: DropAimer::new(),
+            // Dotted guess at the aimed piece's path
+            trajectory: TrajectoryPreview::new(),
+            odds: LandingOdds::new(),
+            time: TimeControl::new(),
+            perf: PerfHud::new(),
+            console: EventConsole::new(),
+            // Galton board statistics: which bin every piece ends in, shown as a histogram overlay
+            stats: BinStats::new(),
+            show_stats: false,
+            objectives: Objectives::new(),
+            show_objectives: true,
+            combo: Combo::new(),
+            announcer: Announcer::new(),
+            // Starts on "Any Shape"
+            next_kind: random_kind(),
+            multiball: false,
+            spawn_queue: SpawnQueue::new(),
+            custom_board: None,
+            game_over: false,
+            bonus,
+            pending_bonuses: VecDeque::new(),
+            seeds: SeedSequence::default(),
+            requested_seed,
+            seed_typed: false,
+            session,
+            telemetry,
+            summary: SessionSummary::default(),
+            round_left: None,
+            inventory: None,
+            idle: IdleTimer::new(ATTRACT_SECONDS),
+            demo: DemoRecorder::new(demo),
+            players: None,
+            bonus_owner: None,
+            turn_banner: 0.0,
+            lbl_turn,
+            scoreboard_pos: Vec2::ZERO,
+            hud_resolution: (0.0, 0.0),
+            hud_text_scale: 0.0,
+        };
+        scene.set_seed(random_game_seed());
+        scene.demo.start(&scene.board, &scene.settings);
+        scene.layout_hud();
+        scene
+    }
+
+    // Starts the drops over from a new game seed (shown under the board name)
+    fn set_seed(&mut self, seed: u64) {
+        self.seeds = SeedSequence::new(Some(seed));
+        self.lbl_seed.set_text(format!("Seed: {}", seed));
+    }
+
+    // Places the right-hand column and the bottom row from their anchors at the current virtual
+    // resolution, top to bottom and left to right
+    fn layout_hud(&mut self) {
+        self.hud_resolution = current_resolution();
+        self.hud_text_scale = text_scale();
+
+        // Bigger text takes up more of the column, so the gaps between the widgets shrink to keep it on screen
+        let text_growth: f32 = HUD_TEXT_SIZES.iter().map(|&size| scaled_font_size(size) as f32 - size as f32).sum();
+        let spacing = (HUD_SPACING - text_growth.max(0.0) / HUD_GAPS).max(HUD_MIN_SPACING);
+        let mut column = Layout::column(Anchor::RightColumn, vec2(0.0, 0.0), spacing);
+        // The clock of a timed round and whose turn it is share the top line
+        let pos = column.next_text(scaled_font_size(26));
+        self.lbl_timer.set_position(pos.x, pos.y);
+        self.lbl_turn.set_position(pos.x, pos.y);
+        let pos = column.next_text(scaled_font_size(30));
+        self.lbl_balance.set_position(pos.x, pos.y);
+        let pos = column.next_text(scaled_font_size(30));
+        self.lbl_bet.set_position(pos.x, pos.y);
+        let mut bet_buttons = column.row_in(50.0, 6.0);
+        for button in [&mut self.btn_bet_down, &mut self.btn_risk, &mut self.btn_bet_up] {
+            let pos = bet_buttons.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+        // The slot machine picture sits behind the column, from the mode button down
+        self.slot_machine.set_position(column.peek() - vec2(20.0, 0.0));
+        let pos = column.next(self.btn_mode.width, self.btn_mode.height);
+        self.btn_mode.update_position(pos.x, pos.y, None, None);
+        let pos = column.next_text(scaled_font_size(25));
+        self.lbl_last_win.set_position(pos.x, pos.y);
+        let pos = column.next(self.btn_load_board.width, self.btn_load_board.height);
+        self.btn_load_board.update_position(pos.x, pos.y, None, None);
+        let pos = column.next_text(scaled_font_size(20));
+        self.lbl_board.set_position(pos.x, pos.y);
+        let pos = column.next_text(scaled_font_size(18));
+        self.lbl_seed.set_position(pos.x, pos.y);
+        let pos = column.next(self.btn_settings.width, self.btn_settings.height);
+        self.btn_settings.update_position(pos.x, pos.y, None, None);
+        let mut menu_buttons = column.row_in(40.0, 4.0);
+        for button in [&mut self.btn_menu, &mut self.btn_share] {
+            let pos = menu_buttons.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+        let pos = column.next(180.0, 34.0);
+        self.txt_board_name.update_position(pos.x, pos.y);
+        let mut board_buttons = column.row_in(40.0, 4.0);
+        for button in [&mut self.btn_save_board, &mut self.btn_edit] {
+            let pos = board_buttons.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+        // Clear Board and Tilt aren't offered in hot-seat games, so the scoreboard takes their place;
+        // in an arcade round Insert Coin takes Clear Board's, and in an inventory round the spawn buttons do
+        let pos = column.next(180.0, SCOREBOARD_HEIGHT);
+        self.btn_clear.update_position(pos.x, pos.y, None, None);
+        self.btn_coin.update_position(pos.x, pos.y, None, None);
+        let mut piece_buttons = Layout::row(Anchor::TopLeft, pos, 3.0);
+        for button in &mut self.btn_pieces {
+            let pos = piece_buttons.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+        self.btn_tilt.update_position(pos.x, pos.y + SCOREBOARD_HEIGHT - self.btn_tilt.height, None, None);
+        self.scoreboard_pos = pos + vec2(0.0, 20.0);
+        let mut time_buttons = column.row_in(40.0, 4.0);
+        for button in [&mut self.btn_pause, &mut self.btn_slow, &mut self.btn_fast] {
+            let pos = time_buttons.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+
+        // Bottom row, left to right: shape, boards, ball variant, then the toggles
+        let mut row = Layout::row(Anchor::BottomLeft, vec2(40.0, -42.0), 4.0);
+        let pos = row.next(100.0, 34.0);
+        self.dd_shape.update_position(pos.x, pos.y);
+        let pos = row.next(self.btn_boards.width, self.btn_boards.height);
+        self.btn_boards.update_position(pos.x, pos.y, None, None);
+        let pos = row.next(100.0, 34.0);
+        self.dd_ball.update_position(pos.x, pos.y);
+        for button in [&mut self.btn_multiball, &mut self.btn_history, &mut self.btn_stats, &mut self.btn_reset_stats, &mut self.btn_shapes] {
+            let pos = row.next(button.width, button.height);
+            button.update_position(pos.x, pos.y, None, None);
+        }
+    }
+
+    // Swap in a new board: rebuilding clears all shapes (and any stakes riding on them)
+    // and recreates ground, pegs, walls and bins in one call
+    fn set_board(&mut self, board: BoardConfig) {
+        self.design = board;
+        self.lbl_board.set_text(format!("Board: {}", self.design.name));
+        self.fit_bins();
+        self.world.build_board(&self.board);
+        self.demo.start(&self.board, &self.settings);
+        self.entities.clear();
+        self.inspector.deselect();
+        self.odds.reset();
+        self.stakes.clear();
+        self.multipliers.clear();
+        self.bumpers.clear();
+        self.combo.reset();
+        self.lifetime.clear();
+        self.history.lose_all();
+        self.trails.clear();
+        self.juice.clear();
+        self.replay.clear();
+        // Drops still waiting to spawn never made it onto the board: their bets (and pieces) go back
+        for queued in self.spawn_queue.clear() {
+            self.refund_drop(queued);
+        }
+    }
+
+    // Switches to another peg map under the pieces still falling: they stay in play (and keep
+    // their stakes), only the board around them changes
+    fn swap_board(&mut self, board: BoardConfig) {
+        self.design = board;
+        self.lbl_board.set_text(format!("Board: {}", self.design.name));
+        self.fit_bins();
+        self.world.swap_board(&self.board);
+        self.demo.start(&self.board, &self.settings);
+        self.bumpers.clear();
+        // A streak of landings in one bin doesn't carry over to another board's bins
+        self.combo.reset();
+        self.odds.reset();
+    }
+
+    // Fits the board in play to the bin count and payout layout in the settings, and the payout
+    // table to the risk level
+    fn fit_bins(&mut self) {
+        self.board = self.design.with_bin_count(self.settings.bins, self.settings.payout_layout).with_risk(self.risk);
+        self.prize_bar.set_board(&self.board);
+    }
+
+    // Switches the payout table to `risk`: the labels, payouts and stats all read it from self.board
+    fn set_risk(&mut self, risk: RiskLevel) {
+        self.risk = risk;
+        self.fit_bins();
+        self.world.set_payouts(&self.board.payouts);
+        self.show_risk();
+    }
+
+    // Shows the risk level on its button, in green, gray or red
+    fn show_risk(&mut self) {
+        self.btn_risk.set_text(risk_text(self.risk));
+        (self.btn_risk.normal_color, self.btn_risk.hover_color) = match self.risk {
+            RiskLevel::Low => (DARKGREEN, GREEN),
+            RiskLevel::Medium => (DARKGRAY, GRAY),
+            RiskLevel::High => (MAROON, RED),
+        };
+    }
+
+    // Start over after a game over (or when a new round is asked for): full wallet, an empty board
+    // and a fresh session summary
+    fn new_game(&mut self, round: RoundKind) {
+        // An arcade round starts with nothing until a coin goes in
+        let credits = if round == RoundKind::Arcade { 0 } else { STARTING_CREDITS };
+        self.wallet = Wallet::new(credits, DEFAULT_BET);
+        self.spawn_queue.clear();
+        self.fit_bins();
+        self.world.build_board(&self.board);
+        self.demo.start(&self.board, &self.settings);
+        self.entities.clear();
+        self.inspector.deselect();
+        self.odds.reset();
+        self.stakes.clear();
+        self.multipliers.clear();
+        self.bumpers.clear();
+        self.combo.reset();
+        self.lifetime.clear();
+        self.history.lose_all();
+        self.trails.clear();
+        self.juice.clear();
+        self.replay.clear();
+        self.slots.clear();
+        self.lbl_last_win.set_text("");
+        self.multiball = false;
+        self.time.paused = false;
+        self.game_over = false;
+        self.summary = SessionSummary::new(round);
+        self.objectives.clear();
+        self.announcer.clear();
+        self.round_left = (round == RoundKind::Timed).then_some(ROUND_SECONDS);
+        self.inventory = (round == RoundKind::Inventory).then(Inventory::new);
+        self.players = match round {
+            RoundKind::HotSeat { players } => Some(PlayerManager::new(players)),
+            _ => None,
+        };
+        self.bonus_owner = None;
+        self.pending_bonuses.clear();
+        self.turn_banner = if self.players.is_some() { TURN_BANNER_SECONDS } else { 0.0 };
+        self.tilt.reset(&mut self.world);
+        // A seeded game replays the same drops from the start, first shape included
+        self.seeds.restart();
+        if let Some(base) = self.seeds.base() {
+            rand::srand(base);
+        }
+        // A seeded game's reels spin the same symbols every time too
+        self.slot_rng.srand(self.seeds.base().unwrap_or_else(|| rand::rand() as u64));
+        self.next_kind = self.pick_kind();
+    }
+
+    // Save the current board under the typed name, asking first if that would replace a saved board
+    fn request_save_board(&mut self) {
+        let name = self.txt_board_name.text();
+        if BoardConfig::is_saved(&name) {
+            self.dialog.open(&format!("Overwrite the saved board \"{}\"?", name.trim()), "Overwrite", "Cancel");
+            self.confirming = Some(Confirm::OverwriteBoard);
+        } else {
+            self.save_board();
+        }
+    }
+
+    // Save the current board under the typed name; the result shows on the board label
+    fn save_board(&mut self) {
+        match self.board.save_as(&self.txt_board_name.text()) {
+            Ok(path) => {
+                self.lbl_board.set_text(format!("Saved {}", path));
+                self.txt_board_name.set_text("");
+                self.txt_board_name.unfocus();
+            }
+            Err(error) => {
+                self.lbl_board.set_text(error);
+            }
+        }
+    }
+
+    // The shape chosen in the shape dropdown, or a random one for "Any Shape". In an inventory round
+    // it stays the shape picked on the spawn buttons while any are left, then moves on to one that is
+    fn pick_kind(&self) -> ShapeKind {
+        if let Some(inventory) = self.inventory {
+            return if inventory.count(self.next_kind) > 0 { self.next_kind } else { inventory.first_left().unwrap_or(self.next_kind) };
+        }
+        match self.dd_shape.selected() {
+            0 => random_kind(),
+            chosen => SHAPE_CHOICES[chosen - 1],
+        }
+    }
+
+    // Rolls the column the Random button drops in, with a seed of its own for the drop, and swaps in a
+    // random peg map while Random Maps is on. Returns the x to drop at and the drop's seed.
+    fn roll_drop(&mut self) -> (f32, u64) {
+        // Every drop reseeds the random number generator with a seed of its own, which the
+        // history records so the drop's random choices can be reproduced
+        let seed = self.seeds.next_seed();
+        rand::srand(seed);
+        // Roll a random number 1-6 (like rolling a dice) to determine spawn position
+        // This creates variety in where objects enter the game
+        let dice = rand::gen_range(0, 7);
+        let map = rand::gen_range(0, self.peg_maps.len());
+        // Map dice result to X coordinate: simulates random column selection
+        // Results spread across six different horizontal positions: 201, 300, 400, 501, 590, 690
+        let place = match dice {
+            1 => 201,
+            2 => 300,
+            3 => 400,
+            4 => 501,
+            5 => 590,
+            _ if self.next_kind == ShapeKind::Square && dice == 6 => 710,
+            6 => 690,
+            _ => 400,
+        };
+
+        // Each map has its own payout table. A board picked in the gallery stays put instead.
+        if self.random_maps {
+            self.swap_board(self.peg_maps[map].clone());
+        }
+        (place as f32, seed)
+    }
+
+    // Releases a burst of MULTIBALL_BALLS balls around x, each with its own stake and a random
+    // sideways push. The burst is kept far enough from the walls that no ball spawns inside one.
+    fn drop_multiball(&mut self, x: f32, seed: u64) {
+        self.multiball = false;
+        let zone = DropAimer::drop_zone(&self.board);
+        let half_width = PhysicsWorld::spread_half_width().min(zone.w / 2.0);
+        let x = x.clamp(zone.left() + half_width, zone.right() - half_width);
+        let spawns: Vec<(f32, f32, f32)> = PhysicsWorld::spread(x, DROP_Y, MULTIBALL_BALLS as usize)
+            .into_iter()
+            .map(|(ball_x, ball_y)| (ball_x, ball_y, rand::gen_range(-MULTIBALL_SIDE_SPEED, MULTIBALL_SIDE_SPEED)))
+            .collect();
+        let handles = self.world.spawn_batch(ShapeKind::Ball, &spawns);
+        let owner = self.players.as_ref().map(PlayerManager::current);
+        for (handle, (ball_x, _, _)) in handles.into_iter().zip(spawns) {
+            // can_afford_drops() was checked before arming, so every ball has its bet
+            let Some(stake) = self.wallet.place_bet() else {
+                self.world.remove_body(handle);
+                continue;
+            };
+            self.add_piece(handle, ShapeKind::Ball, ball_x, seed, stake, owner);
+        }
+    }
+
+    // The colour a piece is drawn in: its owner's in a hot-seat game, otherwise its ball variant's
+    // in the board theme (or the theme's colour for squares and triangles)
+    fn piece_color(&self, body: &RigidBody) -> Color {
+        let theme = &self.themes[self.theme];
+        let owner_color = self.players.as_ref().and_then(|players| players.owner_color(body));
+        let variant = body.colliders().first().and_then(|collider| self.world.colliders.get(*collider)).and_then(PhysicsWorld::ball_variant_of);
+        owner_color.or(variant.map(|variant| theme.ball_color(variant))).unwrap_or(theme.piece_color())
+    }
+
+    // Switches to the board theme named in the settings and repaints the pieces in play with it
+    fn apply_theme(&mut self) {
+        self.theme = theme_index(&self.themes, &self.settings.theme);
+        let pieces: Vec<(RigidBodyHandle, Color)> = self
+            .world
+            .bodies
+            .iter()
+            .filter(|(_, body)| PhysicsWorld::shape_of(body).is_some())
+            .map(|(handle, body)| (handle, self.piece_color(body)))
+            .collect();
+        for (handle, color) in pieces {
+            self.entities.set_color(handle, color);
+        }
+    }
+
+    // Bookkeeping for a piece that was just spawned: its stake, history record, sprite, entity and lifetime
+    fn add_piece(&mut self, handle: RigidBodyHandle, kind: ShapeKind, x: f32, seed: u64, stake: u32, owner: Option<usize>) {
+        self.history.record_drop(handle, kind, x, seed, stake);
+        self.summary.record_drop(stake);
+        self.demo.record_drop(&self.world, handle);
+        // Balls are drawn with the ball sprite; squares and triangles stay as outlines
+        if kind == ShapeKind::Ball
+            && let Some(sprite) = self.ball_sprite
+        {
+            TextureAtlas::assign(&mut self.world.bodies[handle], sprite);
+        }
+        if let Some(owner) = owner {
+            PlayerManager::tag(&mut self.world.bodies[handle], owner);
+        }
+        let color = self.piece_color(&self.world.bodies[handle]);
+        self.entities.register(handle, &self.world.bodies[handle], stake, color);
+        self.stakes.insert(handle, stake);
+        self.objectives.record_drop(handle, kind);
+        let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+        self.console.log(LogCategory::Spawn, format!("{}{} ({:?}) at x {:.0}, bet {}", who, piece_name(handle), kind, x, stake));
+        for removed in self.lifetime.track(&mut self.world, handle) {
+            self.stakes.remove(&removed);
+            self.multipliers.remove(removed);
+            self.history.lose(removed);
+            self.objectives.forget(removed);
+            self.console.log(LogCategory::Despawn, format!("{} removed (too many pieces)", piece_name(removed)));
+        }
+    }
+
+    // Hands a drop that never spawned back: its bet goes back to whoever placed it, its piece back
+    // into the inventory, and in a hot-seat game the drop back to its player if they are still on turn
+    fn refund_drop(&mut self, queued: QueuedDrop) {
+        self.owner_wallet(queued.owner).deposit(queued.stake);
+        if let (Some(players), Some(owner)) = (self.players.as_mut(), queued.owner) {
+            players.return_drop(owner);
+        }
+        if let Some(inventory) = self.inventory.as_mut() {
+            inventory.put_back(queued.kind);
+        }
+    }
+
+    // True in a coin-operated arcade round
+    fn arcade(&self) -> bool {
+        self.summary.round == RoundKind::Arcade
+    }
+
+    // True once a timed round's clock has run out
+    fn time_up(&self) -> bool {
+        self.round_left.is_some_and(|left| left <= 0.0)
+    }
+
+    // The wallet of hot-seat player `owner`, or the one-player wallet
+    fn owner_wallet(&mut self, owner: Option<usize>) -> &mut Wallet {
+        owner_wallet(self.players.as_mut(), &mut self.wallet, owner)
+    }
+
+    // The wallet drops are paid from: the player on turn's in a hot-seat game
+    fn wallet(&self) -> &Wallet {
+        turn_wallet(self.players.as_ref(), &self.wallet)
+    }
+
+    fn wallet_mut(&mut self) -> &mut Wallet {
+        turn_wallet_mut(self.players.as_mut(), &mut self.wallet)
+    }
+
+    // Whether a piece can be dropped now: the bet is covered, a timed round's clock hasn't run
+    // out, in a hot-seat game the player on turn has drops left, and in an inventory round there
+    // is a piece of the next drop's shape left
+    fn can_drop(&self) -> bool {
+        self.wallet().can_afford()
+            && !self.time_up()
+            && self.players.as_ref().is_none_or(|players| players.can_drop())
+            && self.inventory.is_none_or(|inventory| inventory.count(self.next_kind) > 0)
+    }
+
+    // Rebuild the board from the custom board file once it has been read
+    fn finish_loading_custom_board(&mut self) {
+        let Some(result) = self.custom_board.as_ref().and_then(|loading| loading.retrieve()) else {
+            return;
+        };
+        self.custom_board = None;
+        match result {
+            Ok(custom) => self.set_board(custom),
+            Err(error) => {
+                self.lbl_board.set_text(error);
+            }
+        }
+    }
+
+    // Writes the whole game to the quick-save slot (F5), saying how it went on the last-win line
+    fn quick_save(&mut self) {
+        if self.players.is_some() {
+            self.lbl_last_win.set_text("Hot-seat games can't be saved");
+            return;
+        }
+        // The anchor holding a grabbed piece isn't part of the game
+        self.grab.release(&mut self.world);
+        let snapshot = GameSnapshot {
+            version: SNAPSHOT_VERSION,
+            world: self.world.snapshot(),
+            design: self.design.clone(),
+            random_maps: self.random_maps,
+            wallet: self.wallet.clone(),
+            stakes: self.stakes.clone(),
+            multipliers: self.multipliers.clone(),
+            spawn_queue: self.spawn_queue.clone(),
+            lifetime: self.lifetime.clone(),
+            stats: self.stats.clone(),
+            history: self.history.clone(),
+            summary: self.summary.clone(),
+            seeds: self.seeds,
+            seed_typed: self.seed_typed,
+            round_left: self.round_left,
+            inventory: self.inventory,
+            objectives: self.objectives.clone(),
+            combo: self.combo.clone(),
+            risk: self.risk,
+            tilts_left: self.tilt.left(),
+        };
+        match snapshot.save() {
+            Ok(()) => self.lbl_last_win.set_text("Game saved (F9 to load)"),
+            Err(error) => {
+                self.console.log(LogCategory::Warning, format!("Quick save failed: {}", error));
+                self.lbl_last_win.set_text(error)
+            }
+        };
+    }
+
+    // Replaces the game with the one in the quick-save slot (F9)
+    fn quick_load(&mut self) {
+        let snapshot = match GameSnapshot::load() {
+            Ok(snapshot) => snapshot,
+            Err(error) => {
+                self.console.log(LogCategory::Warning, format!("Quick load failed: {}", error));
+                self.lbl_last_win.set_text(error);
+                return;
+            }
+        };
+        self.world.restore(snapshot.world);
+        // The pieces put back get their colours and bets again (0 for the ones that have landed);
+        // the board's bodies are picked up by the next update
+        self.entities.clear();
+        self.inspector.deselect();
+        let pieces: Vec<(RigidBodyHandle, Color)> = self
+            .world
+            .bodies
+            .iter()
+            .filter(|(_, body)| PhysicsWorld::shape_of(body).is_some())
+            .map(|(handle, body)| (handle, self.piece_color(body)))
+            .collect();
+        for (handle, color) in pieces {
+            let stake = snapshot.stakes.get(&handle).copied().unwrap_or(0);
+            self.entities.register(handle, &self.world.bodies[handle], stake, color);
+        }
+        // The world holds the board as it was fitted when saved, bins and all
+        self.design = snapshot.design;
+        self.board = self.world.board.clone().unwrap_or_else(|| self.design.clone());
+        self.prize_bar.set_board(&self.board);
+        self.odds.reset();
+        self.telemetry.borrow_mut().forget_pieces();
+        self.lbl_board.set_text(format!("Board: {}", self.design.name));
+        self.random_maps = snapshot.random_maps;
+        self.wallet = snapshot.wallet;
+        self.stakes = snapshot.stakes;
+        self.multipliers = snapshot.multipliers;
+        self.spawn_queue = snapshot.spawn_queue;
+        self.lifetime = snapshot.lifetime;
+        self.stats = snapshot.stats;
+        self.history = snapshot.history;
+        self.summary = snapshot.summary;
+        self.seeds = snapshot.seeds;
+        self.seed_typed = snapshot.seed_typed;
+        self.lbl_seed.set_text(self.seeds.base().map(|seed| format!("Seed: {}", seed)).unwrap_or_default());
+        self.round_left = snapshot.round_left;
+        self.inventory = snapshot.inventory;
+        self.objectives = snapshot.objectives;
+        self.combo = snapshot.combo;
+        self.risk = snapshot.risk;
+        self.show_risk();
+        self.tilt.restore(&mut self.world, snapshot.tilts_left);
+        self.players = None;
+        self.bonus_owner = None;
+        self.pending_bonuses.clear();
+        self.turn_banner = 0.0;
+        self.game_over = false;
+        self.trails.clear();
+        self.juice.clear();
+        self.replay.clear();
+        self.slots.clear();
+        self.bumpers.clear();
+        self.demo.start(&self.board, &self.settings);
+        self.lbl_last_win.set_text("Game loaded");
+    }
+
+    // Gives the peg under the mouse a tooltip saying what kind of peg it is, and a sticky pad one
+    // saying what it pays
+    fn set_peg_tooltip(&self) {
+        let (mouse_x, mouse_y) = mouse_position();
+        for pad in &self.board.sticky_pads {
+            let area = Rect::new(pad.x - pad.half_width, pad.y - pad.half_height, pad.half_width * 2.0, pad.half_height * 2.0);
+            if area.contains(vec2(mouse_x, mouse_y)) {
+                set_tooltip(area, format!("Sticky pad: holds a piece for {:.1}s, then pays {} its bet", pad.delay, format_multiplier(pad.bonus)));
+                return;
+            }
+        }
+        for (handle, body) in self.world.bodies.iter() {
+            if EntityKind::of(body) != Some(EntityKind::Peg) {
+                continue;
+            }
+            let (pos, _) = self.world.render_pose(handle, body);
+            for col_handle in body.colliders() {
+                let collider = &self.world.colliders[*col_handle];
+                let radius = collider.shape().compute_local_bounding_sphere().radius;
+                if vec2(mouse_x - pos.x, mouse_y - pos.y).length() > radius {
+                    continue;
+                }
+                let text = if self.world.is_bumper(*col_handle) {
+                    format!("Bumper: kicks pieces away and scores {} credits a hit", BUMPER_POINTS)
+                } else if PhysicsWorld::is_golden(collider) {
+                    "Golden peg: every piece that hits it gets +1 to its multiplier".to_string()
+                } else if let Some(health) = self.world.peg_health(*col_handle) {
+                    format!("Breakable peg ({:.0}% left): breaks after enough hits", health * 100.0)
+                } else if body.is_kinematic() {
+                    "Moving peg".to_string()
+                } else {
+                    "Peg".to_string()
+                };
+                set_tooltip(Rect::new(pos.x - radius, pos.y - radius, radius * 2.0, radius * 2.0), text);
+                return;
+            }
+        }
+    }
+}
+
+impl Scene for PlinkoScene {
+    fn on_enter(&mut self) {
+        // A seed typed on the menu starts a new game playing that seed's drops, and the menu's
+        // Timed Round or the summary's Play Again start a new round of their kind
+        let round = self.session.borrow_mut().request.take();
+        let seeded = self.requested_seed.take();
+        if seeded.is_some() || round.is_some() || self.game_over {
+            // A typed seed is kept for the games after it; otherwise every game rolls a new one
+            self.seed_typed |= seeded.is_some();
+            match seeded {
+                Some(seed) => self.set_seed(seed),
+                None if !self.seed_typed => self.set_seed(random_game_seed()),
+                None => {}
+            }
+            self.new_game(round.unwrap_or_default());
+        }
+        // Back from the presets gallery with a board picked
+        let choice = self.board_choice.borrow_mut().take();
+        match choice {
+            Some(BoardChoice::Preset(board)) => {
+                self.random_maps = false;
+                self.set_board(*board);
+            }
+            Some(BoardChoice::RandomMaps) => self.random_maps = true,
+            None => {}
+        }
+        // Back from the prize wheel: pay what it awarded
+        let award = self.bonus.borrow_mut().award.take();
+        if let Some(award) = award {
+            let owner = self.bonus_owner.take();
+            self.owner_wallet(owner).deposit(award);
+            self.summary.record_win(award);
+            self.lbl_last_win.set_text(format!("Bonus wheel: +{}", award));
+        }
+        // The music plays on the board only; by now the player has clicked through the menu, so
+        // a browser lets it start
+        self.audio.play_music();
+        self.idle.reset();
+    }
+
+    // A question left open when the game moves to another screen (a timed round running out, say)
+    // would keep blocking the input there
+    fn on_exit(&mut self) {
+        self.dialog.close();
+        self.confirming = None;
+        // ... and the music stops with the board
+        self.audio.stop_music();
+    }
+
+    fn update(&mut self, dt: f32) -> SceneChange {
+        if self.hud_resolution != current_resolution() || self.hud_text_scale != text_scale() {
+            self.layout_hud();
+        }
+        // Greyed out once the bet can't go any further that way
+        self.btn_bet_down.enabled = self.wallet().bet() > MIN_BET;
+        self.btn_bet_up.enabled = self.wallet().bet() < MAX_BET;
+        if self.btn_bet_down.click() {
+            self.audio.play_click();
+            self.wallet_mut().lower_bet();
+        }
+        if self.btn_bet_up.click() {
+            self.audio.play_click();
+            self.wallet_mut().raise_bet();
+        }
+        // The payout table can only change while nothing is riding on it
+        self.btn_risk.enabled = self.stakes.is_empty() && self.spawn_queue.is_empty();
+        if self.btn_risk.click() {
+            self.audio.play_click();
+            self.set_risk(self.risk.next());
+        }
+
+        if self.btn_settings.click() {
+            self.audio.play_click();
+            self.settings_panel.open(&self.settings);
+        }
+
+        // Typing a board name takes the keyboard: Escape only leaves the name box then, and Space types a space
+        let typing = self.txt_board_name.is_focused();
+        if typing && is_key_pressed(KeyCode::Escape) {
+            self.txt_board_name.unfocus();
+        }
+        if self.txt_board_name.update() | self.btn_save_board.click() {
+            self.audio.play_click();
+            self.request_save_board();
+        }
+
+        // Clearing throws away the stakes riding on the falling pieces, so it asks first
+        self.btn_clear.visible = self.players.is_none() && !self.arcade() && self.inventory.is_none();
+        if self.btn_clear.click() {
+            self.audio.play_click();
+            self.dialog.open("Clear all shapes off the board? Bets on falling pieces are lost.", "Clear", "Cancel");
+            self.confirming = Some(Confirm::ClearBoard);
+        }
+        // Tilting shakes loose the pieces still falling, at a price on each of their payouts
+        self.btn_tilt.visible = self.players.is_none();
+        self.btn_tilt.enabled = self.tilt.left() > 0 && !self.tilt.is_tilting() && !self.stakes.is_empty();
+        self.btn_tilt.set_text(format!("Tilt ({} left)", self.tilt.left()));
+        if self.btn_tilt.click() {
+            self.audio.play_click();
+            for piece in self.tilt.tilt(&mut self.world) {
+                self.multipliers.add(piece, -TILT_PENALTY);
+            }
+        }
+
+        // A coin buys credits in an arcade round; drops take their bets out of them as usual
+        self.btn_coin.visible = self.arcade();
+        if self.btn_coin.click() || (self.arcade() && !typing && is_key_pressed(KeyCode::C)) {
+            self.audio.play_click();
+            self.wallet.deposit(COIN_CREDITS);
+            self.lbl_last_win.set_text(format!("Coin in: +{}", COIN_CREDITS));
+        }
+
+        if self.btn_menu.click() || (!typing && !self.dialog.is_open() && is_key_pressed(KeyCode::Escape)) {
+            self.audio.play_click();
+            return SceneChange::Goto(SceneId::Menu);
+        }
+
+        if self.btn_share.click()
+            && let Some(seed) = self.seeds.base()
+        {
+            self.audio.play_click();
+            let code = ShareCode::new(self.board.clone(), seed).encode();
+            miniquad::window::clipboard_set(&code);
+            self.lbl_last_win.set_text(format!("Code copied ({} characters)", code.len()));
+        }
+
+        if self.btn_load_board.click() && self.custom_board.is_none() {
+            self.audio.play_click();
+            // Read the custom board fresh from disk each time so edits show up without restarting
+            self.custom_board = Some(start_coroutine(BoardConfig::load(CUSTOM_BOARD)));
+        }
+        self.finish_loading_custom_board();
+
+        // Switch between dropping at a random column and aiming with the mouse
+        if self.btn_mode.click() {
+            self.audio.play_click();
+            self.random_mode = !self.random_mode;
+            self.btn_mode.set_text(if self.random_mode { "Mode: Random" } else { "Mode: Aim" });
+        }
+
+        // X coordinate (and random seed) to drop the next piece at, if one is dropped this frame
+        let mut drop = None;
+
+        // An inventory round picks the shape on its spawn buttons instead
+        self.dd_shape.visible = self.inventory.is_none();
+        if self.dd_shape.update().is_some() {
+            self.audio.play_click();
+            self.next_kind = self.pick_kind();
+        }
+        if self.btn_boards.click() {
+            self.audio.play_click();
+            return SceneChange::Goto(SceneId::Presets);
+        }
+        if self.btn_shapes.click() {
+            self.audio.play_click();
+            return SceneChange::Goto(SceneId::ShapeStats);
+        }
+        if self.btn_edit.click() {
+            self.audio.play_click();
+            *self.editor_board.borrow_mut() = Some(self.design.clone());
+            return SceneChange::Goto(SceneId::Editor);
+        }
+        if let Some(index) = self.dd_ball.update() {
+            self.audio.play_click();
+            self.world.ball_variant = BallVariant::ALL[index];
+        }
+        // The ball dropdown takes a dark shade of the selected variant's colour
+        let shade = self.themes[self.theme].ball_color(self.world.ball_variant);
+        self.dd_ball.normal_color = Color::new(shade.r * 0.4, shade.g * 0.4, shade.b * 0.4, 1.0);
+        let dropdown_open = self.dd_shape.is_open() || self.dd_ball.is_open();
+
+        // Multiball can only be armed while the balance covers a bet for every ball (and never in
+        // a hot-seat game or an inventory round)
+        let multiball_allowed = self.players.is_none() && self.inventory.is_none() && self.wallet().can_afford_drops(MULTIBALL_BALLS);
+        if !multiball_allowed {
+            self.multiball = false;
+        }
+        self.btn_multiball.enabled = multiball_allowed;
+        if self.btn_multiball.click() {
+            self.audio.play_click();
+            self.multiball = !self.multiball;
+        }
+        self.btn_multiball.normal_color = if self.multiball { DARKBLUE } else { DARKGRAY };
+
+        // The spawn buttons of an inventory round show what is left of each shape and pick the shape
+        // dropped next, highlighted; a shape with none left is greyed out
+        let mut picked = None;
+        for (button, kind) in self.btn_pieces.iter_mut().zip(ShapeKind::ALL) {
+            let left = self.inventory.map_or(0, |inventory| inventory.count(kind));
+            button.visible = self.inventory.is_some();
+            button.enabled = left > 0;
+            button.set_text(format!("x{}", left));
+            button.normal_color = if kind == self.next_kind { DARKBLUE } else { DARKGRAY };
+            if button.click() {
+                picked = Some(kind);
+            }
+        }
+        if let Some(kind) = picked {
+            self.audio.play_click();
+            self.next_kind = kind;
+        }
+
+        // The Random button is greyed out once the balance can't cover the bet (or in aim mode,
+        // once a timed round is over, once the hot-seat player on turn is out of drops, or once an
+        // inventory round has none of the shape left)
+        let can_drop = self.can_drop();
+        self.btn_random.enabled = self.random_mode && can_drop;
+        if self.btn_random.click() {
+            self.audio.play_click();
+            drop = Some(self.roll_drop());
+        }
+        // In random mode a spawn button drops its shape straight away
+        if picked.is_some() && self.random_mode && can_drop {
+            drop = Some(self.roll_drop());
+        }
+
+        // In inspect mode a click on the board picks the body under it (I switches the mode)
+        if !typing && is_key_pressed(KeyCode::I) {
+            self.inspector.toggle();
+        }
+        let (mouse_x, mouse_y) = mouse_position();
+        // Clicks on the console's category chips are the console's too
+        let panel_open = self.settings_panel.visible
+            || self.history_panel.visible
+            || dropdown_open
+            || self.console.contains(vec2(mouse_x, mouse_y));
+        if is_mouse_button_pressed(MouseButton::Left)
+            && !input_blocked()
+            && !panel_open
+            && mouse_x < current_resolution().0 - RIGHT_COLUMN_WIDTH
+        {
+            self.inspector.select_at(&self.world, vec2(mouse_x, mouse_y));
+        }
+        // With the Sandbox setting on, pressing on a piece picks it up and letting go throws it
+        self.grab.enabled = self.settings.sandbox && !self.inspector.enabled && !panel_open;
+        let grabbing = self.grab.update(&mut self.world, vec2(mouse_x, mouse_y));
+
+        // In aim mode, releasing the mouse over the drop zone drops on the current board
+        self.aimer.enabled = !self.random_mode
+            && can_drop
+            && !self.inspector.enabled
+            && !grabbing
+            && !panel_open;
+        if let Some(x) = self.aimer.update(&self.board) {
+            let seed = self.seeds.next_seed();
+            rand::srand(seed);
+            drop = Some((x, seed));
+        }
+
+        if let Some((x, seed)) = drop {
+            if self.multiball {
+                self.drop_multiball(x, seed);
+            } else if self.spawn_queue.has_room()
+                && self.inventory.is_none_or(|inventory| inventory.count(self.next_kind) > 0)
+                && let Some(stake) = self.wallet_mut().place_bet()
+            {
+                // The piece only comes out of the inventory once the bet has gone through
+                if let Some(inventory) = self.inventory.as_mut() {
+                    inventory.take(self.next_kind);
+                }
+                // Spawned below once nothing is in the way at the drop point
+                let owner = self.players.as_ref().map(PlayerManager::current);
+                self.spawn_queue.push(QueuedDrop { kind: self.next_kind, x, y: DROP_Y, seed, stake, owner });
+                if let Some(players) = self.players.as_mut() {
+                    players.record_drop();
+                }
+            }
+            // Pick the piece after this one now so aim mode can preview it
+            self.next_kind = self.pick_kind();
+        }
+        for (handle, queued) in self.spawn_queue.release(&mut self.world) {
+            self.add_piece(handle, queued.kind, queued.x, queued.seed, queued.stake, queued.owner);
+        }
+
+        // Where a single aimed drop would go (a multiball burst spreads too much to preview)
+        if self.aimer.is_active() && !self.multiball {
+            self.trajectory.update(&self.world, self.next_kind, self.aimer.get_x(), dt);
+        } else {
+            self.trajectory.clear();
+        }
+        // Odds are only shown in aim mode; there they are worked out in the background too
+        if !self.random_mode {
+            let aiming = self.aimer.is_active() && !self.multiball;
+            self.odds.update(&self.world, aiming.then(|| self.aimer.get_x()));
+        }
+
+        // ----- TIME CONTROLS -----
+        // Space also pauses; the buttons light up while their mode is on
+        if self.btn_pause.click() || (!typing && !self.dialog.is_open() && is_key_pressed(KeyCode::Space)) {
+            self.audio.play_click();
+            self.time.toggle_pause();
+        }
+        if self.btn_slow.click() {
+            self.audio.play_click();
+            self.time.toggle_slow_motion();
+        }
+        if self.btn_fast.click() {
+            self.audio.play_click();
+            self.time.toggle_fast_forward();
+        }
+        if !typing && !self.dialog.is_open() && is_key_pressed(KeyCode::F5) {
+            self.quick_save();
+        }
+        if !typing && !self.dialog.is_open() && is_key_pressed(KeyCode::F9) {
+            self.quick_load();
+        }
+        if !typing && is_key_pressed(KeyCode::GraveAccent) {
+            self.console.visible = !self.console.visible;
+        }
+        if !typing {
+            self.console.update();
+        }
+        if !typing && is_key_pressed(KeyCode::F1) {
+            self.perf.visible = !self.perf.visible;
+        }
+        if self.perf.visible && !typing && is_key_pressed(KeyCode::F2) {
+            self.perf.dim_sleeping = !self.perf.dim_sleeping;
+        }
+        if self.perf.visible && !typing && is_key_pressed(KeyCode::F3) {
+            self.perf.batched = !self.perf.batched;
+        }
+        self.btn_pause.set_text(if self.time.paused { "Play" } else { "Pause" });
+        self.btn_pause.normal_color = if self.time.paused { DARKBLUE } else { DARKGRAY };
+        self.btn_slow.normal_color = if self.time.speed == GameSpeed::SlowMotion { DARKBLUE } else { DARKGRAY };
+        self.btn_fast.normal_color = if self.time.speed == GameSpeed::FastForward { DARKBLUE } else { DARKGRAY };
+        // Simulated time for this frame; everything in the game world runs on it, the UI doesn't
+        // (and none passes during a hit-pause)
+        let sim_dt = if self.juice.hit_paused() { 0.0 } else { self.time.scaled(dt) };
+
+        // A drop whose spawn point has stayed blocked too long (in game time) is called off
+        if let Some(queued) = self.spawn_queue.expire(sim_dt) {
+            self.refund_drop(queued);
+            self.console.log(LogCategory::Warning, format!("Drop at x {:.0} called off (spawn point blocked), bet {} refunded", queued.x, queued.stake));
+        }
+
+        // A tilt tips the board for a moment of game time; the screen shake winds down in real time
+        self.tilt.update(&mut self.world, sim_dt, dt);
+
+        // ----- PHYSICS SIMULATION STEP -----
+        // Run as many fixed-length physics steps as this frame's (scaled) duration covers
+        // Using fixed steps keeps gameplay speed the same regardless of monitor refresh rate
+        // (timed for the performance overlay)
+        let physics_start = get_time();
+        let steps = self.world.advance(sim_dt);
+        self.perf.update(dt, (get_time() - physics_start) as f32, steps);
+        // Game time the steps covered
+        let seconds = steps as f32 * self.world.integration_params.dt;
+
+        // ----- ROUND CLOCK -----
+        // A timed round's clock runs on the fixed physics steps actually taken, not on frame time:
+        // pausing stops it, and a long frame the physics only partly catches up on doesn't take more
+        // off the clock than was played. Near the end it turns red, and once it is out the pieces
+        // still falling land (and pay) before the summary
+        if let Some(left) = self.round_left.as_mut() {
+            *left = (*left - seconds).max(0.0);
+            let whole = left.ceil() as u32;
+            let text = if whole == 0 { "Time's up!".to_string() } else { format!("Time: {}:{:02}", whole / 60, whole % 60) };
+            let color = if *left <= ROUND_WARNING_SECONDS { RED } else { YELLOW };
+            self.lbl_timer.set_text(text).with_colors(color, None);
+        }
+
+        // ----- TRAILS -----
+        // Only record while the physics moves, so pausing freezes the trails too
+        self.trails.enabled = self.settings.trails;
+        if steps > 0 {
+            self.trails.update(&self.world);
+        }
+
+        // ----- IMPACT SOUNDS -----
+        // Pieces that hit a peg or wall this frame tick or thud, louder the faster they were going
+        self.audio.play_events(self.world.events());
+
+        // ----- MUSIC -----
+        // Layers join the music as more pieces fall and the combo grows, and fade out as it calms down
+        self.audio.update_music(self.stakes.len(), self.combo.length(), dt);
+
+        // ----- STATISTICS -----
+        // Count every piece that settles (kept across board rebuilds until reset)
+        self.stats.record_events(self.world.events(), self.board.bins.count);
+        self.demo.update(seconds);
+        self.entities.update(&self.world, seconds);
+        self.telemetry.borrow_mut().update(&self.world, seconds);
+        self.console.record_events(self.world.events());
+        if self.btn_stats.click() {
+            self.audio.play_click();
+            self.show_stats = !self.show_stats;
+            self.btn_stats.set_text(if self.show_stats { "Hide Stats" } else { "Stats" });
+        }
+        if self.btn_reset_stats.click() {
+            self.audio.play_click();
+            self.stats.reset();
+        }
+        if self.btn_history.click() {
+            self.audio.play_click();
+            self.history_panel.open();
+        }
+        self.history.update(sim_dt);
+
+        // ----- PARTICLES -----
+        // Sparks for hard peg hits, dust for ground landings, confetti for high-value bins
+        self.particles.emit_for_events(&self.world, self.world.events());
+        self.particles.update(sim_dt);
+        self.combo.update(sim_dt);
+        self.announcer.update(dt);
+
+        // ----- JUICE -----
+        // Shake for hard hits, a hit-pause for the jackpot and a squash for every landing
+        self.juice.settings = self.settings.juice;
+        self.juice.record_events(&self.world, self.world.events());
+        self.juice.update(dt, sim_dt);
+
+        // ----- MULTIPLIERS -----
+        // Golden peg hits add +1 to a piece's multiplier, zones add their bonus
+        self.multipliers.record_events(self.world.events(), &self.board);
+
+        // ----- BUMPERS -----
+        // Every bumper hit lights the bumper up and scores a few credits for whoever dropped the piece
+        for piece in self.bumpers.record_events(self.world.events()) {
+            let owner = self.world.bodies.get(piece).and_then(PlayerManager::owner);
+            self.owner_wallet(owner).deposit(BUMPER_POINTS);
+            self.summary.record_win(BUMPER_POINTS);
+        }
+        self.bumpers.update(sim_dt);
+
+        // ----- INSTANT REPLAY -----
+        // Every landing is offered as a slowed-down replay for a few seconds; R plays it
+        self.replay.record(&self.world, seconds);
+        self.replay.record_events(self.world.events(), |handle| self.entities.get(handle).map_or(WHITE, |entity| entity.color));
+        if !typing && !self.dialog.is_open() && is_key_pressed(KeyCode::R) {
+            self.replay.play();
+        }
+        self.replay.update(dt);
+
+        // ----- BLOCKS -----
+        // Every block knocked off a block stack scores its points for whoever dropped the piece behind it
+        let toppled: Vec<GameEvent> = self.world.events().iter().filter(|event| matches!(event, GameEvent::BlockToppled { points, .. } if *points > 0)).copied().collect();
+        for event in toppled {
+            if let GameEvent::BlockToppled { piece, points, .. } = event {
+                let owner = self.world.bodies.get(piece).and_then(PlayerManager::owner);
+                self.owner_wallet(owner).deposit(points);
+                self.summary.record_win(points);
+                let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+                self.console.log(LogCategory::Payout, format!("{}{} toppled a block: +{}", who, piece_name(piece), points));
+            }
+        }
+
+        // ----- BIN PAYOUTS -----
+        // Pay out every piece that has come to rest in a bin: stake × bin value × accumulated multiplier,
+        // times the combo multiplier when it landed in the same bin as the landings before it
+        // A piece in the center bin also earns a spin of the prize wheel
+        let mut finished: Vec<(Objective, Option<usize>)> = Vec::new();
+        for event in self.world.events() {
+            if let GameEvent::Settled { piece, bin } = *event
+                && let Some(stake) = self.stakes.remove(&piece)
+            {
+                let payout = self.board.payouts[bin];
+                let multiplier = self.multipliers.take(piece);
+                let combo = self.combo.land(bin);
+                // In a hot-seat game the piece pays whoever dropped it, even if the turn has moved on
+                let owner = self.world.bodies.get(piece).and_then(PlayerManager::owner);
+                let won = owner_wallet(self.players.as_mut(), &mut self.wallet, owner).pay_out(stake, payout * multiplier * combo);
+                self.announcer.record_landing(&self.board, bin, stake, won);
+                self.history.land(piece, bin, won);
+                self.summary.record_landing(bin, self.board.bins.count, won);
+                finished.extend(self.objectives.record_landing(piece, bin, won).into_iter().map(|objective| (objective, owner)));
+                let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+                self.console.log(
+                    LogCategory::Payout,
+                    format!("{}{} paid {} (bet {} × {} × {} × combo {})", who, piece_name(piece), won, stake, format_multiplier(payout), format_multiplier(multiplier), format_multiplier(combo)),
+                );
+                let combo_text = if combo > 1.0 { format!(" combo {}", format_multiplier(combo)) } else { String::new() };
+                if multiplier > 1.0 {
+                    self.lbl_last_win.set_text(format!("{}Won {} ({} × {}){}", who, won, format_multiplier(payout), format_multiplier(multiplier), combo_text));
+                } else {
+                    self.lbl_last_win.set_text(format!("{}Won {} ({}){}", who, won, format_multiplier(payout), combo_text));
+                }
+                self.audio.play_jingle();
+                self.slots.spin(won, owner);
+                if self.board.is_center_bin(bin) {
+                    self.pending_bonuses.push_back((stake, owner));
+                }
+            }
+        }
+
+        // ----- STICKY PADS -----
+        // A piece whose wait on a sticky pad is over pays stake × pad bonus × accumulated multiplier;
+        // the piece is already gone from the world
+        let paid: Vec<GameEvent> = self.world.events().iter().filter(|event| matches!(event, GameEvent::StickyPayout { .. })).copied().collect();
+        for event in paid {
+            if let GameEvent::StickyPayout { piece, bonus, user_data, .. } = event
+                && let Some(stake) = self.stakes.remove(&piece)
+            {
+                let multiplier = self.multipliers.take(piece);
+                let owner = PlayerManager::owner_from(user_data);
+                let won = self.owner_wallet(owner).pay_out(stake, bonus * multiplier);
+                self.history.lose(piece);
+                self.objectives.forget(piece);
+                self.summary.record_win(won);
+                let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+                self.console.log(
+                    LogCategory::Payout,
+                    format!("{}{} paid {} on a sticky pad (bet {} × {} × {})", who, piece_name(piece), won, stake, format_multiplier(bonus), format_multiplier(multiplier)),
+                );
+                self.lbl_last_win.set_text(format!("{}Sticky pad: won {} ({})", who, won, format_multiplier(bonus * multiplier)));
+                self.audio.play_jingle();
+            }
+        }
+
+        // ----- WATCHDOG -----
+        // Pieces the physics removed for blowing up or leaving the board; their bet is given back
+        // if the settings say so, otherwise it is lost like a piece cleared off the board
+        let lost: Vec<GameEvent> = self.world.events().iter().filter(|event| matches!(event, GameEvent::PieceLost { .. })).copied().collect();
+        for event in lost {
+            if let GameEvent::PieceLost { piece, reason, user_data } = event
+                && let Some(stake) = self.stakes.remove(&piece)
+            {
+                self.multipliers.remove(piece);
+                self.history.lose(piece);
+                self.objectives.forget(piece);
+                let owner = PlayerManager::owner_from(user_data);
+                let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+                if self.settings.refund_lost {
+                    self.owner_wallet(owner).deposit(stake);
+                    self.lbl_last_win.set_text(format!("{}Piece lost ({}), refunded {}", who, reason.name(), stake));
+                } else {
+                    self.lbl_last_win.set_text(format!("{}Piece lost ({})", who, reason.name()));
+                }
+            }
+        }
+
+        // ----- SLOT REELS -----
+        // Three of a kind pays the landing's win again, times the symbol's multiplier less one
+        if let Some(result) = self.slots.update(sim_dt, &self.slot_rng) {
+            let bonus = result.bonus();
+            if bonus > 0 {
+                self.owner_wallet(result.owner).deposit(bonus);
+                self.summary.record_win(bonus);
+                let who = result.owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+                let symbol = result.symbols[0];
+                self.lbl_last_win.set_text(format!("{}Slots: 3 {}s x{} +{}", who, symbol.name(), symbol.multiplier(), bonus));
+                self.console.log(LogCategory::Payout, format!("{}Slots paid {}", who, bonus));
+                self.audio.play_jingle();
+            }
+        }
+
+        // ----- DESPAWN -----
+        // Fade out and remove pieces that have settled in a bin, and drop any that fell off the board
+        for removed in self.lifetime.update(&mut self.world, sim_dt) {
+            self.stakes.remove(&removed);
+            self.multipliers.remove(removed);
+            self.history.lose(removed);
+            self.objectives.forget(removed);
+            self.console.log(LogCategory::Despawn, format!("{} despawned", piece_name(removed)));
+        }
+
+        // ----- OBJECTIVES -----
+        // Finished objectives pay whoever landed the piece that finished them; failed ones are
+        // replaced along with them, keeping three on the board
+        for (objective, owner) in finished {
+            self.owner_wallet(owner).deposit(objective.reward);
+            self.summary.record_win(objective.reward);
+            let who = owner.map(|owner| format!("P{} ", owner + 1)).unwrap_or_default();
+            self.lbl_last_win.set_text(format!("{}Objective done: +{}", who, objective.reward));
+            self.console.log(LogCategory::Payout, format!("{}Objective \"{}\" paid {}", who, objective.describe(), objective.reward));
+            self.audio.play_jingle();
+        }
+        let bet = self.wallet().bet();
+        for objective in self.objectives.rotate(&self.board, bet) {
+            self.console.log(LogCategory::Warning, format!("Objective \"{}\" failed", objective.describe()));
+        }
+        if !typing && is_key_pressed(KeyCode::O) {
+            self.show_objectives = !self.show_objectives;
+        }
+        if !typing && is_key_pressed(KeyCode::M) {
+            self.minimap.visible = !self.minimap.visible;
+        }
+
+        self.lbl_balance.set_text(format!("Credits: {}", self.wallet().balance()));
+        self.lbl_bet.set_text(format!("Bet: {}", self.wallet().bet()));
+        if let Some(players) = self.players.as_ref() {
+            let player = players.current_player();
+            self.lbl_balance.set_text(format!("P{} Credits: {}", players.current() + 1, player.wallet.balance()));
+            self.lbl_turn.set_text(format!("{}: {} drops left", player.name, players.drops_left()));
+            self.lbl_turn.with_colors(player.color, None);
+        }
+        self.turn_banner = (self.turn_banner - dt).max(0.0);
+
+        // ----- BONUS ROUND -----
+        // The board waits (nothing is simulated) while the wheel is spun; two pieces reaching the
+        // center bin together earn a spin each, one after the other
+        if let Some((stake, owner)) = self.pending_bonuses.pop_front() {
+            self.bonus_owner = owner;
+            *self.bonus.borrow_mut() = BonusRound { stake, award: None };
+            return SceneChange::Goto(SceneId::PrizeWheel);
+        }
+
+        // ----- HOT SEAT TURNS -----
+        // Once the player on turn is out of drops (or credits) and their pieces have all landed,
+        // the board goes to the next player; when nobody has turns left the winner is shown
+        if let Some(players) = self.players.as_mut()
+            && players.turn_over()
+            && self.stakes.is_empty()
+            && self.spawn_queue.is_empty()
+            && self.slots.is_idle()
+        {
+            if players.next_turn() {
+                self.turn_banner = TURN_BANNER_SECONDS;
+            } else {
+                let standings = players.standings();
+                let best = standings.first().map_or(0, |standing| standing.balance);
+                self.summary.finish(SessionEnd::OutOfCredits, best, &self.board);
+                let mut session = self.session.borrow_mut();
+                session.summary = Some(self.summary.clone());
+                session.standings = Some(standings);
+                self.game_over = true;
+                return SceneChange::Goto(SceneId::Winner);
+            }
+        }
+
+        // ----- ATTRACT MODE -----
+        // An arcade round left alone with nothing in play hands the screen to the demo
+        self.idle.set_limit(self.settings.attract_seconds);
+        let idle = self.idle.update(dt);
+        if self.arcade() && idle && self.stakes.is_empty() && self.spawn_queue.is_empty() && self.slots.is_idle() {
+            return SceneChange::Goto(SceneId::Attract);
+        }
+
+        // ----- GAME OVER -----
+        // Out of credits (or out of time in a timed round, or out of pieces in an inventory round)
+        // with nothing left in play that could still win some back: hand the session's summary over
+        // and show it. An arcade round waits for another coin instead
+        let end = if self.players.is_some() || self.arcade() {
+            None
+        } else if self.inventory.is_some_and(|inventory| inventory.is_empty()) {
+            Some(SessionEnd::OutOfPieces)
+        } else if !self.wallet.can_afford() {
+            Some(SessionEnd::OutOfCredits)
+        } else if self.time_up() {
+            Some(SessionEnd::TimeUp)
+        } else {
+            None
+        };
+        if let Some(end) = end
+            && self.stakes.is_empty()
+            && self.spawn_queue.is_empty()
+            && self.slots.is_idle()
+        {
+            self.summary.finish(end, self.wallet.balance(), &self.board);
+            self.session.borrow_mut().summary = Some(self.summary.clone());
+            self.game_over = true;
+            return SceneChange::Goto(SceneId::GameOver);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.set_peg_tooltip();
+        // Everything on the board and the HUD is queued on its render layer and drawn in layer
+        // order by the renderer, whatever order it is queued in (see renderer.rs)
+        // The replay view and the minimap are drawn into their own viewports before anything goes on the screen
+        self.replay.render(&self.world);
+        self.minimap.render(&self.world, &self.board, |handle| self.entities.get(handle).map_or(WHITE, |entity| entity.color));
+        // The board and HUD shake while the board is tilted or after a big hit; the panels drawn after them don't
+        offset_camera(self.tilt.shake_offset() + self.juice.shake_offset());
+        // Nothing outside what the camera shows is drawn
+        let view = screen_view();
+        let mut renderer = Renderer::new().batched(self.perf.batched).culled(view);
+        let theme = &self.themes[self.theme];
+
+        // The theme's background and parallax layers, then the multiplier zones and force fields,
+        // sit behind everything else on the board
+        renderer.custom(Layer::Background, || theme.draw_background(get_time() as f32));
+        renderer.custom(Layer::Background, || {
+            DropMultipliers::draw_zones(&self.board.zones);
+            draw_force_fields(&self.board.force_fields, get_time() as f32);
+        });
+        // Trails go under the pieces, in the colour each piece is drawn in
+        renderer.custom(Layer::Background, || self.trails.draw(|handle| self.entities.get(handle).map_or(WHITE, |entity| entity.color)));
+        // Chains, link by link and joint by joint, with the pegs
+        renderer.custom(Layer::Pegs, || draw_chains(&self.world));
+        // The pool's water over the pieces sinking through it, under the walls and bin dividers
+        renderer.custom(Layer::Walls, || draw_pool(&self.board, get_time() as f32));
+
+        // ----- RENDER ALL PHYSICS BODIES -----
+        // Iterate through all bodies in the physics world and queue them on the layer of their kind
+        for (handle, body) in self.world.bodies.iter() {
+            // Get the body's world position (center point coordinates) and rotation angle in radians
+            // Moving bodies are interpolated between the last two physics steps for smooth motion
+            // The rotation is used to properly orient polygon shapes (balls rotate too but it's not visible)
+            let (pos, rot) = self.world.render_pose(handle, body);
+            // Pieces that have just landed are drawn squashed, springing back to their shape
+            let squash = self.juice.squash(handle);
+            // Bodies entirely outside the view are skipped (sprites included, which the renderer can't cull itself);
+            // the bounds are where the physics has the body, padded for how far it is drawn from there
+            let bounds = body.colliders().iter().filter_map(|collider| self.world.colliders.get(*collider)).map(|collider| collider.compute_aabb()).reduce(|a, b| a.merged(&b));
+            if let Some(aabb) = bounds {
+                let reach = CULL_MARGIN + (vec2(pos.x, pos.y) - vec2(body.translation().x, body.translation().y)).length();
+                let bounds = Rect::new(aabb.mins.x - reach, aabb.mins.y - reach, aabb.maxs.x - aabb.mins.x + reach * 2.0, aabb.maxs.y - aabb.mins.y + reach * 2.0);
+                if !renderer.is_visible(bounds) {
+                    continue;
+                }
+            }
+            // Pieces on their way out fade towards transparent, and sleeping ones can be dimmed
+            let opacity = self.lifetime.opacity(handle) * self.perf.body_alpha(body);
+            // What the body is decides its colour and layer; bodies without a kind tag aren't drawn
+            let Some(entity) = self.entities.lookup(handle, body) else {
+                continue;
+            };
+            let layer = Layer::of(entity.kind);
+            let owner_color = entity.owner.map(|_| entity.color);
+
+            // ----- RENDER SPRITES -----
+            // Bodies with a sprite are drawn as the texture stretched over their collider
+            if let (Some(atlas), Some(sprite)) = (self.atlas.as_ref(), TextureAtlas::body_sprite(body)) {
+                for col_handle in body.colliders() {
+                    let collider = &self.world.colliders[*col_handle];
+                    let aabb = collider.shape().compute_local_aabb();
+                    let size = vec2(aabb.maxs.x - aabb.mins.x, aabb.maxs.y - aabb.mins.y);
+                    // Normal balls show the sprite as it is, the other variants tint it with their colour
+                    // and in a hot-seat game every piece takes its owner's colour
+                    let tint = match (owner_color, PhysicsWorld::ball_variant_of(collider)) {
+                        (Some(color), _) => color,
+                        (None, Some(variant)) if variant != BallVariant::Normal => theme.ball_color(variant),
+                        _ => WHITE,
+                    };
+                    renderer.custom(layer, move || atlas.draw(sprite, vec2(pos.x, pos.y), rot, size * squash, Color { a: opacity, ..tint }));
+                }
+                continue;
+            }
+
+            // Iterate through all collision shapes attached to this body
+            // A body can have multiple colliders (though our game uses one per body)
+            for col_handle in body.colliders() {
+                // Get reference to the collision shape object from the collider set
+                let collider = &self.world.colliders[*col_handle];
+                // Multiplier zone sensors are drawn separately by DropMultipliers::draw_zones, and
+                // chains by draw_chains
+                if collider.is_sensor() || entity.kind == EntityKind::Chain {
+                    continue;
+                }
+                // Extract the geometric shape from the collider (can be ball, convex polygon, etc.)
+                let shape = collider.shape();
+                let color = match entity.kind {
+                    // Pieces in their owner's or ball variant's colour, fading on their way out
+                    EntityKind::Piece(_) => Color { a: opacity, ..entity.color },
+                    // Bumpers flash when hit, golden pegs raise the multiplier, breakable pegs fade as they take damage
+                    EntityKind::Peg if self.world.is_bumper(*col_handle) => self.bumpers.color(handle),
+                    EntityKind::Peg if PhysicsWorld::is_golden(collider) => GOLDEN_PEG_COLOR,
+                    EntityKind::Peg => self.world.peg_health(*col_handle).map_or(theme.peg_color(), breakable_peg_color),
+                    EntityKind::StickyPad => STICKY_PAD_COLOR,
+                    EntityKind::Block => entity.color,
+                    // The ground, walls, bin dividers and funnels
+                    _ => theme.wall_color(),
+                };
+
+                // ----- RENDER SHAPES -----
+                // Balls and round pegs as circles (squashed into ellipses after a landing), the ground,
+                // walls and blocks as rectangles, funnels and ramps as thick lines with round joints and
+                // triangles and squares as outlines
+                renderer.shape(layer, shape, vec2(pos.x, pos.y), rot, squash, color, false);
+            }
+        }
+
+        // ----- EFFECTS -----
+        // Bonus of every sticky pad and the wait left on the pieces stuck to them, the multiplier of
+        // each piece that has picked up a bonus, then particles over the pieces
+        renderer.custom(Layer::Effects, || {
+            draw_sticky_pads(&self.board.sticky_pads);
+            draw_stuck_timers(&self.world);
+        });
+        renderer.custom(Layer::Effects, || self.multipliers.draw(&self.world));
+        renderer.custom(Layer::Effects, || self.particles.draw());
+        // Histogram over the bins
+        if self.show_stats {
+            renderer.custom(Layer::Effects, || self.stats.draw(&self.board));
+        }
+        // Slot reels across the top of the board
+        renderer.custom(Layer::Effects, || self.slots.draw());
+        // Guide line, trajectory preview, landing odds and ghost piece while aiming, and the line to a grabbed piece
+        renderer.custom(Layer::Effects, || {
+            self.trajectory.draw();
+            if !self.random_mode {
+                self.odds.draw(&self.board);
+            }
+            self.aimer.draw(&self.board, self.next_kind);
+            self.grab.draw(&self.world);
+        });
+        renderer.custom(Layer::Effects, || self.combo.draw(&self.board));
+        // The replay view sits over the board, under the rest of the HUD
+        renderer.custom(Layer::Ui, || {
+            self.replay.draw();
+            self.minimap.draw();
+        });
+
+        // ----- UI -----
+        renderer.custom(Layer::Ui, || {
+            self.prize_bar.draw();
+            self.lbl_balance.draw();
+            self.lbl_bet.draw();
+            self.lbl_last_win.draw();
+            self.lbl_board.draw();
+            self.lbl_seed.draw();
+            if self.round_left.is_some() {
+                self.lbl_timer.draw();
+            }
+        });
+        // Announcer banners over the board (and under the hot-seat turn banner)
+        renderer.custom(Layer::Ui, || self.announcer.draw(self.board.ground.x));
+        // An arcade round out of credits, with nothing left in play, asks for a coin
+        if self.arcade() && !self.wallet.can_afford() && self.stakes.is_empty() && self.spawn_queue.is_empty() {
+            renderer.custom(Layer::Ui, || {
+                if get_time() as f32 % INSERT_COIN_BLINK < INSERT_COIN_BLINK / 2.0 {
+                    let font_size = scaled_font_size(60);
+                    let width = measure_text("INSERT COIN", None, font_size, 1.0).width;
+                    draw_text("INSERT COIN", self.board.ground.x - width / 2.0, 330.0, font_size as f32, GOLD);
+                }
+            });
+        }
+        if let Some(players) = self.players.as_ref() {
+            renderer.custom(Layer::Ui, || {
+                self.lbl_turn.draw();
+                players.draw_scoreboard(self.scoreboard_pos.x, self.scoreboard_pos.y);
+                if self.turn_banner > 0.0 {
+                    let player = players.current_player();
+                    let text = format!("{}'s turn", player.name);
+                    let font_size = scaled_font_size(60);
+                    let size = measure_text(&text, None, font_size, 1.0);
+                    let alpha = self.turn_banner.min(1.0);
+                    draw_text(&text, 425.0 - size.width / 2.0, 330.0, font_size as f32, Color { a: alpha, ..player.color });
+                }
+            });
+        }
+        renderer.custom(Layer::Ui, || {
+            self.slot_machine.draw();
+            if self.show_objectives {
+                self.objectives.draw();
+            }
+            // Dropdowns after the board so their open lists sit on top of it
+            self.dd_shape.draw();
+            self.dd_ball.draw();
+            self.txt_board_name.draw();
+            self.perf.draw(&self.world);
+            self.console.draw();
+        });
+        // (timed for the performance overlay)
+        let draw_start = get_time();
+        let draw_calls = renderer.flush();
+        self.perf.record_draw((get_time() - draw_start) as f32, draw_calls);
+        offset_camera(Vec2::ZERO);
+
+        // The inspector's outline and panel go over the board and HUD, under the other panels
+        self.inspector.update(&mut self.world);
+        // The settings panel is drawn last so it sits on top of everything
+        if self.settings_panel.update(&mut self.settings) {
+            self.settings.apply(&mut self.world, &mut self.audio);
+            self.apply_theme();
+            self.odds.reset();
+        }
+        self.history_panel.update(&self.history);
+        // ... apart from the confirmation dialog, which is above even the panels
+        if let Some(confirmed) = self.dialog.update() {
+            match self.confirming.take() {
+                Some(Confirm::ClearBoard) if confirmed => self.set_board(self.design.clone()),
+                Some(Confirm::OverwriteBoard) if confirmed => self.save_board(),
+                _ => {}
+            }
+        }
+    }
+}
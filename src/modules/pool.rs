@@ -0,0 +1,58 @@
+/*
+Program Details: Drawing the board's water pool
+
+A pool fills the bottom of the board from the ground up to its surface line (see
+PoolConfig in board.rs; PhysicsWorld lifts, slows and carries along the pieces in
+it every step). It is drawn as see-through water over the pieces sinking through
+it, with a surface that ripples: two waves of different lengths running in
+opposite directions, so it never looks like the same wave sliding along.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod pool;
+
+Then with the other use commands add:
+use crate::modules::pool::draw_pool;
+
+Then in the loop you would use (after drawing the pieces so the water tints them):
+    draw_pool(&board, get_time() as f32);
+*/
+use macroquad::prelude::*;
+use crate::modules::board::BoardConfig;
+
+// Colour of the water, and of the line along its surface
+const WATER_COLOR: Color = Color::new(0.2, 0.5, 0.95, 0.3);
+const SURFACE_COLOR: Color = Color::new(0.6, 0.85, 1.0, 0.8);
+const SURFACE_THICKNESS: f32 = 2.0;
+// Width of the strips the rippling surface is drawn in
+const STRIP_WIDTH: f32 = 8.0;
+// Height (pixels), length (pixels) and speed (pixels per second) of the two surface waves
+const WAVES: [(f32, f32, f32); 2] = [(2.5, 90.0, 30.0), (1.5, 37.0, -45.0)];
+
+/// Draws the board's pool, if it has one. `time` (seconds) moves the ripples.
+pub fn draw_pool(board: &BoardConfig, time: f32) {
+    let Some(pool) = board.pool else {
+        return;
+    };
+    let left = board.ground.x - board.ground.half_width;
+    let right = board.ground.x + board.ground.half_width;
+    let bottom = board.ground.y - board.ground.half_height;
+    let ripple = |x: f32| {
+        WAVES
+            .iter()
+            .map(|(height, length, speed)| height * ((x - speed * time) / length * std::f32::consts::TAU).sin())
+            .sum::<f32>()
+    };
+
+    // The water in strips, each reaching up to the rippling surface
+    let mut x = left;
+    while x < right {
+        let next = (x + STRIP_WIDTH).min(right);
+        let (top, next_top) = (pool.surface + ripple(x), pool.surface + ripple(next));
+        draw_triangle(vec2(x, top), vec2(next, next_top), vec2(next, bottom), WATER_COLOR);
+        draw_triangle(vec2(x, top), vec2(next, bottom), vec2(x, bottom), WATER_COLOR);
+        draw_line(x, top, next, next_top, SURFACE_THICKNESS, SURFACE_COLOR);
+        x = next;
+    }
+}
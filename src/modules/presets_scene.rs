@@ -0,0 +1,307 @@
+/*
+Program Details: Board presets gallery scene
+
+Shows every board that ships with the game as a thumbnail with its name, plus a
+"Random Maps" tile that goes back to the Random button picking between the
+circle, square and triangle maps on every drop. Clicking a tile hands the
+choice back through the shared BoardChoice and returns to the board, which
+rebuilds the world from the chosen BoardConfig.
+
+The "Random Board" button makes a brand new layout with the BoardGenerator, in
+the frame (walls, bins and payouts) of the first preset, and plays it the same
+way; the "Symmetric" button beside it picks whether its halves mirror each other.
+
+The "Casino Pyramid" button plays a casino-style pyramid of pegs, also in the
+frame of the first preset, with as many rows as the "Rows" button beside it
+shows (8, 12 or 16; each click picks the next). The row count decides the pegs,
+the number of bins and the payout table (see BoardConfig::pyramid).
+
+The thumbnails are drawn once, into Viewports of their own (see viewport.rs), the first time the
+gallery is drawn: each board is built into a PhysicsWorld of its own and its
+colliders are drawn the way the Plinko scene draws them. They have to be drawn
+inside a frame (clear_background() throws away anything drawn before it).
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod presets_scene;
+
+Then with the other use commands add:
+use crate::modules::presets_scene::{PresetsScene, SharedBoardChoice};
+
+Then above the loop section to use you would go:
+    let board_choice = SharedBoardChoice::default();
+    scenes.add(SceneId::Presets, Box::new(PresetsScene::new(board_choice.clone()).await));
+*/
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::modules::board::{BoardConfig, PRESET_BOARDS, PYRAMID_ROWS};
+use crate::modules::board_generator::{BoardGenerator, GeneratorParams, PlayableSearch};
+use crate::modules::entity_registry::EntityKind;
+use crate::modules::force_field::draw_force_fields;
+use crate::modules::pool::draw_pool;
+use crate::modules::chain::draw_chains;
+use crate::modules::sticky_pad::{draw_sticky_pads, STICKY_PAD_COLOR};
+use crate::modules::label::Label;
+use crate::modules::multiplier::{DropMultipliers, GOLDEN_PEG_COLOR};
+use crate::modules::particles::BREAKABLE_PEG_COLOR;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::renderer::{Layer, Renderer};
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::text_button::TextButton;
+use crate::modules::viewport::Viewport;
+
+// Grid of tiles: columns, tile size on screen and the gaps between tiles
+const COLUMNS: usize = 5;
+const TILE_WIDTH: f32 = 180.0;
+const TILE_HEIGHT: f32 = 170.0;
+const GAP_X: f32 = 20.0;
+const GAP_Y: f32 = 60.0;
+const GRID_LEFT: f32 = 22.0;
+const GRID_TOP: f32 = 110.0;
+// Thumbnails are rendered at twice their size on screen so they stay sharp when the window is scaled up
+const THUMBNAIL_SCALE: f32 = 2.0;
+/// Part of the board (virtual screen coordinates) a thumbnail, or the minimap, shows: walls, pegs and bins.
+pub const BOARD_AREA: Rect = Rect { x: 55.0, y: 40.0, w: 740.0, h: 695.0 };
+const THUMBNAIL_BACKGROUND: Color = Color::new(0.1, 0.1, 0.15, 1.0);
+
+/// What the player picked in the gallery, waiting for the Plinko scene to apply it.
+#[derive(Clone, Debug)]
+pub enum BoardChoice {
+    /// Play on this board
+    Preset(Box<BoardConfig>),
+    /// Let the Random button pick a random map on every drop
+    RandomMaps,
+}
+
+pub type SharedBoardChoice = Rc<RefCell<Option<BoardChoice>>>;
+
+pub struct PresetsScene {
+    presets: Vec<BoardConfig>,
+    // One per preset, drawn on the first draw()
+    thumbnails: Vec<Viewport>,
+    choice: SharedBoardChoice,
+    generator: BoardGenerator,
+    // The Random Board layout being looked for, a few physics steps a frame
+    search: Option<PlayableSearch>,
+    lbl_title: Label,
+    // Why the last Random Board couldn't be made, if it couldn't
+    lbl_status: Label,
+    btn_back: TextButton,
+    btn_random: TextButton,
+    btn_symmetric: TextButton,
+    btn_pyramid: TextButton,
+    btn_rows: TextButton,
+    // Rows the Casino Pyramid button plays with, one of PYRAMID_ROWS
+    pyramid_rows: usize,
+}
+
+impl PresetsScene {
+    /// Loads every preset board. Boards that fail to load are left out of the gallery.
+    pub async fn new(choice: SharedBoardChoice) -> Self {
+        let mut presets = Vec::new();
+        for path in PRESET_BOARDS {
+            match BoardConfig::load(path).await {
+                Ok(board) => presets.push(board),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+
+        let mut lbl_title = Label::new("Choose a Board", 362.0, 70.0, 50);
+        lbl_title.with_colors(YELLOW, None);
+        let mut lbl_status = Label::new("", 182.0, 750.0, 20);
+        lbl_status.with_colors(RED, None);
+        let generator = BoardGenerator::new(GeneratorParams::default());
+        let symmetric_text = Self::symmetric_text(generator.params.symmetric);
+        let pyramid_rows = PYRAMID_ROWS[0];
+
+        Self {
+            presets,
+            thumbnails: Vec::new(),
+            choice,
+            generator,
+            search: None,
+            lbl_title,
+            lbl_status,
+            btn_back: TextButton::new(412.0, 680.0, 200.0, 50.0, "Back", DARKGRAY, GRAY, 28),
+            btn_random: TextButton::new(182.0, 680.0, 200.0, 50.0, "Random Board", DARKBLUE, BLUE, 24),
+            btn_symmetric: TextButton::new(642.0, 680.0, 200.0, 50.0, symmetric_text, DARKGRAY, GRAY, 24),
+            btn_pyramid: TextButton::new(297.0, 600.0, 200.0, 50.0, "Casino Pyramid", DARKPURPLE, PURPLE, 24),
+            btn_rows: TextButton::new(527.0, 600.0, 200.0, 50.0, Self::rows_text(pyramid_rows), DARKGRAY, GRAY, 24),
+            pyramid_rows,
+        }
+    }
+
+    fn rows_text(rows: usize) -> String {
+        format!("Rows: {}", rows)
+    }
+
+    fn symmetric_text(symmetric: bool) -> String {
+        format!("Symmetric: {}", if symmetric { "On" } else { "Off" })
+    }
+
+    // Screen rectangle of tile `index`; the presets come first, then the Random Maps tile
+    fn tile_rect(index: usize) -> Rect {
+        let (row, col) = (index / COLUMNS, index % COLUMNS);
+        Rect::new(
+            GRID_LEFT + col as f32 * (TILE_WIDTH + GAP_X),
+            GRID_TOP + row as f32 * (TILE_HEIGHT + GAP_Y),
+            TILE_WIDTH,
+            TILE_HEIGHT,
+        )
+    }
+
+    // Draws every preset into a render target of its own, then puts the screen camera back
+    fn render_thumbnails(&mut self) {
+        for board in &self.presets {
+            let thumbnail = Viewport::new((TILE_WIDTH * THUMBNAIL_SCALE) as u32, (TILE_HEIGHT * THUMBNAIL_SCALE) as u32);
+            let mut world = PhysicsWorld::new();
+            world.build_board(board);
+            thumbnail.render(BOARD_AREA, || {
+                draw_rectangle(BOARD_AREA.x, BOARD_AREA.y, BOARD_AREA.w, BOARD_AREA.h, THUMBNAIL_BACKGROUND);
+                DropMultipliers::draw_zones(&board.zones);
+                draw_force_fields(&board.force_fields, 0.0);
+                draw_board_colliders(&world, BOARD_AREA);
+                draw_sticky_pads(&board.sticky_pads);
+                draw_pool(board, 0.0);
+                draw_chains(&world);
+            });
+            self.thumbnails.push(thumbnail);
+        }
+    }
+}
+
+/// Draws the fixed colliders of a freshly built board: pegs (golden and breakable ones in their own colours),
+/// walls, ground, bin dividers, funnels, ramps, sticky pads and stacked blocks, each on the render layer of its kind.
+/// Only what reaches into `view` (the part of the board the camera shows) is drawn.
+pub fn draw_board_colliders(world: &PhysicsWorld, view: Rect) {
+    let mut renderer = Renderer::new().culled(view);
+    for (handle, collider) in world.colliders.iter() {
+        let kind = collider.parent().and_then(|body| world.bodies.get(body)).and_then(EntityKind::of);
+        // Chains are drawn by draw_chains, and pieces (on a board that has any) aren't part of the board
+        if collider.is_sensor() || kind == Some(EntityKind::Chain) || kind.is_some_and(EntityKind::is_piece) {
+            continue;
+        }
+        let pos = collider.position();
+        let shape = collider.shape();
+        let layer = kind.map_or(Layer::Pegs, Layer::of);
+        // Golden and breakable pegs in their own colours; other pegs are green, or red with corners
+        let peg_color = |plain: Color| {
+            if PhysicsWorld::is_golden(collider) {
+                GOLDEN_PEG_COLOR
+            } else if world.peg_health(handle).is_some() {
+                BREAKABLE_PEG_COLOR
+            } else {
+                plain
+            }
+        };
+        let color = match kind {
+            _ if shape.as_convex_polygon().is_some() => peg_color(RED),
+            Some(EntityKind::Peg) => peg_color(GREEN),
+            Some(EntityKind::StickyPad) => STICKY_PAD_COLOR,
+            Some(EntityKind::Block) => EntityKind::Block.default_color(),
+            _ => GREEN,
+        };
+        renderer.shape(layer, shape, vec2(pos.translation.x, pos.translation.y), pos.rotation.angle(), Vec2::ONE, color, true);
+    }
+    renderer.flush();
+}
+
+impl Scene for PresetsScene {
+    // A Random Board search still running when the gallery is left is given up on
+    fn on_exit(&mut self) {
+        if self.search.take().is_some() {
+            self.lbl_status.set_text("");
+        }
+    }
+
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        if self.btn_back.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_symmetric.click() {
+            self.generator.params.symmetric = !self.generator.params.symmetric;
+            self.btn_symmetric.set_text(Self::symmetric_text(self.generator.params.symmetric));
+        }
+        if self.btn_rows.click() {
+            let next = PYRAMID_ROWS.iter().position(|rows| *rows == self.pyramid_rows).map_or(0, |index| (index + 1) % PYRAMID_ROWS.len());
+            self.pyramid_rows = PYRAMID_ROWS[next];
+            self.btn_rows.set_text(Self::rows_text(self.pyramid_rows));
+        }
+        if self.btn_pyramid.click()
+            && let Some(frame) = self.presets.first()
+        {
+            let board = BoardConfig::pyramid(frame, self.pyramid_rows);
+            *self.choice.borrow_mut() = Some(BoardChoice::Preset(Box::new(board)));
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_random.click()
+            && let Some(frame) = self.presets.first()
+        {
+            self.search = Some(self.generator.search(frame, rand::rand() as u64));
+            self.lbl_status.set_text("Trying out a random board...");
+            self.lbl_status.with_colors(LIGHTGRAY, None);
+        }
+        if let Some(search) = self.search.as_mut()
+            && let Some(result) = search.update()
+        {
+            self.search = None;
+            match result {
+                Ok(board) => {
+                    self.lbl_status.set_text("");
+                    *self.choice.borrow_mut() = Some(BoardChoice::Preset(Box::new(board)));
+                    return SceneChange::Goto(SceneId::Plinko);
+                }
+                Err(error) => {
+                    self.lbl_status.set_text(error);
+                    self.lbl_status.with_colors(RED, None);
+                }
+            }
+        }
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            let mouse = vec2(mouse_x, mouse_y);
+            if let Some(index) = (0..=self.presets.len()).find(|index| Self::tile_rect(*index).contains(mouse)) {
+                let picked = match self.presets.get(index) {
+                    Some(board) => BoardChoice::Preset(Box::new(board.clone())),
+                    None => BoardChoice::RandomMaps,
+                };
+                *self.choice.borrow_mut() = Some(picked);
+                return SceneChange::Goto(SceneId::Plinko);
+            }
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        if self.thumbnails.is_empty() && !self.presets.is_empty() {
+            self.render_thumbnails();
+        }
+        self.lbl_title.draw();
+        self.lbl_status.draw();
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse = vec2(mouse_x, mouse_y);
+        for index in 0..=self.presets.len() {
+            let rect = Self::tile_rect(index);
+            match self.thumbnails.get(index) {
+                Some(thumbnail) => thumbnail.draw(rect),
+                None => {
+                    draw_rectangle(rect.x, rect.y, rect.w, rect.h, THUMBNAIL_BACKGROUND);
+                    let size = measure_text("?", None, 90, 1.0);
+                    draw_text("?", rect.center().x - size.width / 2.0, rect.center().y + size.height / 2.0, 90.0, YELLOW);
+                }
+            }
+            let hovered = rect.contains(mouse);
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, if hovered { 4.0 } else { 2.0 }, if hovered { YELLOW } else { GRAY });
+
+            let name = self.presets.get(index).map(|board| board.name.as_str()).unwrap_or("Random Maps");
+            let size = measure_text(name, None, 24, 1.0);
+            draw_text(name, rect.center().x - size.width / 2.0, rect.y + rect.h + 26.0, 24.0, WHITE);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+/*
+Program Details: Physics-driven prize wheel for the bonus round
+
+The wheel is a Rapier body pinned to a fixed hub by a revolute joint, with a pin
+on the rim between every pair of prize segments. A pointer hangs above the wheel
+on a second revolute joint: as the wheel turns the pins knock the pointer aside
+and gravity swings it back, so it ticks from segment to segment and slows the
+wheel down until it stops between two pins. The prize is the segment under the
+pointer, paid as a multiple of the stake that triggered the bonus.
+
+The wheel has its own PhysicsWorld (no board is built in it) so it never
+touches the pieces on the Plinko board.
+
+BonusRound is how the Plinko scene and the wheel scene talk: the Plinko scene
+fills in the stake when it starts a bonus, the wheel scene fills in the award,
+and the Plinko scene pays it into the wallet when it is entered again.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod prize_wheel;
+
+Then with the other use commands add:
+use crate::modules::prize_wheel::PrizeWheel;
+
+Then above the loop section to use you would go:
+    let mut wheel = PrizeWheel::new(512.0, 400.0, 220.0);
+
+To spin it (e.g. from a "Spin" button):
+    wheel.spin();
+
+Then in the loop you would use:
+    wheel.update(get_frame_time());
+    wheel.draw();
+    if let Some(multiplier) = wheel.result() {
+        let award = (stake as f32 * multiplier) as u32;
+    }
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::*;
+use std::cell::RefCell;
+use std::f32::consts::{PI, TAU};
+use std::rc::Rc;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::wallet::format_multiplier;
+
+// Multiple of the stake each segment pays, clockwise from the top
+pub const WHEEL_PRIZES: [f32; 8] = [0.5, 1.0, 0.5, 2.0, 0.5, 1.0, 0.5, 5.0];
+// Alternating segment colours
+const SEGMENT_COLORS: [Color; 2] = [DARKBLUE, DARKPURPLE];
+// Range of the starting spin in radians per second
+const MIN_SPIN: f32 = 5.0;
+const MAX_SPIN: f32 = 8.0;
+// Below this spin the wheel counts as stopped (once it has stayed there for STOP_TIME seconds)
+const STOPPED_SPIN: f32 = 0.05;
+const STOP_TIME: f32 = 0.5;
+const PIN_RADIUS: f32 = 7.0;
+const POINTER_LENGTH: f32 = 55.0;
+
+/// What the Plinko scene and the prize wheel scene share about the current bonus round.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BonusRound {
+    // Stake of the piece that triggered the bonus
+    pub stake: u32,
+    // Credits won on the wheel, waiting to be paid into the wallet
+    pub award: Option<u32>,
+}
+
+pub type SharedBonus = Rc<RefCell<BonusRound>>;
+
+pub struct PrizeWheel {
+    world: PhysicsWorld,
+    wheel: RigidBodyHandle,
+    pointer: RigidBodyHandle,
+    x: f32,
+    y: f32,
+    radius: f32,
+    spun: bool,
+    // How long the wheel has been (nearly) still
+    still_time: f32,
+}
+
+impl PrizeWheel {
+    /// Builds a wheel centered on (x, y) with the pointer hanging above it.
+    pub fn new(x: f32, y: f32, radius: f32) -> Self {
+        let mut world = PhysicsWorld::new();
+
+        // The hub the wheel turns around
+        let hub = world.bodies.insert(RigidBodyBuilder::fixed().translation(vector![x, y]).build());
+
+        // The wheel's weight comes from its mass properties (a solid disc) rather than a
+        // collider, so the only thing on it that collides is the ring of pins
+        let mass = 20000.0;
+        let wheel = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(vector![x, y])
+                .additional_mass_properties(MassProperties::new(point![0.0, 0.0], mass, 0.5 * mass * radius * radius))
+                .angular_damping(0.8)
+                .ccd_enabled(true)
+                .build(),
+        );
+        let pin_distance = radius - PIN_RADIUS * 2.0;
+        for segment in 0..WHEEL_PRIZES.len() {
+            let angle = Self::segment_start(segment);
+            let pin = ColliderBuilder::ball(PIN_RADIUS)
+                .translation(vector![angle.cos() * pin_distance, angle.sin() * pin_distance])
+                .density(0.0)
+                .build();
+            world.colliders.insert_with_parent(pin, wheel, &mut world.bodies);
+        }
+        let axle = RevoluteJointBuilder::new().local_anchor1(point![0.0, 0.0]).local_anchor2(point![0.0, 0.0]);
+        world.joints.insert(hub, wheel, axle, true);
+
+        // The pointer: a narrow triangle hanging from a pivot above the wheel, reaching in
+        // between the pins. Gravity swings it back to hanging straight down.
+        let pivot_y = y - radius - 25.0;
+        let pivot = world.bodies.insert(RigidBodyBuilder::fixed().translation(vector![x, pivot_y]).build());
+        let pointer = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(vector![x, pivot_y])
+                .angular_damping(2.0)
+                .ccd_enabled(true)
+                .build(),
+        );
+        let tip = [point![-8.0, 0.0], point![8.0, 0.0], point![0.0, POINTER_LENGTH]];
+        world.colliders.insert_with_parent(ColliderBuilder::convex_hull(&tip).unwrap().build(), pointer, &mut world.bodies);
+        let hinge = RevoluteJointBuilder::new()
+            .local_anchor1(point![0.0, 0.0])
+            .local_anchor2(point![0.0, 0.0])
+            .limits([-1.0, 1.0]);
+        world.joints.insert(pivot, pointer, hinge, true);
+
+        Self {
+            world,
+            wheel,
+            pointer,
+            x,
+            y,
+            radius,
+            spun: false,
+            still_time: 0.0,
+        }
+    }
+
+    /// Sets the wheel turning clockwise at a random speed. Only the first call does anything.
+    pub fn spin(&mut self) {
+        if self.spun {
+            return;
+        }
+        self.spun = true;
+        self.world.bodies[self.wheel].set_angvel(rand::gen_range(MIN_SPIN, MAX_SPIN), true);
+    }
+
+    // Whether the wheel has been spun
+    pub fn is_spun(&self) -> bool {
+        self.spun
+    }
+
+    /// Advances the wheel by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.world.advance(dt);
+        if self.spun && self.world.bodies[self.wheel].angvel().abs() < STOPPED_SPIN {
+            self.still_time += dt;
+        } else {
+            self.still_time = 0.0;
+        }
+    }
+
+    /// The prize multiplier under the pointer once the spun wheel has stopped.
+    pub fn result(&self) -> Option<f32> {
+        if !self.spun || self.still_time < STOP_TIME {
+            return None;
+        }
+        Some(WHEEL_PRIZES[self.segment_under_pointer()])
+    }
+
+    /// Draws the segments with their prizes, the pins, the hub and the pointer.
+    pub fn draw(&self) {
+        let (_, rotation) = self.world.render_pose(self.wheel, &self.world.bodies[self.wheel]);
+        let center = vec2(self.x, self.y);
+        let sweep = TAU / WHEEL_PRIZES.len() as f32;
+
+        for (segment, prize) in WHEEL_PRIZES.iter().enumerate() {
+            let start = Self::segment_start(segment) + rotation;
+            let color = SEGMENT_COLORS[segment % SEGMENT_COLORS.len()];
+            // Each segment is a fan of thin triangles so its outer edge looks round
+            let steps = 12;
+            for step in 0..steps {
+                let a = start + sweep * step as f32 / steps as f32;
+                let b = start + sweep * (step + 1) as f32 / steps as f32;
+                draw_triangle(center, center + Vec2::from_angle(a) * self.radius, center + Vec2::from_angle(b) * self.radius, color);
+            }
+            draw_line(center.x, center.y, center.x + start.cos() * self.radius, center.y + start.sin() * self.radius, 2.0, WHITE);
+
+            let text = format_multiplier(*prize);
+            let middle = center + Vec2::from_angle(start + sweep / 2.0) * self.radius * 0.65;
+            let size = measure_text(&text, None, 30, 1.0);
+            draw_text(&text, middle.x - size.width / 2.0, middle.y + size.height / 2.0, 30.0, if *prize >= 2.0 { YELLOW } else { WHITE });
+        }
+        draw_circle_lines(center.x, center.y, self.radius, 4.0, GOLD);
+
+        // Pins and hub
+        for collider in self.world.bodies[self.wheel].colliders() {
+            let pin = self.world.colliders[*collider].position().translation;
+            draw_circle(pin.x, pin.y, PIN_RADIUS, LIGHTGRAY);
+        }
+        draw_circle(center.x, center.y, 20.0, GOLD);
+
+        // Pointer
+        let (pos, angle) = self.world.render_pose(self.pointer, &self.world.bodies[self.pointer]);
+        let corners: Vec<Vec2> = [vec2(-8.0, 0.0), vec2(8.0, 0.0), vec2(0.0, POINTER_LENGTH)]
+            .iter()
+            .map(|corner| vec2(pos.x, pos.y) + Vec2::from_angle(angle).rotate(*corner))
+            .collect();
+        draw_triangle(corners[0], corners[1], corners[2], RED);
+        draw_circle(pos.x, pos.y, 6.0, WHITE);
+    }
+
+    // Angle (radians, clockwise from the +x axis as the screen's y points down) where a
+    // segment starts on the unrotated wheel; segment 0 starts at the top
+    fn segment_start(segment: usize) -> f32 {
+        -PI / 2.0 + TAU * segment as f32 / WHEEL_PRIZES.len() as f32
+    }
+
+    // Segment whose slice of the wheel is currently at the top, under the pointer
+    fn segment_under_pointer(&self) -> usize {
+        let rotation = self.world.bodies[self.wheel].rotation().angle();
+        // The top of the screen seen from the wheel's own (unrotated) frame
+        let local = (-PI / 2.0 - rotation - Self::segment_start(0)).rem_euclid(TAU);
+        ((local / (TAU / WHEEL_PRIZES.len() as f32)) as usize).min(WHEEL_PRIZES.len() - 1)
+    }
+}
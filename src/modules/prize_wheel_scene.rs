@@ -0,0 +1,99 @@
+/*
+Program Details: Prize wheel bonus round scene
+
+Entered from the Plinko scene when a piece lands in the center bin. A fresh
+wheel is built each time; Spin (or Space) sets it turning, and once it stops the
+prize under the pointer is shown as credits (the prize multiple × the stake of
+the piece that triggered the bonus). Collect hands the award back through the
+shared BonusRound and returns to the board, which pays it into the wallet.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod prize_wheel_scene;
+
+Then with the other use commands add:
+use crate::modules::prize_wheel_scene::PrizeWheelScene;
+
+Then above the loop section to use you would go:
+    let bonus = SharedBonus::default();
+    scenes.add(SceneId::PrizeWheel, Box::new(PrizeWheelScene::new(bonus.clone())));
+*/
+use macroquad::prelude::*;
+use crate::modules::label::Label;
+use crate::modules::prize_wheel::{PrizeWheel, SharedBonus};
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::text_button::TextButton;
+
+// Where the wheel sits on screen and how big it is
+const WHEEL_X: f32 = 512.0;
+const WHEEL_Y: f32 = 400.0;
+const WHEEL_RADIUS: f32 = 220.0;
+
+pub struct PrizeWheelScene {
+    bonus: SharedBonus,
+    wheel: PrizeWheel,
+    // Credits won, once the wheel has stopped
+    award: Option<u32>,
+    lbl_title: Label,
+    lbl_result: Label,
+    btn_spin: TextButton,
+    btn_collect: TextButton,
+}
+
+impl PrizeWheelScene {
+    pub fn new(bonus: SharedBonus) -> Self {
+        let mut lbl_title = Label::new("BONUS WHEEL!", 372.0, 60.0, 50);
+        lbl_title.with_colors(GOLD, None);
+        let mut lbl_result = Label::new("", 820.0, 380.0, 30);
+        lbl_result.with_colors(YELLOW, None);
+
+        Self {
+            bonus,
+            wheel: PrizeWheel::new(WHEEL_X, WHEEL_Y, WHEEL_RADIUS),
+            award: None,
+            lbl_title,
+            lbl_result,
+            btn_spin: TextButton::new(820.0, 300.0, 180.0, 60.0, "Spin!", DARKGREEN, GREEN, 30),
+            btn_collect: TextButton::new(820.0, 420.0, 180.0, 60.0, "Collect", DARKBLUE, BLUE, 30),
+        }
+    }
+}
+
+impl Scene for PrizeWheelScene {
+    fn on_enter(&mut self) {
+        self.wheel = PrizeWheel::new(WHEEL_X, WHEEL_Y, WHEEL_RADIUS);
+        self.award = None;
+        self.lbl_result.set_text(format!("Stake: {}", self.bonus.borrow().stake));
+    }
+
+    fn update(&mut self, dt: f32) -> SceneChange {
+        self.btn_spin.enabled = !self.wheel.is_spun();
+        if self.btn_spin.click() || is_key_pressed(KeyCode::Space) {
+            self.wheel.spin();
+        }
+
+        self.wheel.update(dt);
+        if self.award.is_none()
+            && let Some(multiplier) = self.wheel.result()
+        {
+            let award = (self.bonus.borrow().stake as f32 * multiplier).floor() as u32;
+            self.award = Some(award);
+            self.lbl_result.set_text(format!("You won {}!", award));
+        }
+
+        // Collect only shows up once there's something to collect
+        self.btn_collect.visible = self.award.is_some();
+        if self.btn_collect.click() {
+            self.bonus.borrow_mut().award = self.award;
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.wheel.draw();
+        self.lbl_title.draw();
+        self.lbl_result.draw();
+    }
+}
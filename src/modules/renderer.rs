@@ -0,0 +1,321 @@
+/*
+Program Details: Render layers and draw ordering
+
+A Renderer collects a frame's draw calls, each on a Layer, and draws them all at
+once in layer order: background, pegs, pieces, walls, effects and UI. Calls on
+the same layer are drawn in the order they were made. What ends up on top is
+then down to the layer a call is made on rather than to the order the bodies were
+created in or the order the calls happen to be made.
+
+Circles, rectangles and lines are kept as plain shapes; anything else (text,
+sprites, a whole widget) can be queued as a closure that draws it. shape()
+queues a collider's shape the way the board draws it, made of those calls; its
+debug flag gives the flat look of the board thumbnails and the replay view.
+
+With hundreds of pegs on a board, drawing every shape with its own draw_circle()
+or draw_line() costs more than the shapes themselves. So by default flush()
+gathers the shapes into shared meshes instead: every run of shapes between two
+closures on a layer becomes one mesh, drawn with a single draw_mesh() (split only
+when it outgrows macroquad's batch size). batched(false) draws every shape on
+its own as before, so the two can be compared; flush() returns how many draw
+calls it made.
+
+A renderer can also be told what part of the world its camera shows (culled):
+shapes entirely outside that view are dropped as they are queued, and so are
+closures queued with custom_in() whose bounds are outside it. Anything queued
+with custom() is always drawn, since there is no telling where it draws.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod renderer;
+
+Then with the other use commands add:
+use crate::modules::renderer::{Layer, Renderer};
+
+Then in the loop you would use:
+    let mut renderer = Renderer::new();
+    renderer.circle(Layer::Pegs, 400.0, 300.0, 5.0, GREEN);
+    renderer.rectangle(Layer::Walls, 10.0, 0.0, 20.0, 700.0, GREEN);
+    renderer.custom(Layer::Ui, || label.draw());
+    renderer.custom_in(Layer::Pieces, Rect::new(90.0, 90.0, 20.0, 20.0), || draw_sprite());
+    renderer.shape(Layer::Pegs, collider.shape(), vec2(400.0, 300.0), 0.0, Vec2::ONE, GREEN, false);
+    let draw_calls = renderer.flush();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::Shape;
+use crate::modules::entity_registry::EntityKind;
+
+// How thick funnels and ramps are drawn (their colliders are infinitely thin lines) and how thick the
+// outlines of polygon shapes are, normally and in the debug look
+const SEGMENT_WIDTH: f32 = 4.0;
+const OUTLINE_WIDTH: f32 = 2.0;
+const DEBUG_SEGMENT_WIDTH: f32 = 6.0;
+const DEBUG_OUTLINE_WIDTH: f32 = 3.0;
+
+/// Where a draw call sits in the frame, bottom (Background) to top (Ui).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Layer {
+    Background,
+    Pegs,
+    Pieces,
+    Walls,
+    Effects,
+    Ui,
+}
+
+impl Layer {
+    /// The layer a body of `kind` is drawn on: the walls, bin dividers, funnels and ground over the
+    /// pieces so nothing seems to slip through them.
+    pub fn of(kind: EntityKind) -> Layer {
+        match kind {
+            EntityKind::Piece(_) => Layer::Pieces,
+            EntityKind::Peg | EntityKind::StickyPad | EntityKind::Chain | EntityKind::Block => Layer::Pegs,
+            EntityKind::Zone => Layer::Background,
+            EntityKind::Wall | EntityKind::Bin | EntityKind::Segment | EntityKind::Ground => Layer::Walls,
+        }
+    }
+}
+
+// Vertices and indices macroquad takes in one draw call (its default batch capacity)
+const MAX_BATCH_VERTICES: usize = 10000;
+const MAX_BATCH_INDICES: usize = 5000;
+// Sides of a batched circle (as many as draw_circle uses)
+const CIRCLE_SIDES: usize = 20;
+
+// One queued draw call
+enum DrawCall<'a> {
+    Circle { x: f32, y: f32, radius: f32, color: Color },
+    Rectangle { x: f32, y: f32, w: f32, h: f32, color: Color },
+    Line { x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color },
+    Custom(Box<dyn FnOnce() + 'a>),
+}
+
+/// A frame's draw calls, drawn in layer order by flush().
+pub struct Renderer<'a> {
+    calls: Vec<(Layer, DrawCall<'a>)>,
+    // Whether flush() gathers the shapes into shared meshes
+    batched: bool,
+    // The part of the world the camera shows, if shapes outside it are to be dropped
+    view: Option<Rect>,
+}
+
+impl Default for Renderer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new() -> Self {
+        Self { calls: Vec::new(), batched: true, view: None }
+    }
+
+    /// Whether flush() gathers the shapes into shared meshes (the default) or draws each one on its own.
+    pub fn batched(mut self, batched: bool) -> Self {
+        self.batched = batched;
+        self
+    }
+
+    /// Drops every shape queued from now on that lies entirely outside `view` (world coordinates: what the
+    /// camera the renderer draws through shows).
+    pub fn culled(mut self, view: Rect) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// True if something drawn within `bounds` would be seen (always, for a renderer that doesn't cull).
+    pub fn is_visible(&self, bounds: Rect) -> bool {
+        self.view.is_none_or(|view| view.overlaps(&bounds))
+    }
+
+    /// Queues a filled circle centered on (x, y).
+    pub fn circle(&mut self, layer: Layer, x: f32, y: f32, radius: f32, color: Color) {
+        if !self.is_visible(Rect::new(x - radius, y - radius, radius * 2.0, radius * 2.0)) {
+            return;
+        }
+        self.calls.push((layer, DrawCall::Circle { x, y, radius, color }));
+    }
+
+    /// Queues a filled rectangle with its top-left corner at (x, y).
+    pub fn rectangle(&mut self, layer: Layer, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        if !self.is_visible(Rect::new(x, y, w, h)) {
+            return;
+        }
+        self.calls.push((layer, DrawCall::Rectangle { x, y, w, h, color }));
+    }
+
+    /// Queues a line from (x1, y1) to (x2, y2).
+    #[allow(clippy::too_many_arguments)]
+    pub fn line(&mut self, layer: Layer, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+        let bounds = Rect::new(x1.min(x2) - thickness, y1.min(y2) - thickness, (x2 - x1).abs() + thickness * 2.0, (y2 - y1).abs() + thickness * 2.0);
+        if !self.is_visible(bounds) {
+            return;
+        }
+        self.calls.push((layer, DrawCall::Line { x1, y1, x2, y2, thickness, color }));
+    }
+
+    /// Queues anything else, drawn by calling `draw` when its layer comes up.
+    pub fn custom(&mut self, layer: Layer, draw: impl FnOnce() + 'a) {
+        self.calls.push((layer, DrawCall::Custom(Box::new(draw))));
+    }
+
+    /// Queues anything else that draws within `bounds`, dropped if those are outside the view.
+    pub fn custom_in(&mut self, layer: Layer, bounds: Rect, draw: impl FnOnce() + 'a) {
+        if self.is_visible(bounds) {
+            self.custom(layer, draw);
+        }
+    }
+
+    /// Queues `shape` (a collider's) centred on `pos` and turned by `rot` radians: circles and boxes filled,
+    /// polylines as thick lines with round joints, convex polygons as outlines. `squash` stretches circles
+    /// and polygons (Vec2::ONE for none). With `debug` lines and outlines are thicker and polylines go without
+    /// their joints.
+    #[allow(clippy::too_many_arguments)]
+    pub fn shape(&mut self, layer: Layer, shape: &dyn Shape, pos: Vec2, rot: f32, squash: Vec2, color: Color, debug: bool) {
+        let (segment_width, outline_width) = if debug { (DEBUG_SEGMENT_WIDTH, DEBUG_OUTLINE_WIDTH) } else { (SEGMENT_WIDTH, OUTLINE_WIDTH) };
+        if let Some(ball) = shape.as_ball() {
+            if squash == Vec2::ONE {
+                self.circle(layer, pos.x, pos.y, ball.radius, color);
+            } else {
+                let (w, h) = (ball.radius * squash.x, ball.radius * squash.y);
+                self.custom_in(layer, Rect::new(pos.x - w, pos.y - h, w * 2.0, h * 2.0), move || draw_ellipse(pos.x, pos.y, w, h, 0.0, color));
+            }
+        }
+        if let Some(cuboid) = shape.as_cuboid() {
+            let (hx, hy) = (cuboid.half_extents.x, cuboid.half_extents.y);
+            // Only knocked-over blocks turn, so most boxes are plain rectangles
+            if rot == 0.0 {
+                self.rectangle(layer, pos.x - hx, pos.y - hy, hx * 2.0, hy * 2.0, color);
+            } else {
+                let reach = hx.hypot(hy);
+                let params = DrawRectangleParams { offset: vec2(0.5, 0.5), rotation: rot, color };
+                self.custom_in(layer, Rect::new(pos.x - reach, pos.y - reach, reach * 2.0, reach * 2.0), move || {
+                    draw_rectangle_ex(pos.x, pos.y, hx * 2.0, hy * 2.0, params)
+                });
+            }
+        }
+        if let Some(polyline) = shape.as_polyline() {
+            for segment in polyline.segments() {
+                let (a, b) = (pos + vec2(segment.a.x, segment.a.y), pos + vec2(segment.b.x, segment.b.y));
+                self.line(layer, a.x, a.y, b.x, b.y, segment_width, color);
+                if !debug {
+                    self.circle(layer, b.x, b.y, segment_width / 2.0, color);
+                }
+            }
+        }
+        if let Some(convex) = shape.as_convex_polygon() {
+            let (sin, cos) = rot.sin_cos();
+            let corners: Vec<Vec2> = convex
+                .points()
+                .iter()
+                .map(|point| pos + vec2(point.x * cos - point.y * sin, point.x * sin + point.y * cos) * squash)
+                .collect();
+            for (index, corner) in corners.iter().enumerate() {
+                let next = corners[(index + 1) % corners.len()];
+                self.line(layer, corner.x, corner.y, next.x, next.y, outline_width, color);
+            }
+        }
+    }
+
+    /// Draws every queued call, layer by layer (in the order they were queued within a layer), and returns
+    /// how many draw calls that took (a closure counts as one).
+    pub fn flush(mut self) -> usize {
+        // A stable sort keeps each layer's calls in the order they were made
+        self.calls.sort_by_key(|(layer, _)| *layer);
+        if !self.batched {
+            let draw_calls = self.calls.len();
+            for (_, call) in self.calls {
+                match call {
+                    DrawCall::Circle { x, y, radius, color } => draw_circle(x, y, radius, color),
+                    DrawCall::Rectangle { x, y, w, h, color } => draw_rectangle(x, y, w, h, color),
+                    DrawCall::Line { x1, y1, x2, y2, thickness, color } => draw_line(x1, y1, x2, y2, thickness, color),
+                    DrawCall::Custom(draw) => draw(),
+                }
+            }
+            return draw_calls;
+        }
+
+        let mut batch = Batch::default();
+        let mut draw_calls = 0;
+        let mut current = None;
+        for (layer, call) in self.calls {
+            // A layer's shapes aren't drawn in the same mesh as the layer below's
+            if current != Some(layer) {
+                draw_calls += batch.draw();
+                current = Some(layer);
+            }
+            match call {
+                DrawCall::Circle { x, y, radius, color } => draw_calls += batch.circle(x, y, radius, color),
+                DrawCall::Rectangle { x, y, w, h, color } => {
+                    draw_calls += batch.quad([vec2(x, y), vec2(x + w, y), vec2(x + w, y + h), vec2(x, y + h)], color);
+                }
+                DrawCall::Line { x1, y1, x2, y2, thickness, color } => {
+                    // The line's two sides, half its thickness either side of it
+                    let along = vec2(x2 - x1, y2 - y1).normalize_or_zero();
+                    let side = vec2(-along.y, along.x) * thickness / 2.0;
+                    let (start, end) = (vec2(x1, y1), vec2(x2, y2));
+                    draw_calls += batch.quad([start + side, end + side, end - side, start - side], color);
+                }
+                DrawCall::Custom(draw) => {
+                    // Whatever the closure draws has to go over the shapes queued before it
+                    draw_calls += batch.draw() + 1;
+                    draw();
+                }
+            }
+        }
+        draw_calls + batch.draw()
+    }
+}
+
+// Shapes from consecutive draw calls gathered into one mesh
+#[derive(Default)]
+struct Batch {
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
+}
+
+impl Batch {
+    // Makes room for `vertices` and `indices` more, drawing what has been gathered first if they wouldn't fit,
+    // and returns the draw calls that took (0 or 1)
+    fn reserve(&mut self, vertices: usize, indices: usize) -> usize {
+        if self.vertices.len() + vertices > MAX_BATCH_VERTICES || self.indices.len() + indices > MAX_BATCH_INDICES {
+            self.draw()
+        } else {
+            0
+        }
+    }
+
+    // Adds a filled circle as a fan of triangles around its centre
+    fn circle(&mut self, x: f32, y: f32, radius: f32, color: Color) -> usize {
+        let draw_calls = self.reserve(CIRCLE_SIDES + 1, CIRCLE_SIDES * 3);
+        let center = self.vertices.len() as u16;
+        self.vertices.push(Vertex::new(x, y, 0.0, 0.0, 0.0, color));
+        for side in 0..CIRCLE_SIDES {
+            let angle = side as f32 / CIRCLE_SIDES as f32 * std::f32::consts::TAU;
+            self.vertices.push(Vertex::new(x + radius * angle.cos(), y + radius * angle.sin(), 0.0, 0.0, 0.0, color));
+            let (this, next) = (side as u16 + 1, ((side + 1) % CIRCLE_SIDES) as u16 + 1);
+            self.indices.extend_from_slice(&[center, center + this, center + next]);
+        }
+        draw_calls
+    }
+
+    // Adds a filled four-cornered shape, corners in order around it
+    fn quad(&mut self, corners: [Vec2; 4], color: Color) -> usize {
+        let draw_calls = self.reserve(4, 6);
+        let first = self.vertices.len() as u16;
+        self.vertices.extend(corners.iter().map(|corner| Vertex::new(corner.x, corner.y, 0.0, 0.0, 0.0, color)));
+        self.indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        draw_calls
+    }
+
+    // Draws what has been gathered as one mesh and starts over, returning the draw calls that took (0 or 1)
+    fn draw(&mut self) -> usize {
+        if self.indices.is_empty() {
+            return 0;
+        }
+        let mesh = Mesh { vertices: std::mem::take(&mut self.vertices), indices: std::mem::take(&mut self.indices), texture: None };
+        draw_mesh(&mesh);
+        1
+    }
+}
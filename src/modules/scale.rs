@@ -28,6 +28,11 @@ Usage examples:
         // Draw your game objects...
     }
 
+2. Shaking the screen: draw with the view moved, then put it back:
+    offset_camera(vec2(3.0, -2.0));
+    // Draw the shaking part...
+    offset_camera(Vec2::ZERO);
+
 Benefits:
 - Your game will maintain the correct aspect ratio on any screen size
 - All game coordinates stay consistent regardless of the physical screen resolution
@@ -47,7 +52,7 @@ thread_local! {
     });
     
     // We'll store the current virtual resolution here - made pub so other modules can access it
-    pub static VIRTUAL_RESOLUTION: RefCell<(f32, f32)> = RefCell::new((1024.0, 768.0));
+    pub static VIRTUAL_RESOLUTION: RefCell<(f32, f32)> = const { RefCell::new((1024.0, 768.0)) };
 }
 
 /// Sets the camera to the virtual resolution and adjusts the scale
@@ -86,11 +91,29 @@ pub fn use_virtual_resolution(virtual_width: f32, virtual_height: f32) {
     });
 }
 
+/// Draws everything after this call moved by `offset` virtual pixels (e.g. for a screen shake);
+/// `Vec2::ZERO` puts the view back where use_virtual_resolution set it
+pub fn offset_camera(offset: Vec2) {
+    CAMERA.with(|camera| {
+        let mut camera = camera.borrow().clone();
+        camera.target -= offset;
+        set_camera(&camera);
+    });
+}
+
 
 
 
 
 
+/// How many screen pixels one virtual pixel takes up at the current window size
+pub fn screen_scale() -> f32 {
+    VIRTUAL_RESOLUTION.with(|res| {
+        let (virtual_width, virtual_height) = *res.borrow();
+        (screen_width() / virtual_width).min(screen_height() / virtual_height)
+    })
+}
+
 /// Function to get the mouse position in world coordinates based on the current camera state
 pub fn mouse_position_world() -> (f32, f32) {
     let (mouse_x, mouse_y) = ::macroquad::input::mouse_position();  // Get the raw mouse position
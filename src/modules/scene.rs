@@ -0,0 +1,162 @@
+/*
+Program Details: Scene manager for switching between the game's screens
+
+Each screen of the game (title menu, the Plinko board, the bonus prize wheel,
+the board presets gallery, the board editor, game over, high scores, the
+hot-seat winner screen, the physics benchmark, the attract mode demo)
+is a Scene.
+The SceneManager owns one instance of every scene and runs the current one each
+frame: update() handles input and advances the game, then draw() renders it.
+A scene asks to change screens by returning a SceneChange from update(); the
+manager then calls on_exit() on the old scene and on_enter() on the new one.
+Scenes are kept alive while hidden, so going back to one picks up where it was.
+
+Widgets in this crate are immediate mode (buttons draw themselves when asked if
+they were clicked), so a widget that has to sit on top of the scene, like a
+popup panel, is handled in draw() rather than update().
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod scene;
+
+Then with the other use commands add:
+use crate::modules::scene::{Scene, SceneChange, SceneId, SceneManager};
+
+Then above the loop section to use you would go:
+    let mut scenes = SceneManager::new(SceneId::Menu);
+    scenes.add(SceneId::Menu, Box::new(MenuScene::new()));
+    scenes.add(SceneId::Plinko, Box::new(PlinkoScene::new(bonus.clone()).await));
+
+Then in the loop you would use:
+    if !scenes.update(get_frame_time()) {
+        break; // a scene asked to quit
+    }
+    scenes.draw();
+
+A scene implements:
+    impl Scene for MyScene {
+        fn update(&mut self, dt: f32) -> SceneChange {
+            if self.btn_back.click() {
+                return SceneChange::Goto(SceneId::Menu);
+            }
+            SceneChange::None
+        }
+        fn draw(&mut self) { ... }
+    }
+*/
+use std::collections::HashMap;
+
+/// The screens of the game.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SceneId {
+    Menu,
+    Plinko,
+    GameOver,
+    PrizeWheel,
+    Presets,
+    Editor,
+    HighScores,
+    Winner,
+    ShapeStats,
+    Benchmark,
+    Attract,
+}
+
+/// What a scene wants to happen after its update.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SceneChange {
+    /// Stay on the current scene
+    None,
+    /// Switch to another scene
+    Goto(SceneId),
+    /// Close the game (never used on the web, where there's nothing to quit to)
+    #[cfg_attr(target_arch = "wasm32", allow(unused))]
+    Quit,
+}
+
+pub trait Scene {
+    /// Handles input and advances the scene by `dt` seconds of real time.
+    fn update(&mut self, dt: f32) -> SceneChange;
+
+    /// Renders the scene. Called every frame after update().
+    fn draw(&mut self);
+
+    /// Called when the scene becomes the current one.
+    fn on_enter(&mut self) {}
+
+    /// Called when another scene takes over.
+    fn on_exit(&mut self) {}
+}
+
+pub struct SceneManager {
+    scenes: HashMap<SceneId, Box<dyn Scene>>,
+    current: SceneId,
+    // True until the first scene has had on_enter() called
+    entering: bool,
+}
+
+impl SceneManager {
+    /// Creates an empty manager that will start on `start` once that scene has been added.
+    pub fn new(start: SceneId) -> Self {
+        Self {
+            scenes: HashMap::new(),
+            current: start,
+            entering: true,
+        }
+    }
+
+    /// Adds (or replaces) the scene shown for `id`.
+    pub fn add(&mut self, id: SceneId, scene: Box<dyn Scene>) -> &mut Self {
+        self.scenes.insert(id, scene);
+        self
+    }
+
+    // Getter for the scene currently running
+    #[allow(unused)]
+    pub fn current(&self) -> SceneId {
+        self.current
+    }
+
+    /// Switches to another scene, calling on_exit() on the old one and on_enter() on the new one.
+    pub fn switch_to(&mut self, id: SceneId) {
+        if let Some(scene) = self.scenes.get_mut(&self.current) {
+            scene.on_exit();
+        }
+        self.current = id;
+        if let Some(scene) = self.scenes.get_mut(&id) {
+            scene.on_enter();
+        }
+    }
+
+    /// Updates the current scene and applies the change it asks for.
+    /// Returns false once a scene has asked to quit.
+    pub fn update(&mut self, dt: f32) -> bool {
+        if self.entering {
+            self.entering = false;
+            if let Some(scene) = self.scenes.get_mut(&self.current) {
+                scene.on_enter();
+            }
+        }
+
+        let change = match self.scenes.get_mut(&self.current) {
+            Some(scene) => scene.update(dt),
+            None => SceneChange::None,
+        };
+        match change {
+            SceneChange::None => true,
+            SceneChange::Goto(id) => {
+                self.switch_to(id);
+                true
+            }
+            SceneChange::Quit => false,
+        }
+    }
+
+    /// Draws the current scene.
+    pub fn draw(&mut self) {
+        if let Some(scene) = self.scenes.get_mut(&self.current) {
+            scene.draw();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+/*
+Program Details: Seeds for reproducible sessions ("daily seed")
+
+Every drop reseeds the random number generator with a seed of its own, taken
+from a fixed sequence worked out from the game's seed, so two players typing the
+same seed on the title menu (e.g. today's date) get the same drops: same random
+columns, same maps and same shapes. A game nobody typed a seed for gets a random
+one (random_game_seed), and a sequence without a base hands out random seeds.
+
+A typed seed that is a whole number is used as it is; any other text is hashed,
+so words work as seeds too.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod seed;
+
+Then with the other use commands add:
+use crate::modules::seed::{parse_seed, SeedSequence};
+
+Then above the loop section to use you would go:
+    let mut seeds = SeedSequence::new(parse_seed("20250614"));
+
+For every drop:
+    let seed = seeds.next_seed();
+    rand::srand(seed);
+*/
+use macroquad::rand;
+use std::cell::Cell;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+
+/// Seed typed on the title menu, waiting for the Plinko scene to start a game with it.
+pub type SharedSeed = Rc<Cell<Option<u64>>>;
+
+/// The seed for a typed text: whole numbers as they are, anything else hashed. None if blank.
+pub fn parse_seed(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(number) = text.parse::<u64>() {
+        return Some(number);
+    }
+    // FNV-1a, so the same text gives the same seed on every platform
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+    Some(hash)
+}
+
+/// A random seed for a game nobody typed one for, short enough to read off the screen and type in.
+pub fn random_game_seed() -> u64 {
+    rand::gen_range(0, 1_000_000_000u32) as u64
+}
+
+/// Hands out the seed for each drop: random ones, or a fixed sequence worked out from a base seed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct SeedSequence {
+    base: Option<u64>,
+    // Seeds handed out since the sequence (re)started
+    count: u64,
+}
+
+impl SeedSequence {
+    pub fn new(base: Option<u64>) -> Self {
+        Self { base, count: 0 }
+    }
+
+    // The seed the sequence was started from (None when the seeds are random)
+    pub fn base(&self) -> Option<u64> {
+        self.base
+    }
+
+    /// Goes back to the first seed of the sequence.
+    pub fn restart(&mut self) {
+        self.count = 0;
+    }
+
+    /// The seed for the next drop.
+    pub fn next_seed(&mut self) -> u64 {
+        let index = self.count;
+        self.count += 1;
+        match self.base {
+            Some(base) => mix(base.wrapping_add(index)),
+            None => rand::rand() as u64,
+        }
+    }
+}
+
+// SplitMix64 finaliser: neighbouring inputs give unrelated seeds
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
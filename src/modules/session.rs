@@ -0,0 +1,163 @@
+/*
+Program Details: Session tracking for the end-of-game summary
+
+A session is one game from a fresh wallet to its end: either the player runs out
+of credits, or, in a timed round, the clock runs out. While it lasts the Plinko
+scene records every drop and payout into a SessionSummary; when it ends the
+summary is handed to the summary scene (SceneId::GameOver) through the shared
+Session, which shows the total drops, the biggest single payout and how the
+pieces spread over the bins.
+
+A hot-seat game ends on the winner screen (SceneId::Winner) instead, which gets
+the players' standings through the Session.
+
+An arcade round never ends: out of credits it waits for another coin, and left
+alone it gives the screen to the attract mode.
+
+An inventory round has a fixed stock of pieces (see inventory.rs) and ends once
+every one of them has been dropped and has landed, or when the credits run out
+first.
+
+The shared Session also carries the other way: the menu's Timed Round, Hot
+Seat, Arcade and Inventory buttons and the Play Again buttons ask for a new round of a given kind,
+which the Plinko scene starts the next time it is entered.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod session;
+
+Then with the other use commands add:
+use crate::modules::session::{RoundKind, SessionEnd, SessionSummary, SharedSession};
+
+Then above the loop section to use you would go:
+    let session = SharedSession::default();
+    let mut summary = SessionSummary::new(RoundKind::Timed);
+
+While playing:
+    summary.record_drop(stake);
+    summary.record_landing(bin, board.bins.count, won);
+
+When the session ends:
+    summary.finish(SessionEnd::TimeUp, wallet.balance(), &board);
+    session.borrow_mut().summary = Some(summary.clone());
+*/
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::modules::board::BoardConfig;
+use crate::modules::players::Standing;
+use crate::modules::stats::BinStats;
+use serde::{Deserialize, Serialize};
+
+// Length of a timed round in seconds of game time, counted in physics steps (pausing stops the clock)
+pub const ROUND_SECONDS: f32 = 120.0;
+
+/// The kinds of round a session can be.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RoundKind {
+    /// Play until the credits run out
+    #[default]
+    Free,
+    /// Play until the credits or ROUND_SECONDS run out
+    Timed,
+    /// 2-4 players taking turns on one board, ending on the winner screen
+    HotSeat { players: usize },
+    /// Coin-operated: starts with no credits and never ends by running out, it waits for the next coin instead
+    Arcade,
+    /// A fixed stock of pieces (STARTING_PIECES) to drop; ends once they have all been dropped and landed
+    Inventory,
+}
+
+/// Why a session ended.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SessionEnd {
+    OutOfCredits,
+    TimeUp,
+    OutOfPieces,
+}
+
+/// Everything the summary screen shows about a finished session.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionSummary {
+    pub round: RoundKind,
+    pub end: Option<SessionEnd>,
+    // Pieces dropped and credits staked on them
+    pub drops: u32,
+    pub total_bet: u32,
+    // Credits paid out by the bins and the bonus wheel, and the biggest single one of those
+    pub total_won: u32,
+    pub biggest_payout: u32,
+    // Balance left when the session ended
+    pub final_balance: u32,
+    // Where the pieces settled, and the name and bin payouts of the board played last
+    pub bins: BinStats,
+    pub board: String,
+    pub payouts: Vec<f32>,
+}
+
+impl SessionSummary {
+    pub fn new(round: RoundKind) -> Self {
+        Self {
+            round,
+            end: None,
+            drops: 0,
+            total_bet: 0,
+            total_won: 0,
+            biggest_payout: 0,
+            final_balance: 0,
+            bins: BinStats::new(),
+            board: String::new(),
+            payouts: Vec::new(),
+        }
+    }
+
+    /// Counts a piece dropped with `stake` riding on it.
+    pub fn record_drop(&mut self, stake: u32) {
+        self.drops += 1;
+        self.total_bet += stake;
+    }
+
+    /// Counts a piece settling in `bin` (of `bin_count`) and paying `won` credits.
+    pub fn record_landing(&mut self, bin: usize, bin_count: usize, won: u32) {
+        self.bins.record(bin, bin_count);
+        self.record_win(won);
+    }
+
+    /// Counts credits won without a landing (a bonus wheel award).
+    pub fn record_win(&mut self, won: u32) {
+        self.total_won += won;
+        self.biggest_payout = self.biggest_payout.max(won);
+    }
+
+    /// Marks the session as over, remembering the balance left and the board it ended on.
+    pub fn finish(&mut self, end: SessionEnd, final_balance: u32, board: &BoardConfig) {
+        self.end = Some(end);
+        self.final_balance = final_balance;
+        self.board = board.name.clone();
+        self.payouts = board.payouts.clone();
+    }
+
+    // The session's high score: every credit it won
+    pub fn score(&self) -> u32 {
+        self.total_won
+    }
+}
+
+impl Default for SessionSummary {
+    fn default() -> Self {
+        Self::new(RoundKind::Free)
+    }
+}
+
+/// Passed between the menu, the Plinko scene and the summary scene.
+#[derive(Default)]
+pub struct Session {
+    // A new round asked for, started the next time the Plinko scene is entered
+    pub request: Option<RoundKind>,
+    // The summary of the session that just ended
+    pub summary: Option<SessionSummary>,
+    // How the players finished the hot-seat game that just ended
+    pub standings: Option<Vec<Standing>>,
+}
+
+pub type SharedSession = Rc<RefCell<Session>>;
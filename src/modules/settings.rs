@@ -0,0 +1,225 @@
+/*
+Program Details: Settings module with persistent player preferences
+
+Holds the values the player can change from the settings panel (gravity, how
+bouncy and how damped the pieces are, sound and music volume, peg density, the number of
+bins and how their payouts are laid out, whether pieces leave motion trails,
+whether pieces can be grabbed and thrown with the mouse (sandbox),
+the material presets given to the parts of the board, the board theme, the size of the UI text, the physics solver's
+accuracy against speed trade-offs, how strong the screen shake, hit-pause and squash-and-stretch are, and how long the game
+is left idle before the attract mode demo starts) and saves them between sessions. Settings are stored as TOML: in a settings.toml file next
+to the game on native, and in the browser's localStorage on the web.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod settings;
+
+In the Cargo.toml file add the following:
+    toml = "0.8"
+    [target.'cfg(target_arch = "wasm32")'.dependencies]
+    quad-storage = "0.1"
+
+Then with the other use commands add:
+use crate::modules::settings::Settings;
+
+Then above the loop section to use you would go (missing or broken settings fall back to defaults):
+    let mut settings = Settings::load();
+    settings.apply(&mut world, &mut audio);
+
+After the player changes something:
+    settings.apply(&mut world, &mut audio);
+    settings.save();
+*/
+
+use crate::modules::attract_scene::ATTRACT_SECONDS;
+use crate::modules::audio::Audio;
+use crate::modules::board::PayoutLayout;
+use crate::modules::board_theme::DEFAULT_THEME;
+use crate::modules::juice::JuiceSettings;
+use crate::modules::materials::Materials;
+use crate::modules::physics::{PhysicsWorld, CCD_SUBSTEPS, FIXED_DT, PIECE_DAMPING, PIECE_RESTITUTION, VELOCITY_ITERATIONS};
+use crate::modules::theme::set_text_scale;
+use rapier2d::prelude::IntegrationParameters;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+
+// File the settings are saved to on native
+#[cfg(not(target_arch = "wasm32"))]
+pub const SETTINGS_FILE: &str = "settings.toml";
+// localStorage key the settings are saved under on the web
+#[cfg(target_arch = "wasm32")]
+pub const SETTINGS_KEY: &str = "plinko2_settings";
+// Fewest physics steps per second allowed: any slower and pieces tunnel through pegs
+const MIN_STEP_RATE: f32 = 30.0;
+
+/// Everything the player can tune from the settings panel.
+/// Fields missing from a saved file keep their default value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Downward pull on every piece
+    pub gravity: f32,
+    /// Share of energy a piece keeps after a bounce (0.0 - 1.0)
+    pub bounciness: f32,
+    /// Air resistance on the movement and spin of pieces
+    pub damping: f32,
+    /// Master sound volume (0.0 - 1.0)
+    pub volume: f32,
+    /// Volume of the background music (0.0 - 1.0), on top of the master volume
+    pub music_volume: f32,
+    /// Multiplies the rows and columns of the peg grids (1.0 is the board as designed)
+    pub peg_density: f32,
+    /// Number of bins across the bottom; 0 keeps the count each board was designed with
+    pub bins: usize,
+    /// Which bins pay the most when `bins` changes the count
+    pub payout_layout: PayoutLayout,
+    /// Whether fast pieces leave motion trails
+    pub trails: bool,
+    /// Whether the bet on a piece the physics watchdog removes is given back
+    pub refund_lost: bool,
+    /// Whether pieces can be picked up with the mouse and thrown (see grab.rs)
+    pub sandbox: bool,
+    /// Tuned material presets and the parts of the board made of them
+    pub materials: Materials,
+    /// Name of the board theme (see board_theme.rs)
+    pub theme: String,
+    /// Size of all UI text against the size it was designed at (0.75 - 2.0)
+    pub text_scale: f32,
+    /// How hard the physics solver works each step
+    pub solver: SolverSettings,
+    /// How strong the screen shake, hit-pause and squash-and-stretch are (see juice.rs)
+    pub juice: JuiceSettings,
+    /// Seconds the menu (or an arcade round) is left idle before the attract mode starts; 0 never starts it
+    pub attract_seconds: f32,
+}
+
+/// Rapier solver knobs, for balancing accuracy against speed with hundreds of pieces on the board.
+/// Fields missing from a saved file keep their default value.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SolverSettings {
+    /// Physics steps per second (each step is 1 / step_rate seconds long)
+    pub step_rate: f32,
+    /// Velocity iterations the contact solver runs every step
+    pub velocity_iterations: usize,
+    /// Most impacts continuous collision detection resolves for one piece in a step
+    pub ccd_substeps: usize,
+    /// Share of the overlap between two colliders corrected every step (0.0 - 1.0)
+    pub erp: f32,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self {
+            step_rate: 1.0 / FIXED_DT,
+            velocity_iterations: VELOCITY_ITERATIONS,
+            ccd_substeps: CCD_SUBSTEPS,
+            erp: IntegrationParameters::default().erp,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            gravity: 800.0,
+            bounciness: PIECE_RESTITUTION,
+            damping: PIECE_DAMPING,
+            volume: 0.8,
+            music_volume: 0.5,
+            peg_density: 1.0,
+            bins: 0,
+            payout_layout: PayoutLayout::EdgesHigh,
+            trails: true,
+            refund_lost: true,
+            sandbox: false,
+            materials: Materials::default(),
+            theme: DEFAULT_THEME.to_string(),
+            text_scale: 1.0,
+            solver: SolverSettings::default(),
+            juice: JuiceSettings::default(),
+            attract_seconds: ATTRACT_SECONDS,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the saved settings, or the defaults if nothing was saved or it can't be read.
+    pub fn load() -> Settings {
+        storage::read().and_then(|text| Self::from_toml(&text).ok()).unwrap_or_default()
+    }
+
+    /// Saves the settings so the next session starts with them.
+    pub fn save(&self) -> Result<(), String> {
+        storage::write(&self.to_toml()?)
+    }
+
+    /// Parses settings from TOML text.
+    pub fn from_toml(text: &str) -> Result<Settings, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    /// Writes the settings as TOML text.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /// Pushes the settings into the game.
+    /// Gravity, the volumes and the text scale change right away; bounciness and damping apply to pieces dropped
+    /// from now on, and peg density applies the next time the board is built. Materials change
+    /// everything already on the board in place, and the solver settings take effect from the next step.
+    /// The bin count isn't pushed anywhere: the caller fits it to each board it builds
+    /// (see BoardConfig::with_bin_count), and the caller draws the board with the theme.
+    pub fn apply(&self, world: &mut PhysicsWorld, audio: &mut Audio) {
+        self.apply_physics(world);
+        audio.set_master_volume(self.volume);
+        audio.set_music_volume(self.music_volume);
+        set_text_scale(self.text_scale);
+    }
+
+    /// Pushes only the settings that change how pieces move into `world`, e.g. for a demo played
+    /// back the way it was recorded.
+    pub fn apply_physics(&self, world: &mut PhysicsWorld) {
+        world.gravity.y = self.gravity;
+        world.piece_restitution = self.bounciness;
+        world.piece_damping = self.damping;
+        world.peg_density = self.peg_density;
+        world.set_materials(&self.materials);
+        let params = &mut world.integration_params;
+        params.dt = 1.0 / self.solver.step_rate.max(MIN_STEP_RATE);
+        params.num_solver_iterations = NonZeroUsize::new(self.solver.velocity_iterations).unwrap_or(NonZeroUsize::MIN);
+        params.max_ccd_substeps = self.solver.ccd_substeps.max(1);
+        params.erp = self.solver.erp.clamp(0.0, 1.0);
+    }
+}
+
+// Where the settings text lives: a file on native
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use super::SETTINGS_FILE;
+
+    pub fn read() -> Option<String> {
+        std::fs::read_to_string(SETTINGS_FILE).ok()
+    }
+
+    pub fn write(text: &str) -> Result<(), String> {
+        std::fs::write(SETTINGS_FILE, text).map_err(|e| format!("Couldn't save {}: {}", SETTINGS_FILE, e))
+    }
+}
+
+// ... and localStorage on the web
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use super::SETTINGS_KEY;
+
+    pub fn read() -> Option<String> {
+        quad_storage::STORAGE.lock().ok()?.get(SETTINGS_KEY)
+    }
+
+    pub fn write(text: &str) -> Result<(), String> {
+        let mut storage = quad_storage::STORAGE.lock().map_err(|e| e.to_string())?;
+        storage.set(SETTINGS_KEY, text);
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+/*
+Program Details: Settings panel drawn over the board
+
+A popup with one slider per setting (gravity, bounciness, damping, sound and
+music volume side by side, and peg density), buttons switching motion trails on and off, picking the number of
+bins and how their payouts are laid out, a "Materials" button opening the
+materials panel in its place (see materials_panel.rs), a "Solver" button doing
+the same for the physics solver settings (see solver_panel.rs), a button
+choosing whether bets on pieces lost to the physics watchdog are refunded, a
+Sandbox button letting pieces be grabbed and thrown with the mouse, a "Juice"
+button opening the screen shake, hit-pause and squash sliders in its place (see
+juice_panel.rs), a button cycling through the board themes given with with_themes, a button stepping
+the size of the UI text from 75% to 200%, a button choosing how long the game is
+left idle before the attract mode demo starts (or never), plus "Save & Close" and
+"Defaults" buttons. Moving a slider changes
+the Settings right away so the player can feel the difference; closing the panel
+saves them.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod settings_panel;
+
+Then with the other use commands add:
+use crate::modules::settings_panel::SettingsPanel;
+
+Then above the loop section to use you would go:
+    let mut settings_panel = SettingsPanel::new(200.0, 70.0, &settings);
+    settings_panel.with_themes(themes.iter().map(|theme| theme.name().to_string()).collect(), &settings);
+
+To show it (e.g. from a "Settings" button):
+    settings_panel.open(&settings);
+
+Then in the loop, after the board has been drawn so the panel sits on top:
+if settings_panel.update(&mut settings) {
+    settings.apply(&mut world, &mut audio);
+}
+*/
+use macroquad::prelude::*;
+use crate::modules::board::PayoutLayout;
+use crate::modules::juice_panel::JuicePanel;
+use crate::modules::label::Label;
+use crate::modules::materials_panel::MaterialsPanel;
+use crate::modules::settings::Settings;
+use crate::modules::slider::Slider;
+use crate::modules::solver_panel::SolverPanel;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::{draw_panel, MAX_TEXT_SCALE, MIN_TEXT_SCALE};
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 692.0;
+const SLIDER_WIDTH: f32 = 320.0;
+// Width of the sound and music volume sliders, which share a row
+const HALF_SLIDER_WIDTH: f32 = 150.0;
+// Vertical distance between two sliders, and between two rows of buttons
+const SLIDER_SPACING: f32 = 58.0;
+const TOGGLE_SPACING: f32 = 42.0;
+// Bin counts the bins button cycles through (after "Board", the count the board was designed with)
+const MIN_BINS: usize = 3;
+const MAX_BINS: usize = 12;
+// How much each click of the text size button grows the text
+const TEXT_SCALE_STEP: f32 = 0.25;
+// Idle seconds the attract mode button steps through; 0 switches the attract mode off
+const ATTRACT_CHOICES: [f32; 5] = [15.0, 30.0, 60.0, 120.0, 0.0];
+
+pub struct SettingsPanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    sld_gravity: Slider,
+    sld_bounciness: Slider,
+    sld_damping: Slider,
+    sld_volume: Slider,
+    sld_music: Slider,
+    sld_peg_density: Slider,
+    btn_trails: TextButton,
+    btn_materials: TextButton,
+    btn_bins: TextButton,
+    btn_layout: TextButton,
+    btn_solver: TextButton,
+    btn_refund: TextButton,
+    btn_sandbox: TextButton,
+    btn_juice: TextButton,
+    btn_attract: TextButton,
+    btn_theme: TextButton,
+    btn_text_scale: TextButton,
+    // Names of the board themes the theme button cycles through
+    themes: Vec<String>,
+    btn_save: TextButton,
+    btn_defaults: TextButton,
+    lbl_title: Label,
+    // Result of the last save, shown at the bottom of the panel
+    lbl_status: Label,
+    // Shown instead of the settings while the materials, the solver or the juice are being tuned
+    materials_panel: MaterialsPanel,
+    solver_panel: SolverPanel,
+    juice_panel: JuicePanel,
+}
+
+impl SettingsPanel {
+    pub fn new(x: f32, y: f32, settings: &Settings) -> Self {
+        let slider_x = x + (PANEL_WIDTH - SLIDER_WIDTH) / 2.0;
+        let first_y = y + 100.0;
+        // Parameters: x_pos, y_pos, width, min, max, starting value
+        let mut sld_gravity = Slider::new(slider_x, first_y, SLIDER_WIDTH, 200.0, 1600.0, settings.gravity);
+        sld_gravity.with_step(50.0).with_label("Gravity", 20);
+        let mut sld_bounciness = Slider::new(slider_x, first_y + SLIDER_SPACING, SLIDER_WIDTH, 0.0, 1.0, settings.bounciness);
+        sld_bounciness.with_step(0.05).with_label("Bounciness", 20).with_decimals(2);
+        let mut sld_damping = Slider::new(slider_x, first_y + SLIDER_SPACING * 2.0, SLIDER_WIDTH, 0.0, 3.0, settings.damping);
+        sld_damping.with_step(0.1).with_label("Damping", 20).with_decimals(1);
+        let mut sld_volume = Slider::new(slider_x, first_y + SLIDER_SPACING * 3.0, HALF_SLIDER_WIDTH, 0.0, 1.0, settings.volume);
+        sld_volume.with_step(0.05).with_label("Sound", 20).with_decimals(2);
+        let mut sld_music = Slider::new(slider_x + SLIDER_WIDTH - HALF_SLIDER_WIDTH, first_y + SLIDER_SPACING * 3.0, HALF_SLIDER_WIDTH, 0.0, 1.0, settings.music_volume);
+        sld_music.with_step(0.05).with_label("Music", 20).with_decimals(2);
+        // Much denser than 1.3 and the gaps get too small for pieces to fall through
+        let mut sld_peg_density = Slider::new(slider_x, first_y + SLIDER_SPACING * 4.0, SLIDER_WIDTH, 0.5, 1.3, settings.peg_density);
+        sld_peg_density.with_step(0.1).with_label("Peg density", 20).with_decimals(1);
+
+        let toggles_y = first_y + SLIDER_SPACING * 4.0 + 24.0;
+        let btn_trails = TextButton::new(slider_x, toggles_y, 150.0, 35.0, trails_text(settings.trails), DARKGRAY, GRAY, 20);
+        let btn_materials = TextButton::new(slider_x + 160.0, toggles_y, 160.0, 35.0, "Materials", DARKGRAY, GRAY, 20);
+        let btn_bins = TextButton::new(slider_x, toggles_y + TOGGLE_SPACING, 150.0, 35.0, bins_text(settings.bins), DARKGRAY, GRAY, 20);
+        let btn_layout = TextButton::new(slider_x + 160.0, toggles_y + TOGGLE_SPACING, 160.0, 35.0, settings.payout_layout.name(), DARKGRAY, GRAY, 20);
+        let btn_solver = TextButton::new(slider_x, toggles_y + TOGGLE_SPACING * 2.0, 150.0, 35.0, "Solver", DARKGRAY, GRAY, 20);
+        let btn_refund = TextButton::new(slider_x + 160.0, toggles_y + TOGGLE_SPACING * 2.0, 160.0, 35.0, refund_text(settings.refund_lost), DARKGRAY, GRAY, 20);
+        let btn_theme = TextButton::new(slider_x, toggles_y + TOGGLE_SPACING * 3.0, 150.0, 35.0, theme_text(&settings.theme), DARKGRAY, GRAY, 20);
+        let btn_text_scale = TextButton::new(slider_x + 160.0, toggles_y + TOGGLE_SPACING * 3.0, 160.0, 35.0, text_scale_text(settings.text_scale), DARKGRAY, GRAY, 20);
+        let btn_sandbox = TextButton::new(slider_x, toggles_y + TOGGLE_SPACING * 4.0, 150.0, 35.0, sandbox_text(settings.sandbox), DARKGRAY, GRAY, 20);
+        let btn_juice = TextButton::new(slider_x + 160.0, toggles_y + TOGGLE_SPACING * 4.0, 160.0, 35.0, "Juice", DARKGRAY, GRAY, 20);
+        let mut btn_attract = TextButton::new(slider_x, toggles_y + TOGGLE_SPACING * 5.0, 150.0, 35.0, attract_text(settings.attract_seconds), DARKGRAY, GRAY, 20);
+        btn_attract.with_tooltip("How long the menu (or an arcade round) is left alone before the demo plays");
+
+        let buttons_y = y + PANEL_HEIGHT - 80.0;
+        let btn_save = TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "Save & Close", DARKGREEN, GREEN, 25);
+        let btn_defaults = TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Defaults", DARKGRAY, GRAY, 25);
+
+        let mut lbl_title = Label::new("Settings", x + 30.0, y + 45.0, 35);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_status = Label::new("", x + 30.0, y + PANEL_HEIGHT - 12.0, 18);
+        lbl_status.with_colors(LIGHTGRAY, None);
+
+        Self {
+            x,
+            y,
+            visible: false,
+            sld_gravity,
+            sld_bounciness,
+            sld_damping,
+            sld_volume,
+            sld_music,
+            sld_peg_density,
+            btn_trails,
+            btn_materials,
+            btn_bins,
+            btn_layout,
+            btn_solver,
+            btn_refund,
+            btn_sandbox,
+            btn_juice,
+            btn_attract,
+            btn_theme,
+            btn_text_scale,
+            themes: Vec::new(),
+            btn_save,
+            btn_defaults,
+            lbl_title,
+            lbl_status,
+            materials_panel: MaterialsPanel::new(x, y),
+            solver_panel: SolverPanel::new(x, y),
+            juice_panel: JuicePanel::new(x, y),
+        }
+    }
+
+    /// Gives the theme button the names of the board themes to cycle through.
+    pub fn with_themes(&mut self, themes: Vec<String>, settings: &Settings) -> &mut Self {
+        self.themes = themes;
+        self.btn_theme.set_text(theme_text(&settings.theme));
+        self
+    }
+
+    /// Shows the panel with the sliders set to the current settings.
+    pub fn open(&mut self, settings: &Settings) {
+        self.sync(settings);
+        self.lbl_status.set_text("Peg density and bins apply to the next board");
+        self.materials_panel.visible = false;
+        self.solver_panel.visible = false;
+        self.juice_panel.visible = false;
+        self.visible = true;
+    }
+
+    /// Draws the panel and copies any slider changes into `settings`.
+    /// "Save & Close" saves the settings and hides the panel.
+    /// Returns true when a setting changed this frame.
+    pub fn update(&mut self, settings: &mut Settings) -> bool {
+        if !self.visible {
+            return false;
+        }
+        if self.materials_panel.visible {
+            return self.materials_panel.update(&mut settings.materials);
+        }
+        if self.solver_panel.visible {
+            return self.solver_panel.update(&mut settings.solver);
+        }
+        if self.juice_panel.visible {
+            return self.juice_panel.update(&mut settings.juice);
+        }
+
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+
+        let mut changed = false;
+        if self.sld_gravity.update() {
+            settings.gravity = self.sld_gravity.value();
+            changed = true;
+        }
+        if self.sld_bounciness.update() {
+            settings.bounciness = self.sld_bounciness.value();
+            changed = true;
+        }
+        if self.sld_damping.update() {
+            settings.damping = self.sld_damping.value();
+            changed = true;
+        }
+        if self.sld_volume.update() {
+            settings.volume = self.sld_volume.value();
+            changed = true;
+        }
+        if self.sld_music.update() {
+            settings.music_volume = self.sld_music.value();
+            changed = true;
+        }
+        if self.sld_peg_density.update() {
+            settings.peg_density = self.sld_peg_density.value();
+            changed = true;
+        }
+
+        if self.btn_trails.click() {
+            settings.trails = !settings.trails;
+            self.btn_trails.set_text(trails_text(settings.trails));
+            changed = true;
+        }
+        if self.btn_materials.click() {
+            self.materials_panel.open(&settings.materials);
+        }
+        if self.btn_solver.click() {
+            self.solver_panel.open(&settings.solver);
+        }
+        if self.btn_juice.click() {
+            self.juice_panel.open(&settings.juice);
+        }
+        if self.btn_refund.click() {
+            settings.refund_lost = !settings.refund_lost;
+            self.btn_refund.set_text(refund_text(settings.refund_lost));
+            changed = true;
+        }
+        if self.btn_sandbox.click() {
+            settings.sandbox = !settings.sandbox;
+            self.btn_sandbox.set_text(sandbox_text(settings.sandbox));
+            changed = true;
+        }
+        if self.btn_theme.click() && !self.themes.is_empty() {
+            let next = self.themes.iter().position(|theme| *theme == settings.theme).map_or(0, |index| (index + 1) % self.themes.len());
+            settings.theme = self.themes[next].clone();
+            self.btn_theme.set_text(theme_text(&settings.theme));
+            changed = true;
+        }
+        if self.btn_text_scale.click() {
+            // Past the biggest size it starts over from the smallest
+            let next = settings.text_scale + TEXT_SCALE_STEP;
+            settings.text_scale = if next > MAX_TEXT_SCALE + 0.01 { MIN_TEXT_SCALE } else { next };
+            self.btn_text_scale.set_text(text_scale_text(settings.text_scale));
+            changed = true;
+        }
+        if self.btn_attract.click() {
+            // The next choice after the current one, starting over past the last
+            let next = ATTRACT_CHOICES.iter().position(|&seconds| seconds == settings.attract_seconds).map_or(0, |index| (index + 1) % ATTRACT_CHOICES.len());
+            settings.attract_seconds = ATTRACT_CHOICES[next];
+            self.btn_attract.set_text(attract_text(settings.attract_seconds));
+            changed = true;
+        }
+        if self.btn_bins.click() {
+            settings.bins = match settings.bins {
+                0 => MIN_BINS,
+                MAX_BINS.. => 0,
+                bins => bins + 1,
+            };
+            self.btn_bins.set_text(bins_text(settings.bins));
+            changed = true;
+        }
+        if self.btn_layout.click() {
+            settings.payout_layout = match settings.payout_layout {
+                PayoutLayout::EdgesHigh => PayoutLayout::CenterHigh,
+                PayoutLayout::CenterHigh => PayoutLayout::EdgesHigh,
+            };
+            self.btn_layout.set_text(settings.payout_layout.name());
+            changed = true;
+        }
+
+        if self.btn_defaults.click() {
+            *settings = Settings::default();
+            self.sync(settings);
+            changed = true;
+        }
+        if self.btn_save.click() {
+            match settings.save() {
+                Ok(()) => self.visible = false,
+                Err(error) => {
+                    self.lbl_status.set_text(error);
+                }
+            }
+        }
+
+        self.lbl_status.draw();
+        changed
+    }
+
+    // Moves every slider to the matching setting
+    fn sync(&mut self, settings: &Settings) {
+        self.sld_gravity.set_value(settings.gravity);
+        self.sld_bounciness.set_value(settings.bounciness);
+        self.sld_damping.set_value(settings.damping);
+        self.sld_volume.set_value(settings.volume);
+        self.sld_music.set_value(settings.music_volume);
+        self.sld_peg_density.set_value(settings.peg_density);
+        self.btn_trails.set_text(trails_text(settings.trails));
+        self.btn_refund.set_text(refund_text(settings.refund_lost));
+        self.btn_sandbox.set_text(sandbox_text(settings.sandbox));
+        self.btn_bins.set_text(bins_text(settings.bins));
+        self.btn_layout.set_text(settings.payout_layout.name());
+        self.btn_theme.set_text(theme_text(&settings.theme));
+        self.btn_text_scale.set_text(text_scale_text(settings.text_scale));
+        self.btn_attract.set_text(attract_text(settings.attract_seconds));
+    }
+}
+
+// Text of the trails button for the current setting
+fn trails_text(trails: bool) -> &'static str {
+    if trails { "Trails: On" } else { "Trails: Off" }
+}
+
+// Text of the button choosing whether bets on lost pieces are refunded
+fn refund_text(refund: bool) -> &'static str {
+    if refund { "Refund lost: On" } else { "Refund lost: Off" }
+}
+
+// Text of the button letting pieces be grabbed and thrown
+fn sandbox_text(sandbox: bool) -> &'static str {
+    if sandbox { "Sandbox: On" } else { "Sandbox: Off" }
+}
+
+// Text of the theme button
+fn theme_text(theme: &str) -> String {
+    format!("Theme: {}", theme)
+}
+
+// Text of the text size button
+fn text_scale_text(scale: f32) -> String {
+    format!("Text: {:.0}%", scale * 100.0)
+}
+
+// Text of the bins button: the chosen count, or "Board" for the count each board was designed with
+fn bins_text(bins: usize) -> String {
+    if bins == 0 { "Bins: Board".to_string() } else { format!("Bins: {}", bins) }
+}
+
+// Text of the attract mode button: the idle seconds before the demo, or Off
+fn attract_text(seconds: f32) -> String {
+    if seconds <= 0.0 { "Demo: Off".to_string() } else { format!("Demo: {:.0} s", seconds) }
+}
@@ -0,0 +1,153 @@
+/*
+Program Details: Shape stats screen
+
+Opened with the Shapes button on the board. Compares balls, squares and
+triangles from the per-shape telemetry (see shape_telemetry.rs) in three bar
+charts: the average time a piece takes to come down and settle, the average
+number of pegs it hits on the way, and how each shape's landings spread over the
+bins (three bars per bin, one per shape). The legend above them says how many
+landings of each shape the charts are made from.
+
+Reset clears the telemetry; Back (or Escape) returns to the board.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod shape_stats_scene;
+
+Then with the other use commands add:
+use crate::modules::shape_stats_scene::ShapeStatsScene;
+
+Then above the loop section to use you would go:
+    let telemetry = SharedShapeTelemetry::default();
+    scenes.add(SceneId::ShapeStats, Box::new(ShapeStatsScene::new(telemetry.clone())));
+*/
+use macroquad::prelude::*;
+use crate::modules::label::Label;
+use crate::modules::physics::ShapeKind;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::shape_telemetry::{shape_color, SharedShapeTelemetry, ShapeTelemetry};
+use crate::modules::text_button::TextButton;
+
+// Areas the charts are drawn in: descent time and peg hits side by side, the bins below
+const DESCENT_CHART: Rect = Rect { x: 100.0, y: 210.0, w: 360.0, h: 190.0 };
+const HITS_CHART: Rect = Rect { x: 564.0, y: 210.0, w: 360.0, h: 190.0 };
+const BINS_CHART: Rect = Rect { x: 100.0, y: 480.0, w: 824.0, h: 140.0 };
+const LEGEND_Y: f32 = 130.0;
+// Space left between neighbouring bars (and bin groups)
+const BAR_GAP: f32 = 24.0;
+const GROUP_GAP: f32 = 8.0;
+const FONT_SIZE: f32 = 20.0;
+
+pub struct ShapeStatsScene {
+    telemetry: SharedShapeTelemetry,
+    lbl_title: Label,
+    btn_back: TextButton,
+    btn_reset: TextButton,
+}
+
+impl ShapeStatsScene {
+    pub fn new(telemetry: SharedShapeTelemetry) -> Self {
+        let mut lbl_title = Label::new("Shape Stats", 382.0, 70.0, 50);
+        lbl_title.with_colors(YELLOW, None);
+
+        Self {
+            telemetry,
+            lbl_title,
+            btn_back: TextButton::new(412.0, 680.0, 200.0, 60.0, "Back", DARKGRAY, GRAY, 30),
+            btn_reset: TextButton::new(642.0, 680.0, 200.0, 60.0, "Reset", MAROON, RED, 30),
+        }
+    }
+}
+
+// Title and axis of a chart in `area`
+fn draw_frame(area: Rect, title: &str) {
+    draw_text(title, area.x, area.y - 14.0, 24.0, LIGHTGRAY);
+    let bottom = area.y + area.h;
+    draw_line(area.x, bottom, area.x + area.w, bottom, 2.0, GRAY);
+}
+
+// One bar per shape, scaled to the biggest, with its value written above it
+fn draw_shape_chart(area: Rect, title: &str, values: [Option<f32>; 3], format: impl Fn(f32) -> String) {
+    draw_frame(area, title);
+    let max = values.iter().flatten().copied().fold(0.0, f32::max);
+    let width = area.w / values.len() as f32;
+    let bottom = area.y + area.h;
+    for (index, (kind, value)) in ShapeKind::ALL.into_iter().zip(values).enumerate() {
+        let x = area.x + width * index as f32 + BAR_GAP / 2.0;
+        let bar_width = width - BAR_GAP;
+        let color = shape_color(kind);
+        // A shape with no landings yet gets a dash instead of a bar
+        let (text, text_y) = match value {
+            Some(value) => {
+                let height = if max > 0.0 { value / max * (area.h - 30.0) } else { 0.0 };
+                draw_rectangle(x, bottom - height, bar_width, height, Color { a: 0.35, ..color });
+                draw_rectangle_lines(x, bottom - height, bar_width, height, 2.0, color);
+                (format(value), bottom - height - 6.0)
+            }
+            None => ("-".to_string(), bottom - 6.0),
+        };
+        let size = measure_text(&text, None, FONT_SIZE as u16, 1.0);
+        draw_text(&text, x + (bar_width - size.width) / 2.0, text_y, FONT_SIZE, WHITE);
+        let name = format!("{:?}", kind);
+        let size = measure_text(&name, None, FONT_SIZE as u16, 1.0);
+        draw_text(&name, x + (bar_width - size.width) / 2.0, bottom + 22.0, FONT_SIZE, color);
+    }
+}
+
+// A group of three bars (one per shape) for every bin: the share of that shape's landings in it
+fn draw_bins_chart(telemetry: &ShapeTelemetry) {
+    draw_frame(BINS_CHART, "Where each shape lands");
+    let bins = telemetry.bin_count();
+    if bins == 0 {
+        return;
+    }
+    let shares = ShapeKind::ALL.map(|kind| telemetry.bin_shares(kind));
+    let max = shares.iter().flatten().copied().fold(0.0, f32::max).max(f32::EPSILON);
+    let group_width = BINS_CHART.w / bins as f32;
+    let bar_width = (group_width - GROUP_GAP) / ShapeKind::ALL.len() as f32;
+    let bottom = BINS_CHART.y + BINS_CHART.h;
+    for bin in 0..bins {
+        let left = BINS_CHART.x + group_width * bin as f32 + GROUP_GAP / 2.0;
+        for (index, kind) in ShapeKind::ALL.into_iter().enumerate() {
+            let share = shares[index].get(bin).copied().unwrap_or(0.0);
+            let height = share / max * (BINS_CHART.h - 10.0);
+            draw_rectangle(left + bar_width * index as f32, bottom - height, bar_width - 1.0, height, shape_color(kind));
+        }
+        let text = (bin + 1).to_string();
+        let size = measure_text(&text, None, 16, 1.0);
+        draw_text(&text, left + (group_width - GROUP_GAP - size.width) / 2.0, bottom + 18.0, 16.0, LIGHTGRAY);
+    }
+}
+
+impl Scene for ShapeStatsScene {
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        if self.btn_back.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_reset.click() {
+            self.telemetry.borrow_mut().reset();
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.lbl_title.draw();
+        let telemetry = self.telemetry.borrow();
+
+        // Legend: each shape's colour and how many of its landings were counted
+        let mut x = DESCENT_CHART.x;
+        for kind in ShapeKind::ALL {
+            draw_rectangle(x, LEGEND_Y - 14.0, 16.0, 16.0, shape_color(kind));
+            let text = format!("{:?}: {} landed", kind, telemetry.landings(kind));
+            draw_text(&text, x + 24.0, LEGEND_Y, 24.0, WHITE);
+            x += 280.0;
+        }
+
+        let descent = ShapeKind::ALL.map(|kind| telemetry.average_descent(kind));
+        draw_shape_chart(DESCENT_CHART, "Average time to land", descent, |seconds| format!("{:.1} s", seconds));
+        let hits = ShapeKind::ALL.map(|kind| telemetry.average_peg_hits(kind));
+        draw_shape_chart(HITS_CHART, "Average peg hits", hits, |hits| format!("{:.1}", hits));
+        draw_bins_chart(&telemetry);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+Program Details: Per-shape physics telemetry
+
+Compares how balls, squares and triangles behave on the board: for each shape
+it counts the landings, and averages how long a piece takes from being dropped
+to settling in a bin (in seconds of game time, so pausing and slow motion don't
+skew it) and how many pegs it hits on the way, and keeps how its landings spread
+over the bins. The shape stats screen (see shape_stats_scene.rs) draws them as
+bar charts.
+
+Pieces are told apart by the shape tag PhysicsWorld::spawn puts in every piece's
+body user_data, so nothing has to be recorded when a piece is dropped: update()
+picks up new pieces from the world by itself. Pieces that leave the board
+without landing (despawned, or removed by the watchdog) aren't counted.
+
+The telemetry is shared (SharedShapeTelemetry) between the Plinko scene, which
+feeds it, and the stats screen. Like the bin statistics it keeps counting across
+boards and games until it is reset; when the number of bins changes the bin
+spreads start over.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod shape_telemetry;
+
+Then with the other use commands add:
+use crate::modules::shape_telemetry::SharedShapeTelemetry;
+
+Then above the loop section to use you would go:
+    let telemetry = SharedShapeTelemetry::default();
+
+Then in the loop you would use (with the seconds of game time the frame's steps took):
+    let steps = world.advance(get_frame_time());
+    telemetry.borrow_mut().update(&world, steps as f32 * world.integration_params.dt);
+
+And to read it, e.g. for the balls:
+    let seconds = telemetry.borrow().average_descent(ShapeKind::Ball);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::modules::events::GameEvent;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+
+pub type SharedShapeTelemetry = Rc<RefCell<ShapeTelemetry>>;
+
+// A piece on its way down
+struct Flight {
+    kind: ShapeKind,
+    // Game time since it was first seen, and pegs hit so far
+    seconds: f32,
+    peg_hits: u32,
+    // Set once it has settled, so it isn't picked up again while it lies in its bin
+    landed: bool,
+}
+
+// What has been counted for one shape
+#[derive(Clone, Default)]
+struct ShapeRecord {
+    landings: u32,
+    descent_seconds: f32,
+    peg_hits: u32,
+    bins: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct ShapeTelemetry {
+    // One per shape, in ShapeKind::ALL order
+    records: [ShapeRecord; 3],
+    flights: HashMap<RigidBodyHandle, Flight>,
+}
+
+impl ShapeTelemetry {
+    /// Forgets everything counted so far (pieces still falling are still counted when they land).
+    pub fn reset(&mut self) {
+        self.records = Default::default();
+    }
+
+    /// Stops following the pieces on the board, e.g. after a quick load replaced them.
+    pub fn forget_pieces(&mut self) {
+        self.flights.clear();
+    }
+
+    // Index of `kind` in ShapeKind::ALL (and in records)
+    fn index(kind: ShapeKind) -> usize {
+        ShapeKind::ALL.iter().position(|shape| *shape == kind).unwrap_or(0)
+    }
+
+    /// Follows the pieces through the frame's physics steps, `seconds` of game time long: picks up
+    /// new pieces, counts their peg hits and records their landings.
+    pub fn update(&mut self, world: &PhysicsWorld, seconds: f32) {
+        self.flights.retain(|handle, _| world.bodies.contains(*handle));
+        for (handle, body) in world.bodies.iter() {
+            if let Some(kind) = PhysicsWorld::shape_of(body) {
+                self.flights.entry(handle).or_insert(Flight { kind, seconds: 0.0, peg_hits: 0, landed: false });
+            }
+        }
+        for flight in self.flights.values_mut().filter(|flight| !flight.landed) {
+            flight.seconds += seconds;
+        }
+
+        let bin_count = world.board.as_ref().map_or(0, |board| board.bins.count);
+        for event in world.events() {
+            match *event {
+                GameEvent::PegHit { piece, .. } => {
+                    if let Some(flight) = self.flights.get_mut(&piece) {
+                        flight.peg_hits += 1;
+                    }
+                }
+                GameEvent::Settled { piece, bin } => {
+                    let Some(flight) = self.flights.get_mut(&piece).filter(|flight| !flight.landed) else {
+                        continue;
+                    };
+                    flight.landed = true;
+                    // A different number of bins starts every shape's spread over
+                    if self.records.iter().any(|record| !record.bins.is_empty() && record.bins.len() != bin_count) {
+                        for record in &mut self.records {
+                            record.bins.clear();
+                        }
+                    }
+                    let record = &mut self.records[Self::index(flight.kind)];
+                    record.landings += 1;
+                    record.descent_seconds += flight.seconds;
+                    record.peg_hits += flight.peg_hits;
+                    record.bins.resize(bin_count, 0);
+                    if let Some(count) = record.bins.get_mut(bin) {
+                        *count += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Pieces of `kind` that have landed.
+    pub fn landings(&self, kind: ShapeKind) -> u32 {
+        self.records[Self::index(kind)].landings
+    }
+
+    /// Average seconds (of game time) a piece of `kind` takes from the drop to settling, once one has landed.
+    pub fn average_descent(&self, kind: ShapeKind) -> Option<f32> {
+        let record = &self.records[Self::index(kind)];
+        (record.landings > 0).then(|| record.descent_seconds / record.landings as f32)
+    }
+
+    /// Average pegs a piece of `kind` hits on the way down, once one has landed.
+    pub fn average_peg_hits(&self, kind: ShapeKind) -> Option<f32> {
+        let record = &self.records[Self::index(kind)];
+        (record.landings > 0).then(|| record.peg_hits as f32 / record.landings as f32)
+    }
+
+    /// Share of the landings of `kind` in each bin, left to right (empty before the first landing).
+    pub fn bin_shares(&self, kind: ShapeKind) -> Vec<f32> {
+        let bins = &self.records[Self::index(kind)].bins;
+        let total: u32 = bins.iter().sum();
+        bins.iter().map(|count| *count as f32 / total.max(1) as f32).collect()
+    }
+
+    /// Number of bins the spreads are over (0 before the first landing).
+    pub fn bin_count(&self) -> usize {
+        self.records.iter().map(|record| record.bins.len()).max().unwrap_or(0)
+    }
+}
+
+/// The colour a shape's bars are drawn in.
+pub fn shape_color(kind: ShapeKind) -> Color {
+    match kind {
+        ShapeKind::Ball => SKYBLUE,
+        ShapeKind::Square => ORANGE,
+        ShapeKind::Triangle => LIME,
+    }
+}
@@ -0,0 +1,77 @@
+/*
+Program Details: Shareable board codes
+
+A share code packs a board and a game seed into one line of text that can be
+pasted into a chat or a forum post. Whoever types (or pastes) it on the title
+menu plays the same board with the same seed, so with the same drops they get
+the same random columns, maps and shapes.
+
+The code is the board and seed as compact JSON, compressed with DEFLATE and
+written in URL-safe base64 (letters, digits, '-' and '_'), after a short
+prefix naming the format: "PLK1-" for this first version, so a later version
+of the game can tell old codes apart.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod share_code;
+
+Then with the other use commands add:
+use crate::modules::share_code::ShareCode;
+
+Then above the loop section to use you would go:
+    let code = ShareCode::new(board.clone(), seed).encode();
+
+And to read one back:
+    match ShareCode::decode(&typed_text) {
+        Ok(shared) => world.build_board(&shared.board),
+        Err(error) => println!("{}", error),
+    }
+*/
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use crate::modules::board::BoardConfig;
+
+// Start of every code this version writes
+const CODE_PREFIX: &str = "PLK1-";
+// DEFLATE level (0-10): codes are made rarely and should be short
+const COMPRESSION_LEVEL: u8 = 9;
+// Most bytes a code may unpack to, so a bogus code can't take all the memory
+const MAX_UNPACKED_SIZE: usize = 1 << 20;
+
+/// A board and the seed its game's drops come from.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShareCode {
+    pub seed: u64,
+    pub board: BoardConfig,
+}
+
+impl ShareCode {
+    pub fn new(board: BoardConfig, seed: u64) -> Self {
+        Self { seed, board }
+    }
+
+    /// The code as text.
+    pub fn encode(&self) -> String {
+        // Both are plain data, so this can't fail
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        let packed = miniz_oxide::deflate::compress_to_vec(&json, COMPRESSION_LEVEL);
+        format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(packed))
+    }
+
+    /// Reads a code back. Spaces and line breaks are ignored (long codes get wrapped when shared),
+    /// and the board is checked the same way a board file is.
+    pub fn decode(code: &str) -> Result<ShareCode, String> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let Some(body) = code.strip_prefix(CODE_PREFIX) else {
+            return Err("That isn't a board code".to_string());
+        };
+        let packed = URL_SAFE_NO_PAD.decode(body).map_err(|_| "The board code is mistyped".to_string())?;
+        let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&packed, MAX_UNPACKED_SIZE)
+            .map_err(|_| "The board code is incomplete".to_string())?;
+        let shared: ShareCode = serde_json::from_slice(&json).map_err(|e| format!("The board code is damaged: {}", e))?;
+        shared.board.validate()?;
+        Ok(shared)
+    }
+}
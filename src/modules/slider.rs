@@ -0,0 +1,265 @@
+/*
+To import you need:
+Adds a horizontal slider object
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod slider;
+
+Then with the other use commands add:
+use crate::modules::slider::Slider;
+
+Then above the loop section to use you would go:
+
+    let mut sld_gravity = Slider::new(
+        820.0,    // x of the left end of the track
+        460.0,    // y of the track's center line
+        180.0,    // track width
+        200.0,    // min value
+        1600.0,   // max value
+        800.0,    // starting value
+    );
+
+Values snap to a step (0.0 means no snapping):
+    sld_gravity.with_step(50.0);
+
+You can show a caption and the current value above the track with:
+    sld_gravity.with_label("Gravity", 20);
+Values are shown with the given number of decimals (default 0):
+    sld_gravity.with_decimals(2);
+
+You can customize the colors with:
+    sld_gravity.with_colors(DARKGRAY, SKYBLUE, WHITE);   // track, filled part, handle
+
+You can run code every time the value changes with:
+    sld_gravity.on_change(|value| println!("gravity is now {}", value));
+
+To read or set the value:
+    let value = sld_gravity.value();
+    sld_gravity.set_value(1000.0);
+
+Then in the loop you would use (draws the slider and handles dragging;
+returns true on the frames the value changed):
+if sld_gravity.update() {
+    world.gravity.y = sld_gravity.value();
+}
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::dialog::input_blocked;
+
+pub struct Slider {
+    x: f32,
+    y: f32,
+    pub width: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+    value: f32,
+    pub enabled: bool,
+    pub visible: bool,
+    pub track_color: Color,
+    pub fill_color: Color,
+    pub handle_color: Color,
+    pub handle_hover_color: Color,
+    pub track_thickness: f32,
+    pub handle_radius: f32,
+    label: Option<String>,
+    pub font_size: u16,
+    pub text_color: Color,
+    pub decimals: usize,
+    // True while the player is holding the handle
+    dragging: bool,
+    on_change: Option<Box<dyn FnMut(f32)>>,
+}
+
+impl Slider {
+    pub fn new(x: f32, y: f32, width: f32, min: f32, max: f32, value: f32) -> Self {
+        // Allow the range to be given either way round
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        Self {
+            x,
+            y,
+            width,
+            min,
+            max,
+            step: 0.0,
+            value: value.clamp(min, max),
+            enabled: true,
+            visible: true,
+            track_color: DARKGRAY,
+            fill_color: SKYBLUE,
+            handle_color: WHITE,
+            handle_hover_color: LIGHTGRAY,
+            track_thickness: 6.0,
+            handle_radius: 10.0,
+            label: None,
+            font_size: 20,
+            text_color: WHITE,
+            decimals: 0,
+            dragging: false,
+            on_change: None,
+        }
+    }
+
+    // Method to make values snap to multiples of step (counted from min)
+    #[allow(unused)]
+    pub fn with_step(&mut self, step: f32) -> &mut Self {
+        self.step = step.max(0.0);
+        self.value = self.snap(self.value);
+        self
+    }
+
+    // Method to show a caption and the current value above the track
+    #[allow(unused)]
+    pub fn with_label(&mut self, label: impl Into<String>, font_size: u16) -> &mut Self {
+        self.label = Some(label.into());
+        self.font_size = font_size;
+        self
+    }
+
+    // Method to set how many decimals the shown value has
+    #[allow(unused)]
+    pub fn with_decimals(&mut self, decimals: usize) -> &mut Self {
+        self.decimals = decimals;
+        self
+    }
+
+    // Method to set the track, filled part and handle colors
+    #[allow(unused)]
+    pub fn with_colors(&mut self, track: Color, fill: Color, handle: Color) -> &mut Self {
+        self.track_color = track;
+        self.fill_color = fill;
+        self.handle_color = handle;
+        self
+    }
+
+    // Method to set a callback that runs with the new value whenever it changes
+    #[allow(unused)]
+    pub fn on_change(&mut self, callback: impl FnMut(f32) + 'static) -> &mut Self {
+        self.on_change = Some(Box::new(callback));
+        self
+    }
+
+    // Getter for the current value
+    #[allow(unused)]
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    // Setter for the value - clamped to the range and snapped to the step (doesn't run the callback)
+    #[allow(unused)]
+    pub fn set_value(&mut self, value: f32) -> &mut Self {
+        self.value = self.snap(value.clamp(self.min, self.max));
+        self
+    }
+
+    // Getter for the range as (min, max)
+    #[allow(unused)]
+    pub fn get_range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    // Getter for position as Vec2 (left end of the track's center line)
+    #[allow(unused)]
+    pub fn get_position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    // Update method to move the slider
+    #[allow(unused)]
+    pub fn update_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Draws the slider and lets the player drag the handle (or click anywhere on the track).
+    /// Returns true on the frames the value changed; the on_change callback runs at the same time.
+    pub fn update(&mut self) -> bool {
+        if !self.visible {
+            self.dragging = false;
+            return false;
+        }
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse_pos = Vec2::new(mouse_x, mouse_y);
+        let handle_pos = Vec2::new(self.value_to_x(self.value), self.y);
+
+        // The handle and the track (padded to the handle's height) can both be grabbed
+        let grab_area = Rect::new(
+            self.x - self.handle_radius,
+            self.y - self.handle_radius,
+            self.width + self.handle_radius * 2.0,
+            self.handle_radius * 2.0,
+        );
+        let is_hovered = self.enabled && !input_blocked() && (grab_area.contains(mouse_pos) || handle_pos.distance(mouse_pos) <= self.handle_radius);
+
+        if is_hovered && is_mouse_button_pressed(MouseButton::Left) {
+            self.dragging = true;
+        }
+        if !self.enabled || !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+
+        let mut changed = false;
+        if self.dragging {
+            let new_value = self.snap(self.x_to_value(mouse_x));
+            if new_value != self.value {
+                self.value = new_value;
+                changed = true;
+                if let Some(callback) = self.on_change.as_mut() {
+                    callback(new_value);
+                }
+            }
+        }
+
+        self.draw(is_hovered || self.dragging);
+        changed
+    }
+
+    fn draw(&self, highlighted: bool) {
+        let alpha = if self.enabled { 1.0 } else { 0.5 };
+        let handle_x = self.value_to_x(self.value);
+
+        // Track, then the filled part from the left end up to the handle
+        draw_line(self.x, self.y, self.x + self.width, self.y, self.track_thickness, Color { a: alpha, ..self.track_color });
+        draw_line(self.x, self.y, handle_x, self.y, self.track_thickness, Color { a: alpha, ..self.fill_color });
+
+        let handle_color = if highlighted { self.handle_hover_color } else { self.handle_color };
+        draw_circle(handle_x, self.y, self.handle_radius, Color { a: alpha, ..handle_color });
+
+        if let Some(label) = &self.label {
+            let text = format!("{}: {:.*}", label, self.decimals, self.value);
+            let text_y = self.y - self.handle_radius - 6.0;
+            draw_text(&text, self.x, text_y, self.font_size as f32, Color { a: alpha, ..self.text_color });
+        }
+    }
+
+    // Screen x of the handle for a value
+    fn value_to_x(&self, value: f32) -> f32 {
+        let range = self.max - self.min;
+        if range <= 0.0 {
+            return self.x;
+        }
+        self.x + (value - self.min) / range * self.width
+    }
+
+    // Value under a screen x, clamped to the range
+    fn x_to_value(&self, x: f32) -> f32 {
+        if self.width <= 0.0 {
+            return self.min;
+        }
+        let t = ((x - self.x) / self.width).clamp(0.0, 1.0);
+        self.min + t * (self.max - self.min)
+    }
+
+    // Rounds a value to the nearest step, staying inside the range
+    fn snap(&self, value: f32) -> f32 {
+        if self.step <= 0.0 {
+            return value;
+        }
+        let snapped = self.min + ((value - self.min) / self.step).round() * self.step;
+        snapped.clamp(self.min, self.max)
+    }
+}
@@ -0,0 +1,324 @@
+/*
+Program Details: Slot machine reels spun by every bin landing
+
+Three reels in a strip at the top of the board. Every piece that lands in a bin
+spins them; when they stop, three matching symbols multiply what that landing
+won by the symbol's multiplier and the extra is paid into the wallet. Anything
+else pays nothing more.
+
+Each reel picks its symbol from a weighted SymbolTable, so common symbols (and
+their small multipliers) come up far more often than the rare Seven. The result
+is picked when the spin starts, with the random number generator handed to
+update() (the game's own seeded one, so a seeded game spins the same symbols
+whatever the drops do); the animation just flickers through symbols and stops
+the reels one after the other, left to right.
+
+Landings that come in while the reels are still turning queue up behind the
+spin in progress, and queued spins run faster so a multiball burst doesn't keep
+the reels busy for long. The game waits for the queue to empty before it ends.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod slot_reel;
+
+Then with the other use commands add:
+use crate::modules::slot_reel::SlotMachine;
+
+Then above the loop section to use you would go:
+    let mut slots = SlotMachine::new();
+
+When a piece lands:
+    slots.spin(won, None);
+
+Then in the loop you would use (with a seeded rand::RandGenerator):
+    if let Some(result) = slots.update(get_frame_time(), &rng) {
+        wallet.deposit(result.bonus());
+    }
+    slots.draw();
+*/
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use std::collections::VecDeque;
+
+// How long a spin takes until the first reel stops, and the gap before each of the others stops
+const SPIN_SECONDS: f32 = 0.6;
+const STOP_GAP: f32 = 0.2;
+// Seconds between two symbols while a reel is turning
+const FLICKER_SECONDS: f32 = 0.05;
+// Where the strip is drawn: centered at the top of the board
+const STRIP_CENTER_X: f32 = 425.0;
+const STRIP_Y: f32 = 2.0;
+const REEL_WIDTH: f32 = 46.0;
+const REEL_HEIGHT: f32 = 38.0;
+const REEL_GAP: f32 = 6.0;
+pub const REELS: usize = 3;
+
+/// The symbols on the reels.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symbol {
+    Cherry,
+    Lemon,
+    Bell,
+    Star,
+    Seven,
+}
+
+impl Symbol {
+    pub const ALL: [Symbol; 5] = [Symbol::Cherry, Symbol::Lemon, Symbol::Bell, Symbol::Star, Symbol::Seven];
+
+    /// What three of this symbol multiply a landing's win by.
+    pub fn multiplier(self) -> f32 {
+        match self {
+            Symbol::Cherry => 2.0,
+            Symbol::Lemon => 3.0,
+            Symbol::Bell => 5.0,
+            Symbol::Star => 10.0,
+            Symbol::Seven => 25.0,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symbol::Cherry => "Cherry",
+            Symbol::Lemon => "Lemon",
+            Symbol::Bell => "Bell",
+            Symbol::Star => "Star",
+            Symbol::Seven => "Seven",
+        }
+    }
+
+    // Draws the symbol centered on (x, y) with primitives, faded to `alpha`
+    fn draw(self, x: f32, y: f32, alpha: f32) {
+        let fade = |color: Color| Color { a: alpha, ..color };
+        match self {
+            Symbol::Cherry => {
+                draw_line(x - 5.0, y + 3.0, x + 2.0, y - 11.0, 2.0, fade(GREEN));
+                draw_line(x + 6.0, y + 3.0, x + 2.0, y - 11.0, 2.0, fade(GREEN));
+                draw_circle(x - 5.0, y + 5.0, 6.0, fade(RED));
+                draw_circle(x + 6.0, y + 5.0, 6.0, fade(RED));
+            }
+            Symbol::Lemon => draw_ellipse(x, y, 13.0, 9.0, 0.0, fade(YELLOW)),
+            Symbol::Bell => {
+                draw_triangle(vec2(x, y - 12.0), vec2(x - 12.0, y + 8.0), vec2(x + 12.0, y + 8.0), fade(GOLD));
+                draw_circle(x, y + 10.0, 3.0, fade(GOLD));
+            }
+            Symbol::Star => {
+                draw_triangle(vec2(x, y - 13.0), vec2(x - 11.0, y + 6.0), vec2(x + 11.0, y + 6.0), fade(SKYBLUE));
+                draw_triangle(vec2(x, y + 12.0), vec2(x - 11.0, y - 6.0), vec2(x + 11.0, y - 6.0), fade(SKYBLUE));
+            }
+            Symbol::Seven => {
+                let size = measure_text("7", None, 36, 1.0);
+                draw_text("7", x - size.width / 2.0, y + size.height / 2.0, 36.0, fade(RED));
+            }
+        }
+    }
+}
+
+/// Symbols with the weight each is picked with; a symbol with twice the weight comes up twice as often.
+#[derive(Clone, Debug)]
+pub struct SymbolTable {
+    entries: Vec<(Symbol, u32)>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new(&[(Symbol::Cherry, 8), (Symbol::Lemon, 6), (Symbol::Bell, 4), (Symbol::Star, 2), (Symbol::Seven, 1)])
+    }
+}
+
+impl SymbolTable {
+    /// A table from (symbol, weight) pairs; symbols with no weight never come up.
+    pub fn new(entries: &[(Symbol, u32)]) -> Self {
+        Self { entries: entries.iter().copied().filter(|(_, weight)| *weight > 0).collect() }
+    }
+
+    /// Picks a symbol at random with `rng`, each with the chance of its share of the total weight.
+    pub fn pick(&self, rng: &RandGenerator) -> Symbol {
+        let total: u32 = self.entries.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.gen_range(0, total.max(1));
+        for (symbol, weight) in &self.entries {
+            if roll < *weight {
+                return *symbol;
+            }
+            roll -= weight;
+        }
+        Symbol::Cherry
+    }
+
+    /// The chance of `symbol` coming up on one spin of a reel using this table (0.0 - 1.0).
+    #[allow(unused)]
+    pub fn chance(&self, symbol: Symbol) -> f32 {
+        let total: u32 = self.entries.iter().map(|(_, weight)| weight).sum();
+        let weight: u32 = self.entries.iter().filter(|(entry, _)| *entry == symbol).map(|(_, weight)| weight).sum();
+        if total == 0 { 0.0 } else { weight as f32 / total as f32 }
+    }
+}
+
+/// One reel: the symbol table it spins from and the symbol it shows.
+pub struct SlotReel {
+    table: SymbolTable,
+    // The symbol showing (the result once stopped)
+    symbol: Symbol,
+    // Index into Symbol::ALL of the symbol flashed while turning
+    flicker: usize,
+    // Seconds left until this reel stops (0 when stopped)
+    spin_left: f32,
+    flicker_timer: f32,
+}
+
+impl SlotReel {
+    /// A reel showing a symbol picked from `table` with `rng`.
+    pub fn new(table: SymbolTable, rng: &RandGenerator) -> Self {
+        let symbol = table.pick(rng);
+        Self { table, symbol, flicker: 0, spin_left: 0.0, flicker_timer: 0.0 }
+    }
+
+    // Sets the reel turning for `seconds`, to stop on a symbol freshly picked with `rng`
+    fn spin(&mut self, seconds: f32, rng: &RandGenerator) -> Symbol {
+        self.symbol = self.table.pick(rng);
+        self.spin_left = seconds;
+        self.symbol
+    }
+
+    fn is_spinning(&self) -> bool {
+        self.spin_left > 0.0
+    }
+
+    // Counts down the spin, flicking to the next symbol every FLICKER_SECONDS. The flicker steps
+    // through the symbols in order rather than at random so it leaves the random number
+    // generator (and with it a seeded game's drops) alone
+    fn update(&mut self, dt: f32) {
+        if !self.is_spinning() {
+            return;
+        }
+        self.spin_left = (self.spin_left - dt).max(0.0);
+        self.flicker_timer += dt;
+        if self.flicker_timer >= FLICKER_SECONDS {
+            self.flicker_timer = 0.0;
+            self.flicker = (self.flicker + 1) % Symbol::ALL.len();
+        }
+    }
+}
+
+/// What a finished spin paid: the landing's win, the symbols and who the landing belonged to.
+#[derive(Clone, Copy, Debug)]
+pub struct SpinResult {
+    pub won: u32,
+    pub symbols: [Symbol; REELS],
+    // The hot-seat player whose piece landed (None in a one-player game)
+    pub owner: Option<usize>,
+}
+
+impl SpinResult {
+    /// The multiplier the reels give the landing's win: the symbol's for three of a kind, else 1.
+    pub fn multiplier(&self) -> f32 {
+        let [first, second, third] = self.symbols;
+        if first == second && second == third { first.multiplier() } else { 1.0 }
+    }
+
+    /// Credits paid on top of the landing's win.
+    pub fn bonus(&self) -> u32 {
+        (self.won as f32 * (self.multiplier() - 1.0)).floor() as u32
+    }
+}
+
+/// The three reels and the spins waiting their turn.
+pub struct SlotMachine {
+    reels: [SlotReel; REELS],
+    // The spin on the reels now, and the landings still to spin for (win and owner)
+    current: Option<SpinResult>,
+    queue: VecDeque<(u32, Option<usize>)>,
+    // Lights the strip up after a winning spin, counting down to 0
+    win_flash: f32,
+}
+
+impl Default for SlotMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlotMachine {
+    /// Three reels spinning from the default symbol table.
+    pub fn new() -> Self {
+        Self::with_tables([SymbolTable::default(), SymbolTable::default(), SymbolTable::default()])
+    }
+
+    /// Reels with a symbol table each, left to right.
+    pub fn with_tables(tables: [SymbolTable; REELS]) -> Self {
+        // The symbols showing before the first spin are only for looks, so they come from a generator of their own
+        let rng = RandGenerator::new();
+        Self { reels: tables.map(|table| SlotReel::new(table, &rng)), current: None, queue: VecDeque::new(), win_flash: 0.0 }
+    }
+
+    /// Asks for a spin for a landing that won `won` credits (for hot-seat player `owner`).
+    pub fn spin(&mut self, won: u32, owner: Option<usize>) {
+        self.queue.push_back((won, owner));
+    }
+
+    /// True when nothing is spinning or waiting to.
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none() && self.queue.is_empty()
+    }
+
+    /// Forgets every spin (e.g. when a new game starts).
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.queue.clear();
+        for reel in &mut self.reels {
+            reel.spin_left = 0.0;
+        }
+    }
+
+    /// Turns the reels and starts the next queued spin, its symbols picked with `rng`. Returns a spin's
+    /// result once all its reels stop.
+    pub fn update(&mut self, dt: f32, rng: &RandGenerator) -> Option<SpinResult> {
+        self.win_flash = (self.win_flash - dt).max(0.0);
+        for reel in &mut self.reels {
+            reel.update(dt);
+        }
+
+        if let Some(result) = self.current
+            && !self.reels.iter().any(SlotReel::is_spinning)
+        {
+            self.current = None;
+            if result.multiplier() > 1.0 {
+                self.win_flash = 1.0;
+            }
+            return Some(result);
+        }
+
+        if self.current.is_none()
+            && let Some((won, owner)) = self.queue.pop_front()
+        {
+            // The longer the queue, the quicker the spin
+            let speed = 1.0 + self.queue.len() as f32;
+            let mut symbols = [Symbol::Cherry; REELS];
+            for (index, reel) in self.reels.iter_mut().enumerate() {
+                symbols[index] = reel.spin((SPIN_SECONDS + STOP_GAP * index as f32) / speed, rng);
+            }
+            self.current = Some(SpinResult { won, symbols, owner });
+        }
+        None
+    }
+
+    /// Draws the strip of reels at the top of the board.
+    pub fn draw(&self) {
+        let width = REEL_WIDTH * REELS as f32 + REEL_GAP * (REELS as f32 + 1.0);
+        let left = STRIP_CENTER_X - width / 2.0;
+        let border = if self.win_flash > 0.0 && ((self.win_flash * 8.0) as u32).is_multiple_of(2) { GOLD } else { GRAY };
+        draw_rectangle(left, STRIP_Y, width, REEL_HEIGHT + REEL_GAP * 2.0, Color::new(0.1, 0.05, 0.15, 0.9));
+        draw_rectangle_lines(left, STRIP_Y, width, REEL_HEIGHT + REEL_GAP * 2.0, 2.0, border);
+
+        for (index, reel) in self.reels.iter().enumerate() {
+            let x = left + REEL_GAP + (REEL_WIDTH + REEL_GAP) * index as f32;
+            let y = STRIP_Y + REEL_GAP;
+            draw_rectangle(x, y, REEL_WIDTH, REEL_HEIGHT, Color::new(0.2, 0.17, 0.25, 1.0));
+            // Each reel flickers a couple of symbols apart from its neighbour so they don't move in step
+            let flicker = Symbol::ALL[(reel.flicker + index * 2) % Symbol::ALL.len()];
+            let (symbol, alpha) = if reel.is_spinning() { (flicker, 0.6) } else { (reel.symbol, 1.0) };
+            symbol.draw(x + REEL_WIDTH / 2.0, y + REEL_HEIGHT / 2.0, alpha);
+        }
+    }
+}
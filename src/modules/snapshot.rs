@@ -0,0 +1,151 @@
+/*
+Program Details: Quick save and quick load of a whole game
+
+A GameSnapshot holds everything needed to carry on a game later, even after the
+game has been closed: the physics world with every piece where it was and moving
+the way it was (see PhysicsWorld::snapshot), the board, the wallet, the stakes
+and multipliers riding on the falling pieces, the drops waiting to spawn, the
+statistics, the drop history, the session summary, the challenge objectives,
+the combo, the risk level, the tilts left, the pieces left in an inventory round
+and the seed the drops come from.
+
+There is one quick-save slot. It is written as CBOR (a compact binary form of
+the same data serde would write as JSON; JSON can't hold the physics world's
+maps), compressed with DEFLATE: to a quicksave.cbor file next to the game on native, and in base64 to
+localStorage on the web. A snapshot written by a different version of the
+snapshot format is refused rather than half loaded.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod snapshot;
+
+Then with the other use commands add:
+use crate::modules::snapshot::GameSnapshot;
+
+Then to save (e.g. on F5), fill one in from the game and:
+    snapshot.save()?;
+
+And to load (e.g. on F9):
+    let snapshot = GameSnapshot::load()?;
+    world.restore(snapshot.world);
+*/
+use rapier2d::prelude::RigidBodyHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::modules::board::{BoardConfig, RiskLevel};
+use crate::modules::drop_history::DropHistory;
+use crate::modules::inventory::Inventory;
+use crate::modules::lifetime::LifetimeManager;
+use crate::modules::multiplier::{Combo, DropMultipliers};
+use crate::modules::objectives::Objectives;
+use crate::modules::physics::WorldSnapshot;
+use crate::modules::seed::SeedSequence;
+use crate::modules::session::SessionSummary;
+use crate::modules::spawn_queue::SpawnQueue;
+use crate::modules::stats::BinStats;
+use crate::modules::wallet::Wallet;
+
+// File the quick save is written to on native
+#[cfg(not(target_arch = "wasm32"))]
+pub const SNAPSHOT_FILE: &str = "quicksave.cbor";
+// localStorage key the quick save is kept under on the web
+#[cfg(target_arch = "wasm32")]
+pub const SNAPSHOT_KEY: &str = "plinko2_quicksave";
+// Bumped whenever the saved fields change, so an old save isn't misread
+pub const SNAPSHOT_VERSION: u32 = 9;
+// DEFLATE level (0-10): quick saves are made by hand, so saving can take a moment
+const COMPRESSION_LEVEL: u8 = 6;
+// Most bytes a quick save may unpack to, so a damaged one can't take all the memory
+const MAX_UNPACKED_SIZE: usize = 64 << 20;
+
+/// Everything needed to pick a game up again.
+#[derive(Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub version: u32,
+    pub world: WorldSnapshot,
+    /// The board as designed (the world holds it fitted to the bin count)
+    pub design: BoardConfig,
+    pub random_maps: bool,
+    pub wallet: Wallet,
+    pub stakes: HashMap<RigidBodyHandle, u32>,
+    pub multipliers: DropMultipliers,
+    pub spawn_queue: SpawnQueue,
+    pub lifetime: LifetimeManager,
+    pub stats: BinStats,
+    pub history: DropHistory,
+    pub summary: SessionSummary,
+    pub seeds: SeedSequence,
+    pub seed_typed: bool,
+    pub round_left: Option<f32>,
+    /// Pieces left to drop in an inventory round
+    pub inventory: Option<Inventory>,
+    pub objectives: Objectives,
+    pub combo: Combo,
+    pub risk: RiskLevel,
+    pub tilts_left: u32,
+}
+
+impl GameSnapshot {
+    /// The snapshot in compressed CBOR.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).map_err(|e| e.to_string())?;
+        Ok(miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL))
+    }
+
+    /// Reads a snapshot back from compressed CBOR, refusing one from another version of the format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<GameSnapshot, String> {
+        let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(bytes, MAX_UNPACKED_SIZE)
+            .map_err(|_| "The quick save is damaged".to_string())?;
+        let snapshot: GameSnapshot = ciborium::from_reader(&bytes[..]).map_err(|_| "The quick save can't be read".to_string())?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err("The quick save is from another version of the game".to_string());
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot to the quick-save slot, replacing what was there.
+    pub fn save(&self) -> Result<(), String> {
+        storage::write(&self.to_bytes()?)
+    }
+
+    /// Reads the snapshot in the quick-save slot.
+    pub fn load() -> Result<GameSnapshot, String> {
+        let bytes = storage::read().ok_or("There is no quick save yet")?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+// Where the quick save lives: a file on native
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use super::SNAPSHOT_FILE;
+
+    pub fn read() -> Option<Vec<u8>> {
+        std::fs::read(SNAPSHOT_FILE).ok()
+    }
+
+    pub fn write(bytes: &[u8]) -> Result<(), String> {
+        std::fs::write(SNAPSHOT_FILE, bytes).map_err(|e| format!("Couldn't save {}: {}", SNAPSHOT_FILE, e))
+    }
+}
+
+// ... and localStorage on the web, which only holds text
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use super::SNAPSHOT_KEY;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+
+    pub fn read() -> Option<Vec<u8>> {
+        let text = quad_storage::STORAGE.lock().ok()?.get(SNAPSHOT_KEY)?;
+        STANDARD.decode(text).ok()
+    }
+
+    pub fn write(bytes: &[u8]) -> Result<(), String> {
+        let mut storage = quad_storage::STORAGE.lock().map_err(|e| e.to_string())?;
+        storage.set(SNAPSHOT_KEY, &STANDARD.encode(bytes));
+        Ok(())
+    }
+}
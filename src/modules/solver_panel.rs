@@ -0,0 +1,156 @@
+/*
+Program Details: Solver panel for tuning the physics solver
+
+A popup (opened from the settings panel) with one slider per solver setting:
+physics steps per second, velocity iterations, CCD substeps and the error
+reduction parameter (how much of the overlap between colliders is corrected
+each step). Lower values make each step cheaper when hundreds of pieces are on
+the board; higher values make bounces and stacking more accurate. Changes are
+copied into the SolverSettings right away and take effect from the next
+physics step. "Back" closes the panel; the solver settings are saved with the
+rest of the settings.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod solver_panel;
+
+Then with the other use commands add:
+use crate::modules::solver_panel::SolverPanel;
+
+Then above the loop section to use you would go:
+    let mut solver_panel = SolverPanel::new(200.0, 150.0);
+
+To show it (e.g. from a "Solver" button):
+    solver_panel.open(&settings.solver);
+
+Then in the loop, after the board has been drawn so the panel sits on top:
+if solver_panel.update(&mut settings.solver) {
+    settings.apply(&mut world, &mut audio);
+}
+*/
+use macroquad::prelude::*;
+use crate::modules::label::Label;
+use crate::modules::settings::SolverSettings;
+use crate::modules::slider::Slider;
+use crate::modules::text_button::TextButton;
+use crate::modules::theme::draw_panel;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 560.0;
+const SLIDER_WIDTH: f32 = 320.0;
+// Vertical distance between two sliders
+const SLIDER_SPACING: f32 = 62.0;
+
+pub struct SolverPanel {
+    x: f32,
+    y: f32,
+    pub visible: bool,
+    sld_step_rate: Slider,
+    sld_iterations: Slider,
+    sld_ccd_substeps: Slider,
+    sld_erp: Slider,
+    btn_back: TextButton,
+    btn_defaults: TextButton,
+    lbl_title: Label,
+    lbl_hint: Label,
+}
+
+impl SolverPanel {
+    pub fn new(x: f32, y: f32) -> Self {
+        let slider_x = x + (PANEL_WIDTH - SLIDER_WIDTH) / 2.0;
+        let first_y = y + 100.0;
+        let defaults = SolverSettings::default();
+        // Parameters: x_pos, y_pos, width, min, max, starting value
+        let mut sld_step_rate = Slider::new(slider_x, first_y, SLIDER_WIDTH, 30.0, 240.0, defaults.step_rate);
+        sld_step_rate.with_step(10.0).with_label("Steps per second", 20);
+        let mut sld_iterations = Slider::new(slider_x, first_y + SLIDER_SPACING, SLIDER_WIDTH, 1.0, 16.0, defaults.velocity_iterations as f32);
+        sld_iterations.with_step(1.0).with_label("Velocity iterations", 20);
+        let mut sld_ccd_substeps = Slider::new(slider_x, first_y + SLIDER_SPACING * 2.0, SLIDER_WIDTH, 1.0, 8.0, defaults.ccd_substeps as f32);
+        sld_ccd_substeps.with_step(1.0).with_label("CCD substeps", 20);
+        let mut sld_erp = Slider::new(slider_x, first_y + SLIDER_SPACING * 3.0, SLIDER_WIDTH, 0.05, 1.0, defaults.erp);
+        sld_erp.with_step(0.05).with_label("Overlap correction (ERP)", 20).with_decimals(2);
+
+        let buttons_y = y + PANEL_HEIGHT - 80.0;
+        let btn_back = TextButton::new(x + 30.0, buttons_y, 170.0, 45.0, "Back", DARKGREEN, GREEN, 25);
+        let btn_defaults = TextButton::new(x + PANEL_WIDTH - 200.0, buttons_y, 170.0, 45.0, "Defaults", DARKGRAY, GRAY, 25);
+
+        let mut lbl_title = Label::new("Solver", x + 30.0, y + 45.0, 35);
+        lbl_title.with_colors(WHITE, None);
+        let mut lbl_hint = Label::new(
+            "Lower values keep the game fast with hundreds of pieces; higher values make bounces and piles more accurate.",
+            slider_x,
+            first_y + SLIDER_SPACING * 3.0 + 50.0,
+            18,
+        );
+        lbl_hint.with_colors(LIGHTGRAY, None).with_wrap(SLIDER_WIDTH);
+
+        Self {
+            x,
+            y,
+            visible: false,
+            sld_step_rate,
+            sld_iterations,
+            sld_ccd_substeps,
+            sld_erp,
+            btn_back,
+            btn_defaults,
+            lbl_title,
+            lbl_hint,
+        }
+    }
+
+    /// Shows the panel with the sliders set to the current solver settings.
+    pub fn open(&mut self, solver: &SolverSettings) {
+        self.sync(solver);
+        self.visible = true;
+    }
+
+    /// Draws the panel and copies any slider changes into `solver`. "Back" hides the panel.
+    /// Returns true when a setting changed this frame.
+    pub fn update(&mut self, solver: &mut SolverSettings) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        draw_panel(self.x, self.y, PANEL_WIDTH, PANEL_HEIGHT);
+        self.lbl_title.draw();
+        self.lbl_hint.draw();
+
+        let mut changed = false;
+        if self.sld_step_rate.update() {
+            solver.step_rate = self.sld_step_rate.value();
+            changed = true;
+        }
+        if self.sld_iterations.update() {
+            solver.velocity_iterations = self.sld_iterations.value().round() as usize;
+            changed = true;
+        }
+        if self.sld_ccd_substeps.update() {
+            solver.ccd_substeps = self.sld_ccd_substeps.value().round() as usize;
+            changed = true;
+        }
+        if self.sld_erp.update() {
+            solver.erp = self.sld_erp.value();
+            changed = true;
+        }
+
+        if self.btn_defaults.click() {
+            *solver = SolverSettings::default();
+            self.sync(solver);
+            changed = true;
+        }
+        if self.btn_back.click() {
+            self.visible = false;
+        }
+        changed
+    }
+
+    // Moves every slider to the matching setting
+    fn sync(&mut self, solver: &SolverSettings) {
+        self.sld_step_rate.set_value(solver.step_rate);
+        self.sld_iterations.set_value(solver.velocity_iterations as f32);
+        self.sld_ccd_substeps.set_value(solver.ccd_substeps as f32);
+        self.sld_erp.set_value(solver.erp);
+    }
+}
@@ -0,0 +1,130 @@
+/*
+Program Details: Spawn queue that waits for the drop point to be clear
+
+Drops asked for in quick succession (two fast clicks, or holding the Random
+button) would spawn a piece right inside the one before it, and the solver
+pushing the two apart launches them both. Instead every drop goes into a queue
+and is only spawned once nothing is in the way at its drop point (checked with
+an intersection test against the pieces in play, see
+PhysicsWorld::spawn_point_clear). Drops come out in the order they were asked
+for: one still waiting holds back the ones behind it.
+
+The bet for a drop is taken when it is queued, so the wallet can't be
+overspent while drops wait; clearing the queue hands the queued drops back so
+their stakes can be refunded. A drop whose spawn point stays blocked for
+MAX_WAIT_SECONDS of game time (a piece wedged right under it) is given up on and
+handed back the same way, so it doesn't hold up every drop behind it forever.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod spawn_queue;
+
+Then with the other use commands add:
+use crate::modules::spawn_queue::{QueuedDrop, SpawnQueue};
+
+Then above the loop section to use you would go:
+    let mut spawn_queue = SpawnQueue::new();
+
+When a drop is asked for:
+    spawn_queue.push(QueuedDrop { kind: ShapeKind::Ball, x: 400.0, y: DROP_Y, seed, stake, owner: None });
+
+Then in the loop, before the physics step:
+    for (handle, drop) in spawn_queue.release(&mut world) {
+        stakes.insert(handle, drop.stake);
+    }
+    if let Some(drop) = spawn_queue.expire(game_dt) {
+        wallet.deposit(drop.stake);
+    }
+*/
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::VecDeque;
+use crate::modules::physics::{PhysicsWorld, ShapeKind};
+use serde::{Deserialize, Serialize};
+
+// Most drops that can wait at once; drops asked for beyond that are turned down
+pub const MAX_QUEUED: usize = 20;
+/// Seconds of game time a drop waits for its spawn point to clear before it's handed back.
+pub const MAX_WAIT_SECONDS: f32 = 3.0;
+
+/// A drop waiting for its spawn point to clear.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct QueuedDrop {
+    pub kind: ShapeKind,
+    pub x: f32,
+    pub y: f32,
+    /// Seed the drop was made with, for the history
+    pub seed: u64,
+    /// Bet already taken for the drop
+    pub stake: u32,
+    /// Player who asked for it in a hot-seat game
+    pub owner: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SpawnQueue {
+    pending: VecDeque<QueuedDrop>,
+    // Seconds the drop at the front has been waiting
+    #[serde(default)]
+    waited: f32,
+}
+
+impl SpawnQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if another drop can be queued.
+    pub fn has_room(&self) -> bool {
+        self.pending.len() < MAX_QUEUED
+    }
+
+    /// Queues a drop. Returns false (and drops nothing) when the queue is full.
+    pub fn push(&mut self, drop: QueuedDrop) -> bool {
+        if !self.has_room() {
+            return false;
+        }
+        self.pending.push_back(drop);
+        true
+    }
+
+    /// Spawns the drops at the front of the queue whose spawn point is clear, in order, and
+    /// returns each new piece's handle with the drop it came from.
+    pub fn release(&mut self, world: &mut PhysicsWorld) -> Vec<(RigidBodyHandle, QueuedDrop)> {
+        let mut released = Vec::new();
+        while let Some(drop) = self.pending.front().copied() {
+            if !world.spawn_point_clear(drop.kind, drop.x, drop.y) {
+                break;
+            }
+            self.pending.pop_front();
+            self.waited = 0.0;
+            released.push((world.spawn(drop.kind, drop.x, drop.y), drop));
+        }
+        released
+    }
+
+    /// Counts `dt` seconds of waiting against the drop at the front of the queue, and takes it off the
+    /// queue and hands it back (e.g. to refund it) once it has waited MAX_WAIT_SECONDS.
+    pub fn expire(&mut self, dt: f32) -> Option<QueuedDrop> {
+        if self.pending.is_empty() {
+            self.waited = 0.0;
+            return None;
+        }
+        self.waited += dt;
+        if self.waited < MAX_WAIT_SECONDS {
+            return None;
+        }
+        self.waited = 0.0;
+        self.pending.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Empties the queue, handing back the drops that never spawned (e.g. to refund them).
+    pub fn clear(&mut self) -> Vec<QueuedDrop> {
+        self.waited = 0.0;
+        self.pending.drain(..).collect()
+    }
+}
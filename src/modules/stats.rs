@@ -0,0 +1,143 @@
+/*
+Program Details: Galton board statistics
+
+Counts which bin every dropped piece settles in and draws the counts as a live
+histogram over the board (one bar per bin with its count and percentage), so the
+game doubles as a Galton board probability demo. Stats are kept separately from
+the pieces: clearing the board doesn't reset them, only reset() does.
+Under the count it shows what the counted landings pay on average with the
+payout table in play (it follows the risk level) next to what the odds say.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod stats;
+
+Then with the other use commands add:
+use crate::modules::stats::BinStats;
+
+Then above the loop section to use you would go:
+    let mut stats = BinStats::new();
+
+Then in the loop you would use:
+    stats.record_events(world.events(), board.bins.count);
+    // ... after the board is drawn:
+    if show_stats {
+        stats.draw(&board);
+    }
+
+To start counting again:
+    stats.reset();
+*/
+use macroquad::prelude::*;
+use crate::modules::board::BoardConfig;
+use crate::modules::events::GameEvent;
+use crate::modules::theme::scaled_font_size;
+use crate::modules::wallet::format_multiplier;
+use serde::{Deserialize, Serialize};
+
+// Height of the tallest bar in the overlay
+const MAX_BAR_HEIGHT: f32 = 300.0;
+// Space left between neighbouring bars
+const BAR_GAP: f32 = 8.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BinStats {
+    // Pieces settled in each bin, left to right
+    counts: Vec<u32>,
+}
+
+impl Default for BinStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinStats {
+    pub fn new() -> Self {
+        Self { counts: Vec::new() }
+    }
+
+    /// Counts a piece that settled in `bin`. `bin_count` is how many bins the board has;
+    /// if the board layout has changed to a different number of bins the stats start over.
+    pub fn record(&mut self, bin: usize, bin_count: usize) {
+        if self.counts.len() != bin_count {
+            self.counts = vec![0; bin_count];
+        }
+        if let Some(count) = self.counts.get_mut(bin) {
+            *count += 1;
+        }
+    }
+
+    /// Counts every Settled event in a frame's events.
+    pub fn record_events(&mut self, events: &[GameEvent], bin_count: usize) {
+        for event in events {
+            if let GameEvent::Settled { bin, .. } = *event {
+                self.record(bin, bin_count);
+            }
+        }
+    }
+
+    // Number of pieces counted so far
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    // Counts per bin, left to right
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Share of all counted pieces that settled in `bin` (0.0 - 1.0).
+    pub fn fraction(&self, bin: usize) -> f32 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        self.counts.get(bin).copied().unwrap_or(0) as f32 / total as f32
+    }
+
+    /// What the counted landings pay on average (as a multiple of the bet) with `payouts`,
+    /// rounded to hundredths.
+    pub fn average_payout(&self, payouts: &[f32]) -> f32 {
+        let total: f32 = payouts.iter().enumerate().map(|(bin, payout)| self.fraction(bin) * payout).sum();
+        (total * 100.0).round() / 100.0
+    }
+
+    /// Forgets every count.
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    /// Draws the histogram: a bar rising from the top of each bin, scaled so the fullest bin
+    /// is MAX_BAR_HEIGHT tall, labelled with its count and percentage, and what the counted
+    /// landings would have paid on average with the board's payout table.
+    pub fn draw(&self, board: &BoardConfig) {
+        let bottom = board.bins_top();
+        let width = board.bin_width();
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+
+        for bin in 0..board.bins.count {
+            let count = self.counts.get(bin).copied().unwrap_or(0);
+            let height = count as f32 / max as f32 * MAX_BAR_HEIGHT;
+            let x = board.bins_left() + width * bin as f32 + BAR_GAP / 2.0;
+            let bar_width = width - BAR_GAP;
+
+            draw_rectangle(x, bottom - height, bar_width, height, Color::new(0.2, 0.6, 1.0, 0.35));
+            draw_rectangle_lines(x, bottom - height, bar_width, height, 2.0, Color::new(0.2, 0.6, 1.0, 0.8));
+
+            let text = format!("{} ({:.1}%)", count, self.fraction(bin) * 100.0);
+            let font_size = scaled_font_size(18);
+            let text_width = measure_text(&text, None, font_size, 1.0).width;
+            draw_text(&text, x + (bar_width - text_width) / 2.0, bottom - height - 6.0, font_size as f32, WHITE);
+        }
+
+        draw_text(&format!("Drops counted: {}", self.total()), board.bins_left() + 10.0, bottom - MAX_BAR_HEIGHT - 30.0, scaled_font_size(22) as f32, WHITE);
+        let average = format!(
+            "Average payout: {} (expected {})",
+            format_multiplier(self.average_payout(&board.payouts)),
+            format_multiplier((board.expected_payout() * 100.0).round() / 100.0),
+        );
+        draw_text(&average, board.bins_left() + 10.0, bottom - MAX_BAR_HEIGHT - 8.0, scaled_font_size(20) as f32, YELLOW);
+    }
+}
@@ -0,0 +1,73 @@
+/*
+Program Details: Drawing the board's sticky pads and the pieces stuck to them
+
+Sticky pads are ledges on the board that hold on to every piece landing on them
+(see StickyPadConfig in board.rs; PhysicsWorld does the holding and sends
+GameEvent::PieceStuck and GameEvent::StickyPayout). They are a gamble against
+the bins: a pad pays its bonus for sure, but only once the piece has sat on it
+for the pad's delay. The pads themselves are drawn with the board's bodies in
+STICKY_PAD_COLOR; this module adds the bonus each one pays above it and a ring
+around every stuck piece that closes as its wait runs out.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod sticky_pad;
+
+Then with the other use commands add:
+use crate::modules::sticky_pad::{draw_sticky_pads, draw_stuck_timers, STICKY_PAD_COLOR};
+
+Then in the loop you would use (after drawing the board's bodies):
+    draw_sticky_pads(&board.sticky_pads);
+    draw_stuck_timers(&world);
+*/
+use macroquad::prelude::*;
+use crate::modules::board::StickyPadConfig;
+use crate::modules::physics::PhysicsWorld;
+use crate::modules::wallet::format_multiplier;
+
+// Colour of the sticky pads, and of their bonus and the timer rings
+pub const STICKY_PAD_COLOR: Color = Color::new(0.45, 0.85, 0.3, 1.0);
+const LABEL_COLOR: Color = Color::new(0.7, 1.0, 0.55, 1.0);
+// Gap between a stuck piece and its timer ring, and the ring's thickness
+const RING_GAP: f32 = 4.0;
+const RING_THICKNESS: f32 = 2.0;
+// Radius given to a stuck piece's ring when its collider has no size to go by
+const RING_RADIUS: f32 = 10.0;
+// Line segments in a full ring
+const RING_SEGMENTS: usize = 32;
+
+/// Draws the bonus every sticky pad pays just above it.
+pub fn draw_sticky_pads(pads: &[StickyPadConfig]) {
+    for pad in pads {
+        let text = format_multiplier(pad.bonus);
+        let size = measure_text(&text, None, 18, 1.0);
+        draw_text(&text, pad.x - size.width / 2.0, pad.y - pad.half_height - 4.0, 18.0, LABEL_COLOR);
+    }
+}
+
+/// Draws a ring around every piece stuck to a sticky pad, closing as its wait runs out.
+pub fn draw_stuck_timers(world: &PhysicsWorld) {
+    for piece in world.stuck_pieces() {
+        let (Some(body), Some(progress)) = (world.bodies.get(piece), world.stick_progress(piece)) else {
+            continue;
+        };
+        let radius = body
+            .colliders()
+            .first()
+            .and_then(|collider| world.colliders.get(*collider))
+            .map_or(RING_RADIUS, |collider| collider.shape().compute_local_aabb().half_extents().max())
+            + RING_GAP;
+        let center = body.translation();
+        // The ring starts full at the top and loses its segments anticlockwise, back towards the top
+        let segments = ((1.0 - progress) * RING_SEGMENTS as f32).ceil() as usize;
+        let point = |index: usize| {
+            let angle = index as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
+            vec2(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        };
+        for index in 0..segments {
+            let (a, b) = (point(index), point(index + 1));
+            draw_line(a.x, a.y, b.x, b.y, RING_THICKNESS, STICKY_PAD_COLOR);
+        }
+    }
+}
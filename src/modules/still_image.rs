@@ -56,20 +56,31 @@ Usage examples:
     
     // The unwrap() is safe because we know the texture was preloaded
 
-5. Clear an image (set to transparent):
+5. Using with Assets (see assets.rs), which loads each image once and shares its
+   texture and masks with every StillImage made from it:
+    let img = StillImage::from_asset(assets.image("ball").unwrap(), 40.0, 40.0, 100.0, 50.0, true, 1.0);
+    img.set_asset(assets.image("slot").unwrap());
+
+6. Clear an image (set to transparent):
     img.clear();
     
-6. Draw the image in your game loop:
+7. Draw the image in your game loop:
     img.draw();
 
 Additional functionality:
 - Zoom controls: set_zoom(), zoom_in(), zoom_out(), reset_zoom()
 - Stretch controls: enable_stretch(), disable_stretch(), toggle_stretch()
 - Position control: set_position()
+- Collision layer: set_layer() (see layers.rs)
+- Collision mask: regenerate_mask() with an alpha threshold or grown/shrunk (see collision.rs)
 - Check if empty: is_empty()
 */
 use macroquad::prelude::*;
 use macroquad::texture::Texture2D;
+use std::rc::Rc;
+use crate::modules::assets::ImageAsset;
+use crate::modules::collision::{coarse_mask, mask_from_image, mask_from_texture, MaskOptions};
+use crate::modules::layers::Layers;
 
 pub struct StillImage {
     texture: Texture2D,
@@ -77,11 +88,13 @@ pub struct StillImage {
     y: f32,
     width: f32,
     height: f32,
-    transparency_mask: Option<Vec<u8>>, // Changed to Option<Vec<u8>> to make it optional
+    transparency_mask: Option<Rc<Vec<u8>>>, // Optional, and shared with other images of the same asset
+    coarse_mask: Option<Rc<Vec<u8>>>, // The mask at 1/8 the size, so collisions can skip transparent parts quickly
     stretch_enabled: bool, // Flag to control image stretching
     zoom_level: f32, // Zoom factor to scale the image
     filename: String, // Store the original filename/path
     angle: f32, // Angle of rotation
+    layer: Option<Layers>, // Collision layer, None to collide with everything
 }
 
 impl StillImage {
@@ -99,7 +112,7 @@ impl StillImage {
         if asset_path.is_empty() {
             // Create an empty/clear image
             let empty_texture = Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]);
-            let empty_mask = Some(vec![0]); // Single transparent pixel
+            let empty_mask = Some(Rc::new(vec![0])); // Single transparent pixel
             
             return Self { 
                 x, 
@@ -108,15 +121,19 @@ impl StillImage {
                 height, 
                 texture: empty_texture, 
                 transparency_mask: empty_mask,
+                coarse_mask: None, // One pixel doesn't need a smaller copy
                 stretch_enabled,
                 zoom_level: zoom_level.max(0.1), // Ensure minimum zoom
                 filename: "__empty__".to_string(), // Use a special filename
                 angle: 0.0, // Default angle
+                layer: None, // Collides with everything
             };
         }
         
         // Normal path for valid asset paths
         let (texture, transparency_mask) = set_texture_main(asset_path).await;
+        let coarse_mask = coarse_mask_for(&texture, transparency_mask.as_deref());
+        let transparency_mask = transparency_mask.map(Rc::new);
         Self { 
             x, 
             y, 
@@ -124,10 +141,39 @@ impl StillImage {
             height, 
             texture, 
             transparency_mask,
+            coarse_mask,
             stretch_enabled,
             zoom_level: zoom_level.max(0.1), // Ensure minimum zoom
             filename: asset_path.to_string(), // Store the original filename
             angle: 0.0, // Default angle
+            layer: None, // Collides with everything
+        }
+    }
+
+    // Constructor for an image already loaded by Assets, sharing its texture and masks
+    #[allow(unused)]
+    pub fn from_asset(
+        asset: &ImageAsset,
+        width: f32,
+        height: f32,
+        x: f32,
+        y: f32,
+        stretch_enabled: bool,
+        zoom_level: f32
+    ) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            texture: asset.texture.clone(),
+            transparency_mask: asset.mask.clone(),
+            coarse_mask: asset.coarse_mask.clone(),
+            stretch_enabled,
+            zoom_level: zoom_level.max(0.1), // Ensure minimum zoom
+            filename: asset.path.clone(),
+            angle: 0.0, // Default angle
+            layer: None, // Collides with everything
         }
     }
 
@@ -190,6 +236,15 @@ impl StillImage {
     pub fn get_angle(&self) -> f32 {
         self.angle
     }
+    // Get and set the collision layer
+    #[allow(unused)]
+    pub fn set_layer(&mut self, layer: Layers) {
+        self.layer = Some(layer);
+    }
+    #[allow(unused)]
+    pub fn get_layer(&self) -> Option<Layers> {
+        self.layer
+    }
     // Get and set x position
     #[allow(unused)]
     pub fn get_x(&self) -> f32 {
@@ -221,16 +276,44 @@ impl StillImage {
     // Get the transparency mask (bitmask)
     #[allow(unused)]
     pub fn get_mask(&self) -> Option<Vec<u8>> {
-        self.transparency_mask.clone()
+        self.transparency_mask.as_deref().cloned()
+    }
+
+    // Get the low resolution copy of the transparency mask
+    #[allow(unused)]
+    pub fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.coarse_mask.as_deref().cloned()
+    }
+
+    // Makes the transparency mask again from the texture with other options, e.g. a higher alpha threshold for art
+    // with soft edges (see MaskOptions in collision.rs). Images sharing the old mask keep it
+    #[allow(unused)]
+    pub fn regenerate_mask(&mut self, options: &MaskOptions) {
+        if self.is_empty() {
+            return;
+        }
+        let mask = mask_from_texture(&self.texture, options);
+        self.coarse_mask = coarse_mask_for(&self.texture, mask.as_deref());
+        self.transparency_mask = mask.map(Rc::new);
     }
 
     #[allow(unused)]
     pub async fn set_texture(&mut self, texture_path: &str) {
         let (texture, transparency_mask) = set_texture_main(texture_path).await;
+        self.coarse_mask = coarse_mask_for(&texture, transparency_mask.as_deref());
         self.texture = texture;
-        self.transparency_mask = transparency_mask;
+        self.transparency_mask = transparency_mask.map(Rc::new);
         self.filename = texture_path.to_string(); // Update the filename when texture changes
     }
+
+    // Switches to an image already loaded by Assets, sharing its texture and masks
+    #[allow(unused)]
+    pub fn set_asset(&mut self, asset: &ImageAsset) {
+        self.texture = asset.texture.clone();
+        self.transparency_mask = asset.mask.clone();
+        self.coarse_mask = asset.coarse_mask.clone();
+        self.filename = asset.path.clone();
+    }
     
     // Methods to toggle stretching
     #[allow(unused)]
@@ -306,8 +389,9 @@ impl StillImage {
     #[allow(unused)]
     pub fn set_preload(&mut self, preloaded: (Texture2D, Option<Vec<u8>>, String)) {
         let (texture, mask, filename) = preloaded;
+        self.coarse_mask = coarse_mask_for(&texture, mask.as_deref());
         self.texture = texture;
-        self.transparency_mask = mask;
+        self.transparency_mask = mask.map(Rc::new);
         self.filename = filename;
     }
 
@@ -316,11 +400,12 @@ impl StillImage {
     pub fn clear(&mut self) {
         // Create a 1x1 transparent pixel texture
         let empty_texture = Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]);
-        let empty_mask = Some(vec![0]); // Single transparent pixel
+        let empty_mask = Some(Rc::new(vec![0])); // Single transparent pixel
         
         // Update the image object with this empty texture
         self.texture = empty_texture;
         self.transparency_mask = empty_mask;
+        self.coarse_mask = None;
         self.filename = "__empty__".to_string();
     }
 
@@ -331,69 +416,16 @@ impl StillImage {
     }
 }
 
-async fn generate_mask(texture_path: &str, width: usize, height: usize) -> Option<Vec<u8>> {
-    let image = load_image(texture_path).await.unwrap();
-    let pixels = image.bytes; // Image pixels in RGBA8 format
-    
-    // Check if the image format has an alpha channel at all (RGBA)
-    // If pixels length isn't divisible by 4, it's not RGBA format
-    if pixels.len() != width * height * 4 {
-        // No alpha channel, return None immediately
-        return None;
-    }
-
-   
-    let mut has_transparency = false;
-
-    // First, scan to see if the image has any transparency at all
-    for y in 0..height {
-        for x in 0..width {
-            let idx = (y * width + x) * 4; // Each pixel is 4 bytes (RGBA)
-            let alpha = pixels[idx + 3]; // Get alpha channel
-            
-            if alpha < 255 {
-                has_transparency = true;
-                break;
-            }
-        }
-        if has_transparency {
-            break;
-        }
-    }
-
-    // If there's no transparency, return None
-    if !has_transparency {
-        return None;
-    }
- // Only create the mask if we know the image has transparency
- let mut mask = vec![0; (width * height + 7) / 8]; // Create a bitmask with enough bytes
-    // Otherwise, create the transparency mask
-    for y in 0..height {
-        for x in 0..width {
-            let idx = (y * width + x) * 4; // Each pixel is 4 bytes (RGBA)
-            let alpha = pixels[idx + 3]; // Get alpha channel
-            let mask_byte_idx = (y * width + x) / 8; // Find which byte this pixel belongs to
-            let bit_offset = (y * width + x) % 8; // Find the bit offset inside the byte
-
-            if alpha > 0 {
-                // Set the bit to 1 (opaque pixel)
-                mask[mask_byte_idx] |= 1 << (7 - bit_offset);
-            } else {
-                // Set the bit to 0 (transparent pixel)
-                mask[mask_byte_idx] &= !(1 << (7 - bit_offset));
-            }
-        }
-    }
-
-    Some(mask)
+// The low resolution copy of a texture's transparency mask, made once when the texture is loaded
+fn coarse_mask_for(texture: &Texture2D, mask: Option<&[u8]>) -> Option<Rc<Vec<u8>>> {
+    mask.map(|mask| Rc::new(coarse_mask(mask, texture.width() as usize, texture.height() as usize)))
 }
 
 pub async fn set_texture_main(texture_path: &str) -> (Texture2D, Option<Vec<u8>>) {
-    let texture = load_texture(texture_path).await.unwrap();
+    let image = load_image(texture_path).await.unwrap();
+    let texture = Texture2D::from_image(&image);
     texture.set_filter(FilterMode::Linear);
-    let tex_width = texture.width() as usize;
-    let tex_height = texture.height() as usize;
-    let transparency_mask = generate_mask(texture_path, tex_width, tex_height).await;
-    return (texture, transparency_mask);
+    let transparency_mask = mask_from_image(&image, &MaskOptions::default());
+    (texture, transparency_mask)
 }
 
@@ -33,6 +33,11 @@ You can also specify a custom font with:
     btn_text.with_font(my_font.clone());
 Otherwise the default system font will be used.
 
+You can draw the button with a stretchable texture instead of a flat rectangle with:
+    btn_text.with_skin(NinePatch::rounded(WHITE, LIGHTGRAY, 8, 2));
+The skin is tinted with the button's colours. New buttons take their skin, text colour and
+font from the current theme (see theme.rs).
+
 You can add rounded corners to the button with:
     btn_text.with_round(10.0);
 Where the value is the corner radius in pixels.
@@ -53,6 +58,28 @@ Where the first two values are x and y positions, and the optional values are fo
 To change the button's text:
     btn_text.set_text("New Text");
 
+You can explain what the button does in a tooltip shown while the mouse rests on it with:
+    btn_text.with_tooltip("Drops ten balls at once");
+(draw_tooltips() has to be called at the end of the frame, see tooltip.rs)
+
+You can show an icon to the left of the text with:
+    btn_text.with_icon(texture);
+The icon is scaled to fit the button's height; with empty text it sits in the middle.
+
+To grey the button out so it can't be clicked (e.g. while the player can't afford a bet):
+    btn_text.enabled = false;
+
+To keep the button firing while it is held down (e.g. for a spawn button):
+    btn_text.with_repeat(0.4, 0.1);
+Where the first value is how long (seconds) it has to be held before it starts repeating and
+the second is the time between repeats. click() then also returns true for every repeat.
+
+The text is drawn at the theme's text scale (see theme.rs); text scaled up past the
+button's width shrinks back until it fits, so bigger text never spills out of the button.
+
+While the mouse button is held down on it the button is drawn pressed: darker, with the
+text and icon nudged down a pixel.
+
 Then in the loop you would use:
 if btn_text.click() {
 
@@ -62,9 +89,17 @@ Note: For buttons with transparent backgrounds (set normal_color with alpha=0),
 only the text area is clickable, not the entire button area.
 */
 use macroquad::prelude::*;
+use std::cell::Cell;
+use crate::modules::dialog::input_blocked;
+use crate::modules::nine_patch::NinePatch;
+use crate::modules::theme::{scaled_font_size, text_scale, theme};
+use crate::modules::tooltip::set_tooltip;
 #[cfg(feature = "scale")]
 use crate::modules::scale::mouse_position_world as mouse_position;
 
+// Space kept between scaled-up text and the sides of the button
+const BUTTON_TEXT_MARGIN: f32 = 4.0;
+
 // Custom struct for ButtonText
 pub struct TextButton {
     x: f32,              // Now private
@@ -84,6 +119,15 @@ pub struct TextButton {
     pub border: bool,       // Whether to draw a border
     pub border_color: Color, // Color of the border
     pub border_thickness: f32, // Thickness of the border
+    skin: Option<NinePatch>, // Drawn tinted instead of the flat background
+    icon: Option<Texture2D>, // Drawn to the left of the text
+    tooltip: Option<String>, // Shown while the mouse rests on the button
+    // Hold-to-repeat: seconds before the first repeat and between repeats
+    repeat: Option<(f32, f32)>,
+    // Seconds the button has been held down, and when it next repeats (None while not held)
+    held: Cell<Option<(f32, f32)>>,
+    // Text scale the text was last fitted to the button at, with the font size and text width that gave
+    fitted: Cell<Option<(f32, u16, f32)>>,
     
     // Cached values for performance
     cached_text_width: f32,
@@ -93,14 +137,16 @@ pub struct TextButton {
 }
 
 impl TextButton {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(x: f32, y: f32, width: f32, height: f32, text: impl Into<String>, normal_color: Color, hover_color: Color, font_size: u16) -> Self {
         let enabled = true;
         let off_color = lerp_color(normal_color, GRAY, 0.5);
         let text_string = text.into();
-        let text_color = WHITE; // Default text color
+        let theme = theme();
+        let text_color = theme.text_color; // Default text color
         
         // Pre-calculate and cache values
-        let cached_text_width = measure_text(&text_string, None, font_size, 1.0).width;
+        let cached_text_width = measure_text(&text_string, theme.font.as_ref(), font_size, 1.0).width;
         let cached_text_position = Vec2::new(
             x + (width / 2.0) - (cached_text_width / 2.0),
             y + (height / 2.0),
@@ -120,11 +166,17 @@ impl TextButton {
             text_color,
             hover_text_color: text_color, // Default hover text color to regular text color
             font_size,
-            font: None, // Default to None (use system font)
+            font: theme.font, // The theme's font (None uses the system font)
             corner_radius: 0.0, // Default to no rounded corners
             border: false, // Default to no border
             border_color: BLACK, // Default border color
             border_thickness: 1.0, // Default border thickness
+            skin: theme.button_skin, // The theme's skin (None draws flat rectangles)
+            icon: None, // No icon by default
+            tooltip: None, // No tooltip by default
+            repeat: None, // One click per press by default
+            held: Cell::new(None),
+            fitted: Cell::new(None),
             cached_text_width,
             cached_text_position,
             cached_rect,
@@ -139,6 +191,7 @@ impl TextButton {
         
         // Update cached text width with the new font
         self.cached_text_width = measure_text(&self.text, Some(&font), self.font_size, 1.0).width;
+        self.fitted.set(None);
         
         // Update text position based on new measurement
         self.cached_text_position = Vec2::new(
@@ -165,6 +218,35 @@ impl TextButton {
         self
     }
     
+    // Method to draw the button with a nine-patch skin (tinted with the button colours)
+    #[allow(unused)]
+    pub fn with_skin(&mut self, skin: NinePatch) -> &mut Self {
+        self.skin = Some(skin);
+        self
+    }
+
+    // Method to show an icon to the left of the text
+    #[allow(unused)]
+    pub fn with_icon(&mut self, icon: Texture2D) -> &mut Self {
+        self.icon = Some(icon);
+        self.fitted.set(None);
+        self
+    }
+
+    // Method to give the button a hover tooltip
+    #[allow(unused)]
+    pub fn with_tooltip(&mut self, text: impl Into<String>) -> &mut Self {
+        self.tooltip = Some(text.into());
+        self
+    }
+
+    // Method to keep firing while held: after `delay` seconds, then every `interval` seconds
+    #[allow(unused)]
+    pub fn with_repeat(&mut self, delay: f32, interval: f32) -> &mut Self {
+        self.repeat = Some((delay, interval.max(0.01)));
+        self
+    }
+
     // Method to set hover text color
     #[allow(unused)]
     pub fn with_hover_text_color(&mut self, color: Color) -> &mut Self {
@@ -216,6 +298,7 @@ impl TextButton {
             Some(font) => measure_text(&self.text, Some(font), self.font_size, 1.0).width,
             None => measure_text(&self.text, None, self.font_size, 1.0).width,
         };
+        self.fitted.set(None);
         
         // Update text position
         self.cached_text_position = Vec2::new(
@@ -242,6 +325,7 @@ impl TextButton {
         
         // Update cached rectangle
         self.cached_rect = Rect::new(self.x, self.y, self.width, self.height);
+        self.fitted.set(None);
         
         // Update text position
         self.cached_text_position = Vec2::new(
@@ -263,8 +347,8 @@ impl TextButton {
         // Check if the background is transparent (alpha is 0)
         let is_background_transparent = self.normal_color.a == 0.0;
         
-        // Determine is_hovered based on background transparency
-        let is_hovered = if is_background_transparent {
+        // Determine is_hovered based on background transparency (never while a dialog has the input)
+        let is_hovered = !input_blocked() && if is_background_transparent {
             // If transparent, only detect clicks on the text area
             let text_height = self.font_size as f32; // Approximate text height
             let text_rect = Rect::new(
@@ -279,9 +363,19 @@ impl TextButton {
             self.cached_rect.contains(mouse_pos)
         };
 
-        // Draw the text button (change color on hover)
+        if let Some(tooltip) = &self.tooltip {
+            set_tooltip(self.cached_rect, tooltip.as_str());
+        }
+
+        // Held down on the button: drawn pressed, and repeating if that's switched on
+        let is_pressed = is_hovered && self.enabled && is_mouse_button_down(MouseButton::Left);
+        let press_offset = if is_pressed { 1.0 } else { 0.0 };
+
+        // Draw the text button (change color on hover, darker while pressed)
         let button_color = if self.enabled {
-            if is_hovered {
+            if is_pressed {
+                lerp_color(self.hover_color, BLACK, 0.3)
+            } else if is_hovered {
                 self.hover_color
             } else {
                 self.normal_color
@@ -290,8 +384,10 @@ impl TextButton {
             self.off_color
         };
 
-        // Draw the button with or without rounded corners
-        if self.corner_radius > 0.0 {
+        // Draw the button with its skin, or with or without rounded corners
+        if let Some(skin) = &self.skin {
+            skin.draw(self.x, self.y, self.width, self.height, button_color);
+        } else if self.corner_radius > 0.0 {
             draw_round_rect(self.x, self.y, self.width, self.height, self.corner_radius, button_color);
             
             // Draw rounded border if enabled
@@ -321,15 +417,33 @@ impl TextButton {
             Color::new(self.text_color.r, self.text_color.g, self.text_color.b, 0.5)
         };
         
+        // With an icon the icon and the text are centered together, the icon on the left
+        let (font_size, text_width) = self.fitted_text();
+        let mut text_x = self.x + (self.width - text_width) / 2.0;
+        let text_y = self.cached_text_position.y + press_offset;
+        if let Some(icon) = &self.icon {
+            let (icon_size, gap) = self.icon_room();
+            let left = self.x + (self.width - icon_size - gap - text_width) / 2.0;
+            let tint = if self.enabled { WHITE } else { Color::new(1.0, 1.0, 1.0, 0.5) };
+            draw_texture_ex(
+                icon,
+                left,
+                self.y + (self.height - icon_size) / 2.0 + press_offset,
+                tint,
+                DrawTextureParams { dest_size: Some(vec2(icon_size, icon_size)), ..Default::default() },
+            );
+            text_x = left + icon_size + gap;
+        }
+
         match &self.font {
             Some(font) => {
                 draw_text_ex(
                     &self.text,
-                    self.cached_text_position.x,
-                    self.cached_text_position.y,
+                    text_x,
+                    text_y,
                     TextParams {
                         font: Some(font),
-                        font_size: self.font_size,
+                        font_size,
                         color: current_text_color,
                         ..Default::default()
                     },
@@ -339,16 +453,65 @@ impl TextButton {
                 // Use the default draw_text function
                 draw_text(
                     &self.text,
-                    self.cached_text_position.x,
-                    self.cached_text_position.y,
-                    self.font_size.into(),
+                    text_x,
+                    text_y,
+                    font_size.into(),
                     current_text_color,
                 );
             }
         }
 
         // After drawing, check if the button was clicked
-        is_hovered && self.enabled && is_mouse_button_pressed(MouseButton::Left)
+        let clicked = is_hovered && self.enabled && is_mouse_button_pressed(MouseButton::Left);
+        clicked || self.repeated(clicked, is_pressed)
+    }
+
+    // Font size and width of the text at the theme's text scale, shrunk back (no further than the
+    // designed font size) if it would be wider than the button
+    fn fitted_text(&self) -> (u16, f32) {
+        let scale = text_scale();
+        if let Some((_, font_size, width)) = self.fitted.get().filter(|(fitted_scale, _, _)| *fitted_scale == scale) {
+            return (font_size, width);
+        }
+        let (icon_size, gap) = self.icon_room();
+        let room = self.width - icon_size - gap - BUTTON_TEXT_MARGIN * 2.0;
+        let mut font_size = scaled_font_size(self.font_size);
+        let mut width = measure_text(&self.text, self.font.as_ref(), font_size, 1.0).width;
+        if width > room && font_size > self.font_size && room > 0.0 {
+            // Text width grows in step with the font size
+            font_size = ((font_size as f32 * room / width).floor() as u16).max(self.font_size);
+            width = measure_text(&self.text, self.font.as_ref(), font_size, 1.0).width;
+        }
+        self.fitted.set(Some((scale, font_size, width)));
+        (font_size, width)
+    }
+
+    // Size of the icon and the gap between it and the text (nothing without an icon)
+    fn icon_room(&self) -> (f32, f32) {
+        match self.icon {
+            Some(_) => (self.height * 0.6, if self.text.is_empty() { 0.0 } else { 6.0 }),
+            None => (0.0, 0.0),
+        }
+    }
+
+    // True when a button held down since `clicked` is due to fire again
+    fn repeated(&self, clicked: bool, is_pressed: bool) -> bool {
+        let Some((delay, interval)) = self.repeat else {
+            return false;
+        };
+        if clicked {
+            self.held.set(Some((0.0, delay)));
+            return false;
+        }
+        // Letting go, sliding off the button or the button being disabled stops the repeats
+        let Some((held_for, next)) = self.held.get().filter(|_| is_pressed) else {
+            self.held.set(None);
+            return false;
+        };
+        let held_for = held_for + get_frame_time();
+        let fire = held_for >= next;
+        self.held.set(Some((held_for, if fire { next + interval } else { next })));
+        fire
     }
 }
 
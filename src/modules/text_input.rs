@@ -0,0 +1,323 @@
+/*
+To import you need:
+Adds a single-line text input box
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod text_input;
+
+Then with the other use commands add:
+use crate::modules::text_input::TextInput;
+
+Then above the loop section to use you would go:
+
+    let mut txt_seed = TextInput::new(
+        412.0,    // x
+        580.0,    // y
+        200.0,    // width
+        40.0,     // height
+        24,       // font size
+    );
+
+You can show grey hint text while the box is empty with:
+    txt_seed.with_placeholder("Seed");
+
+And limit how many characters can be typed with:
+    txt_seed.with_max_length(20);
+
+You can customize the colors with:
+    txt_seed.with_colors(Color::new(0.1, 0.1, 0.15, 1.0), GRAY, SKYBLUE);   // background, border, border while focused
+
+To read or set the text:
+    let text = txt_seed.text();
+    txt_seed.set_text("12345");
+
+Then in the loop you would use (handles focus and typing; returns true on
+the frame Enter is pressed while the box has focus):
+if txt_seed.update() {
+    // use txt_seed.text()
+}
+    txt_seed.draw();
+
+Clicking the box gives it focus and clicking anywhere else takes it away. While
+focused the box takes the typed characters (macroquad's character events, so it
+works with the keyboard on native and in the browser on the web), Backspace and
+Delete, and Left/Right/Home/End to move the cursor. Ctrl+V (Cmd+V on a Mac)
+pastes the clipboard at the cursor, line breaks left out. Check is_focused() to
+keep keyboard shortcuts from firing while the player is typing.
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::dialog::input_blocked;
+
+// Seconds the cursor stays shown (then hidden) while blinking
+const BLINK_TIME: f32 = 0.5;
+// Held Backspace/Delete/arrow keys start repeating after this many seconds, then repeat this often
+const REPEAT_DELAY: f32 = 0.4;
+const REPEAT_RATE: f32 = 0.05;
+// Space between the box's left edge and the text
+const PADDING: f32 = 8.0;
+
+pub struct TextInput {
+    x: f32,
+    y: f32,
+    pub width: f32,
+    pub height: f32,
+    // The text as characters so the cursor can index it directly
+    chars: Vec<char>,
+    // Cursor position as a character index (0 = before the first character)
+    cursor: usize,
+    focused: bool,
+    placeholder: String,
+    max_length: usize,
+    pub enabled: bool,
+    pub visible: bool,
+    pub background_color: Color,
+    pub border_color: Color,
+    pub focus_color: Color,
+    pub text_color: Color,
+    pub font_size: u16,
+    // Time since the last keypress, for blinking the cursor and repeating held keys
+    blink_timer: f32,
+    held_key: Option<KeyCode>,
+    held_time: f32,
+}
+
+impl TextInput {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, font_size: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            chars: Vec::new(),
+            cursor: 0,
+            focused: false,
+            placeholder: String::new(),
+            max_length: 64,
+            enabled: true,
+            visible: true,
+            background_color: Color::new(0.1, 0.1, 0.15, 1.0),
+            border_color: GRAY,
+            focus_color: SKYBLUE,
+            text_color: WHITE,
+            font_size,
+            blink_timer: 0.0,
+            held_key: None,
+            held_time: 0.0,
+        }
+    }
+
+    // Method to set the hint shown while the box is empty
+    #[allow(unused)]
+    pub fn with_placeholder(&mut self, placeholder: impl Into<String>) -> &mut Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    // Method to limit how many characters can be typed
+    #[allow(unused)]
+    pub fn with_max_length(&mut self, max_length: usize) -> &mut Self {
+        self.max_length = max_length;
+        self.chars.truncate(max_length);
+        self.cursor = self.cursor.min(self.chars.len());
+        self
+    }
+
+    // Method to set the background, border and focused border colors
+    #[allow(unused)]
+    pub fn with_colors(&mut self, background: Color, border: Color, focus: Color) -> &mut Self {
+        self.background_color = background;
+        self.border_color = border;
+        self.focus_color = focus;
+        self
+    }
+
+    // Getter for the typed text
+    #[allow(unused)]
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    // Setter for the text - cut to the max length, with the cursor moved to the end
+    #[allow(unused)]
+    pub fn set_text(&mut self, text: impl AsRef<str>) -> &mut Self {
+        self.chars = text.as_ref().chars().take(self.max_length).collect();
+        self.cursor = self.chars.len();
+        self
+    }
+
+    // Whether the box currently takes the keyboard
+    #[allow(unused)]
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    // Gives the box the keyboard, with the cursor at the end of the text
+    #[allow(unused)]
+    pub fn focus(&mut self) {
+        // Forget anything typed while no box had focus
+        clear_input_queue();
+        self.focused = true;
+        self.cursor = self.chars.len();
+        self.blink_timer = 0.0;
+    }
+
+    // Takes the keyboard away from the box
+    #[allow(unused)]
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+        self.held_key = None;
+    }
+
+    // Getter for position as Vec2
+    #[allow(unused)]
+    pub fn get_position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    // Update method to move the box
+    #[allow(unused)]
+    pub fn update_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Handles focusing and typing. Returns true on the frame Enter is pressed while focused.
+    pub fn update(&mut self) -> bool {
+        if !self.visible || !self.enabled {
+            self.unfocus();
+            return false;
+        }
+        // An open dialog has the mouse and keyboard; the box keeps its focus for afterwards
+        if input_blocked() {
+            return false;
+        }
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let (mouse_x, mouse_y) = mouse_position();
+            if Rect::new(self.x, self.y, self.width, self.height).contains(vec2(mouse_x, mouse_y)) {
+                if !self.focused {
+                    self.focus();
+                }
+            } else {
+                self.unfocus();
+            }
+        }
+        if !self.focused {
+            return false;
+        }
+
+        // macroquad hands out the typed characters newest first
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+        let dt = get_frame_time();
+        self.blink_timer += dt;
+        // Control characters (Backspace and Enter arrive as characters on some platforms) are
+        // handled through their key codes below instead. Letters typed with Ctrl or Cmd held are
+        // shortcuts, not text.
+        let command = [KeyCode::LeftControl, KeyCode::RightControl, KeyCode::LeftSuper, KeyCode::RightSuper].into_iter().any(is_key_down);
+        if !command {
+            self.insert(typed);
+        }
+        if command
+            && is_key_pressed(KeyCode::V)
+            && let Some(pasted) = miniquad::window::clipboard_get()
+        {
+            self.insert(pasted.chars());
+        }
+
+        for key in [KeyCode::Backspace, KeyCode::Delete, KeyCode::Left, KeyCode::Right] {
+            if self.key_repeats(key, dt) {
+                self.edit(key);
+            }
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.cursor = 0;
+        }
+        if is_key_pressed(KeyCode::End) {
+            self.cursor = self.chars.len();
+        }
+
+        is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter)
+    }
+
+    /// Draws the box, the text (or the placeholder) and the blinking cursor while focused.
+    pub fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        let alpha = if self.enabled { 1.0 } else { 0.5 };
+        draw_rectangle(self.x, self.y, self.width, self.height, Color { a: alpha, ..self.background_color });
+        let border = if self.focused { self.focus_color } else { self.border_color };
+        draw_rectangle_lines(self.x, self.y, self.width, self.height, 2.0, Color { a: alpha, ..border });
+
+        let text = self.text();
+        let baseline = self.y + self.height / 2.0 + measure_text("Ag", None, self.font_size, 1.0).offset_y / 2.0;
+        // Text that doesn't fit is scrolled left so the cursor stays inside the box
+        let before_cursor: String = self.chars[..self.cursor].iter().collect();
+        let cursor_offset = measure_text(&before_cursor, None, self.font_size, 1.0).width;
+        let scroll = (cursor_offset - (self.width - PADDING * 2.0)).max(0.0);
+        let text_x = self.x + PADDING - scroll;
+
+        if text.is_empty() && !self.focused {
+            draw_text(&self.placeholder, text_x, baseline, self.font_size as f32, Color { a: alpha * 0.6, ..GRAY });
+        } else {
+            draw_text(&text, text_x, baseline, self.font_size as f32, Color { a: alpha, ..self.text_color });
+        }
+
+        if self.focused && ((self.blink_timer / BLINK_TIME) as u32).is_multiple_of(2) {
+            let cursor_x = text_x + cursor_offset + 1.0;
+            draw_line(cursor_x, self.y + 6.0, cursor_x, self.y + self.height - 6.0, 2.0, self.text_color);
+        }
+    }
+
+    // Types `text` at the cursor, leaving out control characters and anything past the max length
+    fn insert(&mut self, text: impl IntoIterator<Item = char>) {
+        for c in text.into_iter().filter(|c| !c.is_control()) {
+            if self.chars.len() < self.max_length {
+                self.chars.insert(self.cursor, c);
+                self.cursor += 1;
+                self.blink_timer = 0.0;
+            }
+        }
+    }
+
+    // Applies one press of an editing key
+    fn edit(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.chars.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.chars.len() => {
+                self.chars.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.chars.len()),
+            _ => {}
+        }
+        self.blink_timer = 0.0;
+    }
+
+    // True on the frame a key is pressed and then repeatedly while it is held
+    fn key_repeats(&mut self, key: KeyCode, dt: f32) -> bool {
+        if is_key_pressed(key) {
+            self.held_key = Some(key);
+            self.held_time = 0.0;
+            return true;
+        }
+        if self.held_key != Some(key) {
+            return false;
+        }
+        if !is_key_down(key) {
+            self.held_key = None;
+            return false;
+        }
+        let before = self.held_time;
+        self.held_time += dt;
+        // Once past the delay, one repeat every time the held time crosses a multiple of the rate
+        self.held_time > REPEAT_DELAY && ((self.held_time - REPEAT_DELAY) / REPEAT_RATE) as u32 != ((before - REPEAT_DELAY).max(0.0) / REPEAT_RATE) as u32
+    }
+}
@@ -0,0 +1,129 @@
+/*
+Program Details: Texture atlas for drawing physics bodies as sprites
+
+Packs several images side by side into one texture at load time, so every sprite
+drawn from the atlas shares a texture and macroquad can batch them together.
+A body is given a sprite by storing the sprite's id in the Rapier body's
+user_data; bodies without one keep the primitive (circle/line) rendering.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod texture_atlas;
+
+Then with the other use commands add:
+use crate::modules::texture_atlas::TextureAtlas;
+
+Then above the loop section to use you would go:
+    let atlas = TextureAtlas::load(&["assets/ball.png"]).await.unwrap();
+    let ball_sprite = atlas.sprite("assets/ball.png");
+
+When spawning a body, give it a sprite:
+    let handle = world.spawn(ShapeKind::Ball, 400.0, 50.0);
+    if let Some(sprite) = ball_sprite {
+        TextureAtlas::assign(&mut world.bodies[handle], sprite);
+    }
+
+Then when drawing bodies:
+    if let Some(sprite) = TextureAtlas::body_sprite(body) {
+        atlas.draw(sprite, pos, rotation, size, WHITE);
+    } else {
+        // draw_circle / draw_line as before
+    }
+*/
+
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBody;
+use std::collections::HashMap;
+
+// The part of a body's user_data holding its sprite
+const SPRITE_BITS: u128 = u64::MAX as u128;
+
+/// Index of a sprite inside a TextureAtlas.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SpriteId(pub u32);
+
+pub struct TextureAtlas {
+    texture: Texture2D,
+    // Where each sprite sits in the texture, indexed by SpriteId
+    regions: Vec<Rect>,
+    // Sprite ids by the path they were loaded from
+    names: HashMap<String, SpriteId>,
+}
+
+impl TextureAtlas {
+    /// Loads the images and packs them left to right into a single texture.
+    /// Each sprite is named after its path.
+    pub async fn load(paths: &[&str]) -> Result<TextureAtlas, String> {
+        let mut images = Vec::with_capacity(paths.len());
+        for path in paths {
+            let image = load_image(path).await.map_err(|e| format!("Couldn't load {}: {}", path, e))?;
+            images.push(image);
+        }
+
+        let width: usize = images.iter().map(|image| image.width as usize).sum();
+        let height = images.iter().map(|image| image.height as usize).max().unwrap_or(0);
+        if width > u16::MAX as usize {
+            return Err("atlas images are too wide to pack into one texture".to_string());
+        }
+        let mut packed = Image::gen_image_color(width.max(1) as u16, height.max(1) as u16, BLANK);
+
+        let mut regions = Vec::with_capacity(images.len());
+        let mut names = HashMap::new();
+        let mut x = 0;
+        for (path, image) in paths.iter().zip(&images) {
+            // Copy the image in row by row (4 bytes per RGBA pixel)
+            let row_bytes = image.width as usize * 4;
+            for row in 0..image.height as usize {
+                let src = row * row_bytes;
+                let dst = (row * width + x) * 4;
+                packed.bytes[dst..dst + row_bytes].copy_from_slice(&image.bytes[src..src + row_bytes]);
+            }
+            names.insert(path.to_string(), SpriteId(regions.len() as u32));
+            regions.push(Rect::new(x as f32, 0.0, image.width as f32, image.height as f32));
+            x += image.width as usize;
+        }
+
+        let texture = Texture2D::from_image(&packed);
+        texture.set_filter(FilterMode::Linear);
+        Ok(TextureAtlas { texture, regions, names })
+    }
+
+    /// The sprite loaded from `path`, if the atlas has it.
+    pub fn sprite(&self, path: &str) -> Option<SpriteId> {
+        self.names.get(path).copied()
+    }
+
+    /// Gives a body a sprite by storing the id in the low 64 bits of its user_data (0 is kept to
+    /// mean "no sprite"); the bits above are left for other tags, like a piece's owner.
+    pub fn assign(body: &mut RigidBody, sprite: SpriteId) {
+        body.user_data = (body.user_data & !SPRITE_BITS) | (sprite.0 as u128 + 1);
+    }
+
+    /// The sprite a body was given with assign(), or None to draw it with primitives.
+    pub fn body_sprite(body: &RigidBody) -> Option<SpriteId> {
+        match body.user_data & SPRITE_BITS {
+            0 => None,
+            id => Some(SpriteId((id - 1) as u32)),
+        }
+    }
+
+    /// Draws a sprite centered on `pos`, stretched to `size` and rotated by `rotation` radians.
+    pub fn draw(&self, sprite: SpriteId, pos: Vec2, rotation: f32, size: Vec2, color: Color) {
+        let Some(region) = self.regions.get(sprite.0 as usize) else {
+            return;
+        };
+        draw_texture_ex(
+            &self.texture,
+            pos.x - size.x / 2.0,
+            pos.y - size.y / 2.0,
+            color,
+            DrawTextureParams {
+                dest_size: Some(size),
+                source: Some(*region),
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
+}
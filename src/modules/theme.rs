@@ -0,0 +1,124 @@
+/*
+Program Details: UI theme shared by every widget
+
+One Theme holds the colours, font and padding the UI widgets are drawn with,
+plus optional nine-patch skins for panels and buttons. Widgets read the current
+theme when they are created (buttons take their text colour, font and skin from
+it, labels their font and padding), and the popup panels draw their background
+with draw_panel, so changing the theme before the UI is built restyles all of it.
+The text scale is the exception: labels, buttons and HUD text read it every time
+they are drawn, so the player's text size setting applies right away.
+
+The default theme looks like the game always has: dark see-through panels with a
+grey border, flat buttons and the system font.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod theme;
+
+Then with the other use commands add:
+use crate::modules::theme::{set_theme, theme, Theme};
+
+Then before the widgets are created you would go:
+    let mut custom = Theme::default();
+    custom.font = Some(load_ttf_font("assets/love.ttf").await.unwrap());
+    custom.button_skin = Some(NinePatch::rounded(WHITE, LIGHTGRAY, 8, 2));
+    set_theme(custom);
+
+To make all text bigger (1.0 is the size every widget was designed at):
+    set_text_scale(1.5);
+and to draw text of your own at the player's text size:
+    draw_text("Hello", 20.0, 40.0, scaled_font_size(20) as f32, WHITE);
+
+To draw a panel background in the theme's style:
+    draw_panel(200.0, 150.0, 420.0, 500.0);
+*/
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use crate::modules::nine_patch::NinePatch;
+
+/// Smallest and largest text scale the player can choose (75% - 200%)
+pub const MIN_TEXT_SCALE: f32 = 0.75;
+pub const MAX_TEXT_SCALE: f32 = 2.0;
+
+/// Colours, font, padding and skins the UI widgets are drawn with.
+#[derive(Clone)]
+pub struct Theme {
+    /// Fill and border of popup panels drawn without a skin
+    pub panel_color: Color,
+    pub panel_border: Color,
+    pub panel_border_width: f32,
+    /// Text on buttons
+    pub text_color: Color,
+    /// Font of every widget; None uses the system font
+    pub font: Option<Font>,
+    /// Space between a label's text and the edge of its background
+    pub padding: f32,
+    /// Drawn for panel backgrounds instead of the flat fill and border
+    pub panel_skin: Option<NinePatch>,
+    /// Drawn for button backgrounds, tinted with the button's colour, instead of a flat rectangle
+    pub button_skin: Option<NinePatch>,
+    /// Multiplies the font size of every label, button and piece of HUD text
+    pub text_scale: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            panel_color: Color::new(0.05, 0.05, 0.1, 0.92),
+            panel_border: GRAY,
+            panel_border_width: 2.0,
+            text_color: WHITE,
+            font: None,
+            padding: 5.0,
+            panel_skin: None,
+            button_skin: None,
+            text_scale: 1.0,
+        }
+    }
+}
+
+thread_local! {
+    // The theme every widget reads
+    static THEME: RefCell<Theme> = RefCell::new(Theme::default());
+}
+
+/// A copy of the current theme.
+pub fn theme() -> Theme {
+    THEME.with(|current| current.borrow().clone())
+}
+
+/// Replaces the theme. Widgets created from now on use the new one; panels switch right away.
+pub fn set_theme(theme: Theme) {
+    THEME.with(|current| *current.borrow_mut() = theme);
+}
+
+/// Changes the text scale of the current theme; text drawn from now on uses it.
+pub fn set_text_scale(scale: f32) {
+    THEME.with(|current| current.borrow_mut().text_scale = scale.clamp(MIN_TEXT_SCALE, MAX_TEXT_SCALE));
+}
+
+/// The current text scale.
+pub fn text_scale() -> f32 {
+    THEME.with(|current| current.borrow().text_scale)
+}
+
+/// `font_size` at the current text scale.
+pub fn scaled_font_size(font_size: u16) -> u16 {
+    ((font_size as f32 * text_scale()).round() as u16).max(1)
+}
+
+/// Draws a popup panel's background at (x, y): the theme's panel skin, or its flat fill and border.
+pub fn draw_panel(x: f32, y: f32, width: f32, height: f32) {
+    THEME.with(|current| {
+        let theme = current.borrow();
+        match &theme.panel_skin {
+            Some(skin) => skin.draw(x, y, width, height, WHITE),
+            None => {
+                draw_rectangle(x, y, width, height, theme.panel_color);
+                draw_rectangle_lines(x, y, width, height, theme.panel_border_width, theme.panel_border);
+            }
+        }
+    });
+}
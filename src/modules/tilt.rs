@@ -0,0 +1,141 @@
+/*
+Program Details: Tilting the board to shake stuck pieces loose
+
+Like nudging a pinball table: a tilt tips the board for TILT_SECONDS of game
+time by adding a sideways pull to gravity (alternating left and right from one
+tilt to the next) and wakes every piece above the bins, so pieces resting on
+pegs or wedged between them slide off. The screen shakes while it lasts.
+Tilting isn't free: a round gets TILTS_PER_ROUND of them, and every piece in play
+when the board is tilted has TILT_PENALTY taken off its payout multiplier (see
+multiplier.rs), so it is for rescuing pieces rather than steering them.
+
+The shake is a wobble worked out from the time left, not random numbers, so
+tilting doesn't use up the random number generator the drops are seeded from.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod tilt;
+
+Then with the other use commands add:
+use crate::modules::tilt::{BoardTilt, TILT_PENALTY};
+
+Then above the loop section to use you would go:
+    let mut tilt = BoardTilt::new();
+
+To tilt (e.g. from a button), penalising the pieces it shook:
+    for piece in tilt.tilt(&mut world) {
+        multipliers.add(piece, -TILT_PENALTY);
+    }
+
+Then in the loop you would use (game time for the tilt, real time for the shake):
+    tilt.update(&mut world, sim_dt, get_frame_time());
+    // ... when drawing the board:
+    offset_camera(tilt.shake_offset());
+
+And when a new round starts:
+    tilt.reset(&mut world);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use crate::modules::physics::PhysicsWorld;
+
+/// Tilts each round starts with.
+pub const TILTS_PER_ROUND: u32 = 3;
+/// Taken off the payout multiplier of every piece in play when the board is tilted.
+pub const TILT_PENALTY: f32 = 0.25;
+// Seconds of game time the board stays tipped, and the sideways pull meanwhile (pixels per second squared)
+const TILT_SECONDS: f32 = 0.35;
+const TILT_GRAVITY: f32 = 900.0;
+// Seconds the screen shakes for, how far it moves at first (pixels) and how fast it wobbles (radians per second)
+const SHAKE_SECONDS: f32 = 0.5;
+const SHAKE_AMPLITUDE: f32 = 8.0;
+const SHAKE_FREQUENCY: f32 = 70.0;
+
+pub struct BoardTilt {
+    left: u32,
+    // Game seconds the board stays tipped, and which way: -1.0 left, 1.0 right
+    tipped: f32,
+    direction: f32,
+    // Real seconds of screen shake left
+    shake: f32,
+}
+
+impl Default for BoardTilt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BoardTilt {
+    pub fn new() -> Self {
+        Self { left: TILTS_PER_ROUND, tipped: 0.0, direction: 1.0, shake: 0.0 }
+    }
+
+    /// Tilts the round has left.
+    pub fn left(&self) -> u32 {
+        self.left
+    }
+
+    /// True while the board is tipped.
+    pub fn is_tilting(&self) -> bool {
+        self.tipped > 0.0
+    }
+
+    /// Tips the board the other way from last time and wakes every piece above the bins. Returns the
+    /// pieces shaken (for the payout penalty), or nothing once the round is out of tilts.
+    pub fn tilt(&mut self, world: &mut PhysicsWorld) -> Vec<RigidBodyHandle> {
+        if self.left == 0 || self.is_tilting() {
+            return Vec::new();
+        }
+        self.left -= 1;
+        self.direction = -self.direction;
+        self.tipped = TILT_SECONDS;
+        self.shake = SHAKE_SECONDS;
+        world.gravity.x = self.direction * TILT_GRAVITY;
+
+        // Pieces already in a bin stay put: only the ones still falling are shaken (and penalised)
+        let bins_top = world.board.as_ref().map_or(f32::MAX, |board| board.bins_top());
+        let mut shaken = Vec::new();
+        for (handle, body) in world.bodies.iter_mut() {
+            if PhysicsWorld::is_piece(body) && body.translation().y <= bins_top {
+                body.wake_up(true);
+                shaken.push(handle);
+            }
+        }
+        shaken
+    }
+
+    /// Levels the board again once the tilt's time is up (`sim_dt`, game time) and winds the screen
+    /// shake down (`dt`, real time).
+    pub fn update(&mut self, world: &mut PhysicsWorld, sim_dt: f32, dt: f32) {
+        if self.is_tilting() {
+            self.tipped = (self.tipped - sim_dt).max(0.0);
+            if !self.is_tilting() {
+                world.gravity.x = 0.0;
+            }
+        }
+        self.shake = (self.shake - dt).max(0.0);
+    }
+
+    /// How far to move the view this frame for the screen shake (zero once it has settled).
+    pub fn shake_offset(&self) -> Vec2 {
+        if self.shake <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let strength = SHAKE_AMPLITUDE * self.shake / SHAKE_SECONDS;
+        let phase = self.shake * SHAKE_FREQUENCY;
+        vec2(phase.sin(), (phase * 1.3).cos()) * strength
+    }
+
+    /// Gives a new round its tilts back and levels the board.
+    pub fn reset(&mut self, world: &mut PhysicsWorld) {
+        self.restore(world, TILTS_PER_ROUND);
+    }
+
+    /// Levels the board with `left` tilts left, e.g. when a quick save is loaded.
+    pub fn restore(&mut self, world: &mut PhysicsWorld, left: u32) {
+        *self = Self { left, ..Self::new() };
+        world.gravity.x = 0.0;
+    }
+}
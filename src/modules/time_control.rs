@@ -0,0 +1,94 @@
+/*
+Program Details: Time controls for pausing, slowing down and speeding up the game
+
+The physics world always steps by the same fixed timestep, so changing the speed never
+changes how pieces behave; instead the frame time fed to world.advance() is
+scaled. Paused feeds nothing (no steps at all), slow motion feeds a quarter of
+each frame and fast-forward feeds four frames' worth. The UI keeps running on
+the real frame time, so buttons and menus work the same at every speed.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod time_control;
+
+Then with the other use commands add:
+use crate::modules::time_control::TimeControl;
+
+Then above the loop section to use you would go:
+    let mut time = TimeControl::new();
+
+To change the speed (e.g. from buttons):
+    time.toggle_pause();
+    time.toggle_slow_motion();
+    time.toggle_fast_forward();
+
+Then in the loop you would use:
+    let sim_dt = time.scaled(get_frame_time());
+    world.advance(sim_dt);
+    lifetime.update(&mut world, sim_dt);
+*/
+
+// How fast the game runs in slow motion and fast-forward
+pub const SLOW_MOTION_SCALE: f32 = 0.25;
+pub const FAST_FORWARD_SCALE: f32 = 4.0;
+
+/// How fast simulated time runs compared to real time (while not paused).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameSpeed {
+    Normal,
+    SlowMotion,
+    FastForward,
+}
+
+pub struct TimeControl {
+    pub paused: bool,
+    pub speed: GameSpeed,
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeControl {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            speed: GameSpeed::Normal,
+        }
+    }
+
+    // Pause or resume
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    // Switch slow motion on (turning fast-forward off) or back to normal speed
+    pub fn toggle_slow_motion(&mut self) {
+        self.speed = if self.speed == GameSpeed::SlowMotion { GameSpeed::Normal } else { GameSpeed::SlowMotion };
+    }
+
+    // Switch fast-forward on (turning slow motion off) or back to normal speed
+    pub fn toggle_fast_forward(&mut self) {
+        self.speed = if self.speed == GameSpeed::FastForward { GameSpeed::Normal } else { GameSpeed::FastForward };
+    }
+
+    /// Multiplier applied to real time: 0.0 while paused, otherwise the current speed's scale.
+    pub fn scale(&self) -> f32 {
+        if self.paused {
+            return 0.0;
+        }
+        match self.speed {
+            GameSpeed::Normal => 1.0,
+            GameSpeed::SlowMotion => SLOW_MOTION_SCALE,
+            GameSpeed::FastForward => FAST_FORWARD_SCALE,
+        }
+    }
+
+    /// Converts a real frame time into the simulated time to advance the game by.
+    pub fn scaled(&self, frame_time: f32) -> f32 {
+        frame_time * self.scale()
+    }
+}
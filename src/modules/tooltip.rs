@@ -0,0 +1,120 @@
+/*
+Program Details: Hover tooltips for the UI
+
+Any widget (or any area of the screen, like a bin or a peg) can register a
+tooltip for the current frame with set_tooltip(area, text). Once everything is
+drawn, draw_tooltips() looks for the last registered area under the mouse (the
+one drawn on top) and, after the mouse has rested over it for TOOLTIP_DELAY
+seconds, shows its text in a small box next to the cursor. The box is laid out
+in virtual-resolution coordinates, kept on the screen near the edges, and its
+text doesn't shrink below a readable size in small windows.
+
+Registrations only last one frame, so a tooltip goes away with its widget; no
+tooltips show while a dialog has the input.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod tooltip;
+
+Then with the other use commands add:
+use crate::modules::tooltip::{draw_tooltips, set_tooltip};
+
+Then in the loop, while drawing something that should explain itself:
+    set_tooltip(Rect::new(100.0, 560.0, 118.0, 140.0), "Pays 5x the bet");
+Buttons can register their own with:
+    btn_text.with_tooltip("Drops ten balls at once");
+
+Then at the very end of the loop, after everything else has been drawn:
+    draw_tooltips();
+*/
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use crate::modules::dialog::input_blocked;
+use crate::modules::label::Label;
+#[cfg(feature = "scale")]
+use crate::modules::scale::mouse_position_world as mouse_position;
+use crate::modules::scale::VIRTUAL_RESOLUTION;
+use crate::modules::theme::theme;
+
+// Seconds the mouse has to rest over an area before its tooltip shows
+const TOOLTIP_DELAY: f32 = 0.5;
+const FONT_SIZE: u16 = 18;
+// Longest line before the text wraps, and smallest the text may get on screen (real pixels)
+const MAX_WIDTH: f32 = 260.0;
+const MIN_SCREEN_SIZE: f32 = 12.0;
+// Distance from the cursor to the box
+const CURSOR_GAP: f32 = 16.0;
+
+#[derive(Default)]
+struct Tooltips {
+    // Areas registered this frame, in drawing order
+    areas: Vec<(Rect, String)>,
+    // The text under the mouse last frame and how long it has been there
+    hovered: Option<String>,
+    hover_time: f32,
+}
+
+thread_local! {
+    static TOOLTIPS: RefCell<Tooltips> = RefCell::new(Tooltips::default());
+}
+
+/// Gives `area` (virtual coordinates) a tooltip for this frame.
+pub fn set_tooltip(area: Rect, text: impl Into<String>) {
+    TOOLTIPS.with(|tooltips| tooltips.borrow_mut().areas.push((area, text.into())));
+}
+
+/// Shows the tooltip of the area under the mouse, if it has been hovered long enough, and forgets
+/// this frame's areas. Call once a frame, after everything else has been drawn.
+pub fn draw_tooltips() {
+    let (mouse_x, mouse_y) = mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    let shown = TOOLTIPS.with(|tooltips| {
+        let mut tooltips = tooltips.borrow_mut();
+        let areas = std::mem::take(&mut tooltips.areas);
+        let under_mouse = match input_blocked() {
+            true => None,
+            false => areas.into_iter().rev().find(|(area, _)| area.contains(mouse)).map(|(_, text)| text),
+        };
+        if under_mouse.is_some() && under_mouse == tooltips.hovered {
+            tooltips.hover_time += get_frame_time();
+        } else {
+            tooltips.hovered = under_mouse;
+            tooltips.hover_time = 0.0;
+        }
+        tooltips.hovered.clone().filter(|_| tooltips.hover_time >= TOOLTIP_DELAY)
+    });
+    if let Some(text) = shown {
+        draw_box(&text, mouse);
+    }
+}
+
+// Draws the tooltip box below and to the right of the cursor, flipped to the other side of it
+// where it would run off the screen
+fn draw_box(text: &str, mouse: Vec2) {
+    let mut label = Label::new(text, 0.0, 0.0, FONT_SIZE);
+    label
+        .with_colors(WHITE, Some(Color::new(0.1, 0.1, 0.15, 0.95)))
+        .with_border(LIGHTGRAY, 1.0)
+        .with_wrap(MAX_WIDTH)
+        .with_min_screen_size(MIN_SCREEN_SIZE);
+    let width = label.get_width().unwrap_or(0.0);
+    let height = label.get_height().unwrap_or(0.0);
+    let (screen_width, screen_height) = VIRTUAL_RESOLUTION.with(|resolution| *resolution.borrow());
+
+    // Top-left corner of the box
+    let mut left = mouse.x + CURSOR_GAP;
+    if left + width > screen_width {
+        left = mouse.x - CURSOR_GAP - width;
+    }
+    let mut top = mouse.y + CURSOR_GAP;
+    if top + height > screen_height {
+        top = mouse.y - CURSOR_GAP - height;
+    }
+    let left = left.clamp(0.0, (screen_width - width).max(0.0));
+    let top = top.clamp(0.0, (screen_height - height).max(0.0));
+
+    // A label is placed by the baseline of its first line, inside its padding
+    label.set_position(left + theme().padding, top + FONT_SIZE as f32);
+    label.draw();
+}
@@ -0,0 +1,104 @@
+/*
+Program Details: Motion trails behind fast-moving pieces
+
+Keeps a ring buffer of the last TRAIL_LENGTH positions of every dynamic body
+and draws it as a polyline behind the piece, fading out and thinning towards
+its tail. Only pieces moving faster than MIN_TRAIL_SPEED add to their trail; a
+slower one loses a point a step instead, so the trail shrinks away as the piece
+settles. Trails of bodies that have despawned (or whose trail has shrunk to
+nothing) are dropped.
+
+Trails can be switched off from the settings panel.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod trails;
+
+Then with the other use commands add:
+use crate::modules::trails::TrailRenderer;
+
+Then above the loop section to use you would go:
+    let mut trails = TrailRenderer::new();
+
+Then in the loop you would use (after the physics has stepped):
+    trails.update(&world);
+    // ... before the pieces are drawn so they sit on top of their trails:
+    trails.draw(|handle| YELLOW);
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use std::collections::{HashMap, VecDeque};
+use crate::modules::physics::PhysicsWorld;
+
+// Positions kept per trail
+const TRAIL_LENGTH: usize = 12;
+// Speed (pixels/second) a piece has to be going for its trail to grow
+const MIN_TRAIL_SPEED: f32 = 150.0;
+// Width of the trail at its head; it thins to nothing at the tail
+const TRAIL_WIDTH: f32 = 5.0;
+// Opacity of the trail at its head
+const TRAIL_ALPHA: f32 = 0.5;
+
+pub struct TrailRenderer {
+    // Recent positions of each piece, oldest first
+    trails: HashMap<RigidBodyHandle, VecDeque<Vec2>>,
+    pub enabled: bool,
+}
+
+impl Default for TrailRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrailRenderer {
+    pub fn new() -> Self {
+        Self { trails: HashMap::new(), enabled: true }
+    }
+
+    /// Records where every piece is now. Call once per frame in which the physics stepped.
+    pub fn update(&mut self, world: &PhysicsWorld) {
+        if !self.enabled {
+            self.trails.clear();
+            return;
+        }
+        for (handle, body) in world.bodies.iter() {
+            if !PhysicsWorld::is_piece(body) {
+                continue;
+            }
+            if body.linvel().norm() >= MIN_TRAIL_SPEED {
+                let trail = self.trails.entry(handle).or_insert_with(|| VecDeque::with_capacity(TRAIL_LENGTH));
+                if trail.len() == TRAIL_LENGTH {
+                    trail.pop_front();
+                }
+                let pos = body.translation();
+                trail.push_back(vec2(pos.x, pos.y));
+            } else if let Some(trail) = self.trails.get_mut(&handle) {
+                trail.pop_front();
+            }
+        }
+        self.trails.retain(|handle, trail| !trail.is_empty() && world.bodies.contains(*handle));
+    }
+
+    /// Forgets every trail (e.g. when the board is rebuilt).
+    pub fn clear(&mut self) {
+        self.trails.clear();
+    }
+
+    /// Draws every trail as a fading polyline in the colour `color_of` gives its piece.
+    pub fn draw(&self, color_of: impl Fn(RigidBodyHandle) -> Color) {
+        if !self.enabled {
+            return;
+        }
+        for (handle, trail) in &self.trails {
+            let color = color_of(*handle);
+            let segments = trail.len().saturating_sub(1);
+            for (index, (from, to)) in trail.iter().zip(trail.iter().skip(1)).enumerate() {
+                // 0.0 at the tail, 1.0 at the head
+                let along = (index + 1) as f32 / segments as f32;
+                draw_line(from.x, from.y, to.x, to.y, TRAIL_WIDTH * along, Color { a: TRAIL_ALPHA * along, ..color });
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+/*
+Program Details: Trajectory preview for aimed drops
+
+While aiming, the next piece's fall is tried out on a copy of the board (see
+PhysicsWorld::clone_board) for up to PREVIEW_SECONDS and drawn as a faint dotted
+line from the drop point.
+
+The real drop won't follow it exactly: other pieces on the board aren't in the
+copy, moving pegs keep moving, and a fraction of a pixel makes a different
+bounce a few pegs down. So the preview is run PREVIEW_RUNS times from drop
+points a hair apart; where the runs stay together the path is a safe bet, and
+where they split the dots fan out to show it. The dots also fade along the path
+as the guess gets worse.
+
+To keep the frame rate up the runs are stepped STEPS_PER_FRAME steps a frame, so
+the line grows down the board over a few frames, and are only started again when
+the aim, the piece or the ball variant changes (at most every RESTART_SECONDS)
+and every REFRESH_SECONDS on boards with moving pegs.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod trajectory;
+
+Then with the other use commands add:
+use crate::modules::trajectory::TrajectoryPreview;
+
+Then above the loop section to use you would go:
+    let mut preview = TrajectoryPreview::new();
+
+Then in the loop you would use (while aiming at x):
+    preview.update(&world, next_kind, x, get_frame_time());
+    // ... after the board is drawn:
+    preview.draw();
+
+When not aiming:
+    preview.clear();
+*/
+use macroquad::prelude::*;
+use rapier2d::prelude::RigidBodyHandle;
+use crate::modules::aim::DROP_Y;
+use crate::modules::layers::Layers;
+use crate::modules::physics::{BallVariant, PhysicsWorld, ShapeKind};
+
+// Longest stretch of the fall that is previewed, in seconds of game time
+const PREVIEW_SECONDS: f32 = 2.0;
+// Runs from drop points RUN_SPREAD pixels apart, drawn on top of each other
+const PREVIEW_RUNS: usize = 3;
+const RUN_SPREAD: f32 = 0.5;
+// Physics steps each run is advanced by per frame
+const STEPS_PER_FRAME: usize = 40;
+// A dot every this many physics steps
+const DOT_EVERY: usize = 3;
+const DOT_RADIUS: f32 = 1.5;
+// Shortest time between two restarts while the aim keeps moving, and how often the preview is
+// redone on boards with moving pegs even if the aim hasn't moved
+const RESTART_SECONDS: f32 = 0.1;
+const REFRESH_SECONDS: f32 = 0.25;
+
+// One try of the drop: the copy of the board it falls through (None once it has finished)
+struct Run {
+    world: Option<PhysicsWorld>,
+    piece: RigidBodyHandle,
+    // Steps taken, and the steps PREVIEW_SECONDS takes at the board's timestep
+    steps: usize,
+    max_steps: usize,
+    dots: Vec<Vec2>,
+}
+
+impl Run {
+    fn new(world: &PhysicsWorld, kind: ShapeKind, x: f32) -> Self {
+        let mut preview = world.clone_board();
+        let piece = preview.spawn(kind, x, DROP_Y);
+        preview.set_layer(piece, Layers::Ghosts);
+        // CCD is most of the cost of a step, and the odd miss only shows in the preview
+        preview.bodies[piece].enable_ccd(false);
+        let max_steps = (PREVIEW_SECONDS / preview.integration_params.dt) as usize;
+        Self { world: Some(preview), piece, steps: 0, max_steps, dots: Vec::new() }
+    }
+
+    // Advances the run by up to `steps` steps, recording a dot every DOT_EVERY steps. It finishes
+    // once the piece reaches the bins, comes to rest or PREVIEW_SECONDS have passed
+    fn advance(&mut self, steps: usize) {
+        let Some(world) = self.world.as_mut() else {
+            return;
+        };
+        let bins_top = world.board.as_ref().map_or(f32::MAX, |board| board.bins_top());
+        for _ in 0..steps {
+            world.step();
+            let body = &world.bodies[self.piece];
+            let pos = body.translation();
+            if self.steps.is_multiple_of(DOT_EVERY) {
+                self.dots.push(vec2(pos.x, pos.y));
+            }
+            self.steps += 1;
+            if pos.y > bins_top || body.is_sleeping() || self.steps >= self.max_steps {
+                self.world = None;
+                return;
+            }
+        }
+    }
+}
+
+pub struct TrajectoryPreview {
+    runs: Vec<Run>,
+    // What the current runs were started for
+    aim: Option<(ShapeKind, BallVariant, f32)>,
+    // Seconds since the runs were started
+    age: f32,
+}
+
+impl Default for TrajectoryPreview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrajectoryPreview {
+    pub fn new() -> Self {
+        Self { runs: Vec::new(), aim: None, age: 0.0 }
+    }
+
+    /// Starts the runs again if the aim, the piece or the board has changed, then advances them.
+    pub fn update(&mut self, world: &PhysicsWorld, kind: ShapeKind, x: f32, dt: f32) {
+        self.age += dt;
+        let aim = (kind, world.ball_variant, x);
+        let changed = self.aim != Some(aim) && (self.aim.is_none() || self.age >= RESTART_SECONDS);
+        let stale = world.has_moving_pegs() && self.age >= REFRESH_SECONDS;
+        if changed || stale {
+            self.aim = Some(aim);
+            self.age = 0.0;
+            let offsets = (0..PREVIEW_RUNS).map(|run| (run as f32 - (PREVIEW_RUNS - 1) as f32 / 2.0) * RUN_SPREAD);
+            self.runs = offsets.map(|offset| Run::new(world, kind, x + offset)).collect();
+        }
+        for run in &mut self.runs {
+            run.advance(STEPS_PER_FRAME);
+        }
+    }
+
+    /// Forgets the preview (when the player stops aiming).
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.aim = None;
+    }
+
+    /// Draws every run as faint dots, fading out along the path.
+    pub fn draw(&self) {
+        for run in &self.runs {
+            let max_dots = run.max_steps / DOT_EVERY;
+            for (index, dot) in run.dots.iter().enumerate() {
+                let fade = 1.0 - index as f32 / max_dots as f32;
+                draw_circle(dot.x, dot.y, DOT_RADIUS, Color::new(1.0, 1.0, 1.0, 0.15 + 0.35 * fade));
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+/*
+Program Details: Secondary viewports drawn through their own camera
+
+A Viewport is an offscreen render target with a camera of its own: whatever is
+drawn inside render() lands in the target, looking at any rectangle of the world
+(zoomed in, zoomed out or following a piece) whatever the main view shows. The
+target is then drawn like a texture wherever it should appear on the screen.
+Once render() is done the screen's camera is back as it was (the scaled virtual
+resolution camera with the "scale" feature, the default camera without).
+
+Used for the board gallery's thumbnails, the minimap in the corner of the board
+(see minimap.rs) and the instant-replay window (see instant_replay.rs).
+
+Render targets can be made at any time, but have to be drawn into inside a
+frame (clear_background() throws away anything drawn before it).
+
+screen_view() gives the part of the world the screen's own camera shows, for
+culling what the main view draws (see Renderer::culled in renderer.rs).
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod viewport;
+
+Then with the other use commands add:
+use crate::modules::viewport::Viewport;
+
+Then above the loop section to use you would go:
+    let viewport = Viewport::new(300, 200);
+
+Then in the loop you would use (before anything else is drawn to the screen):
+    let view = Rect::new(0.0, 0.0, 850.0, 768.0);
+    viewport.render(view, || draw_board_colliders(&world, view));
+    // ... and wherever it should show:
+    viewport.draw(Rect::new(20.0, 20.0, 150.0, 100.0));
+*/
+use macroquad::prelude::*;
+#[cfg(feature = "scale")]
+use crate::modules::scale::{screen_scale, use_virtual_resolution, VIRTUAL_RESOLUTION};
+
+/// The part of the world the screen's camera shows: the virtual resolution, and whatever the window's shape
+/// adds around it.
+#[cfg(feature = "scale")]
+pub fn screen_view() -> Rect {
+    let (width, height) = VIRTUAL_RESOLUTION.with(|resolution| *resolution.borrow());
+    let (view_width, view_height) = (screen_width() / screen_scale(), screen_height() / screen_scale());
+    Rect::new((width - view_width) / 2.0, (height - view_height) / 2.0, view_width, view_height)
+}
+
+/// The part of the world the screen's camera shows: the window itself.
+#[cfg(not(feature = "scale"))]
+pub fn screen_view() -> Rect {
+    Rect::new(0.0, 0.0, screen_width(), screen_height())
+}
+
+/// An offscreen render target drawn through a camera of its own.
+pub struct Viewport {
+    target: RenderTarget,
+}
+
+impl Viewport {
+    /// A viewport `width` × `height` pixels big (drawn smoothed when shown at another size).
+    pub fn new(width: u32, height: u32) -> Self {
+        let target = render_target(width, height);
+        target.texture.set_filter(FilterMode::Linear);
+        Self { target }
+    }
+
+    /// Draws `draw` into the viewport through a camera showing the `view` rectangle of the world, then puts
+    /// the screen's camera back.
+    pub fn render(&self, view: Rect, draw: impl FnOnce()) {
+        let mut camera = Camera2D::from_display_rect(view);
+        camera.render_target = Some(self.target.clone());
+        set_camera(&camera);
+
+        draw();
+
+        #[cfg(feature = "scale")]
+        {
+            let (width, height) = VIRTUAL_RESOLUTION.with(|resolution| *resolution.borrow());
+            use_virtual_resolution(width, height);
+        }
+        #[cfg(not(feature = "scale"))]
+        set_default_camera();
+    }
+
+    /// Draws what was last rendered into the viewport stretched over `dest` on the screen.
+    pub fn draw(&self, dest: Rect) {
+        draw_texture_ex(
+            &self.target.texture,
+            dest.x,
+            dest.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(dest.w, dest.h)),
+                // Render targets come out upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,120 @@
+/*
+Program Details: Wallet module for the Plinko credit economy
+
+The player starts with a number of credits. Every drop costs the current bet,
+and when the piece lands in a bin the stake is paid back multiplied by that
+bin's payout multiplier.
+In an arcade round the wallet starts empty and every coin inserted deposits
+COIN_CREDITS.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod wallet;
+
+Then with the other use commands add:
+use crate::modules::wallet::Wallet;
+
+Then above the loop section to use you would go:
+    let mut wallet = Wallet::new(STARTING_CREDITS, DEFAULT_BET);
+
+To take the bet for a drop (returns None if the player can't afford it):
+    if let Some(stake) = wallet.place_bet() {
+        // spawn the piece and remember its stake
+    }
+
+When the piece lands in a bin:
+    let won = wallet.pay_out(stake, multiplier);
+
+To change the bet:
+    wallet.raise_bet();
+    wallet.lower_bet();
+*/
+use serde::{Deserialize, Serialize};
+
+// Credits the player starts a session with
+pub const STARTING_CREDITS: u32 = 100;
+// Credits one coin buys in an arcade round
+pub const COIN_CREDITS: u32 = 50;
+// Bet taken for each drop until the player changes it
+pub const DEFAULT_BET: u32 = 10;
+// Amount the bet goes up or down per click, and its limits
+pub const BET_STEP: u32 = 5;
+pub const MIN_BET: u32 = 5;
+pub const MAX_BET: u32 = 50;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Wallet {
+    balance: u32,
+    bet: u32,
+}
+
+impl Wallet {
+    pub fn new(starting_credits: u32, bet: u32) -> Self {
+        Self {
+            balance: starting_credits,
+            bet: bet.clamp(MIN_BET, MAX_BET),
+        }
+    }
+
+    // Getter for the current credit balance
+    pub fn balance(&self) -> u32 {
+        self.balance
+    }
+
+    // Getter for the current bet per drop
+    pub fn bet(&self) -> u32 {
+        self.bet
+    }
+
+    // Increase the bet by one step, up to MAX_BET
+    pub fn raise_bet(&mut self) {
+        self.bet = (self.bet + BET_STEP).min(MAX_BET);
+    }
+
+    // Decrease the bet by one step, down to MIN_BET
+    pub fn lower_bet(&mut self) {
+        self.bet = self.bet.saturating_sub(BET_STEP).max(MIN_BET);
+    }
+
+    // Whether the balance covers one more drop at the current bet
+    pub fn can_afford(&self) -> bool {
+        self.balance >= self.bet
+    }
+
+    // Whether the balance covers `drops` drops at the current bet
+    pub fn can_afford_drops(&self, drops: u32) -> bool {
+        self.balance >= self.bet * drops
+    }
+
+    /// Takes the current bet out of the balance.
+    /// Returns the stake that was taken, or None if the player can't afford it.
+    pub fn place_bet(&mut self) -> Option<u32> {
+        if !self.can_afford() {
+            return None;
+        }
+        self.balance -= self.bet;
+        Some(self.bet)
+    }
+
+    /// Credits the wallet with stake × multiplier (rounded down) and returns the amount won.
+    pub fn pay_out(&mut self, stake: u32, multiplier: f32) -> u32 {
+        let won = (stake as f32 * multiplier).floor() as u32;
+        self.balance += won;
+        won
+    }
+
+    /// Credits the wallet with a flat amount (e.g. a bonus prize).
+    pub fn deposit(&mut self, amount: u32) {
+        self.balance += amount;
+    }
+}
+
+/// Formats a multiplier for the bin labels, e.g. "0.5x" or "2x".
+pub fn format_multiplier(multiplier: f32) -> String {
+    if multiplier.fract() == 0.0 {
+        format!("{}x", multiplier as u32)
+    } else {
+        format!("{}x", multiplier)
+    }
+}
@@ -0,0 +1,103 @@
+/*
+Program Details: Winner screen for hot-seat games
+
+Shown when a hot-seat game ends: every player has had their turns (or nobody
+has the credits for another bet) and the last pieces have landed. The richest
+player wins; the standings handed over through the shared Session list every
+player's final credits, in their colour, best first. Players level on credits
+at the top share the win.
+
+Play Again starts a new hot-seat game with the same number of players; Main
+Menu goes back to the title screen.
+
+To import you need:
+
+In your mod.rs file located in the modules folder add the following to the end of the file:
+    pub mod winner_scene;
+
+Then with the other use commands add:
+use crate::modules::winner_scene::WinnerScene;
+
+Then above the loop section to use you would go:
+    let session = SharedSession::default();
+    scenes.add(SceneId::Winner, Box::new(WinnerScene::new(session.clone())));
+*/
+use macroquad::prelude::*;
+use crate::modules::label::Label;
+use crate::modules::players::Standing;
+use crate::modules::scene::{Scene, SceneChange, SceneId};
+use crate::modules::session::{RoundKind, SharedSession};
+use crate::modules::text_button::TextButton;
+
+const TABLE_X: f32 = 340.0;
+const TABLE_TOP: f32 = 300.0;
+const ROW_HEIGHT: f32 = 50.0;
+
+pub struct WinnerScene {
+    session: SharedSession,
+    // Final standings of the game, richest first
+    standings: Vec<Standing>,
+    lbl_title: Label,
+    btn_again: TextButton,
+    btn_menu: TextButton,
+}
+
+impl WinnerScene {
+    pub fn new(session: SharedSession) -> Self {
+        let mut lbl_title = Label::new("", 312.0, 180.0, 60);
+        lbl_title.with_colors(YELLOW, None);
+
+        Self {
+            session,
+            standings: Vec::new(),
+            lbl_title,
+            btn_again: TextButton::new(412.0, 580.0, 200.0, 60.0, "Play Again", DARKGREEN, GREEN, 30),
+            btn_menu: TextButton::new(412.0, 660.0, 200.0, 60.0, "Main Menu", DARKGRAY, GRAY, 30),
+        }
+    }
+}
+
+impl Scene for WinnerScene {
+    fn on_enter(&mut self) {
+        if let Some(standings) = self.session.borrow_mut().standings.take() {
+            self.standings = standings;
+        }
+        let Some(best) = self.standings.first() else {
+            return;
+        };
+        let winners = self.standings.iter().filter(|standing| standing.balance == best.balance).count();
+        if winners > 1 {
+            self.lbl_title.set_text("It's a tie!");
+            self.lbl_title.with_colors(YELLOW, None);
+        } else {
+            self.lbl_title.set_text(format!("{} wins!", best.name));
+            self.lbl_title.with_colors(best.color, None);
+        }
+    }
+
+    fn update(&mut self, _dt: f32) -> SceneChange {
+        if self.btn_again.click() || is_key_pressed(KeyCode::Enter) {
+            self.session.borrow_mut().request = Some(RoundKind::HotSeat { players: self.standings.len() });
+            return SceneChange::Goto(SceneId::Plinko);
+        }
+        if self.btn_menu.click() || is_key_pressed(KeyCode::Escape) {
+            return SceneChange::Goto(SceneId::Menu);
+        }
+        SceneChange::None
+    }
+
+    fn draw(&mut self) {
+        self.lbl_title.draw();
+
+        let best = self.standings.first().map_or(0, |standing| standing.balance);
+        for (place, standing) in self.standings.iter().enumerate() {
+            let y = TABLE_TOP + ROW_HEIGHT * place as f32;
+            // Anyone level with the best balance shares first place
+            let place = if standing.balance == best { 1 } else { place + 1 };
+            draw_text(&format!("{}.", place), TABLE_X, y, 36.0, WHITE);
+            draw_circle(TABLE_X + 60.0, y - 10.0, 10.0, standing.color);
+            draw_text(&standing.name, TABLE_X + 85.0, y, 36.0, standing.color);
+            draw_text(&format!("{} credits", standing.balance), TABLE_X + 240.0, y, 36.0, WHITE);
+        }
+    }
+}
@@ -0,0 +1,584 @@
+/*
+Program Details: Property tests for the collision module
+
+Run with `cargo test`. The objects here are plain shapes implementing
+Collidable, so no texture (and no window) is needed.
+
+- Rotated rectangles without masks (the separating axis test) are cross-checked
+  against a brute-force sampler that tries points on a fine grid: any point the
+  sampler finds inside both rectangles means check_collision has to report a
+  hit, and any hit it reports has to show up in the sampler once both rectangles
+  are grown by a pixel and a half (so slivers thinner than the grid still count).
+  Angles under SMALL_ANGLE (but not zero) are left out: check_collision treats
+  those rectangles as unrotated on purpose. Circles and regular polygons are
+  checked against the sampler the same way, with each other and with rectangles.
+- A fully opaque mask has to collide exactly like no mask at all, and a coarse
+  mask (rotated or not) must not change what collides, only how fast.
+- Moving the first of two colliding rectangles by the minimum translation
+  vector check_collision_info gives (and a hair further) separates them.
+- A swept check has to find a hit whenever the objects overlap at either end
+  of the move, at time 0.0 if they already overlapped, and has to catch a fast
+  rectangle jumping right over another one.
+- Collision layers: every layer pair collides both ways or neither, and
+  overlapping sprites on layers that don't collide are never reported.
+- A CollisionWorld has to find exactly the pairs checking every pair finds,
+  including for shapes reaching outside its area.
+- point_hits agrees with the shape about which points are on it, and a ray
+  aimed at a shape's centre meets it at its edge and nowhere before; with a
+  mask, clicks and rays only stop at opaque pixels.
+- Masks made from images: the alpha threshold decides which pixels are solid,
+  dilating closes pinholes, eroding removes specks, and doing both keeps the
+  outline. Sprite sheets get one mask per frame.
+- Regression cases for the mask index at the right and bottom edges of a
+  texture: masks whose last byte is only partly used, and masks shorter than the
+  texture they claim to cover, must neither panic nor read past their end.
+*/
+use macroquad::prelude::{Image, Rect, Vec2};
+use plinko2::modules::collision::{
+    check_collision, check_collision_info, check_swept_collision, coarse_mask, dilate_mask, erode_mask, frame_masks,
+    mask_from_image, point_hits, raycast, Collidable, CollisionWorld, Hitbox, MaskOptions,
+};
+use plinko2::modules::layers::Layers;
+use std::f32::consts::PI;
+use proptest::prelude::*;
+
+// Below this angle (radians) check_collision doesn't rotate rectangles
+const SMALL_ANGLE: f32 = 0.05;
+// Spacing of the brute-force sampler's grid, and how much the rectangles are grown to check a reported hit
+const SAMPLE_STEP: f32 = 0.5;
+const GROWTH: f32 = 1.5;
+// How far inside both rectangles a sampled point has to be before check_collision must see it (rounding)
+const INSET: f32 = 0.01;
+
+// A rectangle, circle or polygon that is all the Collidable trait asks for
+#[derive(Clone, Debug)]
+struct Shape {
+    pos: Vec2,
+    size: Vec2,
+    angle: f32,
+    texture_size: Vec2,
+    mask: Option<Vec<u8>>,
+    coarse_mask: Option<Vec<u8>>,
+    hitbox: Hitbox,
+    layer: Option<Layers>,
+}
+
+impl Shape {
+    fn new(x: f32, y: f32, w: f32, h: f32, angle: f32) -> Self {
+        Self {
+            pos: Vec2::new(x, y),
+            size: Vec2::new(w, h),
+            angle,
+            texture_size: Vec2::new(w, h),
+            mask: None,
+            coarse_mask: None,
+            hitbox: Hitbox::Rectangle,
+            layer: None,
+        }
+    }
+
+    // A circle of `radius` around (x, y)
+    fn circle(x: f32, y: f32, radius: f32) -> Self {
+        Self { hitbox: Hitbox::Circle { radius }, ..Self::new(x - radius, y - radius, radius * 2.0, radius * 2.0, 0.0) }
+    }
+
+    // A regular polygon with `sides` corners `radius` from (x, y), turned by `angle`
+    fn polygon(x: f32, y: f32, sides: usize, radius: f32, angle: f32) -> Self {
+        let corners = (0..sides).map(|side| Vec2::from_angle(side as f32 * 2.0 * PI / sides as f32) * radius).collect();
+        Self { hitbox: Hitbox::Polygon(corners), ..Self::new(x - radius, y - radius, radius * 2.0, radius * 2.0, angle) }
+    }
+
+    // The same rectangle with a texture of `width` × `height` pixels and the mask `mask` over it
+    fn with_mask(mut self, width: f32, height: f32, mask: Vec<u8>) -> Self {
+        self.texture_size = Vec2::new(width, height);
+        self.mask = Some(mask);
+        self
+    }
+
+    // The same rectangle with a coarse copy of its mask
+    fn with_coarse_mask(mut self) -> Self {
+        let mask = self.mask.as_ref().expect("only a masked rectangle has a coarse mask");
+        self.coarse_mask = Some(coarse_mask(mask, self.texture_size.x as usize, self.texture_size.y as usize));
+        self
+    }
+
+    // The same shape, `by` pixels bigger on every side (polygons have to be regular)
+    fn grown(&self, by: f32) -> Self {
+        let hitbox = match &self.hitbox {
+            Hitbox::Rectangle => Hitbox::Rectangle,
+            Hitbox::Circle { radius } => Hitbox::Circle { radius: radius + by },
+            Hitbox::Polygon(corners) => {
+                // Moving every edge out by `by` moves the corners out by more
+                let out = by / (PI / corners.len() as f32).cos();
+                Hitbox::Polygon(corners.iter().map(|&corner| corner * (1.0 + out / corner.length())).collect())
+            }
+        };
+        Self { pos: self.pos - Vec2::splat(by), size: self.size + Vec2::splat(by * 2.0), hitbox, ..self.clone() }
+    }
+
+    fn center(&self) -> Vec2 {
+        self.pos + self.size / 2.0
+    }
+
+    // True if `point` is inside the shape (not on its edge) as it is drawn, rotated about its centre
+    fn contains(&self, point: Vec2) -> bool {
+        let (sin, cos) = (-self.angle).sin_cos();
+        let offset = point - self.center();
+        let local = Vec2::new(offset.x * cos - offset.y * sin, offset.x * sin + offset.y * cos);
+        match &self.hitbox {
+            Hitbox::Rectangle => local.x.abs() < self.size.x / 2.0 && local.y.abs() < self.size.y / 2.0,
+            Hitbox::Circle { radius } => local.length() < *radius,
+            Hitbox::Polygon(corners) => (0..corners.len()).all(|index| {
+                let (corner, next) = (corners[index], corners[(index + 1) % corners.len()]);
+                (next - corner).perp_dot(local - corner) > 0.0
+            }),
+        }
+    }
+
+    // How far the shape reaches from its centre in any direction
+    fn reach(&self) -> f32 {
+        match &self.hitbox {
+            Hitbox::Rectangle => self.size.length() / 2.0,
+            Hitbox::Circle { radius } => *radius,
+            Hitbox::Polygon(corners) => corners.iter().map(|corner| corner.length()).fold(0.0, f32::max),
+        }
+    }
+}
+
+impl Collidable for Shape {
+    fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    fn texture_size(&self) -> Vec2 {
+        self.texture_size
+    }
+
+    fn get_mask(&self) -> Option<Vec<u8>> {
+        self.mask.clone()
+    }
+
+    fn get_angle(&self) -> f32 {
+        self.angle
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        self.hitbox.clone()
+    }
+
+    fn get_coarse_mask(&self) -> Option<Vec<u8>> {
+        self.coarse_mask.clone()
+    }
+
+    fn layer(&self) -> Option<Layers> {
+        self.layer
+    }
+}
+
+// A mask for a texture of `width` × `height` pixels with every pixel opaque
+fn opaque_mask(width: usize, height: usize) -> Vec<u8> {
+    let pixels = width * height;
+    let mut mask = vec![0xff; pixels.div_ceil(8)];
+    // Bits past the last pixel are left clear, as still_image.rs leaves them
+    if pixels % 8 != 0 {
+        *mask.last_mut().unwrap() = 0xff << (8 - pixels % 8);
+    }
+    mask
+}
+
+// True if a point on the sampler's grid lies inside both shapes
+fn sampled_overlap(a: &Shape, b: &Shape) -> bool {
+    let (center_a, center_b) = (a.center(), b.center());
+    let min = (center_a - Vec2::splat(a.reach())).max(center_b - Vec2::splat(b.reach()));
+    let max = (center_a + Vec2::splat(a.reach())).min(center_b + Vec2::splat(b.reach()));
+    let mut y = min.y;
+    while y <= max.y {
+        let mut x = min.x;
+        while x <= max.x {
+            let point = Vec2::new(x, y);
+            if a.contains(point) && b.contains(point) {
+                return true;
+            }
+            x += SAMPLE_STEP;
+        }
+        y += SAMPLE_STEP;
+    }
+    false
+}
+
+// How much further than the minimum translation vector a rectangle is moved to be clear of the other
+const CLEARANCE: f32 = 0.01;
+// The area the CollisionWorld covers: smaller than where rectangles are placed, so some reach outside it
+const WORLD_AREA: Rect = Rect { x: 0.0, y: 0.0, w: 150.0, h: 150.0 };
+
+// Angles check_collision really rotates by: none at all, or at least SMALL_ANGLE either way
+fn angle() -> impl Strategy<Value = f32> {
+    prop_oneof![Just(0.0f32), SMALL_ANGLE..PI, -PI..-SMALL_ANGLE]
+}
+
+fn rectangle() -> impl Strategy<Value = Shape> {
+    (0.0f32..120.0, 0.0f32..120.0, 4.0f32..60.0, 4.0f32..60.0, angle()).prop_map(|(x, y, w, h, angle)| Shape::new(x, y, w, h, angle))
+}
+
+// A rectangle, a circle or a regular polygon of 3 to 8 sides
+fn shape() -> impl Strategy<Value = Shape> {
+    prop_oneof![
+        rectangle(),
+        (0.0f32..150.0, 0.0f32..150.0, 2.0f32..30.0).prop_map(|(x, y, radius)| Shape::circle(x, y, radius)),
+        (0.0f32..150.0, 0.0f32..150.0, 3usize..9, 3.0f32..30.0, angle())
+            .prop_map(|(x, y, sides, radius, angle)| Shape::polygon(x, y, sides, radius, angle)),
+    ]
+}
+
+// A rectangle on whole pixels, for comparing masks against no mask
+fn pixel_rectangle() -> impl Strategy<Value = Shape> {
+    (0u32..60, 0u32..60, 1u32..24, 1u32..24).prop_map(|(x, y, w, h)| Shape::new(x as f32, y as f32, w as f32, h as f32, 0.0))
+}
+
+// A rectangle on whole pixels, turned by angle(), stretched over a texture of up to 40 × 40 pixels with a mask
+// that is mostly transparent (so whole coarse blocks are)
+fn masked_rectangle() -> impl Strategy<Value = Shape> {
+    (pixel_rectangle(), 1usize..40, 1usize..40, angle()).prop_flat_map(|(shape, width, height, angle)| {
+        let bytes = prop::collection::vec(prop_oneof![9 => Just(0u8), 1 => any::<u8>()], (width * height).div_ceil(8));
+        bytes.prop_map(move |mask| Shape { angle, ..shape.clone() }.with_mask(width as f32, height as f32, mask))
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(300))]
+
+    #[test]
+    fn rotated_rectangles_agree_with_the_sampler(a in rectangle(), b in rectangle()) {
+        let hit = check_collision(&a, &b, 1);
+        if sampled_overlap(&a.grown(-INSET), &b.grown(-INSET)) {
+            prop_assert!(hit, "the sampler found an overlap check_collision missed: {:?} {:?}", a, b);
+        }
+        if hit {
+            prop_assert!(sampled_overlap(&a.grown(GROWTH), &b.grown(GROWTH)), "check_collision found an overlap that isn't there: {:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn circles_and_polygons_agree_with_the_sampler(a in shape(), b in shape()) {
+        let hit = check_collision(&a, &b, 1);
+        if sampled_overlap(&a.grown(-INSET), &b.grown(-INSET)) {
+            prop_assert!(hit, "the sampler found an overlap check_collision missed: {:?} {:?}", a, b);
+        }
+        if hit {
+            prop_assert!(sampled_overlap(&a.grown(GROWTH), &b.grown(GROWTH)), "check_collision found an overlap that isn't there: {:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn collision_is_symmetric(a in shape(), b in shape()) {
+        prop_assert_eq!(check_collision(&a, &b, 1), check_collision(&b, &a, 1));
+    }
+
+    #[test]
+    fn an_opaque_mask_collides_like_no_mask(a in pixel_rectangle(), b in pixel_rectangle()) {
+        let masked = a.clone().with_mask(a.size.x, a.size.y, opaque_mask(a.size.x as usize, a.size.y as usize));
+        prop_assert_eq!(check_collision(&masked, &b, 1), check_collision(&a, &b, 1));
+        let both = b.clone().with_mask(b.size.x, b.size.y, opaque_mask(b.size.x as usize, b.size.y as usize));
+        prop_assert_eq!(check_collision(&masked, &both, 1), check_collision(&a, &b, 1));
+    }
+
+    #[test]
+    fn a_coarse_mask_changes_nothing_but_speed(a in masked_rectangle(), b in masked_rectangle(), skip in 1usize..4) {
+        let expected = check_collision(&a, &b, skip);
+        let (coarse_a, coarse_b) = (a.clone().with_coarse_mask(), b.clone().with_coarse_mask());
+        prop_assert_eq!(check_collision(&coarse_a, &coarse_b, skip), expected);
+        prop_assert_eq!(check_collision(&coarse_a, &b, skip), expected);
+        let solid = Shape { mask: None, ..b.clone() };
+        prop_assert_eq!(check_collision(&coarse_a, &solid, skip), check_collision(&a, &solid, skip));
+    }
+
+    #[test]
+    fn the_minimum_translation_vector_separates(a in rectangle(), b in rectangle()) {
+        let info = check_collision_info(&a, &b, 1);
+        prop_assert_eq!(info.is_some(), check_collision(&a, &b, 1));
+        if let Some(info) = info {
+            prop_assert!((info.normal.length() - 1.0).abs() < 0.001, "the normal isn't a unit vector: {:?}", info);
+            prop_assert!(info.depth >= 0.0);
+            let moved = Shape { pos: a.pos + info.mtv - info.normal * CLEARANCE, ..a.clone() };
+            prop_assert!(!check_collision(&moved, &b, 1), "still colliding after moving by {:?}: {:?} {:?}", info, a, b);
+        }
+    }
+
+    #[test]
+    fn a_swept_check_sees_both_ends_of_the_move(a in rectangle(), b in rectangle(), (dx, dy) in (-200.0f32..200.0, -200.0f32..200.0)) {
+        let prev = a.pos;
+        let moved = Shape { pos: a.pos + Vec2::new(dx, dy), ..a.clone() };
+        let swept = check_swept_collision(&moved, prev, &b, b.pos, 1);
+        if check_collision(&a, &b, 1) {
+            prop_assert_eq!(swept, Some(0.0));
+        }
+        if check_collision(&moved, &b, 1) {
+            prop_assert!(swept.is_some_and(|time| (0.0..=1.0).contains(&time)), "missed a hit at the end of the move: {:?}", swept);
+        }
+    }
+
+    #[test]
+    fn point_hits_agrees_with_the_shape(a in shape(), (x, y) in (-20.0f32..180.0, -20.0f32..180.0)) {
+        let point = Vec2::new(x, y);
+        if a.grown(-INSET).contains(point) {
+            prop_assert!(point_hits(&a, point), "missed a point inside: {:?} {:?}", a, point);
+        }
+        if point_hits(&a, point) {
+            prop_assert!(a.grown(INSET).contains(point), "hit a point outside: {:?} {:?}", a, point);
+        }
+    }
+
+    #[test]
+    fn a_ray_at_the_centre_meets_the_edge_first(a in shape(), (x, y) in (-50.0f32..200.0, -50.0f32..200.0)) {
+        let origin = Vec2::new(x, y);
+        let to_center = a.center() - origin;
+        prop_assume!(to_center.length() > 0.1);
+        let dir = to_center.normalize();
+        let distance = raycast(&a, origin, to_center);
+        prop_assert!(distance.is_some_and(|distance| distance <= to_center.length() + INSET), "missed the centre: {:?} {:?}", a, distance);
+        let distance = distance.unwrap();
+        prop_assert!(a.grown(INSET).contains(origin + dir * distance), "the hit isn't on the shape: {:?} {}", a, distance);
+        if distance > INSET {
+            prop_assert!(!a.grown(-INSET).contains(origin + dir * (distance - INSET)), "passed into the shape before the hit: {:?} {}", a, distance);
+        }
+        // Pointing away, it only meets the shape if it starts on it
+        let away = raycast(&a, origin, -to_center);
+        if a.grown(-INSET).contains(origin) {
+            prop_assert_eq!(away, Some(0.0));
+        } else if !a.grown(INSET).contains(origin) {
+            prop_assert_eq!(away, None);
+        }
+    }
+
+    #[test]
+    fn collision_world_finds_every_colliding_pair(rectangles in prop::collection::vec(shape(), 0..60)) {
+        let mut world = CollisionWorld::new(WORLD_AREA);
+        for rectangle in &rectangles {
+            world.insert(rectangle);
+        }
+        let mut expected = Vec::new();
+        for (a, first) in rectangles.iter().enumerate() {
+            for (b, second) in rectangles.iter().enumerate().skip(a + 1) {
+                if check_collision(first, second, 1) {
+                    expected.push((a, b));
+                }
+            }
+        }
+        prop_assert_eq!(world.collisions(&rectangles, 1), expected);
+    }
+}
+
+#[test]
+fn a_fast_rectangle_cannot_jump_over_another() {
+    // 10 pixels wide, moving 100 pixels in a frame, past a wall 4 pixels thick halfway along
+    let bullet = Shape::new(110.0, 0.0, 10.0, 10.0, 0.0);
+    let wall = Shape::new(60.0, -50.0, 4.0, 100.0, 0.0);
+    let prev = Vec2::new(10.0, 0.0);
+    assert!(!check_collision(&Shape { pos: prev, ..bullet.clone() }, &wall, 1));
+    assert!(!check_collision(&bullet, &wall, 1));
+    let time = check_swept_collision(&bullet, prev, &wall, wall.pos, 1).expect("the bullet went through the wall");
+    assert!((time - 0.4).abs() < 0.001, "hit the wall at {} instead of 0.4", time);
+    // Rotated, and with the wall moving the other way, it still can't get through
+    let spun = Shape { angle: 0.8, ..bullet.clone() };
+    assert!(check_swept_collision(&spun, prev, &wall, wall.pos + Vec2::new(20.0, 0.0), 1).is_some());
+    // Moving past the end of the wall misses
+    let below = Shape { pos: Vec2::new(110.0, 60.0), ..bullet.clone() };
+    assert_eq!(check_swept_collision(&below, Vec2::new(10.0, 60.0), &wall, wall.pos, 1), None);
+}
+
+#[test]
+fn a_swept_check_waits_for_opaque_pixels() {
+    // A 40 × 10 sprite from a 4 × 1 texture with only its left-most pixel opaque, moving 100 pixels at a wall
+    let mut sprite = Shape::new(110.0, 0.0, 40.0, 10.0, 0.0).with_mask(4.0, 1.0, vec![0b1000_0000]);
+    let wall = Shape::new(100.0, 0.0, 4.0, 10.0, 0.0);
+    // The sprite's rectangle reaches the wall at 0.5, but its opaque quarter only at 0.8
+    let time = check_swept_collision(&sprite, Vec2::new(10.0, 0.0), &wall, wall.pos, 1).expect("the sprite went through the wall");
+    assert!((time - 0.8).abs() < 0.05, "hit the wall at {} instead of 0.8", time);
+    sprite.mask = Some(vec![0]);
+    assert_eq!(check_swept_collision(&sprite, Vec2::new(10.0, 0.0), &wall, wall.pos, 1), None);
+}
+
+#[test]
+fn a_circle_only_hits_opaque_pixels() {
+    // A 20 × 20 sprite whose left half is transparent
+    let sprite = Shape::new(0.0, 0.0, 20.0, 20.0, 0.0).with_mask(2.0, 1.0, vec![0b0100_0000]);
+    assert!(!check_collision(&sprite, &Shape::circle(-2.0, 10.0, 6.0), 1));
+    assert!(check_collision(&Shape::circle(22.0, 10.0, 6.0), &sprite, 1));
+    // Turned upside down the opaque half is on the left
+    let flipped = Shape { angle: PI, ..sprite.clone() };
+    assert!(check_collision(&flipped, &Shape::circle(-2.0, 10.0, 6.0), 1));
+    assert!(!check_collision(&Shape::polygon(22.0, 10.0, 3, 6.0, 0.0), &flipped, 1));
+}
+
+#[test]
+fn a_coarse_mask_bit_is_set_when_any_pixel_in_its_block_is() {
+    // 10 × 9 pixels: 2 × 2 coarse bits, the right and bottom blocks only partly covered
+    let mut mask = vec![0u8; 90usize.div_ceil(8)];
+    let mut set = |x: usize, y: usize| mask[(y * 10 + x) / 8] |= 1 << (7 - (y * 10 + x) % 8);
+    set(3, 2);
+    set(9, 8);
+    assert_eq!(coarse_mask(&mask, 10, 9), vec![0b1001_0000]);
+    assert_eq!(coarse_mask(&[0; 12], 10, 9), vec![0]);
+}
+
+#[test]
+fn layers_collide_both_ways_or_not_at_all() {
+    for a in Layers::ALL {
+        for b in Layers::ALL {
+            assert_eq!(a.interacts_with(b), b.interacts_with(a), "{:?} and {:?}", a, b);
+            // Rapier only lets two colliders touch if each one's filter takes in the other's layer
+            assert_eq!(a.filter() & b.bit() != 0, b.filter() & a.bit() != 0, "{:?} and {:?} disagree", a, b);
+        }
+    }
+    assert!(Layers::ALL.iter().all(|layer| !layer.interacts_with(Layers::Decorations)));
+}
+
+#[test]
+fn sprites_on_layers_that_dont_collide_pass_through_each_other() {
+    let on = |layer| Shape { layer: Some(layer), ..Shape::new(0.0, 0.0, 20.0, 20.0, 0.0) };
+    let moved = |layer| Shape { pos: Vec2::new(10.0, 10.0), ..on(layer) };
+    assert!(check_collision(&on(Layers::Pieces), &moved(Layers::Powerups), 1));
+    assert!(check_collision(&on(Layers::Pieces), &Shape::new(5.0, 5.0, 4.0, 4.0, 0.0), 1));
+    assert!(!check_collision(&on(Layers::Ghosts), &moved(Layers::Pieces), 1));
+    assert!(!check_collision(&on(Layers::Decorations), &moved(Layers::Board), 1));
+    assert!(check_collision_info(&on(Layers::Powerups), &moved(Layers::Powerups), 1).is_none());
+    assert_eq!(check_swept_collision(&moved(Layers::Ghosts), Vec2::new(-50.0, 0.0), &on(Layers::Pieces), Vec2::ZERO, 1), None);
+}
+
+#[test]
+fn last_pixel_of_a_partly_used_mask_byte_collides() {
+    // 3 × 3 pixels: the ninth pixel is the first bit of the mask's second byte
+    let sprite = Shape::new(0.0, 0.0, 3.0, 3.0, 0.0).with_mask(3.0, 3.0, opaque_mask(3, 3));
+    let corner = Shape::new(2.0, 2.0, 5.0, 5.0, 0.0);
+    assert!(check_collision(&sprite, &corner, 1));
+    assert!(check_collision(&corner, &sprite, 1));
+}
+
+#[test]
+fn the_bottom_right_pixel_is_read_from_the_right_bit() {
+    // Only the bottom-right pixel of a 3 × 3 sprite is opaque
+    let sprite = Shape::new(0.0, 0.0, 3.0, 3.0, 0.0).with_mask(3.0, 3.0, vec![0b0000_0000, 0b1000_0000]);
+    assert!(check_collision(&sprite, &Shape::new(2.0, 2.0, 1.0, 1.0, 0.0), 1));
+    assert!(!check_collision(&sprite, &Shape::new(0.0, 0.0, 2.0, 2.0, 0.0), 1));
+}
+
+#[test]
+fn a_mask_shorter_than_its_texture_does_not_panic() {
+    // The texture claims 16 × 16 pixels, but the mask only covers the first row
+    let sprite = Shape::new(0.0, 0.0, 16.0, 16.0, 0.0).with_mask(16.0, 16.0, vec![0xff, 0xff]);
+    assert!(check_collision(&sprite, &Shape::new(0.0, 0.0, 4.0, 1.0, 0.0), 1));
+    assert!(!check_collision(&sprite, &Shape::new(0.0, 8.0, 16.0, 8.0, 0.0), 1));
+    let rotated = Shape { angle: 0.7, ..sprite.clone() };
+    check_collision(&rotated, &Shape::new(4.0, 4.0, 16.0, 16.0, 0.0), 1);
+    check_collision(&rotated, &rotated, 1);
+}
+
+#[test]
+fn a_sprite_drawn_bigger_than_its_texture_reads_its_edge_pixels() {
+    // A 4 × 4 texture stretched to 40 × 40: points near the right and bottom edges map to the last column and row
+    let mut mask = vec![0u8; 2];
+    // Only the bottom-right pixel (index 15) is opaque
+    mask[1] = 0b0000_0001;
+    let sprite = Shape::new(0.0, 0.0, 40.0, 40.0, 0.0).with_mask(4.0, 4.0, mask);
+    assert!(check_collision(&sprite, &Shape::new(35.0, 35.0, 10.0, 10.0, 0.0), 1));
+    assert!(!check_collision(&sprite, &Shape::new(0.0, 0.0, 25.0, 25.0, 0.0), 1));
+}
+
+// A white image with the given alpha for each pixel, row by row
+fn alpha_image(width: u16, height: u16, alpha: &[u8]) -> Image {
+    Image { bytes: alpha.iter().flat_map(|a| [255, 255, 255, *a]).collect(), width, height }
+}
+
+// The solid pixels of a width × height mask, as (x, y)
+fn solid_pixels(mask: &[u8], width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..width * height).filter(|idx| mask[idx / 8] & (1 << (7 - idx % 8)) != 0).map(|idx| (idx % width, idx / width)).collect()
+}
+
+#[test]
+fn the_alpha_threshold_decides_which_pixels_are_solid() {
+    let image = alpha_image(4, 1, &[255, 100, 20, 0]);
+    assert_eq!(mask_from_image(&image, &MaskOptions::default()), Some(vec![0b1110_0000]));
+    let options = MaskOptions { alpha_threshold: 128, ..Default::default() };
+    assert_eq!(mask_from_image(&image, &options), Some(vec![0b1000_0000]));
+}
+
+#[test]
+fn an_opaque_image_has_no_mask() {
+    let image = alpha_image(3, 3, &[255; 9]);
+    assert_eq!(mask_from_image(&image, &MaskOptions::default()), None);
+    // The edges of the image don't erode it
+    assert_eq!(mask_from_image(&image, &MaskOptions { erode: 1, ..Default::default() }), None);
+}
+
+#[test]
+fn dilating_closes_a_pinhole() {
+    let mut alpha = [255; 25];
+    alpha[12] = 0;
+    let image = alpha_image(5, 5, &alpha);
+    assert!(mask_from_image(&image, &MaskOptions::default()).is_some());
+    assert_eq!(mask_from_image(&image, &MaskOptions { dilate: 1, ..Default::default() }), None);
+}
+
+#[test]
+fn eroding_removes_a_speck() {
+    let mut alpha = [0; 25];
+    alpha[12] = 255;
+    let image = alpha_image(5, 5, &alpha);
+    let mask = mask_from_image(&image, &MaskOptions { erode: 1, ..Default::default() }).unwrap();
+    assert!(solid_pixels(&mask, 5, 5).is_empty());
+}
+
+#[test]
+fn dilating_then_eroding_keeps_the_outline() {
+    // A 3 × 3 square with a hole in the middle, in a 7 × 7 image
+    let mut mask = vec![0u8; 7];
+    for (x, y) in [(2, 2), (3, 2), (4, 2), (2, 3), (4, 3), (2, 4), (3, 4), (4, 4)] {
+        let idx = y * 7 + x;
+        mask[idx / 8] |= 1 << (7 - idx % 8);
+    }
+    let closed = erode_mask(&dilate_mask(&mask, 7, 7, 1), 7, 7, 1);
+    let square: Vec<(usize, usize)> = (2..5).flat_map(|y| (2..5).map(move |x| (x, y))).collect();
+    assert_eq!(solid_pixels(&closed, 7, 7), square);
+}
+
+#[test]
+fn every_frame_of_a_sheet_gets_its_own_mask() {
+    // Two 2 × 2 frames side by side; only the second has a transparent pixel
+    let image = alpha_image(4, 2, &[255, 255, 255, 0, 255, 255, 255, 255]);
+    assert_eq!(frame_masks(&image, 2, 2, &MaskOptions::default()), vec![None, Some(vec![0b1011_0000])]);
+}
+
+// A 4 × 1 texture drawn 40 × 10 with only its last pixel opaque
+fn last_pixel_sprite(angle: f32) -> Shape {
+    Shape::new(0.0, 0.0, 40.0, 10.0, angle).with_mask(4.0, 1.0, vec![0b0001_0000])
+}
+
+#[test]
+fn a_click_only_hits_opaque_pixels() {
+    let sprite = last_pixel_sprite(0.0);
+    assert!(!point_hits(&sprite, Vec2::new(5.0, 5.0)));
+    assert!(point_hits(&sprite, Vec2::new(35.0, 5.0)));
+    assert!(!point_hits(&sprite, Vec2::new(45.0, 5.0)));
+    // Turned a quarter turn about its centre (20, 5), the opaque pixel ends up below the centre
+    let turned = last_pixel_sprite(PI / 2.0);
+    assert!(point_hits(&turned, Vec2::new(20.0, 20.0)));
+    assert!(!point_hits(&turned, Vec2::new(20.0, -10.0)));
+}
+
+#[test]
+fn a_ray_passes_through_transparent_pixels() {
+    let near = |distance: Option<f32>, expected: f32| distance.is_some_and(|distance| (distance - expected).abs() < 0.001);
+    let sprite = last_pixel_sprite(0.0);
+    assert!(near(raycast(&sprite, Vec2::new(-10.0, 5.0), Vec2::new(1.0, 0.0)), 40.0));
+    // From the other side it meets the opaque pixel as soon as it reaches the sprite
+    assert!(near(raycast(&sprite, Vec2::new(50.0, 5.0), Vec2::new(-3.0, 0.0)), 10.0));
+    assert!(near(raycast(&last_pixel_sprite(PI / 2.0), Vec2::new(20.0, -30.0), Vec2::new(0.0, 1.0)), 45.0));
+
+    let clear = Shape::new(0.0, 0.0, 40.0, 10.0, 0.0).with_mask(4.0, 1.0, vec![0]);
+    assert_eq!(raycast(&clear, Vec2::new(-10.0, 5.0), Vec2::new(1.0, 0.0)), None);
+    assert_eq!(raycast(&sprite, Vec2::new(-10.0, 5.0), Vec2::ZERO), None);
+}
@@ -0,0 +1,38 @@
+/*
+Program Details: Fixtures shared by the headless tests and the benchmarks
+
+Loading a board file and building the classic board into a PhysicsWorld
+without a window, so every test and benchmark starts from the same setup.
+
+To import you need:
+
+In a test file in the tests folder add the following with the other use commands:
+    mod common;
+    use common::{classic_world, load_board, BALL_RADIUS};
+
+In a benchmark in the benches folder point the module at this file instead:
+    #[path = "../tests/common/mod.rs"]
+    mod common;
+*/
+// Every test file gets its own copy of this module, and not all of them use every fixture
+#![allow(dead_code)]
+
+use plinko2::modules::board::{BoardConfig, CLASSIC_BOARD};
+use plinko2::modules::physics::PhysicsWorld;
+
+/// Radius of a ball piece.
+pub const BALL_RADIUS: f32 = 7.0;
+
+/// The board in the file at `path`. Panics (failing the test) if it can't be read or isn't a valid board.
+pub fn load_board(path: &str) -> BoardConfig {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Couldn't read {}: {}", path, e));
+    BoardConfig::from_json(&text).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// A world with the classic board built into it, and the board.
+pub fn classic_world() -> (PhysicsWorld, BoardConfig) {
+    let board = load_board(CLASSIC_BOARD);
+    let mut world = PhysicsWorld::new();
+    world.build_board(&board);
+    (world, board)
+}
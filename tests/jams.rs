@@ -0,0 +1,87 @@
+/*
+Program Details: Headless checks of the physics world's jam detection
+
+Run with `cargo test`. Pieces are put on the classic board without a window:
+
+- A ball balanced exactly on top of a peg would sit there forever; it is reported
+  as jammed (GameEvent::PieceJammed) and nudged off the peg.
+- A ball resting in a bin is where it should be and is never nudged.
+*/
+mod common;
+
+use common::{classic_world, BALL_RADIUS};
+use plinko2::modules::aim::DROP_Y;
+use plinko2::modules::entity_registry::EntityKind;
+use plinko2::modules::events::GameEvent;
+use plinko2::modules::physics::{PhysicsWorld, ShapeKind, FIXED_DT};
+use rapier2d::prelude::*;
+
+// Simulated seconds each check waits at most
+const WAIT_SECONDS: f32 = 8.0;
+
+// Runs the world for up to WAIT_SECONDS, until `done` says so. Returns false if it never did
+fn run_until(world: &mut PhysicsWorld, mut done: impl FnMut(&PhysicsWorld) -> bool) -> bool {
+    let mut time = 0.0;
+    while time < WAIT_SECONDS {
+        world.advance(FIXED_DT);
+        time += FIXED_DT;
+        if done(world) {
+            return true;
+        }
+    }
+    false
+}
+
+#[test]
+fn a_ball_balanced_on_a_peg_is_nudged_off() {
+    let (mut world, board) = classic_world();
+    // The peg in the top row nearest the middle (well clear of the walls), and its radius
+    let pegs: Vec<(Vector<Real>, Real)> = world
+        .bodies
+        .iter()
+        .filter(|(_, body)| EntityKind::of(body) == Some(EntityKind::Peg))
+        .filter_map(|(_, body)| {
+            let collider = &world.colliders[*body.colliders().first()?];
+            Some((*body.translation(), collider.shape().as_ball()?.radius))
+        })
+        .collect();
+    let top = pegs.iter().map(|(pos, _)| pos.y).fold(f32::MAX, f32::min);
+    let (peg, radius) = pegs
+        .into_iter()
+        .filter(|(pos, _)| pos.y < top + 1.0)
+        .min_by(|a, b| (a.0.x - board.ground.x).abs().total_cmp(&(b.0.x - board.ground.x).abs()))
+        .expect("the classic board has no round pegs");
+    let piece = world.spawn(ShapeKind::Ball, peg.x, peg.y - radius - BALL_RADIUS - 0.5);
+
+    let mut jammed_at = None;
+    assert!(
+        run_until(&mut world, |world| {
+            jammed_at = world.events().iter().find_map(|event| match *event {
+                GameEvent::PieceJammed { piece: jammed, x, y, nudges } if jammed == piece => Some((x, y, nudges)),
+                _ => None,
+            });
+            jammed_at.is_some()
+        }),
+        "the balanced ball was never reported as jammed"
+    );
+    let (x, y, nudges) = jammed_at.unwrap();
+    assert_eq!(nudges, 1);
+    assert!((x - peg.x).abs() < 1.0 && y < peg.y, "jammed at ({}, {}), the peg is at ({}, {})", x, y, peg.x, peg.y);
+
+    assert!(
+        run_until(&mut world, |world| world.bodies.get(piece).is_none_or(|body| (body.translation().x - peg.x).abs() > radius + BALL_RADIUS)),
+        "the nudged ball didn't leave the peg"
+    );
+}
+
+#[test]
+fn a_ball_resting_in_a_bin_is_left_alone() {
+    let (mut world, board) = classic_world();
+    let piece = world.spawn(ShapeKind::Ball, board.ground.x, DROP_Y);
+    assert!(
+        run_until(&mut world, |world| world.events().iter().any(|event| matches!(event, GameEvent::Settled { piece: settled, .. } if *settled == piece))),
+        "the ball never landed"
+    );
+    let nudged = run_until(&mut world, |world| world.events().iter().any(|event| matches!(event, GameEvent::PieceJammed { .. })));
+    assert!(!nudged, "a ball in a bin was nudged");
+}
@@ -0,0 +1,54 @@
+/*
+Program Details: Headless checks of the physics world's scene queries
+
+Run with `cargo test`. The classic board is built into a PhysicsWorld without a
+window and asked what is where, before and after a piece is dropped on it:
+
+- A ray straight down the middle stops on the board, and one pointing up from
+  above the board hits nothing.
+- A piece is found by ray casts, box queries and point projections as soon as
+  it is spawned (before any step) and still where it fell after some steps, and
+  spawn_point_clear sees it.
+*/
+mod common;
+
+use common::{classic_world, BALL_RADIUS};
+use plinko2::modules::aim::DROP_Y;
+use plinko2::modules::physics::{PhysicsWorld, ShapeKind, FIXED_DT};
+use rapier2d::prelude::*;
+
+#[test]
+fn a_ray_down_the_middle_stops_on_the_board() {
+    let (world, board) = classic_world();
+    let ground_top = board.ground.y - board.ground.half_height;
+    let hit = world.raycast(point![board.ground.x, DROP_Y], vector![0.0, 1.0]).expect("the ray fell through the board");
+    assert!(hit.point.y <= ground_top + 0.01, "the ray went past the ground: {:?}", hit);
+    assert!(hit.body.is_some_and(|body| !PhysicsWorld::is_piece(&world.bodies[body])));
+    assert!(world.raycast(point![board.ground.x, -5000.0], vector![0.0, -1.0]).is_none());
+}
+
+#[test]
+fn a_spawned_piece_is_found_straight_away_and_after_falling() {
+    let (mut world, board) = classic_world();
+    let (x, y) = (board.ground.x, DROP_Y);
+    assert!(world.spawn_point_clear(ShapeKind::Ball, x, y));
+    let handle = world.spawn(ShapeKind::Ball, x, y);
+    assert!(!world.spawn_point_clear(ShapeKind::Ball, x, y));
+
+    let hit = world.raycast(point![x, y - 100.0], vector![0.0, 5.0]).expect("the ray missed the piece");
+    assert_eq!(hit.body, Some(handle));
+    assert!((hit.distance - (100.0 - BALL_RADIUS)).abs() < 0.01, "hit at {}", hit.distance);
+
+    let nearest = world.point_project(point![x + 20.0, y]).expect("nothing near the piece");
+    assert_eq!(nearest.body, Some(handle));
+    assert!(!nearest.inside);
+    assert!((nearest.distance - (20.0 - BALL_RADIUS)).abs() < 0.01, "{} away", nearest.distance);
+
+    for _ in 0..10 {
+        world.advance(FIXED_DT);
+    }
+    let pos = *world.bodies[handle].translation();
+    let around = Aabb::new(point![pos.x - 1.0, pos.y - 1.0], point![pos.x + 1.0, pos.y + 1.0]);
+    assert!(world.bodies_in_aabb(around).contains(&handle));
+    assert!(world.point_project(point![pos.x, pos.y]).is_some_and(|nearest| nearest.inside && nearest.body == Some(handle)));
+}